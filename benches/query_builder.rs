@@ -0,0 +1,68 @@
+//! Rendering queries with the query builder
+//!
+//! Covers a filtered join on every platform and multi-row INSERTs, the
+//! statements built on hot paths.
+
+// Generated by criterion_group!/criterion_main!
+#![allow(missing_docs, clippy::missing_docs_in_private_items)]
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rustine_dbal::platform::{MySqlPlatform, Platform, PostgresPlatform, SqlitePlatform};
+use rustine_dbal::query::{col, val, OrderDirection, QueryBuilder};
+use rustine_dbal::SqlValue;
+
+/// A SELECT with a join, a compound filter, ordering and a limit
+fn report_query() -> QueryBuilder {
+    QueryBuilder::select()
+        .columns(&["users.id", "users.name", "orders.total"])
+        .from("users")
+        .inner_join("orders", col("orders.user_id").eq(col("users.id")))
+        .where_expr(
+            col("users.active")
+                .eq(val(true))
+                .and(col("orders.total").gt(val(100)))
+                .and(col("users.country").in_list(vec![val("DE"), val("FR"), val("IT")])),
+        )
+        .order_by("orders.total", OrderDirection::Desc)
+        .limit(50)
+}
+
+/// Build and render the SELECT for each platform
+fn select(c: &mut Criterion) {
+    let platforms: [(&str, Box<dyn Platform>); 3] = [
+        ("postgres", Box::new(PostgresPlatform)),
+        ("mysql", Box::new(MySqlPlatform::new())),
+        ("sqlite", Box::new(SqlitePlatform::new())),
+    ];
+    let mut group = c.benchmark_group("select");
+    for (name, platform) in &platforms {
+        group.bench_function(BenchmarkId::new("build_and_render", name), |b| {
+            b.iter(|| black_box(report_query().to_sql(platform.as_ref())));
+        });
+        let query = report_query();
+        group.bench_function(BenchmarkId::new("render", name), |b| {
+            b.iter(|| black_box(query.try_to_sql(platform.as_ref())));
+        });
+    }
+    group.finish();
+}
+
+/// Render multi-row INSERTs of growing size
+fn insert(c: &mut Criterion) {
+    let platform = PostgresPlatform;
+    let mut group = c.benchmark_group("insert");
+    for rows in [10_u64, 100, 1_000] {
+        let mut query = QueryBuilder::insert().into("users").insert_columns(&["id", "name", "score"]);
+        for i in 0..rows {
+            query = query.values(vec![SqlValue::from(i), SqlValue::from(format!("user {i}")), SqlValue::F64(0.5)]);
+        }
+        group.throughput(Throughput::Elements(rows));
+        group.bench_with_input(BenchmarkId::new("render", rows), &query, |b, query| {
+            b.iter(|| black_box(query.to_sql(&platform)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, select, insert);
+criterion_main!(benches);
@@ -0,0 +1,130 @@
+//! Reading large result sets from `SQLite`
+//!
+//! Run with `cargo bench --features sqlite --bench rows`. Compares owned
+//! rows (`all_rows`, `fetch_rows`) with borrowed rows (`for_each_row_ref`),
+//! which read text straight from the result buffer, and the buffer layouts
+//! of `SqliteResult`: a vector per row cloned by `next_row()`, as before,
+//! against one row-major buffer whose values are moved out.
+
+// Generated by criterion_group!/criterion_main!
+#![allow(missing_docs, clippy::missing_docs_in_private_items)]
+#![allow(clippy::unwrap_used)]
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rustine_dbal::core::{ConnectionParams, SqlValue};
+use rustine_dbal::driver::{Driver, DriverConnection, DriverResult, SqliteDriver};
+use tokio::runtime::Runtime;
+
+/// Query reading all users
+const QUERY: &str = "SELECT id, name, email, score FROM users";
+
+/// Connect to an in-memory database holding `rows` users
+fn setup(runtime: &Runtime, rows: usize) -> <SqliteDriver as Driver>::Connection {
+    runtime.block_on(async {
+        let conn = SqliteDriver::new().connect(&ConnectionParams::sqlite_memory()).await.unwrap();
+        conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, email TEXT, score REAL)")
+            .await
+            .unwrap();
+        conn.execute(&format!(
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < {rows}) \
+             INSERT INTO users SELECT i, 'user ' || i, 'user' || i || '@example.com', i * 0.5 FROM n"
+        ))
+        .await
+        .unwrap();
+        conn
+    })
+}
+
+/// Read all users as owned and as borrowed rows
+fn read_rows(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("read_rows");
+
+    for rows in [1_000, 10_000, 100_000] {
+        let conn = setup(&runtime, rows);
+        group.throughput(Throughput::Elements(rows as u64));
+
+        group.bench_with_input(BenchmarkId::new("all_rows", rows), &rows, |b, _| {
+            b.iter(|| {
+                let mut result = runtime.block_on(conn.query(QUERY)).unwrap();
+                black_box(result.all_rows().unwrap())
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("fetch_rows", rows), &rows, |b, _| {
+            b.iter(|| {
+                let mut result = runtime.block_on(conn.query(QUERY)).unwrap();
+                black_box(result.fetch_rows().unwrap())
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("for_each_row_ref", rows), &rows, |b, _| {
+            b.iter(|| {
+                let mut result = runtime.block_on(conn.query(QUERY)).unwrap();
+                let mut length = 0;
+                result
+                    .for_each_row_ref(&mut |row| {
+                        length += row.get_at(1).and_then(|name| name.as_str()).map_or(0, str::len);
+                        Ok(())
+                    })
+                    .unwrap();
+                black_box(length)
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Rows of four values like the users of [`setup`]
+fn user_rows(rows: usize) -> Vec<Vec<SqlValue>> {
+    (0..u32::try_from(rows).unwrap())
+        .map(|i| {
+            vec![
+                SqlValue::I64(i.into()),
+                SqlValue::String(format!("user {i}")),
+                SqlValue::String(format!("user{i}@example.com")),
+                SqlValue::F64(f64::from(i) * 0.5),
+            ]
+        })
+        .collect()
+}
+
+/// Read rows with `next_row()` from the old and the new result layout
+fn result_layout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("result_layout");
+
+    for rows in [1_000, 10_000, 100_000] {
+        group.throughput(Throughput::Elements(rows as u64));
+
+        // A vector per row, cloned when read
+        group.bench_with_input(BenchmarkId::new("rows_cloned", rows), &rows, |b, &rows| {
+            b.iter_batched(
+                || user_rows(rows),
+                |buffer| {
+                    let read: Vec<Vec<SqlValue>> = buffer.iter().map(Clone::clone).collect();
+                    black_box(read)
+                },
+                BatchSize::LargeInput,
+            );
+        });
+
+        // One row-major buffer, values moved out when read
+        group.bench_with_input(BenchmarkId::new("row_major_moved", rows), &rows, |b, &rows| {
+            b.iter_batched(
+                || user_rows(rows).into_iter().flatten().collect::<Vec<_>>(),
+                |mut buffer| {
+                    let read: Vec<Vec<SqlValue>> =
+                        buffer.chunks_mut(4).map(|row| row.iter_mut().map(std::mem::take).collect()).collect();
+                    black_box(read)
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, read_rows, result_layout);
+criterion_main!(benches);
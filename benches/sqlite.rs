@@ -0,0 +1,107 @@
+//! `SQLite` end to end through `Connection`
+//!
+//! Inserts row by row in a transaction, inserts in multi-row statements and
+//! selects into tuples, against an in-memory database.
+
+// Generated by criterion_group!/criterion_main!
+#![allow(missing_docs, clippy::missing_docs_in_private_items)]
+#![allow(clippy::unwrap_used)]
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rustine_dbal::connection::Connection;
+use rustine_dbal::driver::SqliteDriver;
+use rustine_dbal::platform::SqlitePlatform;
+use rustine_dbal::query::QueryBuilder;
+use rustine_dbal::SqlValue;
+use tokio::runtime::Runtime;
+
+/// Number of rows each benchmark writes or reads
+const ROWS: u64 = 1_000;
+
+/// Connect to an empty in-memory database with a users table
+async fn connect() -> Connection<SqliteDriver> {
+    let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+    conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, score REAL)")
+        .await
+        .unwrap();
+    conn
+}
+
+/// Get the values of a generated user
+fn user(id: u64) -> Vec<SqlValue> {
+    vec![SqlValue::from(id), SqlValue::from(format!("user {id}")), SqlValue::F64(0.5)]
+}
+
+/// Insert rows one statement at a time and in multi-row statements
+fn insert(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let platform = SqlitePlatform::new();
+    let mut group = c.benchmark_group("sqlite_insert");
+    group.throughput(Throughput::Elements(ROWS));
+
+    group.bench_function(BenchmarkId::new("row_by_row", ROWS), |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let conn = connect().await;
+                conn.begin_transaction().await.unwrap();
+                for id in 0..ROWS {
+                    let query = QueryBuilder::insert().into("users").insert_columns(&["id", "name", "score"]).values(user(id));
+                    conn.execute(&query.to_sql(&platform)).await.unwrap();
+                }
+                conn.commit().await.unwrap();
+            });
+        });
+    });
+
+    group.bench_function(BenchmarkId::new("batches_of_100", ROWS), |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                let conn = connect().await;
+                for batch in 0..ROWS / 100 {
+                    let mut query = QueryBuilder::insert().into("users").insert_columns(&["id", "name", "score"]);
+                    for id in batch * 100..(batch + 1) * 100 {
+                        query = query.values(user(id));
+                    }
+                    conn.execute(&query.to_sql(&platform)).await.unwrap();
+                }
+            });
+        });
+    });
+
+    group.finish();
+}
+
+/// Select all rows into tuples and into result sets
+fn select(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let platform = SqlitePlatform::new();
+    let conn = runtime.block_on(async {
+        let conn = connect().await;
+        let mut query = QueryBuilder::insert().into("users").insert_columns(&["id", "name", "score"]);
+        for id in 0..ROWS {
+            query = query.values(user(id));
+        }
+        conn.execute(&query.to_sql(&platform)).await.unwrap();
+        conn
+    });
+    let mut group = c.benchmark_group("sqlite_select");
+    group.throughput(Throughput::Elements(ROWS));
+
+    group.bench_function(BenchmarkId::new("fetch_all_as", ROWS), |b| {
+        b.iter(|| {
+            let rows: Vec<(i64, String, f64)> =
+                runtime.block_on(conn.fetch_all_as("SELECT id, name, score FROM users", &[])).unwrap();
+            black_box(rows)
+        });
+    });
+
+    let query = QueryBuilder::select().columns(&["id", "name", "score"]).from("users");
+    group.bench_function(BenchmarkId::new("fetch", ROWS), |b| {
+        b.iter(|| black_box(runtime.block_on(conn.fetch(&platform, &query)).unwrap()));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, insert, select);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+//! Converting between Rust types and `SqlValue`
+//!
+//! Covers `ToSql`/`FromSql` for common column types and `FromRow` for
+//! tuples, which run once per cell when reading results.
+
+// Generated by criterion_group!/criterion_main!
+#![allow(missing_docs, clippy::missing_docs_in_private_items)]
+#![allow(clippy::unwrap_used)]
+
+use chrono::NaiveDateTime;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rustine_dbal::core::FromRow;
+use rustine_dbal::{FromSql, SqlValue, ToSql};
+
+/// Convert single values in both directions
+fn scalars(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scalars");
+    group.bench_function("i64_to_sql", |b| b.iter(|| black_box(42_i64).to_sql().unwrap()));
+    group.bench_function("i64_from_sql", |b| b.iter(|| i64::from_sql(black_box(SqlValue::I32(42))).unwrap()));
+    group.bench_function("string_to_sql", |b| {
+        let text = "Grüße aus Berlin".to_string();
+        b.iter(|| black_box(&text).to_sql().unwrap());
+    });
+    group.bench_function("string_from_sql", |b| {
+        let value = SqlValue::String("Grüße aus Berlin".into());
+        b.iter(|| String::from_sql(black_box(value.clone())).unwrap());
+    });
+    group.bench_function("f64_from_sql_strict", |b| {
+        b.iter(|| f64::from_sql_strict(black_box(SqlValue::I64(1 << 40))).unwrap());
+    });
+    group.bench_function("datetime_from_text", |b| {
+        let value = SqlValue::String("2024-05-17 13:45:10".into());
+        b.iter(|| NaiveDateTime::from_sql(black_box(value.clone())).unwrap());
+    });
+    group.bench_function("option_from_null", |b| {
+        b.iter(|| Option::<i64>::from_sql(black_box(SqlValue::Null)).unwrap());
+    });
+    group.finish();
+}
+
+/// Convert whole rows into tuples
+fn rows(c: &mut Criterion) {
+    let row = vec![
+        SqlValue::I64(7),
+        SqlValue::String("Alice".into()),
+        SqlValue::String("alice@example.com".into()),
+        SqlValue::F64(12.5),
+        SqlValue::Null,
+    ];
+    c.bench_function("tuple_from_row", |b| {
+        b.iter(|| <(i64, String, String, f64, Option<bool>)>::from_row(black_box(row.clone())).unwrap());
+    });
+}
+
+criterion_group!(benches, scalars, rows);
+criterion_main!(benches);
@@ -0,0 +1,191 @@
+//! Integration tests against PostgreSQL and MySQL servers
+//!
+//! `Connection` and `SchemaManager` only have `SQLite` drivers so far, so the
+//! SQL of the PostgreSQL and MySQL platforms is otherwise only checked by
+//! comparing strings. This crate starts both servers in Docker with
+//! testcontainers and runs the DDL, introspection and query builder SQL
+//! through the native clients (`tokio-postgres`, `mysql_async`).
+//!
+//! Starting the containers needs Docker, so the tests are skipped unless
+//! `RUSTINE_IT` is set:
+//!
+//! ```bash
+//! RUSTINE_IT=1 cargo test --manifest-path it/Cargo.toml
+//! ```
+
+use mysql_async::prelude::Queryable;
+use rustine_dbal::platform::{MySqlPlatform, Platform, PostgresPlatform};
+use testcontainers_modules::mysql::Mysql;
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
+
+/// Environment variable enabling the tests
+pub const ENABLE_VAR: &str = "RUSTINE_IT";
+
+/// A result row as text, `None` for NULL
+pub type Row = Vec<Option<String>>;
+
+/// Connection to a server, keeping its container running
+enum Client {
+    Postgres { client: tokio_postgres::Client, _container: ContainerAsync<Postgres> },
+    MySql { conn: mysql_async::Conn, _container: ContainerAsync<Mysql> },
+}
+
+/// A database server running in Docker and the platform generating its SQL
+pub struct Server {
+    platform: Box<dyn Platform>,
+    client: Client,
+}
+
+impl Server {
+    /// Start PostgreSQL 16
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container doesn't start or the connection fails.
+    pub async fn postgres() -> Self {
+        let container = Postgres::default()
+            .with_tag("16-alpine")
+            .start()
+            .await
+            .expect("PostgreSQL container should start");
+        let url = format!(
+            "postgres://postgres:postgres@{}:{}/postgres",
+            container.get_host().await.expect("container host"),
+            container.get_host_port_ipv4(5432).await.expect("PostgreSQL port"),
+        );
+        let (client, connection) = tokio_postgres::connect(&url, tokio_postgres::NoTls)
+            .await
+            .expect("PostgreSQL should accept connections");
+        tokio::spawn(connection);
+        Self {
+            platform: Box::new(PostgresPlatform),
+            client: Client::Postgres { client, _container: container },
+        }
+    }
+
+    /// Start MySQL 8
+    ///
+    /// # Panics
+    ///
+    /// Panics if the container doesn't start or the connection fails.
+    pub async fn mysql() -> Self {
+        let container = Mysql::default()
+            .with_tag("8.4")
+            .start()
+            .await
+            .expect("MySQL container should start");
+        let url = format!(
+            "mysql://root@{}:{}/test",
+            container.get_host().await.expect("container host"),
+            container.get_host_port_ipv4(3306).await.expect("MySQL port"),
+        );
+        let conn = mysql_async::Conn::from_url(url)
+            .await
+            .expect("MySQL should accept connections");
+        Self {
+            platform: Box::new(MySqlPlatform::new()),
+            client: Client::MySql { conn, _container: container },
+        }
+    }
+
+    /// Get the platform generating SQL for this server
+    pub fn platform(&self) -> &dyn Platform {
+        self.platform.as_ref()
+    }
+
+    /// Get the platform name, for assertion messages
+    pub fn name(&self) -> &'static str {
+        self.platform.name()
+    }
+
+    /// Execute a statement, returning the number of affected rows
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error message.
+    pub async fn execute(&mut self, sql: &str) -> Result<u64, String> {
+        match &mut self.client {
+            Client::Postgres { client, .. } => client.execute(sql, &[]).await.map_err(|e| error(sql, e)),
+            Client::MySql { conn, .. } => {
+                conn.query_drop(sql).await.map_err(|e| error(sql, e))?;
+                Ok(conn.affected_rows())
+            }
+        }
+    }
+
+    /// Execute statements in order, stopping at the first failure
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error message.
+    pub async fn execute_all(&mut self, statements: &[String]) -> Result<(), String> {
+        for sql in statements {
+            self.execute(sql).await?;
+        }
+        Ok(())
+    }
+
+    /// Run a query, returning the rows as text
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error message.
+    pub async fn query(&mut self, sql: &str) -> Result<Vec<Row>, String> {
+        match &mut self.client {
+            Client::Postgres { client, .. } => {
+                let messages = client.simple_query(sql).await.map_err(|e| error(sql, e))?;
+                Ok(messages
+                    .iter()
+                    .filter_map(|message| match message {
+                        tokio_postgres::SimpleQueryMessage::Row(row) => {
+                            Some((0..row.len()).map(|i| row.get(i).map(str::to_string)).collect())
+                        }
+                        _ => None,
+                    })
+                    .collect())
+            }
+            Client::MySql { conn, .. } => {
+                let rows: Vec<mysql_async::Row> = conn.query(sql).await.map_err(|e| error(sql, e))?;
+                Ok(rows
+                    .into_iter()
+                    .map(|row| {
+                        row.unwrap()
+                            .into_iter()
+                            .map(|value| match value {
+                                mysql_async::Value::NULL => None,
+                                mysql_async::Value::Bytes(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+                                other => Some(other.as_sql(false)),
+                            })
+                            .collect()
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Run a query, returning the first column of every row
+    ///
+    /// # Errors
+    ///
+    /// Returns the server's error message.
+    pub async fn query_column(&mut self, sql: &str) -> Result<Vec<Option<String>>, String> {
+        let rows = self.query(sql).await?;
+        Ok(rows.into_iter().map(|row| row.into_iter().next().flatten()).collect())
+    }
+}
+
+/// Describe a failed statement
+fn error(sql: &str, error: impl std::fmt::Display) -> String {
+    format!("{error}\n  in: {sql}")
+}
+
+/// Start every server, or none unless the tests are enabled
+pub async fn servers() -> Vec<Server> {
+    if std::env::var_os(ENABLE_VAR).is_none() {
+        eprintln!("skipped: set {ENABLE_VAR}=1 to run against PostgreSQL and MySQL in Docker");
+        return Vec::new();
+    }
+    vec![Server::postgres().await, Server::mysql().await]
+}
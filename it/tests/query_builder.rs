@@ -0,0 +1,103 @@
+//! Query builder SQL on PostgreSQL and MySQL
+
+use rustine_dbal::platform::{Column, Index, SqlType, Table};
+use rustine_dbal::query::{col, val, OrderDirection, QueryBuilder};
+use rustine_dbal::SqlValue;
+use rustine_dbal_it::{servers, Server};
+
+/// Strings that break naive quoting
+const TRICKY: [&str; 6] = ["O'Brien", "C:\\temp\\", "\\'; DROP TABLE notes; --", "?", "$1 :name", "Grüße 😀"];
+
+/// Create the notes table
+async fn create_notes(server: &mut Server) {
+    let table = Table::new("notes")
+        .column(Column::new("id", SqlType::Integer).not_null())
+        .column(Column::new("body", SqlType::Text))
+        .column(Column::new("data", SqlType::Blob))
+        .column(Column::new("score", SqlType::Double))
+        .column(Column::new("done", SqlType::Boolean))
+        .index(Index::primary(vec!["id".into()]));
+    let name = server.name();
+    let sql = server.platform().get_create_table_sql(&table);
+    server.execute(&sql).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+}
+
+#[tokio::test]
+async fn literals_round_trip() {
+    for mut server in servers().await {
+        let name = server.name();
+        create_notes(&mut server).await;
+
+        let rows = TRICKY
+            .iter()
+            .zip(1_i64..)
+            .map(|(text, id)| {
+                vec![
+                    SqlValue::I64(id),
+                    SqlValue::from(*text),
+                    SqlValue::Bytes(text.as_bytes().to_vec()),
+                    SqlValue::F64(0.1 * id as f64),
+                    SqlValue::Bool(id % 2 == 0),
+                ]
+            })
+            .collect();
+        let insert = QueryBuilder::insert()
+            .into("notes")
+            .insert_columns(&["id", "body", "data", "score", "done"])
+            .values_batch(rows);
+        let inserted = server.execute(&insert.to_sql(server.platform())).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(inserted, TRICKY.len() as u64, "{name}");
+
+        for text in TRICKY {
+            let select = QueryBuilder::select()
+                .columns(&["body"])
+                .from("notes")
+                .where_expr(col("body").eq(val(text)).and(col("data").eq(val(text.as_bytes().to_vec()))));
+            let bodies = server.query_column(&select.to_sql(server.platform())).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert_eq!(bodies, vec![Some(text.to_string())], "{name}");
+        }
+
+        let sql = server.platform().get_drop_table_sql("notes");
+        server.execute(&sql).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+    }
+}
+
+#[tokio::test]
+async fn select_update_delete() {
+    for mut server in servers().await {
+        let name = server.name();
+        create_notes(&mut server).await;
+
+        let rows = (1..=10).map(|id| vec![SqlValue::I64(id), SqlValue::from(format!("note {id}"))]).collect();
+        let insert = QueryBuilder::insert().into("notes").insert_columns(&["id", "body"]).values_batch(rows);
+        server.execute(&insert.to_sql(server.platform())).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        let page = QueryBuilder::select()
+            .columns(&["id"])
+            .from("notes")
+            .where_expr(col("id").between(3, 8).and(col("body").like("note%")))
+            .order_by("id", OrderDirection::Desc)
+            .limit(2)
+            .offset(1);
+        let ids = server.query_column(&page.to_sql(server.platform())).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(ids, vec![Some("7".into()), Some("6".into())], "{name}");
+
+        let update = QueryBuilder::update()
+            .table("notes")
+            .set("done", true)
+            .where_expr(col("id").in_list(vec![val(1), val(2), val(3)]));
+        let updated = server.execute(&update.to_sql(server.platform())).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(updated, 3, "{name}");
+
+        let delete = QueryBuilder::delete().from("notes").where_expr(col("done").is_null());
+        let deleted = server.execute(&delete.to_sql(server.platform())).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(deleted, 7, "{name}");
+
+        let count = QueryBuilder::select().all().from("notes").count_query();
+        let count = server.query_column(&count.to_sql(server.platform())).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(count, vec![Some("3".into())], "{name}");
+
+        let sql = server.platform().get_drop_table_sql("notes");
+        server.execute(&sql).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+    }
+}
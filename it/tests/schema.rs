@@ -0,0 +1,77 @@
+//! DDL and introspection SQL on PostgreSQL and MySQL
+
+use rustine_dbal::platform::{Column, ForeignKey, ForeignKeyAction, Index, Schema, SqlType, Table};
+use rustine_dbal_it::servers;
+
+/// Customers and their orders, covering every column type
+fn shop() -> Schema {
+    let customers = Table::new("customers")
+        .column(Column::new("id", SqlType::BigSerial))
+        .column(Column::new("email", SqlType::varchar(255)).not_null())
+        .column(Column::new("name", SqlType::Text))
+        .column(Column::new("country", SqlType::char(2)).default_value("DE"))
+        .column(Column::new("active", SqlType::Boolean).not_null().default_value(true))
+        .column(Column::new("created_at", SqlType::timestamp(None)).default_current_timestamp())
+        .column(Column::new("updated_at", SqlType::timestamp(Some(6))))
+        .index(Index::primary(vec!["id".into()]))
+        .index(Index::unique("customers_email", vec!["email".into()]));
+    let orders = Table::new("orders")
+        .column(Column::new("id", SqlType::Serial))
+        .column(Column::new("customer_id", SqlType::BigInt).not_null())
+        .column(Column::new("quantity", SqlType::SmallInt).default_value(1))
+        .column(Column::new("total", SqlType::decimal(10, 2)))
+        .column(Column::new("weight", SqlType::Float))
+        .column(Column::new("discount", SqlType::Double))
+        .column(Column::new("ordered_on", SqlType::Date))
+        .column(Column::new("ordered_at", SqlType::Time { precision: None }))
+        .column(Column::new("shipped_at", SqlType::TimestampTz { precision: None }))
+        .column(Column::new("reference", SqlType::Uuid))
+        .column(Column::new("details", SqlType::Json))
+        .column(Column::new("receipt", SqlType::Blob))
+        .column(Column::new("checksum", SqlType::VarBinary { length: 32 }))
+        .index(Index::primary(vec!["id".into()]))
+        .index(Index::new("orders_ordered_on", vec!["ordered_on".into()]))
+        .foreign_key(ForeignKey {
+            name: "orders_customer".into(),
+            local_columns: vec!["customer_id".into()],
+            foreign_table: "customers".into(),
+            foreign_columns: vec!["id".into()],
+            on_delete: ForeignKeyAction::Cascade,
+            on_update: ForeignKeyAction::NoAction,
+        });
+    Schema::new().table(orders).table(customers)
+}
+
+#[tokio::test]
+async fn create_introspect_and_drop_schema() {
+    let schema = shop();
+    for mut server in servers().await {
+        let name = server.name();
+        let create = server.platform().get_create_schema_sql(&schema);
+        server.execute_all(&create).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        let tables = server.query_column(server.platform().get_list_tables_sql()).await.unwrap();
+        for table in &schema.tables {
+            assert!(tables.contains(&Some(table.name.clone())), "{name}: {} not listed in {tables:?}", table.name);
+
+            let sql = server.platform().get_list_columns_sql(&table.name);
+            let columns = server.query_column(&sql).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+            let expected: Vec<Option<String>> = table.columns.iter().map(|c| Some(c.name.clone())).collect();
+            assert_eq!(columns, expected, "{name}: columns of {}", table.name);
+        }
+
+        let sql = server.platform().get_list_indexes_sql("customers");
+        let indexes = server.query_column(&sql).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert!(indexes.contains(&Some("customers_email".into())), "{name}: {indexes:?}");
+
+        let sql = server.platform().get_list_foreign_keys_sql("orders");
+        let foreign_keys = server.query(&sql).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(foreign_keys.len(), 1, "{name}: {foreign_keys:?}");
+        assert_eq!(foreign_keys[0][2].as_deref(), Some("customers"), "{name}");
+
+        let drop = server.platform().get_drop_schema_sql(&schema);
+        server.execute_all(&drop).await.unwrap_or_else(|e| panic!("{name}: {e}"));
+        let tables = server.query_column(server.platform().get_list_tables_sql()).await.unwrap();
+        assert!(tables.is_empty(), "{name}: {tables:?} left over");
+    }
+}
@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# Compare benchmark results of the working tree against another revision.
+#
+# Usage: scripts/bench-compare.sh [base-revision] [criterion args...]
+#
+# Runs the benchmarks on the base revision (default: main) in a temporary
+# worktree and saves them as the criterion baseline "base", then runs them
+# on the working tree against that baseline. Criterion prints the change of
+# every benchmark; extra arguments are passed on, e.g. a filter such as
+# `sqlite_select`.
+set -euo pipefail
+
+base="${1:-main}"
+shift || true
+
+root="$(git rev-parse --show-toplevel)"
+worktree="$(mktemp -d)"
+trap 'git -C "$root" worktree remove --force "$worktree"' EXIT
+
+# Both runs share a target directory so criterion finds the saved baseline
+export CARGO_TARGET_DIR="${CARGO_TARGET_DIR:-$root/target}"
+
+git -C "$root" worktree add --detach "$worktree" "$base"
+(cd "$worktree" && cargo bench --features sqlite -- --save-baseline base "$@")
+(cd "$root" && cargo bench --features sqlite -- --baseline base "$@")
@@ -0,0 +1,99 @@
+//! `rustine db` commands
+
+use std::path::Path;
+
+use clap::Subcommand;
+use rustine_dbal::blocking::Connection;
+use rustine_dbal::driver::SqliteDriver;
+use rustine_dbal::{ConnectionParams, Error, Result};
+
+/// Database commands
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Create the database of a connection URL
+    Create {
+        /// Connection URL, e.g. `sqlite://app.db`
+        url: String,
+    },
+    /// Drop the database of a connection URL
+    Drop {
+        /// Connection URL, e.g. `sqlite://app.db`
+        url: String,
+    },
+}
+
+/// Run a database command and return its output
+pub fn run(command: &DbCommand) -> Result<String> {
+    match command {
+        DbCommand::Create { url } => {
+            let path = database_file(&ConnectionParams::from_url(url)?)?;
+            create(&path)?;
+            Ok(format!("Created database {path}"))
+        }
+        DbCommand::Drop { url } => {
+            let path = database_file(&ConnectionParams::from_url(url)?)?;
+            drop_database(&path)?;
+            Ok(format!("Dropped database {path}"))
+        }
+    }
+}
+
+/// Get the database file of `SQLite` connection parameters
+fn database_file(params: &ConnectionParams) -> Result<String> {
+    if params.driver != "sqlite" {
+        return Err(Error::config(format!(
+            "db commands support sqlite only, got '{}'",
+            params.driver
+        )));
+    }
+    match params.path.as_deref() {
+        None | Some(":memory:") => Err(Error::config("an in-memory database can't be created or dropped")),
+        Some(path) => Ok(path.to_string()),
+    }
+}
+
+/// Create a database file, failing if it exists
+fn create(path: &str) -> Result<()> {
+    if Path::new(path).exists() {
+        return Err(Error::config(format!("database {path} already exists")));
+    }
+    let params = ConnectionParams::sqlite().with_path(path).with_option("mode", "rwc");
+    Connection::new(&SqliteDriver::new(), &params)?.close()
+}
+
+/// Remove a database file with its journal files
+fn drop_database(path: &str) -> Result<()> {
+    std::fs::remove_file(path).map_err(|e| Error::driver(format!("Failed to drop database {path}"), e))?;
+    for suffix in ["-journal", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{path}{suffix}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_and_drop() {
+        let path = std::env::temp_dir().join(format!("rustine_cli_{}.db", std::process::id()));
+        let path = path.display().to_string();
+
+        create(&path).unwrap();
+        assert!(Path::new(&path).exists());
+        assert!(create(&path).is_err());
+
+        drop_database(&path).unwrap();
+        assert!(!Path::new(&path).exists());
+        assert!(drop_database(&path).is_err());
+    }
+
+    #[test]
+    fn test_database_file() {
+        let params = ConnectionParams::from_url("sqlite://app.db").unwrap();
+        assert_eq!(database_file(&params).unwrap(), "app.db");
+
+        assert!(database_file(&ConnectionParams::sqlite_memory()).is_err());
+        assert!(database_file(&ConnectionParams::from_url("postgres://localhost/app").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! `rustine` command line tool
+//!
+//! Database and schema operations on top of the library:
+//!
+//! ```text
+//! rustine db create sqlite://app.db
+//! rustine db drop sqlite://app.db
+//! rustine schema dump sqlite://app.db --platform postgres --data
+//! rustine schema diff --from sqlite://app.db --to sqlite://expected.db
+//! rustine migrate up sqlite://app.db --dir migrations
+//! rustine migrate down sqlite://app.db --to 3
+//! rustine migrate status sqlite://app.db
+//! rustine shell sqlite://app.db
+//! ```
+//!
+//! Requires the `cli` feature. Connections use the `SQLite` driver.
+
+mod db;
+mod migrate;
+mod schema;
+mod shell;
+
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+/// Database and schema operations for Rustine DBAL
+#[derive(Debug, Parser)]
+#[command(name = "rustine", version)]
+struct Cli {
+    /// Command to run
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Top-level commands
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create or drop databases
+    #[command(subcommand)]
+    Db(db::DbCommand),
+    /// Inspect and compare schemas
+    #[command(subcommand)]
+    Schema(schema::SchemaCommand),
+    /// Apply, revert and list migrations
+    #[command(subcommand)]
+    Migrate(migrate::MigrateCommand),
+    /// Start an interactive SQL shell
+    Shell {
+        /// Connection URL, e.g. `sqlite://app.db`
+        url: String,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Db(command) => db::run(&command),
+        Command::Schema(command) => schema::run(&command),
+        Command::Migrate(command) => migrate::run(&command),
+        Command::Shell { url } => shell::run(&url),
+    };
+
+    match result {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{output}");
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
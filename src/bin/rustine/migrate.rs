@@ -0,0 +1,188 @@
+//! `rustine migrate` commands
+//!
+//! Migrations are read from a directory of SQL files named
+//! `<version>_<name>.up.sql`, with an optional `<version>_<name>.down.sql`
+//! reverting it. Each file is run as one script.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use clap::Subcommand;
+use rustine_dbal::blocking::Connection;
+use rustine_dbal::driver::SqliteDriver;
+use rustine_dbal::migrations::{Migration, Migrator};
+use rustine_dbal::platform::SqlitePlatform;
+use rustine_dbal::{Error, Result};
+
+/// Migration commands
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// Apply the pending migrations
+    Up {
+        /// Connection URL, e.g. `sqlite://app.db`
+        url: String,
+        /// Directory of the migration files
+        #[arg(long, default_value = "migrations")]
+        dir: String,
+    },
+    /// Revert applied migrations
+    Down {
+        /// Connection URL, e.g. `sqlite://app.db`
+        url: String,
+        /// Directory of the migration files
+        #[arg(long, default_value = "migrations")]
+        dir: String,
+        /// Revert the migrations above this version (defaults to reverting the latest one)
+        #[arg(long)]
+        to: Option<i64>,
+    },
+    /// List the migrations and whether they are applied
+    Status {
+        /// Connection URL, e.g. `sqlite://app.db`
+        url: String,
+        /// Directory of the migration files
+        #[arg(long, default_value = "migrations")]
+        dir: String,
+    },
+}
+
+/// Run a migration command and return its output
+pub fn run(command: &MigrateCommand) -> Result<String> {
+    let (MigrateCommand::Up { url, dir } | MigrateCommand::Down { url, dir, .. } | MigrateCommand::Status { url, dir }) =
+        command;
+    let conn = Connection::<SqliteDriver>::from_url(url)?;
+    let migrations = load(Path::new(dir))?;
+    match command {
+        MigrateCommand::Up { .. } => up(&conn, migrations),
+        MigrateCommand::Down { to, .. } => down(&conn, migrations, *to),
+        MigrateCommand::Status { .. } => status(&conn, migrations),
+    }
+}
+
+/// Read the migrations of a directory
+fn load(dir: &Path) -> Result<Vec<Migration>> {
+    let read_error = |e| Error::driver(format!("Failed to read migrations from {}", dir.display()), e);
+    let mut migrations = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        let Some(stem) = path.file_name().and_then(|name| name.to_str()?.strip_suffix(".up.sql")) else {
+            continue;
+        };
+        let (version, name) = stem.split_once('_').unwrap_or((stem, ""));
+        let version = version
+            .parse()
+            .map_err(|_| Error::config(format!("migration file {} doesn't start with a version", path.display())))?;
+        let mut migration = Migration::new(version, name.replace('_', " ")).up(read(&path)?);
+        let down = path.with_file_name(format!("{stem}.down.sql"));
+        if down.exists() {
+            migration = migration.down(read(&down)?);
+        }
+        migrations.push(migration);
+    }
+    Ok(migrations)
+}
+
+/// Read a migration file
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .map(|sql| sql.trim().to_string())
+        .map_err(|e| Error::driver(format!("Failed to read {}", path.display()), e))
+}
+
+/// Create a migrator for the migrations
+fn migrator<'a>(
+    conn: &'a Connection<SqliteDriver>,
+    platform: &'a SqlitePlatform,
+    migrations: Vec<Migration>,
+) -> Migrator<'a, <SqliteDriver as rustine_dbal::driver::Driver>::Connection> {
+    migrations
+        .into_iter()
+        .fold(Migrator::new(conn.inner(), platform), Migrator::migration)
+}
+
+/// Apply the pending migrations
+fn up(conn: &Connection<SqliteDriver>, migrations: Vec<Migration>) -> Result<String> {
+    let platform = SqlitePlatform::new();
+    let migrator = migrator(conn, &platform, migrations);
+    let versions = conn.block_on(migrator.migrate())?;
+    Ok(versions_report("Applied", &versions))
+}
+
+/// Revert the applied migrations above a version, by default the latest one
+fn down(conn: &Connection<SqliteDriver>, migrations: Vec<Migration>, to: Option<i64>) -> Result<String> {
+    let platform = SqlitePlatform::new();
+    let migrator = migrator(conn, &platform, migrations);
+    let to = if let Some(to) = to {
+        to
+    } else {
+        let applied = conn.block_on(migrator.applied())?;
+        match applied.as_slice() {
+            [] => return Ok(versions_report("Reverted", &[])),
+            [.., previous, _] => previous.version,
+            [_] => i64::MIN,
+        }
+    };
+    let versions = conn.block_on(migrator.migrate_down(to))?;
+    Ok(versions_report("Reverted", &versions))
+}
+
+/// List the migrations as applied, pending, changed or missing
+fn status(conn: &Connection<SqliteDriver>, mut migrations: Vec<Migration>) -> Result<String> {
+    migrations.sort_by_key(|migration| migration.version);
+    let platform = SqlitePlatform::new();
+    let applied = conn.block_on(migrator(conn, &platform, Vec::new()).applied())?;
+
+    let mut out = String::new();
+    for migration in &migrations {
+        let state = match applied.iter().find(|a| a.version == migration.version) {
+            Some(a) if a.checksum != migration.checksum() => "changed",
+            Some(_) => "applied",
+            None => "pending",
+        };
+        let _ = writeln!(out, "{state:<8} {} {}", migration.version, migration.name);
+    }
+    for a in applied.iter().filter(|a| !migrations.iter().any(|m| m.version == a.version)) {
+        let _ = writeln!(out, "{:<8} {} {}", "missing", a.version, a.name);
+    }
+    out.pop();
+    Ok(out)
+}
+
+/// Describe the versions a command applied or reverted
+fn versions_report(action: &str, versions: &[i64]) -> String {
+    if versions.is_empty() {
+        return "Nothing to do".to_string();
+    }
+    let versions: Vec<String> = versions.iter().map(ToString::to_string).collect();
+    format!("{action} {}", versions.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate() {
+        let dir = std::env::temp_dir().join(format!("rustine-migrate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("1_create_users.up.sql"), "CREATE TABLE users (id INTEGER PRIMARY KEY);\n").unwrap();
+        std::fs::write(dir.join("1_create_users.down.sql"), "DROP TABLE users;").unwrap();
+        std::fs::write(dir.join("2_add_email.up.sql"), "ALTER TABLE users ADD COLUMN email TEXT").unwrap();
+        std::fs::write(dir.join("2_add_email.down.sql"), "ALTER TABLE users DROP COLUMN email").unwrap();
+        std::fs::write(dir.join("README"), "not a migration").unwrap();
+
+        let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").unwrap();
+        let migrations = || load(&dir).unwrap();
+        assert_eq!(status(&conn, migrations()).unwrap(), "pending  1 create users\npending  2 add email");
+        assert_eq!(up(&conn, migrations()).unwrap(), "Applied 1, 2");
+        assert_eq!(up(&conn, migrations()).unwrap(), "Nothing to do");
+        assert_eq!(down(&conn, migrations(), None).unwrap(), "Reverted 2");
+        assert_eq!(status(&conn, migrations()).unwrap(), "applied  1 create users\npending  2 add email");
+        assert_eq!(up(&conn, migrations()).unwrap(), "Applied 2");
+        assert_eq!(down(&conn, migrations(), Some(0)).unwrap(), "Reverted 2, 1");
+
+        std::fs::write(dir.join("x_broken.up.sql"), "SELECT 1").unwrap();
+        assert!(load(&dir).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
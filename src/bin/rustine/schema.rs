@@ -0,0 +1,226 @@
+//! `rustine schema` commands
+
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use clap::Subcommand;
+use rustine_dbal::blocking::Connection;
+use rustine_dbal::driver::SqliteDriver;
+use rustine_dbal::dump::{DumpOptions, Dumper};
+use rustine_dbal::platform::{Platform, PlatformRegistry, Schema, SqlitePlatform};
+use rustine_dbal::schema::{Comparator, SchemaDiff, SchemaManager};
+use rustine_dbal::{Error, Result};
+
+/// Schema commands
+#[derive(Debug, Subcommand)]
+pub enum SchemaCommand {
+    /// Print the schema as a SQL script
+    Dump {
+        /// Connection URL, e.g. `sqlite://app.db`
+        url: String,
+        /// Platform to write the SQL for (defaults to the database's own)
+        #[arg(long)]
+        platform: Option<String>,
+        /// Only dump these tables (comma separated)
+        #[arg(long, value_delimiter = ',')]
+        tables: Vec<String>,
+        /// Include the table data as INSERT statements
+        #[arg(long)]
+        data: bool,
+    },
+    /// Print the differences turning one schema into another
+    Diff {
+        /// Connection URL or JSON schema file (`.json`) of the current schema
+        #[arg(long)]
+        from: String,
+        /// Connection URL or JSON schema file (`.json`) of the wanted schema
+        #[arg(long)]
+        to: String,
+    },
+}
+
+/// Run a schema command and return its output
+pub fn run(command: &SchemaCommand) -> Result<String> {
+    match command {
+        SchemaCommand::Dump {
+            url,
+            platform,
+            tables,
+            data,
+        } => {
+            let conn = Connection::<SqliteDriver>::from_url(url)?;
+            let target = platform.as_deref().unwrap_or("sqlite");
+            dump(&conn, &platform_named(target)?, tables, *data)
+        }
+        SchemaCommand::Diff { from, to } => {
+            let from = load(from)?;
+            let to = load(to)?;
+            Ok(report(&Comparator::new().compare(&from, &to), &SqlitePlatform::new()))
+        }
+    }
+}
+
+/// Read a schema from a JSON file (`.json`) or the database a URL points to
+fn load(source: &str) -> Result<Schema> {
+    if !Path::new(source)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    {
+        return introspect(&Connection::<SqliteDriver>::from_url(source)?);
+    }
+    let json = std::fs::read_to_string(source)
+        .map_err(|e| Error::driver(format!("Failed to read {source}"), e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| Error::config(format!("invalid schema file {source}: {e}")))
+}
+
+/// Read the schema of a database
+fn introspect(conn: &Connection<SqliteDriver>) -> Result<Schema> {
+    let platform = SqlitePlatform::new();
+    let manager = SchemaManager::new(conn.inner(), &platform);
+    conn.block_on(manager.introspect_schema())
+}
+
+/// Describe a schema diff, one line per table, column, index and foreign key
+fn report(diff: &SchemaDiff, platform: &dyn Platform) -> String {
+    if diff.is_empty() {
+        return "No differences".to_string();
+    }
+    let mut out = String::new();
+    for table in &diff.created_tables {
+        let _ = writeln!(out, "+ table {}", table.name);
+    }
+    for table in &diff.dropped_tables {
+        let _ = writeln!(out, "- table {}", table.name);
+    }
+    for table in &diff.changed_tables {
+        let _ = writeln!(out, "~ table {}", table.name);
+        for column in &table.added_columns {
+            let _ = writeln!(out, "    + column {}", platform.get_column_declaration(column));
+        }
+        for column in &table.removed_columns {
+            let _ = writeln!(out, "    - column {}", column.name);
+        }
+        for column in &table.changed_columns {
+            let _ = writeln!(
+                out,
+                "    ~ column {} -> {}",
+                platform.get_column_declaration(&column.from),
+                platform.get_column_declaration(&column.to)
+            );
+        }
+        for index in &table.added_indexes {
+            let _ = writeln!(out, "    + index {} ({})", index.name, index.columns.join(", "));
+        }
+        for index in &table.removed_indexes {
+            let _ = writeln!(out, "    - index {} ({})", index.name, index.columns.join(", "));
+        }
+        for fk in &table.added_foreign_keys {
+            let _ = writeln!(out, "    + foreign key ({}) -> {}", fk.local_columns.join(", "), fk.foreign_table);
+        }
+        for fk in &table.removed_foreign_keys {
+            let _ = writeln!(out, "    - foreign key ({}) -> {}", fk.local_columns.join(", "), fk.foreign_table);
+        }
+        if table.column_order_changed {
+            let _ = writeln!(out, "    ~ column order");
+        }
+    }
+    out.pop();
+    out
+}
+
+/// Look up a platform by driver name
+fn platform_named(name: &str) -> Result<Arc<dyn Platform>> {
+    PlatformRegistry::with_defaults()
+        .get(name)
+        .ok_or_else(|| Error::config(format!("unknown platform '{name}'")))
+}
+
+/// Dump the schema (and optionally the data) as a script for the target platform
+fn dump(conn: &Connection<SqliteDriver>, target: &Arc<dyn Platform>, tables: &[String], data: bool) -> Result<String> {
+    let mut options = DumpOptions::new();
+    if !tables.is_empty() {
+        options = options.with_tables(tables.iter().cloned());
+    }
+    if !data {
+        options = options.schema_only();
+    }
+
+    let source = platform_named("sqlite")?;
+    let dump = conn.block_on(Dumper::new(conn.inner(), source.as_ref()).dump(&options))?;
+    Ok(dump.to_sql_script(target.as_ref(), &options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump() {
+        let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").unwrap();
+        conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").unwrap();
+        conn.execute("INSERT INTO users (name) VALUES ('Alice')").unwrap();
+
+        let postgres = platform_named("postgres").unwrap();
+        let script = dump(&conn, &postgres, &[], false).unwrap();
+        assert!(script.contains("CREATE TABLE \"users\""));
+        assert!(!script.contains("INSERT"));
+
+        let script = dump(&conn, &postgres, &["users".to_string()], true).unwrap();
+        assert!(script.contains("'Alice'"));
+
+        assert!(dump(&conn, &postgres, &["missing".to_string()], false).is_err());
+        assert!(platform_named("oracle").is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let from = Connection::<SqliteDriver>::from_url("sqlite::memory:").unwrap();
+        from.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").unwrap();
+        from.execute("CREATE TABLE legacy (id INTEGER)").unwrap();
+        let to = Connection::<SqliteDriver>::from_url("sqlite::memory:").unwrap();
+        to.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, email TEXT)").unwrap();
+        to.execute("CREATE INDEX users_email ON users (email)").unwrap();
+        to.execute("CREATE TABLE posts (id INTEGER)").unwrap();
+
+        let platform = SqlitePlatform::new();
+        let diff = Comparator::new().compare(&introspect(&from).unwrap(), &introspect(&to).unwrap());
+        assert_eq!(
+            report(&diff, &platform),
+            "+ table posts\n- table legacy\n~ table users\n    + column \"email\" TEXT\n    \
+             ~ column \"name\" TEXT -> \"name\" TEXT NOT NULL\n    + index users_email (email)"
+        );
+        let same = Comparator::new().compare(&introspect(&from).unwrap(), &introspect(&from).unwrap());
+        assert_eq!(report(&same, &platform), "No differences");
+    }
+
+    #[test]
+    fn test_diff_schema_file() {
+        let db = Connection::<SqliteDriver>::from_url("sqlite::memory:").unwrap();
+        db.execute("CREATE TABLE users (id INTEGER NOT NULL, name TEXT)")
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("rustine-schema-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{"tables": [{"name": "users", "columns": [
+                {"name": "id", "sql_type": "Integer", "nullable": false},
+                {"name": "name", "sql_type": "Text", "nullable": true},
+                {"name": "email", "sql_type": {"Varchar": {"length": 255}}, "nullable": true}
+            ]}]}"#,
+        )
+        .unwrap();
+
+        let wanted = load(path.to_str().unwrap()).unwrap();
+        let diff = Comparator::new().compare(&introspect(&db).unwrap(), &wanted);
+        assert_eq!(
+            report(&diff, &SqlitePlatform::new()),
+            "~ table users\n    + column \"email\" VARCHAR(255)"
+        );
+
+        std::fs::write(&path, "{\"tables\": 1}").unwrap();
+        assert!(load(path.to_str().unwrap()).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(load(path.to_str().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! `rustine shell`, an interactive SQL shell
+//!
+//! Statements run when a line ends with `;`, meta-commands start with `\`:
+//!
+//! ```text
+//! \dt        list tables
+//! \d table   describe a table
+//! \q         quit
+//! ```
+
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use rustine_dbal::blocking::{Connection, SchemaManager};
+use rustine_dbal::driver::{ResultSet, SqliteDriver};
+use rustine_dbal::platform::{Platform, SqlitePlatform};
+use rustine_dbal::{Error, Result, SqlValue};
+
+/// Prompt for a new statement
+const PROMPT: &str = "rustine> ";
+
+/// Prompt for the continuation of a statement
+const CONTINUATION_PROMPT: &str = "     ...> ";
+
+/// What to do after a line of input
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    /// Print the output and read the next line
+    Print(String),
+    /// Wait for the rest of the statement
+    Continue,
+    /// Leave the shell
+    Quit,
+}
+
+/// Shell state: the connection and the statement being typed
+struct Shell {
+    /// Connection statements run on
+    conn: Connection<SqliteDriver>,
+    /// Platform used for introspection
+    platform: SqlitePlatform,
+    /// Lines of an unfinished statement
+    buffer: String,
+}
+
+impl Shell {
+    /// Create a shell on a connection
+    const fn new(conn: Connection<SqliteDriver>) -> Self {
+        Self {
+            conn,
+            platform: SqlitePlatform::new(),
+            buffer: String::new(),
+        }
+    }
+
+    /// Handle a line of input
+    fn handle_line(&mut self, line: &str) -> Result<Action> {
+        let line = line.trim();
+        if self.buffer.is_empty() {
+            if line.is_empty() {
+                return Ok(Action::Continue);
+            }
+            if let Some(command) = line.strip_prefix('\\') {
+                return self.meta_command(command);
+            }
+        }
+
+        if !self.buffer.is_empty() {
+            self.buffer.push('\n');
+        }
+        self.buffer.push_str(line);
+        if !line.ends_with(';') {
+            return Ok(Action::Continue);
+        }
+
+        let sql = std::mem::take(&mut self.buffer);
+        let sets = self.conn.query_multiple(&sql)?;
+        Ok(Action::Print(
+            sets.iter().map(format_result_set).collect::<Vec<_>>().join("\n\n"),
+        ))
+    }
+
+    /// Run a meta-command (without the leading backslash)
+    fn meta_command(&self, command: &str) -> Result<Action> {
+        let schema = SchemaManager::new(&self.conn, &self.platform);
+        let mut parts = command.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some("q"), None) => Ok(Action::Quit),
+            (Some("dt"), None) => {
+                let rows = schema.list_table_names()?.into_iter().map(|name| vec![name]).collect::<Vec<_>>();
+                Ok(Action::Print(format_table(&["Table"], &rows)))
+            }
+            (Some("d"), Some(table)) => {
+                if !schema.table_exists(table)? {
+                    return Err(Error::config(format!("table '{table}' does not exist")));
+                }
+                let rows = schema
+                    .list_table_columns(table)?
+                    .into_iter()
+                    .map(|column| {
+                        vec![
+                            column.name,
+                            column.type_name,
+                            if column.nullable { "YES" } else { "NO" }.to_string(),
+                            column.default.map(|d| self.platform.get_default_value_sql(&d)).unwrap_or_default(),
+                            if column.is_primary_key { "PRI" } else { "" }.to_string(),
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                Ok(Action::Print(format_table(&["Column", "Type", "Nullable", "Default", "Key"], &rows)))
+            }
+            _ => Err(Error::config(format!(
+                "unknown command '\\{command}', use \\dt, \\d table or \\q"
+            ))),
+        }
+    }
+}
+
+/// Run the shell on a connection URL until `\q` or end of input
+pub fn run(url: &str) -> Result<String> {
+    let mut shell = Shell::new(Connection::<SqliteDriver>::from_url(url)?);
+    let mut editor = DefaultEditor::new().map_err(|e| Error::driver("Failed to start line editor", e))?;
+
+    loop {
+        let prompt = if shell.buffer.is_empty() { PROMPT } else { CONTINUATION_PROMPT };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            // Ctrl-C discards the statement being typed
+            Err(ReadlineError::Interrupted) => {
+                shell.buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(Error::driver("Failed to read input", e)),
+        };
+        let _ = editor.add_history_entry(line.as_str());
+
+        match shell.handle_line(&line) {
+            Ok(Action::Print(output)) => println!("{output}"),
+            Ok(Action::Continue) => {}
+            Ok(Action::Quit) => break,
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+
+    shell.conn.close()?;
+    Ok(String::new())
+}
+
+/// Format a result set as a table, or the affected rows for statements
+fn format_result_set(set: &ResultSet) -> String {
+    if set.columns.is_empty() {
+        return format!("OK, {} rows affected", set.rows_affected);
+    }
+    let headers = set.columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+    let rows = set
+        .rows
+        .iter()
+        .map(|row| row.values().iter().map(format_value).collect())
+        .collect::<Vec<_>>();
+    format_table(&headers, &rows)
+}
+
+/// Format a value for display, without SQL quoting
+fn format_value(value: &SqlValue) -> String {
+    match value {
+        SqlValue::String(s) => s.clone(),
+        other => other.to_string().trim_matches('\'').to_string(),
+    }
+}
+
+/// Format rows as a table with aligned columns and a row count
+fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths = headers.iter().map(|h| h.chars().count()).collect::<Vec<_>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &mut dyn Iterator<Item = &str>| {
+        cells
+            .zip(&widths)
+            .map(|(cell, width)| format!(" {cell:<width$} "))
+            .collect::<Vec<_>>()
+            .join("|")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![
+        format_row(&mut headers.iter().copied()),
+        widths.iter().map(|w| "-".repeat(w + 2)).collect::<Vec<_>>().join("+"),
+    ];
+    lines.extend(rows.iter().map(|row| format_row(&mut row.iter().map(String::as_str))));
+    lines.push(match rows.len() {
+        1 => "(1 row)".to_string(),
+        n => format!("({n} rows)"),
+    });
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell() -> Shell {
+        let mut shell = Shell::new(Connection::<SqliteDriver>::from_url("sqlite::memory:").unwrap());
+        shell
+            .handle_line("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, bio TEXT);")
+            .unwrap();
+        shell
+    }
+
+    fn print(output: &str) -> Action {
+        Action::Print(output.to_string())
+    }
+
+    #[test]
+    fn test_statements() {
+        let mut shell = shell();
+        assert_eq!(shell.handle_line("INSERT INTO users (name) VALUES").unwrap(), Action::Continue);
+        assert_eq!(shell.handle_line("('Alice'), ('Bob');").unwrap(), print("OK, 2 rows affected"));
+
+        assert_eq!(
+            shell.handle_line("SELECT id, name, bio FROM users;").unwrap(),
+            print(" id | name  | bio\n----+-------+------\n 1  | Alice | NULL\n 2  | Bob   | NULL\n(2 rows)")
+        );
+
+        assert!(shell.handle_line("SELECT * FROM missing;").is_err());
+        assert_eq!(shell.handle_line("\\q").unwrap(), Action::Quit);
+    }
+
+    #[test]
+    fn test_meta_commands() {
+        let mut shell = shell();
+        assert_eq!(shell.handle_line("\\dt").unwrap(), print(" Table\n-------\n users\n(1 row)"));
+
+        assert_eq!(
+            shell.handle_line("\\d users").unwrap(),
+            print(concat!(
+                " Column | Type    | Nullable | Default | Key\n",
+                "--------+---------+----------+---------+-----\n",
+                " id     | INTEGER | NO       |         | PRI\n",
+                " name   | TEXT    | NO       |         |\n",
+                " bio    | TEXT    | YES      |         |\n",
+                "(3 rows)"
+            ))
+        );
+
+        assert!(shell.handle_line("\\d missing").is_err());
+        assert!(shell.handle_line("\\x").is_err());
+    }
+}
@@ -0,0 +1,718 @@
+//! Blocking connection
+
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+use crate::connection::{self, QueryLogger, SqlRewriter};
+use crate::core::{
+    Configuration, ConnectionParams, Error, FromRow, IsolationLevel, Result, SqlValue, TenantStrategy,
+    TransactionStatus,
+};
+use crate::driver::{Driver, DriverConnection, ResultSet};
+use crate::platform::{Platform, PlatformRegistry};
+
+use super::Statement;
+
+/// Blocking database connection
+///
+/// Wraps an async [`connection::Connection`] together with the runtime
+/// driving it. See the [module documentation](super) for details.
+pub struct Connection<D: Driver> {
+    /// The async connection
+    inner: connection::Connection<D>,
+    /// Runtime executing the async calls
+    runtime: Arc<Runtime>,
+}
+
+impl<D: Driver> std::fmt::Debug for Connection<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Connection")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<D: Driver> Connection<D> {
+    /// Create a new connection using the given driver and parameters
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime cannot be created or connecting fails.
+    pub fn new(driver: &D, params: &ConnectionParams) -> Result<Self> {
+        Self::connect_with(|| connection::Connection::new(driver, params))
+    }
+
+    /// Create a new connection using the given driver, parameters and configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime cannot be created or connecting fails.
+    pub fn new_with_configuration(
+        driver: &D,
+        params: &ConnectionParams,
+        configuration: Configuration,
+    ) -> Result<Self> {
+        Self::connect_with(|| connection::Connection::new_with_configuration(driver, params, configuration))
+    }
+
+    /// Wrap an async connection, creating a runtime for it
+    ///
+    /// The connection must not depend on the runtime it was created on
+    /// (drivers that spawn background tasks there stop working once that
+    /// runtime shuts down).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime cannot be created.
+    pub fn from_async(inner: connection::Connection<D>) -> Result<Self> {
+        Ok(Self {
+            inner,
+            runtime: Arc::new(new_runtime()?),
+        })
+    }
+
+    /// Use a custom query logger instead of the default `tracing` logger
+    #[must_use]
+    pub fn with_logger(mut self, logger: Arc<dyn QueryLogger>) -> Self {
+        self.inner = self.inner.with_logger(logger);
+        self
+    }
+
+    /// Add a hook rewriting statements before they are executed
+    #[must_use]
+    pub fn with_rewriter(mut self, rewriter: Arc<dyn SqlRewriter>) -> Self {
+        self.inner = self.inner.with_rewriter(rewriter);
+        self
+    }
+
+    /// Get the runtime configuration of this connection
+    #[must_use]
+    pub const fn configuration(&self) -> &Configuration {
+        self.inner.configuration()
+    }
+
+    /// Apply session settings from the configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed or a statement fails.
+    pub fn configure_session(&self, platform: &dyn Platform) -> Result<()> {
+        self.block_on(self.inner.configure_session(platform))
+    }
+
+    /// Switch the connection to a tenant's schema or database
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform can't switch to the tenant or the
+    /// statement fails.
+    pub fn use_tenant(&self, platform: &dyn Platform, tenant: &TenantStrategy) -> Result<()> {
+        self.block_on(self.inner.use_tenant(platform, tenant))
+    }
+
+    /// Get the async connection
+    #[must_use]
+    pub const fn as_async(&self) -> &connection::Connection<D> {
+        &self.inner
+    }
+
+    /// Get the underlying driver connection
+    #[must_use]
+    pub const fn inner(&self) -> &D::Connection {
+        self.inner.inner()
+    }
+
+    /// Run a future on the connection's runtime
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    // ========================================================================
+    // Query Execution
+    // ========================================================================
+
+    /// Execute a SQL query and return results
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails or the connection is closed.
+    pub fn query(&self, sql: &str) -> Result<<D::Connection as DriverConnection>::Result> {
+        self.block_on(self.inner.query(sql))
+    }
+
+    /// Execute a SQL batch and collect all of its result sets
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails or the connection is closed.
+    pub fn query_multiple(&self, sql: &str) -> Result<Vec<ResultSet>> {
+        self.block_on(self.inner.query_multiple(sql))
+    }
+
+    /// Execute a SQL statement and return affected rows
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement fails or the connection is closed.
+    pub fn execute(&self, sql: &str) -> Result<u64> {
+        self.block_on(self.inner.execute(sql))
+    }
+
+    /// Execute a data change and record it in the audit table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering or executing the query or writing the
+    /// audit row fails.
+    #[cfg(feature = "query-builder")]
+    pub fn execute_audited(
+        &self,
+        query: &crate::query::QueryBuilder,
+        platform: &dyn Platform,
+        audit: &connection::AuditLogger,
+    ) -> Result<u64> {
+        self.block_on(self.inner.execute_audited(query, platform, audit))
+    }
+
+    /// Execute a query and return the rows of its RETURNING columns
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering or executing a query fails, or if the
+    /// RETURNING clause can't be emulated.
+    #[cfg(feature = "query-builder")]
+    pub fn execute_returning(
+        &self,
+        query: &crate::query::QueryBuilder,
+        platform: &dyn Platform,
+        key: &str,
+    ) -> Result<ResultSet> {
+        self.block_on(self.inner.execute_returning(query, platform, key))
+    }
+
+    /// Insert a row into a table and return its primary key
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform can't provide the key for the
+    /// strategy or a query fails.
+    #[cfg(feature = "query-builder")]
+    pub fn insert(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        key: &str,
+        ids: &connection::IdGenerator,
+        values: &[(&str, SqlValue)],
+    ) -> Result<SqlValue> {
+        self.block_on(self.inner.insert(platform, table, key, ids, values))
+    }
+
+    /// Select the rows of a table matching the criteria
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering or executing the query fails.
+    #[cfg(feature = "query-builder")]
+    pub fn select(&self, platform: &dyn Platform, table: &str, criteria: &crate::query::Criteria) -> Result<ResultSet> {
+        self.block_on(self.inner.select(platform, table, criteria))
+    }
+
+    /// Run a SELECT built with the query builder, see `Configuration::select_star_policy`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `SELECT *` policy rejects the query, or
+    /// rendering or executing it fails.
+    #[cfg(feature = "query-builder")]
+    pub fn fetch(&self, platform: &dyn Platform, query: &crate::query::QueryBuilder) -> Result<ResultSet> {
+        self.block_on(self.inner.fetch(platform, query))
+    }
+
+    /// Update the rows of a table matching the criteria
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the criteria have no conditions, and
+    /// an error if rendering or executing the query fails.
+    #[cfg(feature = "query-builder")]
+    pub fn update(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        values: &[(&str, SqlValue)],
+        criteria: &crate::query::Criteria,
+    ) -> Result<u64> {
+        self.block_on(self.inner.update(platform, table, values, criteria))
+    }
+
+    /// Update many rows of a table by key in as few statements as the platform allows
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the key columns, columns and rows
+    /// don't match, and an error if a statement fails.
+    pub fn update_batch(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        key_columns: &[&str],
+        columns: &[&str],
+        rows: &[Vec<SqlValue>],
+    ) -> Result<u64> {
+        self.block_on(self.inner.update_batch(platform, table, key_columns, columns, rows))
+    }
+
+    /// Update a row with only the columns that changed, returning them
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `key` is empty,
+    /// `QueryError::RowNotFound` if no row matches `key`, and an error if
+    /// rendering or executing the query fails.
+    #[cfg(feature = "query-builder")]
+    pub fn update_changed(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        key: &[(&str, SqlValue)],
+        old_row: &[(&str, SqlValue)],
+        new_row: &[(&str, SqlValue)],
+    ) -> Result<Vec<String>> {
+        self.block_on(self.inner.update_changed(platform, table, key, old_row, new_row))
+    }
+
+    /// Delete the rows of a table matching the criteria
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the criteria have no conditions, and
+    /// an error if rendering or executing the query fails.
+    #[cfg(feature = "query-builder")]
+    pub fn delete(&self, platform: &dyn Platform, table: &str, criteria: &crate::query::Criteria) -> Result<u64> {
+        self.block_on(self.inner.delete(platform, table, criteria))
+    }
+
+    /// Run a query for a large IN list, in chunks the platform can bind
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the query acts on its whole row set
+    /// and needs several chunks, and an error if rendering or executing a
+    /// query fails.
+    #[cfg(feature = "query-builder")]
+    pub fn fetch_in_chunks(
+        &self,
+        platform: &dyn Platform,
+        query: &crate::query::QueryBuilder,
+        column: &str,
+        values: &[SqlValue],
+    ) -> Result<ResultSet> {
+        self.block_on(self.inner.fetch_in_chunks(platform, query, column, values))
+    }
+
+    /// Run an UPDATE or DELETE for a large IN list, in chunks the platform can bind
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the query acts on its whole row set
+    /// and needs several chunks, and an error if rendering or executing a
+    /// query fails.
+    #[cfg(feature = "query-builder")]
+    pub fn execute_in_chunks(
+        &self,
+        platform: &dyn Platform,
+        query: &crate::query::QueryBuilder,
+        column: &str,
+        values: &[SqlValue],
+    ) -> Result<u64> {
+        self.block_on(self.inner.execute_in_chunks(platform, query, column, values))
+    }
+
+    /// Prepare a SQL statement
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement preparation fails or the connection is closed.
+    pub fn prepare(&self, sql: &str) -> Result<Statement<'_, D>> {
+        let statement = self.block_on(self.inner.prepare(sql))?;
+        Ok(Statement::new(statement, &self.runtime))
+    }
+
+    /// Fetch all rows of a query and convert each into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails, the connection is closed, or a
+    /// row cannot be converted.
+    pub fn fetch_all_as<T: FromRow>(&self, sql: &str, params: &[SqlValue]) -> Result<Vec<T>> {
+        self.block_on(self.inner.fetch_all_as(sql, params))
+    }
+
+    // ========================================================================
+    // Transaction Management
+    // ========================================================================
+
+    /// Begin a new transaction or create a savepoint if already in a transaction
+    ///
+    /// # Errors
+    ///
+    /// Returns a transaction error if the transaction cannot be started.
+    pub fn begin_transaction(&self) -> Result<()> {
+        self.block_on(self.inner.begin_transaction())
+    }
+
+    /// Commit the current transaction or release the savepoint
+    ///
+    /// # Errors
+    ///
+    /// Returns a transaction error if no transaction is active, the
+    /// transaction is rollback-only, or the commit fails.
+    pub fn commit(&self) -> Result<()> {
+        self.block_on(self.inner.commit())
+    }
+
+    /// Roll back the current transaction or to the savepoint
+    ///
+    /// # Errors
+    ///
+    /// Returns a transaction error if no transaction is active or the
+    /// rollback fails.
+    pub fn rollback(&self) -> Result<()> {
+        self.block_on(self.inner.rollback())
+    }
+
+    /// Run a closure within a transaction
+    ///
+    /// Commits if the closure succeeds and rolls back if it fails. This is
+    /// the blocking counterpart of [`connection::Connection::transactional_boxed`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if beginning, committing, or the closure fails.
+    pub fn transactional<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        self.begin_transaction()?;
+        let result = f(self);
+        self.in_transaction(result)
+    }
+
+    /// Run a closure within a transaction, restarting it on retryable errors
+    ///
+    /// The blocking counterpart of [`connection::Connection::transactional`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once the retries are used up, and errors that
+    /// are not retryable right away.
+    pub fn transactional_retrying<T>(&self, mut f: impl FnMut(&Self) -> Result<T>) -> Result<T> {
+        let mut retries = self.inner.transaction_retries();
+        loop {
+            match self.transactional(&mut f) {
+                Err(e) if retries > 0 && e.is_retryable() => retries -= 1,
+                result => return result,
+            }
+        }
+    }
+
+    /// Change a setting for the rest of the current transaction
+    ///
+    /// See [`connection::Connection::set_local`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error outside a transaction, for an invalid name, if the
+    /// platform has no session settings, or if a statement fails.
+    pub fn set_local(&self, platform: &dyn Platform, name: &str, value: &str) -> Result<()> {
+        self.block_on(self.inner.set_local(platform, name, value))
+    }
+
+    /// Run a closure in a transaction with transaction-local session variables
+    ///
+    /// The blocking counterpart of [`connection::Connection::with_session_context`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction, a session statement, or the
+    /// closure fails.
+    pub fn with_session_context<T>(
+        &self,
+        platform: &dyn Platform,
+        context: &[(&str, &str)],
+        f: impl FnOnce(&Self) -> Result<T>,
+    ) -> Result<T> {
+        self.begin_transaction()?;
+        let result: Result<T> = (|| {
+            let previous = self.block_on(self.inner.enter_session_context(platform, context))?;
+            let value = f(self)?;
+            self.block_on(self.inner.leave_session_context(platform, previous))?;
+            Ok(value)
+        })();
+        self.in_transaction(result)
+    }
+
+    /// Commit on success or roll back on error
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the result is an error or if commit fails.
+    pub fn in_transaction<T, E>(&self, result: std::result::Result<T, E>) -> Result<T>
+    where
+        E: Into<Error>,
+    {
+        self.block_on(self.inner.in_transaction(result))
+    }
+
+    /// Set the isolation level for new transactions
+    pub fn set_transaction_isolation(&mut self, level: IsolationLevel) {
+        self.inner.set_transaction_isolation(level);
+    }
+
+    /// Get the current isolation level setting
+    #[must_use]
+    pub const fn transaction_isolation(&self) -> IsolationLevel {
+        self.inner.transaction_isolation()
+    }
+
+    /// Get the current transaction nesting level
+    pub fn transaction_nesting_level(&self) -> u32 {
+        self.inner.transaction_nesting_level()
+    }
+
+    /// Get the names of the savepoints of the active nested transactions
+    pub fn active_savepoints(&self) -> Vec<String> {
+        self.inner.active_savepoints()
+    }
+
+    /// Check if a transaction is currently active
+    pub fn is_transaction_active(&self) -> bool {
+        self.inner.is_transaction_active()
+    }
+
+    /// Get the transaction state reported by the driver connection
+    pub fn transaction_status(&self) -> TransactionStatus {
+        self.inner.transaction_status()
+    }
+
+    /// Turn auto-commit on or off
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if committing the active transaction or a statement fails.
+    pub fn set_auto_commit(&self, platform: &dyn Platform, auto_commit: bool) -> Result<()> {
+        self.block_on(self.inner.set_auto_commit(platform, auto_commit))
+    }
+
+    /// Check whether auto-commit is on
+    pub fn is_auto_commit(&self) -> bool {
+        self.inner.is_auto_commit()
+    }
+
+    /// Check if the current transaction is marked as rollback-only
+    pub fn is_rollback_only(&self) -> bool {
+        self.inner.is_rollback_only()
+    }
+
+    /// Mark the current transaction as rollback-only
+    pub fn set_rollback_only(&self) {
+        self.inner.set_rollback_only();
+    }
+
+    /// Get the backend identifier of the current transaction
+    ///
+    /// # Errors
+    ///
+    /// Returns an error outside a transaction, if the platform has no
+    /// transaction identifier, or if the query fails.
+    pub fn current_transaction_id(&self, platform: &dyn Platform) -> Result<Option<String>> {
+        self.block_on(self.inner.current_transaction_id(platform))
+    }
+
+    // ========================================================================
+    // Connection State
+    // ========================================================================
+
+    /// Check if the connection is still alive
+    pub fn is_alive(&self) -> bool {
+        self.block_on(self.inner.is_alive())
+    }
+
+    /// Get the server version string
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed or version query fails.
+    pub fn server_version(&self) -> Result<String> {
+        self.block_on(self.inner.server_version())
+    }
+
+    /// Get the platform for a driver name matching the server's version
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if no platform is registered for the
+    /// driver, or an error if the version query fails.
+    pub fn detect_platform(&self, driver: &str, registry: &PlatformRegistry) -> Result<Arc<dyn Platform>> {
+        self.block_on(self.inner.detect_platform(driver, registry))
+    }
+
+    /// Discard the driver's cached prepared statements
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed or the driver fails.
+    pub fn clear_statement_cache(&self) -> Result<()> {
+        self.block_on(self.inner.clear_statement_cache())
+    }
+
+    /// Close the connection, rolling back an active transaction
+    ///
+    /// # Errors
+    ///
+    /// This method currently does not fail, but returns `Result` for future compatibility.
+    pub fn close(&self) -> Result<()> {
+        self.block_on(self.inner.close())
+    }
+
+    /// Check if the connection has been closed
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Create a runtime and connect on it
+    fn connect_with<F, Fut>(connect: F) -> Result<Self>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<connection::Connection<D>>>,
+    {
+        let runtime = new_runtime()?;
+        let inner = runtime.block_on(connect())?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+}
+
+impl<D: Driver + Default> Connection<D> {
+    /// Connect using a connection URL
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the URL is invalid or names a driver
+    /// that `D` doesn't support, or a connection error if connecting fails.
+    pub fn from_url(url: &str) -> Result<Self> {
+        Self::connect_with(|| connection::Connection::from_url(url))
+    }
+
+    /// Connect using a connection URL and a runtime configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the URL is invalid or names a driver
+    /// that `D` doesn't support, or an error if connecting or configuring the
+    /// session fails.
+    pub fn from_url_with_configuration(url: &str, configuration: Configuration) -> Result<Self> {
+        Self::connect_with(|| connection::Connection::from_url_with_configuration(url, configuration))
+    }
+
+    /// Connect using connection parameters and the default configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `D` doesn't support the driver of the
+    /// parameters, or a connection error if connecting fails.
+    pub fn from_params(params: &ConnectionParams) -> Result<Self> {
+        Self::connect_with(|| connection::Connection::from_params(params))
+    }
+
+    /// Connect using connection parameters and a runtime configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `D` doesn't support the driver of the
+    /// parameters, or an error if connecting or configuring the session fails.
+    pub fn from_params_with_configuration(
+        params: &ConnectionParams,
+        configuration: Configuration,
+    ) -> Result<Self> {
+        Self::connect_with(|| connection::Connection::from_params_with_configuration(params, configuration))
+    }
+}
+
+/// Create the single-threaded runtime driving a blocking connection
+fn new_runtime() -> Result<Runtime> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .map_err(|e| Error::driver("Failed to create runtime", e))
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::driver::{DriverResult, SqliteDriver};
+
+    #[test]
+    fn test_blocking_connection() {
+        let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").unwrap();
+        conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").unwrap();
+
+        let mut stmt = conn.prepare("INSERT INTO users (name) VALUES (?)").unwrap();
+        stmt.bind(0, SqlValue::String("Alice".into())).unwrap();
+        assert_eq!(stmt.execute_update().unwrap(), 1);
+
+        let rows: Vec<(i64, String)> = conn.fetch_all_as("SELECT id, name FROM users", &[]).unwrap();
+        assert_eq!(rows, vec![(1, "Alice".to_string())]);
+        assert_eq!(conn.query("SELECT * FROM users").unwrap().all_rows().unwrap().len(), 1);
+        assert!(conn.is_alive());
+    }
+
+    #[test]
+    fn test_blocking_transactional() {
+        let conn = Connection::new(&SqliteDriver::new(), &ConnectionParams::sqlite_memory()).unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER)").unwrap();
+
+        let failed: Result<()> = conn.transactional(|conn| {
+            conn.execute("INSERT INTO t VALUES (1)")?;
+            Err(Error::config("abort"))
+        });
+        assert!(failed.is_err());
+
+        let inserted = conn.transactional(|conn| conn.execute("INSERT INTO t VALUES (2)")).unwrap();
+        assert_eq!(inserted, 1);
+        assert!(!conn.is_transaction_active());
+
+        let rows: Vec<(i64,)> = conn.fetch_all_as("SELECT id FROM t", &[]).unwrap();
+        assert_eq!(rows, vec![(2,)]);
+
+        let mut stmt = conn.prepare("SELECT id FROM t WHERE id = ?").unwrap();
+        assert_eq!(stmt.sql(), "SELECT id FROM t WHERE id = ?");
+        stmt.bind(0, SqlValue::I64(2)).unwrap();
+        assert_eq!(stmt.execute().unwrap().all_rows().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_blocking_transactional_retrying() {
+        let conn = Connection::<SqliteDriver>::from_url_with_configuration(
+            "sqlite::memory:",
+            Configuration::new().with_transaction_retries(1),
+        )
+        .unwrap();
+        conn.execute("CREATE TABLE t (id INTEGER)").unwrap();
+        let mut attempts = 0;
+        let inserted = conn
+            .transactional_retrying(|conn| {
+                let rows = conn.execute("INSERT INTO t VALUES (1)")?;
+                attempts += 1;
+                if attempts == 1 {
+                    return Err(Error::Query(crate::core::QueryError::Deadlock));
+                }
+                Ok(rows)
+            })
+            .unwrap();
+        assert_eq!((inserted, attempts), (1, 2));
+        assert_eq!(conn.fetch_all_as::<(i64,)>("SELECT COUNT(*) FROM t", &[]).unwrap(), [(1,)]);
+    }
+}
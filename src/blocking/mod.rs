@@ -0,0 +1,33 @@
+//! # Blocking Module
+//!
+//! Synchronous facade for applications without an async runtime (CLI
+//! tools, build scripts, tests). Requires the `blocking` feature.
+//!
+//! Each [`Connection`] owns a single-threaded Tokio runtime and drives the
+//! async API on it, so the methods mirror [`crate::connection::Connection`]
+//! one-to-one without `.await`. Do not call them from inside an async
+//! runtime; blocking inside a runtime panics.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rustine_dbal::blocking::{Connection, SchemaManager};
+//! use rustine_dbal::driver::SqliteDriver;
+//! use rustine_dbal::platform::SqlitePlatform;
+//!
+//! let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")?;
+//! conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)")?;
+//!
+//! let platform = SqlitePlatform::new();
+//! let tables = SchemaManager::new(&conn, &platform).list_table_names()?;
+//! ```
+
+mod connection;
+#[cfg(feature = "schema")]
+mod schema;
+mod statement;
+
+pub use connection::Connection;
+#[cfg(feature = "schema")]
+pub use schema::SchemaManager;
+pub use statement::Statement;
@@ -0,0 +1,184 @@
+//! Blocking schema manager
+
+use crate::core::Result;
+use crate::driver::Driver;
+use crate::platform::{Index, Platform, Table};
+use crate::schema::{self, ColumnInfo, ForeignKeyInfo, GrantInfo, IndexInfo, TableInfo, TableStatistics};
+
+use super::Connection;
+
+/// Blocking schema manager
+///
+/// Runs [`schema::SchemaManager`] on the runtime of a blocking
+/// [`Connection`].
+pub struct SchemaManager<'a, D: Driver> {
+    /// Connection providing the runtime
+    connection: &'a Connection<D>,
+    /// The async schema manager
+    inner: schema::SchemaManager<'a, D::Connection>,
+}
+
+impl<D: Driver> std::fmt::Debug for SchemaManager<'_, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SchemaManager")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, D: Driver> SchemaManager<'a, D> {
+    /// Create a new `SchemaManager`
+    #[must_use]
+    pub const fn new(connection: &'a Connection<D>, platform: &'a dyn Platform) -> Self {
+        Self {
+            connection,
+            inner: schema::SchemaManager::new(connection.inner(), platform),
+        }
+    }
+
+    /// List all table names in the database
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_table_names(&self) -> Result<Vec<String>> {
+        self.connection.block_on(self.inner.list_table_names())
+    }
+
+    /// List all columns of a table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_table_columns(&self, table_name: &str) -> Result<Vec<ColumnInfo>> {
+        self.connection.block_on(self.inner.list_table_columns(table_name))
+    }
+
+    /// List all indexes of a table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_table_indexes(&self, table_name: &str) -> Result<Vec<IndexInfo>> {
+        self.connection.block_on(self.inner.list_table_indexes(table_name))
+    }
+
+    /// List all foreign keys of a table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    pub fn list_table_foreign_keys(&self, table_name: &str) -> Result<Vec<ForeignKeyInfo>> {
+        self.connection.block_on(self.inner.list_table_foreign_keys(table_name))
+    }
+
+    /// Check if a table exists
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if listing tables fails.
+    pub fn table_exists(&self, table_name: &str) -> Result<bool> {
+        self.connection.block_on(self.inner.table_exists(table_name))
+    }
+
+    /// Get full table information including columns, indexes, and foreign keys
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any introspection query fails.
+    pub fn introspect_table(&self, table_name: &str) -> Result<TableInfo> {
+        self.connection.block_on(self.inner.introspect_table(table_name))
+    }
+
+    /// Get the row count and on-disk size of a table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table is unknown or the platform keeps no statistics.
+    pub fn table_statistics(&self, table_name: &str) -> Result<TableStatistics> {
+        self.connection.block_on(self.inner.table_statistics(table_name))
+    }
+
+    /// List the privileges granted on a table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform has no grants or the query fails.
+    pub fn grants_for(&self, table_name: &str) -> Result<Vec<GrantInfo>> {
+        self.connection.block_on(self.inner.grants_for(table_name))
+    }
+
+    /// Create a table from a Table definition
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CREATE TABLE statement fails.
+    pub fn create_table(&self, table: &Table) -> Result<()> {
+        self.connection.block_on(self.inner.create_table(table))
+    }
+
+    /// Drop a table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the DROP TABLE statement fails.
+    pub fn drop_table(&self, table_name: &str) -> Result<()> {
+        self.connection.block_on(self.inner.drop_table(table_name))
+    }
+
+    /// Drop a table if it exists
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the DROP TABLE IF EXISTS statement fails.
+    pub fn drop_table_if_exists(&self, table_name: &str) -> Result<()> {
+        self.connection.block_on(self.inner.drop_table_if_exists(table_name))
+    }
+
+    /// Create an index
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CREATE INDEX statement fails.
+    pub fn create_index(&self, table_name: &str, index: &Index) -> Result<()> {
+        self.connection.block_on(self.inner.create_index(table_name, index))
+    }
+
+    /// Drop an index
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the DROP INDEX statement fails.
+    pub fn drop_index(&self, index_name: &str, table_name: &str) -> Result<()> {
+        self.connection.block_on(self.inner.drop_index(index_name, table_name))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::driver::SqliteDriver;
+    use crate::platform::{Column, SqlType, SqlitePlatform};
+
+    #[test]
+    fn test_blocking_schema_manager() {
+        let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").unwrap();
+        let platform = SqlitePlatform::new();
+        let schema = SchemaManager::new(&conn, &platform);
+
+        let table = Table::new("users")
+            .column(Column::new("id", SqlType::Integer).not_null())
+            .column(Column::new("email", SqlType::Text))
+            .index(Index::primary(vec!["id".into()]));
+        schema.create_table(&table).unwrap();
+        schema.create_index("users", &Index::new("idx_users_email", vec!["email".into()])).unwrap();
+
+        assert_eq!(schema.list_table_names().unwrap(), vec!["users".to_string()]);
+        let info = schema.introspect_table("users").unwrap();
+        assert_eq!(info.primary_key_columns(), vec!["id"]);
+        assert!(info.indexes.iter().any(|index| index.name == "idx_users_email"));
+
+        schema.drop_table("users").unwrap();
+        assert!(!schema.table_exists("users").unwrap());
+    }
+}
@@ -0,0 +1,111 @@
+//! Blocking prepared statement
+
+use tokio::runtime::Runtime;
+
+use crate::connection;
+use crate::core::{ParameterType, Result, SqlValue};
+use crate::driver::{Driver, DriverConnection, DriverStatement};
+
+/// Driver statement type of a driver
+type InnerStatement<D> = <<D as Driver>::Connection as DriverConnection>::Statement;
+
+/// Blocking prepared statement
+///
+/// Created by [`Connection::prepare`](super::Connection::prepare) and
+/// executed on the runtime of that connection.
+pub struct Statement<'c, D: Driver> {
+    /// The async statement
+    inner: connection::Statement<'c, D>,
+    /// Runtime of the preparing connection
+    runtime: &'c Runtime,
+}
+
+impl<D: Driver> std::fmt::Debug for Statement<'_, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Statement")
+            .field("sql", &self.inner.sql())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'c, D: Driver> Statement<'c, D> {
+    /// Wrap an async statement
+    pub(super) const fn new(inner: connection::Statement<'c, D>, runtime: &'c Runtime) -> Self {
+        Self { inner, runtime }
+    }
+
+    /// Bind a parameter by position (0-indexed)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position is invalid or binding fails.
+    pub fn bind(&mut self, position: usize, value: SqlValue) -> Result<()> {
+        self.inner.bind(position, value)
+    }
+
+    /// Bind a parameter by name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not found or binding fails.
+    pub fn bind_named(&mut self, name: &str, value: SqlValue) -> Result<()> {
+        self.inner.bind_named(name, value)
+    }
+
+    /// Bind a parameter by position with an explicit type
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position is invalid or binding fails.
+    pub fn bind_typed(&mut self, position: usize, value: SqlValue, param_type: ParameterType) -> Result<()> {
+        self.inner.bind_typed(position, value, param_type)
+    }
+
+    /// Bind a parameter by name with an explicit type
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not found or binding fails.
+    pub fn bind_named_typed(&mut self, name: &str, value: SqlValue, param_type: ParameterType) -> Result<()> {
+        self.inner.bind_named_typed(name, value, param_type)
+    }
+
+    /// Bind typed parameters by position, starting at 0
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if binding any of the parameters fails.
+    pub fn bind_all_typed(&mut self, params: Vec<(SqlValue, ParameterType)>) -> Result<()> {
+        self.inner.bind_all_typed(params)
+    }
+
+    /// Execute the statement and return results
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement fails or the connection is closed.
+    pub fn execute(&self) -> Result<<InnerStatement<D> as DriverStatement>::Result> {
+        self.runtime.block_on(self.inner.execute())
+    }
+
+    /// Execute the statement and return affected rows
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement fails or the connection is closed.
+    pub fn execute_update(&self) -> Result<u64> {
+        self.runtime.block_on(self.inner.execute_update())
+    }
+
+    /// Get the SQL for this statement
+    #[must_use]
+    pub fn sql(&self) -> &str {
+        self.inner.sql()
+    }
+
+    /// Get the driver statement
+    #[must_use]
+    pub const fn inner(&self) -> &InnerStatement<D> {
+        self.inner.inner()
+    }
+}
@@ -0,0 +1,161 @@
+//! Audit log of data changes made through the query builder
+
+use crate::core::SqlValue;
+use crate::platform::{Column, SqlType, Table};
+use crate::query::{QueryBuilder, QueryType};
+
+/// A data change recorded in the audit table
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// `INSERT`, `UPDATE` or `DELETE`
+    pub operation: &'static str,
+    /// Changed table
+    pub table: String,
+    /// Primary key of the changed row, if the query targets a single row
+    pub primary_key: Option<String>,
+    /// Columns written by an INSERT or UPDATE
+    pub changed_columns: Vec<String>,
+    /// User the change was made for
+    pub user: Option<String>,
+}
+
+/// Records INSERT/UPDATE/DELETE queries in an audit table
+///
+/// Used with [`Connection::execute_audited`](super::Connection::execute_audited),
+/// which writes the audit row in the same transaction as the change. Create
+/// one per request with the acting user; cloning is cheap.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let audit = AuditLogger::new("audit_log").with_user("alice");
+/// conn.execute(&platform.get_create_table_sql(&audit.table_definition())).await?;
+///
+/// let query = QueryBuilder::update().table("users").set("name", "Bob").where_eq("id", 7);
+/// conn.execute_audited(&query, &platform, &audit).await?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLogger {
+    /// Name of the audit table
+    table: String,
+    /// Primary key column of audited tables
+    primary_key: String,
+    /// User the changes are made for
+    user: Option<String>,
+}
+
+impl AuditLogger {
+    /// Create an audit logger writing to the given table
+    ///
+    /// The primary key column of audited tables defaults to `id`.
+    #[must_use]
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            primary_key: "id".to_string(),
+            user: None,
+        }
+    }
+
+    /// Set the primary key column of audited tables
+    #[must_use]
+    pub fn with_primary_key(mut self, column: impl Into<String>) -> Self {
+        self.primary_key = column.into();
+        self
+    }
+
+    /// Set the user the changes are made for
+    #[must_use]
+    pub fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Get the name of the audit table
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Get the definition of the audit table
+    #[must_use]
+    pub fn table_definition(&self) -> Table {
+        Table::new(&self.table)
+            .column(Column::new("operation", SqlType::varchar(10)).not_null())
+            .column(Column::new("table_name", SqlType::varchar(255)).not_null())
+            .column(Column::new("primary_key", SqlType::varchar(255)))
+            .column(Column::new("changed_columns", SqlType::Text))
+            .column(Column::new("user_context", SqlType::varchar(255)))
+            .column(
+                Column::new("created_at", SqlType::Timestamp { precision: None })
+                    .not_null()
+                    .default_current_timestamp(),
+            )
+    }
+
+    /// Get the audit entry for a query, `None` for SELECT queries
+    #[must_use]
+    pub fn entry(&self, query: &QueryBuilder) -> Option<AuditEntry> {
+        let operation = match query.query_type() {
+            QueryType::Insert => "INSERT",
+            QueryType::Update => "UPDATE",
+            QueryType::Delete => "DELETE",
+            QueryType::Select => return None,
+        };
+        Some(AuditEntry {
+            operation,
+            table: query.table_name().to_string(),
+            primary_key: query
+                .key_value(&self.primary_key)
+                .and_then(|value| value.clone().into_string()),
+            changed_columns: query.changed_columns().into_iter().map(str::to_string).collect(),
+            user: self.user.clone(),
+        })
+    }
+
+    /// Get the INSERT query writing an entry to the audit table
+    #[must_use]
+    pub fn insert_query(&self, entry: &AuditEntry) -> QueryBuilder {
+        let changed_columns = if entry.changed_columns.is_empty() {
+            SqlValue::Null
+        } else {
+            SqlValue::String(entry.changed_columns.join(","))
+        };
+        QueryBuilder::insert()
+            .into(&self.table)
+            .insert_columns(&["operation", "table_name", "primary_key", "changed_columns", "user_context"])
+            .values(vec![
+                entry.operation.into(),
+                entry.table.as_str().into(),
+                entry.primary_key.clone().into(),
+                changed_columns,
+                entry.user.clone().into(),
+            ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::SqlitePlatform;
+
+    #[test]
+    fn test_entry() {
+        let audit = AuditLogger::new("audit_log").with_user("alice");
+        let query = QueryBuilder::update().table("users").set("name", "Bob").set("email", "b@x").where_eq("id", 7);
+
+        let entry = audit.entry(&query).unwrap();
+        assert_eq!(entry.operation, "UPDATE");
+        assert_eq!(entry.primary_key.as_deref(), Some("7"));
+        assert_eq!(entry.changed_columns, ["name", "email"]);
+        assert_eq!(
+            audit.insert_query(&entry).to_sql(&SqlitePlatform::new()),
+            "INSERT INTO \"audit_log\" (\"operation\", \"table_name\", \"primary_key\", \"changed_columns\", \"user_context\") \
+             VALUES ('UPDATE', 'users', '7', 'name,email', 'alice')"
+        );
+
+        assert_eq!(audit.entry(&QueryBuilder::select().from("users")), None);
+        let delete = audit.entry(&QueryBuilder::delete().from("users")).unwrap();
+        assert_eq!((delete.primary_key, delete.changed_columns.len()), (None, 0));
+    }
+}
@@ -1,10 +1,29 @@
 //! High-level database connection with transaction management
 
+use std::borrow::Cow;
 use std::future::Future;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-
-use crate::core::{ConnectionParams, Error, IsolationLevel, Result, TransactionError};
-use crate::driver::{Driver, DriverConnection};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+// `std::time::Instant::now()` panics on wasm32-unknown-unknown
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use crate::core::{
+    Configuration, ConnectionParams, Error, FromRow, IsolationLevel, NestedTransactionPolicy, NonFiniteFloatPolicy,
+    Result, SqlValue, TenantStrategy, TimezonePolicy, TransactionError, TransactionStatus,
+};
+use crate::driver::{Driver, DriverConnection, DriverResult, ResultSet};
+use crate::platform::{Platform, PlatformRegistry};
+
+use super::logger::{QueryLogEntry, QueryLogger, TracingQueryLogger};
+use super::rewriter::SqlRewriter;
+use super::statement::Statement;
+#[cfg(feature = "pool")]
+use super::pool::PoolTiming;
 
 /// High-level database connection with transaction management
 ///
@@ -43,6 +62,26 @@ pub struct Connection<D: Driver> {
     isolation_level: IsolationLevel,
     /// Whether this connection has been explicitly closed
     closed: AtomicBool,
+    /// Whether statements outside transactions commit on their own
+    auto_commit: AtomicBool,
+    /// Whether the platform supports savepoints, see `configure_session`
+    savepoints_supported: AtomicBool,
+    /// Runtime configuration
+    configuration: Configuration,
+    /// Receiver for query log and slow query events
+    logger: Arc<dyn QueryLogger>,
+    /// Hooks transforming statements before execution, applied in order
+    rewriters: Vec<Arc<dyn SqlRewriter>>,
+    /// SQL restoring settings changed by `set_local`, with the nesting level they belong to
+    local_settings: std::sync::Mutex<Vec<(u32, String)>>,
+    /// Deepest nesting level that ran DDL not committed yet (0 = none)
+    schema_change_level: AtomicU32,
+    /// Checkout times for pool events, see [`ConnectionManager`](super::ConnectionManager)
+    #[cfg(feature = "pool")]
+    pool_timing: std::sync::Mutex<PoolTiming>,
+    /// Introspected columns per table for `SelectStarPolicy::Expand`
+    #[cfg(all(feature = "schema", feature = "query-builder"))]
+    table_columns: std::sync::Mutex<std::collections::BTreeMap<String, Vec<String>>>,
 }
 
 impl<D: Driver> std::fmt::Debug for Connection<D> {
@@ -52,6 +91,10 @@ impl<D: Driver> std::fmt::Debug for Connection<D> {
             .field("rollback_only", &self.rollback_only.load(std::sync::atomic::Ordering::Relaxed))
             .field("isolation_level", &self.isolation_level)
             .field("closed", &self.closed.load(std::sync::atomic::Ordering::Relaxed))
+            .field("auto_commit", &self.auto_commit.load(std::sync::atomic::Ordering::Relaxed))
+            .field("configuration", &self.configuration)
+            .field("logger", &self.logger)
+            .field("rewriters", &self.rewriters.len())
             .finish_non_exhaustive()
     }
 }
@@ -64,13 +107,26 @@ impl<D: Driver> Connection<D> {
     /// Returns a connection error if the database connection fails.
     pub async fn new(driver: &D, params: &ConnectionParams) -> Result<Self> {
         let inner = driver.connect(params).await?;
-        Ok(Self {
-            inner,
-            nesting_level: AtomicU32::new(0),
-            rollback_only: AtomicBool::new(false),
-            isolation_level: IsolationLevel::default(),
-            closed: AtomicBool::new(false),
-        })
+        Ok(Self::from_driver_connection(inner))
+    }
+
+    /// Create a new connection using the given driver, parameters and configuration
+    ///
+    /// The configuration is passed to [`Driver::connect_with_configuration`]
+    /// so the driver can apply timeouts and value formats, and is available
+    /// afterwards through [`Connection::configuration`]. Session settings are
+    /// applied separately with [`Connection::configure_session`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a connection error if the database connection fails.
+    pub async fn new_with_configuration(
+        driver: &D,
+        params: &ConnectionParams,
+        configuration: Configuration,
+    ) -> Result<Self> {
+        let inner = driver.connect_with_configuration(params, &configuration).await?;
+        Ok(Self::from_driver_connection(inner).with_configuration(configuration))
     }
 
     /// Create a connection from an existing driver connection
@@ -81,7 +137,125 @@ impl<D: Driver> Connection<D> {
             rollback_only: AtomicBool::new(false),
             isolation_level: IsolationLevel::default(),
             closed: AtomicBool::new(false),
+            auto_commit: AtomicBool::new(true),
+            savepoints_supported: AtomicBool::new(true),
+            configuration: Configuration::default(),
+            logger: Arc::new(TracingQueryLogger),
+            rewriters: Vec::new(),
+            local_settings: std::sync::Mutex::default(),
+            schema_change_level: AtomicU32::new(0),
+            #[cfg(feature = "pool")]
+            pool_timing: std::sync::Mutex::new(PoolTiming::new()),
+            #[cfg(all(feature = "schema", feature = "query-builder"))]
+            table_columns: std::sync::Mutex::default(),
+        }
+    }
+
+    /// Use the given runtime configuration for this connection
+    #[must_use]
+    pub fn with_configuration(mut self, configuration: Configuration) -> Self {
+        self.configuration = configuration;
+        self
+    }
+
+    /// Use a custom query logger instead of the default `tracing` logger
+    #[must_use]
+    pub fn with_logger(mut self, logger: Arc<dyn QueryLogger>) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    /// Add a hook rewriting statements before they are executed
+    ///
+    /// Rewriters run in the order they were added, see [`SqlRewriter`].
+    #[must_use]
+    pub fn with_rewriter(mut self, rewriter: Arc<dyn SqlRewriter>) -> Self {
+        self.rewriters.push(rewriter);
+        self
+    }
+
+    /// Get the checkout times used for pool events
+    #[cfg(feature = "pool")]
+    pub(crate) fn pool_timing(&self) -> std::sync::MutexGuard<'_, PoolTiming> {
+        self.pool_timing.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Get the runtime configuration of this connection
+    #[must_use]
+    pub const fn configuration(&self) -> &Configuration {
+        &self.configuration
+    }
+
+    /// Apply session settings from the configuration
+    ///
+    /// Sets the character set, default schema, session timezone (see
+    /// [`Configuration::session_time_zone`]) and application name using the
+    /// platform's syntax. Settings the platform has no statement for (like
+    /// the timezone on `SQLite`) are skipped. Without
+    /// [`Configuration::auto_commit`] auto-commit is turned off last (see
+    /// [`Connection::set_auto_commit`]), so the settings aren't part of the
+    /// implicit transaction.
+    ///
+    /// Also records whether the platform
+    /// [supports savepoints](Platform::supports_savepoints); without them
+    /// nested transactions are only counted (see [`Connection::rollback`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed or a statement fails.
+    pub async fn configure_session(&self, platform: &dyn Platform) -> Result<()> {
+        self.savepoints_supported.store(platform.supports_savepoints(), Ordering::SeqCst);
+        let configuration = &self.configuration;
+        let statements = [
+            configuration.charset.as_deref().and_then(|c| platform.set_charset_sql(c)),
+            configuration.schema.as_deref().and_then(|s| platform.set_schema_sql(s)),
+            configuration.session_time_zone().and_then(|tz| platform.set_time_zone_sql(tz)),
+            configuration
+                .application_name
+                .as_deref()
+                .and_then(|name| platform.set_application_name_sql(name)),
+        ];
+
+        for sql in statements.into_iter().flatten() {
+            self.execute(&sql).await?;
+        }
+        if let Some(ref tenant) = configuration.tenant {
+            self.use_tenant(platform, tenant).await?;
         }
+        if !configuration.auto_commit {
+            self.set_auto_commit(platform, false).await?;
+        }
+        Ok(())
+    }
+
+    /// Switch the connection to a tenant
+    ///
+    /// Selects the tenant's schema or database, e.g. when a pooled
+    /// connection is handed to another tenant. Table prefixes are applied by
+    /// [`QueryBuilder::tenant`](crate::query::QueryBuilder::tenant) instead,
+    /// so nothing is executed for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns a platform error if the platform can't switch to the tenant's
+    /// schema or database on an open connection (connect with
+    /// [`ConnectionParams::for_tenant`] instead), or an error if the
+    /// statement fails.
+    pub async fn use_tenant(&self, platform: &dyn Platform, tenant: &TenantStrategy) -> Result<()> {
+        let sql = match tenant {
+            TenantStrategy::Schema(schema) => platform
+                .set_schema_sql(schema)
+                .ok_or_else(|| Error::platform(format!("{} does not support schemas", platform.name())))?,
+            TenantStrategy::Database(database) => platform.use_database_sql(database).ok_or_else(|| {
+                Error::platform(format!(
+                    "{} can't switch databases, connect with ConnectionParams::for_tenant()",
+                    platform.name()
+                ))
+            })?,
+            TenantStrategy::TablePrefix(_) => return Ok(()),
+        };
+        self.execute(&sql).await?;
+        Ok(())
     }
 
     /// Get the underlying driver connection
@@ -96,12 +270,32 @@ impl<D: Driver> Connection<D> {
 
     /// Execute a SQL query and return results
     ///
+    /// A batch of several statements returns the result set of the first
+    /// one; use [`Connection::query_multiple`] to read all of them.
+    ///
     /// # Errors
     ///
     /// Returns an error if the query fails or the connection is closed.
     pub async fn query(&self, sql: &str) -> Result<<D::Connection as DriverConnection>::Result> {
         self.ensure_not_closed()?;
-        self.inner.query(sql).await
+        let sql = self.rewrite_sql(sql)?;
+        let started = Instant::now();
+        let result = self.inner.query(&sql).await;
+        self.log_query(&sql, &[], started.elapsed()).await;
+        result.map_err(|e| self.sanitize_error(e))
+    }
+
+    /// Execute a SQL batch or procedure call and return all result sets
+    ///
+    /// Unlike [`Connection::query`], statements after the first are not
+    /// dropped: each statement contributes one [`ResultSet`] (drivers
+    /// permitting multiple statements per call).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails or the connection is closed.
+    pub async fn query_multiple(&self, sql: &str) -> Result<Vec<ResultSet>> {
+        self.query(sql).await?.result_sets()
     }
 
     /// Execute a SQL statement and return affected rows
@@ -111,20 +305,697 @@ impl<D: Driver> Connection<D> {
     /// Returns an error if the statement fails or the connection is closed.
     pub async fn execute(&self, sql: &str) -> Result<u64> {
         self.ensure_not_closed()?;
-        self.inner.execute(sql).await
+        let sql = self.rewrite_sql(sql)?;
+        let started = Instant::now();
+        let result = self.inner.execute(&sql).await;
+        self.log_query(&sql, &[], started.elapsed()).await;
+        let affected = result.map_err(|e| self.sanitize_error(e))?;
+        if is_schema_change(&sql) {
+            self.schema_change_level.fetch_max(self.nesting_level.load(Ordering::SeqCst), Ordering::SeqCst);
+            self.clear_statement_cache().await?;
+        }
+        Ok(affected)
     }
 
-    /// Prepare a SQL statement
+    /// Execute a data change and record it in the audit table
+    ///
+    /// The query and the audit row are written in one transaction (a
+    /// savepoint inside an active one), so the audit log can't miss a change
+    /// or record one that was rolled back. Queries affecting no rows and
+    /// SELECT queries are not recorded.
     ///
     /// # Errors
     ///
-    /// Returns an error if the statement preparation fails or the connection is closed.
-    pub async fn prepare(
+    /// Returns an error if rendering or executing the query or writing the
+    /// audit row fails.
+    #[cfg(feature = "query-builder")]
+    pub async fn execute_audited(
+        &self,
+        query: &crate::query::QueryBuilder,
+        platform: &dyn Platform,
+        audit: &super::AuditLogger,
+    ) -> Result<u64> {
+        let sql = query.try_to_sql(platform)?;
+        let Some(entry) = audit.entry(query) else {
+            return self.execute(&sql).await;
+        };
+
+        self.begin_transaction().await?;
+        let result: Result<u64> = async {
+            let affected = self.execute(&sql).await?;
+            if affected > 0 {
+                self.execute(&audit.insert_query(&entry).to_sql(platform)).await?;
+            }
+            Ok(affected)
+        }
+        .await;
+        self.in_transaction(result).await
+    }
+
+    /// Execute a query and return the rows of its RETURNING columns
+    ///
+    /// Where the platform lacks RETURNING the clause is emulated in a
+    /// transaction (a savepoint inside an active one) using the primary key
+    /// column `key`: a DELETE's rows are selected before it runs, an UPDATE's
+    /// keys are selected before and its rows after it runs, and an INSERT's
+    /// row is read back by its key value or the platform's last insert id.
+    /// Emulation covers single-row INSERTs and UPDATEs that don't change the
+    /// key. Queries without RETURNING return no rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering or executing a query fails, or if the
+    /// RETURNING clause can't be emulated.
+    #[cfg(feature = "query-builder")]
+    pub async fn execute_returning(
+        &self,
+        query: &crate::query::QueryBuilder,
+        platform: &dyn Platform,
+        key: &str,
+    ) -> Result<ResultSet> {
+        use crate::query::{PlatformRenderer, SqlRenderer};
+
+        let statement = query.to_statement();
+        let sql = PlatformRenderer::new(platform).try_render(&statement)?;
+        let native = match statement {
+            crate::query::Statement::Update(_) => platform.supports_update_returning(),
+            _ => platform.supports_returning(),
+        };
+        if native || statement.returning().is_empty() {
+            return self.fetch_result_set(&sql).await;
+        }
+
+        self.begin_transaction().await?;
+        let result = self.emulate_returning(&statement, &sql, platform, key).await;
+        self.in_transaction(result).await
+    }
+
+    /// Run an INSERT, UPDATE or DELETE and read its RETURNING columns with SELECTs
+    #[cfg(feature = "query-builder")]
+    async fn emulate_returning(
         &self,
+        statement: &crate::query::Statement,
         sql: &str,
-    ) -> Result<<D::Connection as DriverConnection>::Statement> {
+        platform: &dyn Platform,
+        key: &str,
+    ) -> Result<ResultSet> {
+        use crate::query::{Expr, ModifyFilter, PlatformRenderer, SelectItem, SqlRenderer, Statement};
+
+        let renderer = PlatformRenderer::new(platform);
+        let returning: Vec<SelectItem> = statement
+            .returning()
+            .iter()
+            .map(|column| match column.as_str() {
+                "*" => SelectItem::Wildcard,
+                _ => SelectItem::Column(column.clone()),
+            })
+            .collect();
+        let select_keys = |table: &str, keys: Vec<Expr>| {
+            let condition = if keys.is_empty() { Expr::raw("1 = 0") } else { Expr::col(key).in_list(keys) };
+            let filter = ModifyFilter { condition: Some(condition), ..ModifyFilter::default() };
+            renderer.render(&Statement::Select(filter.select(table, returning.clone())))
+        };
+
+        let (select_sql, rows_affected) = match statement {
+            Statement::Insert(insert) => {
+                let [row] = insert.rows.as_slice() else {
+                    return Err(Error::platform(format!(
+                        "{} can't emulate RETURNING for multi-row INSERTs",
+                        platform.name()
+                    )));
+                };
+                let rows_affected = self.execute(sql).await?;
+                let inserted_key = insert.columns.iter().position(|column| column == key).and_then(|i| row.get(i));
+                let id = if let Some(id) = inserted_key {
+                    id.clone()
+                } else {
+                    let last_insert_id_sql = platform.last_insert_id_sql().ok_or_else(|| {
+                        Error::platform(format!("{} can't read back generated keys", platform.name()))
+                    })?;
+                    Expr::Value(self.first_value(&last_insert_id_sql).await?)
+                };
+                (select_keys(&insert.table, vec![id]), rows_affected)
+            }
+            Statement::Update(update) => {
+                let keys_sql = renderer.render(&Statement::Select(
+                    update.filter.select(&update.table, vec![SelectItem::Column(key.to_string())]),
+                ));
+                let keys = self.query(&keys_sql).await?.all_rows()?;
+                let keys = keys.into_iter().filter_map(|row| row.into_iter().next()).map(Expr::Value).collect();
+                let rows_affected = self.execute(sql).await?;
+                (select_keys(&update.table, keys), rows_affected)
+            }
+            Statement::Delete(delete) => {
+                let select_sql = renderer.render(&Statement::Select(delete.filter.select(&delete.table, returning)));
+                let mut deleted = self.fetch_result_set(&select_sql).await?;
+                deleted.rows_affected = self.execute(sql).await?;
+                return Ok(deleted);
+            }
+            Statement::Select(_) => return self.fetch_result_set(sql).await,
+        };
+        let mut result = self.fetch_result_set(&select_sql).await?;
+        result.rows_affected = rows_affected;
+        Ok(result)
+    }
+
+    /// Run a query and return its first result set
+    #[cfg(feature = "query-builder")]
+    async fn fetch_result_set(&self, sql: &str) -> Result<ResultSet> {
+        self.query_multiple(sql)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::driver_message("query returned no result set"))
+    }
+
+    /// Insert a row into a table and return its primary key
+    ///
+    /// The key column `key` is filled by `ids`: keys of pre-generated
+    /// strategies are added to `values`, serial keys are read back with
+    /// RETURNING or the platform's last insert id query.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform can't provide the key for the
+    /// strategy (no sequences, no way to read serial keys) or a query fails.
+    #[cfg(feature = "query-builder")]
+    pub async fn insert(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        key: &str,
+        ids: &super::IdGenerator,
+        values: &[(&str, SqlValue)],
+    ) -> Result<SqlValue> {
+        use super::IdGenerator;
+        use crate::query::QueryBuilder;
+
+        let generated = match ids {
+            IdGenerator::Serial => None,
+            IdGenerator::Sequence(sequence) => Some(SqlValue::I64(self.next_sequence_value(platform, sequence).await?)),
+            #[cfg(feature = "uuid")]
+            IdGenerator::UuidV4 => Some(SqlValue::Uuid(uuid::Uuid::new_v4())),
+            #[cfg(feature = "uuid")]
+            IdGenerator::UuidV7 => Some(SqlValue::Uuid(uuid::Uuid::now_v7())),
+        };
+
+        let mut columns: Vec<&str> = generated.iter().map(|_| key).collect();
+        let mut row: Vec<SqlValue> = generated.iter().cloned().collect();
+        for (column, value) in values {
+            columns.push(column);
+            row.push(self.configuration.timezone_policy.apply(value.clone()));
+        }
+        let query = QueryBuilder::insert().into(table).insert_columns(&columns).values(row);
+
+        if let Some(id) = generated {
+            self.execute(&query.try_to_sql(platform)?).await?;
+            return Ok(id);
+        }
+        if platform.supports_returning() {
+            return self.first_value(&query.returning(&[key]).try_to_sql(platform)?).await;
+        }
+        let sql = platform.last_insert_id_sql().ok_or_else(|| {
+            Error::platform(format!("{} can't read back generated keys", platform.name()))
+        })?;
+        self.execute(&query.try_to_sql(platform)?).await?;
+        self.first_value(&sql).await
+    }
+
+    /// Select the rows of a table matching the criteria
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering or executing the query fails.
+    #[cfg(feature = "query-builder")]
+    pub async fn select(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        criteria: &crate::query::Criteria,
+    ) -> Result<ResultSet> {
+        let query = criteria.apply(crate::query::QueryBuilder::select().all().from(table));
+        self.fetch(platform, &query).await
+    }
+
+    /// Run a SELECT built with the query builder
+    ///
+    /// Queries selecting `*` are handled according to
+    /// `Configuration::select_star_policy`: run as written, expanded to the
+    /// table's columns, reported through `QueryLogger::log_warning` or
+    /// rejected. Queries without LIMIT are limited to
+    /// `Configuration::max_rows`, if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the policy denies the query, or
+    /// expansion is requested without the `schema` feature, and an error if
+    /// introspection, rendering or executing the query fails.
+    #[cfg(feature = "query-builder")]
+    pub async fn fetch(&self, platform: &dyn Platform, query: &crate::query::QueryBuilder) -> Result<ResultSet> {
+        use crate::core::SelectStarPolicy;
+
+        let limited;
+        let query = match self.configuration.max_rows {
+            Some(max_rows) if query.limit_value().is_none() => {
+                limited = query.clone().limit(max_rows);
+                &limited
+            }
+            _ => query,
+        };
+        let sql = match self.configuration.select_star_policy {
+            _ if !query.selects_wildcard() => query.try_to_sql(platform)?,
+            SelectStarPolicy::Allow => query.try_to_sql(platform)?,
+            SelectStarPolicy::Expand => self.expand_wildcard(platform, query).await?.try_to_sql(platform)?,
+            SelectStarPolicy::Warn => {
+                let sql = query.try_to_sql(platform)?;
+                self.logger.log_warning(&sql, "SELECT * used; list the columns explicitly");
+                sql
+            }
+            SelectStarPolicy::Deny => {
+                return Err(Error::config(format!(
+                    "SELECT * is not allowed by the configuration: {}",
+                    query.try_to_sql(platform)?
+                )));
+            }
+        };
+        self.fetch_result_set(&sql).await
+    }
+
+    /// Replace `*` in a query with the introspected columns of its table
+    ///
+    /// Queries not selecting from a single table are returned unchanged.
+    #[cfg(all(feature = "schema", feature = "query-builder"))]
+    async fn expand_wildcard(
+        &self,
+        platform: &dyn Platform,
+        query: &crate::query::QueryBuilder,
+    ) -> Result<crate::query::QueryBuilder> {
+        let Some(table) = query.wildcard_table() else {
+            return Ok(query.clone());
+        };
+        let cached = self.table_columns.lock().unwrap_or_else(std::sync::PoisonError::into_inner).get(&table).cloned();
+        let columns = if let Some(columns) = cached {
+            columns
+        } else {
+            let columns: Vec<String> = crate::schema::SchemaManager::new(&self.inner, platform)
+                .list_table_columns(&table)
+                .await?
+                .into_iter()
+                .map(|column| column.name)
+                .collect();
+            if columns.is_empty() {
+                return Err(Error::config(format!("Can't expand SELECT *: table {table} has no columns")));
+            }
+            self.table_columns
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .insert(table, columns.clone());
+            columns
+        };
+        let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+        Ok(query.clone().expand_wildcard(&columns))
+    }
+
+    /// Replace `*` in a query with the introspected columns of its table
+    #[cfg(all(feature = "query-builder", not(feature = "schema")))]
+    #[allow(clippy::unused_async)]
+    async fn expand_wildcard(
+        &self,
+        _platform: &dyn Platform,
+        _query: &crate::query::QueryBuilder,
+    ) -> Result<crate::query::QueryBuilder> {
+        Err(Error::config("Expanding SELECT * needs the schema feature"))
+    }
+
+    /// Update the rows of a table matching the criteria
+    ///
+    /// Returns the number of affected rows. Ordering and limits need
+    /// platform support for UPDATE ... LIMIT. Criteria without conditions
+    /// are rejected rather than updating every row; run an UPDATE without
+    /// WHERE through [`execute`](Self::execute) to do that.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the criteria have no conditions, and
+    /// an error if rendering or executing the query fails.
+    #[cfg(feature = "query-builder")]
+    pub async fn update(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        values: &[(&str, SqlValue)],
+        criteria: &crate::query::Criteria,
+    ) -> Result<u64> {
+        if criteria.to_expr().is_none() {
+            return Err(Error::config(format!("Updating {table} needs criteria with at least one condition")));
+        }
+        let query = values
+            .iter()
+            .fold(crate::query::QueryBuilder::update().table(table), |query, (column, value)| {
+                query.set(column, self.configuration.timezone_policy.apply(value.clone()))
+            });
+        self.execute(&criteria.apply(query).try_to_sql(platform)?).await
+    }
+
+    /// Update many rows of a table by key in as few statements as the platform allows
+    ///
+    /// Each row holds the values of `columns`; the `key_columns` among them
+    /// select the row, the others are written (see
+    /// [`Platform::get_update_batch_sql`]: one `UPDATE ... FROM (VALUES ...)`
+    /// on `PostgreSQL`, CASE-based UPDATEs in chunks elsewhere). On `MySQL` it
+    /// is an `INSERT ... ON DUPLICATE KEY UPDATE`, which inserts rows whose
+    /// key is missing and counts updated rows twice. Several statements run
+    /// in one transaction. Returns the number of affected rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if a key column is not in `columns`, no
+    /// other column is, or a row doesn't match `columns`, and an error if a
+    /// statement fails.
+    pub async fn update_batch(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        key_columns: &[&str],
+        columns: &[&str],
+        rows: &[Vec<SqlValue>],
+    ) -> Result<u64> {
+        if key_columns.is_empty() || key_columns.iter().any(|key| !columns.contains(key)) {
+            return Err(Error::config("update_batch needs key columns among the row columns"));
+        }
+        if columns.iter().all(|column| key_columns.contains(column)) {
+            return Err(Error::config("update_batch needs a column to update"));
+        }
+        if rows.iter().any(|row| row.len() != columns.len()) {
+            return Err(Error::config(format!("update_batch rows need {} values", columns.len())));
+        }
+
+        let statements = platform.get_update_batch_sql(table, key_columns, columns, rows);
+        match &statements[..] {
+            [] => return Ok(0),
+            [sql] => return self.execute(sql).await,
+            _ => {}
+        }
+        self.begin_transaction().await?;
+        let result: Result<u64> = async {
+            let mut affected = 0;
+            for sql in &statements {
+                affected += self.execute(sql).await?;
+            }
+            Ok(affected)
+        }
+        .await;
+        self.in_transaction(result).await
+    }
+
+    /// Update a row with only the columns that changed
+    ///
+    /// Compares `new_row` to `old_row` column by column and writes the
+    /// columns whose value differs (numbers compare by value, so `I32(1)`
+    /// equals `I64(1)`) or that `old_row` doesn't have, in the row matching
+    /// `key`. Returns the written columns; nothing is executed when none
+    /// changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `key` is empty,
+    /// `QueryError::RowNotFound` if no row matches `key` (e.g. it was
+    /// deleted meanwhile), and an error if rendering or executing the query
+    /// fails.
+    #[cfg(feature = "query-builder")]
+    pub async fn update_changed(
+        &self,
+        platform: &dyn Platform,
+        table: &str,
+        key: &[(&str, SqlValue)],
+        old_row: &[(&str, SqlValue)],
+        new_row: &[(&str, SqlValue)],
+    ) -> Result<Vec<String>> {
+        if key.is_empty() {
+            return Err(Error::config("update_changed needs the key of the row"));
+        }
+        let changed: Vec<(&str, SqlValue)> = new_row
+            .iter()
+            .filter(|(column, value)| {
+                !old_row.iter().any(|(old_column, old_value)| {
+                    old_column == column
+                        && (old_value == value || old_value.compare(value) == Some(core::cmp::Ordering::Equal))
+                })
+            })
+            .cloned()
+            .collect();
+        if changed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let criteria = key
+            .iter()
+            .fold(crate::query::Criteria::new(), |criteria, (column, value)| criteria.eq(column, value.clone()));
+        if self.update(platform, table, &changed, &criteria).await? == 0 {
+            return Err(Error::Query(crate::core::QueryError::RowNotFound(table.to_string())));
+        }
+        Ok(changed.into_iter().map(|(column, _)| column.to_string()).collect())
+    }
+
+    /// Delete the rows of a table matching the criteria
+    ///
+    /// Returns the number of deleted rows. Ordering and limits need platform
+    /// support for DELETE ... LIMIT. Criteria without conditions are
+    /// rejected rather than deleting every row; run a DELETE without WHERE
+    /// through [`execute`](Self::execute) to do that.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the criteria have no conditions, and
+    /// an error if rendering or executing the query fails.
+    #[cfg(feature = "query-builder")]
+    pub async fn delete(&self, platform: &dyn Platform, table: &str, criteria: &crate::query::Criteria) -> Result<u64> {
+        if criteria.to_expr().is_none() {
+            return Err(Error::config(format!("Deleting from {table} needs criteria with at least one condition")));
+        }
+        let query = criteria.apply(crate::query::QueryBuilder::delete().from(table));
+        self.execute(&query.try_to_sql(platform)?).await
+    }
+
+    /// Run a query for a large IN list, in chunks the platform can bind
+    ///
+    /// Adds `column IN (?, ...)` to the query and runs it once per chunk of
+    /// at most [`Platform::max_bind_parameters`] values, binding the values
+    /// as parameters, and returns the rows of all chunks in order. The query
+    /// must not contain placeholders of its own.
+    ///
+    /// Each chunk is a separate statement, so DISTINCT, GROUP BY, HAVING,
+    /// ORDER BY, LIMIT and OFFSET would only apply within a chunk; such
+    /// queries are rejected when the values need more than one chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the query acts on its whole row set
+    /// and needs several chunks, and an error if rendering or executing a
+    /// query fails.
+    #[cfg(feature = "query-builder")]
+    pub async fn fetch_in_chunks(
+        &self,
+        platform: &dyn Platform,
+        query: &crate::query::QueryBuilder,
+        column: &str,
+        values: &[SqlValue],
+    ) -> Result<ResultSet> {
+        let mut chunks = Self::in_list_chunks(platform, query, column, values)?;
+        let Some((sql, params)) = chunks.next() else {
+            let none = query.clone().where_expr(crate::query::Expr::raw("1 = 0"));
+            return self.fetch_result_set(&none.try_to_sql(platform)?).await;
+        };
+        let mut result = self.fetch_bound(&sql?, params).await?;
+        for (sql, params) in chunks {
+            let more = self.fetch_bound(&sql?, params).await?;
+            result.rows.extend(more.rows);
+            result.rows_affected += more.rows_affected;
+        }
+        Ok(result)
+    }
+
+    /// Run an UPDATE or DELETE for a large IN list, in chunks the platform can bind
+    ///
+    /// Like [`fetch_in_chunks`](Self::fetch_in_chunks), running the chunks
+    /// in one transaction (a savepoint inside an active one). Returns the
+    /// number of affected rows of all chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the query has ORDER BY or LIMIT and
+    /// needs several chunks, and an error if rendering or executing a query
+    /// fails; the chunks already run are rolled back.
+    #[cfg(feature = "query-builder")]
+    pub async fn execute_in_chunks(
+        &self,
+        platform: &dyn Platform,
+        query: &crate::query::QueryBuilder,
+        column: &str,
+        values: &[SqlValue],
+    ) -> Result<u64> {
+        let chunks = Self::in_list_chunks(platform, query, column, values)?;
+        self.begin_transaction().await?;
+        let result: Result<u64> = async {
+            let mut affected = 0;
+            for (sql, params) in chunks {
+                affected += self.execute_bound(&sql?, params).await?;
+            }
+            Ok(affected)
+        }
+        .await;
+        self.in_transaction(result).await
+    }
+
+    /// Split an IN list into statements binding at most `max_bind_parameters` values each
+    ///
+    /// Yields the SQL of each chunk with the values to bind.
+    #[cfg(feature = "query-builder")]
+    fn in_list_chunks<'a>(
+        platform: &'a dyn Platform,
+        query: &'a crate::query::QueryBuilder,
+        column: &'a str,
+        values: &'a [SqlValue],
+    ) -> Result<impl Iterator<Item = (Result<String>, &'a [SqlValue])> + 'a> {
+        use crate::query::Expr;
+
+        let size = platform.max_bind_parameters().unwrap_or(usize::MAX).max(1);
+        if values.len() > size && query.acts_on_row_set() {
+            return Err(Error::config(format!(
+                "{} values need {} statements, which DISTINCT, GROUP BY, HAVING, ORDER BY, LIMIT or OFFSET \
+                 would apply to one by one",
+                values.len(),
+                values.len().div_ceil(size)
+            )));
+        }
+        Ok(values.chunks(size).map(move |chunk| {
+            let placeholders = (0..chunk.len()).map(|i| Expr::param(platform.parameter_placeholder(i))).collect();
+            let sql = query.clone().where_expr(Expr::col(column).in_list(placeholders)).try_to_sql(platform);
+            (sql, chunk)
+        }))
+    }
+
+    /// Take the next key of a sequence, fetching a new block when the current one is used up
+    #[cfg(feature = "query-builder")]
+    async fn next_sequence_value(&self, platform: &dyn Platform, sequence: &super::SequenceAllocator) -> Result<i64> {
+        if let Some(id) = sequence.next_reserved() {
+            return Ok(id);
+        }
+        let sql = platform
+            .sequence_next_value_sql(sequence.name())
+            .ok_or_else(|| Error::platform(format!("{} does not support sequences", platform.name())))?;
+        let first = self.first_value(&sql).await?.as_i64().ok_or_else(|| {
+            Error::driver_message(format!("sequence {} returned a non-integer value", sequence.name()))
+        })?;
+        Ok(sequence.reserve(first))
+    }
+
+    /// Run a query and return the first column of its first row
+    #[cfg(feature = "query-builder")]
+    async fn first_value(&self, sql: &str) -> Result<SqlValue> {
+        self.query(sql)
+            .await?
+            .next_row()?
+            .and_then(|row| row.into_iter().next())
+            .ok_or_else(|| Error::driver_message("query returned no rows"))
+    }
+
+    /// Prepare a SQL statement
+    ///
+    /// The SQL rewriters are applied when the statement is executed, to the
+    /// SQL together with the parameters bound by then (see [`Statement`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement preparation fails or the connection is closed.
+    pub async fn prepare(&self, sql: &str) -> Result<Statement<'_, D>> {
         self.ensure_not_closed()?;
-        self.inner.prepare(sql).await
+        // Rewriters that depend on the parameters get another chance at execution
+        let (prepared, _) = self.rewrite(sql, &[])?;
+        let inner = self.inner.prepare(&prepared).await.map_err(|e| self.sanitize_error(e))?;
+        Ok(Statement::new(self, inner, sql.to_string()))
+    }
+
+    /// Fetch all rows of a query and convert each into `T`
+    ///
+    /// With `Configuration::max_rows` set, reading stops after that many
+    /// rows and the cut-off is reported through `QueryLogger::log_warning`.
+    /// `params` are bound positionally in order. Rows are converted under
+    /// `Configuration::timezone_policy` (see [`FromRow::from_row_with`]) and
+    /// map onto tuples of up to 16 elements:
+    ///
+    /// ```rust,ignore
+    /// let users: Vec<(i64, String, Option<bool>)> = conn
+    ///     .fetch_all_as("SELECT id, name, active FROM users WHERE age > ?", &[SqlValue::I64(18)])
+    ///     .await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails, the connection is closed, or a
+    /// row cannot be converted.
+    pub async fn fetch_all_as<T: FromRow>(&self, sql: &str, params: &[SqlValue]) -> Result<Vec<T>> {
+        let stmt = self.prepare_bound(sql, params).await?;
+        let mut result = stmt.execute().await?;
+        let (rows, cut_off) = self.read_rows(&mut result).map_err(|e| stmt.sanitize_error(e))?;
+        if cut_off {
+            self.logger.log_warning(stmt.sql(), "result cut off at Configuration::max_rows rows");
+        }
+        let policy = self.configuration.timezone_policy;
+        rows.into_iter()
+            .map(|row| match (self.configuration.strict_numeric_conversions, policy) {
+                (false, TimezonePolicy::PassThrough) => T::from_row(row),
+                (false, policy) => T::from_row_with(row, policy),
+                (true, TimezonePolicy::PassThrough) => T::from_row_strict(row),
+                // Checked for lossy numbers, then converted under the policy
+                (true, policy) => T::from_row_strict(row.clone()).and_then(|_| T::from_row_with(row, policy)),
+            })
+            .collect()
+    }
+
+    /// Prepare a statement and bind its parameters by position
+    async fn prepare_bound(&self, sql: &str, params: &[SqlValue]) -> Result<Statement<'_, D>> {
+        let mut stmt = self.prepare(sql).await?;
+        for (position, value) in params.iter().enumerate() {
+            stmt.bind(position, value.clone())?;
+        }
+        Ok(stmt)
+    }
+
+    /// Run a query with positional parameters and return its first result set
+    #[cfg(feature = "query-builder")]
+    async fn fetch_bound(&self, sql: &str, params: &[SqlValue]) -> Result<ResultSet> {
+        let stmt = self.prepare_bound(sql, params).await?;
+        stmt.execute()
+            .await?
+            .result_sets()
+            .map_err(|e| stmt.sanitize_error(e))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::driver_message("query returned no result set"))
+    }
+
+    /// Run a statement with positional parameters and return the affected rows
+    #[cfg(feature = "query-builder")]
+    async fn execute_bound(&self, sql: &str, params: &[SqlValue]) -> Result<u64> {
+        self.prepare_bound(sql, params).await?.execute_update().await
+    }
+
+    /// Read the rows of a result, at most `Configuration::max_rows`
+    ///
+    /// Also returns whether rows were left unread.
+    fn read_rows(&self, result: &mut impl DriverResult) -> Result<(Vec<Vec<SqlValue>>, bool)> {
+        let Some(max_rows) = self.configuration.max_rows else {
+            return Ok((result.all_rows()?, false));
+        };
+        let mut rows = Vec::new();
+        while let Some(row) = result.next_row()? {
+            if rows.len() as u64 == max_rows {
+                return Ok((rows, true));
+            }
+            rows.push(row);
+        }
+        Ok((rows, false))
     }
 
     // ========================================================================
@@ -134,28 +1005,49 @@ impl<D: Driver> Connection<D> {
     /// Begin a new transaction or create a savepoint if already in a transaction
     ///
     /// If no transaction is active, starts a new transaction.
-    /// If a transaction is already active, creates a savepoint for nested transaction.
+    /// If a transaction is already active, the configured
+    /// [`NestedTransactionPolicy`] applies: by default a savepoint is created
+    /// for the nested transaction, or the nesting level only counted if the
+    /// platform doesn't support savepoints.
     ///
     /// # Errors
     ///
-    /// Returns a transaction error if the transaction cannot be started.
+    /// Returns a transaction error if the transaction cannot be started, or
+    /// `TransactionError::NestedNotSupported` if a transaction is active and
+    /// nesting is disabled.
     pub async fn begin_transaction(&self) -> Result<()> {
         self.ensure_not_closed()?;
 
-        let current_level = self.nesting_level.load(Ordering::SeqCst);
+        let current_level = self.synced_nesting_level();
 
         if current_level == 0 {
-            // Start a real transaction
-            self.inner.begin_transaction().await?;
+            // Start a real transaction, or join one begun on the driver connection
+            match self.inner.transaction_status() {
+                TransactionStatus::Idle | TransactionStatus::Unknown => self.inner.begin_transaction().await?,
+                TransactionStatus::Active => {}
+                TransactionStatus::Failed => {
+                    // Nothing of a failed transaction can be committed any more
+                    self.inner.rollback().await?;
+                    self.inner.begin_transaction().await?;
+                }
+            }
         } else {
-            // Create a savepoint for nested transaction
-            let savepoint_name = self.savepoint_name(current_level);
-            let sql = format!("SAVEPOINT {savepoint_name}");
-            self.inner.execute(&sql).await.map_err(|e| {
-                Error::Transaction(TransactionError::CommitFailed(format!(
-                    "Failed to create savepoint: {e}"
-                )))
-            })?;
+            match self.configuration.nested_transaction_policy {
+                NestedTransactionPolicy::Savepoints if self.uses_savepoints() => {
+                    // Create a savepoint for nested transaction
+                    let savepoint_name = self.savepoint_name(current_level);
+                    let sql = format!("SAVEPOINT {savepoint_name}");
+                    self.inner.execute(&sql).await.map_err(|e| {
+                        Error::Transaction(TransactionError::CommitFailed(format!(
+                            "Failed to create savepoint: {e}"
+                        )))
+                    })?;
+                }
+                NestedTransactionPolicy::Savepoints | NestedTransactionPolicy::Ignore => {}
+                NestedTransactionPolicy::Error => {
+                    return Err(Error::Transaction(TransactionError::NestedNotSupported));
+                }
+            }
         }
 
         self.nesting_level.fetch_add(1, Ordering::SeqCst);
@@ -165,15 +1057,19 @@ impl<D: Driver> Connection<D> {
     /// Commit the current transaction or release the current savepoint
     ///
     /// If at the outermost transaction level, commits the transaction.
-    /// If in a nested transaction, releases the savepoint.
+    /// If in a nested transaction, releases the savepoint (or only leaves the
+    /// nesting level with [`NestedTransactionPolicy::Ignore`]). Without
+    /// auto-commit the next implicit transaction begins right away.
     ///
     /// # Errors
     ///
-    /// Returns a transaction error if the commit fails or if marked for rollback only.
+    /// Returns a transaction error if the commit fails or if marked for rollback
+    /// only. A failed transaction (see [`TransactionStatus::Failed`]) is rolled
+    /// back and `TransactionError::Aborted` returned.
     pub async fn commit(&self) -> Result<()> {
         self.ensure_not_closed()?;
 
-        let current_level = self.nesting_level.load(Ordering::SeqCst);
+        let current_level = self.synced_nesting_level();
 
         if current_level == 0 {
             return Err(Error::Transaction(TransactionError::NoActiveTransaction));
@@ -184,9 +1080,14 @@ impl<D: Driver> Connection<D> {
         }
 
         if current_level == 1 {
+            if self.inner.transaction_status() == TransactionStatus::Failed {
+                // The database would turn the COMMIT into a ROLLBACK anyway
+                self.rollback().await?;
+                return Err(Error::Transaction(TransactionError::Aborted));
+            }
             // Commit the real transaction
             self.inner.commit().await?;
-        } else {
+        } else if self.uses_savepoints() {
             // Release the savepoint (some databases like MySQL don't support this)
             let savepoint_name = self.savepoint_name(current_level - 1);
             let sql = format!("RELEASE SAVEPOINT {savepoint_name}");
@@ -195,10 +1096,13 @@ impl<D: Driver> Connection<D> {
         }
 
         self.nesting_level.fetch_sub(1, Ordering::SeqCst);
+        self.schema_change_level.fetch_min(current_level - 1, Ordering::SeqCst);
+        self.end_local_settings(current_level, true).await?;
 
         // Reset rollback_only when exiting outermost transaction
         if self.nesting_level.load(Ordering::SeqCst) == 0 {
             self.rollback_only.store(false, Ordering::SeqCst);
+            self.begin_implicit_transaction().await?;
         }
 
         Ok(())
@@ -207,15 +1111,23 @@ impl<D: Driver> Connection<D> {
     /// Rollback the current transaction or rollback to the current savepoint
     ///
     /// If at the outermost transaction level, rolls back the entire transaction.
-    /// If in a nested transaction, rolls back to the savepoint.
+    /// If in a nested transaction, rolls back to the savepoint. Without
+    /// savepoints ([`NestedTransactionPolicy::Ignore`], or a platform that
+    /// doesn't support them) the transaction is marked rollback-only instead,
+    /// so the outermost commit fails. Without auto-commit the next implicit
+    /// transaction begins right away.
     ///
     /// # Errors
     ///
-    /// Returns a transaction error if no transaction is active or the rollback fails.
+    /// Returns a transaction error if no transaction is active or the rollback
+    /// fails. A nested rollback that wanted a savepoint the platform doesn't
+    /// support leaves the nesting level and marks the transaction
+    /// rollback-only, then returns `TransactionError::SavepointsNotSupported`,
+    /// as the nested work can only be undone with the whole transaction.
     pub async fn rollback(&self) -> Result<()> {
         self.ensure_not_closed()?;
 
-        let current_level = self.nesting_level.load(Ordering::SeqCst);
+        let current_level = self.synced_nesting_level();
 
         if current_level == 0 {
             return Err(Error::Transaction(TransactionError::NoActiveTransaction));
@@ -224,6 +1136,13 @@ impl<D: Driver> Connection<D> {
         if current_level == 1 {
             // Rollback the real transaction
             self.inner.rollback().await?;
+        } else if !self.uses_savepoints() {
+            // Nothing to roll back to: the outermost transaction has to fail
+            self.rollback_only.store(true, Ordering::SeqCst);
+            if self.configuration.nested_transaction_policy == NestedTransactionPolicy::Savepoints {
+                self.nesting_level.fetch_sub(1, Ordering::SeqCst);
+                return Err(Error::Transaction(TransactionError::SavepointsNotSupported));
+            }
         } else {
             // Rollback to the savepoint
             let savepoint_name = self.savepoint_name(current_level - 1);
@@ -236,10 +1155,17 @@ impl<D: Driver> Connection<D> {
         }
 
         self.nesting_level.fetch_sub(1, Ordering::SeqCst);
+        // Statements prepared after DDL that was rolled back expect the
+        // changed schema (`cached plan must not change result type`)
+        if self.schema_change_level.fetch_min(current_level - 1, Ordering::SeqCst) >= current_level {
+            self.clear_statement_cache().await?;
+        }
+        self.end_local_settings(current_level, false).await?;
 
         // Reset rollback_only when exiting outermost transaction
         if self.nesting_level.load(Ordering::SeqCst) == 0 {
             self.rollback_only.store(false, Ordering::SeqCst);
+            self.begin_implicit_transaction().await?;
         }
 
         Ok(())
@@ -272,20 +1198,57 @@ impl<D: Driver> Connection<D> {
         fut: std::pin::Pin<Box<dyn Future<Output = Result<T>> + Send + '_>>,
     ) -> Result<T> {
         self.begin_transaction().await?;
+        let result = fut.await;
+        self.in_transaction(result).await
+    }
 
-        match fut.await {
-            Ok(result) => {
-                self.commit().await?;
-                Ok(result)
-            }
-            Err(e) => {
-                // Try to rollback, but don't hide the original error
-                let _ = self.rollback().await;
-                Err(e)
+    /// Run the future a closure creates in a transaction, restarting it on retryable errors
+    ///
+    /// Like [`transactional_boxed`](Self::transactional_boxed), but after
+    /// a deadlock, timeout or lost connection the transaction is rolled back
+    /// and the closure called again, up to
+    /// [`Configuration::transaction_retries`] times. Nested calls never
+    /// restart, as only the outermost transaction can be repeated.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let conn = Connection::<SqliteDriver>::from_url_with_configuration(
+    ///     url,
+    ///     Configuration::new().with_transaction_retries(3),
+    /// ).await?;
+    /// conn.transactional(|| Box::pin(async {
+    ///     conn.execute("UPDATE accounts SET balance = balance - 10 WHERE id = 1").await?;
+    ///     conn.execute("UPDATE accounts SET balance = balance + 10 WHERE id = 2").await
+    /// })).await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once the retries are used up, and errors that
+    /// are not retryable right away.
+    pub async fn transactional<'f, T, F>(&self, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> std::pin::Pin<Box<dyn Future<Output = Result<T>> + Send + 'f>>,
+    {
+        let mut retries = self.transaction_retries();
+        loop {
+            match self.transactional_boxed(f()).await {
+                Err(e) if retries > 0 && e.is_retryable() => retries -= 1,
+                result => return result,
             }
         }
     }
 
+    /// Get how often a transaction begun now may be restarted
+    pub(crate) fn transaction_retries(&self) -> u32 {
+        if self.transaction_nesting_level() == 0 {
+            self.configuration.transaction_retries
+        } else {
+            0
+        }
+    }
+
     /// Execute operations within a transaction using a simpler callback pattern
     ///
     /// This is a convenience method that handles begin/commit/rollback automatically.
@@ -305,46 +1268,272 @@ impl<D: Driver> Connection<D> {
     ///
     /// # Errors
     ///
-    /// Returns an error if the result is an error or if commit fails.
+    /// Returns an error if the result is an error or if commit fails; a
+    /// failed commit is rolled back.
     pub async fn in_transaction<T, E>(&self, result: std::result::Result<T, E>) -> Result<T>
     where
         E: Into<Error>,
     {
-        match result {
-            Ok(value) => {
-                self.commit().await?;
-                Ok(value)
-            }
-            Err(e) => {
-                let _ = self.rollback().await;
-                Err(e.into())
-            }
+        let result = match result {
+            Ok(value) => self.commit().await.map(|()| value),
+            Err(e) => Err(e.into()),
+        };
+        if result.is_err() && self.transaction_nesting_level() > 0 {
+            // Try to rollback, but don't hide the original error
+            let _ = self.rollback().await;
         }
+        result
     }
 
-    /// Set the isolation level for new transactions
+    /// Run a future in a transaction with transaction-local session variables
     ///
-    /// This must be called before `begin_transaction()`.
-    pub fn set_transaction_isolation(&mut self, level: IsolationLevel) {
-        self.isolation_level = level;
-    }
-
-    /// Get the current isolation level setting
-    #[must_use]
-    pub const fn transaction_isolation(&self) -> IsolationLevel {
-        self.isolation_level
-    }
-
-    /// Get the current transaction nesting level
+    /// Sets each `(name, value)` pair before running the future, e.g. the
+    /// `app.user_id` a `PostgreSQL` row-level security policy reads via
+    /// `current_setting('app.user_id')`. The values end with the outermost
+    /// transaction; a nested context restores the outer values when it
+    /// completes, and is rolled back to its savepoint when it fails. On
+    /// platforms without session variables only the transaction applies.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let platform = PostgresPlatform::new();
+    /// let orders = conn.with_session_context(&platform, &[("app.user_id", "42")], Box::pin(async {
+    ///     conn.fetch_all_as::<Order>("SELECT * FROM orders", &[]).await
+    /// })).await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction, a session statement, or the
+    /// future fails.
+    pub async fn with_session_context<T>(
+        &self,
+        platform: &dyn Platform,
+        context: &[(&str, &str)],
+        fut: std::pin::Pin<Box<dyn Future<Output = Result<T>> + Send + '_>>,
+    ) -> Result<T> {
+        self.begin_transaction().await?;
+        let result: Result<T> = async {
+            let previous = self.enter_session_context(platform, context).await?;
+            let value = fut.await?;
+            self.leave_session_context(platform, previous).await?;
+            Ok(value)
+        }
+        .await;
+        self.in_transaction(result).await
+    }
+
+    /// Change a setting for the rest of the current transaction
+    ///
+    /// Platforms with transaction-local settings (`SET LOCAL` on
+    /// `PostgreSQL`) revert the setting themselves, also when a nested
+    /// transaction rolls back to its savepoint. Elsewhere (`MySQL`) the
+    /// setting is changed for the session and the previous value restored
+    /// when the outermost transaction ends, or the nested transaction that
+    /// changed it rolls back.
+    ///
+    /// ```rust,ignore
+    /// conn.begin_transaction().await?;
+    /// conn.set_local(&platform, "statement_timeout", "5s").await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransactionError::NoActiveTransaction` outside a transaction,
+    /// a configuration error if the name isn't a plain (dotted) identifier, a
+    /// platform error if the platform has no session settings, and an error
+    /// if a statement fails.
+    pub async fn set_local(&self, platform: &dyn Platform, name: &str, value: &str) -> Result<()> {
+        let level = self.synced_nesting_level();
+        if level == 0 {
+            return Err(Error::Transaction(TransactionError::NoActiveTransaction));
+        }
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+            return Err(Error::config(format!("Invalid setting name: {name}")));
+        }
+        if let Some(sql) = platform.set_session_variable_sql(name, value) {
+            self.execute(&sql).await?;
+            return Ok(());
+        }
+        let no_settings = || Error::platform(format!("{} has no session settings", platform.name()));
+        let (Some(query), Some(sql)) =
+            (platform.get_session_setting_sql(name), platform.set_session_setting_sql(name, Some(value)))
+        else {
+            return Err(no_settings());
+        };
+        // NULL is restored as NULL, a setting without a value to its default
+        let previous = self.query(&query).await?.next_row()?.and_then(|row| row.into_iter().next());
+        let restore = match previous {
+            Some(SqlValue::Null) => platform.set_session_setting_sql(name, None),
+            Some(SqlValue::String(text)) => platform.set_session_setting_sql(name, Some(&text)),
+            Some(value) => platform.set_session_setting_sql(name, Some(&value.to_string())),
+            None => platform.reset_session_setting_sql(name),
+        }
+        .ok_or_else(no_settings)?;
+        self.execute(&sql).await?;
+        self.local_settings
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push((level, restore));
+        Ok(())
+    }
+
+    /// Hand the `set_local` settings of an ended nesting level to its parent
+    /// on commit, otherwise restore them
+    async fn end_local_settings(&self, level: u32, committed: bool) -> Result<()> {
+        let restore = {
+            let mut settings = self.local_settings.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+            if committed && level > 1 {
+                for (setting_level, _) in settings.iter_mut().filter(|(l, _)| *l == level) {
+                    *setting_level = level - 1;
+                }
+                return Ok(());
+            }
+            let start = settings.iter().position(|(l, _)| *l >= level).unwrap_or(settings.len());
+            settings.split_off(start)
+        };
+        for (_, sql) in restore.into_iter().rev() {
+            self.inner.execute(&sql).await?;
+        }
+        Ok(())
+    }
+
+    /// Set session variables, returning the values to restore when nested
+    pub(crate) async fn enter_session_context(
+        &self,
+        platform: &dyn Platform,
+        context: &[(&str, &str)],
+    ) -> Result<Vec<(String, String)>> {
+        let mut previous = Vec::new();
+        for &(name, value) in context {
+            let Some(sql) = platform.set_session_variable_sql(name, value) else {
+                continue;
+            };
+            // The outermost transaction discards the values on its own
+            if self.transaction_nesting_level() > 1 {
+                if let Some(query) = platform.get_session_variable_sql(name) {
+                    let current = self.query(&query).await?.next_row()?;
+                    let current = current.and_then(|row| row.into_iter().next()).and_then(SqlValue::into_string);
+                    previous.push((name.to_string(), current.unwrap_or_default()));
+                }
+            }
+            self.execute(&sql).await?;
+        }
+        Ok(previous)
+    }
+
+    /// Restore session variables saved by [`Self::enter_session_context`]
+    pub(crate) async fn leave_session_context(
+        &self,
+        platform: &dyn Platform,
+        previous: Vec<(String, String)>,
+    ) -> Result<()> {
+        for (name, value) in previous.into_iter().rev() {
+            if let Some(sql) = platform.set_session_variable_sql(&name, &value) {
+                self.execute(&sql).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the isolation level for new transactions
+    ///
+    /// This must be called before `begin_transaction()`.
+    pub fn set_transaction_isolation(&mut self, level: IsolationLevel) {
+        self.isolation_level = level;
+    }
+
+    /// Get the current isolation level setting
+    #[must_use]
+    pub const fn transaction_isolation(&self) -> IsolationLevel {
+        self.isolation_level
+    }
+
+    /// Turn auto-commit on or off
+    ///
+    /// With auto-commit off, a transaction is always active: one begins
+    /// right away, and again after each outermost
+    /// [`commit`](Self::commit) or [`rollback`](Self::rollback), so nothing
+    /// is persisted until committed. [`begin_transaction`](Self::begin_transaction)
+    /// nests inside this implicit transaction. Changing the mode commits an
+    /// active transaction, as in Doctrine. Platforms with a session setting
+    /// for it (`SET autocommit` on `MySQL`) get it switched as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed, committing the active
+    /// transaction fails, or a statement fails.
+    pub async fn set_auto_commit(&self, platform: &dyn Platform, auto_commit: bool) -> Result<()> {
+        self.ensure_not_closed()?;
+        if self.auto_commit.swap(auto_commit, Ordering::SeqCst) == auto_commit {
+            return Ok(());
+        }
+
+        while self.nesting_level.load(Ordering::SeqCst) > 0 {
+            self.commit().await?;
+        }
+        if let Some(sql) = platform.set_auto_commit_sql(auto_commit) {
+            self.execute(&sql).await?;
+        }
+        self.begin_implicit_transaction().await
+    }
+
+    /// Check whether auto-commit is on
+    ///
+    /// Auto-commit is on unless turned off with [`set_auto_commit`](Self::set_auto_commit),
+    /// or by [`configure_session`](Self::configure_session) for a
+    /// configuration without [`Configuration::auto_commit`].
+    pub fn is_auto_commit(&self) -> bool {
+        self.auto_commit.load(Ordering::SeqCst)
+    }
+
+    /// Get the current transaction nesting level
     ///
     /// Returns 0 if no transaction is active.
     pub fn transaction_nesting_level(&self) -> u32 {
-        self.nesting_level.load(Ordering::SeqCst)
+        self.synced_nesting_level()
+    }
+
+    /// Get the names of the savepoints of the active nested transactions
+    ///
+    /// Outermost first; empty outside nested transactions and when nested
+    /// transactions aren't backed by savepoints.
+    pub fn active_savepoints(&self) -> Vec<String> {
+        if !self.uses_savepoints() {
+            return Vec::new();
+        }
+        (1..self.synced_nesting_level()).map(|level| self.savepoint_name(level)).collect()
     }
 
     /// Check if a transaction is currently active
+    ///
+    /// Also true for a transaction begun on the driver connection directly.
     pub fn is_transaction_active(&self) -> bool {
-        self.nesting_level.load(Ordering::SeqCst) > 0
+        self.transaction_status().in_transaction()
+    }
+
+    /// Get the transaction state reported by the driver connection
+    ///
+    /// Never [`TransactionStatus::Unknown`]: for drivers that don't track
+    /// transactions the state follows the nesting level.
+    pub fn transaction_status(&self) -> TransactionStatus {
+        match self.inner.transaction_status() {
+            TransactionStatus::Unknown if self.nesting_level.load(Ordering::SeqCst) > 0 => TransactionStatus::Active,
+            TransactionStatus::Unknown => TransactionStatus::Idle,
+            status => status,
+        }
+    }
+
+    /// Get the nesting level, reset when the driver connection has no transaction
+    ///
+    /// The driver's status is the source of truth: a transaction committed
+    /// or rolled back on [`inner`](Self::inner) ends every nesting level.
+    fn synced_nesting_level(&self) -> u32 {
+        if self.inner.transaction_status() == TransactionStatus::Idle && self.nesting_level.swap(0, Ordering::SeqCst) > 0 {
+            self.rollback_only.store(false, Ordering::SeqCst);
+        }
+        self.nesting_level.load(Ordering::SeqCst)
     }
 
     /// Check if the current transaction is marked as rollback-only
@@ -360,6 +1549,32 @@ impl<D: Driver> Connection<D> {
         self.rollback_only.store(true, Ordering::SeqCst);
     }
 
+    /// Get the backend identifier of the current transaction
+    ///
+    /// Reads e.g. `txid_current()` on `PostgreSQL` or the `InnoDB` `trx_id`
+    /// on `MySQL`; check [`Platform::supports_transaction_id`] first. Returns
+    /// `None` if the backend hasn't assigned an identifier yet (`MySQL` does
+    /// so with the first statement of the transaction).
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransactionError::NoActiveTransaction` outside a transaction,
+    /// a platform error if the platform has no transaction identifier, or an
+    /// error if the query fails.
+    pub async fn current_transaction_id(&self, platform: &dyn Platform) -> Result<Option<String>> {
+        if !self.is_transaction_active() {
+            return Err(Error::Transaction(TransactionError::NoActiveTransaction));
+        }
+        let sql = platform.current_transaction_id_sql().ok_or_else(|| {
+            Error::platform(format!("{} does not expose transaction identifiers", platform.name()))
+        })?;
+        let value = self.query(&sql).await?.next_row()?.and_then(|row| row.into_iter().next());
+        Ok(value.and_then(|value| match value.as_i64() {
+            Some(id) => Some(id.to_string()),
+            None => value.into_string(),
+        }))
+    }
+
     // ========================================================================
     // Connection State
     // ========================================================================
@@ -382,6 +1597,42 @@ impl<D: Driver> Connection<D> {
         self.inner.server_version().await
     }
 
+    /// Get the platform for a driver name matching the server's version
+    ///
+    /// Looks up `driver` in `registry` and adjusts the platform to
+    /// [`server_version`](Self::server_version), e.g. enabling RETURNING on
+    /// `MariaDB` 10.5+. Detect once per pool or connection and reuse the
+    /// result.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if no platform is registered for the
+    /// driver, or an error if the version query fails.
+    pub async fn detect_platform(&self, driver: &str, registry: &PlatformRegistry) -> Result<Arc<dyn Platform>> {
+        let version = self.server_version().await?;
+        registry
+            .for_server_version(driver, &version)
+            .ok_or_else(|| Error::config(format!("No platform registered for driver '{driver}'")))
+    }
+
+    /// Discard the driver's cached prepared statements
+    ///
+    /// Done after DDL (CREATE, ALTER, DROP, TRUNCATE, RENAME, COMMENT) run
+    /// through [`execute`](Self::execute), and again on the rollback undoing
+    /// it; [`SchemaManager`](crate::schema::SchemaManager) does it after every
+    /// schema change. Also forgets the table columns cached for `SELECT *`
+    /// expansion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection is closed or the driver fails.
+    pub async fn clear_statement_cache(&self) -> Result<()> {
+        self.ensure_not_closed()?;
+        #[cfg(all(feature = "schema", feature = "query-builder"))]
+        self.table_columns.lock().unwrap_or_else(std::sync::PoisonError::into_inner).clear();
+        self.inner.clear_statement_cache().await
+    }
+
     /// Close the connection
     ///
     /// If a transaction is active, it will be rolled back first.
@@ -390,15 +1641,19 @@ impl<D: Driver> Connection<D> {
     ///
     /// This method currently does not fail, but returns `Result` for future compatibility.
     pub async fn close(&self) -> Result<()> {
-        if self.closed.swap(true, Ordering::SeqCst) {
+        if self.closed.load(Ordering::SeqCst) {
             return Ok(()); // Already closed
         }
 
-        // Rollback any active transaction
+        // Rollback any active transaction, without beginning an implicit one
+        self.auto_commit.store(true, Ordering::SeqCst);
         while self.nesting_level.load(Ordering::SeqCst) > 0 {
-            let _ = self.rollback().await;
+            if self.rollback().await.is_err() {
+                break;
+            }
         }
 
+        self.closed.store(true, Ordering::SeqCst);
         Ok(())
     }
 
@@ -411,14 +1666,114 @@ impl<D: Driver> Connection<D> {
     // Private Helpers
     // ========================================================================
 
+    /// Begin the implicit transaction if auto-commit is off and none is active
+    async fn begin_implicit_transaction(&self) -> Result<()> {
+        if !self.is_auto_commit() && self.nesting_level.load(Ordering::SeqCst) == 0 {
+            self.begin_transaction().await?;
+        }
+        Ok(())
+    }
+
+    /// Whether nested transactions are backed by savepoints
+    fn uses_savepoints(&self) -> bool {
+        self.configuration.nested_transaction_policy == NestedTransactionPolicy::Savepoints
+            && self.savepoints_supported.load(Ordering::SeqCst)
+    }
+
     /// Generate a savepoint name for the given nesting level
-    #[allow(clippy::unused_self)]
     fn savepoint_name(&self, level: u32) -> String {
-        format!("RUSTINE_{level}")
+        format!("{}{level}", self.configuration.savepoint_prefix)
+    }
+
+    /// Report an executed query to the logger
+    ///
+    /// Slow queries are always reported (when a threshold is configured),
+    /// regular queries only when logging is enabled.
+    pub(super) async fn log_query(&self, sql: &str, params: &[SqlValue], duration: Duration) {
+        let is_slow = self
+            .configuration
+            .slow_query_threshold
+            .is_some_and(|threshold| duration >= threshold);
+
+        if !is_slow && !self.configuration.enable_logging {
+            return;
+        }
+
+        // Redacted copies only, loggers never see sensitive values
+        let redaction = &self.configuration.redaction;
+        let logged_sql = if redaction.redacts_sql(sql) {
+            Cow::Owned(redaction.redact_sql(sql))
+        } else {
+            Cow::Borrowed(sql)
+        };
+        let logged_params = if redaction.is_active() {
+            Cow::Owned(redaction.redact_params(params))
+        } else {
+            Cow::Borrowed(params)
+        };
+
+        let mut entry = QueryLogEntry {
+            sql: &logged_sql,
+            params: &logged_params,
+            duration,
+            explain: None,
+            include_values: self.configuration.log_parameter_values,
+        };
+
+        if is_slow {
+            if self.configuration.explain_slow_queries {
+                // A failing EXPLAIN must not affect the original query
+                entry.explain = self
+                    .inner
+                    .explain(sql)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|plan| redaction.redact_sql(&plan));
+            }
+            self.logger.log_slow_query(&entry);
+        } else {
+            self.logger.log_query(&entry);
+        }
+    }
+
+    /// Apply the non-finite float policy and the SQL rewriters to a statement
+    /// and its parameters
+    pub(super) fn rewrite<'a>(&self, sql: &'a str, params: &'a [SqlValue]) -> Result<(Cow<'a, str>, Cow<'a, [SqlValue]>)> {
+        let policy = self.configuration.non_finite_float_policy;
+        let floats_kept = policy == NonFiniteFloatPolicy::PassThrough
+            || params.iter().all(|value| value.non_finite_float().is_none());
+        if self.rewriters.is_empty() && floats_kept {
+            return Ok((Cow::Borrowed(sql), Cow::Borrowed(params)));
+        }
+        let mut sql = sql.to_string();
+        let mut params = params.iter().cloned().map(|value| policy.apply(value)).collect::<Result<Vec<_>>>()?;
+        for rewriter in &self.rewriters {
+            rewriter.rewrite(&mut sql, &mut params)?;
+        }
+        Ok((Cow::Owned(sql), Cow::Owned(params)))
+    }
+
+    /// Apply the SQL rewriters to a statement without parameters
+    fn rewrite_sql<'a>(&self, sql: &'a str) -> Result<Cow<'a, str>> {
+        let (sql, params) = self.rewrite(sql, &[])?;
+        if !params.is_empty() {
+            return Err(Error::config("SQL rewriter added parameters to a statement that takes none"));
+        }
+        Ok(sql)
+    }
+
+    /// Apply the redaction policy and error length cap before an error reaches the caller
+    pub(super) fn sanitize_error(&self, error: Error) -> Error {
+        let error = self.configuration.redaction.redact_error(error);
+        match self.configuration.error_sql_max_length {
+            Some(max_length) => error.truncated(max_length, self.configuration.keep_full_error_sql),
+            None => error,
+        }
     }
 
     /// Ensure the connection is not closed
-    fn ensure_not_closed(&self) -> Result<()> {
+    pub(super) fn ensure_not_closed(&self) -> Result<()> {
         if self.closed.load(Ordering::SeqCst) {
             return Err(Error::Connection(crate::core::ConnectionError::Closed));
         }
@@ -426,6 +1781,85 @@ impl<D: Driver> Connection<D> {
     }
 }
 
+impl<D: Driver + Default> Connection<D> {
+    /// Connect using a connection URL
+    ///
+    /// The URL is parsed with [`ConnectionParams::from_url`] and connected
+    /// with [`Connection::from_params`].
+    ///
+    /// ```rust,ignore
+    /// let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the URL is invalid or names a driver
+    /// that `D` doesn't support, or a connection error if connecting fails.
+    pub async fn from_url(url: &str) -> Result<Self> {
+        Self::from_params(&ConnectionParams::from_url(url)?).await
+    }
+
+    /// Connect using a connection URL and a runtime configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the URL is invalid or names a driver
+    /// that `D` doesn't support, or an error if connecting or configuring the
+    /// session fails.
+    pub async fn from_url_with_configuration(url: &str, configuration: Configuration) -> Result<Self> {
+        Self::from_params_with_configuration(&ConnectionParams::from_url(url)?, configuration).await
+    }
+
+    /// Connect using connection parameters and the default configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `D` doesn't support the driver of the
+    /// parameters, or a connection error if connecting fails.
+    pub async fn from_params(params: &ConnectionParams) -> Result<Self> {
+        Self::from_params_with_configuration(params, Configuration::default()).await
+    }
+
+    /// Connect using connection parameters and a runtime configuration
+    ///
+    /// The driver is created with `D::default()` and receives the
+    /// configuration (see [`Connection::new_with_configuration`]). Once
+    /// connected, session settings are applied with [`Connection::configure_session`] using the
+    /// platform registered for the driver name.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if `D` doesn't support the driver of the
+    /// parameters, or an error if connecting or configuring the session fails.
+    pub async fn from_params_with_configuration(
+        params: &ConnectionParams,
+        configuration: Configuration,
+    ) -> Result<Self> {
+        let driver = D::default();
+        if !driver.supports(&params.driver) {
+            return Err(Error::config(format!(
+                "Driver '{}' cannot connect to '{}'",
+                driver.name(),
+                params.driver
+            )));
+        }
+
+        let conn = Self::new_with_configuration(&driver, params, configuration).await?;
+        if let Some(platform) = PlatformRegistry::with_defaults().get(&params.driver) {
+            conn.configure_session(platform.as_ref()).await?;
+        }
+        Ok(conn)
+    }
+}
+
+/// Check if a statement changes the schema, by its first keyword
+fn is_schema_change(sql: &str) -> bool {
+    let keyword = sql.trim_start().split(|c: char| !c.is_ascii_alphabetic()).next().unwrap_or_default();
+    ["CREATE", "ALTER", "DROP", "TRUNCATE", "RENAME", "COMMENT"]
+        .iter()
+        .any(|ddl| keyword.eq_ignore_ascii_case(ddl))
+}
+
 impl<D: Driver> Drop for Connection<D> {
     fn drop(&mut self) {
         let level = self.nesting_level.load(Ordering::SeqCst);
@@ -451,7 +1885,7 @@ mod tests {
     #[cfg(feature = "sqlite")]
     mod sqlite_tests {
         use super::*;
-        use crate::core::SqlValue;
+        use crate::core::{ParameterType, QueryError, SqlValue, ValueRedactionPolicy};
         use crate::driver::sqlite::SqliteDriver;
         use crate::driver::DriverResult;
 
@@ -485,6 +1919,41 @@ mod tests {
             assert_eq!(rows[0][0], SqlValue::I64(1));
         }
 
+        #[tokio::test]
+        async fn test_prepared_statement_in_nested_transaction() {
+            let driver = SqliteDriver::new();
+            let params = ConnectionParams::sqlite_memory();
+            let conn = Connection::new(&driver, &params).await.unwrap();
+
+            conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)")
+                .await
+                .unwrap();
+
+            let mut stmt = conn
+                .prepare("INSERT INTO test (id, name) VALUES (?, ?)")
+                .await
+                .unwrap();
+            assert!(!stmt.inner().in_transaction());
+
+            conn.begin_transaction().await.unwrap();
+            stmt.bind(0, SqlValue::I64(1)).unwrap();
+            stmt.bind(1, SqlValue::String("Alice".to_string())).unwrap();
+            stmt.execute_update().await.unwrap();
+
+            // Statement runs inside the savepoint and is rolled back with it
+            conn.begin_transaction().await.unwrap();
+            assert!(stmt.inner().in_transaction());
+            stmt.bind(0, SqlValue::I64(2)).unwrap();
+            stmt.execute_update().await.unwrap();
+            conn.rollback().await.unwrap();
+
+            conn.commit().await.unwrap();
+
+            let mut result = conn.query("SELECT id FROM test").await.unwrap();
+            let rows = result.all_rows().unwrap();
+            assert_eq!(rows, vec![vec![SqlValue::I64(1)]]);
+        }
+
         #[tokio::test]
         async fn test_transaction_rollback() {
             let driver = SqliteDriver::new();
@@ -550,76 +2019,701 @@ mod tests {
         }
 
         #[tokio::test]
-        async fn test_transactional_commit() {
-            let driver = SqliteDriver::new();
-            let params = ConnectionParams::sqlite_memory();
-            let conn = Connection::new(&driver, &params).await.unwrap();
-
-            conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)")
+        async fn test_savepoint_prefix() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
                 .await
-                .unwrap();
+                .unwrap()
+                .with_configuration(Configuration::new().with_savepoint_prefix("app_sp_"));
+            assert!(conn.active_savepoints().is_empty());
 
-            // Use begin/commit pattern
             conn.begin_transaction().await.unwrap();
-            conn.execute("INSERT INTO test (id, name) VALUES (1, 'Alice')")
-                .await
-                .unwrap();
-            conn.commit().await.unwrap();
-
-            assert!(!conn.is_transaction_active());
+            assert!(conn.active_savepoints().is_empty());
+            conn.begin_transaction().await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            assert_eq!(conn.active_savepoints(), ["app_sp_1", "app_sp_2"]);
+            // The savepoints exist under the configured names
+            conn.execute("RELEASE SAVEPOINT app_sp_2").await.unwrap();
+            conn.execute("SAVEPOINT app_sp_2").await.unwrap();
 
-            // Verify data committed
-            let mut result = conn.query("SELECT COUNT(*) FROM test").await.unwrap();
-            let rows = result.all_rows().unwrap();
-            assert_eq!(rows[0][0], SqlValue::I64(1));
+            conn.rollback().await.unwrap();
+            assert_eq!(conn.active_savepoints(), ["app_sp_1"]);
+            conn.commit().await.unwrap();
+            conn.commit().await.unwrap();
+            assert!(conn.active_savepoints().is_empty());
         }
 
         #[tokio::test]
-        async fn test_transactional_rollback_on_error() {
+        async fn test_nested_transaction_policy() {
             let driver = SqliteDriver::new();
             let params = ConnectionParams::sqlite_memory();
-            let conn = Connection::new(&driver, &params).await.unwrap();
+            let configuration = Configuration::new().with_nested_transaction_policy(NestedTransactionPolicy::Ignore);
+            let conn = Connection::new(&driver, &params).await.unwrap().with_configuration(configuration);
 
             conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)")
                 .await
                 .unwrap();
 
-            // Use begin_transaction + in_transaction pattern
+            // Nested levels are only counted, an inner rollback dooms the outer commit
+            conn.begin_transaction().await.unwrap();
             conn.begin_transaction().await.unwrap();
             conn.execute("INSERT INTO test (id, name) VALUES (1, 'Alice')")
                 .await
                 .unwrap();
+            conn.rollback().await.unwrap();
+            assert!(conn.is_rollback_only());
+            assert!(matches!(
+                conn.commit().await,
+                Err(Error::Transaction(TransactionError::RollbackOnly))
+            ));
+            conn.rollback().await.unwrap();
 
-            // Simulate an error and use in_transaction to handle it
-            let simulated_result: std::result::Result<(), Error> =
-                Err(Error::driver_message("Simulated error"));
-            let result = conn.in_transaction(simulated_result).await;
-
-            assert!(result.is_err());
-            assert!(!conn.is_transaction_active());
+            let configuration = Configuration::new().with_nested_transaction_policy(NestedTransactionPolicy::Error);
+            let conn = conn.with_configuration(configuration);
+            conn.begin_transaction().await.unwrap();
+            assert!(matches!(
+                conn.begin_transaction().await,
+                Err(Error::Transaction(TransactionError::NestedNotSupported))
+            ));
+            assert_eq!(conn.transaction_nesting_level(), 1);
+            conn.rollback().await.unwrap();
 
-            // Verify data rolled back
             let mut result = conn.query("SELECT COUNT(*) FROM test").await.unwrap();
             let rows = result.all_rows().unwrap();
             assert_eq!(rows[0][0], SqlValue::I64(0));
         }
 
         #[tokio::test]
-        async fn test_rollback_only() {
-            let driver = SqliteDriver::new();
-            let params = ConnectionParams::sqlite_memory();
-            let conn = Connection::new(&driver, &params).await.unwrap();
-
-            conn.begin_transaction().await.unwrap();
-            conn.set_rollback_only();
+        async fn test_nested_transaction_without_savepoints() {
+            /// `SQLite` pretending not to support savepoints
+            struct NoSavepointPlatform;
+
+            impl Platform for NoSavepointPlatform {
+                fn name(&self) -> &'static str {
+                    "sqlite"
+                }
+
+                fn supports_savepoints(&self) -> bool {
+                    false
+                }
+            }
 
-            assert!(conn.is_rollback_only());
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            conn.configure_session(&NoSavepointPlatform).await.unwrap();
+            conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY)").await.unwrap();
 
-            // Commit should fail
-            let result = conn.commit().await;
-            assert!(matches!(
-                result,
-                Err(Error::Transaction(TransactionError::RollbackOnly))
+            // Nesting is only counted; SAVEPOINT would be accepted by SQLite
+            conn.begin_transaction().await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.execute("INSERT INTO test (id) VALUES (1)").await.unwrap();
+            conn.commit().await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            assert!(matches!(
+                conn.rollback().await,
+                Err(Error::Transaction(TransactionError::SavepointsNotSupported))
+            ));
+            assert_eq!(conn.transaction_nesting_level(), 1);
+            assert!(matches!(
+                conn.commit().await,
+                Err(Error::Transaction(TransactionError::RollbackOnly))
+            ));
+            conn.rollback().await.unwrap();
+
+            let mut result = conn.query("SELECT COUNT(*) FROM test").await.unwrap();
+            assert_eq!(result.all_rows().unwrap()[0][0], SqlValue::I64(0));
+        }
+
+        #[tokio::test]
+        async fn test_criteria_helpers() {
+            use crate::query::Criteria;
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let platform = crate::platform::SqlitePlatform::new();
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER)")
+                .await
+                .unwrap();
+            conn.execute("INSERT INTO users VALUES (1, 'Alice', 30), (2, 'Bob', 17), (3, 'Carol', 45)")
+                .await
+                .unwrap();
+
+            let adults = Criteria::new().ge("age", 18).order_by_desc("age");
+            let result = conn.select(&platform, "users", &adults.clone().limit(1)).await.unwrap();
+            assert_eq!(result.rows[0].get::<String>("name").unwrap(), "Carol");
+
+            let renamed = conn
+                .update(&platform, "users", &[("name", "Robert".into())], &Criteria::new().eq("name", "Bob"))
+                .await
+                .unwrap();
+            assert_eq!(renamed, 1);
+            assert_eq!(conn.delete(&platform, "users", &adults).await.unwrap(), 2);
+
+            // Criteria without conditions would affect the whole table
+            let all = Criteria::new().limit(1);
+            let error = conn.update(&platform, "users", &[("age", 18.into())], &all).await.unwrap_err();
+            assert!(matches!(error, Error::Configuration(_)), "{error}");
+            assert!(matches!(conn.delete(&platform, "users", &all).await, Err(Error::Configuration(_))));
+
+            let result = conn.select(&platform, "users", &Criteria::new()).await.unwrap();
+            assert_eq!(
+                result.rows[0].values(),
+                [SqlValue::I64(2), SqlValue::String("Robert".to_string()), SqlValue::I64(17)]
+            );
+            assert_eq!(result.rows.len(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_update_batch() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let platform = crate::platform::SqlitePlatform::new();
+            conn.execute("CREATE TABLE stock (shop INTEGER, item TEXT, count INTEGER, note TEXT, PRIMARY KEY (shop, item))")
+                .await
+                .unwrap();
+            conn.execute("INSERT INTO stock VALUES (1, 'a', 0, NULL), (1, 'b', 0, NULL), (2, 'a', 0, NULL)")
+                .await
+                .unwrap();
+
+            let columns = ["shop", "item", "count", "note"];
+            let rows: Vec<Vec<SqlValue>> = (0..crate::platform::UPDATE_BATCH_ROWS as i64 + 1)
+                .map(|i| vec![SqlValue::I64(i % 3), SqlValue::from(if i < 3 { "a" } else { "z" }), SqlValue::I64(i), "x".into()])
+                .collect();
+            let affected = conn.update_batch(&platform, "stock", &["shop", "item"], &columns, &rows).await.unwrap();
+            assert_eq!(affected, 2);
+            let stock: Vec<(i64, String, i64, Option<String>)> =
+                conn.fetch_all_as("SELECT shop, item, count, note FROM stock ORDER BY shop, item", &[]).await.unwrap();
+            assert_eq!(stock[0], (1, "a".to_string(), 1, Some("x".to_string())));
+            assert_eq!(stock[1], (1, "b".to_string(), 0, None));
+            assert_eq!(stock[2], (2, "a".to_string(), 2, Some("x".to_string())));
+
+            assert_eq!(conn.update_batch(&platform, "stock", &["shop"], &["shop", "count"], &[]).await.unwrap(), 0);
+            assert!(conn.update_batch(&platform, "stock", &["id"], &["shop", "count"], &[]).await.is_err());
+            assert!(conn.update_batch(&platform, "stock", &["shop"], &["shop"], &[]).await.is_err());
+            let short = [vec![SqlValue::I64(1)]];
+            assert!(conn.update_batch(&platform, "stock", &["shop"], &["shop", "count"], &short).await.is_err());
+        }
+
+        #[cfg(feature = "query-builder")]
+        #[tokio::test]
+        async fn test_update_changed() {
+            let logger = Arc::new(RecordingLogger::default());
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(Configuration::new().with_logging(true))
+                .with_logger(logger.clone());
+            let platform = crate::platform::SqlitePlatform::new();
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER, bio TEXT)")
+                .await
+                .unwrap();
+            conn.execute("INSERT INTO users VALUES (1, 'Alice', 30, NULL)").await.unwrap();
+
+            let key = [("id", SqlValue::I64(1))];
+            let old = [("name", "Alice".into()), ("age", SqlValue::I64(30)), ("bio", SqlValue::Null)];
+            let new = [("name", "Alice".into()), ("age", SqlValue::I32(31)), ("bio", SqlValue::Null)];
+            let written = conn.update_changed(&platform, "users", &key, &old, &new).await.unwrap();
+            assert_eq!(written, vec!["age".to_string()]);
+            let sql = logger.queries.lock().unwrap().last().unwrap().clone();
+            assert_eq!(sql, "UPDATE \"users\" SET \"age\" = 31 WHERE \"id\" = 1");
+
+            let queries = logger.queries.lock().unwrap().len();
+            assert!(conn.update_changed(&platform, "users", &key, &new, &new).await.unwrap().is_empty());
+            assert_eq!(logger.queries.lock().unwrap().len(), queries);
+            assert!(conn.update_changed(&platform, "users", &[], &old, &new).await.is_err());
+
+            let missing = [("id", SqlValue::I64(2))];
+            assert!(matches!(
+                conn.update_changed(&platform, "users", &missing, &old, &new).await,
+                Err(Error::Query(QueryError::RowNotFound(table))) if table == "users"
+            ));
+        }
+
+        #[cfg(feature = "schema")]
+        #[tokio::test]
+        async fn test_fetch_select_star_policy() {
+            use crate::core::SelectStarPolicy;
+            use crate::query::QueryBuilder;
+
+            let logger = Arc::new(RecordingLogger::default());
+            let platform = crate::platform::SqlitePlatform::new();
+            let query = QueryBuilder::select().all().from("users").order_by_asc("id");
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(Configuration::default().with_select_star_policy(SelectStarPolicy::Expand));
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+            conn.execute("INSERT INTO users VALUES (1, 'Alice')").await.unwrap();
+
+            let result = conn.fetch(&platform, &query).await.unwrap();
+            assert_eq!(result.rows[0].values(), [SqlValue::I64(1), SqlValue::String("Alice".to_string())]);
+
+            // DDL run through the connection clears the cached columns
+            conn.execute("ALTER TABLE users ADD COLUMN age INTEGER").await.unwrap();
+            assert_eq!(conn.fetch(&platform, &query).await.unwrap().rows[0].values().len(), 3);
+            // Changes made elsewhere keep the result shape until the cache is cleared
+            conn.inner.execute("ALTER TABLE users ADD COLUMN email TEXT").await.unwrap();
+            assert_eq!(conn.fetch(&platform, &query).await.unwrap().rows[0].values().len(), 3);
+            conn.clear_statement_cache().await.unwrap();
+            assert_eq!(conn.fetch(&platform, &query).await.unwrap().rows[0].values().len(), 4);
+
+            let conn = conn
+                .with_configuration(Configuration::default().with_select_star_policy(SelectStarPolicy::Warn))
+                .with_logger(logger.clone());
+            conn.fetch(&platform, &query).await.unwrap();
+            conn.fetch(&platform, &QueryBuilder::select().columns(&["name"]).from("users")).await.unwrap();
+            assert_eq!(
+                *logger.warnings.lock().unwrap(),
+                ["SELECT * FROM \"users\" ORDER BY \"id\" ASC"]
+            );
+
+            let conn = conn.with_configuration(Configuration::default().with_select_star_policy(SelectStarPolicy::Deny));
+            assert!(matches!(conn.fetch(&platform, &query).await, Err(Error::Configuration(_))));
+            assert!(conn.select(&platform, "users", &crate::query::Criteria::new()).await.is_err());
+        }
+
+        #[cfg(feature = "schema")]
+        #[tokio::test]
+        async fn test_rollback_clears_statement_cache() {
+            use crate::core::SelectStarPolicy;
+            use crate::query::QueryBuilder;
+
+            let platform = crate::platform::SqlitePlatform::new();
+            let query = QueryBuilder::select().all().from("users");
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(Configuration::default().with_select_star_policy(SelectStarPolicy::Expand));
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+            conn.execute("INSERT INTO users VALUES (1, 'Alice')").await.unwrap();
+
+            conn.begin_transaction().await.unwrap();
+            conn.execute("ALTER TABLE users ADD COLUMN age INTEGER").await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.commit().await.unwrap();
+            assert_eq!(conn.fetch(&platform, &query).await.unwrap().rows[0].values().len(), 3);
+            // The expansion cached with the added column would fail now
+            conn.rollback().await.unwrap();
+            assert_eq!(conn.fetch(&platform, &query).await.unwrap().rows[0].values().len(), 2);
+
+            conn.begin_transaction().await.unwrap();
+            conn.execute("ALTER TABLE users ADD COLUMN age INTEGER").await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.execute("ALTER TABLE users ADD COLUMN email TEXT").await.unwrap();
+            assert_eq!(conn.fetch(&platform, &query).await.unwrap().rows[0].values().len(), 4);
+            conn.rollback().await.unwrap();
+            assert_eq!(conn.fetch(&platform, &query).await.unwrap().rows[0].values().len(), 3);
+            conn.commit().await.unwrap();
+            assert_eq!(conn.schema_change_level.load(Ordering::SeqCst), 0);
+
+            assert!(is_schema_change("  create index i ON users (name)"));
+            assert!(is_schema_change("DROP TABLE users"));
+            assert!(!is_schema_change("SELECT 'DROP'"));
+            assert!(!is_schema_change("UPDATE created SET x = 1"));
+        }
+
+        #[tokio::test]
+        async fn test_in_list_chunks() {
+            use crate::query::QueryBuilder;
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let platform = crate::platform::SqlitePlatform::new();
+            conn.execute("CREATE TABLE items (id INTEGER PRIMARY KEY)").await.unwrap();
+            conn.execute("WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 33000) \
+                          INSERT INTO items SELECT i FROM n")
+                .await
+                .unwrap();
+
+            // More values than SQLite binds in one statement (SQLITE_MAX_VARIABLE_NUMBER)
+            let ids: Vec<SqlValue> = (1..=33_000).map(SqlValue::I64).collect();
+            let select = QueryBuilder::select().columns(&["id"]).from("items");
+            let placeholders = vec!["?"; ids.len()].join(", ");
+            let unchunked = format!("SELECT id FROM items WHERE id IN ({placeholders})");
+            let err = conn.fetch_all_as::<(i64,)>(&unchunked, &ids).await.unwrap_err();
+            assert!(err.to_string().contains("too many SQL variables"), "{err}");
+
+            let result = conn.fetch_in_chunks(&platform, &select, "id", &ids).await.unwrap();
+            assert_eq!(result.rows.len(), 33_000);
+            assert_eq!(result.rows[1000].get_at(0), Some(&SqlValue::I64(1001)));
+            assert!(conn.fetch_in_chunks(&platform, &select, "id", &[]).await.unwrap().rows.is_empty());
+
+            // ORDER BY and LIMIT would only apply per chunk
+            let limited = select.clone().order_by_desc("id").limit(10);
+            assert!(conn.fetch_in_chunks(&platform, &limited, "id", &ids).await.is_err());
+            let result = conn.fetch_in_chunks(&platform, &limited, "id", &ids[..999]).await.unwrap();
+            assert_eq!(result.rows[0].get_at(0), Some(&SqlValue::I64(999)));
+
+            let delete = QueryBuilder::delete().from("items");
+            assert_eq!(conn.execute_in_chunks(&platform, &delete, "id", &ids).await.unwrap(), 33_000);
+            assert_eq!(conn.execute_in_chunks(&platform, &delete, "id", &[]).await.unwrap(), 0);
+        }
+
+        #[tokio::test]
+        async fn test_transaction_status_shared_with_driver() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+
+            // A transaction begun on the driver connection is joined
+            conn.inner().begin_transaction().await.unwrap();
+            assert!(conn.is_transaction_active());
+            conn.begin_transaction().await.unwrap();
+            assert_eq!(conn.transaction_nesting_level(), 1);
+            conn.commit().await.unwrap();
+            assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+
+            // Ending it on the driver connection ends every nesting level
+            conn.begin_transaction().await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.inner().rollback().await.unwrap();
+            assert_eq!(conn.transaction_nesting_level(), 0);
+            assert!(matches!(
+                conn.commit().await,
+                Err(Error::Transaction(TransactionError::NoActiveTransaction))
+            ));
+            conn.begin_transaction().await.unwrap();
+            assert_eq!(conn.transaction_status(), TransactionStatus::Active);
+            conn.rollback().await.unwrap();
+        }
+
+        /// `SQLite` statement checking explicitly typed integer parameters
+        /// as they are bound, like drivers with typed binds
+        struct TypedStatement(crate::driver::SqliteStatement);
+
+        #[async_trait::async_trait]
+        impl crate::driver::DriverStatement for TypedStatement {
+            type Result = crate::driver::SqliteResult;
+
+            fn bind(&mut self, position: usize, value: SqlValue) -> Result<()> {
+                self.0.bind(position, value)
+            }
+
+            fn bind_named(&mut self, name: &str, value: SqlValue) -> Result<()> {
+                self.0.bind_named(name, value)
+            }
+
+            fn bind_typed(&mut self, position: usize, value: SqlValue, param_type: ParameterType) -> Result<()> {
+                if param_type == ParameterType::Integer && !matches!(value, SqlValue::I64(_)) {
+                    return Err(QueryError::InvalidParameter {
+                        name: position.to_string(),
+                        message: format!("expected {param_type}"),
+                    }
+                    .into());
+                }
+                self.0.bind(position, value)
+            }
+
+            async fn execute(&self) -> Result<Self::Result> {
+                self.0.execute().await
+            }
+
+            async fn execute_update(&self) -> Result<u64> {
+                self.0.execute_update().await
+            }
+
+            fn sql(&self) -> &str {
+                self.0.sql()
+            }
+        }
+
+        /// `SQLite` connection reporting its open transaction as failed on
+        /// demand, like `PostgreSQL` after an error, or not tracking
+        /// transactions at all
+        struct FailingConnection {
+            inner: crate::driver::SqliteConnection,
+            failed: std::sync::atomic::AtomicBool,
+            untracked: std::sync::atomic::AtomicBool,
+        }
+
+        #[async_trait::async_trait]
+        impl DriverConnection for FailingConnection {
+            type Statement = TypedStatement;
+            type Result = crate::driver::SqliteResult;
+
+            async fn prepare(&self, sql: &str) -> Result<Self::Statement> {
+                self.inner.prepare(sql).await.map(TypedStatement)
+            }
+
+            async fn query(&self, sql: &str) -> Result<Self::Result> {
+                self.inner.query(sql).await
+            }
+
+            async fn execute(&self, sql: &str) -> Result<u64> {
+                self.inner.execute(sql).await
+            }
+
+            async fn begin_transaction(&self) -> Result<()> {
+                self.inner.begin_transaction().await
+            }
+
+            async fn commit(&self) -> Result<()> {
+                self.inner.commit().await
+            }
+
+            async fn rollback(&self) -> Result<()> {
+                self.inner.rollback().await
+            }
+
+            fn transaction_status(&self) -> TransactionStatus {
+                if self.untracked.load(Ordering::SeqCst) {
+                    return TransactionStatus::Unknown;
+                }
+                match self.inner.transaction_status() {
+                    TransactionStatus::Active if self.failed.load(Ordering::SeqCst) => TransactionStatus::Failed,
+                    status => status,
+                }
+            }
+
+            async fn is_alive(&self) -> bool {
+                self.inner.is_alive().await
+            }
+
+            async fn server_version(&self) -> Result<String> {
+                self.inner.server_version().await
+            }
+        }
+
+        struct FailingDriver;
+
+        #[async_trait::async_trait]
+        impl Driver for FailingDriver {
+            type Connection = FailingConnection;
+
+            async fn connect(&self, params: &ConnectionParams) -> Result<Self::Connection> {
+                Ok(FailingConnection {
+                    inner: SqliteDriver::new().connect(params).await?,
+                    failed: std::sync::atomic::AtomicBool::new(false),
+                    untracked: std::sync::atomic::AtomicBool::new(false),
+                })
+            }
+
+            fn name(&self) -> &'static str {
+                "sqlite"
+            }
+        }
+
+        #[tokio::test]
+        async fn test_failed_transaction() {
+            let conn = Connection::new(&FailingDriver, &ConnectionParams::sqlite_memory()).await.unwrap();
+            conn.execute("CREATE TABLE t (id INTEGER)").await.unwrap();
+            let count = || async { conn.fetch_all_as::<(i64,)>("SELECT COUNT(*) FROM t", &[]).await.unwrap()[0].0 };
+
+            // Committing a failed transaction rolls it back
+            conn.begin_transaction().await.unwrap();
+            conn.execute("INSERT INTO t VALUES (1)").await.unwrap();
+            conn.inner().failed.store(true, Ordering::SeqCst);
+            assert_eq!(conn.transaction_status(), TransactionStatus::Failed);
+            assert!(matches!(conn.commit().await, Err(Error::Transaction(TransactionError::Aborted))));
+            assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+            assert_eq!(conn.transaction_nesting_level(), 0);
+            assert_eq!(count().await, 0);
+
+            // A failed transaction begun on the driver connection is replaced
+            conn.inner().begin_transaction().await.unwrap();
+            conn.execute("INSERT INTO t VALUES (2)").await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.inner().failed.store(false, Ordering::SeqCst);
+            assert_eq!(conn.transaction_status(), TransactionStatus::Active);
+            conn.execute("INSERT INTO t VALUES (3)").await.unwrap();
+            conn.commit().await.unwrap();
+            assert_eq!(conn.fetch_all_as::<(i64,)>("SELECT id FROM t", &[]).await.unwrap(), [(3,)]);
+        }
+
+        #[tokio::test]
+        async fn test_untracked_transaction_status() {
+            let conn = Connection::new(&FailingDriver, &ConnectionParams::sqlite_memory()).await.unwrap();
+            conn.inner().untracked.store(true, Ordering::SeqCst);
+
+            // The nesting level is kept, not reset by an unknown driver status
+            assert_eq!(conn.transaction_status(), TransactionStatus::Idle);
+            conn.begin_transaction().await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            assert_eq!(conn.transaction_nesting_level(), 2);
+            assert_eq!(conn.transaction_status(), TransactionStatus::Active);
+            conn.commit().await.unwrap();
+            conn.commit().await.unwrap();
+            assert!(!conn.is_transaction_active());
+            assert!(!conn.inner().inner.transaction_status().in_transaction());
+        }
+
+        #[tokio::test]
+        async fn test_transactional_restarts_on_retryable_errors() {
+            let configuration = Configuration::new().with_transaction_retries(2);
+            let conn = Connection::<SqliteDriver>::from_url_with_configuration("sqlite::memory:", configuration)
+                .await
+                .unwrap();
+            conn.execute("CREATE TABLE t (id INTEGER)").await.unwrap();
+
+            let attempts = AtomicU32::new(0);
+            let inserted = conn
+                .transactional(|| Box::pin(async {
+                    let rows = conn.execute("INSERT INTO t VALUES (1)").await?;
+                    if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                        return Err(Error::Query(QueryError::Deadlock));
+                    }
+                    Ok(rows)
+                }))
+                .await
+                .unwrap();
+            assert_eq!((inserted, attempts.load(Ordering::SeqCst)), (1, 3));
+            let mut result = conn.query("SELECT COUNT(*) FROM t").await.unwrap();
+            assert_eq!(result.all_rows().unwrap(), [vec![SqlValue::I64(1)]]);
+
+            // Other errors and nested transactions are not restarted
+            attempts.store(0, Ordering::SeqCst);
+            conn.begin_transaction().await.unwrap();
+            let failed: Result<()> = conn
+                .transactional(|| Box::pin(async {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Err(Error::Query(QueryError::Deadlock))
+                }))
+                .await;
+            assert!(failed.unwrap_err().is_retryable());
+            assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+            conn.rollback().await.unwrap();
+
+            // A failed commit is rolled back instead of leaving the transaction open
+            let conn = Connection::<SqliteDriver>::from_url_with_configuration(
+                "sqlite::memory:",
+                Configuration::new().with_nested_transaction_policy(NestedTransactionPolicy::Ignore),
+            )
+            .await
+            .unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.rollback().await.unwrap();
+            let committed = conn.in_transaction(Ok::<_, Error>(())).await;
+            assert!(matches!(committed, Err(Error::Transaction(TransactionError::RollbackOnly))));
+            assert!(!conn.is_transaction_active());
+        }
+
+        #[tokio::test]
+        async fn test_values_table() {
+            use crate::query::{Expr, QueryBuilder};
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let platform = crate::platform::SqlitePlatform::new();
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+            conn.execute("INSERT INTO users VALUES (1, 'Alice'), (2, 'Bob'), (3, 'Carol')").await.unwrap();
+
+            let keys = vec![vec![SqlValue::I64(3), "c".into()], vec![SqlValue::I64(1), "a".into()]];
+            let query = QueryBuilder::select()
+                .select_only(&["k.code", "users.name"])
+                .values_table(keys, "k", &["id", "code"])
+                .inner_join("users", Expr::raw("users.id = k.id"))
+                .order_by_asc("code");
+            let mut result = conn.query(&query.to_sql(&platform)).await.unwrap();
+            let rows = result.all_rows().unwrap();
+            assert_eq!(rows, [
+                vec![SqlValue::String("a".to_string()), SqlValue::String("Alice".to_string())],
+                vec![SqlValue::String("c".to_string()), SqlValue::String("Carol".to_string())],
+            ]);
+
+            let empty = QueryBuilder::select().all().values_table(Vec::new(), "k", &["id"]);
+            let mut result = conn.query(&empty.to_sql(&platform)).await.unwrap();
+            assert!(result.all_rows().unwrap().is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_auto_commit() {
+            let platform = crate::platform::SqlitePlatform::new();
+            let configuration = Configuration::new().with_auto_commit(false);
+            let conn = Connection::<SqliteDriver>::from_url_with_configuration("sqlite::memory:", configuration)
+                .await
+                .unwrap();
+            assert!(!conn.is_auto_commit());
+            assert_eq!(conn.transaction_nesting_level(), 1);
+
+            conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY)").await.unwrap();
+            conn.commit().await.unwrap();
+            assert_eq!(conn.transaction_nesting_level(), 1);
+
+            // Explicit transactions nest inside the implicit one
+            conn.begin_transaction().await.unwrap();
+            conn.execute("INSERT INTO test (id) VALUES (1)").await.unwrap();
+            conn.commit().await.unwrap();
+            conn.rollback().await.unwrap();
+            assert_eq!(conn.transaction_nesting_level(), 1);
+
+            conn.execute("INSERT INTO test (id) VALUES (2)").await.unwrap();
+            conn.set_auto_commit(&platform, true).await.unwrap();
+            assert_eq!(conn.transaction_nesting_level(), 0);
+
+            let mut result = conn.query("SELECT id FROM test").await.unwrap();
+            assert_eq!(result.all_rows().unwrap(), vec![vec![SqlValue::I64(2)]]);
+
+            conn.set_auto_commit(&platform, false).await.unwrap();
+            conn.close().await.unwrap();
+            assert_eq!(conn.transaction_nesting_level(), 0);
+        }
+
+        #[tokio::test]
+        async fn test_transactional_commit() {
+            let driver = SqliteDriver::new();
+            let params = ConnectionParams::sqlite_memory();
+            let conn = Connection::new(&driver, &params).await.unwrap();
+
+            conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)")
+                .await
+                .unwrap();
+
+            // Use begin/commit pattern
+            conn.begin_transaction().await.unwrap();
+            conn.execute("INSERT INTO test (id, name) VALUES (1, 'Alice')")
+                .await
+                .unwrap();
+            conn.commit().await.unwrap();
+
+            assert!(!conn.is_transaction_active());
+
+            // Verify data committed
+            let mut result = conn.query("SELECT COUNT(*) FROM test").await.unwrap();
+            let rows = result.all_rows().unwrap();
+            assert_eq!(rows[0][0], SqlValue::I64(1));
+        }
+
+        #[tokio::test]
+        async fn test_transactional_rollback_on_error() {
+            let driver = SqliteDriver::new();
+            let params = ConnectionParams::sqlite_memory();
+            let conn = Connection::new(&driver, &params).await.unwrap();
+
+            conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)")
+                .await
+                .unwrap();
+
+            // Use begin_transaction + in_transaction pattern
+            conn.begin_transaction().await.unwrap();
+            conn.execute("INSERT INTO test (id, name) VALUES (1, 'Alice')")
+                .await
+                .unwrap();
+
+            // Simulate an error and use in_transaction to handle it
+            let simulated_result: std::result::Result<(), Error> =
+                Err(Error::driver_message("Simulated error"));
+            let result = conn.in_transaction(simulated_result).await;
+
+            assert!(result.is_err());
+            assert!(!conn.is_transaction_active());
+
+            // Verify data rolled back
+            let mut result = conn.query("SELECT COUNT(*) FROM test").await.unwrap();
+            let rows = result.all_rows().unwrap();
+            assert_eq!(rows[0][0], SqlValue::I64(0));
+        }
+
+        #[tokio::test]
+        async fn test_rollback_only() {
+            let driver = SqliteDriver::new();
+            let params = ConnectionParams::sqlite_memory();
+            let conn = Connection::new(&driver, &params).await.unwrap();
+
+            conn.begin_transaction().await.unwrap();
+            conn.set_rollback_only();
+
+            assert!(conn.is_rollback_only());
+
+            // Commit should fail
+            let result = conn.commit().await;
+            assert!(matches!(
+                result,
+                Err(Error::Transaction(TransactionError::RollbackOnly))
             ));
 
             // Rollback should work
@@ -648,6 +2742,747 @@ mod tests {
             ));
         }
 
+        #[derive(Debug, Default)]
+        struct RecordingLogger {
+            queries: std::sync::Mutex<Vec<String>>,
+            slow_queries: std::sync::Mutex<Vec<String>>,
+            warnings: std::sync::Mutex<Vec<String>>,
+        }
+
+        impl QueryLogger for RecordingLogger {
+            fn log_query(&self, entry: &QueryLogEntry<'_>) {
+                self.queries.lock().unwrap().push(entry.sql.to_string());
+            }
+
+            fn log_slow_query(&self, entry: &QueryLogEntry<'_>) {
+                self.slow_queries.lock().unwrap().push(entry.sql.to_string());
+            }
+
+            fn log_warning(&self, sql: &str, _message: &str) {
+                self.warnings.lock().unwrap().push(sql.to_string());
+            }
+        }
+
+        #[tokio::test]
+        async fn test_fetch_all_as_tuples() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            conn.execute("CREATE TABLE users (id INTEGER, name TEXT, active BOOLEAN)")
+                .await
+                .unwrap();
+            conn.execute("INSERT INTO users VALUES (1, 'Alice', 1), (2, 'Bob', NULL), (3, 'Eve', 0)")
+                .await
+                .unwrap();
+
+            let users: Vec<(i64, String, Option<bool>)> = conn
+                .fetch_all_as("SELECT id, name, active FROM users WHERE id < ? ORDER BY id", &[SqlValue::I64(3)])
+                .await
+                .unwrap();
+            assert_eq!(users, vec![(1, "Alice".to_string(), Some(true)), (2, "Bob".to_string(), None)]);
+
+            let err = conn.fetch_all_as::<(i64,)>("SELECT id, name FROM users", &[]).await;
+            assert!(err.is_err());
+        }
+
+        #[tokio::test]
+        async fn test_non_finite_float_policy() {
+            use crate::core::NonFiniteFloatPolicy;
+
+            // Passed through, SQLite doesn't keep NaN
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let nan = [SqlValue::F64(f64::NAN)];
+            let values: Vec<(Option<f64>,)> = conn.fetch_all_as("SELECT ?", &nan).await.unwrap();
+            assert!(!values[0].0.is_some_and(f64::is_nan));
+
+            let policy = |policy| Configuration::new().with_non_finite_float_policy(policy);
+            let conn = conn.with_configuration(policy(NonFiniteFloatPolicy::Reject));
+            let err = conn.fetch_all_as::<(Option<f64>,)>("SELECT ?", &nan).await.unwrap_err();
+            assert!(err.to_string().contains("NaN is not a finite number"), "{err}");
+            let values: Vec<(f64,)> = conn.fetch_all_as("SELECT ?", &[SqlValue::F64(0.5)]).await.unwrap();
+            assert_eq!(values, vec![(0.5,)]);
+
+            let conn = conn.with_configuration(policy(NonFiniteFloatPolicy::Clamp));
+            let values: Vec<(f64,)> =
+                conn.fetch_all_as("SELECT ?", &[SqlValue::F64(f64::NEG_INFINITY)]).await.unwrap();
+            assert_eq!(values, vec![(f64::MIN,)]);
+
+            // Prepared statements apply the policy as values are bound
+            let mut stmt = conn.prepare("SELECT ?").await.unwrap();
+            stmt.bind(0, SqlValue::F64(f64::NEG_INFINITY)).unwrap();
+            assert_eq!(stmt.execute().await.unwrap().all_rows().unwrap(), [[SqlValue::F64(f64::MIN)]]);
+
+            let conn = conn.with_configuration(policy(NonFiniteFloatPolicy::Reject));
+            let mut stmt = conn.prepare("SELECT ?").await.unwrap();
+            let err = stmt.bind(0, SqlValue::F64(f64::NAN)).unwrap_err();
+            assert!(err.to_string().contains("NaN is not a finite number"), "{err}");
+            let err = stmt.bind_named("value", SqlValue::F64(f64::INFINITY)).unwrap_err();
+            assert!(err.to_string().contains("is not a finite number"), "{err}");
+        }
+
+        #[tokio::test]
+        async fn test_fetch_all_as_strict_numeric_conversions() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            conn.execute("CREATE TABLE readings (id INTEGER, value REAL)").await.unwrap();
+            conn.execute("INSERT INTO readings VALUES (9007199254740993, 0.1)").await.unwrap();
+
+            let lossy: Vec<(f64, f32)> = conn.fetch_all_as("SELECT id, value FROM readings", &[]).await.unwrap();
+            assert_eq!(lossy[0].1, 0.1);
+
+            let conn = conn.with_configuration(Configuration::new().with_strict_numeric_conversions(true));
+            assert!(conn.fetch_all_as::<(i64, f32)>("SELECT id, value FROM readings", &[]).await.is_err());
+            assert!(conn.fetch_all_as::<(f64, f64)>("SELECT id, value FROM readings", &[]).await.is_err());
+            let exact: Vec<(i64, f64)> = conn.fetch_all_as("SELECT id, value FROM readings", &[]).await.unwrap();
+            assert_eq!(exact, vec![(9_007_199_254_740_993, 0.1)]);
+        }
+
+        #[tokio::test]
+        async fn test_max_rows() {
+            use crate::query::QueryBuilder;
+
+            let logger = Arc::new(RecordingLogger::default());
+            let platform = crate::platform::SqlitePlatform::new();
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(Configuration::new().with_logging(true).with_max_rows(Some(2)))
+                .with_logger(logger.clone());
+            conn.execute("CREATE TABLE users (id INTEGER)").await.unwrap();
+            conn.execute("INSERT INTO users VALUES (1), (2), (3)").await.unwrap();
+
+            let query = QueryBuilder::select().columns(&["id"]).from("users");
+            assert_eq!(conn.fetch(&platform, &query).await.unwrap().rows.len(), 2);
+            assert_eq!(logger.queries.lock().unwrap()[2], "SELECT \"id\" FROM \"users\" LIMIT 2");
+            assert_eq!(conn.fetch(&platform, &query.limit(1)).await.unwrap().rows.len(), 1);
+            assert!(logger.warnings.lock().unwrap().is_empty());
+
+            let ids: Vec<(i64,)> = conn.fetch_all_as("SELECT id FROM users ORDER BY id", &[]).await.unwrap();
+            assert_eq!(ids, [(1,), (2,)]);
+            assert_eq!(*logger.warnings.lock().unwrap(), ["SELECT id FROM users ORDER BY id"]);
+            let ids: Vec<(i64,)> = conn.fetch_all_as("SELECT id FROM users WHERE id > 1", &[]).await.unwrap();
+            assert_eq!(ids.len(), 2);
+            assert_eq!(logger.warnings.lock().unwrap().len(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_query_multiple_result_sets() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            conn.execute("CREATE TABLE test (id INTEGER)").await.unwrap();
+
+            let sets = conn
+                .query_multiple("INSERT INTO test VALUES (1), (2); SELECT id FROM test; SELECT 'x' AS y")
+                .await
+                .unwrap();
+            assert_eq!(sets.len(), 3);
+            assert_eq!(sets[0].rows_affected, 2);
+            assert_eq!(sets[1].rows.len(), 2);
+            assert_eq!(sets[2].rows[0].get::<String>("y").unwrap(), "x");
+        }
+
+        #[tokio::test]
+        async fn test_from_url() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            conn.execute("CREATE TABLE test (id INTEGER)").await.unwrap();
+            assert!(conn.is_alive().await);
+
+            let err = Connection::<SqliteDriver>::from_url("postgres://localhost/db")
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::Configuration(_)));
+        }
+
+        #[tokio::test]
+        async fn test_from_params_with_configuration() {
+            let configuration = Configuration::new().with_slow_query_threshold(Duration::ZERO);
+            let conn = Connection::<SqliteDriver>::from_params_with_configuration(
+                &ConnectionParams::sqlite_memory(),
+                configuration,
+            )
+            .await
+            .unwrap();
+            assert_eq!(conn.configuration().slow_query_threshold, Some(Duration::ZERO));
+        }
+
+        #[tokio::test]
+        async fn test_configure_session_without_time_zone_support() {
+            use crate::core::TimezonePolicy;
+            use crate::platform::SqlitePlatform;
+
+            let driver = SqliteDriver::new();
+            let params = ConnectionParams::sqlite_memory();
+            let conn = Connection::new(&driver, &params)
+                .await
+                .unwrap()
+                .with_configuration(
+                    Configuration::new().with_timezone_policy(TimezonePolicy::StoreUtc),
+                );
+
+            // SQLite has no session timezone, nothing is executed
+            conn.configure_session(&SqlitePlatform::new()).await.unwrap();
+            assert!(conn.is_alive().await);
+        }
+
+        #[cfg(feature = "chrono")]
+        #[tokio::test]
+        async fn test_timezone_policy_applied() {
+            use crate::core::{FromSql, ToSql};
+            use chrono::{NaiveDate, NaiveDateTime};
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(
+                    Configuration::new().with_timezone_policy(TimezonePolicy::StoreUtc),
+                );
+            conn.execute("CREATE TABLE events (at TEXT)").await.unwrap();
+
+            let local = NaiveDate::from_ymd_opt(2024, 7, 1)
+                .and_then(|d| d.and_hms_opt(8, 30, 0))
+                .unwrap();
+            let mut stmt = conn.prepare("INSERT INTO events VALUES (?)").await.unwrap();
+            stmt.bind(0, local.to_sql().unwrap()).unwrap();
+            stmt.execute_update().await.unwrap();
+
+            // Stored in UTC, read back as local time
+            let mut result = conn.query("SELECT at FROM events").await.unwrap();
+            let stored = result.all_rows().unwrap().remove(0).remove(0);
+            assert_eq!(
+                NaiveDateTime::from_sql(stored).unwrap(),
+                crate::core::local_to_utc(local).naive_utc()
+            );
+            let rows: Vec<(NaiveDateTime,)> = conn
+                .fetch_all_as("SELECT at FROM events", &[])
+                .await
+                .unwrap();
+            assert_eq!(rows, vec![(local,)]);
+        }
+
+        #[tokio::test]
+        async fn test_set_local() {
+            /// Keeps session settings in a table, like MySQL without transaction-local settings
+            struct SettingsPlatform;
+
+            impl Platform for SettingsPlatform {
+                fn name(&self) -> &'static str {
+                    "sqlite"
+                }
+
+                fn set_session_setting_sql(&self, name: &str, value: Option<&str>) -> Option<String> {
+                    Some(format!(
+                        "INSERT OR REPLACE INTO settings VALUES ({}, {})",
+                        self.quote_string(name),
+                        value.map_or_else(|| "NULL".to_string(), |value| self.quote_string(value))
+                    ))
+                }
+
+                fn reset_session_setting_sql(&self, name: &str) -> Option<String> {
+                    Some(format!("DELETE FROM settings WHERE name = {}", self.quote_string(name)))
+                }
+
+                fn get_session_setting_sql(&self, name: &str) -> Option<String> {
+                    Some(format!("SELECT value FROM settings WHERE name = {}", self.quote_string(name)))
+                }
+            }
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            conn.execute("CREATE TABLE settings (name TEXT PRIMARY KEY, value TEXT)").await.unwrap();
+            conn.execute("INSERT INTO settings VALUES ('lock_timeout', '1')").await.unwrap();
+            let timeout = || async {
+                let value: Vec<(String,)> = conn.fetch_all_as("SELECT value FROM settings", &[]).await.unwrap();
+                value[0].0.clone()
+            };
+            let platform = SettingsPlatform;
+
+            assert!(matches!(
+                conn.set_local(&platform, "lock_timeout", "5").await,
+                Err(Error::Transaction(TransactionError::NoActiveTransaction))
+            ));
+            conn.begin_transaction().await.unwrap();
+            assert!(matches!(conn.set_local(&platform, "x; DROP", "5").await, Err(Error::Configuration(_))));
+            assert!(conn.set_local(&crate::platform::SqlitePlatform::new(), "lock_timeout", "5").await.is_err());
+
+            // Settings of a committed nested transaction last until the outermost one ends
+            conn.set_local(&platform, "lock_timeout", "5").await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.set_local(&platform, "lock_timeout", "7").await.unwrap();
+            conn.commit().await.unwrap();
+            assert_eq!(timeout().await, "7");
+            conn.commit().await.unwrap();
+            assert_eq!(timeout().await, "1");
+
+            // NULL comes back as NULL, a setting without a value is reset
+            conn.execute("UPDATE settings SET value = NULL").await.unwrap();
+            conn.begin_transaction().await.unwrap();
+            conn.set_local(&platform, "lock_timeout", "5").await.unwrap();
+            conn.set_local(&platform, "deadlock_timeout", "9").await.unwrap();
+            conn.rollback().await.unwrap();
+            let settings: Vec<(String, Option<String>)> =
+                conn.fetch_all_as("SELECT name, value FROM settings", &[]).await.unwrap();
+            assert_eq!(settings, vec![("lock_timeout".to_string(), None)]);
+        }
+
+        #[tokio::test]
+        async fn test_with_session_context() {
+            /// Keeps session variables in a table, so restoring them is visible
+            struct TablePlatform;
+
+            impl Platform for TablePlatform {
+                fn name(&self) -> &'static str {
+                    "sqlite"
+                }
+
+                fn set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+                    Some(format!(
+                        "INSERT OR REPLACE INTO vars VALUES ({}, {})",
+                        self.quote_string(name),
+                        self.quote_string(value)
+                    ))
+                }
+
+                fn get_session_variable_sql(&self, name: &str) -> Option<String> {
+                    Some(format!("SELECT value FROM vars WHERE name = {}", self.quote_string(name)))
+                }
+            }
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            conn.execute("CREATE TABLE vars (name TEXT PRIMARY KEY, value TEXT)").await.unwrap();
+            let user = || async {
+                conn.query("SELECT value FROM vars").await.unwrap().next_row().unwrap().map(|row| row[0].clone())
+            };
+            let platform = TablePlatform;
+
+            let nested = conn
+                .with_session_context(&platform, &[("app.user_id", "1")], Box::pin(async {
+                    let inner = conn
+                        .with_session_context(&platform, &[("app.user_id", "2")], Box::pin(async {
+                            assert_eq!(conn.transaction_nesting_level(), 2);
+                            Ok(user().await)
+                        }))
+                        .await?;
+                    assert_eq!(inner, Some(SqlValue::String("2".to_string())));
+                    let failed = conn
+                        .with_session_context(&platform, &[("app.user_id", "3")], Box::pin(async {
+                            Err::<(), _>(Error::config("fail"))
+                        }))
+                        .await;
+                    assert!(failed.is_err());
+                    Ok(user().await)
+                }))
+                .await
+                .unwrap();
+
+            // Both nested contexts restored the outer value
+            assert_eq!(nested, Some(SqlValue::String("1".to_string())));
+            assert_eq!(conn.transaction_nesting_level(), 0);
+
+            // Platforms without session variables only get the transaction
+            let count = conn
+                .with_session_context(&crate::platform::SqlitePlatform::new(), &[("app.user_id", "4")], Box::pin(async {
+                    conn.execute("DELETE FROM vars").await
+                }))
+                .await
+                .unwrap();
+            assert_eq!(count, 1);
+        }
+
+        #[tokio::test]
+        async fn test_detect_platform() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let registry = PlatformRegistry::with_defaults();
+            let platform = conn.detect_platform("sqlite", &registry).await.unwrap();
+            assert_eq!(platform.name(), "sqlite");
+            assert!(matches!(conn.detect_platform("oracle", &registry).await, Err(Error::Configuration(_))));
+        }
+
+        #[tokio::test]
+        async fn test_current_transaction_id() {
+            use crate::platform::SqlitePlatform;
+
+            /// Reports a fixed transaction id
+            struct TxidPlatform;
+
+            impl Platform for TxidPlatform {
+                fn name(&self) -> &'static str {
+                    "sqlite"
+                }
+
+                fn current_transaction_id_sql(&self) -> Option<String> {
+                    Some("SELECT 42".to_string())
+                }
+            }
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            assert!(matches!(
+                conn.current_transaction_id(&TxidPlatform).await,
+                Err(Error::Transaction(TransactionError::NoActiveTransaction))
+            ));
+
+            conn.begin_transaction().await.unwrap();
+            assert_eq!(conn.current_transaction_id(&TxidPlatform).await.unwrap().as_deref(), Some("42"));
+            assert!(matches!(
+                conn.current_transaction_id(&SqlitePlatform::new()).await,
+                Err(Error::Platform(_))
+            ));
+            conn.rollback().await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_use_tenant() {
+            use crate::platform::SqlitePlatform;
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let platform = SqlitePlatform::new();
+
+            conn.use_tenant(&platform, &TenantStrategy::table_prefix("acme_")).await.unwrap();
+            assert!(matches!(
+                conn.use_tenant(&platform, &TenantStrategy::schema("acme")).await,
+                Err(Error::Platform(_))
+            ));
+            assert!(matches!(
+                conn.use_tenant(&platform, &TenantStrategy::database("acme.db")).await,
+                Err(Error::Platform(_))
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_execute_audited() {
+            use crate::connection::AuditLogger;
+            use crate::platform::SqlitePlatform;
+            use crate::query::QueryBuilder;
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let platform = SqlitePlatform::new();
+            let audit = AuditLogger::new("audit_log").with_user("alice");
+            conn.execute(&platform.get_create_table_sql(&audit.table_definition())).await.unwrap();
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+
+            let insert = QueryBuilder::insert().into("users").insert_columns(&["id", "name"]).values(vec![1.into(), "Alice".into()]);
+            let update = QueryBuilder::update().table("users").set("name", "Bob").where_eq("id", 1);
+            let missing = QueryBuilder::delete().from("users").where_eq("id", 2);
+            for query in [&insert, &update, &missing] {
+                conn.execute_audited(query, &platform, &audit).await.unwrap();
+            }
+            assert_eq!(conn.transaction_nesting_level(), 0);
+
+            let rows = conn
+                .fetch_all_as::<(String, String, Option<String>, Option<String>, Option<String>)>(
+                    "SELECT operation, table_name, primary_key, changed_columns, user_context FROM audit_log",
+                    &[],
+                )
+                .await
+                .unwrap();
+            let user = Some("alice".to_string());
+            assert_eq!(
+                rows,
+                [
+                    ("INSERT".to_string(), "users".to_string(), Some("1".to_string()), Some("id,name".to_string()), user.clone()),
+                    ("UPDATE".to_string(), "users".to_string(), Some("1".to_string()), Some("name".to_string()), user),
+                ]
+            );
+
+            // A failing change leaves no audit row behind
+            assert!(conn.execute_audited(&insert, &platform, &audit).await.is_err());
+            let count = conn.fetch_all_as::<(i64,)>("SELECT COUNT(*) FROM audit_log", &[]).await.unwrap();
+            assert_eq!(count, [(2,)]);
+        }
+
+        #[tokio::test]
+        async fn test_execute_returning_emulation() {
+            use crate::platform::{AnsiPlatform, SqlitePlatform};
+            use crate::query::{Expr, QueryBuilder};
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, active INTEGER DEFAULT 1)")
+                .await
+                .unwrap();
+
+            // ANSI SQL has no RETURNING, SQLite does: both give the same rows
+            for platform in [&AnsiPlatform::new() as &dyn Platform, &SqlitePlatform::new()] {
+                conn.execute("DELETE FROM users").await.unwrap();
+                let rows = |set: ResultSet| set.rows.into_iter().map(|row| row.values().to_vec()).collect::<Vec<_>>();
+
+                let insert = QueryBuilder::insert()
+                    .into("users")
+                    .insert_columns(&["id", "name"])
+                    .values(vec![1.into(), "Alice".into()])
+                    .returning(&["id", "active"]);
+                let inserted = conn.execute_returning(&insert, platform, "id").await.unwrap();
+                assert_eq!(inserted.rows_affected, 1);
+                assert_eq!(rows(inserted), [[SqlValue::I64(1), SqlValue::I64(1)]]);
+
+                conn.execute("INSERT INTO users (id, name) VALUES (2, 'Bob'), (3, 'Carol')").await.unwrap();
+                let update = QueryBuilder::update()
+                    .table("users")
+                    .set("active", 0)
+                    .where_expr(Expr::col("id").ge(2))
+                    .returning(&["name", "active"]);
+                let updated = conn.execute_returning(&update, platform, "id").await.unwrap();
+                assert_eq!(updated.rows_affected, 2);
+                let mut updated = rows(updated);
+                updated.sort_by_key(|row| row[0].to_string());
+                assert_eq!(
+                    updated,
+                    [
+                        [SqlValue::String("Bob".into()), SqlValue::I64(0)],
+                        [SqlValue::String("Carol".into()), SqlValue::I64(0)]
+                    ]
+                );
+
+                let delete = QueryBuilder::delete().from("users").where_eq("active", 1).returning(&["name"]);
+                let deleted = conn.execute_returning(&delete, platform, "id").await.unwrap();
+                assert_eq!(deleted.rows_affected, 1);
+                assert_eq!(rows(deleted), [[SqlValue::String("Alice".into())]]);
+            }
+
+            // Keys generated by the database need the platform's last insert id
+            let insert = QueryBuilder::insert().into("users").insert_columns(&["name"]).values(vec!["Dave".into()]);
+            let result = conn.execute_returning(&insert.returning(&["id"]), &AnsiPlatform::new(), "id").await;
+            assert!(matches!(result, Err(Error::Platform(_))));
+            assert_eq!(conn.transaction_nesting_level(), 0);
+            assert_eq!(conn.fetch_all_as::<(i64,)>("SELECT COUNT(*) FROM users", &[]).await.unwrap(), [(2,)]);
+        }
+
+        #[tokio::test]
+        async fn test_insert_id_generators() {
+            use crate::connection::IdGenerator;
+            use crate::core::FromSql;
+            use crate::platform::{AnsiPlatform, SqlitePlatform};
+
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let platform = SqlitePlatform::new();
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+            conn.execute("CREATE TABLE events (id TEXT PRIMARY KEY, kind TEXT)").await.unwrap();
+
+            let serial = IdGenerator::Serial;
+            let first = conn.insert(&platform, "users", "id", &serial, &[("name", "Alice".into())]).await.unwrap();
+            let second = conn.insert(&platform, "users", "id", &serial, &[("name", "Bob".into())]).await.unwrap();
+            assert_eq!((first.as_i64(), second.as_i64()), (Some(1), Some(2)));
+
+            let v7 = IdGenerator::UuidV7;
+            let a = conn.insert(&platform, "events", "id", &v7, &[("kind", "login".into())]).await.unwrap();
+            let b = conn.insert(&platform, "events", "id", &v7, &[("kind", "logout".into())]).await.unwrap();
+            let (a, b) = (uuid::Uuid::from_sql(a).unwrap(), uuid::Uuid::from_sql(b).unwrap());
+            assert!(a < b);
+            let v4 = conn.insert(&platform, "events", "id", &IdGenerator::UuidV4, &[]).await.unwrap();
+            let v4 = uuid::Uuid::from_sql(v4).unwrap();
+            let rows = conn.fetch_all_as::<(String,)>("SELECT id FROM events ORDER BY kind", &[]).await.unwrap();
+            assert_eq!(rows, [(v4.to_string(),), (a.to_string(),), (b.to_string(),)]);
+
+            // SQLite has no sequences, ANSI SQL no way to read serial keys back
+            let sequence = IdGenerator::sequence("users_id_seq", 10);
+            for (platform, ids) in [(&platform as &dyn Platform, &sequence), (&AnsiPlatform::new(), &serial)] {
+                assert!(matches!(conn.insert(platform, "users", "id", ids, &[]).await, Err(Error::Platform(_))));
+            }
+        }
+
+        #[tokio::test]
+        async fn test_slow_query_logging() {
+            let driver = SqliteDriver::new();
+            let params = ConnectionParams::sqlite_memory();
+            let logger = Arc::new(RecordingLogger::default());
+            let conn = Connection::new(&driver, &params)
+                .await
+                .unwrap()
+                .with_configuration(
+                    Configuration::new().with_slow_query_threshold(Duration::ZERO),
+                )
+                .with_logger(logger.clone());
+
+            conn.execute("CREATE TABLE test (id INTEGER)").await.unwrap();
+            conn.query("SELECT * FROM test").await.unwrap();
+
+            assert_eq!(
+                *logger.slow_queries.lock().unwrap(),
+                vec!["CREATE TABLE test (id INTEGER)", "SELECT * FROM test"]
+            );
+            assert!(logger.queries.lock().unwrap().is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_sql_rewriters() {
+            let logger = Arc::new(RecordingLogger::default());
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(Configuration::new().with_logging(true))
+                .with_logger(logger.clone())
+                .with_rewriter(Arc::new(|sql: &mut String, _: &mut Vec<SqlValue>| {
+                    if sql.starts_with("DROP") {
+                        return Err(Error::config("DROP is not allowed"));
+                    }
+                    Ok(())
+                }))
+                .with_rewriter(Arc::new(|sql: &mut String, params: &mut Vec<SqlValue>| {
+                    *sql = sql.replace("people", "users");
+                    params.iter_mut().for_each(|p| *p = SqlValue::I64(p.as_i64().unwrap_or(0) * 10));
+                    sql.push_str(" /* trace_id=42 */");
+                    Ok(())
+                }));
+
+            conn.execute("CREATE TABLE people (id INTEGER)").await.unwrap();
+            conn.execute("INSERT INTO people VALUES (1)").await.unwrap();
+            let ids: Vec<(i64,)> = conn.fetch_all_as("SELECT id FROM people WHERE id < ?", &[SqlValue::I64(1)]).await.unwrap();
+            assert_eq!(ids, [(1,)]);
+            assert_eq!(
+                logger.queries.lock().unwrap()[2],
+                "SELECT id FROM users WHERE id < ? /* trace_id=42 */"
+            );
+            assert!(conn.execute("DROP TABLE people").await.is_err());
+            let count: Vec<(i64,)> = conn.fetch_all_as("SELECT COUNT(*) FROM people", &[]).await.unwrap();
+            assert_eq!(count, [(1,)]);
+
+            // Prepared statements are rewritten with their parameters
+            let mut stmt = conn.prepare("SELECT id FROM people WHERE id < ?").await.unwrap();
+            assert_eq!(stmt.sql(), "SELECT id FROM users WHERE id < ? /* trace_id=42 */");
+            stmt.bind(0, SqlValue::I64(1)).unwrap();
+            assert_eq!(stmt.execute().await.unwrap().all_rows().unwrap(), [[SqlValue::I64(1)]]);
+            let mut stmt = conn.prepare("SELECT id FROM people WHERE id < ? AND id > ?").await.unwrap();
+            stmt.bind(1, SqlValue::I64(0)).unwrap();
+            assert!(matches!(stmt.execute().await, Err(Error::Query(QueryError::MissingParameter(_)))));
+
+            let conn = conn.with_rewriter(Arc::new(|_: &mut String, params: &mut Vec<SqlValue>| {
+                params.push(SqlValue::I64(1));
+                Ok(())
+            }));
+            assert!(matches!(conn.query("SELECT 1").await, Err(Error::Configuration(_))));
+        }
+
+        #[tokio::test]
+        async fn test_query_logging_disabled_by_default() {
+            let driver = SqliteDriver::new();
+            let params = ConnectionParams::sqlite_memory();
+            let logger = Arc::new(RecordingLogger::default());
+            let conn = Connection::new(&driver, &params)
+                .await
+                .unwrap()
+                .with_logger(logger.clone());
+
+            conn.query("SELECT 1").await.unwrap();
+            assert!(logger.queries.lock().unwrap().is_empty());
+            assert!(logger.slow_queries.lock().unwrap().is_empty());
+
+            let conn = conn.with_configuration(Configuration::new().with_logging(true));
+            conn.query("SELECT 2").await.unwrap();
+            assert_eq!(*logger.queries.lock().unwrap(), vec!["SELECT 2"]);
+        }
+
+        #[tokio::test]
+        async fn test_password_statements_redacted() {
+            use crate::platform::PostgresPlatform;
+
+            let logger = Arc::new(RecordingLogger::default());
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(Configuration::new().with_logging(true))
+                .with_logger(logger.clone());
+
+            let sql = PostgresPlatform.get_create_user_sql("app", "hunter2").unwrap();
+            let error = conn.execute(&sql).await.unwrap_err();
+            assert!(!error.to_string().contains("hunter2"), "{error}");
+            assert_eq!(*logger.queries.lock().unwrap(), vec!["CREATE ROLE \"app\" LOGIN PASSWORD '***'"]);
+        }
+
+        #[tokio::test]
+        async fn test_redaction_policy() {
+            let logger = Arc::new(RecordingLogger::default());
+            let config = Configuration::new()
+                .with_logging(true)
+                .with_redaction_policy(ValueRedactionPolicy::sensitive_columns().with_sql_literals(true));
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(config)
+                .with_logger(logger.clone());
+
+            conn.execute("CREATE TABLE users (name TEXT, password TEXT)").await.unwrap();
+            conn.execute("INSERT INTO users VALUES ('alice', 'hunter2')").await.unwrap();
+            assert_eq!(logger.queries.lock().unwrap()[1], "INSERT INTO users VALUES ('***', '***')");
+
+            let Err(Error::Query(QueryError::ExecutionFailed { message, sql, .. })) =
+                conn.execute("INSERT INTO missing VALUES ('hunter2')").await
+            else {
+                unreachable!("expected an execution error");
+            };
+            assert!(!message.contains("hunter2"));
+            assert_eq!(sql.as_deref(), Some("INSERT INTO missing VALUES ('***')"));
+        }
+
+        #[tokio::test]
+        async fn test_error_parameter_summary() {
+            let config = Configuration::new()
+                .with_redaction_policy(ValueRedactionPolicy::new().with_parameter_type(ParameterType::Binary));
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:")
+                .await
+                .unwrap()
+                .with_configuration(config);
+            conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, token BLOB)").await.unwrap();
+
+            let params = [SqlValue::String("one".to_string()), SqlValue::Bytes(vec![1, 2])];
+            let Err(Error::Query(error)) = conn
+                .fetch_all_as::<(i64,)>("INSERT INTO users (id, token) VALUES (?, ?) RETURNING id", &params)
+                .await
+            else {
+                unreachable!("expected a query error");
+            };
+            assert_eq!(error.params(), Some("#1 STRING='one', #2 BINARY=***"));
+            assert!(error.to_string().ends_with("(parameters: #1 STRING='one', #2 BINARY=***)"));
+
+            // Prepared statements list parameters with the type they were bound as
+            let mut stmt = conn.prepare("INSERT INTO users (id, token) VALUES (?, :token)").await.unwrap();
+            stmt.bind_typed(0, SqlValue::String("one".to_string()), ParameterType::Integer).unwrap();
+            stmt.bind_named("token", SqlValue::Bytes(vec![1, 2])).unwrap();
+            let Err(Error::Query(error)) = stmt.execute_update().await else {
+                unreachable!("expected a query error");
+            };
+            assert_eq!(error.params(), Some("#1 INTEGER='one', :token BINARY=***"));
+        }
+
+        #[tokio::test]
+        async fn test_bind_error_parameter_summary() {
+            let config = Configuration::new()
+                .with_redaction_policy(ValueRedactionPolicy::new().with_parameter_type(ParameterType::Binary));
+            let conn = Connection::new(&FailingDriver, &ConnectionParams::sqlite_memory())
+                .await
+                .unwrap()
+                .with_configuration(config);
+
+            let mut stmt = conn.prepare("SELECT ?, ?").await.unwrap();
+            stmt.bind(0, SqlValue::Bytes(vec![1, 2])).unwrap();
+            let error = stmt.bind_typed(1, SqlValue::String("two".to_string()), ParameterType::Integer).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "Query error: Invalid parameter '1': expected INTEGER (parameters: #1 BINARY=***, #2 INTEGER='two')"
+            );
+
+            // The failed value isn't kept
+            let Err(Error::Query(error)) = stmt.execute().await else {
+                unreachable!("expected a query error");
+            };
+            assert!(matches!(error, QueryError::MissingParameter(_)));
+            stmt.bind_typed(1, SqlValue::I64(2), ParameterType::Integer).unwrap();
+            assert_eq!(stmt.execute().await.unwrap().all_rows().unwrap(), [[SqlValue::Bytes(vec![1, 2]), SqlValue::I64(2)]]);
+        }
+
+        #[tokio::test]
+        async fn test_error_sql_truncation() {
+            let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+            let sql = format!("INSERT INTO missing VALUES {}", vec!["(1)"; 1000].join(", "));
+
+            let Err(Error::Query(error)) = conn.execute(&sql).await else {
+                unreachable!("expected a query error");
+            };
+            assert!(error.sql().unwrap().ends_with("... (4025 more characters)"));
+            assert_eq!(error.full_sql(), error.sql());
+
+            let conn = conn.with_configuration(
+                Configuration::new().with_error_sql_max_length(Some(20)).with_full_error_sql(true),
+            );
+            let Err(Error::Query(error)) = conn.execute(&sql).await else {
+                unreachable!("expected a query error");
+            };
+            assert!(error.sql().unwrap().starts_with("INSERT INTO missing ..."));
+            assert_eq!(error.full_sql(), Some(sql.as_str()));
+        }
+
         #[tokio::test]
         async fn test_connection_close() {
             let driver = SqliteDriver::new();
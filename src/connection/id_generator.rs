@@ -0,0 +1,127 @@
+//! Primary key generation strategies for [`Connection::insert`](super::Connection::insert)
+
+use std::ops::Range;
+use std::sync::{Mutex, PoisonError};
+
+/// How [`Connection::insert`](super::Connection::insert) obtains the primary
+/// key of a new row
+///
+/// Pick one strategy per table and use it for every insert; the same
+/// strategy works on all platforms that support it.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let orders = IdGenerator::sequence("orders_id_seq", 50);
+/// let id = conn.insert(&platform, "orders", "id", &orders, &[("total", 12.into())]).await?;
+///
+/// let id = conn.insert(&platform, "events", "id", &IdGenerator::UuidV7, &[("kind", "login".into())]).await?;
+/// ```
+#[derive(Debug, Default)]
+pub enum IdGenerator {
+    /// Key generated by the database (auto-increment, identity or serial
+    /// column), read back with RETURNING or
+    /// [`Platform::last_insert_id_sql`](crate::platform::Platform::last_insert_id_sql)
+    #[default]
+    Serial,
+    /// Key taken from a database sequence, see [`IdGenerator::sequence`]
+    Sequence(SequenceAllocator),
+    /// Random UUID generated by the application
+    #[cfg(feature = "uuid")]
+    UuidV4,
+    /// Time-ordered UUID generated by the application, keeps index inserts
+    /// local like a serial key
+    #[cfg(feature = "uuid")]
+    UuidV7,
+}
+
+impl IdGenerator {
+    /// Create a generator taking keys from a sequence
+    ///
+    /// See [`SequenceAllocator`] for how `allocation_size` is used.
+    #[must_use]
+    pub fn sequence(name: impl Into<String>, allocation_size: u32) -> Self {
+        Self::Sequence(SequenceAllocator::new(name, allocation_size))
+    }
+
+    /// Check if the key is known before the row is inserted
+    #[must_use]
+    pub const fn is_pre_generated(&self) -> bool {
+        !matches!(self, Self::Serial)
+    }
+}
+
+/// Hands out keys from a sequence in blocks of `allocation_size`
+///
+/// Each value fetched from the sequence reserves the keys
+/// `value..value + allocation_size`, so the sequence must be created with
+/// `INCREMENT BY allocation_size`. Keys of the current block are handed out
+/// without a round trip; keys left in a block when the application stops
+/// are skipped. With an allocation size of 1 every key is fetched.
+#[derive(Debug)]
+pub struct SequenceAllocator {
+    /// Name of the sequence
+    name: String,
+    /// Number of keys reserved per fetched sequence value
+    allocation_size: u32,
+    /// Reserved keys not handed out yet
+    block: Mutex<Range<i64>>,
+}
+
+impl SequenceAllocator {
+    /// Create an allocator for a sequence, an allocation size of 0 counts as 1
+    #[must_use]
+    pub fn new(name: impl Into<String>, allocation_size: u32) -> Self {
+        Self {
+            name: name.into(),
+            allocation_size: allocation_size.max(1),
+            block: Mutex::new(0..0),
+        }
+    }
+
+    /// Get the name of the sequence
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the number of keys reserved per fetched sequence value
+    #[must_use]
+    pub const fn allocation_size(&self) -> u32 {
+        self.allocation_size
+    }
+
+    /// Take the next key of the current block, `None` once it is used up
+    pub(crate) fn next_reserved(&self) -> Option<i64> {
+        self.block.lock().unwrap_or_else(PoisonError::into_inner).next()
+    }
+
+    /// Start a new block at a value fetched from the sequence and take its first key
+    pub(crate) fn reserve(&self, first: i64) -> i64 {
+        let end = first.saturating_add(i64::from(self.allocation_size));
+        *self.block.lock().unwrap_or_else(PoisonError::into_inner) = first.saturating_add(1)..end;
+        first
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_blocks() {
+        let allocator = SequenceAllocator::new("orders_id_seq", 3);
+        assert_eq!(allocator.next_reserved(), None);
+        assert_eq!(allocator.reserve(1), 1);
+        assert_eq!(allocator.next_reserved(), Some(2));
+        assert_eq!(allocator.next_reserved(), Some(3));
+        assert_eq!(allocator.next_reserved(), None);
+        assert_eq!(allocator.reserve(4), 4);
+        assert_eq!(allocator.next_reserved(), Some(5));
+
+        let single = SequenceAllocator::new("users_id_seq", 0);
+        assert_eq!((single.allocation_size(), single.reserve(9), single.next_reserved()), (1, 9, None));
+        assert!(!IdGenerator::Serial.is_pre_generated());
+        assert!(IdGenerator::sequence("s", 10).is_pre_generated());
+    }
+}
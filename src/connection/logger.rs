@@ -0,0 +1,178 @@
+//! Query logging and slow query reporting
+
+use std::time::Duration;
+
+use crate::core::SqlValue;
+
+/// Maximum length of a single parameter in the parameter summary
+const MAX_PARAM_DISPLAY_LEN: usize = 32;
+
+/// A single executed query as seen by a [`QueryLogger`]
+#[derive(Debug, Clone)]
+pub struct QueryLogEntry<'a> {
+    /// The executed SQL
+    pub sql: &'a str,
+    /// Parameters bound to the query
+    pub params: &'a [SqlValue],
+    /// Wall-clock execution time
+    pub duration: Duration,
+    /// Query plan captured for slow queries (if enabled and supported)
+    pub explain: Option<String>,
+    /// Whether parameter values may be logged
+    ///
+    /// Set from `Configuration::log_parameter_values`; when `false`, only
+    /// the parameter types are shown.
+    pub include_values: bool,
+}
+
+impl QueryLogEntry<'_> {
+    /// Compact, single-line summary of the bound parameters
+    ///
+    /// Each parameter is rendered as `#index type`, or as `#index type=value`
+    /// when [`include_values`](Self::include_values) is set. Long values are
+    /// truncated so that large blobs or texts don't flood the log.
+    #[must_use]
+    pub fn parameter_summary(&self) -> String {
+        let parts: Vec<String> = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                if self.include_values {
+                    format!(
+                        "#{} {}={}",
+                        i + 1,
+                        value.param_type(),
+                        truncate(&value.to_string(), MAX_PARAM_DISPLAY_LEN)
+                    )
+                } else {
+                    format!("#{} {}", i + 1, value.param_type())
+                }
+            })
+            .collect();
+        format!("[{}]", parts.join(", "))
+    }
+}
+
+/// Receiver for query log events emitted by [`Connection`](super::Connection)
+///
+/// Implement this trait to forward queries to a custom logging or metrics
+/// backend. `log_query` is only called when `Configuration::enable_logging`
+/// is set, `log_slow_query` whenever a query exceeds
+/// `Configuration::slow_query_threshold` and `log_warning` for queries
+/// flagged by a lint such as `SelectStarPolicy::Warn`.
+pub trait QueryLogger: Send + Sync + std::fmt::Debug {
+    /// Called for every executed query when logging is enabled
+    fn log_query(&self, entry: &QueryLogEntry<'_>);
+
+    /// Called for queries exceeding the slow query threshold
+    fn log_slow_query(&self, entry: &QueryLogEntry<'_>) {
+        self.log_query(entry);
+    }
+
+    /// Called for queries flagged by a lint, before they are executed
+    fn log_warning(&self, sql: &str, message: &str) {
+        let _ = (sql, message);
+    }
+}
+
+/// Default logger writing to `tracing` (no-op without the `tracing` feature)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TracingQueryLogger;
+
+impl QueryLogger for TracingQueryLogger {
+    fn log_query(&self, entry: &QueryLogEntry<'_>) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            sql = entry.sql,
+            params = %entry.parameter_summary(),
+            duration_ms = entry.duration.as_millis(),
+            "Query executed"
+        );
+        #[cfg(not(feature = "tracing"))]
+        let _ = entry;
+    }
+
+    fn log_slow_query(&self, entry: &QueryLogEntry<'_>) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            sql = entry.sql,
+            params = %entry.parameter_summary(),
+            duration_ms = entry.duration.as_millis(),
+            explain = entry.explain.as_deref().unwrap_or(""),
+            "Slow query detected"
+        );
+        #[cfg(not(feature = "tracing"))]
+        let _ = entry;
+    }
+
+    fn log_warning(&self, sql: &str, message: &str) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(sql, "{message}");
+        #[cfg(not(feature = "tracing"))]
+        let _ = (sql, message);
+    }
+}
+
+/// Truncate a string to at most `max` characters, appending an ellipsis
+fn truncate(value: &str, max: usize) -> String {
+    if value.chars().count() <= max {
+        value.to_string()
+    } else {
+        let mut truncated: String = value.chars().take(max).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_summary() {
+        let params = vec![SqlValue::I64(42), SqlValue::String("Alice".to_string()), SqlValue::Null];
+        let entry = QueryLogEntry {
+            sql: "SELECT 1",
+            params: &params,
+            duration: Duration::from_millis(5),
+            explain: None,
+            include_values: true,
+        };
+
+        assert_eq!(
+            entry.parameter_summary(),
+            "[#1 INTEGER=42, #2 STRING='Alice', #3 NULL=NULL]"
+        );
+    }
+
+    #[test]
+    fn test_parameter_summary_hides_values_by_default() {
+        let params = vec![SqlValue::String("s3cr3t".to_string())];
+        let entry = QueryLogEntry {
+            sql: "UPDATE users SET password = ?",
+            params: &params,
+            duration: Duration::ZERO,
+            explain: None,
+            include_values: false,
+        };
+
+        assert_eq!(entry.parameter_summary(), "[#1 STRING]");
+    }
+
+    #[test]
+    fn test_parameter_summary_truncates_long_values() {
+        let params = vec![SqlValue::String("x".repeat(100))];
+        let entry = QueryLogEntry {
+            sql: "INSERT INTO t VALUES (?)",
+            params: &params,
+            duration: Duration::ZERO,
+            explain: None,
+            include_values: true,
+        };
+
+        let summary = entry.parameter_summary();
+        assert!(summary.len() < 60);
+        assert!(summary.contains('…'));
+    }
+}
@@ -7,11 +7,36 @@
 //! - Transaction nesting via savepoints
 //! - Automatic rollback on drop
 //! - Transactional closure API
+//! - Test transactions that are always rolled back
 //! - Isolation level management
+//! - Query logging and slow query detection
+//! - SQL rewriting hooks applied before execution
+//! - Audit log of data changes (requires `query-builder` feature)
+//! - Primary key generation for inserts (requires `query-builder` feature)
+//! - Connection manager for `bb8` and `deadpool` pools, with lifecycle events
+//!   (requires `pool` feature)
 
+#[cfg(feature = "query-builder")]
+mod audit;
 #[allow(clippy::module_inception)]
 mod connection;
+#[cfg(feature = "query-builder")]
+mod id_generator;
+mod logger;
+#[cfg(feature = "pool")]
+mod pool;
+mod rewriter;
+mod statement;
 mod transaction;
 
+#[cfg(feature = "query-builder")]
+pub use audit::{AuditEntry, AuditLogger};
 pub use connection::Connection;
-pub use transaction::TransactionGuard;
+#[cfg(feature = "query-builder")]
+pub use id_generator::{IdGenerator, SequenceAllocator};
+pub use logger::{QueryLogEntry, QueryLogger, TracingQueryLogger};
+#[cfg(feature = "pool")]
+pub use pool::{ConnectionManager, PoolEvent, PoolEventListener, TracingPoolEventListener};
+pub use rewriter::SqlRewriter;
+pub use statement::Statement;
+pub use transaction::{TestTransaction, TransactionGuard};
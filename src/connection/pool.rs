@@ -0,0 +1,455 @@
+//! Connection manager for external connection pools
+//!
+//! [`ConnectionManager`] creates and validates [`Connection`]s. With the
+//! `bb8` feature it implements `bb8::ManageConnection`, with the `deadpool`
+//! feature `deadpool::managed::Manager`:
+//!
+//! ```rust,ignore
+//! use rustine_dbal::connection::ConnectionManager;
+//! use rustine_dbal::prelude::*;
+//!
+//! let manager = ConnectionManager::new(SqliteDriver::new(), ConnectionParams::from_env()?)
+//!     .with_configuration(Configuration::from_env()?);
+//!
+//! // bb8
+//! let pool = bb8::Pool::builder()
+//!     .max_size(manager.max_size().unwrap_or(10))
+//!     .build(manager)
+//!     .await?;
+//!
+//! // deadpool
+//! let pool = deadpool::managed::Pool::builder(manager).max_size(16).build()?;
+//! ```
+//!
+//! The manager reports connection lifecycle [`PoolEvent`]s with timing data
+//! to a [`PoolEventListener`], by default to `tracing`. Pools only call the
+//! manager at some points of a connection's life, so not every event is
+//! seen with every pool:
+//!
+//! | Event | `bb8` | `deadpool` |
+//! |-------|-------|------------|
+//! | `Created` | new connection | new connection |
+//! | `Recycled`, `Acquired` | idle connection checked out (`test_on_check_out`) | idle connection checked out |
+//! | `Released` | connection returned | - |
+//! | `Closed` | returned broken, or failed validation | removed from the pool |
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+// `std::time::Instant::now()` panics on wasm32-unknown-unknown
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use crate::core::{Configuration, ConnectionError, ConnectionParams, Error, Result};
+use crate::driver::Driver;
+use crate::platform::{Platform, PlatformRegistry};
+
+use super::Connection;
+
+/// A connection lifecycle event reported by a [`ConnectionManager`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolEvent {
+    /// A new connection was opened and its session configured
+    Created {
+        /// Time taken to connect and configure the session
+        duration: Duration,
+    },
+    /// An idle connection passed validation for reuse
+    Recycled {
+        /// Time taken to roll back open transactions and ping the database
+        duration: Duration,
+    },
+    /// An idle connection was handed out after validation
+    Acquired {
+        /// Time the connection was idle since its release or creation
+        idle: Duration,
+    },
+    /// A connection was returned to the pool
+    Released {
+        /// Time the connection was checked out
+        held: Duration,
+    },
+    /// A connection was discarded by the pool
+    Closed {
+        /// Time since the connection was opened
+        lifetime: Duration,
+    },
+}
+
+impl PoolEvent {
+    /// Get the name of the event, e.g. `"acquired"`
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Created { .. } => "created",
+            Self::Recycled { .. } => "recycled",
+            Self::Acquired { .. } => "acquired",
+            Self::Released { .. } => "released",
+            Self::Closed { .. } => "closed",
+        }
+    }
+
+    /// Get the duration carried by the event
+    #[must_use]
+    pub const fn duration(&self) -> Duration {
+        match *self {
+            Self::Created { duration } | Self::Recycled { duration } => duration,
+            Self::Acquired { idle } => idle,
+            Self::Released { held } => held,
+            Self::Closed { lifetime } => lifetime,
+        }
+    }
+}
+
+impl fmt::Display for PoolEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} after {:?}", self.name(), self.duration())
+    }
+}
+
+/// Receiver for connection lifecycle events of a [`ConnectionManager`]
+///
+/// Implement this trait to watch pool saturation, e.g. by counting
+/// checked out connections or feeding hold times into a metrics backend.
+/// Events are reported synchronously from the pool's callbacks, so
+/// listeners should return quickly.
+pub trait PoolEventListener: Send + Sync + fmt::Debug {
+    /// Called for every lifecycle event
+    fn on_event(&self, event: &PoolEvent);
+}
+
+/// Default listener writing to `tracing` (no-op without the `tracing` feature)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TracingPoolEventListener;
+
+impl PoolEventListener for TracingPoolEventListener {
+    fn on_event(&self, event: &PoolEvent) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            event = event.name(),
+            duration_ms = event.duration().as_millis(),
+            "Pool connection event"
+        );
+        #[cfg(not(feature = "tracing"))]
+        let _ = event;
+    }
+}
+
+/// Times of a pooled connection, kept on the [`Connection`]
+#[derive(Debug, Clone, Copy)]
+pub struct PoolTiming {
+    /// When the connection was opened
+    opened: Instant,
+    /// When the connection was last checked out or returned
+    handed_over: Instant,
+    /// Whether `Closed` has been reported
+    closed: bool,
+}
+
+impl PoolTiming {
+    /// Start timing a newly opened connection
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self { opened: now, handed_over: now, closed: false }
+    }
+
+    /// Record a checkout or return, returning the time since the previous one
+    fn hand_over(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.handed_over);
+        self.handed_over = now;
+        elapsed
+    }
+}
+
+/// Creates, validates and resets pooled connections
+///
+/// New connections receive the configuration and get their session
+/// configured with the platform registered for the driver name (see
+/// [`Connection::configure_session`]). Connections returned with an open
+/// transaction are rolled back before they are reused.
+pub struct ConnectionManager<D: Driver> {
+    /// Driver creating the connections
+    driver: D,
+    /// Parameters for new connections
+    params: ConnectionParams,
+    /// Configuration for new connections
+    configuration: Configuration,
+    /// Platform used to configure the session
+    platform: Option<Arc<dyn Platform>>,
+    /// Receiver for connection lifecycle events
+    listener: Arc<dyn PoolEventListener>,
+}
+
+impl<D: Driver> fmt::Debug for ConnectionManager<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionManager")
+            .field("driver", &self.driver.name())
+            .field("params", &self.params)
+            .field("configuration", &self.configuration)
+            .field("platform", &self.platform.as_ref().map(|p| p.name()))
+            .field("listener", &self.listener)
+            .finish()
+    }
+}
+
+impl<D: Driver> ConnectionManager<D> {
+    /// Create a manager for the given driver and parameters
+    #[must_use]
+    pub fn new(driver: D, params: ConnectionParams) -> Self {
+        let platform = PlatformRegistry::with_defaults().get(&params.driver);
+        Self {
+            driver,
+            params,
+            configuration: Configuration::default(),
+            platform,
+            listener: Arc::new(TracingPoolEventListener),
+        }
+    }
+
+    /// Use the given configuration for new connections
+    #[must_use]
+    pub fn with_configuration(mut self, configuration: Configuration) -> Self {
+        self.configuration = configuration;
+        self
+    }
+
+    /// Configure sessions with the given platform instead of the registered one
+    #[must_use]
+    pub fn with_platform(mut self, platform: Arc<dyn Platform>) -> Self {
+        self.platform = Some(platform);
+        self
+    }
+
+    /// Report lifecycle events to a custom listener instead of `tracing`
+    #[must_use]
+    pub fn with_event_listener(mut self, listener: Arc<dyn PoolEventListener>) -> Self {
+        self.listener = listener;
+        self
+    }
+
+    /// Get the configured maximum pool size ([`Configuration::pool_max_size`])
+    #[must_use]
+    pub const fn max_size(&self) -> Option<u32> {
+        self.configuration.pool_max_size
+    }
+
+    /// Open and configure a new connection
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if connecting or configuring the session fails.
+    pub async fn connect(&self) -> Result<Connection<D>> {
+        let started = Instant::now();
+        let conn =
+            Connection::new_with_configuration(&self.driver, &self.params, self.configuration.clone())
+                .await?;
+        if let Some(platform) = &self.platform {
+            conn.configure_session(platform.as_ref()).await?;
+        }
+        *conn.pool_timing() = PoolTiming::new();
+        self.listener.on_event(&PoolEvent::Created { duration: started.elapsed() });
+        Ok(conn)
+    }
+
+    /// Check that a connection can be reused
+    ///
+    /// Rolls back transactions left open and pings the database. Reports
+    /// [`PoolEvent::Recycled`] and [`PoolEvent::Acquired`] when the
+    /// connection can be handed out, [`PoolEvent::Closed`] otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConnectionError::Lost`] if the connection is closed or no
+    /// longer alive, or the error of a failing rollback.
+    pub async fn check(&self, conn: &Connection<D>) -> Result<()> {
+        let started = Instant::now();
+        match Self::validate(conn).await {
+            Ok(()) => {
+                self.listener.on_event(&PoolEvent::Recycled { duration: started.elapsed() });
+                let idle = conn.pool_timing().hand_over();
+                self.listener.on_event(&PoolEvent::Acquired { idle });
+                Ok(())
+            }
+            Err(e) => {
+                self.close(conn);
+                Err(e)
+            }
+        }
+    }
+
+    /// Record that a connection was returned to the pool
+    ///
+    /// Reports [`PoolEvent::Released`], and [`PoolEvent::Closed`] if the
+    /// connection was closed meanwhile. Returns whether the connection can
+    /// be kept in the pool.
+    pub fn release(&self, conn: &Connection<D>) -> bool {
+        let held = conn.pool_timing().hand_over();
+        self.listener.on_event(&PoolEvent::Released { held });
+        if conn.is_closed() {
+            self.close(conn);
+            return false;
+        }
+        true
+    }
+
+    /// Record that a connection was discarded by the pool
+    ///
+    /// Reports [`PoolEvent::Closed`] once per connection.
+    pub fn close(&self, conn: &Connection<D>) {
+        let mut timing = conn.pool_timing();
+        if !timing.closed {
+            timing.closed = true;
+            let lifetime = timing.opened.elapsed();
+            drop(timing);
+            self.listener.on_event(&PoolEvent::Closed { lifetime });
+        }
+    }
+
+    /// Roll back open transactions and ping the database
+    async fn validate(conn: &Connection<D>) -> Result<()> {
+        if conn.is_closed() {
+            return Err(Error::Connection(ConnectionError::Lost));
+        }
+        // Without auto-commit the last rollback begins a fresh implicit transaction
+        for _ in 0..conn.transaction_nesting_level() {
+            conn.rollback().await?;
+        }
+        if !conn.is_alive().await {
+            return Err(Error::Connection(ConnectionError::Lost));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bb8")]
+impl<D: Driver + 'static> bb8::ManageConnection for ConnectionManager<D> {
+    type Connection = Connection<D>;
+    type Error = Error;
+
+    async fn connect(&self) -> Result<Self::Connection> {
+        Self::connect(self).await
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<()> {
+        self.check(conn).await
+    }
+
+    // bb8 calls this whenever a connection is returned
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        !self.release(conn)
+    }
+}
+
+#[cfg(feature = "deadpool")]
+impl<D: Driver> deadpool::managed::Manager for ConnectionManager<D> {
+    type Type = Connection<D>;
+    type Error = Error;
+
+    async fn create(&self) -> Result<Self::Type> {
+        self.connect().await
+    }
+
+    async fn recycle(
+        &self,
+        conn: &mut Self::Type,
+        _metrics: &deadpool::managed::Metrics,
+    ) -> deadpool::managed::RecycleResult<Self::Error> {
+        self.check(conn).await.map_err(deadpool::managed::RecycleError::Backend)
+    }
+
+    fn detach(&self, conn: &mut Self::Type) {
+        self.close(conn);
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::driver::SqliteDriver;
+
+    fn manager() -> ConnectionManager<SqliteDriver> {
+        ConnectionManager::new(SqliteDriver::new(), ConnectionParams::sqlite_memory())
+            .with_configuration(Configuration::new().with_pool_max_size(4))
+    }
+
+    #[tokio::test]
+    async fn test_connect_and_check() {
+        let manager = manager();
+        assert_eq!(manager.max_size(), Some(4));
+
+        let conn = manager.connect().await.unwrap();
+        conn.begin_transaction().await.unwrap();
+        conn.begin_transaction().await.unwrap();
+        manager.check(&conn).await.unwrap();
+        assert_eq!(conn.transaction_nesting_level(), 0);
+
+        conn.close().await.unwrap();
+        assert!(matches!(
+            manager.check(&conn).await,
+            Err(Error::Connection(ConnectionError::Lost))
+        ));
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingListener {
+        events: std::sync::Mutex<Vec<&'static str>>,
+    }
+
+    impl PoolEventListener for RecordingListener {
+        fn on_event(&self, event: &PoolEvent) {
+            self.events.lock().unwrap().push(event.name());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_events() {
+        let listener = Arc::new(RecordingListener::default());
+        let manager = manager().with_event_listener(listener.clone());
+
+        let conn = manager.connect().await.unwrap();
+        manager.check(&conn).await.unwrap();
+        assert!(manager.release(&conn));
+        conn.close().await.unwrap();
+        assert!(manager.check(&conn).await.is_err());
+        // Closed is reported once, however often the pool discards it
+        manager.close(&conn);
+
+        assert_eq!(
+            *listener.events.lock().unwrap(),
+            ["created", "recycled", "acquired", "released", "closed"]
+        );
+    }
+
+    #[test]
+    fn test_pool_event_display() {
+        let event = PoolEvent::Released { held: Duration::from_millis(5) };
+        assert_eq!(event.to_string(), "released after 5ms");
+        assert_eq!(event.duration(), Duration::from_millis(5));
+    }
+
+    #[cfg(feature = "bb8")]
+    #[tokio::test]
+    async fn test_bb8_pool() {
+        let pool = bb8::Pool::builder().max_size(2).build(manager()).await.unwrap();
+        let conn = pool.get().await.unwrap();
+        assert_eq!(conn.execute("CREATE TABLE t (id INTEGER)").await.unwrap(), 0);
+    }
+
+    #[cfg(feature = "deadpool")]
+    #[tokio::test]
+    async fn test_deadpool_pool() {
+        let pool: deadpool::managed::Pool<ConnectionManager<SqliteDriver>> =
+            deadpool::managed::Pool::builder(manager()).max_size(2).build().unwrap();
+        let conn = pool.get().await.unwrap();
+        conn.begin_transaction().await.unwrap();
+        drop(conn);
+
+        // The open transaction is rolled back when the connection is reused
+        let conn = pool.get().await.unwrap();
+        assert_eq!(conn.transaction_nesting_level(), 0);
+    }
+}
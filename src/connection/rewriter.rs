@@ -0,0 +1,42 @@
+//! Rewriting of SQL before execution
+
+use crate::core::{Result, SqlValue};
+
+/// Hook transforming statements before [`Connection`](super::Connection)
+/// executes them
+///
+/// Rewriters see the SQL of `query`, `execute`, `prepare` and
+/// `fetch_all_as` (and everything built on them) after the caller and
+/// before the driver, e.g. to append a `/* trace_id */` comment, route
+/// table names or cap LIMITs. Transaction control statements are not
+/// rewritten. The logged SQL is the rewritten one.
+///
+/// Closures taking `(&mut String, &mut Vec<SqlValue>)` are rewriters:
+///
+/// ```rust,ignore
+/// let conn = conn.with_rewriter(Arc::new(|sql: &mut String, _: &mut Vec<SqlValue>| {
+///     sql.push_str(" /* trace_id=4bf92f35 */");
+///     Ok(())
+/// }));
+/// ```
+pub trait SqlRewriter: Send + Sync {
+    /// Rewrite a statement and its parameters in place
+    ///
+    /// `params` is empty for statements whose parameters are bound later
+    /// or not at all (`query`, `execute`, `prepare`); adding parameters to
+    /// those is an error.
+    ///
+    /// # Errors
+    ///
+    /// Return an error to reject the statement, it is then not executed.
+    fn rewrite(&self, sql: &mut String, params: &mut Vec<SqlValue>) -> Result<()>;
+}
+
+impl<F> SqlRewriter for F
+where
+    F: Fn(&mut String, &mut Vec<SqlValue>) -> Result<()> + Send + Sync,
+{
+    fn rewrite(&self, sql: &mut String, params: &mut Vec<SqlValue>) -> Result<()> {
+        self(sql, params)
+    }
+}
@@ -0,0 +1,271 @@
+//! Prepared statement of a connection
+
+use std::borrow::Cow;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+// `std::time::Instant::now()` panics on wasm32-unknown-unknown
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::Instant;
+
+use crate::core::{Error, ParameterType, QueryError, Result, SqlValue};
+use crate::driver::{Driver, DriverConnection, DriverStatement, Placeholder};
+
+use super::Connection;
+
+/// Driver statement type of a driver
+type InnerStatement<D> = <<D as Driver>::Connection as DriverConnection>::Statement;
+
+/// Result type of a driver statement
+type StatementResult<D> = <InnerStatement<D> as DriverStatement>::Result;
+
+/// Prepared statement
+///
+/// Created by [`Connection::prepare`]. Parameters go through the connection's
+/// [`NonFiniteFloatPolicy`](crate::core::NonFiniteFloatPolicy) and
+/// [`TimezonePolicy`](crate::core::TimezonePolicy) as they are bound, then are
+/// bound to the driver statement and kept, so the connection's SQL rewriters see the statement
+/// with its parameters when it is executed, like any other query. A rewriter
+/// changing the SQL or the positional parameters gets a statement prepared
+/// from its output; parameters bound by name are passed on unchanged.
+///
+/// Executions are logged like queries of the connection. Errors of binding
+/// and executing are sanitized like the connection's and carry a summary of
+/// the bound parameters (see [`ValueRedactionPolicy::summarize_labeled`]).
+///
+/// [`ValueRedactionPolicy::summarize_labeled`]: crate::core::ValueRedactionPolicy::summarize_labeled
+pub struct Statement<'c, D: Driver> {
+    /// Connection that prepared the statement
+    connection: &'c Connection<D>,
+    /// Driver statement of the SQL as written
+    inner: InnerStatement<D>,
+    /// SQL as written
+    sql: String,
+    /// Positional parameters bound so far, with their type if given
+    params: Vec<Option<(SqlValue, Option<ParameterType>)>>,
+    /// Parameters bound by name, with their type if given
+    named: Vec<(String, SqlValue, Option<ParameterType>)>,
+}
+
+impl<D: Driver> std::fmt::Debug for Statement<'_, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Statement")
+            .field("sql", &self.sql)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'c, D: Driver> Statement<'c, D> {
+    /// Wrap the driver statement of `sql`
+    pub(super) const fn new(connection: &'c Connection<D>, inner: InnerStatement<D>, sql: String) -> Self {
+        Self {
+            connection,
+            inner,
+            sql,
+            params: Vec::new(),
+            named: Vec::new(),
+        }
+    }
+
+    /// Bind a parameter by position (0-indexed)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position is invalid, the non-finite float
+    /// policy rejects the value or binding fails.
+    pub fn bind(&mut self, position: usize, value: SqlValue) -> Result<()> {
+        self.bind_position(position, value, None)
+    }
+
+    /// Bind a parameter by name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not found, the non-finite float policy
+    /// rejects the value or binding fails.
+    pub fn bind_named(&mut self, name: &str, value: SqlValue) -> Result<()> {
+        self.bind_name(name, value, None)
+    }
+
+    /// Bind a parameter by position with an explicit type
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position is invalid or binding fails.
+    pub fn bind_typed(&mut self, position: usize, value: SqlValue, param_type: ParameterType) -> Result<()> {
+        self.bind_position(position, value, Some(param_type))
+    }
+
+    /// Bind a parameter by name with an explicit type
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not found or binding fails.
+    pub fn bind_named_typed(&mut self, name: &str, value: SqlValue, param_type: ParameterType) -> Result<()> {
+        self.bind_name(name, value, Some(param_type))
+    }
+
+    /// Bind typed parameters by position, starting at 0
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if binding any of the parameters fails.
+    pub fn bind_all_typed(&mut self, params: Vec<(SqlValue, ParameterType)>) -> Result<()> {
+        params
+            .into_iter()
+            .enumerate()
+            .try_for_each(|(position, (value, param_type))| self.bind_typed(position, value, param_type))
+    }
+
+    /// Execute the statement and return results
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rewriter, preparing the rewritten statement or
+    /// executing fails, or the connection is closed.
+    pub async fn execute(&self) -> Result<StatementResult<D>> {
+        let (rewritten, params) = self.rewritten().await.map_err(|e| self.sanitize_error(e))?;
+        let statement = rewritten.as_ref().unwrap_or(&self.inner);
+        let started = Instant::now();
+        let result = statement.execute().await;
+        self.connection.log_query(statement.sql(), &params, started.elapsed()).await;
+        result.map_err(|e| self.sanitize_error(e))
+    }
+
+    /// Execute the statement and return affected rows
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a rewriter, preparing the rewritten statement or
+    /// executing fails, or the connection is closed.
+    pub async fn execute_update(&self) -> Result<u64> {
+        let (rewritten, params) = self.rewritten().await.map_err(|e| self.sanitize_error(e))?;
+        let statement = rewritten.as_ref().unwrap_or(&self.inner);
+        let started = Instant::now();
+        let result = statement.execute_update().await;
+        self.connection.log_query(statement.sql(), &params, started.elapsed()).await;
+        result.map_err(|e| self.sanitize_error(e))
+    }
+
+    /// Get the SQL for this statement
+    #[must_use]
+    pub fn sql(&self) -> &str {
+        self.inner.sql()
+    }
+
+    /// Get the driver statement
+    #[must_use]
+    pub const fn inner(&self) -> &InnerStatement<D> {
+        &self.inner
+    }
+
+    /// Sanitize an error of the statement and attach a summary of its parameters
+    ///
+    /// Values are shown as the connection's redaction policy allows.
+    pub(super) fn sanitize_error(&self, error: Error) -> Error {
+        let error = self.connection.sanitize_error(error);
+        if self.params.iter().all(Option::is_none) && self.named.is_empty() {
+            return error;
+        }
+        let positional = self.params.iter().enumerate().filter_map(|(position, param)| {
+            let (value, param_type) = param.as_ref()?;
+            Some((format!("#{}", position + 1), param_type.unwrap_or_else(|| value.param_type()), value))
+        });
+        let named = self
+            .named
+            .iter()
+            .map(|(name, value, param_type)| {
+                (Placeholder::Named(name.clone()).to_string(), param_type.unwrap_or_else(|| value.param_type()), value)
+            });
+        let summary = self.connection.configuration().redaction.summarize_labeled(positional.chain(named));
+        error.with_params(summary)
+    }
+
+    /// Apply the connection's non-finite float and timezone policies to a value
+    fn converted(&self, value: SqlValue) -> Result<SqlValue> {
+        let configuration = self.connection.configuration();
+        let value = configuration.non_finite_float_policy.apply(value)?;
+        Ok(configuration.timezone_policy.apply(value))
+    }
+
+    /// Bind a positional parameter to the driver statement and keep it
+    ///
+    /// A failed bind reports the parameter in the summary of the error, and
+    /// leaves the previously bound value in place.
+    fn bind_position(&mut self, position: usize, value: SqlValue, param_type: Option<ParameterType>) -> Result<()> {
+        let value = self.converted(value)?;
+        if self.params.len() <= position {
+            self.params.resize(position + 1, None);
+        }
+        let previous = self.params[position].replace((value.clone(), param_type));
+        let bound = match param_type {
+            Some(param_type) => self.inner.bind_typed(position, value, param_type),
+            None => self.inner.bind(position, value),
+        };
+        bound.map_err(|e| {
+            let error = self.sanitize_error(e);
+            self.params[position] = previous;
+            error
+        })
+    }
+
+    /// Bind a named parameter to the driver statement and keep it
+    ///
+    /// A failed bind reports the parameter in the summary of the error, and
+    /// leaves the previously bound value in place.
+    fn bind_name(&mut self, name: &str, value: SqlValue, param_type: Option<ParameterType>) -> Result<()> {
+        let value = self.converted(value)?;
+        let previous = self.named.iter().position(|(bound, ..)| bound == name).map(|index| self.named.remove(index));
+        self.named.push((name.to_string(), value.clone(), param_type));
+        let bound = match param_type {
+            Some(param_type) => self.inner.bind_named_typed(name, value, param_type),
+            None => self.inner.bind_named(name, value),
+        };
+        bound.map_err(|e| {
+            let error = self.sanitize_error(e);
+            self.named.pop();
+            self.named.extend(previous);
+            error
+        })
+    }
+
+    /// Apply the connection's rewriters to the statement and its positional parameters
+    ///
+    /// Returns a statement prepared and bound from their output, or `None`
+    /// if they changed nothing and the driver statement can run as is,
+    /// along with the positional parameters as executed.
+    async fn rewritten(&self) -> Result<(Option<InnerStatement<D>>, Vec<SqlValue>)> {
+        self.connection.ensure_not_closed()?;
+        let params = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(position, param)| match param {
+                Some((value, _)) => Ok(value.clone()),
+                None => Err(QueryError::MissingParameter(Placeholder::Positional(position).to_string()).into()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (sql, rewritten) = self.connection.rewrite(&self.sql, &params)?;
+        if let (Cow::Borrowed(_), Cow::Borrowed(_)) = (&sql, &rewritten) {
+            return Ok((None, params));
+        }
+        if sql == self.inner.sql() && *rewritten == *params {
+            return Ok((None, params));
+        }
+
+        let mut statement = self.connection.inner().prepare(&sql).await?;
+        for (position, value) in rewritten.iter().enumerate() {
+            match self.params.get(position).and_then(|param| param.as_ref()?.1) {
+                Some(param_type) => statement.bind_typed(position, value.clone(), param_type)?,
+                None => statement.bind(position, value.clone())?,
+            }
+        }
+        for (name, value, param_type) in &self.named {
+            match param_type {
+                Some(param_type) => statement.bind_named_typed(name, value.clone(), *param_type)?,
+                None => statement.bind_named(name, value.clone())?,
+            }
+        }
+        Ok((Some(statement), rewritten.into_owned()))
+    }
+}
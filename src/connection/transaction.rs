@@ -1,6 +1,12 @@
-//! Transaction guard for RAII-style transaction management
+//! Transaction guard for RAII-style transaction management, and test
+//! transactions that are always rolled back
 
-use crate::core::Result;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+
+use futures::FutureExt;
+
+use crate::core::{Error, Result, TransactionError};
 use crate::driver::Driver;
 
 use super::Connection;
@@ -105,8 +111,177 @@ impl<D: Driver> Drop for TransactionGuard<'_, D> {
     }
 }
 
+/// A transaction around a test that is always rolled back
+///
+/// Integration tests sharing one database run in a transaction that is
+/// rolled back at the end, so they leave no rows behind. Nested transactions
+/// the test begins and doesn't finish are rolled back as well.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// TestTransaction::wrap(&conn, async {
+///     conn.execute("INSERT INTO users (name) VALUES ('Alice')").await?;
+///     assert_eq!(count_users(&conn).await?, 1);
+///     Ok(())
+/// })
+/// .await?;
+/// // The user is gone again
+/// ```
+#[derive(Debug)]
+#[must_use = "the test transaction stays open until rolled back"]
+pub struct TestTransaction<'a, D: Driver> {
+    /// Connection running the test
+    connection: &'a Connection<D>,
+    /// Nesting level before the test transaction began
+    level: u32,
+}
+
+impl<'a, D: Driver> TestTransaction<'a, D> {
+    /// Begin a test transaction, a savepoint if a transaction is active
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be started.
+    pub async fn begin(connection: &'a Connection<D>) -> Result<Self> {
+        let level = connection.transaction_nesting_level();
+        connection.begin_transaction().await?;
+        Ok(Self { connection, level })
+    }
+
+    /// Run a test in a test transaction and roll it back
+    ///
+    /// The transaction is rolled back whether the test succeeds, fails or
+    /// panics; a panic is resumed after the rollback.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the test, or an error if beginning or rolling
+    /// back the transaction fails.
+    pub async fn wrap<T, F>(connection: &'a Connection<D>, test: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let transaction = Self::begin(connection).await?;
+        let outcome = AssertUnwindSafe(test).catch_unwind().await;
+        let rolled_back = transaction.rollback().await;
+        match outcome {
+            Ok(result) => {
+                let value = result?;
+                rolled_back.map(|()| value)
+            }
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+
+    /// Roll back the test transaction and every nested transaction still open
+    ///
+    /// # Errors
+    ///
+    /// Returns `TransactionError::RollbackFailed` if the test already ended
+    /// the test transaction (its changes may be committed), or an error if
+    /// a rollback fails.
+    pub async fn rollback(self) -> Result<()> {
+        if self.connection.transaction_nesting_level() <= self.level {
+            return Err(Error::Transaction(TransactionError::RollbackFailed(
+                "the test transaction was ended by the test, its changes may be committed".into(),
+            )));
+        }
+        while self.connection.transaction_nesting_level() > self.level {
+            match self.connection.rollback().await {
+                // Only marked rollback-only, the outermost rollback undoes it
+                Ok(()) | Err(Error::Transaction(TransactionError::SavepointsNotSupported)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // Most transaction tests are in connection.rs
-    // This module just tests guard-specific behavior
+    // This module just tests guard-specific and test transaction behavior
+
+    #[cfg(feature = "sqlite")]
+    mod sqlite_tests {
+        use super::super::*;
+        use crate::core::{ConnectionParams, SqlValue};
+        use crate::driver::sqlite::SqliteDriver;
+        use crate::driver::DriverResult;
+
+        async fn setup_connection() -> Connection<SqliteDriver> {
+            let conn = Connection::new(&SqliteDriver::new(), &ConnectionParams::sqlite_memory()).await.unwrap();
+            conn.execute("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)").await.unwrap();
+            conn
+        }
+
+        async fn count_notes(conn: &Connection<SqliteDriver>) -> Result<i64> {
+            let mut result = conn.query("SELECT COUNT(*) FROM notes").await?;
+            match result.all_rows()?[0][0] {
+                SqlValue::I64(count) => Ok(count),
+                ref other => panic!("unexpected count {other:?}"),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_wrap_rolls_back() {
+            let conn = setup_connection().await;
+
+            let seen = TestTransaction::wrap(&conn, async {
+                conn.execute("INSERT INTO notes (body) VALUES ('a')").await?;
+                // Nested transactions committed or left open are undone too
+                conn.begin_transaction().await?;
+                conn.execute("INSERT INTO notes (body) VALUES ('b')").await?;
+                conn.commit().await?;
+                conn.begin_transaction().await?;
+                conn.begin_transaction().await?;
+                conn.execute("INSERT INTO notes (body) VALUES ('c')").await?;
+                count_notes(&conn).await
+            })
+            .await
+            .unwrap();
+            assert_eq!(seen, 3);
+            assert_eq!(count_notes(&conn).await.unwrap(), 0);
+            assert_eq!(conn.transaction_nesting_level(), 0);
+
+            let failed: Result<()> = TestTransaction::wrap(&conn, async {
+                conn.execute("INSERT INTO notes (body) VALUES ('d')").await?;
+                conn.execute("INSERT INTO missing VALUES (1)").await?;
+                Ok(())
+            })
+            .await;
+            assert!(failed.is_err());
+            assert_eq!(count_notes(&conn).await.unwrap(), 0);
+        }
+
+        #[tokio::test]
+        async fn test_wrap_rolls_back_after_panic() {
+            let conn = setup_connection().await;
+            conn.begin_transaction().await.unwrap();
+            conn.execute("INSERT INTO notes (body) VALUES ('kept')").await.unwrap();
+
+            let panicked = AssertUnwindSafe(TestTransaction::wrap::<(), _>(&conn, async {
+                conn.execute("INSERT INTO notes (body) VALUES ('a')").await?;
+                panic!("assertion failed");
+            }))
+            .catch_unwind()
+            .await;
+            assert!(panicked.is_err());
+            // Only the savepoint of the test transaction was rolled back
+            assert_eq!(conn.transaction_nesting_level(), 1);
+            assert_eq!(count_notes(&conn).await.unwrap(), 1);
+            conn.rollback().await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_rollback_after_test_commit() {
+            let conn = setup_connection().await;
+            let transaction = TestTransaction::begin(&conn).await.unwrap();
+            conn.execute("INSERT INTO notes (body) VALUES ('a')").await.unwrap();
+            conn.commit().await.unwrap();
+            assert!(transaction.rollback().await.is_err());
+            assert_eq!(count_notes(&conn).await.unwrap(), 1);
+        }
+    }
 }
@@ -0,0 +1,271 @@
+//! Transparent compression of large text, binary and JSON values
+//!
+//! [`Compressed<T>`] stores its value as compressed bytes, e.g. big JSON
+//! payloads in a BLOB column. The algorithm is recognized by its magic
+//! bytes when reading, so gzip and zstd data can be mixed in a column and
+//! values written before compression was enabled are read as they are.
+//! The algorithms need the `gzip` and `zstd` features.
+//!
+//! Mark such columns with [`Column::compressed`](crate::platform::Column::compressed)
+//! so they are declared with a binary type.
+
+use alloc::{format, string::String, vec::Vec};
+use super::from_sql::value_type_name;
+use super::{Error, FromSql, Result, SqlValue, ToSql};
+
+/// Magic bytes starting gzip data
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Magic bytes starting a zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression algorithm for large values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Compression {
+    /// gzip (RFC 1952), needs the `gzip` feature
+    Gzip,
+    /// Zstandard, needs the `zstd` feature
+    Zstd,
+}
+
+/// zstd, or gzip if only the `gzip` feature is enabled; without either
+/// feature there is no algorithm to default to
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+impl Default for Compression {
+    fn default() -> Self {
+        Self::preferred()
+    }
+}
+
+impl Compression {
+    /// Get the enabled algorithm used unless another one is chosen
+    const fn preferred() -> Self {
+        if cfg!(all(feature = "gzip", not(feature = "zstd"))) {
+            Self::Gzip
+        } else {
+            Self::Zstd
+        }
+    }
+
+    /// Detect the algorithm of compressed data from its magic bytes
+    #[must_use]
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&ZSTD_MAGIC) {
+            Some(Self::Zstd)
+        } else if data.starts_with(&GZIP_MAGIC) {
+            Some(Self::Gzip)
+        } else {
+            None
+        }
+    }
+
+    /// Compress data
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the algorithm's feature is not
+    /// enabled, and a conversion error if compressing fails.
+    #[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+    pub fn compress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                use std::io::Write;
+
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data).and_then(|()| encoder.finish()).map_err(|e| failed("compress", &e))
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::encode_all(data, zstd::DEFAULT_COMPRESSION_LEVEL).map_err(|e| failed("compress", &e)),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.disabled()),
+        }
+    }
+
+    /// Decompress data
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if the algorithm's feature is not
+    /// enabled, and a conversion error if the data is corrupt.
+    #[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                use std::io::Read;
+
+                let mut decompressed = Vec::new();
+                flate2::read::GzDecoder::new(data)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|e| failed("decompress", &e))?;
+                Ok(decompressed)
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::decode_all(data).map_err(|e| failed("decompress", &e)),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.disabled()),
+        }
+    }
+
+    /// Get the error for an algorithm whose feature is not enabled
+    #[allow(dead_code)]
+    fn disabled(self) -> Error {
+        let feature = match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        };
+        Error::config(format!("{self:?} compression needs the `{feature}` feature"))
+    }
+}
+
+/// Get the error for failed (de)compression
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+fn failed(action: &str, error: &std::io::Error) -> Error {
+    Error::conversion("Bytes", "Compressed", format!("failed to {action}: {error}"))
+}
+
+/// A text, binary or JSON value stored compressed
+///
+/// Written as compressed bytes; read back from compressed bytes (any
+/// [`Compression`]) as well as from uncompressed text or bytes. NULL is
+/// written as NULL, use `Option<Compressed<T>>` to read it.
+///
+/// ```rust,ignore
+/// use rustine_dbal::core::Compressed;
+///
+/// conn.execute_params(
+///     "INSERT INTO events (payload) VALUES (?)",
+///     &[Compressed::new(payload_json).to_sql()?],
+/// ).await?;
+/// let payload: Compressed<serde_json::Value> = row.get("payload")?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compressed<T> {
+    /// The uncompressed value
+    pub value: T,
+    /// Algorithm used when writing the value
+    pub compression: Compression,
+}
+
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+impl<T: Default> Default for Compressed<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Compressed<T> {
+    /// Wrap a value to be compressed with the default algorithm
+    ///
+    /// Needs the `gzip` or `zstd` feature, there is no algorithm to
+    /// default to without them.
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            compression: Compression::default(),
+        }
+    }
+
+    /// Use the given algorithm when writing the value
+    #[must_use]
+    pub const fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Take the uncompressed value
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: ToSql> ToSql for Compressed<T> {
+    fn to_sql(&self) -> Result<SqlValue> {
+        let data = match self.value.to_sql()? {
+            SqlValue::Null => return Ok(SqlValue::Null),
+            SqlValue::String(s) => s.into_bytes(),
+            SqlValue::Bytes(b) => b,
+            #[cfg(feature = "json")]
+            SqlValue::Json(j) => alloc::string::ToString::to_string(&j).into_bytes(),
+            other => {
+                return Err(Error::conversion(
+                    value_type_name(&other),
+                    "Compressed",
+                    "only text, binary and JSON values can be compressed",
+                ))
+            }
+        };
+        Ok(SqlValue::Bytes(self.compression.compress(&data)?))
+    }
+}
+
+impl<T: FromSql> FromSql for Compressed<T> {
+    fn from_sql(value: SqlValue) -> Result<Self> {
+        let SqlValue::Bytes(data) = value else {
+            return T::from_sql(value).map(|value| Self {
+                value,
+                compression: Compression::preferred(),
+            });
+        };
+        let (data, compression) = match Compression::detect(&data) {
+            Some(compression) => (compression.decompress(&data)?, compression),
+            None => (data, Compression::preferred()),
+        };
+        // Text comes back as a string so that String and JSON targets work
+        let value = match String::from_utf8(data) {
+            Ok(text) => SqlValue::String(text),
+            Err(e) => SqlValue::Bytes(e.into_bytes()),
+        };
+        Ok(Self {
+            value: T::from_sql(value)?,
+            compression,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0]), Some(Compression::Zstd));
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 8]), Some(Compression::Gzip));
+        assert_eq!(Compression::detect(b"plain"), None);
+    }
+
+    #[cfg(all(feature = "gzip", feature = "zstd"))]
+    #[test]
+    fn test_round_trip() {
+        let text = "payload ".repeat(1000);
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let value = Compressed::new(text.clone()).with_compression(compression).to_sql().unwrap();
+            let SqlValue::Bytes(ref data) = value else { panic!("expected bytes") };
+            assert!(data.len() < text.len() / 10);
+            assert_eq!(Compression::detect(data), Some(compression));
+
+            let read = Compressed::<String>::from_sql(value).unwrap();
+            assert_eq!(read.value, text);
+            assert_eq!(read.compression, compression);
+        }
+
+        let bytes = vec![0u8, 159, 146, 150];
+        let value = Compressed::new(bytes.clone()).to_sql().unwrap();
+        assert_eq!(Compressed::<Vec<u8>>::from_sql(value).unwrap().into_inner(), bytes);
+    }
+
+    #[test]
+    fn test_uncompressed_values() {
+        assert_eq!(Compressed::<String>::from_sql(SqlValue::from("plain")).unwrap().value, "plain");
+        assert_eq!(Compressed::<String>::from_sql(SqlValue::Bytes(b"raw".to_vec())).unwrap().value, "raw");
+        let none = Compressed { value: Option::<String>::None, compression: Compression::Gzip };
+        assert_eq!(none.to_sql().unwrap(), SqlValue::Null);
+        assert!(Compressed { value: 42i64, compression: Compression::Gzip }.to_sql().is_err());
+        assert_eq!(Option::<Compressed<String>>::from_sql(SqlValue::Null).unwrap(), None);
+    }
+}
@@ -0,0 +1,309 @@
+//! Configuration from environment variables
+//!
+//! [`ConnectionParams::from_env`] starts from `DATABASE_URL` (if set) and
+//! lets the individual `DB_*` variables override its parts:
+//!
+//! | Variable | Field |
+//! |----------|-------|
+//! | `DATABASE_URL` | all parts, see [`ConnectionParams::from_url`] |
+//! | `DB_DRIVER` | `driver` (required without `DATABASE_URL`) |
+//! | `DB_HOST` | `host` |
+//! | `DB_PORT` | `port` |
+//! | `DB_NAME` | `database` |
+//! | `DB_USER` | `username` |
+//! | `DB_PASSWORD` | `password` |
+//! | `DB_SOCKET` | `socket` |
+//! | `DB_PATH` | `path` |
+//!
+//! [`Configuration::from_env`] starts from the defaults and applies:
+//!
+//! | Variable | Field |
+//! |----------|-------|
+//! | `DB_AUTO_COMMIT` | `auto_commit` (boolean) |
+//! | `DB_LAZY_CONNECT` | `lazy_connect` (boolean) |
+//! | `DB_CONNECT_TIMEOUT_MS` | `connect_timeout` (milliseconds) |
+//! | `DB_READ_TIMEOUT_MS` | `read_timeout` (milliseconds, reserved) |
+//! | `DB_WRITE_TIMEOUT_MS` | `write_timeout` (milliseconds, reserved) |
+//! | `DB_KEEPALIVE_MS` | `keepalive` (milliseconds, reserved) |
+//! | `DB_QUERY_TIMEOUT_MS` | `query_timeout` (milliseconds) |
+//! | `DB_SLOW_QUERY_MS` | `slow_query_threshold` (milliseconds) |
+//! | `DB_SCHEMA` | `schema` |
+//! | `DB_CHARSET` | `charset` |
+//! | `DB_TIMEZONE` | `timezone` |
+//! | `DB_APPLICATION_NAME` | `application_name` |
+//! | `DB_LOGGING` | `enable_logging` (boolean) |
+//! | `DB_LOG_PARAMETERS` | `log_parameter_values` (boolean) |
+//! | `DB_POOL_MAX` | `pool_max_size` |
+//! | `DB_SELECT_STAR` | `select_star_policy` (`allow`, `expand`, `warn` or `deny`) |
+//! | `DB_MAX_ROWS` | `max_rows` |
+//!
+//! Empty variables count as unset. Booleans accept `true`/`false`, `1`/`0`,
+//! `yes`/`no` and `on`/`off`. Values that cannot be parsed fail with
+//! [`Error::Environment`] naming the variable.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::{Configuration, ConnectionParams, Error, Result, SelectStarPolicy};
+
+impl ConnectionParams {
+    /// Read connection parameters from the environment
+    ///
+    /// See the table above for the variables and their precedence.
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if neither `DATABASE_URL` nor `DB_DRIVER`
+    /// is set or the URL is invalid, and [`Error::Environment`] if `DB_PORT`
+    /// is not a port number.
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read connection parameters using a variable lookup function
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| lookup(name).filter(|value| !value.is_empty());
+
+        let mut params = match (var("DATABASE_URL"), var("DB_DRIVER")) {
+            (Some(url), driver) => {
+                let mut params = Self::from_url(&url)?;
+                if let Some(driver) = driver {
+                    params.driver = driver;
+                }
+                params
+            }
+            (None, Some(driver)) => Self::new(driver),
+            (None, None) => {
+                return Err(Error::config("DATABASE_URL or DB_DRIVER must be set"));
+            }
+        };
+
+        if let Some(host) = var("DB_HOST") {
+            params.host = Some(host);
+        }
+        if let Some(port) = parse_var(&var, "DB_PORT")? {
+            params.port = Some(port);
+        }
+        if let Some(database) = var("DB_NAME") {
+            params.database = Some(database);
+        }
+        if let Some(username) = var("DB_USER") {
+            params.username = Some(username);
+        }
+        if let Some(password) = var("DB_PASSWORD") {
+            params.password = Some(password);
+        }
+        if let Some(socket) = var("DB_SOCKET") {
+            params.socket = Some(socket);
+        }
+        if let Some(path) = var("DB_PATH") {
+            params.path = Some(path);
+        }
+        Ok(params)
+    }
+}
+
+impl Configuration {
+    /// Read the runtime configuration from the environment
+    ///
+    /// Unset variables keep their default values.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Environment`] if a numeric or boolean variable
+    /// cannot be parsed.
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read the runtime configuration using a variable lookup function
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| lookup(name).filter(|value| !value.is_empty());
+        let mut config = Self::default();
+
+        if let Some(value) = parse_bool(&var, "DB_AUTO_COMMIT")? {
+            config.auto_commit = value;
+        }
+        if let Some(value) = parse_bool(&var, "DB_LAZY_CONNECT")? {
+            config.lazy_connect = value;
+        }
+        if let Some(value) = parse_millis(&var, "DB_CONNECT_TIMEOUT_MS")? {
+            config.connect_timeout = Some(value);
+        }
+        if let Some(value) = parse_millis(&var, "DB_READ_TIMEOUT_MS")? {
+            config.read_timeout = Some(value);
+        }
+        if let Some(value) = parse_millis(&var, "DB_WRITE_TIMEOUT_MS")? {
+            config.write_timeout = Some(value);
+        }
+        if let Some(value) = parse_millis(&var, "DB_KEEPALIVE_MS")? {
+            config.keepalive = Some(value);
+        }
+        if let Some(value) = parse_millis(&var, "DB_QUERY_TIMEOUT_MS")? {
+            config.query_timeout = Some(value);
+        }
+        if let Some(value) = parse_millis(&var, "DB_SLOW_QUERY_MS")? {
+            config.slow_query_threshold = Some(value);
+        }
+        if let Some(value) = var("DB_SCHEMA") {
+            config.schema = Some(value);
+        }
+        if let Some(value) = var("DB_CHARSET") {
+            config.charset = Some(value);
+        }
+        if let Some(value) = var("DB_TIMEZONE") {
+            config.timezone = Some(value);
+        }
+        if let Some(value) = var("DB_APPLICATION_NAME") {
+            config.application_name = Some(value);
+        }
+        if let Some(value) = parse_bool(&var, "DB_LOGGING")? {
+            config.enable_logging = value;
+        }
+        if let Some(value) = parse_bool(&var, "DB_LOG_PARAMETERS")? {
+            config.log_parameter_values = value;
+        }
+        if let Some(value) = parse_var(&var, "DB_POOL_MAX")? {
+            config.pool_max_size = Some(value);
+        }
+        if let Some(value) = parse_select_star(&var, "DB_SELECT_STAR")? {
+            config.select_star_policy = value;
+        }
+        if let Some(value) = parse_var(&var, "DB_MAX_ROWS")? {
+            config.max_rows = Some(value);
+        }
+        Ok(config)
+    }
+}
+
+/// Parse a variable with `FromStr`
+fn parse_var<T>(var: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    var(name)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|e| Error::environment(name, format!("'{value}': {e}")))
+        })
+        .transpose()
+}
+
+/// Parse a boolean variable
+fn parse_bool(var: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<bool>> {
+    var(name)
+        .map(|value| match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(true),
+            "false" | "0" | "no" | "off" => Ok(false),
+            _ => Err(Error::environment(name, format!("'{value}' is not a boolean"))),
+        })
+        .transpose()
+}
+
+/// Parse a `SELECT *` policy variable
+fn parse_select_star(var: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<SelectStarPolicy>> {
+    var(name)
+        .map(|value| match value.trim().to_ascii_lowercase().as_str() {
+            "allow" => Ok(SelectStarPolicy::Allow),
+            "expand" => Ok(SelectStarPolicy::Expand),
+            "warn" => Ok(SelectStarPolicy::Warn),
+            "deny" => Ok(SelectStarPolicy::Deny),
+            _ => Err(Error::environment(name, format!("'{value}' is not a SELECT * policy"))),
+        })
+        .transpose()
+}
+
+/// Parse a duration variable given in milliseconds
+fn parse_millis(var: &impl Fn(&str) -> Option<String>, name: &str) -> Result<Option<Duration>> {
+    Ok(parse_var::<u64>(var, name)?.map(Duration::from_millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_params_from_url_with_overrides() {
+        let params = ConnectionParams::from_lookup(lookup(&[
+            ("DATABASE_URL", "postgres://app:secret@db:5433/main"),
+            ("DB_HOST", "replica"),
+            ("DB_PASSWORD", "rotated"),
+            ("DB_NAME", ""),
+        ]))
+        .unwrap();
+
+        assert_eq!(params.driver, "postgres");
+        assert_eq!(params.host.as_deref(), Some("replica"));
+        assert_eq!(params.port, Some(5433));
+        assert_eq!(params.username.as_deref(), Some("app"));
+        assert_eq!(params.password.as_deref(), Some("rotated"));
+        assert_eq!(params.database.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_params_from_variables() {
+        let params = ConnectionParams::from_lookup(lookup(&[
+            ("DB_DRIVER", "sqlite"),
+            ("DB_PATH", "/tmp/app.db"),
+        ]))
+        .unwrap();
+        assert_eq!(params.driver, "sqlite");
+        assert_eq!(params.path.as_deref(), Some("/tmp/app.db"));
+
+        assert!(matches!(
+            ConnectionParams::from_lookup(lookup(&[])),
+            Err(Error::Configuration(_))
+        ));
+
+        let err = ConnectionParams::from_lookup(lookup(&[
+            ("DB_DRIVER", "mysql"),
+            ("DB_PORT", "70000"),
+        ]))
+        .unwrap_err();
+        assert!(matches!(err, Error::Environment { ref variable, .. } if variable == "DB_PORT"));
+    }
+
+    #[test]
+    fn test_configuration_from_variables() {
+        let config = Configuration::from_lookup(lookup(&[
+            ("DB_CONNECT_TIMEOUT_MS", "1500"),
+            ("DB_KEEPALIVE_MS", "30000"),
+            ("DB_SCHEMA", "app"),
+            ("DB_LOGGING", "on"),
+            ("DB_AUTO_COMMIT", "0"),
+            ("DB_POOL_MAX", "16"),
+            ("DB_SELECT_STAR", "Warn"),
+            ("DB_MAX_ROWS", "5000"),
+        ]))
+        .unwrap();
+
+        assert_eq!(config.connect_timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(config.keepalive, Some(Duration::from_secs(30)));
+        assert_eq!(config.read_timeout, None);
+        assert_eq!(config.schema.as_deref(), Some("app"));
+        assert!(config.enable_logging);
+        assert!(!config.auto_commit);
+        assert_eq!(config.pool_max_size, Some(16));
+        assert_eq!(config.select_star_policy, SelectStarPolicy::Warn);
+        assert_eq!(config.max_rows, Some(5000));
+        assert_eq!(config.charset.as_deref(), Some("utf8mb4"));
+
+        let err = Configuration::from_lookup(lookup(&[("DB_LAZY_CONNECT", "maybe")])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid environment variable DB_LAZY_CONNECT: 'maybe' is not a boolean"
+        );
+    }
+}
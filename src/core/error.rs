@@ -5,12 +5,18 @@
 //! - Transaction errors (no active transaction, rollback-only, savepoint issues)
 //! - Query errors (syntax, constraint violations, execution failures)
 //! - Schema errors (table not found, column not found, introspection failures)
+//! - Migration errors (changed or missing applied migrations)
 //! - Conversion errors (type conversion failures)
 
+use alloc::{boxed::Box, format, string::String};
+#[cfg(not(feature = "std"))]
+use core::error::Error as StdError;
+#[cfg(feature = "std")]
+use std::error::Error as StdError;
 use thiserror::Error;
 
 /// Result type alias using the Rustine Error type
-pub type Result<T> = std::result::Result<T, Error>;
+pub type Result<T> = core::result::Result<T, Error>;
 
 /// Main error type for all Rustine operations
 #[derive(Error, Debug)]
@@ -31,6 +37,10 @@ pub enum Error {
     #[error("Schema error: {0}")]
     Schema(#[from] SchemaError),
 
+    /// Migration errors
+    #[error("Migration error: {0}")]
+    Migration(#[from] MigrationError),
+
     /// Type conversion errors
     #[error("Conversion error: cannot convert {from_type} to {to_type}: {message}")]
     Conversion {
@@ -49,7 +59,7 @@ pub enum Error {
         message: String,
         /// Optional underlying error source
         #[source]
-        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+        source: Option<Box<dyn StdError + Send + Sync>>,
     },
 
     /// Platform-specific errors
@@ -59,6 +69,15 @@ pub enum Error {
     /// Configuration errors
     #[error("Configuration error: {0}")]
     Configuration(String),
+
+    /// An environment variable holds a value that cannot be parsed
+    #[error("Invalid environment variable {variable}: {message}")]
+    Environment {
+        /// Name of the variable
+        variable: String,
+        /// Why the value is invalid
+        message: String,
+    },
 }
 
 /// Connection-specific errors
@@ -127,6 +146,11 @@ pub enum TransactionError {
     /// Rollback failed
     #[error("Rollback failed: {0}")]
     RollbackFailed(String),
+
+    /// The transaction failed and rejects statements until rolled back
+    /// (`PostgreSQL` SQLSTATE 25P02)
+    #[error("Transaction aborted by an earlier error; roll back to continue")]
+    Aborted,
 }
 
 /// Query execution errors
@@ -155,12 +179,22 @@ pub enum QueryError {
     },
 
     /// Query execution failed
-    #[error("Execution failed: {message}")]
+    ///
+    /// Build it with [`QueryError::execution_failed`]; fields may be added,
+    /// so patterns need `..`. The complete SQL and the parameter summary are
+    /// also available through [`full_sql`](Self::full_sql) and
+    /// [`params`](Self::params).
+    #[non_exhaustive]
+    #[error("Execution failed: {message}{}", .params.as_ref().map(|p| format!(" (parameters: {p})")).unwrap_or_default())]
     ExecutionFailed {
         /// Error message describing the failure
         message: String,
-        /// The SQL query that failed
+        /// The SQL query that failed, possibly truncated
         sql: Option<String>,
+        /// The complete SQL, if `sql` was truncated and keeping it was requested
+        full_sql: Option<String>,
+        /// Summary of the bound parameters (see `ValueRedactionPolicy::summarize_params`)
+        params: Option<String>,
     },
 
     /// Invalid parameter
@@ -196,6 +230,122 @@ pub enum QueryError {
     /// Query was cancelled
     #[error("Query cancelled")]
     Cancelled,
+
+    /// Result column not found by name
+    #[error("Column not found in result: {0}")]
+    ColumnNotFound(String),
+
+    /// No row of the table matches the key of an update
+    #[error("No row of {0} matches the key")]
+    RowNotFound(String),
+
+    /// Result column name matches more than one column
+    #[error("Ambiguous column '{name}': {count} columns match, qualify the name or use an occurrence index")]
+    AmbiguousColumn {
+        /// The requested column name
+        name: String,
+        /// Number of matching columns
+        count: usize,
+    },
+}
+
+impl QueryError {
+    /// Create an execution failure of the given SQL
+    #[must_use]
+    pub fn execution_failed(message: impl Into<String>, sql: Option<String>) -> Self {
+        Self::ExecutionFailed {
+            message: message.into(),
+            sql,
+            full_sql: None,
+            params: None,
+        }
+    }
+
+    /// Get the SQL the error refers to, possibly truncated
+    #[must_use]
+    pub fn sql(&self) -> Option<&str> {
+        match self {
+            Self::Syntax { sql, .. } | Self::ExecutionFailed { sql, .. } => sql.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Get the complete SQL the error refers to
+    ///
+    /// Differs from [`sql`](Self::sql) only for errors truncated with
+    /// `keep_full_sql`; otherwise the complete text is gone and the
+    /// truncated one is returned.
+    #[must_use]
+    pub fn full_sql(&self) -> Option<&str> {
+        match self {
+            Self::ExecutionFailed { full_sql: Some(full_sql), .. } => Some(full_sql),
+            _ => self.sql(),
+        }
+    }
+
+    /// Get the summary of the parameters bound to the failed statement
+    #[must_use]
+    pub fn params(&self) -> Option<&str> {
+        match self {
+            Self::ExecutionFailed { params, .. } => params.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Attach a summary of the bound parameters to an execution failure
+    ///
+    /// An invalid parameter, as reported when binding fails, gets the
+    /// summary appended to its message. Other errors are returned unchanged.
+    #[must_use]
+    pub fn with_params(self, summary: String) -> Self {
+        match self {
+            Self::ExecutionFailed { message, sql, full_sql, .. } => Self::ExecutionFailed {
+                message,
+                sql,
+                full_sql,
+                params: Some(summary),
+            },
+            Self::InvalidParameter { name, message } => Self::InvalidParameter {
+                name,
+                message: format!("{message} (parameters: {summary})"),
+            },
+            other => other,
+        }
+    }
+
+    /// Cap the SQL and message of the error at `max_length` characters
+    ///
+    /// With `keep_full_sql` the complete SQL of an execution failure stays
+    /// available through [`full_sql`](Self::full_sql).
+    #[must_use]
+    pub fn truncated(self, max_length: usize, keep_full_sql: bool) -> Self {
+        match self {
+            Self::ExecutionFailed { message, sql, full_sql, params } => {
+                let full_sql = full_sql.or_else(|| sql.clone().filter(|_| keep_full_sql));
+                let sql = sql.map(|sql| truncate(sql, max_length));
+                Self::ExecutionFailed {
+                    message: truncate(message, max_length),
+                    full_sql: full_sql.filter(|full| sql.as_ref() != Some(full)),
+                    sql,
+                    params,
+                }
+            }
+            Self::Syntax { message, sql, position } => Self::Syntax {
+                message: truncate(message, max_length),
+                sql: sql.map(|sql| truncate(sql, max_length)),
+                position,
+            },
+            other => other,
+        }
+    }
+}
+
+/// Cut text after `max_length` characters, noting how many were dropped
+fn truncate(text: String, max_length: usize) -> String {
+    match text.char_indices().nth(max_length) {
+        Some((end, _)) => format!("{}... ({} more characters)", &text[..end], text[end..].chars().count()),
+        None => text,
+    }
 }
 
 /// Types of constraint violations
@@ -215,8 +365,8 @@ pub enum ConstraintType {
     Unknown,
 }
 
-impl std::fmt::Display for ConstraintType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for ConstraintType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::PrimaryKey => write!(f, "Primary key"),
             Self::Unique => write!(f, "Unique"),
@@ -270,6 +420,44 @@ pub enum SchemaError {
     UnsupportedOperation(String),
 }
 
+/// Migration errors
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    /// An applied migration was edited after it ran
+    #[error("Migration {version} ({name}) changed after it was applied: checksum {applied}, now {current}")]
+    ChecksumMismatch {
+        /// Version of the migration
+        version: i64,
+        /// Name of the migration
+        name: String,
+        /// Checksum recorded when the migration was applied
+        applied: String,
+        /// Checksum of the migration as it is now
+        current: String,
+    },
+
+    /// An applied migration is no longer known
+    #[error("Applied migration {0} is missing")]
+    Missing(i64),
+
+    /// Two migrations have the same version
+    #[error("Duplicate migration version: {0}")]
+    DuplicateVersion(i64),
+
+    /// A baseline can't be created from the current state
+    #[error("Invalid baseline: {0}")]
+    InvalidBaseline(String),
+
+    /// A migration can't be reverted
+    #[error("Migration {version} can't be reverted: {reason}")]
+    Irreversible {
+        /// Version of the migration
+        version: i64,
+        /// What can't be reverted
+        reason: String,
+    },
+}
+
 impl Error {
     /// Create a conversion error
     pub fn conversion(from_type: &'static str, to_type: &'static str, message: impl Into<String>) -> Self {
@@ -281,7 +469,7 @@ impl Error {
     }
 
     /// Create a driver error from any error source
-    pub fn driver(message: impl Into<String>, source: impl std::error::Error + Send + Sync + 'static) -> Self {
+    pub fn driver(message: impl Into<String>, source: impl StdError + Send + Sync + 'static) -> Self {
         Self::Driver {
             message: message.into(),
             source: Some(Box::new(source)),
@@ -306,6 +494,14 @@ impl Error {
         Self::Configuration(message.into())
     }
 
+    /// Create an environment variable error
+    pub fn environment(variable: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Environment {
+            variable: variable.into(),
+            message: message.into(),
+        }
+    }
+
     /// Check if this error is a connection error
     #[must_use]
     pub const fn is_connection_error(&self) -> bool {
@@ -330,6 +526,41 @@ impl Error {
         matches!(self, Self::Query(QueryError::Deadlock))
     }
 
+    /// Cap the SQL and message of a query error at `max_length` characters
+    ///
+    /// See [`QueryError::truncated`]; other errors are returned unchanged.
+    #[must_use]
+    pub fn truncated(self, max_length: usize, keep_full_sql: bool) -> Self {
+        match self {
+            Self::Query(error) => Self::Query(error.truncated(max_length, keep_full_sql)),
+            other => other,
+        }
+    }
+
+    /// Attach a summary of the bound parameters to a query or conversion error
+    ///
+    /// See [`QueryError::with_params`]; a conversion error, as reported when
+    /// a value cannot be bound as its type, gets the summary appended to its
+    /// message. Other errors are returned unchanged.
+    #[must_use]
+    pub fn with_params(self, summary: String) -> Self {
+        match self {
+            Self::Query(error) => Self::Query(error.with_params(summary)),
+            Self::Conversion { from_type, to_type, message } => Self::Conversion {
+                from_type,
+                to_type,
+                message: format!("{message} (parameters: {summary})"),
+            },
+            other => other,
+        }
+    }
+
+    /// Check if this error reports a failed transaction that must be rolled back
+    #[must_use]
+    pub const fn is_failed_transaction(&self) -> bool {
+        matches!(self, Self::Transaction(TransactionError::Aborted))
+    }
+
     /// Check if this error indicates the operation can be retried
     #[must_use]
     pub const fn is_retryable(&self) -> bool {
@@ -345,6 +576,20 @@ impl Error {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_query_error_truncation() {
+        let error = QueryError::execution_failed("no such table: t", Some("SELECT * FROM t WHERE name = 'äöü'".into()));
+        let truncated = error.truncated(31, false);
+        assert_eq!(truncated.sql(), Some("SELECT * FROM t WHERE name = 'ä... (3 more characters)"));
+        assert_eq!(truncated.full_sql(), truncated.sql());
+        assert!(truncated.to_string().ends_with("no such table: t"));
+
+        let kept = QueryError::execution_failed("", Some("SELECT 1".into()))
+        .truncated(6, true);
+        assert_eq!(kept.sql(), Some("SELECT... (2 more characters)"));
+        assert_eq!(kept.full_sql(), Some("SELECT 1"));
+    }
+
     #[test]
     fn test_error_display() {
         let err = Error::Connection(ConnectionError::Lost);
@@ -0,0 +1,140 @@
+//! `FromRow` trait for converting result rows into Rust types
+//!
+//! Implemented for tuples of up to 16 [`FromSql`] elements, so quick queries
+//! can be mapped without a dedicated struct:
+//!
+//! ```
+//! use rustine_dbal::core::{FromRow, SqlValue};
+//!
+//! let row = vec![SqlValue::I64(1), SqlValue::String("Alice".into()), SqlValue::Null];
+//! let (id, name, active) = <(i64, String, Option<bool>)>::from_row(row).unwrap();
+//! assert_eq!((id, name.as_str(), active), (1, "Alice", None));
+//! ```
+
+use alloc::{vec::Vec, format};
+use super::{Error, FromSql, Result, SqlValue, TimezonePolicy};
+
+/// Conversion from a result row (column values in select order)
+pub trait FromRow: Sized {
+    /// Convert a row into this type
+    ///
+    /// # Errors
+    ///
+    /// Returns a conversion error if the number of columns doesn't match or
+    /// a column value cannot be converted.
+    fn from_row(row: Vec<SqlValue>) -> Result<Self>;
+
+    /// Convert a row into this type, rejecting lossy numeric conversions
+    ///
+    /// Tuples convert their elements with [`FromSql::from_sql_strict`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a conversion error like [`from_row`](Self::from_row), or if
+    /// a column value would lose precision.
+    fn from_row_strict(row: Vec<SqlValue>) -> Result<Self> {
+        Self::from_row(row)
+    }
+
+    /// Convert a row into this type, applying the timezone policy
+    ///
+    /// Tuples convert their elements with [`FromSql::from_sql_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a conversion error like [`from_row`](Self::from_row).
+    fn from_row_with(row: Vec<SqlValue>, policy: TimezonePolicy) -> Result<Self> {
+        let _ = policy;
+        Self::from_row(row)
+    }
+}
+
+impl FromRow for Vec<SqlValue> {
+    fn from_row(row: Vec<SqlValue>) -> Result<Self> {
+        Ok(row)
+    }
+}
+
+/// Check the column count of a row read into a tuple type
+fn tuple_values<T>(row: Vec<SqlValue>, len: usize) -> Result<alloc::vec::IntoIter<SqlValue>> {
+    if row.len() != len {
+        return Err(Error::conversion(
+            "row",
+            core::any::type_name::<T>(),
+            format!("expected {len} columns, got {}", row.len()),
+        ));
+    }
+    Ok(row.into_iter())
+}
+
+/// Implement `FromRow` for a tuple of `FromSql` types
+macro_rules! impl_from_row_for_tuple {
+    ($len:literal => $($name:ident),+) => {
+        impl<$($name: FromSql),+> FromRow for ($($name,)+) {
+            fn from_row(row: Vec<SqlValue>) -> Result<Self> {
+                let mut values = tuple_values::<Self>(row, $len)?;
+                Ok(($($name::from_sql(values.next().unwrap_or(SqlValue::Null))?,)+))
+            }
+
+            fn from_row_strict(row: Vec<SqlValue>) -> Result<Self> {
+                let mut values = tuple_values::<Self>(row, $len)?;
+                Ok(($($name::from_sql_strict(values.next().unwrap_or(SqlValue::Null))?,)+))
+            }
+
+            fn from_row_with(row: Vec<SqlValue>, policy: TimezonePolicy) -> Result<Self> {
+                let mut values = tuple_values::<Self>(row, $len)?;
+                Ok(($($name::from_sql_with(values.next().unwrap_or(SqlValue::Null), policy)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_row_for_tuple!(1 => T1);
+impl_from_row_for_tuple!(2 => T1, T2);
+impl_from_row_for_tuple!(3 => T1, T2, T3);
+impl_from_row_for_tuple!(4 => T1, T2, T3, T4);
+impl_from_row_for_tuple!(5 => T1, T2, T3, T4, T5);
+impl_from_row_for_tuple!(6 => T1, T2, T3, T4, T5, T6);
+impl_from_row_for_tuple!(7 => T1, T2, T3, T4, T5, T6, T7);
+impl_from_row_for_tuple!(8 => T1, T2, T3, T4, T5, T6, T7, T8);
+impl_from_row_for_tuple!(9 => T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_from_row_for_tuple!(10 => T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_from_row_for_tuple!(11 => T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_from_row_for_tuple!(12 => T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+impl_from_row_for_tuple!(13 => T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
+impl_from_row_for_tuple!(14 => T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
+impl_from_row_for_tuple!(15 => T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
+impl_from_row_for_tuple!(16 => T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, T16);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tuple_from_row() {
+        let row = vec![SqlValue::I64(7), SqlValue::String("Bob".into()), SqlValue::Bool(true)];
+        let (id, name, active) = <(i64, String, Option<bool>)>::from_row(row).unwrap();
+        assert_eq!(id, 7);
+        assert_eq!(name, "Bob");
+        assert_eq!(active, Some(true));
+    }
+
+    #[test]
+    fn test_column_count_mismatch() {
+        let err = <(i64, String)>::from_row(vec![SqlValue::I64(1)]).unwrap_err();
+        assert!(err.to_string().contains("expected 2 columns, got 1"));
+
+        let err = <(i64,)>::from_row(vec![SqlValue::String("x".into())]);
+        assert!(err.is_err());
+        assert!(<(i64,)>::from_row_strict(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_strict_tuple_from_row() {
+        let row = vec![SqlValue::I64(1), SqlValue::F64(0.1)];
+        assert!(<(i64, f64)>::from_row_strict(row.clone()).is_ok());
+        assert!(<(i64, f32)>::from_row(row.clone()).is_ok());
+        let err = <(i64, f32)>::from_row_strict(row).unwrap_err();
+        assert!(err.to_string().contains("not exactly representable as f32"));
+    }
+}
@@ -3,7 +3,8 @@
 //! This trait enables converting [`SqlValue`] instances back into
 //! concrete Rust types.
 
-use super::{Error, Result, SqlValue};
+use alloc::{string::{String, ToString}, vec::Vec, format};
+use super::{Error, Result, SqlValue, TimezonePolicy};
 
 /// Trait for types that can be created from SQL values
 ///
@@ -51,6 +52,33 @@ pub trait FromSql: Sized {
             Self::from_sql(value).map(Some)
         }
     }
+
+    /// Convert from a SQL value, applying the timezone policy
+    ///
+    /// Only date-time types are affected, all other types delegate to
+    /// [`from_sql`](Self::from_sql).
+    ///
+    /// # Errors
+    ///
+    /// Returns a conversion error if the SQL value cannot be converted to this type.
+    fn from_sql_with(value: SqlValue, policy: TimezonePolicy) -> Result<Self> {
+        let _ = policy;
+        Self::from_sql(value)
+    }
+
+    /// Convert from a SQL value, rejecting conversions that lose precision
+    ///
+    /// `f32` and `f64` only accept values they represent exactly, see
+    /// [`SqlValue::try_as_f64_exact`]; all other types delegate to
+    /// [`from_sql`](Self::from_sql).
+    ///
+    /// # Errors
+    ///
+    /// Returns a conversion error if the SQL value cannot be converted
+    /// without loss.
+    fn from_sql_strict(value: SqlValue) -> Result<Self> {
+        Self::from_sql(value)
+    }
 }
 
 impl FromSql for bool {
@@ -166,6 +194,13 @@ impl FromSql for f32 {
             _ => Err(Error::conversion(value_type_name(&value), "f32", "cannot convert to f32")),
         }
     }
+
+    fn from_sql_strict(value: SqlValue) -> Result<Self> {
+        match value {
+            SqlValue::String(_) => Self::from_sql(value),
+            value => value.try_as_f32_exact(),
+        }
+    }
 }
 
 impl FromSql for f64 {
@@ -182,6 +217,13 @@ impl FromSql for f64 {
             _ => Err(Error::conversion(value_type_name(&value), "f64", "cannot convert to f64")),
         }
     }
+
+    fn from_sql_strict(value: SqlValue) -> Result<Self> {
+        match value {
+            SqlValue::String(_) => Self::from_sql(value),
+            value => value.try_as_f64_exact(),
+        }
+    }
 }
 
 impl FromSql for String {
@@ -220,6 +262,22 @@ impl<T: FromSql> FromSql for Option<T> {
     fn from_sql(value: SqlValue) -> Result<Self> {
         T::from_sql_nullable(value)
     }
+
+    fn from_sql_strict(value: SqlValue) -> Result<Self> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_sql_strict(value).map(Some)
+        }
+    }
+
+    fn from_sql_with(value: SqlValue, policy: TimezonePolicy) -> Result<Self> {
+        if value.is_null() {
+            Ok(None)
+        } else {
+            T::from_sql_with(value, policy).map(Some)
+        }
+    }
 }
 
 impl FromSql for SqlValue {
@@ -267,6 +325,18 @@ impl FromSql for chrono::NaiveDateTime {
             _ => Err(Error::conversion(value_type_name(&value), "NaiveDateTime", "cannot convert to datetime")),
         }
     }
+
+    fn from_sql_with(value: SqlValue, policy: TimezonePolicy) -> Result<Self> {
+        match (policy, value) {
+            // Stored value is UTC, naive values are local time
+            (TimezonePolicy::StoreUtc, value) => {
+                chrono::DateTime::<chrono::Utc>::from_sql(value).map(super::utc_to_local)
+            }
+            (TimezonePolicy::StoreLocal, SqlValue::DateTimeUtc(dt)) => Ok(super::utc_to_local(dt)),
+            (TimezonePolicy::PassThrough, SqlValue::DateTimeUtc(dt)) => Ok(dt.naive_utc()),
+            (_, value) => Self::from_sql(value),
+        }
+    }
 }
 
 #[cfg(feature = "chrono")]
@@ -277,11 +347,70 @@ impl FromSql for chrono::DateTime<chrono::Utc> {
         match value {
             SqlValue::DateTimeUtc(dt) => Ok(dt),
             SqlValue::DateTime(dt) => Ok(chrono::Utc.from_utc_datetime(&dt)),
+            SqlValue::DateTimeTz(dt) => Ok(dt.to_utc()),
             SqlValue::String(s) => s.parse()
-                .map_err(|e: chrono::ParseError| Error::conversion("String", "DateTime<Utc>", e.to_string())),
+                .or_else(|_| parse_datetime_tz(&s).map(|dt| dt.to_utc()))
+                .or_else(|e: chrono::ParseError| {
+                    // Text without offset, as SQLite stores date-times, is UTC like `DateTime`
+                    chrono::NaiveDateTime::from_sql(SqlValue::String(s.clone()))
+                        .map(|naive| naive.and_utc())
+                        .map_err(|_| e)
+                })
+                .map_err(|e| Error::conversion("String", "DateTime<Utc>", e.to_string())),
             _ => Err(Error::conversion(value_type_name(&value), "DateTime<Utc>", "cannot convert to datetime")),
         }
     }
+
+    fn from_sql_with(value: SqlValue, policy: TimezonePolicy) -> Result<Self> {
+        match (policy, value) {
+            // Stored value without offset is local time
+            (TimezonePolicy::StoreLocal, value @ (SqlValue::DateTime(_) | SqlValue::String(_))) => {
+                match chrono::NaiveDateTime::from_sql(value.clone()) {
+                    Ok(naive) => Ok(super::local_to_utc(naive)),
+                    Err(_) => Self::from_sql(value),
+                }
+            }
+            (_, value) => Self::from_sql(value),
+        }
+    }
+}
+
+/// Parse a date-time with offset (RFC 3339 or Postgres `timestamptz` text)
+///
+/// Postgres renders offsets without minutes (`2024-01-15 12:00:00+02`),
+/// which RFC 3339 parsing rejects.
+#[cfg(feature = "chrono")]
+fn parse_datetime_tz(s: &str) -> core::result::Result<chrono::DateTime<chrono::FixedOffset>, chrono::ParseError> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .or_else(|_| chrono::DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%#z"))
+        .or_else(|_| chrono::DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%#z"))
+}
+
+#[cfg(feature = "chrono")]
+impl FromSql for chrono::DateTime<chrono::FixedOffset> {
+    fn from_sql(value: SqlValue) -> Result<Self> {
+        use chrono::TimeZone;
+
+        match value {
+            SqlValue::DateTimeTz(dt) => Ok(dt),
+            SqlValue::DateTimeUtc(dt) => Ok(dt.fixed_offset()),
+            SqlValue::DateTime(dt) => Ok(chrono::Utc.from_utc_datetime(&dt).fixed_offset()),
+            SqlValue::String(s) => parse_datetime_tz(&s)
+                .map_err(|e| Error::conversion("String", "DateTime<FixedOffset>", e.to_string())),
+            _ => Err(Error::conversion(
+                value_type_name(&value),
+                "DateTime<FixedOffset>",
+                "cannot convert to datetime",
+            )),
+        }
+    }
+}
+
+#[cfg(all(feature = "chrono", feature = "std"))]
+impl FromSql for chrono::DateTime<chrono::Local> {
+    fn from_sql(value: SqlValue) -> Result<Self> {
+        chrono::DateTime::<chrono::FixedOffset>::from_sql(value).map(|dt| dt.with_timezone(&chrono::Local))
+    }
 }
 
 #[cfg(feature = "uuid")]
@@ -313,7 +442,7 @@ impl FromSql for serde_json::Value {
 #[cfg(feature = "decimal")]
 impl FromSql for rust_decimal::Decimal {
     fn from_sql(value: SqlValue) -> Result<Self> {
-        use std::str::FromStr;
+        use core::str::FromStr;
 
         match value {
             SqlValue::Decimal(d) => Ok(d),
@@ -321,6 +450,14 @@ impl FromSql for rust_decimal::Decimal {
             SqlValue::I16(i) => Ok(Self::from(i)),
             SqlValue::I32(i) => Ok(Self::from(i)),
             SqlValue::I64(i) => Ok(Self::from(i)),
+            SqlValue::U32(u) => Ok(Self::from(u)),
+            SqlValue::U64(u) => Ok(Self::from(u)),
+            // Drivers without exact numerics (SQLite) return REAL, go through the
+            // shortest round-trip representation to avoid binary artifacts
+            SqlValue::F32(f) => Self::from_str(&f.to_string())
+                .map_err(|e| Error::conversion("f32", "Decimal", e.to_string())),
+            SqlValue::F64(f) => Self::from_str(&f.to_string())
+                .map_err(|e| Error::conversion("f64", "Decimal", e.to_string())),
             SqlValue::String(s) => Self::from_str(&s)
                 .map_err(|e| Error::conversion("String", "Decimal", e.to_string())),
             _ => Err(Error::conversion(value_type_name(&value), "Decimal", "cannot convert to decimal")),
@@ -329,7 +466,7 @@ impl FromSql for rust_decimal::Decimal {
 }
 
 /// Get a human-readable type name for error messages
-const fn value_type_name(value: &SqlValue) -> &'static str {
+pub(super) const fn value_type_name(value: &SqlValue) -> &'static str {
     match value {
         SqlValue::Null => "NULL",
         SqlValue::Bool(_) => "Bool",
@@ -351,6 +488,8 @@ const fn value_type_name(value: &SqlValue) -> &'static str {
         SqlValue::DateTime(_) => "DateTime",
         #[cfg(feature = "chrono")]
         SqlValue::DateTimeUtc(_) => "DateTime<Utc>",
+        #[cfg(feature = "chrono")]
+        SqlValue::DateTimeTz(_) => "DateTime<FixedOffset>",
         #[cfg(feature = "uuid")]
         SqlValue::Uuid(_) => "Uuid",
         #[cfg(feature = "json")]
@@ -394,6 +533,18 @@ mod tests {
         assert_eq!(f64::from_sql(SqlValue::String("3.14".into())).unwrap(), 3.14);
     }
 
+    #[test]
+    fn test_from_sql_strict_float() {
+        assert_eq!(f32::from_sql(SqlValue::F64(0.1)).unwrap(), 0.1);
+        assert!(f32::from_sql_strict(SqlValue::F64(0.1)).is_err());
+        assert_eq!(f32::from_sql_strict(SqlValue::F64(0.25)).unwrap(), 0.25);
+        assert!(f64::from_sql_strict(SqlValue::I64(i64::MAX)).is_err());
+        assert_eq!(f64::from_sql_strict(SqlValue::String("0.1".into())).unwrap(), 0.1);
+        assert_eq!(Option::<f32>::from_sql_strict(SqlValue::Null).unwrap(), None);
+        assert!(Option::<f32>::from_sql_strict(SqlValue::F64(0.1)).is_err());
+        assert_eq!(i64::from_sql_strict(SqlValue::I32(5)).unwrap(), 5);
+    }
+
     #[test]
     fn test_from_sql_string() {
         assert_eq!(String::from_sql(SqlValue::String("hello".into())).unwrap(), "hello");
@@ -416,7 +567,7 @@ mod tests {
     #[cfg(feature = "uuid")]
     #[test]
     fn test_from_sql_uuid() {
-        let uuid = uuid::Uuid::new_v4();
+        let uuid = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
         assert_eq!(uuid::Uuid::from_sql(SqlValue::Uuid(uuid)).unwrap(), uuid);
         assert_eq!(
             uuid::Uuid::from_sql(SqlValue::String(uuid.to_string())).unwrap(),
@@ -433,4 +584,79 @@ mod tests {
             date
         );
     }
+
+    #[cfg(all(feature = "chrono", feature = "std"))]
+    #[test]
+    fn test_timezone_policy_round_trip() {
+        use crate::core::ToSql;
+        use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+
+        let utc = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let local = utc.with_timezone(&chrono::Local).naive_local();
+
+        // Store UTC: the naive (local) value is written as UTC
+        let stored = local.to_sql_with(TimezonePolicy::StoreUtc).unwrap();
+        assert_eq!(stored, SqlValue::DateTime(utc.naive_utc()));
+        assert_eq!(
+            NaiveDateTime::from_sql_with(stored.clone(), TimezonePolicy::StoreUtc).unwrap(),
+            local
+        );
+        assert_eq!(DateTime::<Utc>::from_sql_with(stored, TimezonePolicy::StoreUtc).unwrap(), utc);
+
+        // Store local: the UTC value is written as local time
+        let stored = utc.to_sql_with(TimezonePolicy::StoreLocal).unwrap();
+        assert_eq!(stored, SqlValue::DateTime(local));
+        assert_eq!(DateTime::<Utc>::from_sql_with(stored, TimezonePolicy::StoreLocal).unwrap(), utc);
+
+        // Pass-through leaves values untouched
+        assert_eq!(
+            utc.to_sql_with(TimezonePolicy::PassThrough).unwrap(),
+            SqlValue::DateTimeUtc(utc)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_from_sql_datetime_with_offset() {
+        use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+        let expected = FixedOffset::east_opt(2 * 3600)
+            .and_then(|tz| tz.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).single())
+            .unwrap();
+
+        // RFC 3339 and Postgres timestamptz text
+        for text in ["2024-01-15T12:00:00+02:00", "2024-01-15 12:00:00+02", "2024-01-15 12:00:00.000+02:00"] {
+            let parsed = DateTime::<FixedOffset>::from_sql(SqlValue::String(text.into())).unwrap();
+            assert_eq!(parsed, expected);
+            assert_eq!(parsed.offset(), expected.offset());
+        }
+
+        let utc = DateTime::<Utc>::from_sql(SqlValue::String("2024-01-15 12:00:00+02".into())).unwrap();
+        assert_eq!(utc, Utc.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap());
+        // Text without offset is UTC
+        let utc = DateTime::<Utc>::from_sql(SqlValue::String("2024-01-15 10:00:00".into())).unwrap();
+        assert_eq!(utc, Utc.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap());
+
+        #[cfg(feature = "std")]
+        {
+            use crate::core::ToSql;
+
+            let local = DateTime::<chrono::Local>::from_sql(SqlValue::DateTimeTz(expected)).unwrap();
+            assert_eq!(local, expected);
+            assert_eq!(local.to_sql().unwrap(), SqlValue::DateTimeTz(local.fixed_offset()));
+        }
+
+        assert!(DateTime::<FixedOffset>::from_sql(SqlValue::I64(1)).is_err());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_from_sql_decimal() {
+        use rust_decimal::Decimal;
+
+        assert_eq!(Decimal::from_sql(SqlValue::F64(0.1)).unwrap(), Decimal::new(1, 1));
+        assert_eq!(Decimal::from_sql(SqlValue::String("12.50".into())).unwrap(), Decimal::new(1250, 2));
+        assert_eq!(Decimal::from_sql(SqlValue::U64(7)).unwrap(), Decimal::from(7u64));
+        assert!(Decimal::from_sql(SqlValue::F64(f64::NAN)).is_err());
+    }
 }
@@ -0,0 +1,27 @@
+//! Hex encoding of binary values
+//!
+//! Shared by the `0x` display of [`SqlValue::Bytes`](super::SqlValue::Bytes),
+//! platform binary literals, CSV and JSON exports and migration checksums.
+
+use alloc::string::String;
+
+/// Encode bytes as lower-case hex
+pub fn hex_encode(bytes: &[u8]) -> String {
+    use core::fmt::Write;
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut acc, b| {
+        let _ = write!(acc, "{b:02x}");
+        acc
+    })
+}
+
+/// Decode hex digits into bytes, `None` if they are not valid hex
+#[cfg(any(feature = "schema", all(feature = "query-builder", feature = "sql-parser")))]
+pub fn hex_decode(hex: &str) -> Option<alloc::vec::Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect()
+}
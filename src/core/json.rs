@@ -0,0 +1,96 @@
+//! Typed JSON values
+//!
+//! [`Json<T>`] reads a JSON (or JSONB) column straight into a type
+//! implementing `serde::Deserialize` and writes a `serde::Serialize` type as
+//! JSON, so call sites don't go through `serde_json::Value` by hand.
+
+use alloc::string::ToString;
+use serde::{de::DeserializeOwned, Serialize};
+use super::from_sql::value_type_name;
+use super::{Error, FromSql, Result, SqlValue, ToSql};
+
+/// A value stored as JSON
+///
+/// Read from JSON values and from JSON text in text or binary columns.
+/// NULL is written as NULL, use `Option<Json<T>>` to read it.
+///
+/// ```rust
+/// use rustine_dbal::core::{FromSql, Json, ToSql};
+/// use rustine_dbal::SqlValue;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct Address {
+///     city: String,
+/// }
+///
+/// let value = SqlValue::String(r#"{"city":"Berlin"}"#.to_string());
+/// let Json(address) = Json::<Address>::from_sql(value).unwrap();
+/// assert_eq!(address.city, "Berlin");
+/// assert!(matches!(Json(address).to_sql().unwrap(), SqlValue::Json(_)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    /// Take the wrapped value
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromSql for Json<T> {
+    fn from_sql(value: SqlValue) -> Result<Self> {
+        let target = core::any::type_name::<T>();
+        let parsed = match value {
+            SqlValue::Json(j) => serde_json::from_value(j),
+            SqlValue::String(ref s) => serde_json::from_str(s),
+            SqlValue::Bytes(ref b) => serde_json::from_slice(b),
+            _ => return Err(Error::conversion(value_type_name(&value), "Json", "cannot convert to JSON")),
+        };
+        parsed.map(Json).map_err(|e| Error::conversion("Json", target, e.to_string()))
+    }
+}
+
+impl<T: Serialize> ToSql for Json<T> {
+    fn to_sql(&self) -> Result<SqlValue> {
+        match serde_json::to_value(&self.0) {
+            Ok(serde_json::Value::Null) => Ok(SqlValue::Null),
+            Ok(value) => Ok(SqlValue::Json(value)),
+            Err(e) => Err(Error::conversion(core::any::type_name::<T>(), "Json", e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec, vec::Vec};
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Settings {
+        theme: String,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let settings = Settings { theme: "dark".into(), tags: vec!["a".into()] };
+        let value = Json(settings).to_sql().unwrap();
+        assert_eq!(value, SqlValue::Json(serde_json::json!({"theme": "dark", "tags": ["a"]})));
+
+        let Json(read) = Json::<Settings>::from_sql(value).unwrap();
+        assert_eq!(read, Settings { theme: "dark".into(), tags: vec!["a".into()] });
+        let read = Json::<Settings>::from_sql(SqlValue::Bytes(br#"{"theme":"light","tags":[]}"#.to_vec())).unwrap();
+        assert_eq!(read.into_inner().theme, "light");
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(Json::<Settings>::from_sql(SqlValue::String(r#"{"theme":1}"#.into())).is_err());
+        assert!(Json::<Settings>::from_sql(SqlValue::I64(1)).is_err());
+        assert_eq!(Option::<Json<Settings>>::from_sql(SqlValue::Null).unwrap(), None);
+        assert_eq!(Json(Option::<Settings>::None).to_sql().unwrap(), SqlValue::Null);
+    }
+}
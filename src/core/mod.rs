@@ -7,19 +7,52 @@
 //! - **Error types**: Structured error hierarchy for all database operations
 //! - **`SqlValue`**: Type-safe representation of database values
 //! - **Type traits**: `ToSql` and `FromSql` for bidirectional type conversion
-//! - **Configuration**: Connection and runtime configuration
+//! - **`FromRow`**: Conversion of result rows into tuples
+//! - **Configuration**: Connection and runtime configuration (requires `std`)
 //! - **`ParameterType`**: Parameter binding type information
+//! - **`TimezonePolicy`**: Timezone handling for date-time values
+//! - **`TenantStrategy`**: Separation of tenant data (schema, database, table prefix)
+//! - **`ValueRedactionPolicy`**: Hiding sensitive values in logs and errors
+//! - **`Compressed`**: Transparent compression of large values (`gzip`, `zstd`)
+//! - **`Json`**: Typed JSON values via serde (requires `json`)
+//! - **`SqlEnum`**: Rust enums stored as text or integer values, see `sql_enum!`
 
 mod error;
 mod parameter;
 mod sql_value;
+mod hex;
 mod to_sql;
 mod from_sql;
+mod from_row;
+#[cfg(feature = "std")]
 mod config;
+#[cfg(feature = "std")]
+mod env;
+mod timezone;
+mod tenant;
+mod redaction;
+mod compression;
+mod sql_enum;
+#[cfg(feature = "json")]
+mod json;
 
 pub use error::*;
 pub use parameter::*;
 pub use sql_value::*;
 pub use to_sql::*;
 pub use from_sql::*;
+pub use from_row::FromRow;
+#[cfg(feature = "std")]
 pub use config::*;
+pub use timezone::TimezonePolicy;
+pub use tenant::TenantStrategy;
+pub use redaction::ValueRedactionPolicy;
+pub use compression::{Compressed, Compression};
+pub use sql_enum::SqlEnum;
+#[cfg(feature = "json")]
+pub use json::Json;
+#[cfg(feature = "chrono")]
+pub(crate) use timezone::{local_to_utc, utc_to_local};
+pub(crate) use hex::hex_encode;
+#[cfg(any(feature = "schema", all(feature = "query-builder", feature = "sql-parser")))]
+pub(crate) use hex::hex_decode;
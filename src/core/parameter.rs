@@ -2,6 +2,8 @@
 //!
 //! Defines the types used when binding parameters to prepared statements.
 
+use alloc::string::String;
+
 /// Parameter binding type for prepared statements
 ///
 /// This enum indicates how a parameter should be bound to a prepared statement.
@@ -29,6 +31,15 @@ pub enum ParameterType {
 
     /// ASCII-only string (for optimization on some platforms)
     Ascii,
+
+    /// Exact numeric value (DECIMAL/NUMERIC)
+    Decimal,
+
+    /// UUID value
+    Uuid,
+
+    /// JSON document
+    Json,
 }
 
 impl ParameterType {
@@ -51,8 +62,8 @@ impl ParameterType {
     }
 }
 
-impl std::fmt::Display for ParameterType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for ParameterType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Null => write!(f, "NULL"),
             Self::Integer => write!(f, "INTEGER"),
@@ -61,6 +72,9 @@ impl std::fmt::Display for ParameterType {
             Self::Boolean => write!(f, "BOOLEAN"),
             Self::Binary => write!(f, "BINARY"),
             Self::Ascii => write!(f, "ASCII"),
+            Self::Decimal => write!(f, "DECIMAL"),
+            Self::Uuid => write!(f, "UUID"),
+            Self::Json => write!(f, "JSON"),
         }
     }
 }
@@ -135,6 +149,7 @@ mod tests {
         assert_eq!(ParameterType::Integer.to_string(), "INTEGER");
         assert_eq!(ParameterType::String.to_string(), "STRING");
         assert_eq!(ParameterType::Boolean.to_string(), "BOOLEAN");
+        assert_eq!(ParameterType::Decimal.to_string(), "DECIMAL");
     }
 
     #[test]
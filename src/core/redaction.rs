@@ -0,0 +1,321 @@
+//! Redaction of sensitive values in logs, debug SQL and error messages
+//!
+//! A [`ValueRedactionPolicy`] decides which values must never show up in
+//! text meant for humans: values of columns matching a name pattern (like
+//! `password` or `ssn`), values of certain parameter types, and optionally
+//! every string literal in raw SQL.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use super::{Error, ParameterType, QueryError, SqlValue};
+
+/// Which values are hidden from logs, debug SQL and error messages
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValueRedactionPolicy {
+    /// Lower-case substrings of column names whose values are redacted
+    column_patterns: Vec<String>,
+    /// Parameter types whose values are redacted
+    parameter_types: Vec<ParameterType>,
+    /// Whether string literals in raw SQL are redacted
+    sql_literals: bool,
+}
+
+impl ValueRedactionPolicy {
+    /// Text shown in place of a redacted value
+    pub const PLACEHOLDER: &'static str = "***";
+
+    /// Number of characters of a value shown in a parameter summary
+    pub const SUMMARY_VALUE_LENGTH: usize = 32;
+
+    /// Create a policy that redacts nothing
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a policy redacting commonly sensitive columns
+    ///
+    /// Matches `password`, `passwd`, `secret`, `token`, `api_key`, `ssn`,
+    /// `credit_card` and `iban`.
+    #[must_use]
+    pub fn sensitive_columns() -> Self {
+        ["password", "passwd", "secret", "token", "api_key", "ssn", "credit_card", "iban"]
+            .into_iter()
+            .fold(Self::new(), Self::with_column_pattern)
+    }
+
+    /// Redact values of columns whose name contains the pattern (case-insensitive)
+    #[must_use]
+    pub fn with_column_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.column_patterns.push(pattern.into().to_lowercase());
+        self
+    }
+
+    /// Redact all values of a parameter type
+    #[must_use]
+    pub fn with_parameter_type(mut self, parameter_type: ParameterType) -> Self {
+        self.parameter_types.push(parameter_type);
+        self
+    }
+
+    /// Redact every string literal in raw SQL
+    ///
+    /// Raw SQL gives no reliable link between a literal and its column, so
+    /// this is the only way to keep every inlined value out of logged SQL
+    /// and error messages. Without it, column patterns still redact all
+    /// string literals of SQL that mentions a matching column.
+    #[must_use]
+    pub const fn with_sql_literals(mut self, redact: bool) -> Self {
+        self.sql_literals = redact;
+        self
+    }
+
+    /// Check if the policy redacts anything
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.sql_literals || !self.column_patterns.is_empty() || !self.parameter_types.is_empty()
+    }
+
+    /// Check if values of a column are redacted
+    #[must_use]
+    pub fn redacts_column(&self, column: &str) -> bool {
+        if self.column_patterns.is_empty() {
+            return false;
+        }
+        let column = column.to_lowercase();
+        self.column_patterns.iter().any(|pattern| column.contains(pattern.as_str()))
+    }
+
+    /// Check if a value, optionally of a known column, is redacted
+    #[must_use]
+    pub fn redacts(&self, column: Option<&str>, value: &SqlValue) -> bool {
+        !value.is_null()
+            && (column.is_some_and(|c| self.redacts_column(c))
+                || self.parameter_types.contains(&value.param_type()))
+    }
+
+    /// Get the value to show for a value, the placeholder if it is redacted
+    #[must_use]
+    pub fn redact_value(&self, column: Option<&str>, value: &SqlValue) -> SqlValue {
+        if self.redacts(column, value) {
+            SqlValue::String(Self::PLACEHOLDER.to_string())
+        } else {
+            value.clone()
+        }
+    }
+
+    /// Redact a list of bound parameters (by parameter type)
+    #[must_use]
+    pub fn redact_params(&self, params: &[SqlValue]) -> Vec<SqlValue> {
+        params.iter().map(|value| self.redact_value(None, value)).collect()
+    }
+
+    /// Summarize bound parameters for error messages
+    ///
+    /// Lists each parameter as 1-based position, type and value, e.g.
+    /// `#1 INTEGER=42, #2 STRING='alice'`, like the query log. Values are cut after
+    /// [`SUMMARY_VALUE_LENGTH`](Self::SUMMARY_VALUE_LENGTH) characters and
+    /// redacted ones replaced by the placeholder.
+    #[must_use]
+    pub fn summarize_params(&self, params: &[SqlValue]) -> String {
+        self.summarize_labeled(
+            params
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (format!("#{}", index + 1), value.param_type(), value)),
+        )
+    }
+
+    /// Summarize parameters under their own labels and types
+    ///
+    /// Like [`summarize_params`](Self::summarize_params), for parameters
+    /// bound by name or with an explicit type, e.g. `:name STRING='alice'`.
+    #[must_use]
+    pub fn summarize_labeled<'a>(
+        &self,
+        params: impl IntoIterator<Item = (String, ParameterType, &'a SqlValue)>,
+    ) -> String {
+        let mut summary = String::new();
+        for (label, param_type, value) in params {
+            if !summary.is_empty() {
+                summary.push_str(", ");
+            }
+            let shown = if self.redacts(None, value) {
+                Self::PLACEHOLDER.to_string()
+            } else {
+                value.to_string()
+            };
+            let _ = write!(summary, "{label} {param_type}=");
+            match shown.char_indices().nth(Self::SUMMARY_VALUE_LENGTH) {
+                Some((end, _)) => {
+                    summary.push_str(&shown[..end]);
+                    summary.push('…');
+                }
+                None => summary.push_str(&shown),
+            }
+        }
+        summary
+    }
+
+    /// Check if the string literals of raw SQL are redacted
+    ///
+    /// They are with [`with_sql_literals`](Self::with_sql_literals), and
+    /// when the SQL mentions a column matching a pattern, as any of its
+    /// literals may be that column's value. Statements setting a password
+    /// (`PASSWORD '...'`, `IDENTIFIED BY '...'`) are redacted under every
+    /// policy, including one that redacts nothing else.
+    #[must_use]
+    pub fn redacts_sql(&self, sql: &str) -> bool {
+        self.sql_literals || sets_password(sql) || self.redacts_column(sql)
+    }
+
+    /// Redact the string literals in raw SQL, if [`redacts_sql`](Self::redacts_sql)
+    #[must_use]
+    pub fn redact_sql(&self, sql: &str) -> String {
+        if !self.redacts_sql(sql) {
+            return sql.to_string();
+        }
+        Self::redact_literals(sql)
+    }
+
+    /// Replace every string literal in SQL with the placeholder
+    fn redact_literals(sql: &str) -> String {
+        let mut redacted = String::with_capacity(sql.len());
+        let mut chars = sql.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\'' {
+                redacted.push(c);
+                continue;
+            }
+            // Skip to the closing quote, `''` is an escaped quote
+            while let Some(c) = chars.next() {
+                if c == '\'' {
+                    if chars.peek() == Some(&'\'') {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            redacted.push('\'');
+            redacted.push_str(Self::PLACEHOLDER);
+            redacted.push('\'');
+        }
+        redacted
+    }
+
+    /// Redact the SQL and message of a query execution error
+    ///
+    /// The message, which may quote literals of the SQL, is redacted along
+    /// with the SQL when [`redacts_sql`](Self::redacts_sql) holds for it.
+    #[must_use]
+    pub fn redact_error(&self, error: Error) -> Error {
+        match error {
+            Error::Query(QueryError::ExecutionFailed { message, sql, full_sql, params })
+                if self.sql_literals || sql.iter().chain(&full_sql).any(|sql| self.redacts_sql(sql)) =>
+            {
+                QueryError::ExecutionFailed {
+                    message: Self::redact_literals(&message),
+                    sql: sql.map(|sql| Self::redact_literals(&sql)),
+                    full_sql: full_sql.map(|sql| Self::redact_literals(&sql)),
+                    params,
+                }
+                .into()
+            }
+            other => other,
+        }
+    }
+}
+
+/// Check if SQL sets a password, which must never be logged
+fn sets_password(sql: &str) -> bool {
+    let sql = sql.to_ascii_lowercase();
+    sql.contains("password") || sql.contains("identified by")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_columns_and_types() {
+        let policy = ValueRedactionPolicy::sensitive_columns().with_parameter_type(ParameterType::Binary);
+        let secret = SqlValue::String("hunter2".to_string());
+
+        assert!(policy.redacts(Some("user_Password"), &secret));
+        assert!(!policy.redacts(Some("name"), &secret));
+        assert!(!policy.redacts(Some("password"), &SqlValue::Null));
+        assert!(policy.redacts(None, &SqlValue::Bytes(vec![1])));
+        assert_eq!(
+            policy.redact_params(&[SqlValue::I64(1), SqlValue::Bytes(vec![1])]),
+            [SqlValue::I64(1), SqlValue::String("***".to_string())]
+        );
+        assert!(!ValueRedactionPolicy::new().is_active());
+    }
+
+    #[test]
+    fn test_summarize_params() {
+        let params = [SqlValue::I64(42), SqlValue::String("x".repeat(40)), SqlValue::Bytes(vec![1]), SqlValue::Null];
+        let summary = ValueRedactionPolicy::new().with_parameter_type(ParameterType::Binary).summarize_params(&params);
+        assert_eq!(
+            summary,
+            format!("#1 INTEGER=42, #2 STRING='{}…, #3 BINARY=***, #4 NULL=NULL", "x".repeat(31))
+        );
+        assert_eq!(ValueRedactionPolicy::new().summarize_params(&[]), "");
+
+        let named = SqlValue::String("alice".to_string());
+        let summary = ValueRedactionPolicy::new().summarize_labeled([
+            (":name".to_string(), ParameterType::Ascii, &named),
+            ("#2".to_string(), ParameterType::Null, &SqlValue::Null),
+        ]);
+        assert_eq!(summary, ":name ASCII='alice', #2 NULL=NULL");
+    }
+
+    #[test]
+    fn test_redact_sql() {
+        let policy = ValueRedactionPolicy::new().with_sql_literals(true);
+        assert_eq!(
+            policy.redact_sql("UPDATE users SET password = 'it''s secret' WHERE id = 1"),
+            "UPDATE users SET password = '***' WHERE id = 1"
+        );
+        assert_eq!(policy.redact_sql("SELECT 'unterminated"), "SELECT '***'");
+        assert_eq!(ValueRedactionPolicy::new().redact_sql("SELECT 'a'"), "SELECT 'a'");
+
+        let error = policy.redact_error(
+            QueryError::execution_failed("near 'x'", Some("SELECT 'x'".to_string()))
+            .into(),
+        );
+        assert!(matches!(
+            error,
+            Error::Query(QueryError::ExecutionFailed { ref message, sql: Some(ref sql), .. })
+                if message == "near '***'" && sql == "SELECT '***'"
+        ));
+
+        // Column patterns redact the literals of SQL mentioning the column
+        let policy = ValueRedactionPolicy::new().with_column_pattern("ssn");
+        assert_eq!(
+            policy.redact_sql("INSERT INTO users (name, SSN) VALUES ('bob', '078-05-1120')"),
+            "INSERT INTO users (name, SSN) VALUES ('***', '***')"
+        );
+        assert_eq!(policy.redact_sql("SELECT 'a' FROM users"), "SELECT 'a' FROM users");
+        let error = policy.redact_error(
+            QueryError::execution_failed("no such table: '078-05-1120'", Some("UPDATE users SET ssn = '078-05-1120'".to_string()))
+            .into(),
+        );
+        assert!(!error.to_string().contains("078-05-1120"), "{error}");
+
+        // Passwords are redacted even by a policy that redacts nothing else
+        let policy = ValueRedactionPolicy::new();
+        assert_eq!(
+            policy.redact_sql("CREATE ROLE \"app\" LOGIN PASSWORD 'it''s'"),
+            "CREATE ROLE \"app\" LOGIN PASSWORD '***'"
+        );
+        assert_eq!(
+            policy.redact_sql("CREATE USER 'app' IDENTIFIED BY 'secret'"),
+            "CREATE USER '***' IDENTIFIED BY '***'"
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Rust enums stored as text or integer columns
+//!
+//! [`sql_enum!`](crate::sql_enum) declares an enum together with the value
+//! each variant is stored as and implements [`SqlEnum`], `ToSql` and
+//! `FromSql` for it. [`Column::sql_enum`](crate::platform::Column::sql_enum)
+//! restricts a column to those values.
+
+use alloc::{format, vec::Vec};
+use core::cmp::Ordering;
+use super::from_sql::value_type_name;
+use super::{Error, SqlValue};
+
+/// A Rust enum stored as one of a fixed set of values
+///
+/// Implemented by [`sql_enum!`](crate::sql_enum).
+pub trait SqlEnum: Sized + 'static {
+    /// All variants, in declaration order
+    const VARIANTS: &'static [Self];
+
+    /// Get the value this variant is stored as
+    fn sql_value(&self) -> SqlValue;
+
+    /// Get the stored values of all variants, in declaration order
+    #[must_use]
+    fn sql_values() -> Vec<SqlValue> {
+        Self::VARIANTS.iter().map(Self::sql_value).collect()
+    }
+
+    /// Get the position in [`VARIANTS`](Self::VARIANTS) of the variant
+    /// stored as a value
+    ///
+    /// Integers match across widths, so a variant stored as `1` is found
+    /// for `I64(1)` read back from the database.
+    fn variant_index(value: &SqlValue) -> Option<usize> {
+        Self::VARIANTS
+            .iter()
+            .position(|variant| variant.sql_value().compare(value) == Some(Ordering::Equal))
+    }
+
+    /// Get the error for a value no variant is stored as
+    #[must_use]
+    fn unknown_value(value: &SqlValue) -> Error {
+        Error::conversion(
+            value_type_name(value),
+            core::any::type_name::<Self>(),
+            format!("no variant is stored as {value}"),
+        )
+    }
+}
+
+/// Declare an enum stored as text or integer values
+///
+/// Each variant names the literal it is stored as. The enum gets
+/// [`SqlEnum`](crate::core::SqlEnum), `ToSql` and `FromSql`; reading a value
+/// no variant is stored as is a conversion error.
+///
+/// ```rust
+/// use rustine_dbal::core::{FromSql, SqlEnum, ToSql};
+/// use rustine_dbal::SqlValue;
+///
+/// rustine_dbal::sql_enum! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub enum Status {
+///         Active = "active",
+///         Banned = "banned",
+///     }
+/// }
+///
+/// assert_eq!(Status::Banned.to_sql().unwrap(), SqlValue::from("banned"));
+/// assert_eq!(Status::from_sql(SqlValue::from("active")).unwrap(), Status::Active);
+/// assert!(Status::from_sql(SqlValue::from("deleted")).is_err());
+/// assert_eq!(Status::sql_values(), vec![SqlValue::from("active"), SqlValue::from("banned")]);
+/// ```
+#[macro_export]
+macro_rules! sql_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant),+
+        }
+
+        impl $crate::core::SqlEnum for $name {
+            const VARIANTS: &'static [Self] = &[$(Self::$variant),+];
+
+            fn sql_value(&self) -> $crate::core::SqlValue {
+                match self {
+                    $(Self::$variant => $crate::core::SqlValue::from($value)),+
+                }
+            }
+        }
+
+        impl $crate::core::ToSql for $name {
+            fn to_sql(&self) -> $crate::core::Result<$crate::core::SqlValue> {
+                Ok($crate::core::SqlEnum::sql_value(self))
+            }
+        }
+
+        impl $crate::core::FromSql for $name {
+            fn from_sql(value: $crate::core::SqlValue) -> $crate::core::Result<Self> {
+                let variants = <Self as $crate::core::SqlEnum>::VARIANTS;
+                match <Self as $crate::core::SqlEnum>::variant_index(&value) {
+                    $(Some(index) if matches!(variants[index], Self::$variant) => Ok(Self::$variant),)+
+                    _ => Err(<Self as $crate::core::SqlEnum>::unknown_value(&value)),
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{FromSql, ToSql};
+    use alloc::vec;
+
+    crate::sql_enum! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Priority {
+            Low = 1,
+            /// Default priority
+            Normal = 2,
+            High = 3,
+        }
+    }
+
+    crate::sql_enum! {
+        #[derive(Debug, PartialEq)]
+        enum Role {
+            Admin = "admin",
+            Guest = "guest",
+        }
+    }
+
+    #[test]
+    fn test_integer_enum() {
+        assert_eq!(Priority::High.to_sql().unwrap(), SqlValue::I32(3));
+        assert_eq!(Priority::from_sql(SqlValue::I64(2)).unwrap(), Priority::Normal);
+        assert_eq!(Priority::from_sql(SqlValue::I16(1)).unwrap(), Priority::Low);
+        assert_eq!(Priority::sql_values(), vec![SqlValue::I32(1), SqlValue::I32(2), SqlValue::I32(3)]);
+        assert_eq!(Option::<Priority>::from_sql(SqlValue::Null).unwrap(), None);
+        assert!(Priority::from_sql(SqlValue::I64(4)).is_err());
+    }
+
+    #[test]
+    fn test_text_enum() {
+        assert_eq!(Role::Guest.to_sql().unwrap(), SqlValue::from("guest"));
+        assert_eq!(Role::from_sql(SqlValue::from("admin")).unwrap(), Role::Admin);
+        let error = Role::from_sql(SqlValue::from("root")).unwrap_err().to_string();
+        assert!(error.contains("no variant is stored as 'root'"), "{error}");
+        assert!(Role::from_sql(SqlValue::I64(1)).is_err());
+    }
+}
@@ -3,7 +3,10 @@
 //! The [`SqlValue`] enum provides a type-safe representation of all values
 //! that can be stored in or retrieved from a database.
 
-use super::ParameterType;
+use alloc::{borrow::ToOwned, format, string::{String, ToString}, vec::Vec};
+use core::cmp::Ordering;
+use super::from_sql::value_type_name;
+use super::{hex_encode, Error, ParameterType, Result};
 
 /// A database value that can represent any SQL type
 ///
@@ -11,6 +14,7 @@ use super::ParameterType;
 /// Rust types and database types. It provides a unified interface for
 /// handling all SQL values regardless of the underlying database platform.
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SqlValue {
     /// SQL NULL value
     #[default]
@@ -65,6 +69,10 @@ pub enum SqlValue {
     #[cfg(feature = "chrono")]
     DateTimeUtc(chrono::DateTime<chrono::Utc>),
 
+    /// `DateTime` value with a fixed UTC offset (TIMESTAMPTZ)
+    #[cfg(feature = "chrono")]
+    DateTimeTz(chrono::DateTime<chrono::FixedOffset>),
+
     /// UUID value
     #[cfg(feature = "uuid")]
     Uuid(uuid::Uuid),
@@ -85,6 +93,16 @@ impl SqlValue {
         matches!(self, Self::Null)
     }
 
+    /// Get the value of a NaN or infinite float, `None` for all other values
+    #[must_use]
+    pub fn non_finite_float(&self) -> Option<f64> {
+        match self {
+            Self::F32(f) if !f.is_finite() => Some(f64::from(*f)),
+            Self::F64(f) if !f.is_finite() => Some(*f),
+            _ => None,
+        }
+    }
+
     /// Get the parameter type for this value
     #[must_use]
     pub const fn param_type(&self) -> ParameterType {
@@ -97,15 +115,17 @@ impl SqlValue {
             Self::String(_) => ParameterType::String,
             Self::Bytes(_) => ParameterType::Binary,
             #[cfg(feature = "chrono")]
-            Self::Date(_) | Self::Time(_) | Self::DateTime(_) | Self::DateTimeUtc(_) => {
-                ParameterType::String
-            }
+            Self::Date(_)
+            | Self::Time(_)
+            | Self::DateTime(_)
+            | Self::DateTimeUtc(_)
+            | Self::DateTimeTz(_) => ParameterType::String,
             #[cfg(feature = "uuid")]
-            Self::Uuid(_) => ParameterType::String,
+            Self::Uuid(_) => ParameterType::Uuid,
             #[cfg(feature = "json")]
-            Self::Json(_) => ParameterType::String,
+            Self::Json(_) => ParameterType::Json,
             #[cfg(feature = "decimal")]
-            Self::Decimal(_) => ParameterType::String,
+            Self::Decimal(_) => ParameterType::Decimal,
         }
     }
 
@@ -149,6 +169,9 @@ impl SqlValue {
     }
 
     /// Try to get as f64
+    ///
+    /// Integers beyond 2^53 are rounded, see
+    /// [`try_as_f64_exact`](Self::try_as_f64_exact) for a checked conversion.
     #[allow(clippy::cast_precision_loss)]
     #[must_use]
     pub fn as_f64(&self) -> Option<f64> {
@@ -163,6 +186,60 @@ impl SqlValue {
         }
     }
 
+    /// Get as f64 if the value is represented exactly
+    ///
+    /// # Errors
+    ///
+    /// Returns a conversion error for integers that would be rounded and
+    /// for non-numeric values.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn try_as_f64_exact(&self) -> Result<f64> {
+        match self {
+            Self::F32(f) => Ok(f64::from(*f)),
+            Self::F64(f) => Ok(*f),
+            Self::I8(i) => Ok(f64::from(*i)),
+            Self::I16(i) => Ok(f64::from(*i)),
+            Self::I32(i) => Ok(f64::from(*i)),
+            Self::U32(u) => Ok(f64::from(*u)),
+            Self::I64(i) if fits_mantissa(i.unsigned_abs(), f64::MANTISSA_DIGITS) => Ok(*i as f64),
+            Self::U64(u) if fits_mantissa(*u, f64::MANTISSA_DIGITS) => Ok(*u as f64),
+            _ => Err(self.inexact_float_error("f64")),
+        }
+    }
+
+    /// Get as f32 if the value is represented exactly
+    ///
+    /// # Errors
+    ///
+    /// Returns a conversion error for doubles and integers that would be
+    /// rounded and for non-numeric values.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::float_cmp)]
+    pub fn try_as_f32_exact(&self) -> Result<f32> {
+        match self {
+            Self::F32(f) => Ok(*f),
+            Self::F64(f) if f.is_nan() || f64::from(*f as f32) == *f => Ok(*f as f32),
+            Self::I8(i) => Ok(f32::from(*i)),
+            Self::I16(i) => Ok(f32::from(*i)),
+            Self::I32(i) if fits_mantissa(u64::from(i.unsigned_abs()), f32::MANTISSA_DIGITS) => Ok(*i as f32),
+            Self::I64(i) if fits_mantissa(i.unsigned_abs(), f32::MANTISSA_DIGITS) => Ok(*i as f32),
+            Self::U32(u) if fits_mantissa(u64::from(*u), f32::MANTISSA_DIGITS) => Ok(*u as f32),
+            Self::U64(u) if fits_mantissa(*u, f32::MANTISSA_DIGITS) => Ok(*u as f32),
+            _ => Err(self.inexact_float_error("f32")),
+        }
+    }
+
+    /// Create the error for a value without an exact float representation
+    fn inexact_float_error(&self, target: &'static str) -> Error {
+        match self {
+            Self::F64(_) | Self::I32(_) | Self::I64(_) | Self::U32(_) | Self::U64(_) => Error::conversion(
+                value_type_name(self),
+                target,
+                format!("{self} is not exactly representable as {target}"),
+            ),
+            _ => Error::conversion(value_type_name(self), target, format!("cannot convert to {target}")),
+        }
+    }
+
     /// Try to get as string reference
     #[must_use]
     pub fn as_str(&self) -> Option<&str> {
@@ -254,6 +331,16 @@ impl SqlValue {
         }
     }
 
+    /// Get as `DateTime<FixedOffset>`
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub const fn as_datetime_tz(&self) -> Option<&chrono::DateTime<chrono::FixedOffset>> {
+        match self {
+            Self::DateTimeTz(dt) => Some(dt),
+            _ => None,
+        }
+    }
+
     /// Get as Decimal
     #[cfg(feature = "decimal")]
     #[must_use]
@@ -263,10 +350,78 @@ impl SqlValue {
             _ => None,
         }
     }
+
+    /// Compare two values the way the database orders them
+    ///
+    /// Numbers compare by value across integer, float and decimal types,
+    /// date-times with offset by instant. Returns `None` for NULL, NaN,
+    /// JSON and values of unrelated types.
+    #[must_use]
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Null, _) | (_, Self::Null) => None,
+            (Self::Bool(a), Self::Bool(b)) => Some(a.cmp(b)),
+            (Self::String(a), Self::String(b)) => Some(a.cmp(b)),
+            (Self::Bytes(a), Self::Bytes(b)) => Some(a.cmp(b)),
+            #[cfg(feature = "chrono")]
+            (Self::Date(a), Self::Date(b)) => Some(a.cmp(b)),
+            #[cfg(feature = "chrono")]
+            (Self::Time(a), Self::Time(b)) => Some(a.cmp(b)),
+            #[cfg(feature = "chrono")]
+            (Self::DateTime(a), Self::DateTime(b)) => Some(a.cmp(b)),
+            #[cfg(feature = "chrono")]
+            (Self::DateTimeUtc(_) | Self::DateTimeTz(_), Self::DateTimeUtc(_) | Self::DateTimeTz(_)) => {
+                Some(self.instant()?.cmp(&other.instant()?))
+            }
+            #[cfg(feature = "uuid")]
+            (Self::Uuid(a), Self::Uuid(b)) => Some(a.cmp(b)),
+            #[cfg(feature = "decimal")]
+            (Self::Decimal(_), _) | (_, Self::Decimal(_)) => Some(self.decimal()?.cmp(&other.decimal()?)),
+            _ => match (self.integer(), other.integer()) {
+                (Some(a), Some(b)) => Some(a.cmp(&b)),
+                _ => self.as_f64()?.partial_cmp(&other.as_f64()?),
+            },
+        }
+    }
+
+    /// Get an integer value of any width
+    fn integer(&self) -> Option<i128> {
+        match self {
+            Self::I8(i) => Some(i128::from(*i)),
+            Self::I16(i) => Some(i128::from(*i)),
+            Self::I32(i) => Some(i128::from(*i)),
+            Self::I64(i) => Some(i128::from(*i)),
+            Self::U32(u) => Some(i128::from(*u)),
+            Self::U64(u) => Some(i128::from(*u)),
+            _ => None,
+        }
+    }
+
+    /// Get a date-time with offset as UTC instant
+    #[cfg(feature = "chrono")]
+    fn instant(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self {
+            Self::DateTimeUtc(dt) => Some(*dt),
+            Self::DateTimeTz(dt) => Some(dt.with_timezone(&chrono::Utc)),
+            _ => None,
+        }
+    }
+
+    /// Get a number as decimal
+    #[cfg(feature = "decimal")]
+    fn decimal(&self) -> Option<rust_decimal::Decimal> {
+        match self {
+            Self::Decimal(d) => Some(*d),
+            _ => match self.integer() {
+                Some(i) => rust_decimal::Decimal::try_from_i128_with_scale(i, 0).ok(),
+                None => rust_decimal::Decimal::try_from(self.as_f64()?).ok(),
+            },
+        }
+    }
 }
 
-impl std::fmt::Display for SqlValue {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for SqlValue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Null => write!(f, "NULL"),
             Self::Bool(b) => write!(f, "{b}"),
@@ -288,23 +443,22 @@ impl std::fmt::Display for SqlValue {
             Self::DateTime(dt) => write!(f, "'{dt}'"),
             #[cfg(feature = "chrono")]
             Self::DateTimeUtc(dt) => write!(f, "'{dt}'"),
+            #[cfg(feature = "chrono")]
+            Self::DateTimeTz(dt) => write!(f, "'{}'", dt.to_rfc3339()),
             #[cfg(feature = "uuid")]
             Self::Uuid(u) => write!(f, "'{u}'"),
             #[cfg(feature = "json")]
             Self::Json(j) => write!(f, "'{j}'"),
+            // Quoted so the literal keeps its exact value instead of becoming a float
             #[cfg(feature = "decimal")]
-            Self::Decimal(d) => write!(f, "{d}"),
+            Self::Decimal(d) => write!(f, "'{d}'"),
         }
     }
 }
 
-/// Simple hex encoding for bytes display
-fn hex_encode(bytes: &[u8]) -> String {
-    use std::fmt::Write;
-    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut acc, b| {
-        let _ = write!(acc, "{b:02x}");
-        acc
-    })
+/// Check whether an integer magnitude fits a float mantissa of `digits` bits
+const fn fits_mantissa(magnitude: u64, digits: u32) -> bool {
+    magnitude == 0 || 64 - magnitude.leading_zeros() - magnitude.trailing_zeros() <= digits
 }
 
 // Convenient From implementations
@@ -426,6 +580,20 @@ impl From<chrono::DateTime<chrono::Utc>> for SqlValue {
     }
 }
 
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::FixedOffset>> for SqlValue {
+    fn from(value: chrono::DateTime<chrono::FixedOffset>) -> Self {
+        Self::DateTimeTz(value)
+    }
+}
+
+#[cfg(all(feature = "chrono", feature = "std"))]
+impl From<chrono::DateTime<chrono::Local>> for SqlValue {
+    fn from(value: chrono::DateTime<chrono::Local>) -> Self {
+        Self::DateTimeTz(value.fixed_offset())
+    }
+}
+
 #[cfg(feature = "uuid")]
 impl From<uuid::Uuid> for SqlValue {
     fn from(value: uuid::Uuid) -> Self {
@@ -471,6 +639,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_exact_float_conversions() {
+        assert_eq!(SqlValue::I64(1 << 53).try_as_f64_exact().unwrap(), 9_007_199_254_740_992.0);
+        assert_eq!(SqlValue::I64(-(1 << 60)).try_as_f64_exact().unwrap(), -1_152_921_504_606_846_976.0);
+        assert!(SqlValue::I64((1 << 53) + 1).try_as_f64_exact().is_err());
+        assert!(SqlValue::U64(u64::MAX).try_as_f64_exact().is_err());
+        assert!(SqlValue::String("1".into()).try_as_f64_exact().is_err());
+
+        assert_eq!(SqlValue::F64(0.5).try_as_f32_exact().unwrap(), 0.5);
+        assert!(SqlValue::F64(0.1).try_as_f32_exact().is_err());
+        assert!(SqlValue::F64(1e40).try_as_f32_exact().is_err());
+        assert!(SqlValue::F64(f64::NAN).try_as_f32_exact().unwrap().is_nan());
+        assert_eq!(SqlValue::I32(1 << 24).try_as_f32_exact().unwrap(), 16_777_216.0);
+        let error = SqlValue::I32((1 << 24) + 1).try_as_f32_exact().unwrap_err().to_string();
+        assert!(error.contains("not exactly representable as f32"), "{error}");
+    }
+
     #[test]
     fn test_sql_value_option() {
         let some_val: Option<i32> = Some(42);
@@ -520,12 +705,45 @@ mod tests {
         assert_eq!(SqlValue::Null.into_string(), None);
     }
 
+    #[test]
+    fn test_sql_value_compare() {
+        assert_eq!(SqlValue::I32(2).compare(&SqlValue::I64(10)), Some(Ordering::Less));
+        assert_eq!(SqlValue::U64(u64::MAX).compare(&SqlValue::I64(-1)), Some(Ordering::Greater));
+        assert_eq!(SqlValue::F64(1.5).compare(&SqlValue::I32(1)), Some(Ordering::Greater));
+        assert_eq!(SqlValue::from("b").compare(&SqlValue::from("a")), Some(Ordering::Greater));
+        assert_eq!(SqlValue::Null.compare(&SqlValue::Null), None);
+        assert_eq!(SqlValue::F64(f64::NAN).compare(&SqlValue::F64(1.0)), None);
+        assert_eq!(SqlValue::from("1").compare(&SqlValue::I32(1)), None);
+    }
+
     #[cfg(feature = "uuid")]
     #[test]
     fn test_sql_value_uuid() {
-        let uuid = uuid::Uuid::new_v4();
+        let uuid = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
         let value = SqlValue::from(uuid);
         assert_eq!(value.as_uuid(), Some(&uuid));
-        assert_eq!(value.param_type(), ParameterType::String);
+        assert_eq!(value.param_type(), ParameterType::Uuid);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_sql_value_datetime_tz() {
+        let dt = chrono::DateTime::parse_from_rfc3339("2024-01-15T12:00:00+02:00").unwrap();
+        let value = SqlValue::from(dt);
+        assert_eq!(value.as_datetime_tz(), Some(&dt));
+        assert_eq!(value.to_string(), "'2024-01-15T12:00:00+02:00'");
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_sql_value_decimal() {
+        let value = SqlValue::from(rust_decimal::Decimal::new(1250, 2));
+        assert_eq!(value.param_type(), ParameterType::Decimal);
+        assert_eq!(value.to_string(), "'12.50'");
+        assert_eq!(value.into_string(), Some("12.50".to_string()));
+        assert_eq!(
+            SqlValue::from(rust_decimal::Decimal::new(1250, 2)).compare(&SqlValue::I32(13)),
+            Some(Ordering::Less)
+        );
     }
 }
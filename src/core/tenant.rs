@@ -0,0 +1,67 @@
+//! Multi-tenancy strategies
+//!
+//! A [`TenantStrategy`] describes how the data of one tenant is separated
+//! from the others. Schema and database strategies are applied to the
+//! connection (see `Connection::use_tenant`), the table prefix strategy
+//! rewrites table names in the query builder (see `QueryBuilder::tenant`).
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+
+/// How the data of a tenant is separated from other tenants
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TenantStrategy {
+    /// The tenant has its own schema (`search_path` on `PostgreSQL`)
+    Schema(String),
+    /// The tenant has its own database
+    ///
+    /// `MySQL` switches databases on the connection, other platforms connect
+    /// to it via `ConnectionParams::for_tenant`.
+    Database(String),
+    /// Tenants share a database, the tenant's tables carry this prefix
+    TablePrefix(String),
+}
+
+impl TenantStrategy {
+    /// Create a schema-per-tenant strategy
+    #[must_use]
+    pub fn schema(schema: impl Into<String>) -> Self {
+        Self::Schema(schema.into())
+    }
+
+    /// Create a database-per-tenant strategy
+    #[must_use]
+    pub fn database(database: impl Into<String>) -> Self {
+        Self::Database(database.into())
+    }
+
+    /// Create a table-prefix strategy
+    #[must_use]
+    pub fn table_prefix(prefix: impl Into<String>) -> Self {
+        Self::TablePrefix(prefix.into())
+    }
+
+    /// Get the name of a table for this tenant
+    ///
+    /// Only the table prefix strategy changes the name.
+    #[must_use]
+    pub fn table_name<'a>(&self, table: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::TablePrefix(prefix) => Cow::Owned(format!("{prefix}{table}")),
+            Self::Schema(_) | Self::Database(_) => Cow::Borrowed(table),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_name() {
+        assert_eq!(TenantStrategy::table_prefix("acme_").table_name("users"), "acme_users");
+        assert_eq!(TenantStrategy::schema("acme").table_name("users"), "users");
+        assert_eq!(TenantStrategy::database("acme").table_name("users"), "users");
+    }
+}
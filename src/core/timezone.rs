@@ -0,0 +1,151 @@
+//! Timezone handling policy for date-time values
+//!
+//! Databases differ in how they store timestamps without an explicit offset.
+//! The [`TimezonePolicy`] decides how `DateTime<Utc>` and `NaiveDateTime`
+//! are converted when written via [`ToSql::to_sql_with`](super::ToSql::to_sql_with)
+//! and read via [`FromSql::from_sql_with`](super::FromSql::from_sql_with).
+//!
+//! A connection applies the policy of its configuration
+//! (`Configuration::timezone_policy`) to every parameter it binds, see
+//! [`TimezonePolicy::apply`], to the values of its `insert` and `update`
+//! helpers, and to rows read with `fetch_all_as` (through
+//! [`FromRow::from_row_with`](super::FromRow::from_row_with)). Values bound
+//! there are plain [`ToSql::to_sql`](super::ToSql::to_sql) output; values
+//! inlined into SQL, like criteria conditions, are rendered as given.
+
+use super::SqlValue;
+
+/// How date-time values without an offset are stored in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimezonePolicy {
+    /// Values are stored as they are, without any conversion
+    #[default]
+    PassThrough,
+    /// Values are stored in UTC
+    ///
+    /// `NaiveDateTime` is interpreted as local time and converted to UTC
+    /// on write, and converted back to local time on read.
+    StoreUtc,
+    /// Values are stored in the local timezone of the application
+    ///
+    /// `DateTime<Utc>` is converted to local time on write, and back to
+    /// UTC on read.
+    StoreLocal,
+}
+
+impl TimezonePolicy {
+    /// Session timezone implied by this policy (used if none is configured)
+    #[must_use]
+    pub const fn session_time_zone(self) -> Option<&'static str> {
+        match self {
+            Self::StoreUtc => Some("UTC"),
+            Self::PassThrough | Self::StoreLocal => None,
+        }
+    }
+
+    /// Convert a value for writing under this policy
+    ///
+    /// `SqlValue::DateTime` and `SqlValue::DateTimeUtc` are converted like
+    /// `NaiveDateTime` and `DateTime<Utc>` in
+    /// [`ToSql::to_sql_with`](super::ToSql::to_sql_with); other values are
+    /// returned unchanged.
+    #[must_use]
+    #[cfg_attr(not(feature = "chrono"), allow(clippy::missing_const_for_fn))]
+    pub fn apply(self, value: SqlValue) -> SqlValue {
+        match (self, value) {
+            // Naive values are local time
+            #[cfg(feature = "chrono")]
+            (Self::StoreUtc, SqlValue::DateTime(naive)) => {
+                SqlValue::DateTime(local_to_utc(naive).naive_utc())
+            }
+            #[cfg(feature = "chrono")]
+            (Self::StoreUtc, SqlValue::DateTimeUtc(utc)) => SqlValue::DateTime(utc.naive_utc()),
+            #[cfg(feature = "chrono")]
+            (Self::StoreLocal, SqlValue::DateTimeUtc(utc)) => SqlValue::DateTime(utc_to_local(utc)),
+            (_, value) => value,
+        }
+    }
+}
+
+/// Interpret a naive date-time as local time and convert it to UTC
+///
+/// Ambiguous local times (DST fold) resolve to the earlier instant; local
+/// times that don't exist (DST gap) are taken as UTC.
+#[cfg(all(feature = "chrono", feature = "std"))]
+pub fn local_to_utc(naive: chrono::NaiveDateTime) -> chrono::DateTime<chrono::Utc> {
+    use chrono::TimeZone;
+
+    chrono::Local.from_local_datetime(&naive).earliest().map_or_else(
+        || chrono::Utc.from_utc_datetime(&naive),
+        |local| local.with_timezone(&chrono::Utc),
+    )
+}
+
+/// Convert a UTC date-time to a naive local date-time
+#[cfg(all(feature = "chrono", feature = "std"))]
+pub fn utc_to_local(utc: chrono::DateTime<chrono::Utc>) -> chrono::NaiveDateTime {
+    utc.with_timezone(&chrono::Local).naive_local()
+}
+
+/// Interpret a naive date-time as UTC, there is no local timezone without `std`
+#[cfg(all(feature = "chrono", not(feature = "std")))]
+pub const fn local_to_utc(naive: chrono::NaiveDateTime) -> chrono::DateTime<chrono::Utc> {
+    naive.and_utc()
+}
+
+/// Get the naive UTC date-time, there is no local timezone without `std`
+#[cfg(all(feature = "chrono", not(feature = "std")))]
+pub const fn utc_to_local(utc: chrono::DateTime<chrono::Utc>) -> chrono::NaiveDateTime {
+    utc.naive_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_time_zone() {
+        assert_eq!(TimezonePolicy::StoreUtc.session_time_zone(), Some("UTC"));
+        assert_eq!(TimezonePolicy::PassThrough.session_time_zone(), None);
+        assert_eq!(TimezonePolicy::default(), TimezonePolicy::PassThrough);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_local_round_trip() {
+        let naive = chrono::NaiveDate::from_ymd_opt(2024, 1, 15)
+            .and_then(|d| d.and_hms_opt(12, 0, 0))
+            .unwrap();
+
+        assert_eq!(utc_to_local(local_to_utc(naive)), naive);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_apply_matches_to_sql_with() {
+        use crate::core::ToSql;
+
+        let naive = chrono::NaiveDate::from_ymd_opt(2024, 7, 1)
+            .and_then(|d| d.and_hms_opt(8, 30, 0))
+            .unwrap();
+        let utc = naive.and_utc();
+        for policy in [
+            TimezonePolicy::PassThrough,
+            TimezonePolicy::StoreUtc,
+            TimezonePolicy::StoreLocal,
+        ] {
+            assert_eq!(
+                policy.apply(SqlValue::DateTime(naive)),
+                naive.to_sql_with(policy).unwrap()
+            );
+            assert_eq!(
+                policy.apply(SqlValue::DateTimeUtc(utc)),
+                utc.to_sql_with(policy).unwrap()
+            );
+        }
+        assert_eq!(
+            TimezonePolicy::StoreUtc.apply(SqlValue::I64(1)),
+            SqlValue::I64(1)
+        );
+    }
+}
@@ -3,7 +3,8 @@
 //! This trait enables any Rust type to be converted into a [`SqlValue`]
 //! for use in query parameters.
 
-use super::{Result, SqlValue};
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use super::{Result, SqlValue, TimezonePolicy};
 
 /// Trait for types that can be converted to SQL values
 ///
@@ -32,6 +33,19 @@ pub trait ToSql {
     ///
     /// Returns an error if the conversion fails (e.g., unsupported type).
     fn to_sql(&self) -> Result<SqlValue>;
+
+    /// Convert this value to a SQL value, applying the timezone policy
+    ///
+    /// Only date-time types are affected, all other types delegate to
+    /// [`to_sql`](Self::to_sql).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the conversion fails (e.g., unsupported type).
+    fn to_sql_with(&self, policy: TimezonePolicy) -> Result<SqlValue> {
+        let _ = policy;
+        self.to_sql()
+    }
 }
 
 // Implement for all types that have Into<SqlValue>
@@ -160,6 +174,14 @@ impl ToSql for chrono::NaiveDateTime {
     fn to_sql(&self) -> Result<SqlValue> {
         Ok(SqlValue::DateTime(*self))
     }
+
+    fn to_sql_with(&self, policy: TimezonePolicy) -> Result<SqlValue> {
+        match policy {
+            // Naive values are local time
+            TimezonePolicy::StoreUtc => Ok(SqlValue::DateTime(super::local_to_utc(*self).naive_utc())),
+            TimezonePolicy::PassThrough | TimezonePolicy::StoreLocal => self.to_sql(),
+        }
+    }
 }
 
 #[cfg(feature = "chrono")]
@@ -167,6 +189,28 @@ impl ToSql for chrono::DateTime<chrono::Utc> {
     fn to_sql(&self) -> Result<SqlValue> {
         Ok(SqlValue::DateTimeUtc(*self))
     }
+
+    fn to_sql_with(&self, policy: TimezonePolicy) -> Result<SqlValue> {
+        match policy {
+            TimezonePolicy::StoreUtc => Ok(SqlValue::DateTime(self.naive_utc())),
+            TimezonePolicy::StoreLocal => Ok(SqlValue::DateTime(super::utc_to_local(*self))),
+            TimezonePolicy::PassThrough => self.to_sql(),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToSql for chrono::DateTime<chrono::FixedOffset> {
+    fn to_sql(&self) -> Result<SqlValue> {
+        Ok(SqlValue::DateTimeTz(*self))
+    }
+}
+
+#[cfg(all(feature = "chrono", feature = "std"))]
+impl ToSql for chrono::DateTime<chrono::Local> {
+    fn to_sql(&self) -> Result<SqlValue> {
+        Ok(SqlValue::DateTimeTz(self.fixed_offset()))
+    }
 }
 
 #[cfg(feature = "uuid")]
@@ -293,7 +337,7 @@ mod tests {
     #[cfg(feature = "uuid")]
     #[test]
     fn test_to_sql_uuid() {
-        let uuid = uuid::Uuid::new_v4();
+        let uuid = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
         assert_eq!(uuid.to_sql().unwrap(), SqlValue::Uuid(uuid));
     }
 }
@@ -0,0 +1,522 @@
+//! Apache Arrow and Parquet export of query results
+//!
+//! [`ArrowExport`] is implemented for every [`DriverResult`] when the
+//! `arrow` feature is enabled. Rows are converted into [`RecordBatch`]es of
+//! a fixed number of rows; with the `parquet` feature the batches can be
+//! written to a Parquet file:
+//!
+//! ```rust,ignore
+//! use rustine_dbal::driver::ArrowExport;
+//!
+//! let mut result = conn.query("SELECT id, name, created_at FROM users").await?;
+//! for batch in result.record_batches(10_000) {
+//!     let batch = batch?;
+//!     // hand the batch to DataFusion, Polars, ...
+//! }
+//! ```
+//!
+//! ## Type mapping
+//!
+//! | `SqlValue` | Arrow type |
+//! |------------|------------|
+//! | `Bool` | `Boolean` |
+//! | `I8` .. `I64`, `U32` | `Int64` |
+//! | `U64` | `UInt64` |
+//! | `F32`, `F64` | `Float64` |
+//! | `String`, `Uuid`, `Json` | `Utf8` |
+//! | `Bytes` | `Binary` |
+//! | `Date` | `Date32` |
+//! | `Time` | `Time64(Microsecond)` |
+//! | `DateTime` | `Timestamp(Microsecond, None)` |
+//! | `DateTimeUtc`, `DateTimeTz` | `Timestamp(Microsecond, "UTC")` |
+//! | `Decimal` | `Decimal128(38, scale)` |
+//!
+//! The schema is inferred from the first batch: each column takes the type
+//! of its values, integers mixed with floats become `Float64`, other mixed
+//! or all-NULL columns become `Utf8`. Later batches are converted to that
+//! schema and fail with a conversion error if a value does not fit.
+
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BinaryArray, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
+    UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+
+use crate::core::{Error, Result, SqlValue};
+use crate::platform::{AnsiPlatform, ValueFormatter};
+
+use super::export::value_text;
+use super::DriverResult;
+
+/// Precision of decimal columns
+#[cfg(feature = "decimal")]
+const DECIMAL_PRECISION: u8 = 38;
+
+/// Export of the remaining rows of a result as Arrow record batches
+pub trait ArrowExport: DriverResult {
+    /// Iterate over the remaining rows in batches of `batch_size` rows
+    fn record_batches(&mut self, batch_size: usize) -> RecordBatches<'_, Self> {
+        RecordBatches {
+            result: self,
+            batch_size: batch_size.max(1),
+            schema: None,
+            formatter: ValueFormatter::for_platform(&AnsiPlatform::new()),
+            done: false,
+        }
+    }
+
+    /// Collect the remaining rows into record batches
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching a row fails or a value does not fit the
+    /// inferred column type.
+    fn to_record_batches(&mut self, batch_size: usize) -> Result<Vec<RecordBatch>> {
+        self.record_batches(batch_size).collect()
+    }
+
+    /// Write the remaining rows to a Parquet file
+    ///
+    /// Batches are written as they are read, so the result is not held in
+    /// memory. An empty result produces a file with `Utf8` columns and no
+    /// rows. Returns the number of rows written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching or converting rows or writing fails.
+    #[cfg(feature = "parquet")]
+    fn to_parquet<W: std::io::Write + Send>(&mut self, writer: W, batch_size: usize) -> Result<u64> {
+        use parquet::arrow::ArrowWriter;
+
+        let parquet_error = |e: parquet::errors::ParquetError| Error::driver("Failed to write Parquet", e);
+
+        let mut batches = self.record_batches(batch_size);
+        let first = batches.next().transpose()?;
+        let schema = first
+            .as_ref()
+            .map_or_else(|| batches.fallback_schema(), RecordBatch::schema);
+
+        let mut writer = ArrowWriter::try_new(writer, schema, None).map_err(parquet_error)?;
+        let mut rows = 0;
+        for batch in first.into_iter().map(Ok).chain(batches) {
+            let batch = batch?;
+            rows += batch.num_rows() as u64;
+            writer.write(&batch).map_err(parquet_error)?;
+        }
+        writer.close().map_err(parquet_error)?;
+        Ok(rows)
+    }
+}
+
+impl<R: DriverResult + ?Sized> ArrowExport for R {}
+
+/// Iterator over record batches of a result, see [`ArrowExport::record_batches`]
+pub struct RecordBatches<'a, R: DriverResult + ?Sized> {
+    /// Result to read rows from
+    result: &'a mut R,
+    /// Maximum number of rows per batch
+    batch_size: usize,
+    /// Schema inferred from the first batch
+    schema: Option<SchemaRef>,
+    /// Formatter for values stored as text
+    formatter: ValueFormatter,
+    /// Whether the result is exhausted or failed
+    done: bool,
+}
+
+impl<R: DriverResult + ?Sized> std::fmt::Debug for RecordBatches<'_, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordBatches")
+            .field("batch_size", &self.batch_size)
+            .field("schema", &self.schema)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: DriverResult + ?Sized> RecordBatches<'_, R> {
+    /// Get the schema, once the first batch has been read
+    #[must_use]
+    pub const fn schema(&self) -> Option<&SchemaRef> {
+        self.schema.as_ref()
+    }
+
+    /// Schema for results without rows: nullable `Utf8` columns
+    #[cfg(feature = "parquet")]
+    fn fallback_schema(&self) -> SchemaRef {
+        self.schema.clone().unwrap_or_else(|| {
+            let fields: Vec<Field> = self
+                .result
+                .column_names()
+                .iter()
+                .map(|name| Field::new(name, DataType::Utf8, true))
+                .collect();
+            Arc::new(Schema::new(fields))
+        })
+    }
+
+    /// Read the next batch of rows, converted to columns
+    fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        let width = self.result.column_count();
+        let mut columns: Vec<Vec<SqlValue>> = vec![Vec::with_capacity(self.batch_size); width];
+        let mut rows = 0;
+        while rows < self.batch_size {
+            let Some(row) = self.result.next_row()? else {
+                break;
+            };
+            for (column, value) in columns.iter_mut().zip(row) {
+                column.push(value);
+            }
+            rows += 1;
+        }
+        if rows == 0 {
+            return Ok(None);
+        }
+
+        let schema = if let Some(schema) = &self.schema {
+            Arc::clone(schema)
+        } else {
+            let schema = infer_schema(self.result.column_names(), &columns);
+            self.schema = Some(Arc::clone(&schema));
+            schema
+        };
+
+        let arrays = schema
+            .fields()
+            .iter()
+            .zip(&columns)
+            .map(|(field, values)| build_array(field, values, &self.formatter))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+
+        RecordBatch::try_new(schema, arrays)
+            .map(Some)
+            .map_err(|e| Error::driver("Failed to build record batch", e))
+    }
+}
+
+impl<R: DriverResult + ?Sized> Iterator for RecordBatches<'_, R> {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let batch = self.next_batch().transpose();
+        if !matches!(batch, Some(Ok(_))) {
+            self.done = true;
+        }
+        batch
+    }
+}
+
+/// Infer the schema from the values of the first batch
+fn infer_schema(names: &[String], columns: &[Vec<SqlValue>]) -> SchemaRef {
+    let fields: Vec<Field> = names
+        .iter()
+        .zip(columns)
+        .map(|(name, values)| {
+            let data_type = values
+                .iter()
+                .filter_map(arrow_type)
+                .reduce(merge_types)
+                .unwrap_or(DataType::Utf8);
+            Field::new(name, data_type, true)
+        })
+        .collect();
+    Arc::new(Schema::new(fields))
+}
+
+/// Get the Arrow type for a value, `None` for NULL
+fn arrow_type(value: &SqlValue) -> Option<DataType> {
+    let data_type = match value {
+        SqlValue::Null => return None,
+        SqlValue::Bool(_) => DataType::Boolean,
+        SqlValue::I8(_) | SqlValue::I16(_) | SqlValue::I32(_) | SqlValue::I64(_) | SqlValue::U32(_) => {
+            DataType::Int64
+        }
+        SqlValue::U64(_) => DataType::UInt64,
+        SqlValue::F32(_) | SqlValue::F64(_) => DataType::Float64,
+        SqlValue::Bytes(_) => DataType::Binary,
+        #[cfg(feature = "chrono")]
+        SqlValue::Date(_) => DataType::Date32,
+        #[cfg(feature = "chrono")]
+        SqlValue::Time(_) => DataType::Time64(arrow_schema::TimeUnit::Microsecond),
+        #[cfg(feature = "chrono")]
+        SqlValue::DateTime(_) => DataType::Timestamp(arrow_schema::TimeUnit::Microsecond, None),
+        #[cfg(feature = "chrono")]
+        SqlValue::DateTimeUtc(_) | SqlValue::DateTimeTz(_) => {
+            DataType::Timestamp(arrow_schema::TimeUnit::Microsecond, Some("UTC".into()))
+        }
+        #[cfg(feature = "decimal")]
+        SqlValue::Decimal(d) => {
+            // Scales above 38 cannot occur, rust_decimal supports at most 28
+            DataType::Decimal128(DECIMAL_PRECISION, i8::try_from(d.scale()).unwrap_or(i8::MAX))
+        }
+        _ => DataType::Utf8,
+    };
+    Some(data_type)
+}
+
+/// Combine the types of two values of the same column
+fn merge_types(a: DataType, b: DataType) -> DataType {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (DataType::Int64 | DataType::UInt64 | DataType::Float64, DataType::Float64)
+        | (DataType::Float64, DataType::Int64 | DataType::UInt64) => DataType::Float64,
+        (DataType::Int64, DataType::UInt64) | (DataType::UInt64, DataType::Int64) => DataType::Int64,
+        #[cfg(feature = "decimal")]
+        (DataType::Decimal128(p, a), DataType::Decimal128(_, b)) => DataType::Decimal128(p, a.max(b)),
+        _ => DataType::Utf8,
+    }
+}
+
+/// Build a column array of the field type
+fn build_array(field: &Field, values: &[SqlValue], formatter: &ValueFormatter) -> Result<ArrayRef> {
+    let array: ArrayRef = match field.data_type() {
+        DataType::Boolean => Arc::new(BooleanArray::from(convert(field, values, |value| match value {
+            SqlValue::Bool(b) => Some(*b),
+            _ => integer(value).map(|i| i != 0),
+        })?)),
+        DataType::Int64 => Arc::new(Int64Array::from(convert(field, values, integer)?)),
+        DataType::UInt64 => Arc::new(UInt64Array::from(convert(field, values, |value| match value {
+            SqlValue::U64(u) => Some(*u),
+            _ => integer(value).and_then(|i| u64::try_from(i).ok()),
+        })?)),
+        DataType::Float64 => Arc::new(Float64Array::from(convert(field, values, float)?)),
+        DataType::Binary => Arc::new(BinaryArray::from(convert(field, values, |value| match value {
+            SqlValue::Bytes(bytes) => Some(bytes.as_slice()),
+            SqlValue::String(s) => Some(s.as_bytes()),
+            _ => None,
+        })?)),
+        #[cfg(feature = "chrono")]
+        DataType::Date32 | DataType::Time64(_) | DataType::Timestamp(..) => build_temporal_array(field, values)?,
+        #[cfg(feature = "decimal")]
+        DataType::Decimal128(precision, scale) => {
+            let target_scale = u32::try_from(*scale).unwrap_or(0);
+            let mantissas = convert(field, values, |value| match value {
+                SqlValue::Decimal(d) => {
+                    let mut d = *d;
+                    d.rescale(target_scale);
+                    Some(d.mantissa())
+                }
+                _ => None,
+            })?;
+            Arc::new(
+                arrow_array::Decimal128Array::from(mantissas)
+                    .with_precision_and_scale(*precision, *scale)
+                    .map_err(|e| Error::driver("Invalid decimal column", e))?,
+            )
+        }
+        _ => Arc::new(StringArray::from(
+            values
+                .iter()
+                .map(|value| value_text(value, formatter))
+                .collect::<Vec<_>>(),
+        )),
+    };
+    Ok(array)
+}
+
+/// Build a date, time or timestamp column array
+#[cfg(feature = "chrono")]
+fn build_temporal_array(field: &Field, values: &[SqlValue]) -> Result<ArrayRef> {
+    use arrow_array::{Date32Array, Time64MicrosecondArray, TimestampMicrosecondArray};
+
+    let array: ArrayRef = match field.data_type() {
+        DataType::Date32 => Arc::new(Date32Array::from(convert(field, values, |value| match value {
+            SqlValue::Date(d) => i32::try_from((*d - chrono::NaiveDate::default()).num_days()).ok(),
+            _ => None,
+        })?)),
+        DataType::Timestamp(_, timezone) => {
+            let micros = convert(field, values, |value| match value {
+                SqlValue::DateTime(dt) if timezone.is_none() => Some(dt.and_utc().timestamp_micros()),
+                SqlValue::DateTimeUtc(dt) if timezone.is_some() => Some(dt.timestamp_micros()),
+                SqlValue::DateTimeTz(dt) if timezone.is_some() => Some(dt.timestamp_micros()),
+                _ => None,
+            })?;
+            Arc::new(TimestampMicrosecondArray::from(micros).with_timezone_opt(timezone.clone()))
+        }
+        _ => Arc::new(Time64MicrosecondArray::from(convert(field, values, |value| match value {
+            SqlValue::Time(t) => (*t - chrono::NaiveTime::MIN).num_microseconds(),
+            _ => None,
+        })?)),
+    };
+    Ok(array)
+}
+
+/// Convert the non-NULL values of a column, failing on values that do not fit
+fn convert<'v, T>(
+    field: &Field,
+    values: &'v [SqlValue],
+    f: impl Fn(&'v SqlValue) -> Option<T>,
+) -> Result<Vec<Option<T>>> {
+    values
+        .iter()
+        .map(|value| {
+            if value.is_null() {
+                Ok(None)
+            } else {
+                f(value).map(Some).ok_or_else(|| mismatch(field, value))
+            }
+        })
+        .collect()
+}
+
+/// Get an integer value, `None` if the value is not an integer
+fn integer(value: &SqlValue) -> Option<i64> {
+    match value {
+        SqlValue::I8(i) => Some(i64::from(*i)),
+        SqlValue::I16(i) => Some(i64::from(*i)),
+        SqlValue::I32(i) => Some(i64::from(*i)),
+        SqlValue::I64(i) => Some(*i),
+        SqlValue::U32(u) => Some(i64::from(*u)),
+        SqlValue::U64(u) => i64::try_from(*u).ok(),
+        _ => None,
+    }
+}
+
+/// Get a float value, `None` if the value is not numeric
+#[allow(clippy::cast_precision_loss)]
+fn float(value: &SqlValue) -> Option<f64> {
+    match value {
+        SqlValue::F32(f) => Some(f64::from(*f)),
+        SqlValue::F64(f) => Some(*f),
+        SqlValue::U64(u) => Some(*u as f64),
+        _ => integer(value).map(|i| i as f64),
+    }
+}
+
+/// Create an error for a value that does not fit its column type
+fn mismatch(field: &Field, value: &SqlValue) -> Error {
+    Error::conversion(
+        "SqlValue",
+        "arrow",
+        format!(
+            "value {value} does not fit column '{}' of type {}",
+            field.name(),
+            field.data_type()
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+
+    /// In-memory result for export tests
+    struct VecResult {
+        columns: Vec<String>,
+        rows: std::vec::IntoIter<Vec<SqlValue>>,
+    }
+
+    impl DriverResult for VecResult {
+        fn next_row(&mut self) -> Result<Option<Vec<SqlValue>>> {
+            Ok(self.rows.next())
+        }
+
+        fn column_count(&self) -> usize {
+            self.columns.len()
+        }
+
+        fn column_names(&self) -> &[String] {
+            &self.columns
+        }
+
+        fn rows_affected(&self) -> u64 {
+            0
+        }
+    }
+
+    fn result(rows: Vec<Vec<SqlValue>>) -> VecResult {
+        VecResult {
+            columns: vec!["id".into(), "score".into(), "name".into(), "flag".into()],
+            rows: rows.into_iter(),
+        }
+    }
+
+    #[test]
+    fn test_record_batches() {
+        let mut result = result(vec![
+            vec![SqlValue::I64(1), SqlValue::I64(3), SqlValue::String("a".into()), SqlValue::Null],
+            vec![SqlValue::I64(2), SqlValue::F64(2.5), SqlValue::Null, SqlValue::Null],
+            vec![SqlValue::I64(3), SqlValue::I32(4), SqlValue::String("c".into()), SqlValue::Bool(true)],
+        ]);
+
+        let batches = result.to_record_batches(2).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].num_rows(), 2);
+        assert_eq!(batches[1].num_rows(), 1);
+
+        let schema = batches[0].schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(2).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(3).data_type(), &DataType::Utf8);
+
+        let names = batches[0].column(2).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(names.value(0), "a");
+        assert!(names.is_null(1));
+
+        // Later batches are converted to the inferred schema
+        let flags = batches[1].column(3).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(flags.value(0), "true");
+    }
+
+    #[test]
+    fn test_schema_mismatch() {
+        let mut result = result(vec![
+            vec![SqlValue::I64(1), SqlValue::Null, SqlValue::Null, SqlValue::Null],
+            vec![SqlValue::String("x".into()), SqlValue::Null, SqlValue::Null, SqlValue::Null],
+        ]);
+        let mut batches = result.record_batches(1);
+        assert!(batches.next().unwrap().is_ok());
+        assert!(batches.next().unwrap().is_err());
+        assert!(batches.next().is_none());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_temporal_types() {
+        let date = chrono::NaiveDate::from_ymd_opt(1970, 1, 11).unwrap();
+        let mut result = VecResult {
+            columns: vec!["day".into(), "at".into()],
+            rows: vec![vec![
+                SqlValue::Date(date),
+                SqlValue::DateTimeUtc(date.and_hms_opt(0, 0, 1).unwrap().and_utc()),
+            ]]
+            .into_iter(),
+        };
+
+        let batch = result.to_record_batches(10).unwrap().remove(0);
+        let days = batch.column(0).as_any().downcast_ref::<arrow_array::Date32Array>().unwrap();
+        assert_eq!(days.value(0), 10);
+        let at = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<arrow_array::TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(at.value(0), 864_001_000_000);
+        assert_eq!(at.timezone(), Some("UTC"));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_to_parquet() {
+        let mut result = result(vec![vec![
+            SqlValue::I64(1),
+            SqlValue::F64(0.5),
+            SqlValue::String("a".into()),
+            SqlValue::Bool(false),
+        ]]);
+        let mut out = Vec::new();
+        assert_eq!(result.to_parquet(&mut out, 100).unwrap(), 1);
+        assert_eq!(&out[..4], b"PAR1");
+
+        let mut empty = self::result(Vec::new());
+        let mut out = Vec::new();
+        assert_eq!(empty.to_parquet(&mut out, 100).unwrap(), 0);
+        assert_eq!(&out[out.len() - 4..], b"PAR1");
+    }
+}
@@ -1,7 +1,7 @@
 //! Driver connection trait
 
 use async_trait::async_trait;
-use crate::core::Result;
+use crate::core::{Result, TransactionStatus};
 
 use super::{DriverStatement, DriverResult};
 
@@ -18,6 +18,10 @@ pub trait DriverConnection: Send + Sync {
     async fn prepare(&self, sql: &str) -> Result<Self::Statement>;
 
     /// Execute a SQL query and return results
+    ///
+    /// For a batch of several statements the result starts at the result
+    /// set of the first one; the others follow via
+    /// [`DriverResult::next_result_set`].
     async fn query(&self, sql: &str) -> Result<Self::Result>;
 
     /// Execute a SQL statement and return affected rows
@@ -32,9 +36,36 @@ pub trait DriverConnection: Send + Sync {
     /// Rollback the current transaction
     async fn rollback(&self) -> Result<()>;
 
+    /// Get the transaction state of the connection
+    ///
+    /// The source of truth for `Connection`, which keeps its nesting level
+    /// in sync with it when transactions are begun or ended on the driver
+    /// connection directly. Drivers that don't track transactions keep the
+    /// default [`TransactionStatus::Unknown`].
+    fn transaction_status(&self) -> TransactionStatus {
+        TransactionStatus::Unknown
+    }
+
     /// Check if the connection is still alive
     async fn is_alive(&self) -> bool;
 
     /// Get the server version
     async fn server_version(&self) -> Result<String>;
+
+    /// Capture the query plan for a SQL statement
+    ///
+    /// Returns `None` if the driver has no textual plan output. Drivers for
+    /// databases with a readable `EXPLAIN` (e.g. `PostgreSQL`) override this.
+    async fn explain(&self, _sql: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Discard the statements the driver prepared and cached
+    ///
+    /// Called after schema changes, as cached statements may refer to
+    /// dropped objects or return outdated columns. Drivers without a
+    /// statement cache keep the default no-op.
+    async fn clear_statement_cache(&self) -> Result<()> {
+        Ok(())
+    }
 }
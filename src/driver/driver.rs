@@ -1,7 +1,7 @@
 //! Driver trait for database abstraction
 
 use async_trait::async_trait;
-use crate::core::{ConnectionParams, Result};
+use crate::core::{Configuration, ConnectionParams, Result};
 
 use super::DriverConnection;
 
@@ -14,6 +14,22 @@ pub trait Driver: Send + Sync {
     /// Create a new connection to the database
     async fn connect(&self, params: &ConnectionParams) -> Result<Self::Connection>;
 
+    /// Create a new connection applying the runtime configuration
+    ///
+    /// Drivers pick the settings they support (connect and query timeouts,
+    /// value formats, ...). The default ignores the configuration. A connect
+    /// exceeding its timeout fails with `ConnectionError::Timeout` carrying
+    /// the configured milliseconds. The read and write timeouts and TCP
+    /// keepalive are reserved for network drivers, which should apply them
+    /// to their sockets; no driver of this crate has any.
+    async fn connect_with_configuration(
+        &self,
+        params: &ConnectionParams,
+        _configuration: &Configuration,
+    ) -> Result<Self::Connection> {
+        self.connect(params).await
+    }
+
     /// Get the name of this driver
     fn name(&self) -> &'static str;
 
@@ -0,0 +1,245 @@
+//! CSV and JSON export of query results
+//!
+//! [`ResultExport`] is implemented for every [`DriverResult`]. Rows are read
+//! one at a time and written through a buffered writer, so large results
+//! are streamed instead of being collected first:
+//!
+//! ```rust,ignore
+//! use rustine_dbal::driver::ResultExport;
+//!
+//! let mut result = conn.query("SELECT id, name FROM users").await?;
+//! let file = std::fs::File::create("users.csv")?;
+//! let rows = result.to_csv(file)?;
+//! ```
+
+use std::fmt::Write as _;
+use std::io::{BufWriter, Write};
+
+use crate::core::{hex_encode, Error, Result, SqlValue};
+use crate::platform::{AnsiPlatform, ValueFormatter};
+
+use super::DriverResult;
+
+/// Export of the remaining rows of a result as CSV or JSON
+///
+/// Dates, times, UUIDs and decimals are written as text in the ANSI formats,
+/// binary values as lower-case hex.
+pub trait ResultExport: DriverResult {
+    /// Write the remaining rows as CSV with a header row
+    ///
+    /// Fields are separated by `,` and quoted (with doubled quotes) when they
+    /// contain a separator, quote or line break. NULL is an empty field.
+    /// Returns the number of rows written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching a row or writing fails.
+    fn to_csv<W: Write>(&mut self, writer: W) -> Result<u64> {
+        let formatter = export_formatter();
+        let mut out = BufWriter::new(writer);
+
+        let header: Vec<String> = self.column_names().iter().map(|name| csv_field(name)).collect();
+        writeln!(out, "{}", header.join(",")).map_err(write_error)?;
+
+        let mut count = 0;
+        while let Some(row) = self.next_row()? {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|value| value_text(value, &formatter).map_or_else(String::new, |text| csv_field(&text)))
+                .collect();
+            writeln!(out, "{}", fields.join(",")).map_err(write_error)?;
+            count += 1;
+        }
+
+        out.flush().map_err(write_error)?;
+        Ok(count)
+    }
+
+    /// Write the remaining rows as a JSON array of objects keyed by column name
+    ///
+    /// Numbers and booleans are written as JSON numbers and booleans
+    /// (non-finite floats as `null`), JSON values are embedded as is and
+    /// everything else as strings. If several columns share a name, the
+    /// object contains the key more than once.
+    /// Returns the number of rows written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching a row or writing fails.
+    fn to_json<W: Write>(&mut self, writer: W) -> Result<u64> {
+        let formatter = export_formatter();
+        let mut out = BufWriter::new(writer);
+        let keys: Vec<String> = self.column_names().iter().map(|name| json_string(name)).collect();
+
+        out.write_all(b"[").map_err(write_error)?;
+        let mut count = 0;
+        while let Some(row) = self.next_row()? {
+            if count > 0 {
+                out.write_all(b",").map_err(write_error)?;
+            }
+            let members: Vec<String> = keys
+                .iter()
+                .zip(&row)
+                .map(|(key, value)| format!("{key}:{}", json_value(value, &formatter)))
+                .collect();
+            write!(out, "\n{{{}}}", members.join(",")).map_err(write_error)?;
+            count += 1;
+        }
+        out.write_all(if count > 0 { b"\n]\n" } else { b"]\n" })
+            .map_err(write_error)?;
+
+        out.flush().map_err(write_error)?;
+        Ok(count)
+    }
+}
+
+impl<R: DriverResult + ?Sized> ResultExport for R {}
+
+/// Formatter for values without a primitive text form
+fn export_formatter() -> ValueFormatter {
+    ValueFormatter::for_platform(&AnsiPlatform::new())
+}
+
+/// Wrap an I/O error from the export writer
+fn write_error(e: std::io::Error) -> Error {
+    Error::driver("Failed to write export", e)
+}
+
+/// Get the text of a value, `None` for NULL
+pub(crate) fn value_text(value: &SqlValue, formatter: &ValueFormatter) -> Option<String> {
+    match value {
+        SqlValue::Null => None,
+        SqlValue::String(s) => Some(s.clone()),
+        SqlValue::Bytes(bytes) => Some(hex_encode(bytes)),
+        _ => Some(formatter.format_value(value).unwrap_or_else(|| value.to_string())),
+    }
+}
+
+/// Quote a CSV field if needed
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        quote_csv_field(text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Quote a CSV field, doubling embedded quotes
+pub(crate) fn quote_csv_field(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// Format a value as JSON
+fn json_value(value: &SqlValue, formatter: &ValueFormatter) -> String {
+    match value {
+        SqlValue::Null => "null".to_string(),
+        SqlValue::Bool(_)
+        | SqlValue::I8(_)
+        | SqlValue::I16(_)
+        | SqlValue::I32(_)
+        | SqlValue::I64(_)
+        | SqlValue::U32(_)
+        | SqlValue::U64(_) => value.to_string(),
+        SqlValue::F32(n) if n.is_finite() => value.to_string(),
+        SqlValue::F64(n) if n.is_finite() => value.to_string(),
+        SqlValue::F32(_) | SqlValue::F64(_) => "null".to_string(),
+        #[cfg(feature = "json")]
+        SqlValue::Json(json) => json.to_string(),
+        _ => value_text(value, formatter).map_or_else(|| "null".to_string(), |text| json_string(&text)),
+    }
+}
+
+/// Quote and escape a JSON string
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// In-memory result for export tests
+    struct VecResult {
+        columns: Vec<String>,
+        rows: std::vec::IntoIter<Vec<SqlValue>>,
+    }
+
+    impl DriverResult for VecResult {
+        fn next_row(&mut self) -> Result<Option<Vec<SqlValue>>> {
+            Ok(self.rows.next())
+        }
+
+        fn column_count(&self) -> usize {
+            self.columns.len()
+        }
+
+        fn column_names(&self) -> &[String] {
+            &self.columns
+        }
+
+        fn rows_affected(&self) -> u64 {
+            0
+        }
+    }
+
+    fn result() -> VecResult {
+        VecResult {
+            columns: vec!["id".into(), "name".into(), "score".into(), "data".into()],
+            rows: vec![
+                vec![
+                    SqlValue::I64(1),
+                    SqlValue::String("Smith, \"Jo\"\nJr.".into()),
+                    SqlValue::F64(1.5),
+                    SqlValue::Bytes(vec![0, 255]),
+                ],
+                vec![SqlValue::I64(2), SqlValue::String("tab\there".into()), SqlValue::F64(f64::NAN), SqlValue::Null],
+            ]
+            .into_iter(),
+        }
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let mut out = Vec::new();
+        assert_eq!(result().to_csv(&mut out).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "id,name,score,data\n1,\"Smith, \"\"Jo\"\"\nJr.\",1.5,00ff\n2,tab\there,NaN,\n"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let mut out = Vec::new();
+        assert_eq!(result().to_json(&mut out).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[\n{\"id\":1,\"name\":\"Smith, \\\"Jo\\\"\\nJr.\",\"score\":1.5,\"data\":\"00ff\"},\n\
+             {\"id\":2,\"name\":\"tab\\there\",\"score\":null,\"data\":null}\n]\n"
+        );
+
+        let mut empty = VecResult {
+            columns: vec!["id".into()],
+            rows: Vec::new().into_iter(),
+        };
+        let mut out = Vec::new();
+        assert_eq!(empty.to_json(&mut out).unwrap(), 0);
+        assert_eq!(out, b"[]\n");
+    }
+}
@@ -8,20 +8,38 @@
 //! ## Available Drivers
 //!
 //! - `sqlite` - `SQLite` driver (requires `sqlite` feature)
+//! - `sqlite_wasm` - `SQLite` driver for WebAssembly on an application-supplied
+//!   engine (requires `sqlite-wasm` feature)
 
 #[allow(clippy::module_inception)]
 pub mod driver;
 pub mod connection;
 pub mod statement;
 pub mod result;
+pub mod row;
+pub mod export;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod placeholder;
 
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
+#[cfg(feature = "sqlite-wasm")]
+pub mod sqlite_wasm;
 
 pub use driver::*;
 pub use connection::*;
 pub use statement::*;
 pub use result::*;
+pub use row::*;
+pub use export::ResultExport;
+#[cfg(feature = "arrow")]
+pub use arrow::{ArrowExport, RecordBatches};
+pub use placeholder::{rewrite_placeholders, Placeholder, PlaceholderStyle, RewrittenSql};
 
 #[cfg(feature = "sqlite")]
-pub use sqlite::{SqliteDriver, SqliteConnection, SqliteStatement, SqliteResult};
+pub use sqlite::{SqliteDriver, SqliteConnection, SqliteStatement, SqliteResult, BusyRetryPolicy};
+#[cfg(feature = "sqlite-wasm")]
+pub use sqlite_wasm::{
+    BackendResult, SqliteBackend, SqliteWasmConnection, SqliteWasmDriver, SqliteWasmResult, SqliteWasmStatement,
+};
@@ -0,0 +1,340 @@
+//! Placeholder rewriting between parameter styles
+//!
+//! Queries are often written for one database and run on another: `?`
+//! (`MySQL`, `SQLite`), `$1` (`PostgreSQL`) and `:name` placeholders are
+//! converted into the style of the target platform. String literals, quoted
+//! identifiers, comments, dollar-quoted strings and `::` casts are left
+//! untouched.
+//!
+//! ```
+//! use rustine_dbal::driver::{rewrite_placeholders, Placeholder, PlaceholderStyle};
+//!
+//! let rewritten = rewrite_placeholders(
+//!     "SELECT * FROM users WHERE id = ? AND name <> '?'",
+//!     PlaceholderStyle::Dollar,
+//! );
+//! assert_eq!(rewritten.sql, "SELECT * FROM users WHERE id = $1 AND name <> '?'");
+//! assert_eq!(rewritten.parameters, vec![Placeholder::Positional(0)]);
+//! ```
+
+use std::fmt::Write;
+
+use crate::platform::Platform;
+
+/// Placeholder syntax for statement parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaceholderStyle {
+    /// Anonymous `?` placeholders, bound in order of appearance
+    QuestionMark,
+    /// Numbered `$1`, `$2`, ... placeholders
+    Dollar,
+    /// Named `:name` placeholders
+    Named,
+}
+
+impl PlaceholderStyle {
+    /// Get the placeholder style used by a platform
+    #[must_use]
+    pub fn for_platform<P: Platform + ?Sized>(platform: &P) -> Self {
+        let placeholder = platform.parameter_placeholder(0);
+        if placeholder.starts_with('$') {
+            Self::Dollar
+        } else if placeholder.starts_with(':') {
+            Self::Named
+        } else {
+            Self::QuestionMark
+        }
+    }
+}
+
+/// A parameter referenced by a placeholder
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Placeholder {
+    /// Positional parameter (0-indexed)
+    Positional(usize),
+    /// Named parameter (without prefix)
+    Named(String),
+}
+
+impl std::fmt::Display for Placeholder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Positional(index) => write!(f, "position {index}"),
+            Self::Named(name) => write!(f, ":{name}"),
+        }
+    }
+}
+
+/// SQL rewritten to a placeholder style
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewrittenSql {
+    /// The rewritten SQL
+    pub sql: String,
+    /// The parameter to bind for each placeholder slot, in binding order
+    ///
+    /// For `?` this is one entry per occurrence, for `$n` entry `n - 1`, and
+    /// for `:name` one entry per distinct name.
+    pub parameters: Vec<Placeholder>,
+}
+
+/// A piece of parsed SQL
+enum Segment<'a> {
+    /// SQL text copied as is
+    Text(&'a str),
+    /// A placeholder
+    Parameter(Placeholder),
+}
+
+/// Rewrite all placeholders in `sql` to the target style
+///
+/// `?` placeholders are numbered by occurrence, `$n` and `?n` refer to
+/// parameter `n - 1`. When converting to `$n`, named parameters are numbered
+/// after the positional ones; when converting to `:name`, positional
+/// parameters become `:p1`, `:p2`, ...
+#[must_use]
+pub fn rewrite_placeholders(sql: &str, target: PlaceholderStyle) -> RewrittenSql {
+    let segments = parse(sql);
+    let mut out = String::with_capacity(sql.len());
+    let mut parameters: Vec<Placeholder> = Vec::new();
+
+    // Slot of each named parameter for `$n`, after all positional ones
+    let positional_slots = segments
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Parameter(Placeholder::Positional(index)) => Some(index + 1),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+
+    for segment in segments {
+        let placeholder = match segment {
+            Segment::Text(text) => {
+                out.push_str(text);
+                continue;
+            }
+            Segment::Parameter(placeholder) => placeholder,
+        };
+
+        match target {
+            PlaceholderStyle::QuestionMark => {
+                out.push('?');
+                parameters.push(placeholder);
+            }
+            PlaceholderStyle::Dollar => {
+                let slot = match placeholder {
+                    Placeholder::Positional(index) => index,
+                    Placeholder::Named(_) => parameters
+                        .iter()
+                        .skip(positional_slots)
+                        .position(|p| *p == placeholder)
+                        .map_or_else(|| parameters.len().max(positional_slots), |i| i + positional_slots),
+                };
+                let _ = write!(out, "${}", slot + 1);
+                if slot >= parameters.len() {
+                    let first_new = parameters.len();
+                    parameters.extend((first_new..slot).map(Placeholder::Positional));
+                    parameters.push(placeholder);
+                }
+            }
+            PlaceholderStyle::Named => {
+                match &placeholder {
+                    Placeholder::Positional(index) => {
+                        let _ = write!(out, ":p{}", index + 1);
+                    }
+                    Placeholder::Named(name) => {
+                        out.push(':');
+                        out.push_str(name);
+                    }
+                }
+                if !parameters.contains(&placeholder) {
+                    parameters.push(placeholder);
+                }
+            }
+        }
+    }
+
+    RewrittenSql { sql: out, parameters }
+}
+
+/// Split SQL into text and placeholders
+fn parse(sql: &str) -> Vec<Segment<'_>> {
+    let bytes = sql.as_bytes();
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut next_anonymous = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let (end, placeholder) = match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => (skip_quoted(bytes, i, quote), None),
+            b'-' if bytes.get(i + 1) == Some(&b'-') => (skip_until(bytes, i + 2, b"\n"), None),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => (skip_until(bytes, i + 2, b"*/"), None),
+            // `::` is a Postgres cast, not a named parameter
+            b':' if bytes.get(i + 1) == Some(&b':') => (i + 2, None),
+            b':' if bytes.get(i + 1).is_some_and(|b| is_ident_start(*b)) => {
+                let end = scan(bytes, i + 1, is_ident_char);
+                (end, Some(Placeholder::Named(sql[i + 1..end].to_string())))
+            }
+            b'?' => {
+                let end = scan(bytes, i + 1, |b| b.is_ascii_digit());
+                let placeholder = if end > i + 1 {
+                    numbered(&sql[i + 1..end])
+                } else {
+                    next_anonymous += 1;
+                    Placeholder::Positional(next_anonymous - 1)
+                };
+                (end, Some(placeholder))
+            }
+            b'$' if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                let end = scan(bytes, i + 1, |b| b.is_ascii_digit());
+                (end, Some(numbered(&sql[i + 1..end])))
+            }
+            b'$' => (skip_dollar_quoted(bytes, i), None),
+            _ => (i + 1, None),
+        };
+
+        if let Some(placeholder) = placeholder {
+            if text_start < i {
+                segments.push(Segment::Text(&sql[text_start..i]));
+            }
+            segments.push(Segment::Parameter(placeholder));
+            text_start = end;
+        }
+        i = end;
+    }
+
+    if text_start < sql.len() {
+        segments.push(Segment::Text(&sql[text_start..]));
+    }
+    segments
+}
+
+/// Parse a 1-based parameter number into a positional placeholder
+fn numbered(digits: &str) -> Placeholder {
+    Placeholder::Positional(digits.parse::<usize>().unwrap_or(1).saturating_sub(1))
+}
+
+/// Check if a byte can start an identifier
+const fn is_ident_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_'
+}
+
+/// Check if a byte can continue an identifier
+const fn is_ident_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Return the index after the bytes matching `pred`, starting at `start`
+fn scan(bytes: &[u8], start: usize, pred: impl Fn(u8) -> bool) -> usize {
+    bytes[start..]
+        .iter()
+        .position(|b| !pred(*b))
+        .map_or(bytes.len(), |offset| start + offset)
+}
+
+/// Return the index after `terminator`, or the end of input
+fn skip_until(bytes: &[u8], start: usize, terminator: &[u8]) -> usize {
+    bytes[start..]
+        .windows(terminator.len())
+        .position(|window| window == terminator)
+        .map_or(bytes.len(), |offset| start + offset + terminator.len())
+}
+
+/// Return the index after a quoted string or identifier
+///
+/// Quotes are escaped by doubling them (standard SQL); backslash escapes are
+/// not recognized.
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Return the index after a dollar-quoted string (`$$...$$`, `$tag$...$tag$`)
+///
+/// A `$` that doesn't open a dollar quote is skipped on its own.
+fn skip_dollar_quoted(bytes: &[u8], start: usize) -> usize {
+    let tag_end = scan(bytes, start + 1, is_ident_char);
+    if bytes.get(tag_end) != Some(&b'$') {
+        return start + 1;
+    }
+    let tag = &bytes[start..=tag_end];
+    skip_until(bytes, tag_end + 1, tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{MySqlPlatform, PostgresPlatform};
+
+    #[test]
+    fn test_question_mark_to_dollar() {
+        let rewritten = rewrite_placeholders(
+            "UPDATE t SET a = ?, b = 'x?' /* ? */ WHERE id = ? -- ?\n AND c = \"?\"",
+            PlaceholderStyle::Dollar,
+        );
+        assert_eq!(
+            rewritten.sql,
+            "UPDATE t SET a = $1, b = 'x?' /* ? */ WHERE id = $2 -- ?\n AND c = \"?\""
+        );
+        assert_eq!(
+            rewritten.parameters,
+            vec![Placeholder::Positional(0), Placeholder::Positional(1)]
+        );
+    }
+
+    #[test]
+    fn test_dollar_to_question_mark() {
+        let rewritten = rewrite_placeholders(
+            "SELECT $2, $1, $2, $$it's $1$$, 'a''$1', x::text",
+            PlaceholderStyle::QuestionMark,
+        );
+        assert_eq!(rewritten.sql, "SELECT ?, ?, ?, $$it's $1$$, 'a''$1', x::text");
+        assert_eq!(
+            rewritten.parameters,
+            vec![
+                Placeholder::Positional(1),
+                Placeholder::Positional(0),
+                Placeholder::Positional(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_named_placeholders() {
+        let sql = "SELECT * FROM t WHERE id = :id OR parent = :id_parent OR id = :id";
+
+        let dollar = rewrite_placeholders(sql, PlaceholderStyle::Dollar);
+        assert_eq!(dollar.sql, "SELECT * FROM t WHERE id = $1 OR parent = $2 OR id = $1");
+        assert_eq!(
+            dollar.parameters,
+            vec![Placeholder::Named("id".into()), Placeholder::Named("id_parent".into())]
+        );
+
+        let question = rewrite_placeholders(sql, PlaceholderStyle::QuestionMark);
+        assert_eq!(question.parameters.len(), 3);
+
+        let named = rewrite_placeholders("SELECT ?, ?, :x", PlaceholderStyle::Named);
+        assert_eq!(named.sql, "SELECT :p1, :p2, :x");
+        assert_eq!(named.parameters.len(), 3);
+    }
+
+    #[test]
+    fn test_style_for_platform() {
+        assert_eq!(PlaceholderStyle::for_platform(&PostgresPlatform), PlaceholderStyle::Dollar);
+        assert_eq!(
+            PlaceholderStyle::for_platform(&MySqlPlatform::new()),
+            PlaceholderStyle::QuestionMark
+        );
+    }
+}
@@ -1,7 +1,107 @@
 //! Driver result trait
 
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
 use crate::core::{Result, SqlValue};
 
+use super::row::column_index;
+use super::{ResultColumn, Row, RowRef};
+
+/// A fully fetched result set
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultSet {
+    /// Columns of the result set
+    pub columns: Vec<ResultColumn>,
+    /// Rows of the result set
+    pub rows: Vec<Row>,
+    /// Number of rows affected by the statement
+    pub rows_affected: u64,
+}
+
+impl ResultSet {
+    /// Convert the rows to maps from column name to value
+    ///
+    /// When several columns share a name (e.g. `id` after a join), the last
+    /// one wins; alias the columns in the query to keep all of them.
+    #[must_use]
+    pub fn to_maps(&self) -> Vec<HashMap<String, SqlValue>> {
+        self.rows
+            .iter()
+            .map(|row| self.columns.iter().map(|c| c.name.clone()).zip(row.values().iter().cloned()).collect())
+            .collect()
+    }
+
+    /// Get the values of a column in row order
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::ColumnNotFound`](crate::core::QueryError::ColumnNotFound)
+    /// if no column matches the (optionally qualified) name and
+    /// [`QueryError::AmbiguousColumn`](crate::core::QueryError::AmbiguousColumn)
+    /// if more than one does.
+    pub fn column_values(&self, name: &str) -> Result<Vec<&SqlValue>> {
+        let index = column_index(&self.columns, name)?;
+        Ok(self.rows.iter().filter_map(|row| row.get_at(index)).collect())
+    }
+
+    /// Get the smallest non-NULL value of a column, computed on the client
+    ///
+    /// Values are ordered by [`SqlValue::compare`]; values that can't be
+    /// compared with the smallest so far (e.g. JSON) are skipped. `None` if
+    /// the column only holds NULLs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column cannot be resolved, see [`column_values`](Self::column_values).
+    pub fn column_min(&self, name: &str) -> Result<Option<SqlValue>> {
+        self.column_extreme(name, Ordering::Less)
+    }
+
+    /// Get the largest non-NULL value of a column, computed on the client
+    ///
+    /// The counterpart of [`column_min`](Self::column_min).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column cannot be resolved, see [`column_values`](Self::column_values).
+    pub fn column_max(&self, name: &str) -> Result<Option<SqlValue>> {
+        self.column_extreme(name, Ordering::Greater)
+    }
+
+    /// Count the distinct non-NULL values of a column, like `COUNT(DISTINCT column)`
+    ///
+    /// Values with the same SQL literal count once, so `1` is the same
+    /// whether returned as `I32` or `I64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column cannot be resolved, see [`column_values`](Self::column_values).
+    pub fn column_distinct_count(&self, name: &str) -> Result<usize> {
+        let distinct: HashSet<String> = self
+            .column_values(name)?
+            .into_iter()
+            .filter(|value| !value.is_null())
+            .map(ToString::to_string)
+            .collect();
+        Ok(distinct.len())
+    }
+
+    /// Get the value of a column ordered first in the wanted direction
+    fn column_extreme(&self, name: &str, wanted: Ordering) -> Result<Option<SqlValue>> {
+        let mut extreme: Option<&SqlValue> = None;
+        for value in self.column_values(name)? {
+            match extreme {
+                None if !value.is_null() => extreme = Some(value),
+                Some(current) if value.compare(current) == Some(wanted) => extreme = Some(value),
+                _ => {}
+            }
+        }
+        Ok(extreme.cloned())
+    }
+}
+
 /// A result set from a query
 pub trait DriverResult: Send + Sync {
     /// Get the next row from the result set
@@ -24,6 +124,24 @@ pub trait DriverResult: Send + Sync {
         Ok(rows)
     }
 
+    /// Visit the remaining rows as borrowed views
+    ///
+    /// Text and binary values are handed out without copying them, for
+    /// read-heavy code that inspects values without keeping them. Drivers
+    /// buffering their rows borrow from the buffer; the default reads each
+    /// row with [`next_row`](Self::next_row). Stops at the first error of
+    /// the callback.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching a row fails or the callback fails.
+    fn for_each_row_ref(&mut self, f: &mut dyn FnMut(RowRef<'_>) -> Result<()>) -> Result<()> {
+        while let Some(row) = self.next_row()? {
+            f(RowRef::new(self.column_names(), &row))?;
+        }
+        Ok(())
+    }
+
     /// Get the number of columns
     fn column_count(&self) -> usize;
 
@@ -32,4 +150,118 @@ pub trait DriverResult: Send + Sync {
 
     /// Get the number of rows affected (for INSERT/UPDATE/DELETE)
     fn rows_affected(&self) -> u64;
+
+    /// Advance to the next result set
+    ///
+    /// Multi-statement batches and stored procedures can return several
+    /// result sets. Returns `false` when there are no more; the default
+    /// supports a single result set only.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the next result set fails.
+    fn next_result_set(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Collect the remaining rows of this and all following result sets
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching any row or result set fails.
+    fn result_sets(&mut self) -> Result<Vec<ResultSet>> {
+        let mut sets = Vec::new();
+        loop {
+            sets.push(ResultSet {
+                columns: self.columns(),
+                rows: self.fetch_rows()?,
+                rows_affected: self.rows_affected(),
+            });
+            if !self.next_result_set()? {
+                return Ok(sets);
+            }
+        }
+    }
+
+    /// Get the column metadata
+    ///
+    /// Drivers that know the originating tables should override this; the
+    /// default only carries the column names.
+    fn columns(&self) -> Vec<ResultColumn> {
+        self.column_names().iter().map(ResultColumn::new).collect()
+    }
+
+    /// Get the next row with its column metadata
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching the row fails.
+    fn fetch_row(&mut self) -> Result<Option<Row>> {
+        let columns: Arc<[ResultColumn]> = self.columns().into();
+        Ok(self.next_row()?.map(|values| Row::new(columns, values)))
+    }
+
+    /// Get all remaining rows with their column metadata
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if fetching any row fails.
+    fn fetch_rows(&mut self) -> Result<Vec<Row>> {
+        let columns: Arc<[ResultColumn]> = self.columns().into();
+        Ok(self
+            .all_rows()?
+            .into_iter()
+            .map(|values| Row::new(Arc::clone(&columns), values))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Error, QueryError};
+
+    fn result_set() -> ResultSet {
+        let columns: Arc<[ResultColumn]> = vec![ResultColumn::new("name"), ResultColumn::new("age")].into();
+        let ages = [SqlValue::I64(30), SqlValue::Null, SqlValue::I32(17), SqlValue::I64(30)];
+        let rows = ["Alice", "Bob", "Carol", "Dave"]
+            .into_iter()
+            .zip(ages)
+            .map(|(name, age)| Row::new(Arc::clone(&columns), vec![name.into(), age]))
+            .collect();
+        ResultSet {
+            columns: columns.to_vec(),
+            rows,
+            rows_affected: 0,
+        }
+    }
+
+    #[test]
+    fn test_to_maps() {
+        let maps = result_set().to_maps();
+        assert_eq!(maps.len(), 4);
+        assert_eq!(maps[0]["name"], SqlValue::from("Alice"));
+        assert_eq!(maps[1]["age"], SqlValue::Null);
+    }
+
+    #[test]
+    fn test_column_statistics() {
+        let result = result_set();
+        assert_eq!(result.column_min("age").unwrap(), Some(SqlValue::I32(17)));
+        assert_eq!(result.column_max("age").unwrap(), Some(SqlValue::I64(30)));
+        assert_eq!(result.column_max("name").unwrap(), Some(SqlValue::from("Dave")));
+        assert_eq!(result.column_distinct_count("age").unwrap(), 2);
+        assert_eq!(result.column_distinct_count("name").unwrap(), 4);
+        assert!(matches!(
+            result.column_min("email"),
+            Err(Error::Query(QueryError::ColumnNotFound(_)))
+        ));
+
+        let empty = ResultSet {
+            rows: Vec::new(),
+            ..result
+        };
+        assert_eq!(empty.column_min("age").unwrap(), None);
+        assert_eq!(empty.column_distinct_count("age").unwrap(), 0);
+    }
 }
@@ -0,0 +1,383 @@
+//! Result rows with column metadata
+
+use std::sync::Arc;
+
+use crate::core::{FromSql, QueryError, Result, SqlValue};
+
+/// A column of a result set
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultColumn {
+    /// Column name (or alias) as returned by the database
+    pub name: String,
+    /// Table the column originates from, if the driver reports it
+    pub table: Option<String>,
+}
+
+impl ResultColumn {
+    /// Create a column without table information
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            table: None,
+        }
+    }
+
+    /// Set the originating table
+    #[must_use]
+    pub fn with_table(mut self, table: impl Into<String>) -> Self {
+        self.table = Some(table.into());
+        self
+    }
+
+    /// Get the table-qualified name (`table.name`), or the plain name
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        self.table
+            .as_ref()
+            .map_or_else(|| self.name.clone(), |table| format!("{table}.{}", self.name))
+    }
+
+    /// Check if this column matches a plain or table-qualified name
+    fn matches(&self, name: &str) -> bool {
+        match name.split_once('.') {
+            Some((table, column)) if self.table.is_some() => {
+                self.table.as_deref() == Some(table) && self.name == column
+            }
+            _ => self.name == name,
+        }
+    }
+}
+
+/// A single result row with access by index or column name
+///
+/// Rows of the same result set share their column list. Name lookups are
+/// case-sensitive and accept `table.column` when the driver reports tables.
+/// When several columns share a name (e.g. `id` after a join), plain lookups
+/// fail with [`QueryError::AmbiguousColumn`]; use [`Row::get_indexed`] or a
+/// qualified name instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// Columns of the result set
+    columns: Arc<[ResultColumn]>,
+    /// Values in column order
+    values: Vec<SqlValue>,
+}
+
+impl Row {
+    /// Create a row from shared column metadata and values
+    #[must_use]
+    pub const fn new(columns: Arc<[ResultColumn]>, values: Vec<SqlValue>) -> Self {
+        Self { columns, values }
+    }
+
+    /// Get the columns of the row
+    #[must_use]
+    pub fn columns(&self) -> &[ResultColumn] {
+        &self.columns
+    }
+
+    /// Get the values in column order
+    #[must_use]
+    pub fn values(&self) -> &[SqlValue] {
+        &self.values
+    }
+
+    /// Take the values in column order
+    #[must_use]
+    pub fn into_values(self) -> Vec<SqlValue> {
+        self.values
+    }
+
+    /// Get the number of values
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if the row has no values
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get the index of a column by (optionally qualified) name
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::ColumnNotFound`] if no column matches and
+    /// [`QueryError::AmbiguousColumn`] if more than one does.
+    pub fn index_of(&self, name: &str) -> Result<usize> {
+        column_index(&self.columns, name)
+    }
+
+    /// Get a value by column index
+    #[must_use]
+    pub fn get_at(&self, index: usize) -> Option<&SqlValue> {
+        self.values.get(index)
+    }
+
+    /// Get a value by column name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name matches no column or several columns.
+    pub fn get_value(&self, name: &str) -> Result<&SqlValue> {
+        let index = self.index_of(name)?;
+        Ok(&self.values[index])
+    }
+
+    /// Get the value of the `occurrence`-th (0-based) column with this name
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::ColumnNotFound`] if there are fewer matching columns.
+    pub fn get_indexed(&self, name: &str, occurrence: usize) -> Result<&SqlValue> {
+        self.positions(name)
+            .nth(occurrence)
+            .map(|index| &self.values[index])
+            .ok_or_else(|| QueryError::ColumnNotFound(format!("{name}#{occurrence}")).into())
+    }
+
+    /// Get a value by column name, converted to `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column cannot be resolved or the value cannot
+    /// be converted.
+    pub fn get<T: FromSql>(&self, name: &str) -> Result<T> {
+        T::from_sql(self.get_value(name)?.clone())
+    }
+
+    /// Iterate over the indices of columns matching a name
+    fn positions<'a>(&'a self, name: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.columns
+            .iter()
+            .enumerate()
+            .filter(move |(_, column)| column.matches(name))
+            .map(|(index, _)| index)
+    }
+}
+
+/// A borrowed view of a value
+///
+/// Text and binary data point into the driver's buffer instead of being
+/// copied; [`to_owned`](Self::to_owned) makes an owned [`SqlValue`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueRef<'a> {
+    /// NULL
+    Null,
+    /// Text
+    Text(&'a str),
+    /// Binary data
+    Blob(&'a [u8]),
+    /// Any other value
+    Value(&'a SqlValue),
+}
+
+impl<'a> ValueRef<'a> {
+    /// Check if the value is NULL
+    #[must_use]
+    pub const fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Get the text, `None` for other values
+    #[must_use]
+    pub const fn as_str(&self) -> Option<&'a str> {
+        match self {
+            Self::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Get binary data or the bytes of text, `None` for other values
+    #[must_use]
+    pub const fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::Blob(bytes) => Some(bytes),
+            Self::Text(text) => Some(text.as_bytes()),
+            _ => None,
+        }
+    }
+
+    /// Copy the value into an owned `SqlValue`
+    #[must_use]
+    pub fn to_owned(&self) -> SqlValue {
+        match self {
+            Self::Null => SqlValue::Null,
+            Self::Text(text) => SqlValue::String((*text).to_string()),
+            Self::Blob(bytes) => SqlValue::Bytes(bytes.to_vec()),
+            Self::Value(value) => (*value).clone(),
+        }
+    }
+}
+
+impl<'a> From<&'a SqlValue> for ValueRef<'a> {
+    fn from(value: &'a SqlValue) -> Self {
+        match value {
+            SqlValue::Null => Self::Null,
+            SqlValue::String(text) => Self::Text(text),
+            SqlValue::Bytes(bytes) => Self::Blob(bytes),
+            value => Self::Value(value),
+        }
+    }
+}
+
+/// A borrowed result row, see [`DriverResult::for_each_row_ref`](super::DriverResult::for_each_row_ref)
+///
+/// Only valid while the callback runs; copy what must outlive it with
+/// [`ValueRef::to_owned`] or [`to_owned`](Self::to_owned).
+#[derive(Debug, Clone, Copy)]
+pub struct RowRef<'a> {
+    /// Column names of the result set
+    column_names: &'a [String],
+    /// Values in column order
+    values: &'a [SqlValue],
+}
+
+impl<'a> RowRef<'a> {
+    /// Create a row view over column names and values
+    #[must_use]
+    pub const fn new(column_names: &'a [String], values: &'a [SqlValue]) -> Self {
+        Self { column_names, values }
+    }
+
+    /// Get the column names of the row
+    #[must_use]
+    pub const fn column_names(&self) -> &'a [String] {
+        self.column_names
+    }
+
+    /// Get the number of values
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if the row has no values
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get a value by column index
+    #[must_use]
+    pub fn get_at(&self, index: usize) -> Option<ValueRef<'a>> {
+        self.values.get(index).map(ValueRef::from)
+    }
+
+    /// Get a value by column name
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::ColumnNotFound`] if no column has the name and
+    /// [`QueryError::AmbiguousColumn`] if more than one has.
+    pub fn get_value(&self, name: &str) -> Result<ValueRef<'a>> {
+        let mut positions = self.column_names.iter().enumerate().filter(|(_, column)| *column == name);
+        let (index, _) = positions
+            .next()
+            .ok_or_else(|| QueryError::ColumnNotFound(name.to_string()))?;
+        let others = positions.count();
+        if others > 0 {
+            return Err(QueryError::AmbiguousColumn {
+                name: name.to_string(),
+                count: others + 1,
+            }
+            .into());
+        }
+        Ok(ValueRef::from(&self.values[index]))
+    }
+
+    /// Copy the values into owned `SqlValue`s
+    #[must_use]
+    pub fn to_owned(&self) -> Vec<SqlValue> {
+        self.values.to_vec()
+    }
+}
+
+/// Get the index of the one column matching a (optionally qualified) name
+pub(super) fn column_index(columns: &[ResultColumn], name: &str) -> Result<usize> {
+    let mut matches = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| column.matches(name))
+        .map(|(index, _)| index);
+    let index = matches
+        .next()
+        .ok_or_else(|| QueryError::ColumnNotFound(name.to_string()))?;
+
+    let others = matches.count();
+    if others > 0 {
+        return Err(QueryError::AmbiguousColumn {
+            name: name.to_string(),
+            count: others + 1,
+        }
+        .into());
+    }
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Error;
+
+    fn joined_row() -> Row {
+        let columns: Arc<[ResultColumn]> = vec![
+            ResultColumn::new("id").with_table("users"),
+            ResultColumn::new("name").with_table("users"),
+            ResultColumn::new("id").with_table("orders"),
+        ]
+        .into();
+        Row::new(
+            columns,
+            vec![SqlValue::I64(1), SqlValue::String("Alice".into()), SqlValue::I64(10)],
+        )
+    }
+
+    #[test]
+    fn test_lookup_by_name() {
+        let row = joined_row();
+        assert_eq!(row.get::<String>("name").unwrap(), "Alice");
+        assert_eq!(row.get::<i64>("orders.id").unwrap(), 10);
+        assert_eq!(row.columns()[2].qualified_name(), "orders.id");
+        assert!(matches!(
+            row.get_value("total"),
+            Err(Error::Query(QueryError::ColumnNotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_columns() {
+        let row = joined_row();
+        assert!(matches!(
+            row.get_value("id"),
+            Err(Error::Query(QueryError::AmbiguousColumn { count: 2, .. }))
+        ));
+        assert_eq!(row.get_indexed("id", 0).unwrap(), &SqlValue::I64(1));
+        assert_eq!(row.get_indexed("id", 1).unwrap(), &SqlValue::I64(10));
+        assert!(row.get_indexed("id", 2).is_err());
+    }
+
+    #[test]
+    fn test_row_ref() {
+        let columns = vec!["id".to_string(), "name".to_string(), "avatar".to_string(), "id".to_string()];
+        let values = vec![SqlValue::I64(1), SqlValue::String("Alice".into()), SqlValue::Bytes(vec![1, 2]), SqlValue::Null];
+        let row = RowRef::new(&columns, &values);
+
+        let name = row.get_value("name").unwrap();
+        assert_eq!(name.as_str(), Some("Alice"));
+        assert!(std::ptr::eq(name.as_str().unwrap(), values[1].as_str().unwrap()));
+        assert_eq!(row.get_value("avatar").unwrap().as_bytes(), Some(&[1, 2][..]));
+        assert_eq!(row.get_at(0), Some(ValueRef::Value(&SqlValue::I64(1))));
+        assert!(row.get_at(3).unwrap().is_null());
+        assert!(matches!(
+            row.get_value("id"),
+            Err(Error::Query(QueryError::AmbiguousColumn { count: 2, .. }))
+        ));
+        assert!(row.get_value("email").is_err());
+        assert_eq!(name.to_owned(), SqlValue::String("Alice".into()));
+        assert_eq!(row.to_owned(), values);
+    }
+}
@@ -0,0 +1,65 @@
+//! Parameter binding for `SQLite` queries
+//!
+//! Converts `SqlValue`s into values sqlx can bind. Types without a native
+//! `SQLite` representation (dates, UUIDs, JSON, decimals) are bound as text
+//! using the connection's [`ValueFormatter`], or as bytes for binary UUIDs.
+//! `SQLite` has no exact numeric type: decimals are bound as exact text, which
+//! columns with NUMERIC affinity may still convert to REAL (use TEXT columns
+//! to keep every digit).
+
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments};
+
+use crate::core::SqlValue;
+use crate::platform::ValueFormatter;
+
+/// An sqlx query with `SQLite` arguments
+type SqliteQuery<'q> = Query<'q, Sqlite, SqliteArguments<'q>>;
+
+/// Bind all values to the query in order
+pub fn bind_values<'q>(
+    query: SqliteQuery<'q>,
+    values: &[SqlValue],
+    formatter: &ValueFormatter,
+) -> SqliteQuery<'q> {
+    values
+        .iter()
+        .fold(query, |query, value| bind_value(query, value, formatter))
+}
+
+/// Bind a single value to the query
+fn bind_value<'q>(
+    query: SqliteQuery<'q>,
+    value: &SqlValue,
+    formatter: &ValueFormatter,
+) -> SqliteQuery<'q> {
+    #[cfg(feature = "uuid")]
+    if let Some(bytes) = formatter.uuid_bytes(value) {
+        return query.bind(bytes);
+    }
+    if let Some(text) = formatter.format_value(value) {
+        return query.bind(text);
+    }
+
+    match value {
+        SqlValue::Null => query.bind(None::<i64>),
+        SqlValue::Bool(v) => query.bind(*v),
+        SqlValue::I8(v) => query.bind(i64::from(*v)),
+        SqlValue::I16(v) => query.bind(i64::from(*v)),
+        SqlValue::I32(v) => query.bind(i64::from(*v)),
+        SqlValue::I64(v) => query.bind(*v),
+        SqlValue::U32(v) => query.bind(i64::from(*v)),
+        // SQLite integers are signed 64-bit, larger values are kept as text
+        SqlValue::U64(v) => match i64::try_from(*v) {
+            Ok(v) => query.bind(v),
+            Err(_) => query.bind(v.to_string()),
+        },
+        SqlValue::F32(v) => query.bind(f64::from(*v)),
+        SqlValue::F64(v) => query.bind(*v),
+        SqlValue::String(v) => query.bind(v.clone()),
+        SqlValue::Bytes(v) => query.bind(v.clone()),
+        // Only reached if the configured format string is invalid
+        #[cfg(any(feature = "chrono", feature = "uuid", feature = "json", feature = "decimal"))]
+        _ => query.bind(ValueFormatter::default().format_value(value)),
+    }
+}
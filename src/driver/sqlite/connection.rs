@@ -1,49 +1,191 @@
 //! `SQLite` connection implementation
 
 use async_trait::async_trait;
-use sqlx::sqlite::SqliteConnection as SqlxSqliteConnection;
-use sqlx::Row;
+use futures::TryStreamExt;
+use sqlx::sqlite::{SqliteConnection as SqlxSqliteConnection, SqliteQueryResult, SqliteRow};
+use sqlx::{Either, Executor, Row};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use crate::core::{Error, QueryError, Result, SqlValue, TransactionError};
+use crate::core::{Error, QueryError, Result, SqlValue, TransactionError, TransactionStatus};
 use crate::driver::DriverConnection;
+use crate::platform::ValueFormatter;
 
-use super::{SqliteResult, SqliteStatement};
+use super::bind::bind_values;
+use super::{BusyRetryPolicy, SqliteResult, SqliteStatement};
 
-/// `SQLite` database connection
+/// Get the number of rows modified since the connection was opened
 ///
-/// Uses a single connection (not a pool) to ensure transactions work correctly.
-pub struct SqliteConnection {
+/// Reads the connection's counter with `sqlite3_total_changes64()` instead of
+/// running `SELECT total_changes()`, which would cost two statements per query.
+#[allow(unsafe_code)]
+async fn total_changes(conn: &mut SqlxSqliteConnection) -> sqlx::Result<i64> {
+    let mut handle = conn.lock_handle().await?;
+    // SAFETY: the raw handle stays valid while the connection is locked, and
+    // sqlite3_total_changes64() only reads a counter of it
+    Ok(unsafe { libsqlite3_sys::sqlite3_total_changes64(handle.as_raw_handle().as_ptr()) })
+}
+
+/// Connection state shared between a [`SqliteConnection`] and its statements
+///
+/// Prepared statements hold an `Arc` to this handle, so they always execute
+/// on the same underlying connection and inside the same transaction as the
+/// connection that prepared them.
+pub struct SqliteHandle {
     /// The underlying sqlx connection wrapped in a mutex for thread safety
-    inner: Mutex<SqlxSqliteConnection>,
+    conn: Mutex<SqlxSqliteConnection>,
     /// Whether a transaction is currently active
     in_transaction: AtomicBool,
+    /// Retry policy for statements failing with `SQLITE_BUSY`
+    busy_retry: BusyRetryPolicy,
+    /// Text formats for values without a native `SQLite` binding
+    formatter: ValueFormatter,
+}
+
+impl SqliteHandle {
+    /// Check if a transaction is currently active on this connection
+    pub(crate) fn in_transaction(&self) -> bool {
+        self.in_transaction.load(Ordering::SeqCst)
+    }
+
+    /// Run a query and collect all rows
+    ///
+    /// Rows affected and the last inserted rowid are captured as well, so
+    /// `INSERT ... RETURNING` reports how many rows were written. Each
+    /// statement of a batch becomes its own result set; within a batch that
+    /// modified rows, read-only statements repeat the preceding count, as
+    /// `SQLite` only tracks changes per connection.
+    pub(crate) async fn fetch(&self, sql: &str, params: &[SqlValue]) -> Result<SqliteResult> {
+        let (items, modified): (Vec<Either<SqliteQueryResult, SqliteRow>>, bool) = self
+            .busy_retry
+            .run(
+                || async {
+                    let mut conn = self.conn.lock().await;
+                    // `changes()` keeps the count of the last modifying statement,
+                    // so compare the total changes to tell if this query wrote rows
+                    let before = total_changes(&mut conn).await?;
+                    let query = bind_values(sqlx::query(sql), params, &self.formatter);
+                    let items = conn.fetch_many(query).try_collect().await?;
+                    let after = total_changes(&mut conn).await?;
+                    Ok((items, after != before))
+                },
+                SqliteConnection::execution_failed(sql),
+            )
+            .await?;
+
+        // Each statement yields its rows followed by its query result
+        let mut sets = Vec::new();
+        let mut rows = Vec::new();
+        for item in items {
+            match item {
+                Either::Left(done) => {
+                    let rows = std::mem::take(&mut rows);
+                    let (rows_affected, last_insert_rowid) = if modified {
+                        (done.rows_affected(), Some(done.last_insert_rowid()))
+                    } else {
+                        (0, None)
+                    };
+                    sets.push(
+                        SqliteConnection::to_result(&rows, rows_affected)
+                            .with_last_insert_rowid(last_insert_rowid),
+                    );
+                }
+                Either::Right(row) => rows.push(row),
+            }
+        }
+        if !rows.is_empty() {
+            sets.push(SqliteConnection::to_result(&rows, 0));
+        }
+
+        Ok(SqliteResult::from_sets(sets))
+    }
+
+    /// Execute a statement and return affected rows
+    pub(crate) async fn run(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        map_err: impl Fn(sqlx::Error) -> Error,
+    ) -> Result<u64> {
+        self.busy_retry
+            .run(
+                || async {
+                    let mut conn = self.conn.lock().await;
+                    bind_values(sqlx::query(sql), params, &self.formatter)
+                        .execute(&mut *conn)
+                        .await
+                },
+                map_err,
+            )
+            .await
+            .map(|result| result.rows_affected())
+    }
+}
+
+/// `SQLite` database connection
+///
+/// Uses a single connection (not a pool) to ensure transactions work correctly.
+/// Prepared statements share this connection, so they run inside the same
+/// transaction as queries executed on the connection itself.
+pub struct SqliteConnection {
+    /// Connection state, shared with prepared statements
+    handle: Arc<SqliteHandle>,
 }
 
 impl std::fmt::Debug for SqliteConnection {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SqliteConnection")
-            .field("in_transaction", &self.in_transaction.load(std::sync::atomic::Ordering::Relaxed))
+            .field("in_transaction", &self.handle.in_transaction())
+            .field("busy_retry", &self.handle.busy_retry)
             .finish_non_exhaustive()
     }
 }
 
 impl SqliteConnection {
     /// Create a new `SQLite` connection
-    pub(crate) fn new(conn: SqlxSqliteConnection) -> Self {
+    pub(crate) fn new(
+        conn: SqlxSqliteConnection,
+        busy_retry: BusyRetryPolicy,
+        formatter: ValueFormatter,
+    ) -> Self {
         Self {
-            inner: Mutex::new(conn),
-            in_transaction: AtomicBool::new(false),
+            handle: Arc::new(SqliteHandle {
+                conn: Mutex::new(conn),
+                in_transaction: AtomicBool::new(false),
+                busy_retry,
+                formatter,
+            }),
+        }
+    }
+
+    /// Map an sqlx error to `QueryError::ExecutionFailed` for the given SQL
+    pub(crate) fn execution_failed(sql: &str) -> impl Fn(sqlx::Error) -> Error + '_ {
+        move |e| QueryError::execution_failed(e.to_string(), Some(sql.to_string())).into()
+    }
+
+    /// Convert the rows of one statement into a result set
+    fn to_result(rows: &[SqliteRow], rows_affected: u64) -> SqliteResult {
+        // Extract column names from first row
+        let column_names = rows
+            .first()
+            .map(Self::extract_column_names)
+            .unwrap_or_default();
+
+        // Convert rows into one buffer
+        let mut values = Vec::with_capacity(rows.len() * column_names.len());
+        for row in rows {
+            Self::push_row_values(row, &mut values);
         }
+
+        SqliteResult::from_values(values, rows.len(), column_names, rows_affected)
     }
 
-    /// Convert sqlx row to Vec<SqlValue>
-    fn row_to_values(row: &sqlx::sqlite::SqliteRow) -> Vec<SqlValue> {
+    /// Append the values of an sqlx row to a buffer
+    fn push_row_values(row: &sqlx::sqlite::SqliteRow, values: &mut Vec<SqlValue>) {
         use sqlx::Column;
 
         let columns = row.columns();
-        let mut values = Vec::with_capacity(columns.len());
 
         for (i, col) in columns.iter().enumerate() {
             let type_info = col.type_info();
@@ -51,33 +193,33 @@ impl SqliteConnection {
 
             let value: SqlValue = match type_name.as_str() {
                 "INTEGER" | "INT" | "BIGINT" => {
-                    match row.try_get::<i64, _>(i) {
-                        Ok(v) => SqlValue::I64(v),
-                        Err(_) => SqlValue::Null,
+                    match row.try_get::<Option<i64>, _>(i) {
+                        Ok(Some(v)) => SqlValue::I64(v),
+                        Ok(None) | Err(_) => SqlValue::Null,
                     }
                 }
                 "REAL" | "DOUBLE" | "FLOAT" => {
-                    match row.try_get::<f64, _>(i) {
-                        Ok(v) => SqlValue::F64(v),
-                        Err(_) => SqlValue::Null,
+                    match row.try_get::<Option<f64>, _>(i) {
+                        Ok(Some(v)) => SqlValue::F64(v),
+                        Ok(None) | Err(_) => SqlValue::Null,
                     }
                 }
                 "TEXT" | "VARCHAR" | "CHAR" => {
-                    match row.try_get::<String, _>(i) {
-                        Ok(v) => SqlValue::String(v),
-                        Err(_) => SqlValue::Null,
+                    match row.try_get::<Option<String>, _>(i) {
+                        Ok(Some(v)) => SqlValue::String(v),
+                        Ok(None) | Err(_) => SqlValue::Null,
                     }
                 }
                 "BLOB" => {
-                    match row.try_get::<Vec<u8>, _>(i) {
-                        Ok(v) => SqlValue::Bytes(v),
-                        Err(_) => SqlValue::Null,
+                    match row.try_get::<Option<Vec<u8>>, _>(i) {
+                        Ok(Some(v)) => SqlValue::Bytes(v),
+                        Ok(None) | Err(_) => SqlValue::Null,
                     }
                 }
                 "BOOLEAN" | "BOOL" => {
-                    match row.try_get::<bool, _>(i) {
-                        Ok(v) => SqlValue::Bool(v),
-                        Err(_) => SqlValue::Null,
+                    match row.try_get::<Option<bool>, _>(i) {
+                        Ok(Some(v)) => SqlValue::Bool(v),
+                        Ok(None) | Err(_) => SqlValue::Null,
                     }
                 }
                 "NULL" => {
@@ -112,8 +254,6 @@ impl SqliteConnection {
             };
             values.push(value);
         }
-
-        values
     }
 
     /// Extract column names from rows
@@ -129,100 +269,49 @@ impl DriverConnection for SqliteConnection {
     type Result = SqliteResult;
 
     async fn prepare(&self, sql: &str) -> Result<Self::Statement> {
-        let conn = self.inner.lock().await;
-        Ok(SqliteStatement::new_with_connection(sql.to_string(), conn))
+        Ok(SqliteStatement::new(sql.to_string(), Arc::clone(&self.handle)))
     }
 
     async fn query(&self, sql: &str) -> Result<Self::Result> {
-        let mut conn = self.inner.lock().await;
-
-        let rows: Vec<sqlx::sqlite::SqliteRow> = sqlx::query(sql)
-            .fetch_all(&mut *conn)
-            .await
-            .map_err(|e| {
-                QueryError::ExecutionFailed {
-                    message: e.to_string(),
-                    sql: Some(sql.to_string()),
-                }
-            })?;
-
-        if rows.is_empty() {
-            return Ok(SqliteResult::new(Vec::new(), Vec::new(), 0));
-        }
-
-        // Extract column names from first row
-        let column_names = Self::extract_column_names(&rows[0]);
-
-        // Convert rows
-        let data: Vec<Vec<SqlValue>> = rows
-            .iter()
-            .map(Self::row_to_values)
-            .collect();
-
-        Ok(SqliteResult::new(data, column_names, 0))
+        self.handle.fetch(sql, &[]).await
     }
 
     async fn execute(&self, sql: &str) -> Result<u64> {
-        let mut conn = self.inner.lock().await;
-
-        let result = sqlx::query(sql)
-            .execute(&mut *conn)
-            .await
-            .map_err(|e| {
-                QueryError::ExecutionFailed {
-                    message: e.to_string(),
-                    sql: Some(sql.to_string()),
-                }
-            })?;
-
-        Ok(result.rows_affected())
+        self.handle.run(sql, &[], Self::execution_failed(sql)).await
     }
 
     async fn begin_transaction(&self) -> Result<()> {
-        if self.in_transaction.load(Ordering::SeqCst) {
+        if self.handle.in_transaction() {
             return Err(Error::Transaction(TransactionError::AlreadyActive));
         }
 
-        let mut conn = self.inner.lock().await;
-
-        sqlx::query("BEGIN TRANSACTION")
-            .execute(&mut *conn)
-            .await
-            .map_err(|e| {
-                QueryError::ExecutionFailed {
-                    message: e.to_string(),
-                    sql: Some("BEGIN TRANSACTION".to_string()),
-                }
-            })?;
+        let sql = "BEGIN TRANSACTION";
+        self.handle.run(sql, &[], Self::execution_failed(sql)).await?;
 
-        self.in_transaction.store(true, Ordering::SeqCst);
+        self.handle.in_transaction.store(true, Ordering::SeqCst);
         Ok(())
     }
 
     async fn commit(&self) -> Result<()> {
-        if !self.in_transaction.load(Ordering::SeqCst) {
+        if !self.handle.in_transaction() {
             return Err(Error::Transaction(TransactionError::NoActiveTransaction));
         }
 
-        let mut conn = self.inner.lock().await;
-
-        sqlx::query("COMMIT")
-            .execute(&mut *conn)
-            .await
-            .map_err(|e| {
-                TransactionError::CommitFailed(e.to_string())
-            })?;
+        self.handle.run("COMMIT", &[], |e| {
+            TransactionError::CommitFailed(e.to_string()).into()
+        })
+        .await?;
 
-        self.in_transaction.store(false, Ordering::SeqCst);
+        self.handle.in_transaction.store(false, Ordering::SeqCst);
         Ok(())
     }
 
     async fn rollback(&self) -> Result<()> {
-        if !self.in_transaction.load(Ordering::SeqCst) {
+        if !self.handle.in_transaction() {
             return Err(Error::Transaction(TransactionError::NoActiveTransaction));
         }
 
-        let mut conn = self.inner.lock().await;
+        let mut conn = self.handle.conn.lock().await;
 
         sqlx::query("ROLLBACK")
             .execute(&mut *conn)
@@ -231,12 +320,21 @@ impl DriverConnection for SqliteConnection {
                 TransactionError::RollbackFailed(e.to_string())
             })?;
 
-        self.in_transaction.store(false, Ordering::SeqCst);
+        self.handle.in_transaction.store(false, Ordering::SeqCst);
         Ok(())
     }
 
+    // SQLite has no failed transaction state: statements keep working after an error
+    fn transaction_status(&self) -> TransactionStatus {
+        if self.handle.in_transaction() {
+            TransactionStatus::Active
+        } else {
+            TransactionStatus::Idle
+        }
+    }
+
     async fn is_alive(&self) -> bool {
-        let mut conn = self.inner.lock().await;
+        let mut conn = self.handle.conn.lock().await;
         sqlx::query("SELECT 1")
             .fetch_one(&mut *conn)
             .await
@@ -244,17 +342,12 @@ impl DriverConnection for SqliteConnection {
     }
 
     async fn server_version(&self) -> Result<String> {
-        let mut conn = self.inner.lock().await;
+        let mut conn = self.handle.conn.lock().await;
 
         let row: sqlx::sqlite::SqliteRow = sqlx::query("SELECT sqlite_version()")
             .fetch_one(&mut *conn)
             .await
-            .map_err(|e| {
-                QueryError::ExecutionFailed {
-                    message: e.to_string(),
-                    sql: Some("SELECT sqlite_version()".to_string()),
-                }
-            })?;
+            .map_err(|e| QueryError::execution_failed(e.to_string(), Some("SELECT sqlite_version()".to_string())))?;
 
         let version: String = row.try_get(0).map_err(|e| {
             Error::conversion("SqliteRow", "String", e.to_string())
@@ -262,6 +355,13 @@ impl DriverConnection for SqliteConnection {
 
         Ok(version)
     }
+
+    async fn clear_statement_cache(&self) -> Result<()> {
+        let mut conn = self.handle.conn.lock().await;
+        sqlx::Connection::clear_cached_statements(&mut *conn)
+            .await
+            .map_err(|e| Error::driver("Failed to clear the statement cache", e))
+    }
 }
 
 #[cfg(test)]
@@ -335,6 +435,185 @@ mod tests {
         assert_eq!(rows[0][0], SqlValue::I64(1));
     }
 
+    #[tokio::test]
+    async fn test_busy_database_times_out() {
+        use crate::core::ConnectionParams;
+        use super::super::BusyRetryPolicy;
+        use std::time::Duration;
+
+        let path = std::env::temp_dir().join(format!("rustine_busy_{}.db", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let writer = SqliteDriver::new()
+            .connect(&ConnectionParams::sqlite().with_path(path_str))
+            .await
+            .unwrap();
+        writer.execute("CREATE TABLE test (id INTEGER)").await.unwrap();
+        writer.execute("BEGIN IMMEDIATE").await.unwrap();
+
+        let policy = BusyRetryPolicy::new()
+            .with_busy_timeout(Duration::ZERO)
+            .with_max_wait(Duration::from_millis(50));
+        let blocked = SqliteDriver::new()
+            .with_busy_retry(policy)
+            .connect(&ConnectionParams::sqlite().with_path(path_str))
+            .await
+            .unwrap();
+
+        let result = blocked.execute("INSERT INTO test (id) VALUES (1)").await;
+        assert!(matches!(result, Err(Error::Query(QueryError::Timeout(50)))));
+
+        // Once the lock is released the statement succeeds
+        writer.execute("COMMIT").await.unwrap();
+        assert_eq!(blocked.execute("INSERT INTO test (id) VALUES (1)").await.unwrap(), 1);
+
+        drop(writer);
+        drop(blocked);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_prepared_statement_shares_transaction() {
+        use crate::driver::DriverStatement;
+
+        let driver = SqliteDriver::new();
+        let params = crate::core::ConnectionParams::sqlite_memory();
+        let conn = driver.connect(&params).await.unwrap();
+
+        conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+        conn.begin_transaction().await.unwrap();
+
+        let mut stmt = conn.prepare("INSERT INTO test (id, name) VALUES (?, ?)").await.unwrap();
+        stmt.bind(0, SqlValue::I64(1)).unwrap();
+        stmt.bind(1, SqlValue::String("Alice".to_string())).unwrap();
+        assert_eq!(stmt.execute_update().await.unwrap(), 1);
+
+        // The insert is visible inside the transaction and undone by rollback
+        let mut result = conn.query("SELECT COUNT(*) FROM test").await.unwrap();
+        assert_eq!(result.all_rows().unwrap()[0][0], SqlValue::I64(1));
+
+        conn.rollback().await.unwrap();
+        let mut result = conn.query("SELECT COUNT(*) FROM test").await.unwrap();
+        assert_eq!(result.all_rows().unwrap()[0][0], SqlValue::I64(0));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn test_bind_datetime_uses_value_formatter() {
+        use crate::driver::DriverStatement;
+        use crate::platform::ValueFormatter;
+
+        let formatter = ValueFormatter::new("%Y%m%dT%H%M%S", "%Y-%m-%d", "%H:%M:%S");
+        let driver = SqliteDriver::new().with_value_formatter(formatter);
+        let conn = driver
+            .connect(&crate::core::ConnectionParams::sqlite_memory())
+            .await
+            .unwrap();
+
+        let dt = chrono::NaiveDate::from_ymd_opt(2024, 3, 15)
+            .and_then(|d| d.and_hms_opt(10, 30, 0))
+            .unwrap();
+        let mut stmt = conn.prepare("SELECT ?").await.unwrap();
+        stmt.bind(0, SqlValue::DateTime(dt)).unwrap();
+        let mut result = stmt.execute().await.unwrap();
+
+        assert_eq!(
+            result.all_rows().unwrap()[0][0],
+            SqlValue::String("20240315T103000".to_string())
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[tokio::test]
+    async fn test_bind_binary_uuid() {
+        use crate::core::FromSql;
+        use crate::driver::DriverStatement;
+        use crate::platform::{Platform, SqlType, SqlitePlatform, ValueFormatter};
+
+        let platform = SqlitePlatform::new().with_binary_uuid(true);
+        let driver = SqliteDriver::new().with_value_formatter(ValueFormatter::for_platform(&platform));
+        let conn = driver
+            .connect(&crate::core::ConnectionParams::sqlite_memory())
+            .await
+            .unwrap();
+
+        let declaration = platform.get_type_declaration(&SqlType::Uuid);
+        conn.execute(&format!("CREATE TABLE test (id {declaration})")).await.unwrap();
+
+        let uuid = uuid::Uuid::new_v4();
+        let mut stmt = conn.prepare("INSERT INTO test (id) VALUES (?)").await.unwrap();
+        stmt.bind(0, SqlValue::Uuid(uuid)).unwrap();
+        stmt.execute().await.unwrap();
+
+        let mut result = conn.query("SELECT id, typeof(id) FROM test").await.unwrap();
+        let row = result.all_rows().unwrap().remove(0);
+        assert_eq!(row[1], SqlValue::String("blob".to_string()));
+        assert_eq!(uuid::Uuid::from_sql(row[0].clone()).unwrap(), uuid);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[tokio::test]
+    async fn test_bind_decimal() {
+        use crate::core::FromSql;
+        use crate::driver::DriverStatement;
+        use rust_decimal::Decimal;
+
+        let conn = SqliteDriver::new()
+            .connect(&crate::core::ConnectionParams::sqlite_memory())
+            .await
+            .unwrap();
+        conn.execute("CREATE TABLE test (price DECIMAL(10, 2), exact TEXT)").await.unwrap();
+
+        let price = Decimal::new(1250, 2);
+        let exact = Decimal::new(12_345_678_901_234_567, 10);
+        let mut stmt = conn.prepare("INSERT INTO test (price, exact) VALUES (?, ?)").await.unwrap();
+        stmt.bind(0, SqlValue::Decimal(price)).unwrap();
+        stmt.bind(1, SqlValue::Decimal(exact)).unwrap();
+        stmt.execute().await.unwrap();
+
+        let mut result = conn.query("SELECT price, exact FROM test").await.unwrap();
+        let row = result.all_rows().unwrap().remove(0);
+        assert_eq!(Decimal::from_sql(row[0].clone()).unwrap().normalize(), price.normalize());
+        assert_eq!(Decimal::from_sql(row[1].clone()).unwrap(), exact);
+    }
+
+    #[tokio::test]
+    async fn test_query_reports_rows_affected() {
+        let driver = SqliteDriver::new();
+        let params = crate::core::ConnectionParams::sqlite_memory();
+        let conn = driver.connect(&params).await.unwrap();
+        conn.execute("CREATE TABLE test (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+
+        let mut result = conn
+            .query("INSERT INTO test (name) VALUES ('a'), ('b') RETURNING id")
+            .await
+            .unwrap();
+        assert_eq!(result.rows_affected(), 2);
+        assert_eq!(result.last_insert_rowid(), Some(2));
+        assert_eq!(result.all_rows().unwrap().len(), 2);
+
+        let result = conn.query("SELECT * FROM test WHERE id > 5").await.unwrap();
+        assert_eq!(result.rows_affected(), 0);
+        assert_eq!(result.last_insert_rowid(), None);
+    }
+
+    #[tokio::test]
+    async fn test_clear_statement_cache() {
+        let conn = SqliteDriver::new()
+            .connect(&crate::core::ConnectionParams::sqlite_memory())
+            .await
+            .unwrap();
+        conn.execute("CREATE TABLE test (id INTEGER)").await.unwrap();
+        conn.query("SELECT id FROM test").await.unwrap();
+
+        let cached = || async { sqlx::Connection::cached_statements_size(&*conn.handle.conn.lock().await) };
+        assert!(cached().await > 0);
+
+        conn.clear_statement_cache().await.unwrap();
+        assert_eq!(cached().await, 0);
+    }
+
     #[tokio::test]
     async fn test_transaction_rollback() {
         let driver = SqliteDriver::new();
@@ -4,53 +4,175 @@ use async_trait::async_trait;
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::ConnectOptions;
 use std::str::FromStr;
+use std::time::Duration;
 
-use crate::core::{ConnectionError, ConnectionParams, Result};
+use crate::core::{Configuration, ConnectionError, ConnectionParams, Error, Result};
 use crate::driver::Driver;
+use crate::platform::{SqlitePlatform, ValueFormatter};
 
-use super::SqliteConnection;
+use super::{BusyRetryPolicy, SqliteConnection};
 
 /// `SQLite` database driver
 #[derive(Debug, Default)]
-pub struct SqliteDriver;
+pub struct SqliteDriver {
+    /// Retry policy for statements failing with `SQLITE_BUSY`
+    busy_retry: BusyRetryPolicy,
+    /// Text formats for dates/times (defaults to the `SQLite` platform formats)
+    value_formatter: Option<ValueFormatter>,
+}
 
 impl SqliteDriver {
     /// Create a new `SQLite` driver instance
     #[must_use]
     pub const fn new() -> Self {
-        Self
+        Self {
+            busy_retry: BusyRetryPolicy::new(),
+            value_formatter: None,
+        }
     }
-}
 
-#[async_trait]
-impl Driver for SqliteDriver {
-    type Connection = SqliteConnection;
+    /// Set the retry policy for busy/locked databases
+    #[must_use]
+    pub const fn with_busy_retry(mut self, policy: BusyRetryPolicy) -> Self {
+        self.busy_retry = policy;
+        self
+    }
 
-    async fn connect(&self, params: &ConnectionParams) -> Result<Self::Connection> {
-        // Build connection options
+    /// Set the formatter for values bound as text (dates, times, UUIDs, ...)
+    ///
+    /// Use `ValueFormatter::for_platform(&SqlitePlatform::new()).with_configuration(&config)`
+    /// to apply `Configuration` format overrides.
+    #[must_use]
+    pub fn with_value_formatter(mut self, formatter: ValueFormatter) -> Self {
+        self.value_formatter = Some(formatter);
+        self
+    }
+
+    /// Get the retry policy for busy/locked databases
+    #[must_use]
+    pub const fn busy_retry(&self) -> &BusyRetryPolicy {
+        &self.busy_retry
+    }
+
+    /// Resolve the effective retry policy for the given parameters
+    ///
+    /// The `busy_timeout` option (milliseconds) overrides the driver default.
+    fn retry_policy(&self, params: &ConnectionParams) -> Result<BusyRetryPolicy> {
+        let mut policy = self.busy_retry;
+        if let Some(value) = params.options.get("busy_timeout") {
+            let millis: u64 = value.parse().map_err(|_| {
+                Error::config(format!("Invalid busy_timeout option: {value}"))
+            })?;
+            policy.busy_timeout = Duration::from_millis(millis);
+        }
+        Ok(policy)
+    }
+
+    /// Get the connect timeout, a `connect_timeout` URI option (milliseconds) overriding `configured`
+    fn connect_timeout(params: &ConnectionParams, configured: Option<Duration>) -> Result<Option<Duration>> {
+        params.options.get("connect_timeout").map_or(Ok(configured), |value| {
+            let millis: u64 = value.parse().map_err(|_| {
+                Error::config(format!("Invalid connect_timeout option: {value}"))
+            })?;
+            Ok(Some(Duration::from_millis(millis)))
+        })
+    }
+
+    /// Build the sqlx connect options from the path and URI options
+    ///
+    /// `mode` (`ro`, `rw`, `rwc`, `memory`), `cache` (`shared`, `private`) and
+    /// `immutable` (`1`/`true`) follow the `SQLite` URI parameters.
+    fn connect_options(params: &ConnectionParams) -> Result<SqliteConnectOptions> {
         let path = params.path.as_deref().unwrap_or(":memory:");
+        let option = |key: &str| params.options.get(key).map(String::as_str);
 
-        let options = if path == ":memory:" {
+        let mut options = if path == ":memory:" || option("mode") == Some("memory") {
             SqliteConnectOptions::from_str("sqlite::memory:")
                 .map_err(|e| ConnectionError::InvalidUrl(e.to_string()))?
         } else {
-            SqliteConnectOptions::new()
-                .filename(path)
-                .create_if_missing(true)
+            SqliteConnectOptions::new().filename(path)
+        };
+
+        options = match option("mode") {
+            None | Some("rwc" | "memory") => options.create_if_missing(true),
+            Some("rw") => options.create_if_missing(false),
+            Some("ro") => options.read_only(true),
+            Some(mode) => return Err(Error::config(format!("Invalid SQLite mode: {mode}"))),
+        };
+
+        options = match option("cache") {
+            None | Some("private") => options,
+            Some("shared") => options.shared_cache(true),
+            Some(cache) => return Err(Error::config(format!("Invalid SQLite cache: {cache}"))),
         };
 
+        match option("immutable") {
+            None | Some("0" | "false") => Ok(options),
+            Some("1" | "true") => Ok(options.immutable(true)),
+            Some(value) => Err(Error::config(format!("Invalid SQLite immutable flag: {value}"))),
+        }
+    }
+
+    /// Open a connection with the given value formatter and connect timeout
+    async fn open(
+        &self,
+        params: &ConnectionParams,
+        formatter: ValueFormatter,
+        connect_timeout: Option<Duration>,
+    ) -> Result<SqliteConnection> {
+        let options = Self::connect_options(params)?;
+        let busy_retry = self.retry_policy(params)?;
+
         // Apply additional options
         let options = options
+            .busy_timeout(busy_retry.busy_timeout)
             .disable_statement_logging()
             .clone();
 
         // Create a single connection (not a pool) for proper transaction support
-        let conn = options
-            .connect()
-            .await
-            .map_err(|e| ConnectionError::Refused(e.to_string()))?;
+        let connect = options.connect();
+        let conn = match connect_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, connect).await.map_err(|_| {
+                ConnectionError::Timeout(u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX))
+            })?,
+            None => connect.await,
+        }
+        .map_err(|e| ConnectionError::Refused(e.to_string()))?;
 
-        Ok(SqliteConnection::new(conn))
+        Ok(SqliteConnection::new(conn, busy_retry, formatter))
+    }
+}
+
+#[async_trait]
+impl Driver for SqliteDriver {
+    type Connection = SqliteConnection;
+
+    async fn connect(&self, params: &ConnectionParams) -> Result<Self::Connection> {
+        let formatter = self
+            .value_formatter
+            .clone()
+            .unwrap_or_else(|| ValueFormatter::for_platform(&SqlitePlatform::new()));
+
+        self.open(params, formatter, Self::connect_timeout(params, None)?).await
+    }
+
+    /// Connect applying the connect timeout and the date/time format overrides
+    ///
+    /// A formatter set with [`SqliteDriver::with_value_formatter`] takes
+    /// precedence over the configured formats. Schema, charset, application
+    /// name, socket timeouts and keepalive have no meaning for `SQLite` and
+    /// are ignored.
+    async fn connect_with_configuration(
+        &self,
+        params: &ConnectionParams,
+        configuration: &Configuration,
+    ) -> Result<Self::Connection> {
+        let formatter = self.value_formatter.clone().unwrap_or_else(|| {
+            ValueFormatter::for_platform(&SqlitePlatform::new()).with_configuration(configuration)
+        });
+
+        let connect_timeout = Self::connect_timeout(params, configuration.connect_timeout)?;
+        self.open(params, formatter, connect_timeout).await
     }
 
     fn name(&self) -> &'static str {
@@ -71,6 +193,76 @@ mod tests {
         assert!(conn.is_ok());
     }
 
+    #[test]
+    fn test_busy_timeout_option() {
+        let driver = SqliteDriver::new();
+        let params = ConnectionParams::sqlite_memory().with_option("busy_timeout", "250");
+        let policy = driver.retry_policy(&params).unwrap();
+        assert_eq!(policy.busy_timeout, Duration::from_millis(250));
+
+        let params = ConnectionParams::sqlite_memory().with_option("busy_timeout", "soon");
+        assert!(driver.retry_policy(&params).is_err());
+    }
+
+    #[test]
+    fn test_connect_timeout_option() {
+        let configured = Some(Duration::from_secs(30));
+        let params = ConnectionParams::sqlite_memory();
+        assert_eq!(SqliteDriver::connect_timeout(&params, configured).unwrap(), configured);
+
+        let params = params.with_option("connect_timeout", "1500");
+        assert_eq!(
+            SqliteDriver::connect_timeout(&params, configured).unwrap(),
+            Some(Duration::from_millis(1500))
+        );
+        let params = ConnectionParams::sqlite_memory().with_option("connect_timeout", "later");
+        assert!(SqliteDriver::connect_timeout(&params, None).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn test_connect_with_configuration() {
+        use crate::core::SqlValue;
+        use crate::driver::{DriverConnection, DriverResult, DriverStatement};
+
+        let configuration = Configuration::new().with_date_format("%d.%m.%Y");
+        let conn = SqliteDriver::new()
+            .connect_with_configuration(&ConnectionParams::sqlite_memory(), &configuration)
+            .await
+            .unwrap();
+
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let mut stmt = conn.prepare("SELECT ?").await.unwrap();
+        stmt.bind(0, SqlValue::Date(date)).unwrap();
+        let mut result = stmt.execute().await.unwrap();
+        assert_eq!(
+            result.all_rows().unwrap(),
+            vec![vec![SqlValue::String("15.01.2024".to_string())]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_only_mode() {
+        use crate::driver::DriverConnection;
+
+        let path = std::env::temp_dir().join(format!("rustine_ro_{}.db", std::process::id()));
+        let params = ConnectionParams::sqlite().with_path(path.display().to_string());
+
+        let conn = SqliteDriver::new().connect(&params).await.unwrap();
+        conn.execute("CREATE TABLE test (id INTEGER)").await.unwrap();
+        drop(conn);
+
+        let params = params.with_option("mode", "ro").with_option("cache", "shared");
+        let conn = SqliteDriver::new().connect(&params).await.unwrap();
+        assert!(conn.query("SELECT * FROM test").await.is_ok());
+        assert!(conn.execute("INSERT INTO test VALUES (1)").await.is_err());
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+
+        let params = ConnectionParams::sqlite_memory().with_option("mode", "rx");
+        assert!(SqliteDriver::new().connect(&params).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_driver_name() {
         let driver = SqliteDriver::new();
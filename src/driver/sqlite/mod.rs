@@ -2,12 +2,15 @@
 //!
 //! This module provides `SQLite` database connectivity using sqlx.
 
+mod bind;
 mod driver;
 mod connection;
 mod statement;
 mod result;
+mod retry;
 
 pub use driver::SqliteDriver;
 pub use connection::SqliteConnection;
 pub use statement::SqliteStatement;
 pub use result::SqliteResult;
+pub use retry::BusyRetryPolicy;
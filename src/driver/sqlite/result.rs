@@ -1,42 +1,117 @@
 //! `SQLite` result set implementation
 
+use std::collections::VecDeque;
+
 use crate::core::{Result, SqlValue};
-use crate::driver::DriverResult;
+use crate::driver::{DriverResult, RowRef};
 
 /// `SQLite` query result
+///
+/// Values of all rows are kept in one row-major buffer instead of a vector
+/// per row, so large result sets cost one allocation for their cells.
 #[derive(Debug)]
 pub struct SqliteResult {
-    /// Result rows as vectors of SQL values
-    rows: Vec<Vec<SqlValue>>,
+    /// Values of all rows, one row of `column_names.len()` values after another
+    values: Vec<SqlValue>,
+    /// Number of rows in `values`
+    row_count: usize,
     /// Column names from the result set
     column_names: Vec<String>,
     /// Number of rows affected by the query
     rows_affected: u64,
+    /// Rowid of the last inserted row, if the query inserted rows
+    last_insert_rowid: Option<i64>,
     /// Current row index for iteration
     current_index: usize,
+    /// Result sets of the following statements in a multi-statement query
+    next_sets: VecDeque<Self>,
 }
 
 impl SqliteResult {
-    /// Create a new result set
-    pub(crate) const fn new(rows: Vec<Vec<SqlValue>>, column_names: Vec<String>, rows_affected: u64) -> Self {
+    /// Create a result set from rows of values
+    pub(crate) fn new(rows: Vec<Vec<SqlValue>>, column_names: Vec<String>, rows_affected: u64) -> Self {
+        let row_count = rows.len();
+        Self::from_values(rows.into_iter().flatten().collect(), row_count, column_names, rows_affected)
+    }
+
+    /// Create a result set from the row-major values of `row_count` rows
+    pub(crate) const fn from_values(
+        values: Vec<SqlValue>,
+        row_count: usize,
+        column_names: Vec<String>,
+        rows_affected: u64,
+    ) -> Self {
         Self {
-            rows,
+            values,
+            row_count,
             column_names,
             rows_affected,
+            last_insert_rowid: None,
             current_index: 0,
+            next_sets: VecDeque::new(),
         }
     }
+
+    /// Create a result from the result sets of a multi-statement query
+    ///
+    /// The first set is current, the others follow via `next_result_set()`:
+    /// rows and affected rows are not combined across statements.
+    pub(crate) fn from_sets(sets: Vec<Self>) -> Self {
+        let mut sets: VecDeque<Self> = sets.into();
+        let mut first = sets
+            .pop_front()
+            .unwrap_or_else(|| Self::new(Vec::new(), Vec::new(), 0));
+        first.next_sets = sets;
+        first
+    }
+
+    /// Set the rowid of the last inserted row
+    #[must_use]
+    pub(crate) const fn with_last_insert_rowid(mut self, rowid: Option<i64>) -> Self {
+        self.last_insert_rowid = rowid;
+        self
+    }
+
+    /// Get the rowid of the last row inserted by the query
+    ///
+    /// This is `SQLite`'s `last_insert_rowid()` after a query that modified
+    /// rows, and `None` if the query didn't modify any (e.g. a plain SELECT).
+    #[must_use]
+    pub const fn last_insert_rowid(&self) -> Option<i64> {
+        self.last_insert_rowid
+    }
 }
 
 impl DriverResult for SqliteResult {
     fn next_row(&mut self) -> Result<Option<Vec<SqlValue>>> {
-        if self.current_index >= self.rows.len() {
+        if self.current_index >= self.row_count {
             return Ok(None);
         }
-
-        let row = self.rows[self.current_index].clone();
+        let width = self.column_names.len();
+        let start = self.current_index * width;
         self.current_index += 1;
-        Ok(Some(row))
+        // Rows are read once, so their values are moved out instead of cloned
+        Ok(Some(self.values[start..start + width].iter_mut().map(std::mem::take).collect()))
+    }
+
+    fn all_rows(&mut self) -> Result<Vec<Vec<SqlValue>>> {
+        let width = self.column_names.len();
+        let mut values = std::mem::take(&mut self.values).into_iter().skip(self.current_index * width);
+        let rows = (self.current_index..self.row_count)
+            .map(|_| values.by_ref().take(width).collect())
+            .collect();
+        self.current_index = self.row_count;
+        Ok(rows)
+    }
+
+    fn for_each_row_ref(&mut self, f: &mut dyn FnMut(RowRef<'_>) -> Result<()>) -> Result<()> {
+        let width = self.column_names.len();
+        while self.current_index < self.row_count {
+            let start = self.current_index * width;
+            self.current_index += 1;
+            f(RowRef::new(&self.column_names, &self.values[start..start + width]))?;
+        }
+        Ok(())
     }
 
     fn column_count(&self) -> usize {
@@ -50,6 +125,15 @@ impl DriverResult for SqliteResult {
     fn rows_affected(&self) -> u64 {
         self.rows_affected
     }
+
+    fn next_result_set(&mut self) -> Result<bool> {
+        let Some(mut next) = self.next_sets.pop_front() else {
+            return Ok(false);
+        };
+        next.next_sets = std::mem::take(&mut self.next_sets);
+        *self = next;
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -108,5 +192,61 @@ mod tests {
         assert_eq!(all[0][0], SqlValue::I64(1));
         assert_eq!(all[1][0], SqlValue::I64(2));
         assert_eq!(all[2][0], SqlValue::I64(3));
+
+        // Rows read with next_row() are not returned again
+        let rows = vec![vec![SqlValue::I64(1)], vec![SqlValue::I64(2)], vec![SqlValue::I64(3)]];
+        let mut result = SqliteResult::new(rows, vec!["num".to_string()], 0);
+        assert_eq!(result.next_row().unwrap(), Some(vec![SqlValue::I64(1)]));
+        assert_eq!(result.all_rows().unwrap(), [[SqlValue::I64(2)], [SqlValue::I64(3)]]);
+        assert_eq!(result.next_row().unwrap(), None);
+    }
+
+    #[test]
+    fn test_for_each_row_ref() {
+        let rows = vec![
+            vec![SqlValue::I64(1), SqlValue::String("Alice".to_string())],
+            vec![SqlValue::I64(2), SqlValue::String("Bob".to_string())],
+        ];
+        let mut result = SqliteResult::new(rows, vec!["id".to_string(), "name".to_string()], 0);
+        assert!(result.next_row().unwrap().is_some());
+
+        let mut length = 0;
+        result
+            .for_each_row_ref(&mut |row| {
+                length += row.get_value("name")?.as_str().map_or(0, str::len);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(length, 3);
+        assert!(result.next_row().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fetch_rows_with_duplicate_columns() {
+        use crate::core::{Error, QueryError};
+
+        let rows = vec![vec![SqlValue::I64(1), SqlValue::I64(10)]];
+        let columns = vec!["id".to_string(), "id".to_string()];
+        let mut result = SqliteResult::new(rows, columns, 0);
+
+        let rows = result.fetch_rows().unwrap();
+        assert!(matches!(
+            rows[0].get_value("id"),
+            Err(Error::Query(QueryError::AmbiguousColumn { .. }))
+        ));
+        assert_eq!(rows[0].get_indexed("id", 1).unwrap(), &SqlValue::I64(10));
+    }
+
+    #[test]
+    fn test_multiple_result_sets() {
+        let first = SqliteResult::new(vec![vec![SqlValue::I64(1)]], vec!["a".to_string()], 0);
+        let second = SqliteResult::new(Vec::new(), Vec::new(), 3);
+        let mut result = SqliteResult::from_sets(vec![first, second]);
+
+        let sets = result.result_sets().unwrap();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].rows[0].get::<i64>("a").unwrap(), 1);
+        assert_eq!(sets[1].rows_affected, 3);
+        assert!(!result.next_result_set().unwrap());
     }
 }
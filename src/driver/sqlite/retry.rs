@@ -0,0 +1,173 @@
+//! Busy retry handling for `SQLite`
+//!
+//! Concurrent writers on the same database file regularly run into
+//! `SQLITE_BUSY` ("database is locked"). The `busy_timeout` pragma makes
+//! `SQLite` wait inside a single call, but some situations (e.g. lock upgrades
+//! in deferred transactions) return `SQLITE_BUSY` immediately. This module
+//! retries such statements with exponential backoff.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::core::{Error, QueryError, Result};
+
+/// Primary result code `SQLITE_BUSY`
+const SQLITE_BUSY: i64 = 5;
+
+/// Primary result code `SQLITE_LOCKED`
+const SQLITE_LOCKED: i64 = 6;
+
+/// Retry policy for statements failing with `SQLITE_BUSY` / `SQLITE_LOCKED`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusyRetryPolicy {
+    /// Value for the `busy_timeout` pragma (wait inside `SQLite` itself)
+    pub busy_timeout: Duration,
+    /// Maximum total time spent retrying a single statement
+    pub max_wait: Duration,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between two retries
+    pub max_backoff: Duration,
+}
+
+impl BusyRetryPolicy {
+    /// Create the default policy (5s busy timeout, retry up to 5s)
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            busy_timeout: Duration::from_secs(5),
+            max_wait: Duration::from_secs(5),
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(500),
+        }
+    }
+
+    /// Policy that never retries (only the `busy_timeout` pragma applies)
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self {
+            max_wait: Duration::ZERO,
+            ..Self::new()
+        }
+    }
+
+    /// Set the `busy_timeout` pragma value
+    #[must_use]
+    pub const fn with_busy_timeout(mut self, timeout: Duration) -> Self {
+        self.busy_timeout = timeout;
+        self
+    }
+
+    /// Set the maximum total retry time
+    #[must_use]
+    pub const fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// Set the initial and maximum backoff between retries
+    #[must_use]
+    pub const fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Delay before the given retry attempt (0-based), doubling each time
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Run an operation, retrying while it fails with a busy error
+    ///
+    /// Non-busy errors are converted with `map_err` and returned immediately.
+    /// When the retry budget is exhausted, `QueryError::Timeout` is returned.
+    pub(crate) async fn run<T, F, Fut, M>(&self, mut op: F, map_err: M) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = std::result::Result<T, sqlx::Error>>,
+        M: Fn(sqlx::Error) -> Error,
+    {
+        let started = Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(e) if is_busy(&e) => {
+                    let delay = self.backoff(attempt);
+                    if started.elapsed() + delay > self.max_wait {
+                        return Err(timeout_error(self.max_wait));
+                    }
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(map_err(e)),
+            }
+        }
+    }
+}
+
+impl Default for BusyRetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check if an sqlx error is a `SQLITE_BUSY` / `SQLITE_LOCKED` error
+fn is_busy(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(db) => {
+            let code = db.code().and_then(|c| c.parse::<i64>().ok());
+            // Extended result codes carry the primary code in the lowest byte
+            matches!(code.map(|c| c & 0xff), Some(SQLITE_BUSY | SQLITE_LOCKED))
+                || db.message().contains("database is locked")
+        }
+        _ => false,
+    }
+}
+
+/// Build the error returned once the retry budget is exhausted
+fn timeout_error(max_wait: Duration) -> Error {
+    let millis = u64::try_from(max_wait.as_millis()).unwrap_or(u64::MAX);
+    Error::Query(QueryError::Timeout(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_until_max() {
+        let policy = BusyRetryPolicy::new()
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(50));
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(10));
+        assert_eq!(policy.backoff(1), Duration::from_millis(20));
+        assert_eq!(policy.backoff(2), Duration::from_millis(40));
+        assert_eq!(policy.backoff(3), Duration::from_millis(50));
+        assert_eq!(policy.backoff(40), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_non_database_error_is_not_busy() {
+        assert!(!is_busy(&sqlx::Error::RowNotFound));
+    }
+
+    #[tokio::test]
+    async fn test_run_passes_through_other_errors() {
+        let policy = BusyRetryPolicy::new();
+        let result: Result<()> = policy
+            .run(
+                || async { Err(sqlx::Error::RowNotFound) },
+                |e| Error::driver_message(e.to_string()),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::Driver { .. })));
+    }
+}
@@ -1,21 +1,20 @@
 //! `SQLite` prepared statement implementation
 
 use async_trait::async_trait;
-use sqlx::sqlite::SqliteConnection as SqlxSqliteConnection;
 use std::collections::HashMap;
-use tokio::sync::MutexGuard;
+use std::sync::Arc;
 
-use crate::core::{Result, SqlValue};
-use crate::driver::DriverStatement;
+use crate::core::{QueryError, Result, SqlValue};
+use crate::driver::{rewrite_placeholders, DriverStatement, Placeholder, PlaceholderStyle};
 
-use super::SqliteResult;
+use super::connection::SqliteHandle;
+use super::{SqliteConnection, SqliteResult};
 
 /// `SQLite` prepared statement
 ///
-/// Note: This implementation stores the SQL and parameters, and executes
-/// them when `execute()` or `execute_update()` is called. For true prepared
-/// statement support with connection binding, use the `Connection` directly.
-#[derive(Debug)]
+/// The statement shares the underlying connection with the
+/// [`SqliteConnection`] that prepared it, so it runs inside the same
+/// transaction. Parameters are collected and bound on execution.
 pub struct SqliteStatement {
     /// The SQL query string
     sql: String,
@@ -23,59 +22,62 @@ pub struct SqliteStatement {
     positional_params: HashMap<usize, SqlValue>,
     /// Named parameters indexed by name
     named_params: HashMap<String, SqlValue>,
+    /// Connection state shared with the owning `SqliteConnection`
+    handle: Arc<SqliteHandle>,
+}
+
+impl std::fmt::Debug for SqliteStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteStatement")
+            .field("sql", &self.sql)
+            .field("positional_params", &self.positional_params)
+            .field("named_params", &self.named_params)
+            .field("in_transaction", &self.in_transaction())
+            .finish_non_exhaustive()
+    }
 }
 
 impl SqliteStatement {
-    /// Create a new prepared statement (simplified version without connection)
-    #[allow(dead_code)]
-    pub(crate) fn new(sql: String) -> Self {
+    /// Create a new prepared statement on the given shared connection
+    pub(crate) fn new(sql: String, handle: Arc<SqliteHandle>) -> Self {
         Self {
             sql,
             positional_params: HashMap::new(),
             named_params: HashMap::new(),
+            handle,
         }
     }
 
-    /// Create a new prepared statement with connection reference
-    /// Note: The connection guard is immediately dropped as we store only the SQL
-    pub(crate) fn new_with_connection(
-        sql: String,
-        _conn: MutexGuard<'_, SqlxSqliteConnection>,
-    ) -> Self {
-        Self {
-            sql,
-            positional_params: HashMap::new(),
-            named_params: HashMap::new(),
-        }
+    /// Check if the owning connection currently has an active transaction
+    ///
+    /// The statement executes inside that transaction.
+    #[must_use]
+    pub fn in_transaction(&self) -> bool {
+        self.handle.in_transaction()
     }
 
     /// Build the final SQL with bound parameters
-    #[allow(dead_code)]
-    fn build_query(&self) -> (String, Vec<SqlValue>) {
-        let mut sql = self.sql.clone();
-        let mut values = Vec::new();
-
-        // Handle named parameters (convert :name to ?N format)
-        for (name, value) in &self.named_params {
-            let placeholder = format!(":{name}");
-            if sql.contains(&placeholder) {
-                values.push(value.clone());
-                let len = values.len();
-                sql = sql.replace(&placeholder, &format!("?{len}"));
-            }
-        }
-
-        // Handle positional parameters
-        let mut positions: Vec<_> = self.positional_params.keys().copied().collect();
-        positions.sort_unstable();
-
-        for pos in positions {
-            if let Some(value) = self.positional_params.get(&pos) {
-                values.push(value.clone());
-            }
-        }
-
-        (sql, values)
+    ///
+    /// Placeholders in any style (`?`, `?N`, `$N`, `:name`) are rewritten to
+    /// `?` and the bound values are collected in matching order.
+    fn build_query(&self) -> Result<(String, Vec<SqlValue>)> {
+        let rewritten = rewrite_placeholders(&self.sql, PlaceholderStyle::QuestionMark);
+
+        let values = rewritten
+            .parameters
+            .iter()
+            .map(|placeholder| {
+                let value = match placeholder {
+                    Placeholder::Positional(index) => self.positional_params.get(index),
+                    Placeholder::Named(name) => self.named_params.get(name),
+                };
+                value
+                    .cloned()
+                    .ok_or_else(|| QueryError::MissingParameter(placeholder.to_string()).into())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok((rewritten.sql, values))
     }
 }
 
@@ -94,19 +96,15 @@ impl DriverStatement for SqliteStatement {
     }
 
     async fn execute(&self) -> Result<Self::Result> {
-        // This simplified implementation cannot execute without a connection
-        // In practice, users should use Connection::query() with parameters
-        Err(crate::core::Error::driver_message(
-            "SqliteStatement::execute() requires a connection. Use Connection::query() instead.",
-        ))
+        let (sql, values) = self.build_query()?;
+        self.handle.fetch(&sql, &values).await
     }
 
     async fn execute_update(&self) -> Result<u64> {
-        // This simplified implementation cannot execute without a connection
-        // In practice, users should use Connection::execute() with parameters
-        Err(crate::core::Error::driver_message(
-            "SqliteStatement::execute_update() requires a connection. Use Connection::execute() instead.",
-        ))
+        let (sql, values) = self.build_query()?;
+        self.handle
+            .run(&sql, &values, SqliteConnection::execution_failed(&sql))
+            .await
     }
 
     fn sql(&self) -> &str {
@@ -117,34 +115,104 @@ impl DriverStatement for SqliteStatement {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::ConnectionParams;
+    use crate::driver::{Driver, DriverConnection, DriverResult};
+    use super::super::SqliteDriver;
+
+    async fn connect() -> SqliteConnection {
+        SqliteDriver::new()
+            .connect(&ConnectionParams::sqlite_memory())
+            .await
+            .unwrap()
+    }
+
+    async fn prepare(sql: &str) -> (SqliteConnection, SqliteStatement) {
+        let conn = connect().await;
+        let stmt = conn.prepare(sql).await.unwrap();
+        (conn, stmt)
+    }
 
-    #[test]
-    fn test_bind_positional() {
-        let mut stmt = SqliteStatement::new("INSERT INTO test VALUES (?, ?)".to_string());
+    #[tokio::test]
+    async fn test_bind_positional() {
+        let (_conn, mut stmt) = prepare("INSERT INTO test VALUES (?, ?)").await;
         stmt.bind(0, SqlValue::I64(1)).unwrap();
         stmt.bind(1, SqlValue::String("Alice".to_string())).unwrap();
 
-        let (sql, values) = stmt.build_query();
+        let (sql, values) = stmt.build_query().unwrap();
         assert_eq!(sql, "INSERT INTO test VALUES (?, ?)");
         assert_eq!(values.len(), 2);
         assert_eq!(values[0], SqlValue::I64(1));
     }
 
-    #[test]
-    fn test_bind_named() {
-        let mut stmt =
-            SqliteStatement::new("INSERT INTO test VALUES (:id, :name)".to_string());
+    #[tokio::test]
+    async fn test_bind_named() {
+        let (_conn, mut stmt) = prepare("INSERT INTO test VALUES (:id, :id_parent)").await;
         stmt.bind_named("id", SqlValue::I64(1)).unwrap();
-        stmt.bind_named("name", SqlValue::String("Bob".to_string()))
-            .unwrap();
+        stmt.bind_named("id_parent", SqlValue::I64(2)).unwrap();
 
-        let (_, values) = stmt.build_query();
-        assert_eq!(values.len(), 2);
+        let (sql, values) = stmt.build_query().unwrap();
+        assert_eq!(sql, "INSERT INTO test VALUES (?, ?)");
+        assert_eq!(values, vec![SqlValue::I64(1), SqlValue::I64(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_bind_all_typed() {
+        use crate::core::ParameterType;
+
+        let (_conn, mut stmt) = prepare("INSERT INTO test VALUES (?, ?)").await;
+        stmt.bind_all_typed(vec![
+            (SqlValue::I64(1), ParameterType::Integer),
+            (SqlValue::String("{}".to_string()), ParameterType::Json),
+        ])
+        .unwrap();
+
+        // SQLite needs no casts, the types are ignored
+        let (sql, values) = stmt.build_query().unwrap();
+        assert_eq!(sql, "INSERT INTO test VALUES (?, ?)");
+        assert_eq!(values, vec![SqlValue::I64(1), SqlValue::String("{}".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_placeholder_styles() {
+        let (_conn, mut stmt) = prepare("SELECT $2, ':id', $1, :id").await;
+        stmt.bind(0, SqlValue::I64(1)).unwrap();
+        stmt.bind(1, SqlValue::I64(2)).unwrap();
+        stmt.bind_named("id", SqlValue::I64(3)).unwrap();
+
+        let (sql, values) = stmt.build_query().unwrap();
+        assert_eq!(sql, "SELECT ?, ':id', ?, ?");
+        assert_eq!(values, vec![SqlValue::I64(2), SqlValue::I64(1), SqlValue::I64(3)]);
+    }
+
+    #[tokio::test]
+    async fn test_missing_parameter() {
+        let (_conn, mut stmt) = prepare("SELECT :id, ?").await;
+        stmt.bind(0, SqlValue::I64(1)).unwrap();
+
+        let err = stmt.execute().await.unwrap_err();
+        assert!(err.to_string().contains(":id"));
     }
 
-    #[test]
-    fn test_sql_getter() {
-        let stmt = SqliteStatement::new("SELECT 1".to_string());
+    #[tokio::test]
+    async fn test_sql_getter() {
+        let (_conn, stmt) = prepare("SELECT 1").await;
         assert_eq!(stmt.sql(), "SELECT 1");
     }
+
+    #[tokio::test]
+    async fn test_execute_with_parameters() {
+        let conn = connect().await;
+        conn.execute("CREATE TABLE test (id INTEGER, name TEXT)").await.unwrap();
+
+        let mut insert = conn.prepare("INSERT INTO test VALUES (:id, :name)").await.unwrap();
+        insert.bind_named("id", SqlValue::I64(7)).unwrap();
+        insert.bind_named("name", SqlValue::String("Bob".to_string())).unwrap();
+        assert_eq!(insert.execute_update().await.unwrap(), 1);
+
+        let mut select = conn.prepare("SELECT name FROM test WHERE id = ?").await.unwrap();
+        select.bind(0, SqlValue::I64(7)).unwrap();
+        let mut result = select.execute().await.unwrap();
+        let rows = result.all_rows().unwrap();
+        assert_eq!(rows, vec![vec![SqlValue::String("Bob".to_string())]]);
+    }
 }
@@ -0,0 +1,116 @@
+//! Backend interface of the wasm `SQLite` driver
+
+use std::sync::Arc;
+
+use crate::core::SqlValue;
+use crate::platform::ValueFormatter;
+
+/// Output of one statement executed by a [`SqliteBackend`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BackendResult {
+    /// Column names, empty for statements without a result
+    pub columns: Vec<String>,
+    /// Result rows
+    pub rows: Vec<Vec<SqlValue>>,
+    /// Number of rows modified by the statement (`sqlite3_changes()`)
+    pub rows_affected: u64,
+    /// Rowid of the last inserted row, if the statement inserted rows
+    pub last_insert_rowid: Option<i64>,
+}
+
+/// A synchronous `SQLite` engine the wasm driver executes on
+///
+/// Implementations bind the parameters to the `?` placeholders in order.
+/// Parameters are always `Null`, `I64`, `F64`, `String` or `Bytes`; other
+/// values are converted by the driver first. Returned values should use the
+/// same variants.
+///
+/// The driver traits require `Send + Sync`. JavaScript handles are neither,
+/// so bindings running on the single browser thread typically wrap them
+/// (e.g. in `send_wrapper::SendWrapper`).
+pub trait SqliteBackend: Send + Sync {
+    /// Execute one or more SQL statements
+    ///
+    /// Returns one result per executed statement.
+    ///
+    /// # Errors
+    ///
+    /// Returns the engine's error message if a statement fails.
+    fn execute(&self, sql: &str, params: &[SqlValue]) -> std::result::Result<Vec<BackendResult>, String>;
+}
+
+impl<B: SqliteBackend + ?Sized> SqliteBackend for Arc<B> {
+    fn execute(&self, sql: &str, params: &[SqlValue]) -> std::result::Result<Vec<BackendResult>, String> {
+        (**self).execute(sql, params)
+    }
+}
+
+impl<B: SqliteBackend + ?Sized> SqliteBackend for Box<B> {
+    fn execute(&self, sql: &str, params: &[SqlValue]) -> std::result::Result<Vec<BackendResult>, String> {
+        (**self).execute(sql, params)
+    }
+}
+
+/// Convert a value to one of the types `SQLite` stores natively
+///
+/// Types without a native representation (dates, UUIDs, JSON, decimals) are
+/// formatted as text, or as bytes for binary UUIDs.
+pub fn native_value(value: &SqlValue, formatter: &ValueFormatter) -> SqlValue {
+    #[cfg(feature = "uuid")]
+    if let Some(bytes) = formatter.uuid_bytes(value) {
+        return SqlValue::Bytes(bytes);
+    }
+    if let Some(text) = formatter.format_value(value) {
+        return SqlValue::String(text);
+    }
+
+    match value {
+        SqlValue::Bool(v) => SqlValue::I64(i64::from(*v)),
+        SqlValue::I8(v) => SqlValue::I64(i64::from(*v)),
+        SqlValue::I16(v) => SqlValue::I64(i64::from(*v)),
+        SqlValue::I32(v) => SqlValue::I64(i64::from(*v)),
+        SqlValue::U32(v) => SqlValue::I64(i64::from(*v)),
+        // SQLite integers are signed 64-bit, larger values are kept as text
+        SqlValue::U64(v) => i64::try_from(*v).map_or_else(|_| SqlValue::String(v.to_string()), SqlValue::I64),
+        SqlValue::F32(v) => SqlValue::F64(f64::from(*v)),
+        SqlValue::Null | SqlValue::I64(_) | SqlValue::F64(_) | SqlValue::String(_) | SqlValue::Bytes(_) => {
+            value.clone()
+        }
+        // Only reached if the configured format string is invalid
+        #[cfg(any(feature = "chrono", feature = "uuid", feature = "json", feature = "decimal"))]
+        _ => ValueFormatter::default()
+            .format_value(value)
+            .map_or(SqlValue::Null, SqlValue::String),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::SqlitePlatform;
+
+    #[test]
+    fn test_native_value() {
+        let formatter = ValueFormatter::for_platform(&SqlitePlatform::new());
+
+        assert_eq!(native_value(&SqlValue::Bool(true), &formatter), SqlValue::I64(1));
+        assert_eq!(native_value(&SqlValue::I32(7), &formatter), SqlValue::I64(7));
+        assert_eq!(
+            native_value(&SqlValue::U64(u64::MAX), &formatter),
+            SqlValue::String(u64::MAX.to_string())
+        );
+        assert_eq!(native_value(&SqlValue::F32(0.5), &formatter), SqlValue::F64(0.5));
+        assert_eq!(native_value(&SqlValue::Null, &formatter), SqlValue::Null);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_native_value_date() {
+        let formatter = ValueFormatter::for_platform(&SqlitePlatform::new());
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        assert_eq!(
+            native_value(&SqlValue::Date(date), &formatter),
+            SqlValue::String("2024-01-15".to_string())
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! wasm `SQLite` connection implementation
+
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::core::{Error, QueryError, Result, SqlValue, TransactionError, TransactionStatus};
+use crate::driver::DriverConnection;
+use crate::platform::ValueFormatter;
+
+use super::backend::native_value;
+use super::{BackendResult, SqliteBackend, SqliteWasmResult, SqliteWasmStatement};
+
+/// Connection state shared between a [`SqliteWasmConnection`] and its statements
+pub struct SqliteWasmHandle {
+    /// The `SQLite` engine
+    backend: Box<dyn SqliteBackend>,
+    /// Whether a transaction is currently active
+    in_transaction: AtomicBool,
+    /// Text formats for values without a native `SQLite` type
+    formatter: ValueFormatter,
+}
+
+impl SqliteWasmHandle {
+    /// Check if a transaction is currently active on this connection
+    fn in_transaction(&self) -> bool {
+        self.in_transaction.load(Ordering::SeqCst)
+    }
+
+    /// Execute SQL on the backend with the parameters converted to native values
+    fn exec(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        map_err: impl Fn(String) -> Error,
+    ) -> Result<Vec<BackendResult>> {
+        let params: Vec<SqlValue> = params
+            .iter()
+            .map(|value| native_value(value, &self.formatter))
+            .collect();
+        self.backend.execute(sql, &params).map_err(map_err)
+    }
+
+    /// Run a query and collect its result sets
+    pub(crate) fn fetch(&self, sql: &str, params: &[SqlValue]) -> Result<SqliteWasmResult> {
+        self.exec(sql, params, SqliteWasmConnection::execution_failed(sql))
+            .map(SqliteWasmResult::new)
+    }
+
+    /// Execute a statement and return affected rows
+    pub(crate) fn run(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        map_err: impl Fn(String) -> Error,
+    ) -> Result<u64> {
+        Ok(self
+            .exec(sql, params, map_err)?
+            .iter()
+            .map(|result| result.rows_affected)
+            .sum())
+    }
+}
+
+/// wasm `SQLite` database connection
+///
+/// Executes on the [`SqliteBackend`] opened by the
+/// [`SqliteWasmDriver`](super::SqliteWasmDriver). Prepared statements share
+/// the backend, so they run inside the same transaction as the connection.
+pub struct SqliteWasmConnection {
+    /// Connection state, shared with prepared statements
+    handle: Arc<SqliteWasmHandle>,
+}
+
+impl std::fmt::Debug for SqliteWasmConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteWasmConnection")
+            .field("in_transaction", &self.handle.in_transaction())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SqliteWasmConnection {
+    /// Create a new connection on the given backend
+    pub(crate) fn new(backend: Box<dyn SqliteBackend>, formatter: ValueFormatter) -> Self {
+        Self {
+            handle: Arc::new(SqliteWasmHandle {
+                backend,
+                in_transaction: AtomicBool::new(false),
+                formatter,
+            }),
+        }
+    }
+
+    /// Map a backend error to `QueryError::ExecutionFailed` for the given SQL
+    pub(crate) fn execution_failed(sql: &str) -> impl Fn(String) -> Error + '_ {
+        move |message| QueryError::execution_failed(message, Some(sql.to_string())).into()
+    }
+}
+
+#[async_trait]
+impl DriverConnection for SqliteWasmConnection {
+    type Statement = SqliteWasmStatement;
+    type Result = SqliteWasmResult;
+
+    async fn prepare(&self, sql: &str) -> Result<Self::Statement> {
+        Ok(SqliteWasmStatement::new(sql.to_string(), Arc::clone(&self.handle)))
+    }
+
+    async fn query(&self, sql: &str) -> Result<Self::Result> {
+        self.handle.fetch(sql, &[])
+    }
+
+    async fn execute(&self, sql: &str) -> Result<u64> {
+        self.handle.run(sql, &[], Self::execution_failed(sql))
+    }
+
+    async fn begin_transaction(&self) -> Result<()> {
+        if self.handle.in_transaction() {
+            return Err(Error::Transaction(TransactionError::AlreadyActive));
+        }
+
+        let sql = "BEGIN TRANSACTION";
+        self.handle.run(sql, &[], Self::execution_failed(sql))?;
+
+        self.handle.in_transaction.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn commit(&self) -> Result<()> {
+        if !self.handle.in_transaction() {
+            return Err(Error::Transaction(TransactionError::NoActiveTransaction));
+        }
+
+        self.handle
+            .run("COMMIT", &[], |e| TransactionError::CommitFailed(e).into())?;
+
+        self.handle.in_transaction.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<()> {
+        if !self.handle.in_transaction() {
+            return Err(Error::Transaction(TransactionError::NoActiveTransaction));
+        }
+
+        self.handle
+            .run("ROLLBACK", &[], |e| TransactionError::RollbackFailed(e).into())?;
+
+        self.handle.in_transaction.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    // SQLite has no failed transaction state: statements keep working after an error
+    fn transaction_status(&self) -> TransactionStatus {
+        if self.handle.in_transaction() {
+            TransactionStatus::Active
+        } else {
+            TransactionStatus::Idle
+        }
+    }
+
+    async fn is_alive(&self) -> bool {
+        self.handle.run("SELECT 1", &[], Self::execution_failed("SELECT 1")).is_ok()
+    }
+
+    async fn server_version(&self) -> Result<String> {
+        let sql = "SELECT sqlite_version()";
+        let results = self.handle.exec(sql, &[], Self::execution_failed(sql))?;
+
+        match results.first().and_then(|result| result.rows.first()).and_then(|row| row.first()) {
+            Some(SqlValue::String(version)) => Ok(version.clone()),
+            _ => Err(Error::conversion("BackendResult", "String", "missing sqlite_version() result")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ConnectionParams;
+    use crate::driver::{Driver, DriverResult, DriverStatement};
+    use super::super::SqliteWasmDriver;
+    use std::sync::Mutex;
+
+    /// Backend echoing the parameters of SELECTs and recording all calls
+    #[derive(Default)]
+    struct EchoBackend {
+        calls: Mutex<Vec<(String, Vec<SqlValue>)>>,
+    }
+
+    impl SqliteBackend for EchoBackend {
+        fn execute(&self, sql: &str, params: &[SqlValue]) -> std::result::Result<Vec<BackendResult>, String> {
+            self.calls.lock().unwrap().push((sql.to_string(), params.to_vec()));
+            if sql.contains("FAIL") {
+                return Err("syntax error".to_string());
+            }
+            if sql == "SELECT sqlite_version()" {
+                return Ok(vec![BackendResult {
+                    columns: vec!["sqlite_version()".to_string()],
+                    rows: vec![vec![SqlValue::String("3.46.0".to_string())]],
+                    ..BackendResult::default()
+                }]);
+            }
+            if sql.starts_with("SELECT") {
+                return Ok(vec![BackendResult {
+                    columns: (1..=params.len()).map(|i| format!("?{i}")).collect(),
+                    rows: vec![params.to_vec()],
+                    ..BackendResult::default()
+                }]);
+            }
+            Ok(vec![BackendResult {
+                rows_affected: 1,
+                last_insert_rowid: Some(1),
+                ..BackendResult::default()
+            }])
+        }
+    }
+
+    async fn connect() -> (Arc<EchoBackend>, SqliteWasmConnection) {
+        let backend = Arc::new(EchoBackend::default());
+        let shared = Arc::clone(&backend);
+        let driver = SqliteWasmDriver::new(move |_: &ConnectionParams| {
+            Ok(Box::new(Arc::clone(&shared)) as Box<dyn SqliteBackend>)
+        });
+        let conn = driver.connect(&ConnectionParams::sqlite_memory()).await.unwrap();
+        (backend, conn)
+    }
+
+    #[tokio::test]
+    async fn test_statement_binding() {
+        let (backend, conn) = connect().await;
+
+        let mut stmt = conn.prepare("SELECT :flag, $1").await.unwrap();
+        stmt.bind(0, SqlValue::I32(5)).unwrap();
+        stmt.bind_named("flag", SqlValue::Bool(true)).unwrap();
+        let mut result = stmt.execute().await.unwrap();
+
+        assert_eq!(result.column_names(), &["?1", "?2"]);
+        assert_eq!(result.all_rows().unwrap(), vec![vec![SqlValue::I64(1), SqlValue::I64(5)]]);
+        assert_eq!(
+            backend.calls.lock().unwrap().last().unwrap().0,
+            "SELECT ?, ?"
+        );
+
+        let insert = conn.prepare("INSERT INTO t VALUES (?)").await.unwrap();
+        assert!(insert.execute_update().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transactions() {
+        let (backend, conn) = connect().await;
+
+        conn.begin_transaction().await.unwrap();
+        assert!(conn.begin_transaction().await.is_err());
+        assert_eq!(conn.execute("INSERT INTO t VALUES (1)").await.unwrap(), 1);
+        conn.commit().await.unwrap();
+        assert!(conn.rollback().await.is_err());
+
+        let calls: Vec<String> = backend.calls.lock().unwrap().iter().map(|(sql, _)| sql.clone()).collect();
+        assert_eq!(calls, vec!["BEGIN TRANSACTION", "INSERT INTO t VALUES (1)", "COMMIT"]);
+    }
+
+    #[tokio::test]
+    async fn test_errors_and_version() {
+        let (_backend, conn) = connect().await;
+
+        let err = conn.query("FAIL").await.unwrap_err();
+        assert!(matches!(err, Error::Query(QueryError::ExecutionFailed { .. })));
+        assert!(conn.is_alive().await);
+        assert_eq!(conn.server_version().await.unwrap(), "3.46.0");
+    }
+}
@@ -0,0 +1,91 @@
+//! wasm `SQLite` driver implementation
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::core::{Configuration, ConnectionParams, Result};
+use crate::driver::Driver;
+use crate::platform::{SqlitePlatform, ValueFormatter};
+
+use super::{SqliteBackend, SqliteWasmConnection};
+
+/// Function opening a backend for the connection parameters
+type OpenBackend = dyn Fn(&ConnectionParams) -> Result<Box<dyn SqliteBackend>> + Send + Sync;
+
+/// `SQLite` driver running on an application-supplied [`SqliteBackend`]
+///
+/// Reports the driver name `sqlite`, so the `SQLite` platform and the
+/// `sqlite:` connection URLs apply as for the sqlx-based driver.
+pub struct SqliteWasmDriver {
+    /// Opens a backend for each new connection
+    open: Arc<OpenBackend>,
+    /// Text formats for dates/times (defaults to the `SQLite` platform formats)
+    value_formatter: Option<ValueFormatter>,
+}
+
+impl std::fmt::Debug for SqliteWasmDriver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteWasmDriver")
+            .field("value_formatter", &self.value_formatter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SqliteWasmDriver {
+    /// Create a driver opening backends with the given function
+    ///
+    /// The function receives the connection parameters, e.g. to pick the
+    /// OPFS file from [`ConnectionParams::path`].
+    #[must_use]
+    pub fn new<F>(open: F) -> Self
+    where
+        F: Fn(&ConnectionParams) -> Result<Box<dyn SqliteBackend>> + Send + Sync + 'static,
+    {
+        Self {
+            open: Arc::new(open),
+            value_formatter: None,
+        }
+    }
+
+    /// Set the formatter for values bound as text (dates, times, UUIDs, ...)
+    #[must_use]
+    pub fn with_value_formatter(mut self, formatter: ValueFormatter) -> Self {
+        self.value_formatter = Some(formatter);
+        self
+    }
+}
+
+#[async_trait]
+impl Driver for SqliteWasmDriver {
+    type Connection = SqliteWasmConnection;
+
+    async fn connect(&self, params: &ConnectionParams) -> Result<Self::Connection> {
+        let formatter = self
+            .value_formatter
+            .clone()
+            .unwrap_or_else(|| ValueFormatter::for_platform(&SqlitePlatform::new()));
+
+        Ok(SqliteWasmConnection::new((self.open)(params)?, formatter))
+    }
+
+    /// Connect applying the date/time format overrides
+    ///
+    /// A formatter set with [`SqliteWasmDriver::with_value_formatter`] takes
+    /// precedence over the configured formats. Timeouts are not supported,
+    /// as backends execute synchronously.
+    async fn connect_with_configuration(
+        &self,
+        params: &ConnectionParams,
+        configuration: &Configuration,
+    ) -> Result<Self::Connection> {
+        let formatter = self.value_formatter.clone().unwrap_or_else(|| {
+            ValueFormatter::for_platform(&SqlitePlatform::new()).with_configuration(configuration)
+        });
+
+        Ok(SqliteWasmConnection::new((self.open)(params)?, formatter))
+    }
+
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+}
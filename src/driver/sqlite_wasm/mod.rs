@@ -0,0 +1,30 @@
+//! `SQLite` driver for WebAssembly
+//!
+//! sqlx can't be built for `wasm32-unknown-unknown`, so this driver runs on
+//! a [`SqliteBackend`] supplied by the application instead: a thin binding
+//! to sql.js, the official `SQLite` wasm build (with OPFS persistence) or any
+//! other synchronous `SQLite` engine. The driver takes care of placeholder
+//! rewriting, value conversion, transactions and result sets; it needs
+//! neither threads nor Tokio.
+//!
+//! ```rust,ignore
+//! use rustine_dbal::driver::{SqliteBackend, SqliteWasmDriver};
+//! use rustine_dbal::prelude::*;
+//!
+//! let driver = SqliteWasmDriver::new(|params: &ConnectionParams| {
+//!     Ok(Box::new(SqlJsBackend::open(params.path.as_deref())?) as Box<dyn SqliteBackend>)
+//! });
+//! let conn = Connection::new(&driver, &ConnectionParams::sqlite_memory()).await?;
+//! ```
+
+mod backend;
+mod driver;
+mod connection;
+mod statement;
+mod result;
+
+pub use backend::{BackendResult, SqliteBackend};
+pub use driver::SqliteWasmDriver;
+pub use connection::SqliteWasmConnection;
+pub use statement::SqliteWasmStatement;
+pub use result::SqliteWasmResult;
@@ -0,0 +1,78 @@
+//! wasm `SQLite` result set implementation
+
+use std::collections::VecDeque;
+
+use crate::core::{Result, SqlValue};
+use crate::driver::{DriverResult, RowRef};
+
+use super::BackendResult;
+
+/// wasm `SQLite` query result
+#[derive(Debug)]
+pub struct SqliteWasmResult {
+    /// Result set currently being read
+    current: BackendResult,
+    /// Current row index for iteration
+    current_index: usize,
+    /// Result sets of the following statements in a multi-statement query
+    next_sets: VecDeque<BackendResult>,
+}
+
+impl SqliteWasmResult {
+    /// Create a result from the backend results of a query
+    ///
+    /// The first set is current, the others follow via `next_result_set()`.
+    pub(crate) fn new(sets: Vec<BackendResult>) -> Self {
+        let mut next_sets: VecDeque<BackendResult> = sets.into();
+        Self {
+            current: next_sets.pop_front().unwrap_or_default(),
+            current_index: 0,
+            next_sets,
+        }
+    }
+
+    /// Get the rowid of the last row inserted by the current statement
+    #[must_use]
+    pub const fn last_insert_rowid(&self) -> Option<i64> {
+        self.current.last_insert_rowid
+    }
+}
+
+impl DriverResult for SqliteWasmResult {
+    fn next_row(&mut self) -> Result<Option<Vec<SqlValue>>> {
+        let row = self.current.rows.get(self.current_index).cloned();
+        if row.is_some() {
+            self.current_index += 1;
+        }
+        Ok(row)
+    }
+
+    fn for_each_row_ref(&mut self, f: &mut dyn FnMut(RowRef<'_>) -> Result<()>) -> Result<()> {
+        while let Some(values) = self.current.rows.get(self.current_index) {
+            self.current_index += 1;
+            f(RowRef::new(&self.current.columns, values))?;
+        }
+        Ok(())
+    }
+
+    fn column_count(&self) -> usize {
+        self.current.columns.len()
+    }
+
+    fn column_names(&self) -> &[String] {
+        &self.current.columns
+    }
+
+    fn rows_affected(&self) -> u64 {
+        self.current.rows_affected
+    }
+
+    fn next_result_set(&mut self) -> Result<bool> {
+        let Some(next) = self.next_sets.pop_front() else {
+            return Ok(false);
+        };
+        self.current = next;
+        self.current_index = 0;
+        Ok(true)
+    }
+}
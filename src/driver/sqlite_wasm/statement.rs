@@ -0,0 +1,103 @@
+//! wasm `SQLite` prepared statement implementation
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::core::{QueryError, Result, SqlValue};
+use crate::driver::{rewrite_placeholders, DriverStatement, Placeholder, PlaceholderStyle};
+
+use super::connection::SqliteWasmHandle;
+use super::{SqliteWasmConnection, SqliteWasmResult};
+
+/// wasm `SQLite` prepared statement
+///
+/// Like the sqlx-based `SqliteStatement`, the
+/// statement shares the backend of the connection that prepared it and
+/// binds its parameters on execution.
+pub struct SqliteWasmStatement {
+    /// The SQL query string
+    sql: String,
+    /// Positional parameters indexed by position
+    positional_params: HashMap<usize, SqlValue>,
+    /// Named parameters indexed by name
+    named_params: HashMap<String, SqlValue>,
+    /// Connection state shared with the owning `SqliteWasmConnection`
+    handle: Arc<SqliteWasmHandle>,
+}
+
+impl std::fmt::Debug for SqliteWasmStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteWasmStatement")
+            .field("sql", &self.sql)
+            .field("positional_params", &self.positional_params)
+            .field("named_params", &self.named_params)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SqliteWasmStatement {
+    /// Create a new prepared statement on the given shared connection
+    pub(crate) fn new(sql: String, handle: Arc<SqliteWasmHandle>) -> Self {
+        Self {
+            sql,
+            positional_params: HashMap::new(),
+            named_params: HashMap::new(),
+            handle,
+        }
+    }
+
+    /// Build the final SQL with bound parameters
+    ///
+    /// Placeholders in any style (`?`, `?N`, `$N`, `:name`) are rewritten to
+    /// `?` and the bound values are collected in matching order.
+    fn build_query(&self) -> Result<(String, Vec<SqlValue>)> {
+        let rewritten = rewrite_placeholders(&self.sql, PlaceholderStyle::QuestionMark);
+
+        let values = rewritten
+            .parameters
+            .iter()
+            .map(|placeholder| {
+                let value = match placeholder {
+                    Placeholder::Positional(index) => self.positional_params.get(index),
+                    Placeholder::Named(name) => self.named_params.get(name),
+                };
+                value
+                    .cloned()
+                    .ok_or_else(|| QueryError::MissingParameter(placeholder.to_string()).into())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok((rewritten.sql, values))
+    }
+}
+
+#[async_trait]
+impl DriverStatement for SqliteWasmStatement {
+    type Result = SqliteWasmResult;
+
+    fn bind(&mut self, position: usize, value: SqlValue) -> Result<()> {
+        self.positional_params.insert(position, value);
+        Ok(())
+    }
+
+    fn bind_named(&mut self, name: &str, value: SqlValue) -> Result<()> {
+        self.named_params.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    async fn execute(&self) -> Result<Self::Result> {
+        let (sql, values) = self.build_query()?;
+        self.handle.fetch(&sql, &values)
+    }
+
+    async fn execute_update(&self) -> Result<u64> {
+        let (sql, values) = self.build_query()?;
+        self.handle
+            .run(&sql, &values, SqliteWasmConnection::execution_failed(&sql))
+    }
+
+    fn sql(&self) -> &str {
+        &self.sql
+    }
+}
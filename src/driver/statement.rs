@@ -1,11 +1,16 @@
 //! Driver statement trait
 
 use async_trait::async_trait;
-use crate::core::{Result, SqlValue};
+use crate::core::{ParameterType, Result, SqlValue};
 
 use super::DriverResult;
 
 /// A prepared statement
+///
+/// Statements are bound to the connection that prepared them: implementations
+/// must execute on that same connection (e.g. by holding an `Arc` to its
+/// state), so that a statement prepared inside a transaction runs within it
+/// and is committed or rolled back together with it.
 #[async_trait]
 pub trait DriverStatement: Send + Sync {
     /// The result type for this statement
@@ -25,6 +30,43 @@ pub trait DriverStatement: Send + Sync {
     /// Returns an error if the name is not found or binding fails.
     fn bind_named(&mut self, name: &str, value: SqlValue) -> Result<()>;
 
+    /// Bind a parameter by position with an explicit type
+    ///
+    /// Drivers whose database can't infer every parameter type (e.g.
+    /// `PostgreSQL`) cast the placeholder via
+    /// [`Platform::typed_parameter_placeholder`](crate::platform::Platform::typed_parameter_placeholder).
+    /// By default the type is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position is invalid or binding fails.
+    fn bind_typed(&mut self, position: usize, value: SqlValue, param_type: ParameterType) -> Result<()> {
+        let _ = param_type;
+        self.bind(position, value)
+    }
+
+    /// Bind a parameter by name with an explicit type
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not found or binding fails.
+    fn bind_named_typed(&mut self, name: &str, value: SqlValue, param_type: ParameterType) -> Result<()> {
+        let _ = param_type;
+        self.bind_named(name, value)
+    }
+
+    /// Bind typed parameters by position, starting at 0
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if binding any of the parameters fails.
+    fn bind_all_typed(&mut self, params: Vec<(SqlValue, ParameterType)>) -> Result<()> {
+        params
+            .into_iter()
+            .enumerate()
+            .try_for_each(|(position, (value, param_type))| self.bind_typed(position, value, param_type))
+    }
+
     /// Execute the statement and return results
     async fn execute(&self) -> Result<Self::Result>;
 
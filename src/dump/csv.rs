@@ -0,0 +1,268 @@
+//! CSV export and import of table dumps
+//!
+//! Fields follow RFC 4180: a header row with the column names, `,` as
+//! separator and `"` quoting with doubled quotes. Strings are always quoted,
+//! so an empty unquoted field is NULL while `""` is an empty string. Binary
+//! values are written as hex.
+
+use std::fmt::Write;
+
+use crate::core::{hex_decode, Error, Result, SqlValue};
+use crate::driver::export::{quote_csv_field, value_text};
+use crate::platform::{AnsiPlatform, SqlType, Table, ValueFormatter};
+
+use super::TableDump;
+
+impl TableDump {
+    /// Write the rows as CSV with a header row
+    ///
+    /// Dates, times, UUIDs, JSON and decimals are written as quoted text in
+    /// the ANSI formats.
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let formatter = ValueFormatter::for_platform(&AnsiPlatform::new());
+        let mut csv = String::new();
+
+        let header: Vec<String> = self
+            .table
+            .columns
+            .iter()
+            .map(|column| quote_csv_field(&column.name))
+            .collect();
+        let _ = writeln!(csv, "{}", header.join(","));
+
+        for row in &self.rows {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|value| format_field(value, &formatter))
+                .collect();
+            let _ = writeln!(csv, "{}", fields.join(","));
+        }
+
+        csv
+    }
+
+    /// Read rows from CSV written for a table definition
+    ///
+    /// The header row selects and orders the columns; table columns missing
+    /// from the header are NULL. Fields are parsed by the column type:
+    /// integers, floats, booleans (`true`/`false`/`1`/`0`) and hex binary,
+    /// everything else is kept as text.
+    ///
+    /// # Errors
+    ///
+    /// Returns a conversion error if the CSV is malformed, names an unknown
+    /// column, or a field cannot be parsed for its column type.
+    pub fn from_csv(table: Table, csv: &str) -> Result<Self> {
+        let mut records = parse_records(csv, ',')?.into_iter();
+        let Some(header) = records.next() else {
+            return Ok(Self::new(table, Vec::new()));
+        };
+
+        let positions = header
+            .iter()
+            .map(|field| {
+                table
+                    .columns
+                    .iter()
+                    .position(|column| column.name == field.text)
+                    .ok_or_else(|| csv_error(format!("unknown column '{}'", field.text)))
+            })
+            .collect::<Result<Vec<usize>>>()?;
+
+        let mut rows = Vec::new();
+        for (line, record) in records.enumerate() {
+            if record.len() != positions.len() {
+                return Err(csv_error(format!(
+                    "row {} has {} fields, expected {}",
+                    line + 1,
+                    record.len(),
+                    positions.len()
+                )));
+            }
+
+            let mut row = vec![SqlValue::Null; table.columns.len()];
+            for (field, &position) in record.into_iter().zip(&positions) {
+                row[position] = parse_field(field, &table.columns[position].sql_type)?;
+            }
+            rows.push(row);
+        }
+
+        Ok(Self::new(table, rows))
+    }
+}
+
+/// A parsed CSV field
+pub struct CsvField {
+    /// Unescaped field text
+    pub text: String,
+    /// Whether the field was quoted
+    pub quoted: bool,
+}
+
+/// Create an error for malformed or unparsable CSV
+fn csv_error(message: impl Into<String>) -> Error {
+    Error::conversion("csv", "row", message)
+}
+
+/// Format a value as a CSV field
+fn format_field(value: &SqlValue, formatter: &ValueFormatter) -> String {
+    let Some(text) = value_text(value, formatter) else {
+        return String::new();
+    };
+    match value {
+        SqlValue::Bool(_)
+        | SqlValue::I8(_)
+        | SqlValue::I16(_)
+        | SqlValue::I32(_)
+        | SqlValue::I64(_)
+        | SqlValue::U32(_)
+        | SqlValue::U64(_)
+        | SqlValue::F32(_)
+        | SqlValue::F64(_)
+        | SqlValue::Bytes(_) => text,
+        _ => quote_csv_field(&text),
+    }
+}
+
+/// Parse a field for a column type
+pub fn parse_field(field: CsvField, sql_type: &SqlType) -> Result<SqlValue> {
+    if field.text.is_empty() && !field.quoted {
+        return Ok(SqlValue::Null);
+    }
+    let text = field.text;
+
+    match sql_type {
+        SqlType::SmallInt
+        | SqlType::Integer
+        | SqlType::BigInt
+        | SqlType::Serial
+        | SqlType::BigSerial => text
+            .trim()
+            .parse()
+            .map(SqlValue::I64)
+            .map_err(|e| csv_error(format!("'{text}' is not an integer: {e}"))),
+        SqlType::Float | SqlType::Double => text
+            .trim()
+            .parse()
+            .map(SqlValue::F64)
+            .map_err(|e| csv_error(format!("'{text}' is not a number: {e}"))),
+        SqlType::Boolean => match text.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" => Ok(SqlValue::Bool(true)),
+            "false" | "0" => Ok(SqlValue::Bool(false)),
+            _ => Err(csv_error(format!("'{text}' is not a boolean"))),
+        },
+        SqlType::Binary { .. } | SqlType::VarBinary { .. } | SqlType::Blob => {
+            let hex = text.trim();
+            hex_decode(hex)
+                .map(SqlValue::Bytes)
+                .ok_or_else(|| csv_error(format!("'{hex}' is not valid hex")))
+        }
+        _ => Ok(SqlValue::String(text)),
+    }
+}
+
+/// Split CSV text into records of fields separated by `delimiter`
+pub fn parse_records(input: &str, delimiter: char) -> Result<Vec<Vec<CsvField>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut text = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    text.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => text.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if text.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            c if c == delimiter => record.push(CsvField {
+                text: std::mem::take(&mut text),
+                quoted: std::mem::replace(&mut quoted, false),
+            }),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(CsvField {
+                    text: std::mem::take(&mut text),
+                    quoted: std::mem::replace(&mut quoted, false),
+                });
+                records.push(std::mem::take(&mut record));
+            }
+            _ => text.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(csv_error("unterminated quoted field"));
+    }
+    if !text.is_empty() || quoted || !record.is_empty() {
+        record.push(CsvField { text, quoted });
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::Column;
+
+    fn notes_table() -> Table {
+        Table::new("notes")
+            .column(Column::new("id", SqlType::Integer))
+            .column(Column::new("body", SqlType::Text))
+            .column(Column::new("pinned", SqlType::Boolean))
+            .column(Column::new("data", SqlType::Blob))
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let dump = TableDump::new(
+            notes_table(),
+            vec![
+                vec![
+                    SqlValue::I64(1),
+                    SqlValue::String("say \"hi\",\nbye".into()),
+                    SqlValue::Bool(true),
+                    SqlValue::Bytes(vec![0, 255]),
+                ],
+                vec![SqlValue::I64(2), SqlValue::String(String::new()), SqlValue::Null, SqlValue::Null],
+            ],
+        );
+
+        let csv = dump.to_csv();
+        assert_eq!(
+            csv,
+            "\"id\",\"body\",\"pinned\",\"data\"\n1,\"say \"\"hi\"\",\nbye\",true,00ff\n2,\"\",,\n"
+        );
+        assert_eq!(TableDump::from_csv(notes_table(), &csv).unwrap(), dump);
+    }
+
+    #[test]
+    fn test_from_csv_by_header() {
+        let dump = TableDump::from_csv(notes_table(), "pinned,id\r\n0,7\r\n").unwrap();
+        assert_eq!(
+            dump.rows,
+            vec![vec![SqlValue::I64(7), SqlValue::Null, SqlValue::Bool(false), SqlValue::Null]]
+        );
+
+        assert!(TableDump::from_csv(notes_table(), "title\nx\n").is_err());
+        assert!(TableDump::from_csv(notes_table(), "id\nseven\n").is_err());
+        assert!(TableDump::from_csv(notes_table(), "id,body\n1\n").is_err());
+        assert!(TableDump::from_csv(notes_table(), "body\n\"open\n").is_err());
+    }
+}
@@ -0,0 +1,506 @@
+//! Export of schema and data into a platform-neutral dump
+
+use crate::core::{Result, SchemaError, SqlValue};
+use crate::driver::{DriverConnection, DriverResult};
+use crate::platform::{Platform, SqlType, Table};
+use crate::schema::SchemaManager;
+
+/// Options controlling what is dumped and how it is written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpOptions {
+    /// Tables to dump (all tables if `None`)
+    pub tables: Option<Vec<String>>,
+    /// Include CREATE TABLE/INDEX statements
+    pub include_schema: bool,
+    /// Include the table rows
+    pub include_data: bool,
+    /// Number of rows per INSERT statement
+    pub batch_size: usize,
+    /// Drop existing tables before creating them
+    pub drop_existing: bool,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self {
+            tables: None,
+            include_schema: true,
+            include_data: true,
+            batch_size: 100,
+            drop_existing: false,
+        }
+    }
+}
+
+impl DumpOptions {
+    /// Create options dumping schema and data of all tables
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict the dump to the given tables
+    #[must_use]
+    pub fn with_tables<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tables = Some(tables.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Dump the schema without data
+    #[must_use]
+    pub const fn schema_only(mut self) -> Self {
+        self.include_schema = true;
+        self.include_data = false;
+        self
+    }
+
+    /// Dump the data without schema
+    #[must_use]
+    pub const fn data_only(mut self) -> Self {
+        self.include_schema = false;
+        self.include_data = true;
+        self
+    }
+
+    /// Set the number of rows per INSERT statement (at least 1)
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Drop existing tables before creating them
+    #[must_use]
+    pub const fn with_drop_existing(mut self, drop_existing: bool) -> Self {
+        self.drop_existing = drop_existing;
+        self
+    }
+}
+
+/// Reads tables and their rows from a connection
+///
+/// Tables are introspected with the [`SchemaManager`] of the source
+/// platform. Rows are read in primary key order; values of `BOOLEAN`
+/// columns stored as integers and of `UUID` columns stored as 16 bytes are
+/// converted to their typed [`SqlValue`] so they can be written for any
+/// target platform.
+pub struct Dumper<'a, C: DriverConnection> {
+    /// Connection to read from
+    connection: &'a C,
+    /// Platform of the source database
+    platform: &'a dyn Platform,
+}
+
+impl<C: DriverConnection> std::fmt::Debug for Dumper<'_, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dumper")
+            .field("platform", &self.platform.name())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, C: DriverConnection> Dumper<'a, C> {
+    /// Create a dumper for a connection and its platform
+    #[must_use]
+    pub const fn new(connection: &'a C, platform: &'a dyn Platform) -> Self {
+        Self {
+            connection,
+            platform,
+        }
+    }
+
+    /// Dump the tables selected by the options
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchemaError::TableNotFound`] if a requested table does not
+    /// exist, or an error if an introspection or SELECT query fails.
+    pub async fn dump(&self, options: &DumpOptions) -> Result<DatabaseDump> {
+        let manager = SchemaManager::new(self.connection, self.platform);
+        let names = match &options.tables {
+            Some(tables) => tables.clone(),
+            None => manager.list_table_names().await?,
+        };
+
+        let mut tables = Vec::with_capacity(names.len());
+        for name in names {
+            let table = manager.introspect_table(&name).await?.to_table();
+            if table.columns.is_empty() {
+                return Err(SchemaError::TableNotFound(name).into());
+            }
+
+            let rows = if options.include_data {
+                self.fetch_rows(&table).await?
+            } else {
+                Vec::new()
+            };
+            tables.push(TableDump { table, rows });
+        }
+
+        Ok(DatabaseDump::new(tables))
+    }
+
+    /// Read all rows of a table, converted by column type
+    async fn fetch_rows(&self, table: &Table) -> Result<Vec<Vec<SqlValue>>> {
+        let columns: Vec<String> = table
+            .columns
+            .iter()
+            .map(|column| self.platform.quote_identifier(&column.name))
+            .collect();
+        let mut sql = format!(
+            "SELECT {} FROM {}",
+            columns.join(", "),
+            self.platform.quote_identifier(&table.name)
+        );
+        if let Some(primary_key) = table.primary_key_columns() {
+            let order: Vec<String> = primary_key
+                .iter()
+                .map(|column| self.platform.quote_identifier(column))
+                .collect();
+            sql.push_str(" ORDER BY ");
+            sql.push_str(&order.join(", "));
+        }
+
+        let rows = self.connection.query(&sql).await?.all_rows()?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .zip(&table.columns)
+                    .map(|(value, column)| coerce_value(value, &column.sql_type))
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// Convert a value read from the database to the type of its column
+fn coerce_value(value: SqlValue, sql_type: &SqlType) -> SqlValue {
+    match (sql_type, value) {
+        (SqlType::Boolean, SqlValue::I64(i)) => SqlValue::Bool(i != 0),
+        (SqlType::Boolean, SqlValue::I32(i)) => SqlValue::Bool(i != 0),
+        (SqlType::Boolean, SqlValue::I16(i)) => SqlValue::Bool(i != 0),
+        (SqlType::Boolean, SqlValue::I8(i)) => SqlValue::Bool(i != 0),
+        #[cfg(feature = "uuid")]
+        (SqlType::Uuid, SqlValue::Bytes(bytes)) => {
+            uuid::Uuid::from_slice(&bytes).map_or(SqlValue::Bytes(bytes), SqlValue::Uuid)
+        }
+        (_, value) => value,
+    }
+}
+
+// =============================================================================
+// Dump contents
+// =============================================================================
+
+/// A table definition with its rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDump {
+    /// Table definition
+    pub table: Table,
+    /// Rows with values in column order
+    pub rows: Vec<Vec<SqlValue>>,
+}
+
+impl TableDump {
+    /// Create a table dump
+    #[must_use]
+    pub const fn new(table: Table, rows: Vec<Vec<SqlValue>>) -> Self {
+        Self { table, rows }
+    }
+
+    /// Generate the CREATE TABLE and CREATE INDEX statements for a platform
+    ///
+    /// Unique indexes are part of the CREATE TABLE statement.
+    #[must_use]
+    pub fn create_statements(&self, platform: &dyn Platform) -> Vec<String> {
+        let mut statements = vec![platform.get_create_table_sql(&self.table)];
+        statements.extend(
+            self.table
+                .indexes
+                .iter()
+                .filter(|index| !index.primary && !index.unique)
+                .map(|index| platform.get_create_index_sql(&self.table.name, index)),
+        );
+        statements
+    }
+
+    /// Generate multi-row INSERT statements with up to `batch_size` rows each
+    #[must_use]
+    pub fn insert_statements(&self, platform: &dyn Platform, batch_size: usize) -> Vec<String> {
+        let columns: Vec<String> = self
+            .table
+            .columns
+            .iter()
+            .map(|column| platform.quote_identifier(&column.name))
+            .collect();
+        let prefix = format!(
+            "INSERT INTO {} ({}) VALUES ",
+            platform.quote_identifier(&self.table.name),
+            columns.join(", ")
+        );
+
+        self.rows
+            .chunks(batch_size.max(1))
+            .map(|chunk| {
+                let rows: Vec<String> = chunk
+                    .iter()
+                    .map(|row| {
+                        let values: Vec<String> =
+                            row.iter().map(|value| platform.format_literal(value)).collect();
+                        format!("({})", values.join(", "))
+                    })
+                    .collect();
+                format!("{prefix}{}", rows.join(", "))
+            })
+            .collect()
+    }
+}
+
+/// Tables and rows of a database, ordered so referenced tables come first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DatabaseDump {
+    /// Table dumps in creation order
+    pub tables: Vec<TableDump>,
+}
+
+impl DatabaseDump {
+    /// Create a dump, ordering the tables by their foreign keys
+    ///
+    /// Tables that reference each other in a cycle keep their given order.
+    #[must_use]
+    pub fn new(tables: Vec<TableDump>) -> Self {
+        Self {
+            tables: sort_by_dependencies(tables),
+        }
+    }
+
+    /// Get the dump of a table by name
+    #[must_use]
+    pub fn table(&self, name: &str) -> Option<&TableDump> {
+        self.tables
+            .iter()
+            .find(|dump| dump.table.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Generate the statements recreating the dump on a platform
+    ///
+    /// Tables are dropped in reverse order (if requested), then created and
+    /// filled one after another.
+    #[must_use]
+    pub fn to_sql(&self, platform: &dyn Platform, options: &DumpOptions) -> Vec<String> {
+        let mut statements = Vec::new();
+
+        if options.include_schema && options.drop_existing {
+            statements.extend(
+                self.tables
+                    .iter()
+                    .rev()
+                    .map(|dump| platform.get_drop_table_if_exists_sql(&dump.table.name)),
+            );
+        }
+
+        for dump in &self.tables {
+            if options.include_schema {
+                statements.extend(dump.create_statements(platform));
+            }
+            if options.include_data {
+                statements.extend(dump.insert_statements(platform, options.batch_size));
+            }
+        }
+
+        statements
+    }
+
+    /// Generate a SQL script with one `;`-terminated statement per line
+    #[must_use]
+    pub fn to_sql_script(&self, platform: &dyn Platform, options: &DumpOptions) -> String {
+        self.to_sql(platform, options)
+            .into_iter()
+            .map(|statement| statement + ";\n")
+            .collect()
+    }
+
+    /// Restore the dump into a connection inside a single transaction
+    ///
+    /// The transaction is rolled back if a statement fails. Note that
+    /// `MySQL` commits implicitly after DDL statements.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first failing statement.
+    pub async fn restore<C: DriverConnection>(
+        &self,
+        connection: &C,
+        platform: &dyn Platform,
+        options: &DumpOptions,
+    ) -> Result<()> {
+        connection.begin_transaction().await?;
+        for statement in self.to_sql(platform, options) {
+            if let Err(e) = connection.execute(&statement).await {
+                let _ = connection.rollback().await;
+                return Err(e);
+            }
+        }
+        connection.commit().await
+    }
+}
+
+/// Order tables so that referenced tables precede the tables referencing them
+fn sort_by_dependencies(mut pending: Vec<TableDump>) -> Vec<TableDump> {
+    let mut sorted = Vec::with_capacity(pending.len());
+
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|dump| {
+            dump.table.foreign_keys.iter().all(|fk| {
+                fk.foreign_table.eq_ignore_ascii_case(&dump.table.name)
+                    || !pending
+                        .iter()
+                        .any(|other| other.table.name.eq_ignore_ascii_case(&fk.foreign_table))
+            })
+        });
+        // A cycle: take the next table as is
+        sorted.push(pending.remove(ready.unwrap_or(0)));
+    }
+
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{Column, ForeignKey, ForeignKeyAction, PostgresPlatform};
+
+    fn users_and_posts() -> Vec<TableDump> {
+        let posts = Table::new("posts")
+            .column(Column::new("id", SqlType::Integer).not_null())
+            .column(Column::new("user_id", SqlType::Integer))
+            .foreign_key(ForeignKey {
+                name: String::new(),
+                local_columns: vec!["user_id".into()],
+                foreign_table: "users".into(),
+                foreign_columns: vec!["id".into()],
+                on_delete: ForeignKeyAction::NoAction,
+                on_update: ForeignKeyAction::NoAction,
+            });
+        let users = Table::new("users")
+            .column(Column::new("id", SqlType::Integer).not_null())
+            .column(Column::new("active", SqlType::Boolean));
+
+        vec![
+            TableDump::new(posts, vec![vec![SqlValue::I64(1), SqlValue::I64(1)]]),
+            TableDump::new(
+                users,
+                vec![
+                    vec![SqlValue::I64(1), SqlValue::Bool(true)],
+                    vec![SqlValue::I64(2), SqlValue::Null],
+                    vec![SqlValue::I64(3), SqlValue::Bool(false)],
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let dump = DatabaseDump::new(users_and_posts());
+        let names: Vec<&str> = dump.tables.iter().map(|t| t.table.name.as_str()).collect();
+        assert_eq!(names, ["users", "posts"]);
+    }
+
+    #[test]
+    fn test_to_sql() {
+        let dump = DatabaseDump::new(users_and_posts());
+        let options = DumpOptions::new().with_batch_size(2).with_drop_existing(true);
+        let statements = dump.to_sql(&PostgresPlatform, &options);
+
+        assert_eq!(statements[0], "DROP TABLE IF EXISTS \"posts\"");
+        assert_eq!(statements[1], "DROP TABLE IF EXISTS \"users\"");
+        assert!(statements[2].starts_with("CREATE TABLE \"users\""));
+        assert!(statements[2].contains("\"active\" BOOLEAN"));
+        assert_eq!(
+            statements[3],
+            "INSERT INTO \"users\" (\"id\", \"active\") VALUES (1, TRUE), (2, NULL)"
+        );
+        assert_eq!(statements[4], "INSERT INTO \"users\" (\"id\", \"active\") VALUES (3, FALSE)");
+        assert!(statements[5].contains("FOREIGN KEY (\"user_id\") REFERENCES \"users\" (\"id\")"));
+        assert!(!statements[5].contains("CONSTRAINT"));
+        assert_eq!(statements.len(), 7);
+
+        let data = dump.to_sql(&PostgresPlatform, &DumpOptions::new().data_only());
+        assert_eq!(data.len(), 2);
+        assert!(data.iter().all(|sql| sql.starts_with("INSERT")));
+    }
+
+    #[test]
+    fn test_coerce_value() {
+        assert_eq!(coerce_value(SqlValue::I64(1), &SqlType::Boolean), SqlValue::Bool(true));
+        assert_eq!(coerce_value(SqlValue::I64(1), &SqlType::Integer), SqlValue::I64(1));
+        assert_eq!(coerce_value(SqlValue::Null, &SqlType::Boolean), SqlValue::Null);
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod sqlite_tests {
+    use super::*;
+    use crate::driver::{Driver, SqliteDriver};
+    use crate::platform::SqlitePlatform;
+
+    async fn setup_connection() -> <SqliteDriver as Driver>::Connection {
+        SqliteDriver::new()
+            .connect(&crate::core::ConnectionParams::sqlite_memory())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dump_and_restore() {
+        let source = setup_connection().await;
+        source
+            .execute("CREATE TABLE authors (id INTEGER PRIMARY KEY, name TEXT NOT NULL, active BOOLEAN)")
+            .await
+            .unwrap();
+        source
+            .execute(
+                "CREATE TABLE books (id INTEGER PRIMARY KEY, author_id INTEGER REFERENCES authors (id), title TEXT)",
+            )
+            .await
+            .unwrap();
+        source.execute("CREATE INDEX idx_books_title ON books (title)").await.unwrap();
+        source
+            .execute("INSERT INTO authors VALUES (1, 'O''Brien', 1), (2, 'Le Guin', 0)")
+            .await
+            .unwrap();
+        source
+            .execute("INSERT INTO books VALUES (1, 2, 'The Dispossessed'), (2, 1, NULL)")
+            .await
+            .unwrap();
+
+        let platform = SqlitePlatform::new();
+        let options = DumpOptions::new().with_batch_size(1);
+        let dump = Dumper::new(&source, &platform).dump(&options).await.unwrap();
+
+        assert_eq!(dump.tables[0].table.name, "authors");
+        assert_eq!(
+            dump.table("authors").unwrap().rows[0],
+            vec![SqlValue::I64(1), SqlValue::String("O'Brien".into()), SqlValue::Bool(true)]
+        );
+
+        let target = setup_connection().await;
+        dump.restore(&target, &platform, &options).await.unwrap();
+
+        let restored = Dumper::new(&target, &platform).dump(&options).await.unwrap();
+        assert_eq!(restored, dump);
+
+        let missing = Dumper::new(&source, &platform)
+            .dump(&DumpOptions::new().with_tables(["nope"]))
+            .await;
+        assert!(matches!(
+            missing,
+            Err(crate::core::Error::Schema(SchemaError::TableNotFound(_)))
+        ));
+    }
+}
@@ -0,0 +1,37 @@
+//! # Dump Module
+//!
+//! Database-agnostic export and import of schema and data.
+//!
+//! A [`Dumper`] introspects the tables of a connection through the
+//! [`SchemaManager`](crate::schema::SchemaManager), converts them into
+//! platform-neutral [`Table`](crate::platform::Table) definitions and reads
+//! their rows. The resulting [`DatabaseDump`] can be rendered as a SQL script
+//! for any [`Platform`](crate::platform::Platform), written as CSV per table,
+//! or restored directly into another connection. Column types are carried as
+//! [`SqlType`](crate::platform::SqlType), so the target platform emits its own
+//! declarations (e.g. `BOOLEAN` columns dumped from `SQLite` become `BOOLEAN`
+//! on `PostgreSQL` and their `0`/`1` values become `FALSE`/`TRUE`).
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rustine_dbal::dump::{DumpOptions, Dumper};
+//! use rustine_dbal::platform::{PostgresPlatform, SqlitePlatform};
+//!
+//! let options = DumpOptions::new().with_batch_size(500);
+//! let dump = Dumper::new(&sqlite_conn, &SqlitePlatform::new()).dump(&options).await?;
+//!
+//! // Script for another platform
+//! let script = dump.to_sql_script(&PostgresPlatform, &options);
+//!
+//! // Or restore directly
+//! dump.restore(&pg_conn, &PostgresPlatform, &options).await?;
+//! ```
+
+mod csv;
+mod dumper;
+
+pub use dumper::{DatabaseDump, DumpOptions, Dumper, TableDump};
+pub(crate) use csv::{parse_field, parse_records};
+#[cfg(feature = "json")]
+pub(crate) use csv::CsvField;
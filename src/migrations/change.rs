@@ -0,0 +1,190 @@
+//! Declarative schema changes that know their inverse
+
+use crate::platform::{Column, Index, Platform, Table};
+
+/// A schema change of a reversible migration, see [`Migration::change`](super::Migration::change)
+///
+/// Changes carry the full definition of what they create or remove, so the
+/// inverse is known: dropping a table needs its [`Table`], removing a column
+/// its [`Column`]. Only raw [`Sql`](Self::Sql) can't be reverted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// Create a table with its indexes
+    CreateTable(Table),
+    /// Drop a table
+    DropTable(Table),
+    /// Add a column to a table
+    AddColumn {
+        /// Table name
+        table: String,
+        /// Column definition
+        column: Column,
+    },
+    /// Remove a column from a table
+    RemoveColumn {
+        /// Table name
+        table: String,
+        /// Definition of the removed column
+        column: Column,
+    },
+    /// Rename a column
+    RenameColumn {
+        /// Table name
+        table: String,
+        /// Current column name
+        from: String,
+        /// New column name
+        to: String,
+    },
+    /// Create an index
+    AddIndex {
+        /// Table name
+        table: String,
+        /// Index definition
+        index: Index,
+    },
+    /// Drop an index
+    RemoveIndex {
+        /// Table name
+        table: String,
+        /// Definition of the dropped index
+        index: Index,
+    },
+    /// A statement without inverse
+    Sql(String),
+}
+
+impl Change {
+    /// Create a table
+    #[must_use]
+    pub const fn create_table(table: Table) -> Self {
+        Self::CreateTable(table)
+    }
+
+    /// Drop a table, given its definition
+    #[must_use]
+    pub const fn drop_table(table: Table) -> Self {
+        Self::DropTable(table)
+    }
+
+    /// Add a column
+    #[must_use]
+    pub fn add_column(table: impl Into<String>, column: Column) -> Self {
+        Self::AddColumn { table: table.into(), column }
+    }
+
+    /// Remove a column, given its definition
+    #[must_use]
+    pub fn remove_column(table: impl Into<String>, column: Column) -> Self {
+        Self::RemoveColumn { table: table.into(), column }
+    }
+
+    /// Rename a column
+    #[must_use]
+    pub fn rename_column(table: impl Into<String>, from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self::RenameColumn {
+            table: table.into(),
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    /// Create an index
+    #[must_use]
+    pub fn add_index(table: impl Into<String>, index: Index) -> Self {
+        Self::AddIndex { table: table.into(), index }
+    }
+
+    /// Drop an index, given its definition
+    #[must_use]
+    pub fn remove_index(table: impl Into<String>, index: Index) -> Self {
+        Self::RemoveIndex { table: table.into(), index }
+    }
+
+    /// Run a statement that can't be reverted
+    #[must_use]
+    pub fn sql(sql: impl Into<String>) -> Self {
+        Self::Sql(sql.into())
+    }
+
+    /// Get the change undoing this one, `None` for raw SQL
+    #[must_use]
+    pub fn inverse(&self) -> Option<Self> {
+        Some(match self {
+            Self::CreateTable(table) => Self::DropTable(table.clone()),
+            Self::DropTable(table) => Self::CreateTable(table.clone()),
+            Self::AddColumn { table, column } => Self::remove_column(table, column.clone()),
+            Self::RemoveColumn { table, column } => Self::add_column(table, column.clone()),
+            Self::RenameColumn { table, from, to } => Self::rename_column(table, to, from),
+            Self::AddIndex { table, index } => Self::remove_index(table, index.clone()),
+            Self::RemoveIndex { table, index } => Self::add_index(table, index.clone()),
+            Self::Sql(_) => return None,
+        })
+    }
+
+    /// Generate the statements applying the change on a platform
+    #[must_use]
+    pub fn to_sql(&self, platform: &dyn Platform) -> Vec<String> {
+        match self {
+            Self::CreateTable(table) => {
+                let mut statements = vec![platform.get_create_table_sql(table)];
+                statements.extend(
+                    table
+                        .indexes
+                        .iter()
+                        .filter(|index| !index.primary && !index.unique)
+                        .map(|index| platform.get_create_index_sql(&table.name, index)),
+                );
+                statements
+            }
+            Self::DropTable(table) => vec![platform.get_drop_table_sql(&table.name)],
+            Self::AddColumn { table, column } => vec![platform.get_add_column_sql(table, column)],
+            Self::RemoveColumn { table, column } => vec![platform.get_drop_column_sql(table, &column.name)],
+            Self::RenameColumn { table, from, to } => vec![platform.get_rename_column_sql(table, from, to)],
+            Self::AddIndex { table, index } => vec![platform.get_create_index_sql(table, index)],
+            Self::RemoveIndex { table, index } => vec![platform.get_drop_index_sql(&index.name, table)],
+            Self::Sql(sql) => vec![sql.clone()],
+        }
+    }
+
+    /// Describe the change for error messages
+    pub(super) fn describe(&self) -> String {
+        match self {
+            Self::CreateTable(table) => format!("create table {}", table.name),
+            Self::DropTable(table) => format!("drop table {}", table.name),
+            Self::AddColumn { table, column } => format!("add column {table}.{}", column.name),
+            Self::RemoveColumn { table, column } => format!("remove column {table}.{}", column.name),
+            Self::RenameColumn { table, from, to } => format!("rename column {table}.{from} to {to}"),
+            Self::AddIndex { index, .. } => format!("add index {}", index.name),
+            Self::RemoveIndex { index, .. } => format!("remove index {}", index.name),
+            Self::Sql(sql) => format!("SQL {sql}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{SqlType, SqlitePlatform};
+
+    #[test]
+    fn test_inverse() {
+        let platform = SqlitePlatform::new();
+        let posts = Table::new("posts")
+            .column(Column::new("id", SqlType::Integer).not_null())
+            .column(Column::new("author", SqlType::Text))
+            .index(Index::primary(vec!["id".into()]))
+            .index(Index::new("posts_author", vec!["author".into()]));
+
+        let create = Change::create_table(posts.clone());
+        assert_eq!(create.to_sql(&platform).len(), 2);
+        assert_eq!(create.inverse(), Some(Change::drop_table(posts)));
+        assert_eq!(
+            Change::rename_column("posts", "author", "writer").inverse().unwrap().to_sql(&platform),
+            ["ALTER TABLE \"posts\" RENAME COLUMN \"writer\" TO \"author\""]
+        );
+        let column = Change::add_column("posts", Column::new("title", SqlType::Text));
+        assert_eq!(column.inverse().and_then(|change| change.inverse()), Some(column));
+        assert_eq!(Change::sql("UPDATE posts SET author = 'anonymous'").inverse(), None);
+    }
+}
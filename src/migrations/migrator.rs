@@ -0,0 +1,677 @@
+//! Migrator applying versioned migrations and detecting drift
+
+
+use sha2::{Digest, Sha256};
+
+use crate::core::{hex_encode, Error, MigrationError, Result, SchemaError, SqlValue};
+use crate::driver::{DriverConnection, DriverResult};
+use crate::dump::{DumpOptions, Dumper};
+use crate::platform::{Column, Index, Platform, SqlType, Table};
+use crate::schema::SchemaManager;
+
+use super::Change;
+
+/// Name of the history table unless set with [`Migrator::with_table`]
+pub const DEFAULT_TABLE: &str = "rustine_migrations";
+
+/// A versioned schema change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    /// Version, migrations are applied in ascending order
+    pub version: i64,
+    /// Human-readable name, recorded in the history table
+    pub name: String,
+    /// Statements migrating the schema up
+    pub up: Vec<String>,
+    /// Statements reverting [`up`](Self::up)
+    pub down: Vec<String>,
+}
+
+impl Migration {
+    /// Create a migration without statements
+    #[must_use]
+    pub fn new(version: i64, name: impl Into<String>) -> Self {
+        Self {
+            version,
+            name: name.into(),
+            up: Vec::new(),
+            down: Vec::new(),
+        }
+    }
+
+    /// Add a statement migrating up
+    #[must_use]
+    pub fn up(mut self, sql: impl Into<String>) -> Self {
+        self.up.push(sql.into());
+        self
+    }
+
+    /// Add a statement migrating down
+    #[must_use]
+    pub fn down(mut self, sql: impl Into<String>) -> Self {
+        self.down.push(sql.into());
+        self
+    }
+
+    /// Add reversible schema changes
+    ///
+    /// The statements of the changes are added to the up statements in
+    /// order, those of their inverses in reverse order in front of the down
+    /// statements, so migrating down undoes the changes last to first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MigrationError::Irreversible`] for a change without inverse
+    /// ([`Change::Sql`]); add such statements with [`up`](Self::up) and
+    /// [`down`](Self::down) instead.
+    pub fn change(mut self, platform: &dyn Platform, changes: impl IntoIterator<Item = Change>) -> Result<Self> {
+        let mut down = Vec::new();
+        for change in changes {
+            let Some(inverse) = change.inverse() else {
+                return Err(MigrationError::Irreversible {
+                    version: self.version,
+                    reason: format!("{} has no inverse", change.describe()),
+                }
+                .into());
+            };
+            self.up.extend(change.to_sql(platform));
+            down.splice(0..0, inverse.to_sql(platform));
+        }
+        down.append(&mut self.down);
+        self.down = down;
+        Ok(self)
+    }
+
+    /// Get the SHA-256 of the up statements as lowercase hex
+    ///
+    /// Only the statements run against the database count, so renaming a
+    /// migration or adding down statements keeps its checksum.
+    #[must_use]
+    pub fn checksum(&self) -> String {
+        let mut hasher = Sha256::new();
+        for sql in &self.up {
+            hasher.update(sql.as_bytes());
+            hasher.update(b";\n");
+        }
+        hex_encode(&hasher.finalize())
+    }
+}
+
+/// A migration recorded in the history table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    /// Version of the migration
+    pub version: i64,
+    /// Name of the migration when it was applied
+    pub name: String,
+    /// Checksum of the migration when it was applied
+    pub checksum: String,
+}
+
+/// Applies migrations and records them in a history table
+///
+/// [`migrate`](Self::migrate) first [`validate`](Self::validate)s the
+/// history: every applied migration must still exist with the checksum it
+/// was applied with. A migration edited on purpose, e.g. to fix a typo in a
+/// statement that can't fail differently, is re-baselined with
+/// [`repair`](Self::repair).
+pub struct Migrator<'a, C: DriverConnection> {
+    /// Database connection to migrate
+    connection: &'a C,
+    /// Platform for generating SQL
+    platform: &'a dyn Platform,
+    /// Name of the history table
+    table: String,
+    /// Known migrations, in the order they were added
+    migrations: Vec<Migration>,
+}
+
+impl<C: DriverConnection> std::fmt::Debug for Migrator<'_, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Migrator")
+            .field("platform", &self.platform.name())
+            .field("table", &self.table)
+            .field("migrations", &self.migrations.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, C: DriverConnection> Migrator<'a, C> {
+    /// Create a migrator without migrations using the [`DEFAULT_TABLE`]
+    #[must_use]
+    pub fn new(connection: &'a C, platform: &'a dyn Platform) -> Self {
+        Self {
+            connection,
+            platform,
+            table: DEFAULT_TABLE.to_string(),
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Record applied migrations in a differently named table
+    #[must_use]
+    pub fn with_table(mut self, table: impl Into<String>) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    /// Add a migration
+    #[must_use]
+    pub fn migration(mut self, migration: Migration) -> Self {
+        self.migrations.push(migration);
+        self
+    }
+
+    /// Get the definition of the history table
+    #[must_use]
+    pub fn history_table(&self) -> Table {
+        Table::new(&self.table)
+            .column(Column::new("version", SqlType::BigInt).not_null())
+            .column(Column::new("name", SqlType::varchar(255)).not_null())
+            .column(Column::new("checksum", SqlType::Char { length: 64 }).not_null())
+            .column(Column::new("applied_at", SqlType::Timestamp { precision: None }).default_current_timestamp())
+            .index(Index::primary(vec!["version".into()]))
+    }
+
+    /// List the applied migrations by version, none before the first migration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history table can't be read.
+    pub async fn applied(&self) -> Result<Vec<AppliedMigration>> {
+        if !SchemaManager::new(self.connection, self.platform).table_exists(&self.table).await? {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "SELECT {}, {}, {} FROM {} ORDER BY {}",
+            self.platform.quote_identifier("version"),
+            self.platform.quote_identifier("name"),
+            self.platform.quote_identifier("checksum"),
+            self.platform.quote_identifier(&self.table),
+            self.platform.quote_identifier("version"),
+        );
+        let mut result = self.connection.query(&sql).await?;
+        result
+            .all_rows()?
+            .iter()
+            .map(|row| match row.as_slice() {
+                [version, name, checksum] => Ok(AppliedMigration {
+                    version: version.as_i64().ok_or_else(|| self.invalid_history("version"))?,
+                    name: name.as_str().ok_or_else(|| self.invalid_history("name"))?.to_string(),
+                    checksum: checksum.as_str().ok_or_else(|| self.invalid_history("checksum"))?.trim_end().to_string(),
+                }),
+                _ => Err(self.invalid_history("row")),
+            })
+            .collect()
+    }
+
+    /// Check that every applied migration is unchanged
+    ///
+    /// Meant to run at startup, before the application uses the schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MigrationError::DuplicateVersion`] if two migrations share a
+    /// version, [`MigrationError::Missing`] for an applied migration that is
+    /// no longer known and [`MigrationError::ChecksumMismatch`] for one that
+    /// was edited.
+    pub async fn validate(&self) -> Result<()> {
+        self.sorted()?;
+        for applied in self.applied().await? {
+            let migration = self.find(applied.version)?;
+            let current = migration.checksum();
+            if current != applied.checksum {
+                return Err(MigrationError::ChecksumMismatch {
+                    version: applied.version,
+                    name: migration.name.clone(),
+                    applied: applied.checksum,
+                    current,
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// List the migrations not applied yet, by version
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`validate`](Self::validate) fails.
+    pub async fn pending(&self) -> Result<Vec<&Migration>> {
+        self.validate().await?;
+        let applied = self.applied().await?;
+        Ok(self
+            .sorted()?
+            .into_iter()
+            .filter(|migration| !applied.iter().any(|a| a.version == migration.version))
+            .collect())
+    }
+
+    /// Apply the pending migrations, returning their versions
+    ///
+    /// Each migration runs in its own transaction together with its history
+    /// row. On platforms committing DDL implicitly (`MySQL`) a failed
+    /// migration may be left half applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`validate`](Self::validate) fails or a statement
+    /// fails; migrations before the failing one stay applied.
+    pub async fn migrate(&self) -> Result<Vec<i64>> {
+        let pending = self.pending().await?;
+        let schema = SchemaManager::new(self.connection, self.platform);
+        if !pending.is_empty() && !schema.table_exists(&self.table).await? {
+            schema.create_table(&self.history_table()).await?;
+        }
+
+        let mut versions = Vec::with_capacity(pending.len());
+        for migration in pending {
+            let record = self.record_sql(migration);
+            self.execute_in_transaction(migration.up.iter().chain([&record])).await?;
+            versions.push(migration.version);
+        }
+        if !versions.is_empty() {
+            self.connection.clear_statement_cache().await?;
+        }
+        Ok(versions)
+    }
+
+    /// Revert the applied migrations above a version, returning their versions
+    ///
+    /// Migrations are reverted newest first, each in its own transaction
+    /// together with removing its history row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`validate`](Self::validate) fails,
+    /// [`MigrationError::Irreversible`] if a migration to revert has no down
+    /// statements (before reverting any), or an error if a statement fails;
+    /// migrations reverted before the failing one stay reverted.
+    pub async fn migrate_down(&self, to_version: i64) -> Result<Vec<i64>> {
+        self.validate().await?;
+        let mut revert = Vec::new();
+        for applied in self.applied().await?.iter().rev().filter(|a| a.version > to_version) {
+            let migration = self.find(applied.version)?;
+            if migration.down.is_empty() && !migration.up.is_empty() {
+                return Err(MigrationError::Irreversible {
+                    version: migration.version,
+                    reason: "no down statements".to_string(),
+                }
+                .into());
+            }
+            revert.push(migration);
+        }
+
+        let mut versions = Vec::with_capacity(revert.len());
+        for migration in revert {
+            let delete = format!(
+                "DELETE FROM {} WHERE {} = {}",
+                self.platform.quote_identifier(&self.table),
+                self.platform.quote_identifier("version"),
+                migration.version,
+            );
+            self.execute_in_transaction(migration.down.iter().chain([&delete])).await?;
+            versions.push(migration.version);
+        }
+        if !versions.is_empty() {
+            self.connection.clear_statement_cache().await?;
+        }
+        Ok(versions)
+    }
+
+    /// Re-baseline edited migrations, returning their versions
+    ///
+    /// Records the current checksum and name of every applied migration whose
+    /// checksum changed, without running any statement of it. This is the
+    /// explicit override for [`MigrationError::ChecksumMismatch`]; the
+    /// database must already match the edited migration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MigrationError::DuplicateVersion`] or
+    /// [`MigrationError::Missing`] like [`validate`](Self::validate), or an
+    /// error if the history table can't be updated.
+    pub async fn repair(&self) -> Result<Vec<i64>> {
+        self.sorted()?;
+        let mut statements = Vec::new();
+        let mut versions = Vec::new();
+        for applied in self.applied().await? {
+            let migration = self.find(applied.version)?;
+            let checksum = migration.checksum();
+            if checksum != applied.checksum {
+                statements.push(format!(
+                    "UPDATE {} SET {} = {}, {} = {} WHERE {} = {}",
+                    self.platform.quote_identifier(&self.table),
+                    self.platform.quote_identifier("checksum"),
+                    self.platform.format_literal(&SqlValue::from(checksum)),
+                    self.platform.quote_identifier("name"),
+                    self.platform.format_literal(&SqlValue::from(migration.name.as_str())),
+                    self.platform.quote_identifier("version"),
+                    applied.version,
+                ));
+                versions.push(applied.version);
+            }
+        }
+        if !statements.is_empty() {
+            self.execute_in_transaction(statements.iter()).await?;
+        }
+        Ok(versions)
+    }
+
+    /// Collapse the applied migrations up to a version into a baseline
+    ///
+    /// The schema of the database is dumped with the [`Dumper`] into a
+    /// migration of that version creating all tables (and dropping them
+    /// again when migrating down), which replaces the squashed migrations in
+    /// the history table. The returned baseline should replace them in the
+    /// application too: migrated databases keep their schema, new databases
+    /// get the same schema from one migration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if [`validate`](Self::validate) fails,
+    /// [`MigrationError::InvalidBaseline`] unless the database is migrated
+    /// exactly up to the version, or an error if the schema can't be dumped
+    /// or the history table can't be updated.
+    pub async fn squash(&self, up_to_version: i64) -> Result<Migration> {
+        self.validate().await?;
+        let applied = self.applied().await?;
+        if !applied.iter().any(|a| a.version == up_to_version) {
+            return Err(MigrationError::InvalidBaseline(format!("migration {up_to_version} is not applied")).into());
+        }
+        if let Some(later) = applied.iter().find(|a| a.version > up_to_version) {
+            return Err(MigrationError::InvalidBaseline(format!(
+                "migration {} is applied after {up_to_version}, the dumped schema would include it",
+                later.version
+            ))
+            .into());
+        }
+
+        let baseline = self.dump_baseline(up_to_version).await?;
+        let delete = format!(
+            "DELETE FROM {} WHERE {} <= {up_to_version}",
+            self.platform.quote_identifier(&self.table),
+            self.platform.quote_identifier("version"),
+        );
+        self.execute_in_transaction([delete, self.record_sql(&baseline)].iter()).await?;
+        Ok(baseline)
+    }
+
+    /// Adopt migrations on a database whose schema was created without them
+    ///
+    /// Dumps the current schema into a baseline migration of the given
+    /// version and records it as applied, creating the history table. Add the
+    /// returned baseline to the migrations; later versions then run on this
+    /// database, while new databases start from the baseline.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MigrationError::InvalidBaseline`] if migrations are already
+    /// recorded, or an error if the schema can't be dumped or recorded.
+    pub async fn baseline_from_database(&self, version: i64) -> Result<Migration> {
+        if let Some(applied) = self.applied().await?.first() {
+            return Err(MigrationError::InvalidBaseline(format!(
+                "migration {} is already recorded in {}",
+                applied.version, self.table
+            ))
+            .into());
+        }
+
+        let baseline = self.dump_baseline(version).await?;
+        let schema = SchemaManager::new(self.connection, self.platform);
+        if !schema.table_exists(&self.table).await? {
+            schema.create_table(&self.history_table()).await?;
+        }
+        self.execute_in_transaction([self.record_sql(&baseline)].iter()).await?;
+        Ok(baseline)
+    }
+
+    /// Dump the schema of the database, without the history table, into a migration
+    async fn dump_baseline(&self, version: i64) -> Result<Migration> {
+        let tables = SchemaManager::new(self.connection, self.platform)
+            .list_table_names()
+            .await?
+            .into_iter()
+            .filter(|name| *name != self.table);
+        let options = DumpOptions::new().with_tables(tables).schema_only();
+        let dump = Dumper::new(self.connection, self.platform).dump(&options).await?;
+        Ok(Migration {
+            version,
+            name: format!("baseline {version}"),
+            up: dump.to_sql(self.platform, &options),
+            down: dump
+                .tables
+                .iter()
+                .rev()
+                .map(|table| self.platform.get_drop_table_sql(&table.table.name))
+                .collect(),
+        })
+    }
+
+    /// Generate the INSERT recording an applied migration
+    fn record_sql(&self, migration: &Migration) -> String {
+        format!(
+            "INSERT INTO {} ({}, {}, {}) VALUES ({}, {}, {})",
+            self.platform.quote_identifier(&self.table),
+            self.platform.quote_identifier("version"),
+            self.platform.quote_identifier("name"),
+            self.platform.quote_identifier("checksum"),
+            migration.version,
+            self.platform.format_literal(&SqlValue::from(migration.name.as_str())),
+            self.platform.format_literal(&SqlValue::from(migration.checksum())),
+        )
+    }
+
+    /// Get the migrations by version, rejecting duplicate versions
+    fn sorted(&self) -> Result<Vec<&Migration>> {
+        let mut migrations: Vec<&Migration> = self.migrations.iter().collect();
+        migrations.sort_by_key(|migration| migration.version);
+        if let Some(pair) = migrations.windows(2).find(|pair| pair[0].version == pair[1].version) {
+            return Err(MigrationError::DuplicateVersion(pair[0].version).into());
+        }
+        Ok(migrations)
+    }
+
+    /// Get the migration of an applied version
+    fn find(&self, version: i64) -> Result<&Migration> {
+        self.migrations
+            .iter()
+            .find(|migration| migration.version == version)
+            .ok_or_else(|| MigrationError::Missing(version).into())
+    }
+
+    /// Run statements in one transaction, rolling back on the first failure
+    async fn execute_in_transaction(&self, statements: impl Iterator<Item = &String>) -> Result<()> {
+        self.connection.begin_transaction().await?;
+        for sql in statements {
+            if let Err(e) = self.connection.execute(sql).await {
+                let _ = self.connection.rollback().await;
+                return Err(e);
+            }
+        }
+        self.connection.commit().await
+    }
+
+    /// Create an error for a history row that can't be read
+    fn invalid_history(&self, field: &str) -> Error {
+        SchemaError::IntrospectionFailed(format!("invalid {field} in migration table {}", self.table)).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        let migration = Migration::new(1, "create users").up("CREATE TABLE users (id INTEGER)");
+        assert_eq!(migration.checksum(), "55514a982f4cecf99df093c3afd0132cd5ef1181e81a8e5620dca4eef4c1e278");
+        assert_eq!(migration.clone().down("DROP TABLE users").checksum(), migration.checksum());
+        assert_ne!(migration.clone().up("CREATE INDEX users_id ON users (id)").checksum(), migration.checksum());
+        assert_eq!(Migration::new(1, "empty").checksum().len(), 64);
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod sqlite_tests {
+    use super::*;
+    use crate::driver::{Driver, SqliteDriver};
+    use crate::platform::SqlitePlatform;
+
+    async fn setup_connection() -> <SqliteDriver as Driver>::Connection {
+        let driver = SqliteDriver::new();
+        let params = crate::core::ConnectionParams::sqlite_memory();
+        driver.connect(&params).await.unwrap()
+    }
+
+    fn create_users() -> Migration {
+        Migration::new(1, "create users")
+            .up("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)")
+            .down("DROP TABLE users")
+    }
+
+    fn add_email() -> Migration {
+        Migration::new(2, "add email").up("ALTER TABLE users ADD COLUMN email TEXT")
+    }
+
+    #[tokio::test]
+    async fn test_migrate() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+
+        let migrator = Migrator::new(&conn, &platform).migration(add_email()).migration(create_users());
+        assert!(migrator.applied().await.unwrap().is_empty());
+        assert_eq!(migrator.migrate().await.unwrap(), [1, 2]);
+        assert_eq!(migrator.migrate().await.unwrap(), Vec::<i64>::new());
+
+        let applied = migrator.applied().await.unwrap();
+        assert_eq!(applied.len(), 2);
+        assert_eq!(applied[0].checksum, create_users().checksum());
+        assert!(SchemaManager::new(&conn, &platform).introspect_table("users").await.unwrap().has_column("email"));
+
+        let migrator = Migrator::new(&conn, &platform).migration(create_users()).migration(Migration { version: 1, ..add_email() });
+        assert!(matches!(
+            migrator.migrate().await,
+            Err(Error::Migration(MigrationError::DuplicateVersion(1)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_rolls_back() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+
+        let broken = Migration::new(2, "broken").up("CREATE TABLE posts (id INTEGER)").up("NOT SQL");
+        let migrator = Migrator::new(&conn, &platform).migration(create_users()).migration(broken);
+        assert!(migrator.migrate().await.is_err());
+        let versions: Vec<i64> = migrator.applied().await.unwrap().iter().map(|a| a.version).collect();
+        assert_eq!(versions, [1]);
+        assert!(!SchemaManager::new(&conn, &platform).table_exists("posts").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_checksum_mismatch_and_repair() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        Migrator::new(&conn, &platform).migration(create_users()).migrate().await.unwrap();
+
+        let edited = Migration::new(1, "create users table").up("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)");
+        let migrator = Migrator::new(&conn, &platform).migration(edited.clone()).migration(add_email());
+        let Err(Error::Migration(MigrationError::ChecksumMismatch { version, applied, current, .. })) = migrator.migrate().await else {
+            panic!("edited migration should be detected");
+        };
+        assert_eq!((version, applied, current), (1, create_users().checksum(), edited.checksum()));
+        assert!(migrator.pending().await.is_err());
+
+        assert_eq!(migrator.repair().await.unwrap(), [1]);
+        assert_eq!(migrator.repair().await.unwrap(), Vec::<i64>::new());
+        assert_eq!(migrator.applied().await.unwrap()[0].name, "create users table");
+        assert_eq!(migrator.migrate().await.unwrap(), [2]);
+
+        let migrator = Migrator::new(&conn, &platform).migration(add_email());
+        assert!(matches!(migrator.validate().await, Err(Error::Migration(MigrationError::Missing(1)))));
+        assert!(migrator.repair().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_squash() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let posts = Migration::new(3, "create posts").up("CREATE TABLE posts (id INTEGER PRIMARY KEY, title TEXT)");
+        Migrator::new(&conn, &platform).migration(create_users()).migration(add_email()).migrate().await.unwrap();
+
+        let migrator = Migrator::new(&conn, &platform).migration(create_users()).migration(add_email()).migration(posts.clone());
+        assert!(matches!(migrator.squash(3).await, Err(Error::Migration(MigrationError::InvalidBaseline(_)))));
+        let baseline = migrator.squash(2).await.unwrap();
+        assert_eq!(baseline.version, 2);
+        assert_eq!(baseline.down, ["DROP TABLE \"users\""]);
+        let applied = migrator.applied().await.unwrap();
+        assert_eq!((applied.len(), applied[0].checksum.clone()), (1, baseline.checksum()));
+
+        let squashed = Migrator::new(&conn, &platform).migration(baseline.clone()).migration(posts.clone());
+        assert_eq!(squashed.migrate().await.unwrap(), [3]);
+        assert!(matches!(squashed.squash(2).await, Err(Error::Migration(MigrationError::InvalidBaseline(_)))));
+
+        let fresh = setup_connection().await;
+        let migrator = Migrator::new(&fresh, &platform).migration(baseline).migration(posts);
+        assert_eq!(migrator.migrate().await.unwrap(), [2, 3]);
+        let users = SchemaManager::new(&fresh, &platform).introspect_table("users").await.unwrap();
+        assert!(users.has_column("email"));
+    }
+
+    #[tokio::test]
+    async fn test_baseline_from_database() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        conn.execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)").await.unwrap();
+
+        let migrator = Migrator::new(&conn, &platform);
+        let baseline = migrator.baseline_from_database(1).await.unwrap();
+        assert_eq!((baseline.version, baseline.up.len()), (1, 1));
+        assert!(migrator.baseline_from_database(1).await.is_err());
+
+        let migrator = Migrator::new(&conn, &platform).migration(baseline).migration(add_email());
+        assert_eq!(migrator.migrate().await.unwrap(), [2]);
+    }
+
+    #[tokio::test]
+    async fn test_change_and_migrate_down() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let schema = SchemaManager::new(&conn, &platform);
+
+        let add_email = Migration::new(2, "add email")
+            .change(
+                &platform,
+                [
+                    Change::add_column("users", Column::new("email", SqlType::Text)),
+                    Change::add_index("users", Index::new("users_email", vec!["email".into()])),
+                ],
+            )
+            .unwrap();
+        assert_eq!(add_email.down, ["DROP INDEX \"users_email\"", "ALTER TABLE \"users\" DROP COLUMN \"email\""]);
+        let backfill = Migration::new(3, "backfill email").up("UPDATE users SET email = name");
+
+        let migrator = Migrator::new(&conn, &platform).migration(create_users()).migration(add_email.clone()).migration(backfill);
+        assert_eq!(migrator.migrate().await.unwrap(), [1, 2, 3]);
+        assert!(matches!(
+            migrator.migrate_down(1).await,
+            Err(Error::Migration(MigrationError::Irreversible { version: 3, .. }))
+        ));
+        assert_eq!(migrator.applied().await.unwrap().len(), 3);
+
+        let migrator = Migrator::new(&conn, &platform)
+            .migration(create_users())
+            .migration(add_email)
+            .migration(Migration::new(3, "backfill email").up("UPDATE users SET email = name").down("UPDATE users SET email = NULL"));
+        assert_eq!(migrator.migrate_down(1).await.unwrap(), [3, 2]);
+        assert!(!schema.introspect_table("users").await.unwrap().has_column("email"));
+        assert_eq!(migrator.migrate_down(0).await.unwrap(), [1]);
+        assert!(!schema.table_exists("users").await.unwrap());
+        assert_eq!(migrator.migrate().await.unwrap(), [1, 2, 3]);
+
+        let irreversible = Migration::new(4, "cleanup").change(&platform, [Change::sql("DELETE FROM users")]);
+        assert!(matches!(irreversible, Err(Error::Migration(MigrationError::Irreversible { version: 4, .. }))));
+    }
+}
@@ -0,0 +1,50 @@
+//! # Migrations Module
+//!
+//! Versioned schema migrations.
+//!
+//! A [`Migration`] is a version, a name and the statements migrating the
+//! schema up (and optionally down). The [`Migrator`] applies the migrations
+//! a database hasn't seen yet in version order and records each in a
+//! history table, together with the SHA-256 checksum of its statements.
+//! Before migrating, the checksums are compared with the migrations as they
+//! are now, so a migration edited after it was applied is reported as
+//! [`MigrationError::ChecksumMismatch`](crate::core::MigrationError::ChecksumMismatch)
+//! instead of leaving databases that ran different versions of it.
+//!
+//! Long histories are collapsed with [`Migrator::squash`], which dumps the
+//! schema into a single baseline migration, and databases created before
+//! migrations were used are adopted with [`Migrator::baseline_from_database`].
+//!
+//! Instead of writing up and down statements by hand, a migration can be
+//! declared with [`Migration::change`] from [`Change`]s (create a table, add
+//! a column or index, ...) whose down statements are generated from their
+//! inverses, for [`Migrator::migrate_down`].
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rustine_dbal::migrations::{Change, Migration, Migrator};
+//! use rustine_dbal::platform::{Column, Index, SqlType, SqlitePlatform};
+//!
+//! let platform = SqlitePlatform::new();
+//! let migrator = Migrator::new(&conn, &platform)
+//!     .migration(Migration::new(1, "create users").up("CREATE TABLE users (id INTEGER PRIMARY KEY)"))
+//!     .migration(Migration::new(2, "add email").change(&platform, [
+//!         Change::add_column("users", Column::new("email", SqlType::Text)),
+//!         Change::add_index("users", Index::new("users_email", vec!["email".into()])),
+//!     ])?);
+//!
+//! let applied = migrator.migrate().await?;
+//!
+//! // Back to version 1, dropping the index and column again
+//! migrator.migrate_down(1).await?;
+//!
+//! // After deliberately editing an applied migration
+//! migrator.repair().await?;
+//! ```
+
+mod change;
+mod migrator;
+
+pub use change::Change;
+pub use migrator::{AppliedMigration, Migration, Migrator, DEFAULT_TABLE};
@@ -0,0 +1,240 @@
+//! Value serialization for drivers without native type bindings
+//!
+//! Drivers bind primitive values (integers, floats, strings, bytes) natively.
+//! Dates, times, UUIDs, JSON and decimals are sent as text when the driver
+//! has no native binding, formatted according to the platform and an
+//! optional [`Configuration`] override.
+
+use alloc::string::String;
+#[cfg(feature = "chrono")]
+use alloc::format;
+#[cfg(any(feature = "uuid", feature = "json", feature = "decimal"))]
+use alloc::string::ToString;
+#[cfg(feature = "uuid")]
+use alloc::vec::Vec;
+use crate::core::SqlValue;
+#[cfg(feature = "std")]
+use crate::core::Configuration;
+
+use super::{Platform, UuidStorage};
+
+/// Formats values as text for drivers that cannot bind them natively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueFormatter {
+    /// `strftime` format for date-time values
+    datetime: String,
+    /// `strftime` format for date values
+    date: String,
+    /// `strftime` format for time values
+    time: String,
+    /// Whether UUIDs are bound as text or bytes
+    uuid_storage: UuidStorage,
+}
+
+impl ValueFormatter {
+    /// Default `strftime` format for date-time values
+    pub const DEFAULT_DATETIME_FORMAT: &'static str = "%Y-%m-%d %H:%M:%S";
+
+    /// Default `strftime` format for date values
+    pub const DEFAULT_DATE_FORMAT: &'static str = "%Y-%m-%d";
+
+    /// Default `strftime` format for time values
+    pub const DEFAULT_TIME_FORMAT: &'static str = "%H:%M:%S";
+
+    /// Create a formatter with explicit `strftime` formats
+    #[must_use]
+    pub fn new(
+        datetime_format: impl Into<String>,
+        date_format: impl Into<String>,
+        time_format: impl Into<String>,
+    ) -> Self {
+        Self {
+            datetime: datetime_format.into(),
+            date: date_format.into(),
+            time: time_format.into(),
+            uuid_storage: UuidStorage::Text,
+        }
+    }
+
+    /// Create a formatter using the platform's date/time formats
+    #[must_use]
+    pub fn for_platform<P: Platform + ?Sized>(platform: &P) -> Self {
+        Self::new(
+            platform.datetime_format(),
+            platform.date_format(),
+            platform.time_format(),
+        )
+        .with_uuid_storage(platform.uuid_storage())
+    }
+
+    /// Set whether UUIDs are bound as text or bytes
+    #[must_use]
+    pub const fn with_uuid_storage(mut self, storage: UuidStorage) -> Self {
+        self.uuid_storage = storage;
+        self
+    }
+
+    /// Get the UUID storage
+    #[must_use]
+    pub const fn uuid_storage(&self) -> UuidStorage {
+        self.uuid_storage
+    }
+
+    /// Apply the format overrides set in the configuration
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn with_configuration(mut self, configuration: &Configuration) -> Self {
+        if let Some(ref format) = configuration.datetime_format {
+            self.datetime.clone_from(format);
+        }
+        if let Some(ref format) = configuration.date_format {
+            self.date.clone_from(format);
+        }
+        if let Some(ref format) = configuration.time_format {
+            self.time.clone_from(format);
+        }
+        self
+    }
+
+    /// Get the date-time format
+    #[must_use]
+    pub fn datetime_format(&self) -> &str {
+        &self.datetime
+    }
+
+    /// Get the date format
+    #[must_use]
+    pub fn date_format(&self) -> &str {
+        &self.date
+    }
+
+    /// Get the time format
+    #[must_use]
+    pub fn time_format(&self) -> &str {
+        &self.time
+    }
+
+    /// Format a value as text, if it has no primitive representation
+    ///
+    /// Returns `None` for NULL, booleans, numbers, strings and bytes, which
+    /// drivers are expected to bind natively, for dates/times whose format
+    /// string is invalid, and for UUIDs stored as bytes (see [`Self::uuid_bytes`]).
+    #[must_use]
+    #[cfg_attr(
+        not(any(feature = "chrono", feature = "uuid", feature = "json", feature = "decimal")),
+        allow(clippy::missing_const_for_fn)
+    )]
+    pub fn format_value(&self, value: &SqlValue) -> Option<String> {
+        match value {
+            #[cfg(feature = "chrono")]
+            SqlValue::Date(v) => render(v.format(&self.date)),
+            #[cfg(feature = "chrono")]
+            SqlValue::Time(v) => render(v.format(&self.time)),
+            #[cfg(feature = "chrono")]
+            SqlValue::DateTime(v) => render(v.format(&self.datetime)),
+            #[cfg(feature = "chrono")]
+            SqlValue::DateTimeUtc(v) => render(v.format(&self.datetime)),
+            // Keep the offset, the configured format has none
+            #[cfg(feature = "chrono")]
+            SqlValue::DateTimeTz(v) => render(v.format(&format!("{}%:z", self.datetime))),
+            #[cfg(feature = "uuid")]
+            SqlValue::Uuid(v) if self.uuid_storage == UuidStorage::Text => Some(v.to_string()),
+            #[cfg(feature = "json")]
+            SqlValue::Json(v) => Some(v.to_string()),
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(v) => Some(v.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Get the bytes of a UUID value, if UUIDs are stored as bytes
+    #[cfg(feature = "uuid")]
+    #[must_use]
+    pub fn uuid_bytes(&self, value: &SqlValue) -> Option<Vec<u8>> {
+        match (value, self.uuid_storage) {
+            (SqlValue::Uuid(v), UuidStorage::Binary) => Some(v.as_bytes().to_vec()),
+            _ => None,
+        }
+    }
+}
+
+/// Render a chrono format item, `None` if the format string is invalid
+#[cfg(feature = "chrono")]
+fn render(item: impl core::fmt::Display) -> Option<String> {
+    use core::fmt::Write;
+    let mut out = String::new();
+    write!(out, "{item}").ok()?;
+    Some(out)
+}
+
+impl Default for ValueFormatter {
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_DATETIME_FORMAT,
+            Self::DEFAULT_DATE_FORMAT,
+            Self::DEFAULT_TIME_FORMAT,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{MySqlPlatform, SqlitePlatform};
+
+    #[test]
+    fn test_primitives_are_not_formatted() {
+        let formatter = ValueFormatter::default();
+        assert_eq!(formatter.format_value(&SqlValue::I64(1)), None);
+        assert_eq!(formatter.format_value(&SqlValue::String("a".to_string())), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_platform_and_configuration_formats() {
+        use chrono::NaiveDate;
+
+        let dt = NaiveDate::from_ymd_opt(2024, 3, 15)
+            .and_then(|d| d.and_hms_micro_opt(10, 30, 0, 250))
+            .map(SqlValue::DateTime)
+            .unwrap();
+
+        let mysql = ValueFormatter::for_platform(&MySqlPlatform::new());
+        assert_eq!(mysql.format_value(&dt).unwrap(), "2024-03-15 10:30:00");
+
+        let sqlite = ValueFormatter::for_platform(&SqlitePlatform::new());
+        assert_eq!(sqlite.format_value(&dt).unwrap(), "2024-03-15 10:30:00.000250");
+
+        #[cfg(feature = "std")]
+        {
+            let config = Configuration::new().with_datetime_format("%d.%m.%Y %H:%M");
+            let custom = ValueFormatter::for_platform(&MySqlPlatform::new()).with_configuration(&config);
+            assert_eq!(custom.format_value(&dt).unwrap(), "15.03.2024 10:30");
+            assert_eq!(custom.date_format(), "%Y-%m-%d");
+        }
+
+        let tz = chrono::DateTime::parse_from_rfc3339("2024-03-15T10:30:00+02:00")
+            .map(SqlValue::DateTimeTz)
+            .unwrap();
+        assert_eq!(mysql.format_value(&tz).unwrap(), "2024-03-15 10:30:00+02:00");
+
+        let invalid = ValueFormatter::new("%Q", "%Y-%m-%d", "%H:%M:%S");
+        assert_eq!(invalid.format_value(&dt), None);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_binary_uuid() {
+        let uuid = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+        let value = SqlValue::Uuid(uuid);
+
+        let text = ValueFormatter::for_platform(&MySqlPlatform::new());
+        assert_eq!(text.format_value(&value), Some(uuid.to_string()));
+        assert_eq!(text.uuid_bytes(&value), None);
+
+        let binary = ValueFormatter::for_platform(&SqlitePlatform::new().with_binary_uuid(true));
+        assert_eq!(binary.uuid_storage(), UuidStorage::Binary);
+        assert_eq!(binary.format_value(&value), None);
+        assert_eq!(binary.uuid_bytes(&value), Some(uuid.as_bytes().to_vec()));
+    }
+}
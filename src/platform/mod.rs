@@ -3,11 +3,17 @@
 //! SQL platform abstractions for generating platform-specific SQL.
 //!
 //! This module provides the `Platform` trait and implementations for
-//! `PostgreSQL`, `MySQL`, and `SQLite`.
+//! `PostgreSQL`, `MySQL`, `SQLite` and generic ANSI SQL.
 
+mod format;
 #[allow(clippy::module_inception)]
 mod platform;
+#[cfg(feature = "std")]
+mod registry;
 mod types;
 
+pub use format::ValueFormatter;
 pub use platform::*;
+#[cfg(feature = "std")]
+pub use registry::PlatformRegistry;
 pub use types::*;
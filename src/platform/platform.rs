@@ -1,14 +1,45 @@
 //! Platform trait for SQL dialect abstraction
 
-use super::types::{Column, Index, SqlType, Table};
+use alloc::{boxed::Box, collections::BTreeMap, string::{String, ToString}, vec, vec::Vec, format};
+use crate::core::{hex_encode, ParameterType, Result, SchemaError, SqlValue};
+
+use super::types::{Column, DefaultValue, ForeignKey, IdentifierCase, Index, Privilege, Schema, SqlType, Table, UuidStorage};
+use super::ValueFormatter;
 
 /// A database platform that generates platform-specific SQL
+///
+/// All methods except [`name`](Self::name) default to standard ANSI SQL (see
+/// [`AnsiPlatform`]), so a custom platform only overrides what differs:
+///
+/// ```
+/// use rustine_dbal::platform::{AnsiPlatform, Platform, SqlType};
+///
+/// struct MyPlatform;
+///
+/// impl Platform for MyPlatform {
+///     fn name(&self) -> &'static str {
+///         "mydb"
+///     }
+///
+///     fn get_type_declaration(&self, sql_type: &SqlType) -> String {
+///         match sql_type {
+///             SqlType::Text => "STRING".to_string(),
+///             _ => AnsiPlatform::new().get_type_declaration(sql_type),
+///         }
+///     }
+/// }
+///
+/// assert_eq!(MyPlatform.quote_identifier("users"), "\"users\"");
+/// assert_eq!(MyPlatform.get_type_declaration(&SqlType::Text), "STRING");
+/// ```
 pub trait Platform: Send + Sync {
     /// Get the name of this platform
     fn name(&self) -> &'static str;
 
     /// Get the identifier quote character
-    fn quote_identifier_char(&self) -> char;
+    fn quote_identifier_char(&self) -> char {
+        '"'
+    }
 
     /// Quote an identifier (table name, column name, etc.)
     fn quote_identifier(&self, identifier: &str) -> String {
@@ -16,14 +47,96 @@ pub trait Platform: Send + Sync {
         format!("{quote}{}{quote}", identifier.replace(quote, &format!("{quote}{quote}")))
     }
 
+    /// Get the maximum length of an identifier in bytes, if limited
+    fn max_identifier_length(&self) -> Option<usize> {
+        None
+    }
+
+    /// Check that a table, column or index name can be quoted safely
+    ///
+    /// Rejects empty names and names containing NUL bytes or the identifier
+    /// quote character (usually a name that was quoted already), and names
+    /// longer than [`max_identifier_length`](Self::max_identifier_length),
+    /// which the database would silently truncate. The name is checked as a
+    /// whole, as [`quote_identifier`](Self::quote_identifier) quotes it: a dot
+    /// doesn't separate a schema, it is part of the name.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchemaError::InvalidDefinition`] describing the problem.
+    fn assert_valid_identifier(&self, identifier: &str) -> Result<()> {
+        let invalid = |problem: &str| {
+            Err(SchemaError::InvalidDefinition(format!("invalid identifier {identifier:?}: {problem}")).into())
+        };
+        if identifier.contains('\0') {
+            return invalid("contains a NUL byte");
+        }
+        if identifier.contains(self.quote_identifier_char()) {
+            return invalid("contains the identifier quote character");
+        }
+        if identifier.is_empty() {
+            return invalid("empty name");
+        }
+        if let Some(max) = self.max_identifier_length().filter(|max| identifier.len() > *max) {
+            return invalid(&format!("longer than {max} bytes"));
+        }
+        Ok(())
+    }
+
+    /// Get how the database folds the case of unquoted identifiers
+    fn identifier_case(&self) -> IdentifierCase {
+        IdentifierCase::PreserveQuoted
+    }
+
     /// Quote a string literal
     fn quote_string(&self, value: &str) -> String {
         format!("'{}'", value.replace('\'', "''"))
     }
 
+    /// Format binary data as a literal
+    fn binary_literal(&self, bytes: &[u8]) -> String {
+        format!("X'{}'", hex_encode(bytes))
+    }
+
+    /// Format a value as a literal for inline SQL (dumps, scripts)
+    ///
+    /// Dates, times, UUIDs, decimals and JSON are rendered as strings in the
+    /// platform formats (see [`ValueFormatter::for_platform`]).
+    fn format_literal(&self, value: &SqlValue) -> String {
+        let formatter = ValueFormatter::for_platform(self);
+        #[cfg(feature = "uuid")]
+        if let Some(bytes) = formatter.uuid_bytes(value) {
+            return self.binary_literal(&bytes);
+        }
+
+        match value {
+            SqlValue::Null => "NULL".to_string(),
+            SqlValue::Bool(true) => "TRUE".to_string(),
+            SqlValue::Bool(false) => "FALSE".to_string(),
+            SqlValue::String(s) => self.quote_string(s),
+            SqlValue::Bytes(b) => self.binary_literal(b),
+            _ => match value.non_finite_float().and_then(|float| self.non_finite_float_sql(float)) {
+                Some(literal) => literal,
+                None => formatter
+                    .format_value(value)
+                    .map_or_else(|| value.to_string(), |text| self.quote_string(&text)),
+            },
+        }
+    }
+
+    /// Check if float columns store NaN and infinity
+    fn supports_non_finite_floats(&self) -> bool {
+        false
+    }
+
+    /// Get the literal of a NaN or infinite float, `None` without support
+    fn non_finite_float_sql(&self, _value: f64) -> Option<String> {
+        None
+    }
+
     /// Get the SQL for LIMIT/OFFSET
     fn limit_offset_sql(&self, limit: Option<u64>, offset: Option<u64>) -> String {
-        use std::fmt::Write;
+        use core::fmt::Write;
         let mut sql = String::new();
         if let Some(limit) = limit {
             let _ = write!(sql, " LIMIT {limit}");
@@ -59,6 +172,239 @@ pub trait Platform: Send + Sync {
         false
     }
 
+    /// Get the maximum number of bound parameters in one statement, if limited
+    ///
+    /// Used by [`Connection::fetch_in_chunks`](crate::connection::Connection::fetch_in_chunks)
+    /// to split large IN lists.
+    fn max_bind_parameters(&self) -> Option<usize> {
+        None
+    }
+
+    /// Check if UPDATE accepts the RETURNING clause, by default if INSERT and DELETE do
+    fn supports_update_returning(&self) -> bool {
+        self.supports_returning()
+    }
+
+    /// Check if joins accept LATERAL subqueries
+    fn supports_lateral_join(&self) -> bool {
+        false
+    }
+
+    /// Get a literal table of rows usable in FROM, including its alias
+    ///
+    /// `rows` holds the rendered values of each row (at least one). Renders
+    /// `(VALUES (...), (...)) AS alias(columns)`.
+    fn values_table_sql(&self, rows: &[Vec<String>], alias: &str, columns: &[String]) -> String {
+        let rows: Vec<String> = rows.iter().map(|row| format!("({})", row.join(", "))).collect();
+        let columns: Vec<String> = columns.iter().map(|column| self.quote_identifier(column)).collect();
+        format!("(VALUES {}) AS {}({})", rows.join(", "), self.quote_identifier(alias), columns.join(", "))
+    }
+
+    /// Generate the statements updating many rows of a table by key
+    ///
+    /// `columns` names the values of each row; the `key_columns` among them
+    /// select the row, the others are written. By default one UPDATE per
+    /// [`UPDATE_BATCH_ROWS`] rows, setting each column with a CASE over the
+    /// keys. No statements for no rows.
+    fn get_update_batch_sql(&self, table: &str, key_columns: &[&str], columns: &[&str], rows: &[Vec<SqlValue>]) -> Vec<String> {
+        use core::fmt::Write;
+        let keys: Vec<usize> = (0..columns.len()).filter(|&i| key_columns.contains(&columns[i])).collect();
+        rows.chunks(UPDATE_BATCH_ROWS)
+            .map(|chunk| {
+                let matches: Vec<String> = chunk
+                    .iter()
+                    .map(|row| {
+                        let conditions: Vec<String> = keys
+                            .iter()
+                            .map(|&i| format!("{} = {}", self.quote_identifier(columns[i]), self.format_literal(&row[i])))
+                            .collect();
+                        conditions.join(" AND ")
+                    })
+                    .collect();
+                let assignments: Vec<String> = (0..columns.len())
+                    .filter(|i| !keys.contains(i))
+                    .map(|i| {
+                        let column = self.quote_identifier(columns[i]);
+                        let mut sql = format!("{column} = CASE");
+                        for (row, condition) in chunk.iter().zip(&matches) {
+                            let _ = write!(sql, " WHEN {condition} THEN {}", self.format_literal(&row[i]));
+                        }
+                        let _ = write!(sql, " ELSE {column} END");
+                        sql
+                    })
+                    .collect();
+                let condition = if let [key] = keys[..] {
+                    let values: Vec<String> = chunk.iter().map(|row| self.format_literal(&row[key])).collect();
+                    format!("{} IN ({})", self.quote_identifier(columns[key]), values.join(", "))
+                } else {
+                    let matches: Vec<String> = matches.iter().map(|condition| format!("({condition})")).collect();
+                    matches.join(" OR ")
+                };
+                format!("UPDATE {} SET {} WHERE {condition}", self.quote_identifier(table), assignments.join(", "))
+            })
+            .collect()
+    }
+
+    /// Get a copy of the platform matching the capabilities of a server version
+    ///
+    /// `version` is the string reported by
+    /// [`DriverConnection::server_version`](crate::driver::DriverConnection::server_version).
+    /// `None` if the capabilities don't depend on the version.
+    fn for_server_version(&self, _version: &str) -> Option<Box<dyn Platform>> {
+        None
+    }
+
+    /// Check if regular expression matches are available
+    fn supports_regexp(&self) -> bool {
+        false
+    }
+
+    /// Render a regular expression match of an expression against a pattern
+    ///
+    /// `expr` is rendered SQL, `pattern` the unquoted regular expression. The
+    /// default is `expr [NOT] REGEXP 'pattern'`, with `(?i)` prepended to the
+    /// pattern for case-insensitive matches.
+    fn regexp_sql(&self, expr: &str, pattern: &str, negated: bool, case_insensitive: bool) -> String {
+        let not = if negated { "NOT " } else { "" };
+        let flags = if case_insensitive { "(?i)" } else { "" };
+        format!("{expr} {not}REGEXP {}", self.quote_string(&format!("{flags}{pattern}")))
+    }
+
+    /// Check if SELECT ... FOR UPDATE accepts SKIP LOCKED
+    fn supports_skip_locked(&self) -> bool {
+        false
+    }
+
+    /// Get the row locking clause of a SELECT, `None` without row locks
+    ///
+    /// With `skip_locked`, rows locked by other transactions are left out
+    /// where supported; other platforms wait for the locks instead.
+    fn for_update_sql(&self, skip_locked: bool) -> Option<&'static str> {
+        if skip_locked && self.supports_skip_locked() {
+            Some("FOR UPDATE SKIP LOCKED")
+        } else {
+            Some("FOR UPDATE")
+        }
+    }
+
+    /// Check if UPDATE/DELETE accept ORDER BY and LIMIT (e.g. `MySQL`)
+    fn supports_update_delete_limit(&self) -> bool {
+        false
+    }
+
+    /// Get the optimizer hint comment placed after the statement keyword
+    ///
+    /// `None` on platforms without inline optimizer hints, where hints are
+    /// dropped (the database would ignore them anyway).
+    fn optimizer_hints_sql(&self, _hints: &[String]) -> Option<String> {
+        None
+    }
+
+    /// Get a comment placed before a statement, e.g. for tracing correlation
+    ///
+    /// A `*/` inside the text is broken up so it cannot end the comment.
+    fn comment_sql(&self, comment: &str) -> String {
+        format!("/* {} */", comment_text(comment))
+    }
+
+    /// Implicit row identifier column (e.g. `ctid`, `rowid`), if any
+    ///
+    /// Used to emulate ORDER BY/LIMIT in UPDATE/DELETE via a subquery.
+    fn row_identifier(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Get the query fetching the next value of a sequence, if supported
+    fn sequence_next_value_sql(&self, _sequence: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the query reading the key generated by the last INSERT, if supported
+    ///
+    /// Only needed on platforms without RETURNING.
+    fn last_insert_id_sql(&self) -> Option<String> {
+        None
+    }
+
+    /// Check if the backend exposes an identifier of the current transaction
+    fn supports_transaction_id(&self) -> bool {
+        self.current_transaction_id_sql().is_some()
+    }
+
+    /// Get the query reading the identifier of the current transaction, if supported
+    ///
+    /// Used for change tracking, e.g. to tag outbox rows with the
+    /// transaction that wrote them.
+    fn current_transaction_id_sql(&self) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to set the session timezone, if the platform has one
+    fn set_time_zone_sql(&self, _time_zone: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to set the default schema of the session, if supported
+    fn set_schema_sql(&self, _schema: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to switch the connection to another database, if supported
+    fn use_database_sql(&self, _database: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to set the client character set, if supported
+    fn set_charset_sql(&self, _charset: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to report the application name to the server, if supported
+    fn set_application_name_sql(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to switch the session's auto-commit mode, if the server has one
+    fn set_auto_commit_sql(&self, _auto_commit: bool) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to set a session variable until the end of the transaction
+    ///
+    /// Used by [`Connection::with_session_context`](crate::connection::Connection::with_session_context),
+    /// e.g. for the settings row-level security policies read.
+    fn set_session_variable_sql(&self, _name: &str, _value: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the query reading a session variable as text, if supported
+    fn get_session_variable_sql(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to change a setting for the rest of the session
+    ///
+    /// Used by [`Connection::set_local`](crate::connection::Connection::set_local)
+    /// on platforms without transaction-local settings, which restores the
+    /// previous value when the transaction ends. A `None` value sets the
+    /// setting to NULL.
+    fn set_session_setting_sql(&self, _name: &str, _value: Option<&str>) -> Option<String> {
+        None
+    }
+
+    /// Get the SQL to reset a session setting to its default
+    ///
+    /// Restores settings [`get_session_setting_sql`](Self::get_session_setting_sql)
+    /// found no value for.
+    fn reset_session_setting_sql(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the query reading a session setting, if supported
+    fn get_session_setting_sql(&self, _name: &str) -> Option<String> {
+        None
+    }
+
     /// Get the SQL for creating a savepoint
     fn create_savepoint_sql(&self, name: &str) -> String {
         format!("SAVEPOINT {}", self.quote_identifier(name))
@@ -75,7 +421,23 @@ pub trait Platform: Send + Sync {
     }
 
     /// Get the parameter placeholder style
-    fn parameter_placeholder(&self, index: usize) -> String;
+    fn parameter_placeholder(&self, _index: usize) -> String {
+        "?".to_string()
+    }
+
+    /// Get the type a parameter must be cast to, if the database can't infer it
+    fn parameter_cast_type(&self, _param_type: ParameterType) -> Option<&'static str> {
+        None
+    }
+
+    /// Get the placeholder for a typed parameter, with an explicit cast if needed
+    fn typed_parameter_placeholder(&self, index: usize, param_type: ParameterType) -> String {
+        let placeholder = self.parameter_placeholder(index);
+        match self.parameter_cast_type(param_type) {
+            Some(cast) => format!("CAST({placeholder} AS {cast})"),
+            None => placeholder,
+        }
+    }
 
     /// Get the current timestamp function
     fn current_timestamp_sql(&self) -> &'static str {
@@ -97,14 +459,153 @@ pub trait Platform: Send + Sync {
     // ========================================================================
 
     /// Get the SQL type name for a given `SqlType`
-    fn get_type_declaration(&self, sql_type: &SqlType) -> String;
+    fn get_type_declaration(&self, sql_type: &SqlType) -> String {
+        match sql_type {
+            SqlType::SmallInt => "SMALLINT".to_string(),
+            SqlType::Integer => "INTEGER".to_string(),
+            SqlType::BigInt => "BIGINT".to_string(),
+            SqlType::Float => "REAL".to_string(),
+            SqlType::Double => "DOUBLE PRECISION".to_string(),
+            SqlType::Decimal { precision, scale } => format!("DECIMAL({precision}, {scale})"),
+            SqlType::Char { length } => format!("CHAR({length})"),
+            SqlType::Varchar { length } => format!("VARCHAR({length})"),
+            SqlType::Text | SqlType::Json => "CLOB".to_string(),
+            SqlType::Binary { length } => format!("BINARY({length})"),
+            SqlType::VarBinary { length } => format!("VARBINARY({length})"),
+            SqlType::Blob => "BLOB".to_string(),
+            SqlType::Boolean => "BOOLEAN".to_string(),
+            SqlType::Date => "DATE".to_string(),
+            SqlType::Time { precision } => match precision {
+                Some(p) => format!("TIME({p})"),
+                None => "TIME".to_string(),
+            },
+            SqlType::Timestamp { precision } => match precision {
+                Some(p) => format!("TIMESTAMP({p})"),
+                None => "TIMESTAMP".to_string(),
+            },
+            SqlType::TimestampTz { precision } => match precision {
+                Some(p) => format!("TIMESTAMP({p}) WITH TIME ZONE"),
+                None => "TIMESTAMP WITH TIME ZONE".to_string(),
+            },
+            SqlType::Uuid => "CHAR(36)".to_string(),
+            SqlType::Serial => "INTEGER GENERATED BY DEFAULT AS IDENTITY".to_string(),
+            SqlType::BigSerial => "BIGINT GENERATED BY DEFAULT AS IDENTITY".to_string(),
+        }
+    }
+
+    /// Parse a type declaration reported by introspection back into a `SqlType`
+    fn parse_type_declaration(&self, declaration: &str) -> Option<SqlType> {
+        SqlType::from_declaration(declaration)
+    }
+
+    /// Parse a column default reported by introspection into a `DefaultValue`
+    ///
+    /// Returns `None` if the column has no default.
+    fn normalize_default(&self, default: &str, sql_type: &SqlType) -> Option<DefaultValue> {
+        DefaultValue::parse(default, sql_type)
+    }
+
+    /// Get the SQL for a column default value
+    fn get_default_value_sql(&self, default: &DefaultValue) -> String {
+        match default {
+            DefaultValue::Value(value) => self.format_literal(value),
+            DefaultValue::Expression(sql) => sql.clone(),
+            DefaultValue::CurrentTimestamp => "CURRENT_TIMESTAMP".to_string(),
+            DefaultValue::Null => "NULL".to_string(),
+        }
+    }
+
+    /// Get the default UUID storage of this platform
+    fn uuid_storage(&self) -> UuidStorage {
+        UuidStorage::Text
+    }
+
+    /// Get the UUID type declaration for the given storage
+    ///
+    /// Platforms with a native UUID type ignore the storage.
+    fn get_uuid_declaration(&self, _storage: UuidStorage) -> String {
+        self.get_type_declaration(&SqlType::Uuid)
+    }
+
+    /// Get the SQL type name for a column, honoring its UUID storage and
+    /// compression
+    fn get_column_type_declaration(&self, column: &Column) -> String {
+        if column.compression.is_some() {
+            return self.get_type_declaration(&SqlType::Blob);
+        }
+        if let Some(declaration) = self.get_enum_declaration(column) {
+            return declaration;
+        }
+        match column.sql_type {
+            SqlType::Uuid => self.get_uuid_declaration(
+                column.uuid_storage.unwrap_or_else(|| self.uuid_storage()),
+            ),
+            _ => self.get_type_declaration(&column.sql_type),
+        }
+    }
+
+    /// Get the native type of a column restricted to a set of values,
+    /// `None` if the values are checked with a CHECK constraint instead
+    fn get_enum_declaration(&self, _column: &Column) -> Option<String> {
+        None
+    }
+
+    /// Get the CHECK constraint restricting a column to its allowed values
+    ///
+    /// `None` for columns without allowed values or with a native enum type.
+    /// Values of numeric columns are written as numbers.
+    fn get_enum_check_sql(&self, column: &Column) -> Option<String> {
+        if column.enum_values.is_empty() || self.get_enum_declaration(column).is_some() {
+            return None;
+        }
+        let values: Vec<String> = column
+            .enum_values
+            .iter()
+            .map(|value| {
+                if column.sql_type.is_numeric() && value.parse::<f64>().is_ok_and(f64::is_finite) {
+                    value.clone()
+                } else {
+                    self.quote_string(value)
+                }
+            })
+            .collect();
+        Some(format!("CHECK ({} IN ({}))", self.quote_identifier(&column.name), values.join(", ")))
+    }
+
+    /// Generate SQL creating a named enum type, `None` if unsupported
+    fn get_create_enum_type_sql(&self, _name: &str, _values: &[String]) -> Option<String> {
+        None
+    }
+
+    /// Generate SQL adding a value to a named enum type, `None` if unsupported
+    fn get_add_enum_value_sql(&self, _name: &str, _value: &str) -> Option<String> {
+        None
+    }
+
+    /// Generate SQL dropping a named enum type, `None` if unsupported
+    fn get_drop_enum_type_sql(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Get the clauses for the platform options of a column
+    ///
+    /// The clauses are appended after the default value. Options this
+    /// platform doesn't know are ignored.
+    fn get_column_options_sql(&self, _column: &Column) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Parse column attributes reported by introspection into platform options
+    fn parse_column_options(&self, _extra: &str) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
 
     /// Get the SQL for a column definition
     fn get_column_declaration(&self, column: &Column) -> String {
         let mut sql = format!(
             "{} {}",
             self.quote_identifier(&column.name),
-            self.get_type_declaration(&column.sql_type)
+            self.get_column_type_declaration(column)
         );
 
         if !column.nullable {
@@ -113,7 +614,17 @@ pub trait Platform: Send + Sync {
 
         if let Some(ref default) = column.default {
             sql.push_str(" DEFAULT ");
-            sql.push_str(default);
+            sql.push_str(&self.get_default_value_sql(default));
+        }
+
+        for clause in self.get_column_options_sql(column) {
+            sql.push(' ');
+            sql.push_str(&clause);
+        }
+
+        if let Some(check) = self.get_enum_check_sql(column) {
+            sql.push(' ');
+            sql.push_str(&check);
         }
 
         sql
@@ -125,7 +636,7 @@ pub trait Platform: Send + Sync {
 
     /// Generate CREATE TABLE SQL
     fn get_create_table_sql(&self, table: &Table) -> String {
-        use std::fmt::Write;
+        use core::fmt::Write;
         let mut sql = format!("CREATE TABLE {} (\n", self.quote_identifier(&table.name));
 
         // Columns
@@ -168,38 +679,113 @@ pub trait Platform: Send + Sync {
 
         // Foreign keys
         for fk in &table.foreign_keys {
-            let local_cols: Vec<String> = fk
-                .local_columns
-                .iter()
-                .map(|c| self.quote_identifier(c))
-                .collect();
-            let foreign_cols: Vec<String> = fk
-                .foreign_columns
-                .iter()
-                .map(|c| self.quote_identifier(c))
-                .collect();
-
-            let _ = write!(
-                sql,
-                ",\n    CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
-                self.quote_identifier(&fk.name),
-                local_cols.join(", "),
-                self.quote_identifier(&fk.foreign_table),
-                foreign_cols.join(", ")
-            );
-
-            if fk.on_delete != super::types::ForeignKeyAction::NoAction {
-                let _ = write!(sql, " ON DELETE {}", fk.on_delete.as_sql());
-            }
-            if fk.on_update != super::types::ForeignKeyAction::NoAction {
-                let _ = write!(sql, " ON UPDATE {}", fk.on_update.as_sql());
-            }
+            sql.push_str(",\n    ");
+            sql.push_str(&self.get_foreign_key_declaration_sql(fk));
         }
 
         sql.push_str("\n)");
         sql
     }
 
+    /// Generate the declaration of a foreign key, as used in CREATE TABLE and ALTER TABLE
+    fn get_foreign_key_declaration_sql(&self, fk: &ForeignKey) -> String {
+        use core::fmt::Write;
+        let local_cols: Vec<String> = fk.local_columns.iter().map(|c| self.quote_identifier(c)).collect();
+        let foreign_cols: Vec<String> = fk.foreign_columns.iter().map(|c| self.quote_identifier(c)).collect();
+
+        let mut sql = String::new();
+        if !fk.name.is_empty() {
+            let _ = write!(sql, "CONSTRAINT {} ", self.quote_identifier(&fk.name));
+        }
+        let _ = write!(
+            sql,
+            "FOREIGN KEY ({}) REFERENCES {} ({})",
+            local_cols.join(", "),
+            self.quote_identifier(&fk.foreign_table),
+            foreign_cols.join(", ")
+        );
+        if fk.on_delete != super::types::ForeignKeyAction::NoAction {
+            let _ = write!(sql, " ON DELETE {}", fk.on_delete.as_sql());
+        }
+        if fk.on_update != super::types::ForeignKeyAction::NoAction {
+            let _ = write!(sql, " ON UPDATE {}", fk.on_update.as_sql());
+        }
+        sql
+    }
+
+    /// Generate SQL adding a foreign key to an existing table, `None` if unsupported
+    fn get_create_foreign_key_sql(&self, table_name: &str, fk: &ForeignKey) -> Option<String> {
+        Some(format!(
+            "ALTER TABLE {} ADD {}",
+            self.quote_identifier(table_name),
+            self.get_foreign_key_declaration_sql(fk)
+        ))
+    }
+
+    /// Generate SQL dropping a named foreign key, `None` if unsupported
+    fn get_drop_foreign_key_sql(&self, table_name: &str, fk_name: &str) -> Option<String> {
+        Some(format!(
+            "ALTER TABLE {} DROP CONSTRAINT {}",
+            self.quote_identifier(table_name),
+            self.quote_identifier(fk_name)
+        ))
+    }
+
+    /// Generate the statements creating all tables of a schema
+    ///
+    /// Tables are created in [`Schema::creation_order`], each followed by its
+    /// non-unique indexes. Foreign keys closing a reference cycle are added
+    /// with ALTER TABLE once all tables exist; platforms that can't add
+    /// foreign keys later (`SQLite`) keep them in CREATE TABLE.
+    fn get_create_schema_sql(&self, schema: &Schema) -> Vec<String> {
+        let order = schema.creation_order();
+        let mut statements: Vec<String> = schema
+            .enum_types()
+            .into_iter()
+            .filter_map(|(name, values)| self.get_create_enum_type_sql(name, values))
+            .collect();
+        let mut deferred_sql = Vec::new();
+
+        for table in order.tables {
+            let mut created = table.clone();
+            for (table_name, fk) in &order.deferred_foreign_keys {
+                if *table_name == table.name {
+                    if let Some(sql) = self.get_create_foreign_key_sql(table_name, fk) {
+                        created.foreign_keys.retain(|other| other != *fk);
+                        deferred_sql.push(sql);
+                    }
+                }
+            }
+            statements.push(self.get_create_table_sql(&created));
+            statements.extend(
+                table
+                    .indexes
+                    .iter()
+                    .filter(|index| !index.primary && !index.unique)
+                    .map(|index| self.get_create_index_sql(&table.name, index)),
+            );
+        }
+        statements.extend(deferred_sql);
+        statements
+    }
+
+    /// Generate the statements dropping all tables of a schema
+    ///
+    /// Foreign keys closing a reference cycle are dropped first (they need
+    /// a name), then the tables in reverse [`Schema::creation_order`].
+    fn get_drop_schema_sql(&self, schema: &Schema) -> Vec<String> {
+        let order = schema.creation_order();
+        let mut statements: Vec<String> = order
+            .deferred_foreign_keys
+            .iter()
+            .filter(|(_, fk)| !fk.name.is_empty())
+            .filter_map(|(table_name, fk)| self.get_drop_foreign_key_sql(table_name, &fk.name))
+            .collect();
+        statements.extend(order.tables.iter().rev().map(|table| self.get_drop_table_sql(&table.name)));
+        statements.extend(schema.enum_types().into_keys().filter_map(|name| self.get_drop_enum_type_sql(name)));
+        statements
+    }
+
     /// Generate DROP TABLE SQL
     fn get_drop_table_sql(&self, table_name: &str) -> String {
         format!("DROP TABLE {}", self.quote_identifier(table_name))
@@ -210,6 +796,34 @@ pub trait Platform: Send + Sync {
         format!("DROP TABLE IF EXISTS {}", self.quote_identifier(table_name))
     }
 
+    /// Generate ALTER TABLE ... ADD COLUMN SQL
+    fn get_add_column_sql(&self, table_name: &str, column: &Column) -> String {
+        format!(
+            "ALTER TABLE {} ADD COLUMN {}",
+            self.quote_identifier(table_name),
+            self.get_column_declaration(column)
+        )
+    }
+
+    /// Generate ALTER TABLE ... DROP COLUMN SQL
+    fn get_drop_column_sql(&self, table_name: &str, column_name: &str) -> String {
+        format!(
+            "ALTER TABLE {} DROP COLUMN {}",
+            self.quote_identifier(table_name),
+            self.quote_identifier(column_name)
+        )
+    }
+
+    /// Generate ALTER TABLE ... RENAME COLUMN SQL
+    fn get_rename_column_sql(&self, table_name: &str, from: &str, to: &str) -> String {
+        format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {}",
+            self.quote_identifier(table_name),
+            self.quote_identifier(from),
+            self.quote_identifier(to)
+        )
+    }
+
     /// Generate CREATE INDEX SQL
     fn get_create_index_sql(&self, table_name: &str, index: &Index) -> String {
         let col_names: Vec<String> = index
@@ -239,23 +853,101 @@ pub trait Platform: Send + Sync {
     // ========================================================================
 
     /// Get SQL to list all tables in the database
-    fn get_list_tables_sql(&self) -> &'static str;
+    fn get_list_tables_sql(&self) -> &'static str {
+        "SELECT table_name FROM information_schema.tables WHERE table_type = 'BASE TABLE'"
+    }
 
     /// Get SQL to list columns of a table
-    fn get_list_columns_sql(&self, table_name: &str) -> String;
+    fn get_list_columns_sql(&self, table_name: &str) -> String {
+        let table_name = self.quote_string(table_name);
+        format!(
+            "SELECT column_name, data_type, is_nullable, column_default, character_maximum_length, numeric_precision, numeric_scale \
+             FROM information_schema.columns WHERE table_name = {table_name} ORDER BY ordinal_position"
+        )
+    }
 
     /// Get SQL to list indexes of a table
-    fn get_list_indexes_sql(&self, table_name: &str) -> String;
+    fn get_list_indexes_sql(&self, table_name: &str) -> String {
+        // Plain indexes are not part of the standard, only key constraints are listed
+        let table_name = self.quote_string(table_name);
+        format!(
+            "SELECT tc.constraint_name AS index_name, kcu.column_name, 1 AS is_unique, \
+             CASE WHEN tc.constraint_type = 'PRIMARY KEY' THEN 1 ELSE 0 END AS is_primary \
+             FROM information_schema.table_constraints AS tc \
+             JOIN information_schema.key_column_usage AS kcu ON tc.constraint_name = kcu.constraint_name \
+             WHERE tc.constraint_type IN ('PRIMARY KEY', 'UNIQUE') AND tc.table_name = {table_name} \
+             ORDER BY kcu.ordinal_position"
+        )
+    }
 
     /// Get SQL to list foreign keys of a table
-    fn get_list_foreign_keys_sql(&self, table_name: &str) -> String;
-}
+    fn get_list_foreign_keys_sql(&self, table_name: &str) -> String {
+        let table_name = self.quote_string(table_name);
+        format!(
+            "SELECT tc.constraint_name, kcu.column_name, ccu.table_name AS foreign_table_name, ccu.column_name AS foreign_column_name \
+             FROM information_schema.table_constraints AS tc \
+             JOIN information_schema.key_column_usage AS kcu ON tc.constraint_name = kcu.constraint_name \
+             JOIN information_schema.constraint_column_usage AS ccu ON ccu.constraint_name = tc.constraint_name \
+             WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_name = {table_name}"
+        )
+    }
 
-/// `PostgreSQL` platform
-#[derive(Debug, Default)]
-pub struct PostgresPlatform;
+    /// Get SQL reading the row count and on-disk size of a table
+    ///
+    /// The query returns one row with `row_count` and `size_bytes`, either
+    /// of which may be NULL, and no row for unknown tables. `None` if the
+    /// platform keeps no such statistics.
+    fn get_table_statistics_sql(&self, _table_name: &str) -> Option<String> {
+        None
+    }
 
-impl Platform for PostgresPlatform {
+    /// Get SQL listing the users (login roles) of the database server
+    ///
+    /// The query returns the names in its first column. `None` if the
+    /// platform has no users, like `SQLite`.
+    fn get_list_users_sql(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Get SQL creating a user that logs in with a password
+    ///
+    /// The password is inlined; connections redact it from query logs and
+    /// errors (see [`ValueRedactionPolicy::redacts_sql`]).
+    ///
+    /// [`ValueRedactionPolicy::redacts_sql`]: crate::core::ValueRedactionPolicy::redacts_sql
+    fn get_create_user_sql(&self, _name: &str, _password: &str) -> Option<String> {
+        None
+    }
+
+    /// Get SQL dropping a user
+    fn get_drop_user_sql(&self, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Get SQL granting privileges on a table to a user or role
+    fn get_grant_sql(&self, _privileges: &[Privilege], _table_name: &str, _role: &str) -> Option<String> {
+        None
+    }
+
+    /// Get SQL revoking privileges on a table from a user or role
+    fn get_revoke_sql(&self, _privileges: &[Privilege], _table_name: &str, _role: &str) -> Option<String> {
+        None
+    }
+
+    /// Get SQL listing the privileges granted on a table
+    ///
+    /// The query returns `grantee` and `privilege_type` columns, one row per
+    /// grantee and privilege.
+    fn get_list_grants_sql(&self, _table_name: &str) -> Option<String> {
+        None
+    }
+}
+
+/// `PostgreSQL` platform
+#[derive(Debug, Default)]
+pub struct PostgresPlatform;
+
+impl Platform for PostgresPlatform {
     fn name(&self) -> &'static str {
         "postgresql"
     }
@@ -264,14 +956,131 @@ impl Platform for PostgresPlatform {
         '"'
     }
 
+    // NAMEDATALEN - 1
+    fn max_identifier_length(&self) -> Option<usize> {
+        Some(63)
+    }
+
+    fn identifier_case(&self) -> IdentifierCase {
+        IdentifierCase::LowerUnquoted
+    }
+
     fn supports_returning(&self) -> bool {
         true
     }
 
+    // The wire protocol counts parameters in 16 bits
+    fn max_bind_parameters(&self) -> Option<usize> {
+        Some(65_535)
+    }
+
+    fn supports_regexp(&self) -> bool {
+        true
+    }
+
+    fn supports_skip_locked(&self) -> bool {
+        true
+    }
+
+    fn supports_lateral_join(&self) -> bool {
+        true
+    }
+
+    fn supports_non_finite_floats(&self) -> bool {
+        true
+    }
+
+    fn non_finite_float_sql(&self, value: f64) -> Option<String> {
+        let text = if value.is_nan() {
+            "NaN"
+        } else if value.is_sign_positive() {
+            "Infinity"
+        } else {
+            "-Infinity"
+        };
+        Some(format!("CAST('{text}' AS DOUBLE PRECISION)"))
+    }
+
+    fn regexp_sql(&self, expr: &str, pattern: &str, negated: bool, case_insensitive: bool) -> String {
+        let not = if negated { "!" } else { "" };
+        let flags = if case_insensitive { "*" } else { "" };
+        format!("{expr} {not}~{flags} {}", self.quote_string(pattern))
+    }
+
+    fn row_identifier(&self) -> Option<&'static str> {
+        Some("ctid")
+    }
+
+    fn sequence_next_value_sql(&self, sequence: &str) -> Option<String> {
+        Some(format!("SELECT nextval({})", self.quote_string(&self.quote_identifier(sequence))))
+    }
+
+    fn current_transaction_id_sql(&self) -> Option<String> {
+        Some("SELECT txid_current()".to_string())
+    }
+
+    fn set_time_zone_sql(&self, time_zone: &str) -> Option<String> {
+        Some(format!("SET TIME ZONE {}", self.quote_string(time_zone)))
+    }
+
+    fn set_schema_sql(&self, schema: &str) -> Option<String> {
+        Some(format!("SET search_path TO {}", self.quote_identifier(schema)))
+    }
+
+    // MySQL's UTF-8 names, like the default `utf8mb4`, are UTF8 here
+    fn set_charset_sql(&self, charset: &str) -> Option<String> {
+        let charset = if charset.eq_ignore_ascii_case("utf8mb4") || charset.eq_ignore_ascii_case("utf8mb3") {
+            "UTF8"
+        } else {
+            charset
+        };
+        Some(format!("SET client_encoding TO {}", self.quote_string(charset)))
+    }
+
+    fn set_application_name_sql(&self, name: &str) -> Option<String> {
+        Some(format!("SET application_name TO {}", self.quote_string(name)))
+    }
+
+    // `set_config(..., true)` is `SET LOCAL` with the name and value as literals
+    fn set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        Some(format!(
+            "SELECT set_config({}, {}, true)",
+            self.quote_string(name),
+            self.quote_string(value)
+        ))
+    }
+
+    fn get_session_variable_sql(&self, name: &str) -> Option<String> {
+        Some(format!("SELECT current_setting({}, true)", self.quote_string(name)))
+    }
+
+    fn binary_literal(&self, bytes: &[u8]) -> String {
+        format!("'\\x{}'::bytea", hex_encode(bytes))
+    }
+
     fn parameter_placeholder(&self, index: usize) -> String {
         format!("${}", index + 1)
     }
 
+    fn parameter_cast_type(&self, param_type: ParameterType) -> Option<&'static str> {
+        // Text parameters are not implicitly converted to these types
+        match param_type {
+            ParameterType::Uuid => Some("uuid"),
+            ParameterType::Json => Some("jsonb"),
+            ParameterType::Decimal => Some("numeric"),
+            ParameterType::Binary | ParameterType::LargeObject => Some("bytea"),
+            _ => None,
+        }
+    }
+
+    fn typed_parameter_placeholder(&self, index: usize, param_type: ParameterType) -> String {
+        let placeholder = self.parameter_placeholder(index);
+        match self.parameter_cast_type(param_type) {
+            Some(cast) => format!("{placeholder}::{cast}"),
+            None => placeholder,
+        }
+    }
+
     fn get_type_declaration(&self, sql_type: &SqlType) -> String {
         match sql_type {
             SqlType::SmallInt => "SMALLINT".to_string(),
@@ -307,40 +1116,205 @@ impl Platform for PostgresPlatform {
         }
     }
 
+    fn get_update_batch_sql(&self, table: &str, key_columns: &[&str], columns: &[&str], rows: &[Vec<SqlValue>]) -> Vec<String> {
+        if rows.is_empty() {
+            return Vec::new();
+        }
+        let mut values: Vec<Vec<String>> =
+            rows.iter().map(|row| row.iter().map(|value| self.format_literal(value)).collect()).collect();
+        // VALUES columns of quoted literals are text, which doesn't compare
+        // with or assign to e.g. uuid columns
+        for (i, literal) in values[0].iter_mut().enumerate() {
+            if let Some(cast) = rows.iter().map(|row| &row[i]).find(|value| !value.is_null()).and_then(postgres_literal_type) {
+                *literal = format!("CAST({literal} AS {cast})");
+            }
+        }
+        let table = self.quote_identifier(table);
+        let alias = self.quote_identifier("batch_values");
+        let assignments: Vec<String> = columns
+            .iter()
+            .filter(|column| !key_columns.contains(column))
+            .map(|column| {
+                let column = self.quote_identifier(column);
+                format!("{column} = {alias}.{column}")
+            })
+            .collect();
+        let conditions: Vec<String> = key_columns
+            .iter()
+            .map(|column| {
+                let column = self.quote_identifier(column);
+                format!("{table}.{column} = {alias}.{column}")
+            })
+            .collect();
+        let names: Vec<String> = columns.iter().map(ToString::to_string).collect();
+        vec![format!(
+            "UPDATE {table} SET {} FROM {} WHERE {}",
+            assignments.join(", "),
+            self.values_table_sql(&values, "batch_values", &names),
+            conditions.join(" AND ")
+        )]
+    }
+
+    fn get_enum_declaration(&self, column: &Column) -> Option<String> {
+        let name = column.enum_type.as_deref().filter(|_| !column.enum_values.is_empty())?;
+        Some(self.quote_identifier(name))
+    }
+
+    fn get_create_enum_type_sql(&self, name: &str, values: &[String]) -> Option<String> {
+        let values: Vec<String> = values.iter().map(|value| self.quote_string(value)).collect();
+        Some(format!("CREATE TYPE {} AS ENUM ({})", self.quote_identifier(name), values.join(", ")))
+    }
+
+    fn get_add_enum_value_sql(&self, name: &str, value: &str) -> Option<String> {
+        Some(format!("ALTER TYPE {} ADD VALUE IF NOT EXISTS {}", self.quote_identifier(name), self.quote_string(value)))
+    }
+
+    fn get_drop_enum_type_sql(&self, name: &str) -> Option<String> {
+        Some(format!("DROP TYPE {}", self.quote_identifier(name)))
+    }
+
     fn get_list_tables_sql(&self) -> &'static str {
         "SELECT table_name FROM information_schema.tables WHERE table_schema = 'public' AND table_type = 'BASE TABLE'"
     }
 
     fn get_list_columns_sql(&self, table_name: &str) -> String {
+        let table_name = self.quote_string(table_name);
+        // Enum labels in MySQL's column_type format
         format!(
-            "SELECT column_name, data_type, is_nullable, column_default, character_maximum_length, numeric_precision, numeric_scale \
-             FROM information_schema.columns WHERE table_schema = 'public' AND table_name = '{table_name}' ORDER BY ordinal_position"
+            "SELECT column_name, data_type, is_nullable, column_default, character_maximum_length, numeric_precision, numeric_scale, NULL AS extra, \
+             (SELECT 'enum(' || string_agg(quote_literal(e.enumlabel), ',' ORDER BY e.enumsortorder) || ')' \
+             FROM pg_enum e JOIN pg_type t ON t.oid = e.enumtypid WHERE t.typname = udt_name) AS column_type \
+             FROM information_schema.columns WHERE table_schema = 'public' AND table_name = {table_name} ORDER BY ordinal_position"
         )
     }
 
     fn get_list_indexes_sql(&self, table_name: &str) -> String {
+        let table_name = self.quote_string(table_name);
         format!(
             "SELECT i.relname AS index_name, a.attname AS column_name, ix.indisunique AS is_unique, ix.indisprimary AS is_primary \
              FROM pg_class t, pg_class i, pg_index ix, pg_attribute a \
              WHERE t.oid = ix.indrelid AND i.oid = ix.indexrelid AND a.attrelid = t.oid AND a.attnum = ANY(ix.indkey) \
-             AND t.relkind = 'r' AND t.relname = '{table_name}'"
+             AND t.relkind = 'r' AND t.relname = {table_name}"
         )
     }
 
     fn get_list_foreign_keys_sql(&self, table_name: &str) -> String {
+        let table_name = self.quote_string(table_name);
         format!(
             "SELECT tc.constraint_name, kcu.column_name, ccu.table_name AS foreign_table_name, ccu.column_name AS foreign_column_name \
              FROM information_schema.table_constraints AS tc \
              JOIN information_schema.key_column_usage AS kcu ON tc.constraint_name = kcu.constraint_name \
              JOIN information_schema.constraint_column_usage AS ccu ON ccu.constraint_name = tc.constraint_name \
-             WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_name = '{table_name}'"
+             WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_name = {table_name}"
         )
     }
+
+    // reltuples is the planner's estimate, -1 before the first ANALYZE
+    fn get_table_statistics_sql(&self, table_name: &str) -> Option<String> {
+        let table_name = self.quote_string(table_name);
+        Some(format!(
+            "SELECT CAST(c.reltuples AS BIGINT) AS row_count, pg_total_relation_size(c.oid) AS size_bytes \
+             FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = 'public' AND c.relkind = 'r' AND c.relname = {table_name}"
+        ))
+    }
+
+    fn get_list_users_sql(&self) -> Option<&'static str> {
+        Some("SELECT rolname FROM pg_roles WHERE rolcanlogin ORDER BY rolname")
+    }
+
+    fn get_create_user_sql(&self, name: &str, password: &str) -> Option<String> {
+        Some(format!("CREATE ROLE {} LOGIN PASSWORD {}", self.quote_identifier(name), self.quote_string(password)))
+    }
+
+    fn get_drop_user_sql(&self, name: &str) -> Option<String> {
+        Some(format!("DROP ROLE {}", self.quote_identifier(name)))
+    }
+
+    fn get_grant_sql(&self, privileges: &[Privilege], table_name: &str, role: &str) -> Option<String> {
+        let (privileges, table_name) = (privilege_list(privileges), self.quote_identifier(table_name));
+        Some(format!("GRANT {privileges} ON {table_name} TO {}", self.quote_identifier(role)))
+    }
+
+    fn get_revoke_sql(&self, privileges: &[Privilege], table_name: &str, role: &str) -> Option<String> {
+        let (privileges, table_name) = (privilege_list(privileges), self.quote_identifier(table_name));
+        Some(format!("REVOKE {privileges} ON {table_name} FROM {}", self.quote_identifier(role)))
+    }
+
+    fn get_list_grants_sql(&self, table_name: &str) -> Option<String> {
+        Some(format!(
+            "SELECT grantee, privilege_type FROM information_schema.role_table_grants \
+             WHERE table_schema = 'public' AND table_name = {} ORDER BY grantee, privilege_type",
+            self.quote_string(table_name)
+        ))
+    }
 }
 
-/// `MySQL` platform
-#[derive(Debug, Default)]
-pub struct MySqlPlatform;
+/// `MySQL` and `MariaDB` platform
+///
+/// UUIDs are stored as `CHAR(36)` by default. With binary UUIDs enabled they
+/// are stored as `BINARY(16)`, which introspection maps back to `SqlType::Uuid`.
+///
+/// `MariaDB` 10.5+ accepts RETURNING on INSERT and DELETE; declare the server
+/// version with [`with_server_version`](Self::with_server_version) to use it.
+/// LATERAL joins need `MySQL` 8.0.14+ and are rejected for `MariaDB`.
+///
+/// Recognized [column platform options](Column::platform_option):
+///
+/// | Option      | Renders                    | Introspected |
+/// |-------------|----------------------------|--------------|
+/// | `on_update` | `ON UPDATE <expression>`   | yes          |
+/// | `srid`      | `SRID <id>` after the type | yes          |
+/// | `invisible` | `INVISIBLE` when `"true"`  | yes          |
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MySqlPlatform {
+    /// Store UUIDs as `BINARY(16)` instead of `CHAR(36)`
+    binary_uuid: bool,
+    /// Server accepts RETURNING on INSERT and DELETE (`MariaDB` 10.5+)
+    returning: bool,
+    /// Server is `MariaDB`, which lacks LATERAL joins
+    mariadb: bool,
+}
+
+impl MySqlPlatform {
+    /// Create a new `MySQL` platform
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { binary_uuid: false, returning: false, mariadb: false }
+    }
+
+    /// Store UUIDs as `BINARY(16)` instead of `CHAR(36)`
+    #[must_use]
+    pub const fn with_binary_uuid(mut self, binary: bool) -> Self {
+        self.binary_uuid = binary;
+        self
+    }
+
+    /// Set the capabilities for a server version string
+    ///
+    /// Accepts `MySQL` (`8.0.36`) and `MariaDB` (`10.6.12-MariaDB`, or
+    /// `5.5.5-10.6.12-MariaDB` as reported over the `MySQL` protocol)
+    /// versions; unknown versions get the `MySQL` capabilities.
+    #[must_use]
+    pub fn with_server_version(mut self, version: &str) -> Self {
+        let mariadb = mariadb_version(version);
+        self.returning = mariadb.is_some_and(|version| version >= (10, 5));
+        self.mariadb = mariadb.is_some();
+        self
+    }
+}
+
+/// Get the major and minor version of a `MariaDB` server version string
+fn mariadb_version(version: &str) -> Option<(u32, u32)> {
+    if !version.to_ascii_lowercase().contains("mariadb") {
+        return None;
+    }
+    let version = version.strip_prefix("5.5.5-").unwrap_or(version);
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
 
 impl Platform for MySqlPlatform {
     fn name(&self) -> &'static str {
@@ -351,6 +1325,124 @@ impl Platform for MySqlPlatform {
         '`'
     }
 
+    fn max_identifier_length(&self) -> Option<usize> {
+        Some(64)
+    }
+
+    // Backslashes escape in MySQL string literals (unless NO_BACKSLASH_ESCAPES is set),
+    // so they are doubled; this also keeps patterns like `\d` intact
+    fn quote_string(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    }
+
+    fn supports_update_delete_limit(&self) -> bool {
+        true
+    }
+
+    fn max_bind_parameters(&self) -> Option<usize> {
+        Some(65_535)
+    }
+
+    fn supports_skip_locked(&self) -> bool {
+        true
+    }
+
+    // MySQL 8.0.14+
+    fn supports_lateral_join(&self) -> bool {
+        !self.mariadb
+    }
+
+    fn optimizer_hints_sql(&self, hints: &[String]) -> Option<String> {
+        let hints: Vec<String> = hints.iter().map(|hint| comment_text(hint)).collect();
+        Some(format!("/*+ {} */", hints.join(" ")))
+    }
+
+    fn supports_regexp(&self) -> bool {
+        true
+    }
+
+    fn supports_returning(&self) -> bool {
+        self.returning
+    }
+
+    fn supports_update_returning(&self) -> bool {
+        false
+    }
+
+    // Derived tables from VALUES ROW() can't name their columns before 8.0.19
+    fn values_table_sql(&self, rows: &[Vec<String>], alias: &str, columns: &[String]) -> String {
+        let selects: Vec<String> = rows.iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let values: Vec<String> = row.iter()
+                    .zip(columns)
+                    .map(|(value, column)| {
+                        if i == 0 { format!("{value} AS {}", self.quote_identifier(column)) } else { value.clone() }
+                    })
+                    .collect();
+                format!("SELECT {}", values.join(", "))
+            })
+            .collect();
+        format!("({}) AS {}", selects.join(" UNION ALL "), self.quote_identifier(alias))
+    }
+
+    fn for_server_version(&self, version: &str) -> Option<Box<dyn Platform>> {
+        Some(Box::new(self.with_server_version(version)))
+    }
+
+    fn last_insert_id_sql(&self) -> Option<String> {
+        Some("SELECT LAST_INSERT_ID()".to_string())
+    }
+
+    fn current_transaction_id_sql(&self) -> Option<String> {
+        // InnoDB assigns the id with the first statement of the transaction
+        Some(
+            "SELECT trx_id FROM information_schema.innodb_trx WHERE trx_mysql_thread_id = CONNECTION_ID()"
+                .to_string(),
+        )
+    }
+
+    fn set_time_zone_sql(&self, time_zone: &str) -> Option<String> {
+        // Named zones need the timezone tables loaded, UTC works without them
+        let time_zone = if time_zone.eq_ignore_ascii_case("UTC") { "+00:00" } else { time_zone };
+        Some(format!("SET time_zone = {}", self.quote_string(time_zone)))
+    }
+
+    fn set_schema_sql(&self, schema: &str) -> Option<String> {
+        Some(format!("USE {}", self.quote_identifier(schema)))
+    }
+
+    // Schemas are databases on MySQL
+    fn use_database_sql(&self, database: &str) -> Option<String> {
+        self.set_schema_sql(database)
+    }
+
+    fn set_charset_sql(&self, charset: &str) -> Option<String> {
+        Some(format!("SET NAMES {}", self.quote_string(charset)))
+    }
+
+    fn set_auto_commit_sql(&self, auto_commit: bool) -> Option<String> {
+        Some(format!("SET autocommit = {}", u8::from(auto_commit)))
+    }
+
+    // Numeric system variables reject quoted values
+    fn set_session_setting_sql(&self, name: &str, value: Option<&str>) -> Option<String> {
+        let value = match value {
+            Some(value) if value.parse::<f64>().is_ok_and(f64::is_finite) => value.to_string(),
+            Some(value) => self.quote_string(value),
+            None => "NULL".to_string(),
+        };
+        Some(format!("SET SESSION {name} = {value}"))
+    }
+
+    fn reset_session_setting_sql(&self, name: &str) -> Option<String> {
+        Some(format!("SET SESSION {name} = DEFAULT"))
+    }
+
+    fn get_session_setting_sql(&self, name: &str) -> Option<String> {
+        Some(format!("SELECT @@SESSION.{name}"))
+    }
+
     fn parameter_placeholder(&self, _index: usize) -> String {
         "?".to_string()
     }
@@ -387,15 +1479,43 @@ impl Platform for MySqlPlatform {
                 Some(p) => format!("TIMESTAMP({p})"),
                 None => "TIMESTAMP".to_string(),
             },
-            SqlType::Uuid => "CHAR(36)".to_string(), // MySQL doesn't have native UUID
+            SqlType::Uuid => self.get_uuid_declaration(self.uuid_storage()),
             SqlType::Json => "JSON".to_string(),
             SqlType::Serial => "INT AUTO_INCREMENT".to_string(),
             SqlType::BigSerial => "BIGINT AUTO_INCREMENT".to_string(),
         }
     }
 
+    fn parse_type_declaration(&self, declaration: &str) -> Option<SqlType> {
+        match SqlType::from_declaration(declaration)? {
+            SqlType::Binary { length: 16 } if self.binary_uuid => Some(SqlType::Uuid),
+            sql_type => Some(sql_type),
+        }
+    }
+
+    fn normalize_default(&self, default: &str, sql_type: &SqlType) -> Option<DefaultValue> {
+        // MySQL reports string defaults unquoted, MariaDB quotes them
+        let unquoted = !default.starts_with('\'') && (sql_type.is_string() || sql_type.is_datetime());
+        match DefaultValue::parse(default, sql_type)? {
+            DefaultValue::Expression(text) if unquoted && !text.contains('(') => Some(DefaultValue::Value(SqlValue::String(text))),
+            parsed => Some(parsed),
+        }
+    }
+
+    fn uuid_storage(&self) -> UuidStorage {
+        if self.binary_uuid { UuidStorage::Binary } else { UuidStorage::Text }
+    }
+
+    fn get_uuid_declaration(&self, storage: UuidStorage) -> String {
+        // MySQL doesn't have native UUID
+        match storage {
+            UuidStorage::Text => "CHAR(36)".to_string(),
+            UuidStorage::Binary => "BINARY(16)".to_string(),
+        }
+    }
+
     fn get_column_declaration(&self, column: &Column) -> String {
-        let type_decl = self.get_type_declaration(&column.sql_type);
+        let type_decl = self.get_column_type_declaration(column);
 
         // Handle AUTO_INCREMENT separately for MySQL
         let (base_type, has_auto_inc) = if type_decl.ends_with(" AUTO_INCREMENT") {
@@ -407,6 +1527,11 @@ impl Platform for MySqlPlatform {
         let quoted_name = self.quote_identifier(&column.name);
         let mut sql = format!("{quoted_name} {base_type}");
 
+        if let Some(srid) = column.get_platform_option("srid") {
+            sql.push_str(" SRID ");
+            sql.push_str(srid);
+        }
+
         if !column.nullable {
             sql.push_str(" NOT NULL");
         }
@@ -417,12 +1542,93 @@ impl Platform for MySqlPlatform {
 
         if let Some(ref default) = column.default {
             sql.push_str(" DEFAULT ");
-            sql.push_str(default);
+            sql.push_str(&self.get_default_value_sql(default));
+        }
+
+        for clause in self.get_column_options_sql(column) {
+            sql.push(' ');
+            sql.push_str(&clause);
+        }
+
+        if let Some(check) = self.get_enum_check_sql(column) {
+            sql.push(' ');
+            sql.push_str(&check);
         }
 
         sql
     }
 
+    // Rows whose key is not in the table are inserted
+    fn get_update_batch_sql(&self, table: &str, key_columns: &[&str], columns: &[&str], rows: &[Vec<SqlValue>]) -> Vec<String> {
+        if rows.is_empty() {
+            return Vec::new();
+        }
+        let names: Vec<String> = columns.iter().map(|column| self.quote_identifier(column)).collect();
+        let values: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let literals: Vec<String> = row.iter().map(|value| self.format_literal(value)).collect();
+                format!("({})", literals.join(", "))
+            })
+            .collect();
+        let assignments: Vec<String> = columns
+            .iter()
+            .zip(&names)
+            .filter(|(column, _)| !key_columns.contains(column))
+            .map(|(_, name)| format!("{name} = VALUES({name})"))
+            .collect();
+        vec![format!(
+            "INSERT INTO {} ({}) VALUES {} ON DUPLICATE KEY UPDATE {}",
+            self.quote_identifier(table),
+            names.join(", "),
+            values.join(", "),
+            assignments.join(", ")
+        )]
+    }
+
+    fn get_enum_declaration(&self, column: &Column) -> Option<String> {
+        if column.enum_values.is_empty() || !column.sql_type.is_string() {
+            return None;
+        }
+        let values: Vec<String> = column.enum_values.iter().map(|value| self.quote_string(value)).collect();
+        Some(format!("ENUM({})", values.join(", ")))
+    }
+
+    fn get_column_options_sql(&self, column: &Column) -> Vec<String> {
+        let mut clauses = Vec::new();
+        if let Some(expression) = column.get_platform_option("on_update") {
+            clauses.push(format!("ON UPDATE {expression}"));
+        }
+        if column.get_platform_option("invisible").is_some_and(|value| value.eq_ignore_ascii_case("true")) {
+            clauses.push("INVISIBLE".to_string());
+        }
+        clauses
+    }
+
+    fn parse_column_options(&self, extra: &str) -> BTreeMap<String, String> {
+        // e.g. `DEFAULT_GENERATED on update CURRENT_TIMESTAMP INVISIBLE`
+        let mut options = BTreeMap::new();
+        let mut words = extra.split_whitespace();
+        while let Some(word) = words.next() {
+            if word.eq_ignore_ascii_case("on") {
+                if let (Some(keyword), Some(expression)) = (words.next(), words.next()) {
+                    if keyword.eq_ignore_ascii_case("update") {
+                        // MariaDB reports `current_timestamp()`
+                        let expression = if expression.eq_ignore_ascii_case("current_timestamp()") {
+                            "CURRENT_TIMESTAMP"
+                        } else {
+                            expression
+                        };
+                        options.insert("on_update".to_string(), expression.to_string());
+                    }
+                }
+            } else if word.eq_ignore_ascii_case("invisible") {
+                options.insert("invisible".to_string(), "true".to_string());
+            }
+        }
+        options
+    }
+
     fn get_drop_index_sql(&self, index_name: &str, table_name: &str) -> String {
         // MySQL requires table name for DROP INDEX
         format!(
@@ -432,90 +1638,305 @@ impl Platform for MySqlPlatform {
         )
     }
 
+    fn get_drop_foreign_key_sql(&self, table_name: &str, fk_name: &str) -> Option<String> {
+        Some(format!(
+            "ALTER TABLE {} DROP FOREIGN KEY {}",
+            self.quote_identifier(table_name),
+            self.quote_identifier(fk_name)
+        ))
+    }
+
     fn get_list_tables_sql(&self) -> &'static str {
         "SELECT table_name FROM information_schema.tables WHERE table_schema = DATABASE() AND table_type = 'BASE TABLE'"
     }
 
     fn get_list_columns_sql(&self, table_name: &str) -> String {
+        let table_name = self.quote_string(table_name);
+        // MariaDB has no SRID column attribute
+        let srs_id = if self.mariadb { "NULL AS srs_id" } else { "srs_id" };
         format!(
-            "SELECT column_name, data_type, is_nullable, column_default, character_maximum_length, numeric_precision, numeric_scale, extra \
-             FROM information_schema.columns WHERE table_schema = DATABASE() AND table_name = '{table_name}' ORDER BY ordinal_position"
+            "SELECT column_name, data_type, is_nullable, column_default, character_maximum_length, numeric_precision, numeric_scale, extra, column_type, {srs_id} \
+             FROM information_schema.columns WHERE table_schema = DATABASE() AND table_name = {table_name} ORDER BY ordinal_position"
         )
     }
 
     fn get_list_indexes_sql(&self, table_name: &str) -> String {
+        let table_name = self.quote_string(table_name);
         format!(
             "SELECT index_name, column_name, non_unique \
-             FROM information_schema.statistics WHERE table_schema = DATABASE() AND table_name = '{table_name}' \
+             FROM information_schema.statistics WHERE table_schema = DATABASE() AND table_name = {table_name} \
              ORDER BY index_name, seq_in_index"
         )
     }
 
     fn get_list_foreign_keys_sql(&self, table_name: &str) -> String {
+        let table_name = self.quote_string(table_name);
         format!(
             "SELECT constraint_name, column_name, referenced_table_name, referenced_column_name \
              FROM information_schema.key_column_usage \
-             WHERE table_schema = DATABASE() AND table_name = '{table_name}' AND referenced_table_name IS NOT NULL"
+             WHERE table_schema = DATABASE() AND table_name = {table_name} AND referenced_table_name IS NOT NULL"
         )
     }
+
+    // table_rows is an estimate for InnoDB
+    fn get_table_statistics_sql(&self, table_name: &str) -> Option<String> {
+        let table_name = self.quote_string(table_name);
+        Some(format!(
+            "SELECT table_rows AS row_count, data_length + index_length AS size_bytes \
+             FROM information_schema.tables WHERE table_schema = DATABASE() AND table_name = {table_name}"
+        ))
+    }
+
+    fn get_list_users_sql(&self) -> Option<&'static str> {
+        Some("SELECT DISTINCT user FROM mysql.user ORDER BY user")
+    }
+
+    // Accounts are user names at any host ('name'@'%')
+    fn get_create_user_sql(&self, name: &str, password: &str) -> Option<String> {
+        Some(format!("CREATE USER {} IDENTIFIED BY {}", self.quote_string(name), self.quote_string(password)))
+    }
+
+    fn get_drop_user_sql(&self, name: &str) -> Option<String> {
+        Some(format!("DROP USER {}", self.quote_string(name)))
+    }
+
+    fn get_grant_sql(&self, privileges: &[Privilege], table_name: &str, role: &str) -> Option<String> {
+        let (privileges, table_name) = (privilege_list(privileges), self.quote_identifier(table_name));
+        Some(format!("GRANT {privileges} ON {table_name} TO {}", self.quote_string(role)))
+    }
+
+    fn get_revoke_sql(&self, privileges: &[Privilege], table_name: &str, role: &str) -> Option<String> {
+        let (privileges, table_name) = (privilege_list(privileges), self.quote_identifier(table_name));
+        Some(format!("REVOKE {privileges} ON {table_name} FROM {}", self.quote_string(role)))
+    }
+
+    // Grantees are reported as 'user'@'host'
+    fn get_list_grants_sql(&self, table_name: &str) -> Option<String> {
+        Some(format!(
+            "SELECT grantee, privilege_type FROM information_schema.table_privileges \
+             WHERE table_schema = DATABASE() AND table_name = {} ORDER BY grantee, privilege_type",
+            self.quote_string(table_name)
+        ))
+    }
 }
 
 /// `SQLite` platform
-#[derive(Debug, Default)]
-pub struct SqlitePlatform;
+///
+/// By default, columns are declared with their full type names (e.g.
+/// `VARCHAR(255)`, `DECIMAL(10, 2)`, `BOOLEAN`, `DATETIME`), which `SQLite`
+/// accepts and maps to a type affinity. This keeps schemas round-trippable.
+/// With strict affinity enabled, only the affinity names `INTEGER`, `REAL`,
+/// `TEXT` and `BLOB` are emitted (as required by `STRICT` tables).
+///
+/// UUIDs are stored as text by default. With binary UUIDs enabled they are
+/// declared as `BLOB(16)` (`BLOB` with strict affinity).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SqlitePlatform {
+    /// Emit plain affinity names instead of declared types
+    strict_affinity: bool,
+    /// Store UUIDs as 16 byte blobs instead of text
+    binary_uuid: bool,
+    /// Whether a `REGEXP` function is loaded
+    regexp: bool,
+}
+
+impl SqlitePlatform {
+    /// Create a new `SQLite` platform emitting declared types
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            strict_affinity: false,
+            binary_uuid: false,
+            regexp: false,
+        }
+    }
+
+    /// Emit plain affinity names (`INTEGER`, `REAL`, `TEXT`, `BLOB`) only
+    #[must_use]
+    pub const fn with_strict_affinity(mut self, strict: bool) -> Self {
+        self.strict_affinity = strict;
+        self
+    }
+
+    /// Check if strict affinity names are emitted
+    #[must_use]
+    pub const fn is_strict_affinity(&self) -> bool {
+        self.strict_affinity
+    }
+
+    /// Declare that a `REGEXP` function is available
+    ///
+    /// `SQLite` parses `x REGEXP y` but only runs it with an application
+    /// defined `regexp()` function, e.g. from a loaded regexp extension.
+    #[must_use]
+    pub const fn with_regexp(mut self, available: bool) -> Self {
+        self.regexp = available;
+        self
+    }
+
+    /// Store UUIDs as 16 byte blobs instead of text
+    #[must_use]
+    pub const fn with_binary_uuid(mut self, binary: bool) -> Self {
+        self.binary_uuid = binary;
+        self
+    }
+
+    /// Map a type to its plain `SQLite` affinity name
+    const fn affinity_name(sql_type: &SqlType) -> &'static str {
+        match sql_type {
+            // Integer affinity
+            SqlType::SmallInt
+            | SqlType::Integer
+            | SqlType::BigInt
+            | SqlType::Boolean  // SQLite uses 0/1 for boolean
+            | SqlType::Serial
+            | SqlType::BigSerial => "INTEGER",
+            // Real affinity
+            SqlType::Float | SqlType::Double | SqlType::Decimal { .. } => "REAL",
+            // Text affinity
+            SqlType::Char { .. }
+            | SqlType::Varchar { .. }
+            | SqlType::Text
+            | SqlType::Date
+            | SqlType::Time { .. }
+            | SqlType::Timestamp { .. }
+            | SqlType::TimestampTz { .. }  // SQLite stores dates as TEXT
+            | SqlType::Uuid
+            | SqlType::Json => "TEXT",  // SQLite has JSON functions but stores as TEXT
+            // Blob affinity
+            SqlType::Binary { .. } | SqlType::VarBinary { .. } | SqlType::Blob => "BLOB",
+        }
+    }
+}
 
 impl Platform for SqlitePlatform {
     fn name(&self) -> &'static str {
         "sqlite"
     }
 
+    fn supports_regexp(&self) -> bool {
+        self.regexp
+    }
+
     fn quote_identifier_char(&self) -> char {
         '"'
     }
 
-    fn supports_returning(&self) -> bool {
-        true // SQLite 3.35+ supports RETURNING
+    fn supports_returning(&self) -> bool {
+        true // SQLite 3.35+ supports RETURNING
+    }
+
+    // SQLITE_MAX_VARIABLE_NUMBER of SQLite before 3.32
+    fn max_bind_parameters(&self) -> Option<usize> {
+        Some(999)
+    }
+
+    // No column list after the alias; VALUES names its columns column1, column2, ...
+    fn values_table_sql(&self, rows: &[Vec<String>], alias: &str, columns: &[String]) -> String {
+        let rows: Vec<String> = rows.iter().map(|row| format!("({})", row.join(", "))).collect();
+        let columns: Vec<String> = columns.iter()
+            .enumerate()
+            .map(|(i, column)| format!("column{} AS {}", i + 1, self.quote_identifier(column)))
+            .collect();
+        format!(
+            "(SELECT {} FROM (VALUES {})) AS {}",
+            columns.join(", "),
+            rows.join(", "),
+            self.quote_identifier(alias)
+        )
+    }
+
+    fn row_identifier(&self) -> Option<&'static str> {
+        Some("rowid")
+    }
+
+    // Write transactions lock the whole database
+    fn for_update_sql(&self, _skip_locked: bool) -> Option<&'static str> {
+        None
+    }
+
+    fn datetime_format(&self) -> &'static str {
+        "%Y-%m-%d %H:%M:%S%.f" // stored as TEXT, keep fractional seconds
+    }
+
+    fn time_format(&self) -> &'static str {
+        "%H:%M:%S%.f"
+    }
+
+    fn parameter_placeholder(&self, _index: usize) -> String {
+        "?".to_string()
+    }
+
+    fn get_type_declaration(&self, sql_type: &SqlType) -> String {
+        // SQLite uses dynamic typing with type affinity
+        if self.strict_affinity && !matches!(sql_type, SqlType::Uuid) {
+            return Self::affinity_name(sql_type).to_string();
+        }
+
+        match sql_type {
+            SqlType::SmallInt => "SMALLINT".to_string(),
+            SqlType::BigInt => "BIGINT".to_string(),
+            // AUTOINCREMENT requires the exact declaration INTEGER
+            SqlType::Integer | SqlType::Serial | SqlType::BigSerial => "INTEGER".to_string(),
+            SqlType::Float => "FLOAT".to_string(),
+            SqlType::Double => "DOUBLE".to_string(),
+            SqlType::Decimal { precision, scale } => format!("DECIMAL({precision}, {scale})"),
+            SqlType::Char { length } => format!("CHAR({length})"),
+            SqlType::Varchar { length } => format!("VARCHAR({length})"),
+            SqlType::Boolean => "BOOLEAN".to_string(),
+            SqlType::Date => "DATE".to_string(),
+            SqlType::Time { precision } => match precision {
+                Some(p) => format!("TIME({p})"),
+                None => "TIME".to_string(),
+            },
+            SqlType::Timestamp { precision } => match precision {
+                Some(p) => format!("DATETIME({p})"),
+                None => "DATETIME".to_string(),
+            },
+            SqlType::TimestampTz { precision } => match precision {
+                Some(p) => format!("TIMESTAMPTZ({p})"),
+                None => "TIMESTAMPTZ".to_string(),
+            },
+            SqlType::Uuid => self.get_uuid_declaration(self.uuid_storage()),
+            // BINARY/VARBINARY/JSON would get NUMERIC affinity and coerce values,
+            // so these keep their affinity names
+            SqlType::Text
+            | SqlType::Json
+            | SqlType::Binary { .. }
+            | SqlType::VarBinary { .. }
+            | SqlType::Blob => Self::affinity_name(sql_type).to_string(),
+        }
+    }
+
+    fn parse_type_declaration(&self, declaration: &str) -> Option<SqlType> {
+        // BLOB(16) is only emitted for binary UUIDs
+        if declaration.trim().eq_ignore_ascii_case("BLOB(16)") {
+            return Some(SqlType::Uuid);
+        }
+        SqlType::from_declaration(declaration)
     }
 
-    fn parameter_placeholder(&self, _index: usize) -> String {
-        "?".to_string()
+    fn uuid_storage(&self) -> UuidStorage {
+        if self.binary_uuid { UuidStorage::Binary } else { UuidStorage::Text }
     }
 
-    fn get_type_declaration(&self, sql_type: &SqlType) -> String {
-        // SQLite uses dynamic typing with type affinity
-        match sql_type {
-            // Integer affinity
-            SqlType::SmallInt
-            | SqlType::Integer
-            | SqlType::BigInt
-            | SqlType::Boolean  // SQLite uses 0/1 for boolean
-            | SqlType::Serial
-            | SqlType::BigSerial => "INTEGER".to_string(),
-            // Real affinity
-            SqlType::Float | SqlType::Double | SqlType::Decimal { .. } => {
-                "REAL".to_string() // SQLite doesn't have DECIMAL
-            }
-            // Text affinity
-            SqlType::Char { .. }
-            | SqlType::Varchar { .. }
-            | SqlType::Text
-            | SqlType::Date
-            | SqlType::Time { .. }
-            | SqlType::Timestamp { .. }
-            | SqlType::TimestampTz { .. }  // SQLite stores dates as TEXT
-            | SqlType::Uuid
-            | SqlType::Json => "TEXT".to_string(),  // SQLite has JSON functions but stores as TEXT
-            // Blob affinity
-            SqlType::Binary { .. } | SqlType::VarBinary { .. } | SqlType::Blob => "BLOB".to_string(),
+    fn get_uuid_declaration(&self, storage: UuidStorage) -> String {
+        match (storage, self.strict_affinity) {
+            (UuidStorage::Text, true) => "TEXT".to_string(),
+            (UuidStorage::Text, false) => "UUID".to_string(),
+            (UuidStorage::Binary, true) => "BLOB".to_string(),
+            (UuidStorage::Binary, false) => "BLOB(16)".to_string(),
         }
     }
 
     fn get_column_declaration(&self, column: &Column) -> String {
-        let mut sql = format!(
-            "{} {}",
-            self.quote_identifier(&column.name),
-            self.get_type_declaration(&column.sql_type)
-        );
+        // AUTOINCREMENT is only allowed on exactly INTEGER, whatever the integer type
+        let declaration = if column.auto_increment {
+            "INTEGER".to_string()
+        } else {
+            self.get_column_type_declaration(column)
+        };
+        let mut sql = format!("{} {declaration}", self.quote_identifier(&column.name));
 
         // SQLite PRIMARY KEY implies AUTOINCREMENT for INTEGER
         if column.auto_increment {
@@ -527,15 +1948,25 @@ impl Platform for SqlitePlatform {
 
             if let Some(ref default) = column.default {
                 sql.push_str(" DEFAULT ");
-                sql.push_str(default);
+                sql.push_str(&self.get_default_value_sql(default));
             }
         }
 
+        for clause in self.get_column_options_sql(column) {
+            sql.push(' ');
+            sql.push_str(&clause);
+        }
+
+        if let Some(check) = self.get_enum_check_sql(column) {
+            sql.push(' ');
+            sql.push_str(&check);
+        }
+
         sql
     }
 
     fn get_create_table_sql(&self, table: &Table) -> String {
-        use std::fmt::Write;
+        use core::fmt::Write;
         let mut sql = format!("CREATE TABLE {} (\n", self.quote_identifier(&table.name));
 
         // Check if we have an auto-increment column (which becomes the PK in SQLite)
@@ -605,6 +2036,15 @@ impl Platform for SqlitePlatform {
         sql
     }
 
+    // SQLite can't alter constraints, but accepts references to tables created later
+    fn get_create_foreign_key_sql(&self, _table_name: &str, _fk: &ForeignKey) -> Option<String> {
+        None
+    }
+
+    fn get_drop_foreign_key_sql(&self, _table_name: &str, _fk_name: &str) -> Option<String> {
+        None
+    }
+
     fn release_savepoint_sql(&self, name: &str) -> String {
         // SQLite uses RELEASE without SAVEPOINT keyword
         format!("RELEASE {}", self.quote_identifier(name))
@@ -625,12 +2065,105 @@ impl Platform for SqlitePlatform {
     fn get_list_foreign_keys_sql(&self, table_name: &str) -> String {
         format!("PRAGMA foreign_key_list({})", self.quote_identifier(table_name))
     }
+
+    // Rows are counted exactly; sizes come from the dbstat table, which needs
+    // SQLITE_ENABLE_DBSTAT_VTAB (enabled in the bundled library)
+    fn get_table_statistics_sql(&self, table_name: &str) -> Option<String> {
+        Some(format!(
+            "SELECT COUNT(*) AS row_count, \
+             (SELECT SUM(pgsize) FROM dbstat WHERE name IN (SELECT name FROM sqlite_master WHERE tbl_name = {})) AS size_bytes \
+             FROM {}",
+            self.quote_string(table_name),
+            self.quote_identifier(table_name)
+        ))
+    }
+}
+
+/// Generic ANSI SQL platform
+///
+/// Uses standard double-quoted identifiers, standard type names and the
+/// `information_schema` views, i.e. the [`Platform`] defaults. It renders
+/// the `Display` output of query builders and is handy in unit tests.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AnsiPlatform {
+    /// Use numbered `$n` placeholders instead of `?`
+    numbered_parameters: bool,
+}
+
+impl AnsiPlatform {
+    /// Create a new ANSI platform using `?` placeholders
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            numbered_parameters: false,
+        }
+    }
+
+    /// Use numbered `$n` placeholders instead of `?`
+    #[must_use]
+    pub const fn with_numbered_parameters(mut self, numbered: bool) -> Self {
+        self.numbered_parameters = numbered;
+        self
+    }
+}
+
+impl Platform for AnsiPlatform {
+    fn name(&self) -> &'static str {
+        "ansi"
+    }
+
+    fn parameter_placeholder(&self, index: usize) -> String {
+        if self.numbered_parameters {
+            format!("${}", index + 1)
+        } else {
+            "?".to_string()
+        }
+    }
+}
+
+/// Rows per statement of the default [`Platform::get_update_batch_sql`]
+///
+/// Keeps the OR of composite keys below `SQLite`'s expression depth limit of 1000.
+pub const UPDATE_BATCH_ROWS: usize = 500;
+
+/// Get the `PostgreSQL` type of a value whose literal is a quoted string
+const fn postgres_literal_type(value: &SqlValue) -> Option<&'static str> {
+    match value {
+        #[cfg(feature = "uuid")]
+        SqlValue::Uuid(_) => Some("uuid"),
+        #[cfg(feature = "json")]
+        SqlValue::Json(_) => Some("jsonb"),
+        #[cfg(feature = "decimal")]
+        SqlValue::Decimal(_) => Some("numeric"),
+        #[cfg(feature = "chrono")]
+        SqlValue::Date(_) => Some("date"),
+        #[cfg(feature = "chrono")]
+        SqlValue::Time(_) => Some("time"),
+        #[cfg(feature = "chrono")]
+        SqlValue::DateTime(_) => Some("timestamp"),
+        #[cfg(feature = "chrono")]
+        SqlValue::DateTimeUtc(_) | SqlValue::DateTimeTz(_) => Some("timestamptz"),
+        _ => None,
+    }
+}
+
+/// Render privileges as a comma-separated list
+fn privilege_list(privileges: &[Privilege]) -> String {
+    privileges.iter().map(Privilege::as_sql).collect::<Vec<_>>().join(", ")
+}
+
+/// Make text safe to place inside a `/* ... */` comment
+///
+/// `PostgreSQL` nests comments, so an opening `/*` is broken up as well as
+/// a closing `*/`.
+fn comment_text(text: &str) -> String {
+    text.replace("*/", "* /").replace("/*", "/ *")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::platform::types::{Column, ForeignKey, ForeignKeyAction, Index, SqlType, Table};
+    use crate::platform::types::{Column, DefaultValue, ForeignKey, ForeignKeyAction, Index, SqlType, Table};
 
     #[test]
     fn test_postgres_quote_identifier() {
@@ -641,10 +2174,28 @@ mod tests {
 
     #[test]
     fn test_mysql_quote_identifier() {
-        let platform = MySqlPlatform;
+        let platform = MySqlPlatform::new();
         assert_eq!(platform.quote_identifier("users"), "`users`");
     }
 
+    #[test]
+    fn test_mariadb_returning() {
+        assert!(!MySqlPlatform::new().supports_returning());
+        for (version, returning) in [
+            ("8.0.36", false),
+            ("10.4.32-MariaDB", false),
+            ("10.5.0-MariaDB", true),
+            ("5.5.5-10.11.6-MariaDB-log", true),
+            ("11.4.2-MariaDB-ubu2404", true),
+        ] {
+            let platform = MySqlPlatform::new().with_binary_uuid(true).for_server_version(version).unwrap();
+            assert_eq!(platform.supports_returning(), returning, "{version}");
+            assert!(!platform.supports_update_returning());
+            assert_eq!(platform.get_type_declaration(&SqlType::Uuid), "BINARY(16)");
+        }
+        assert!(SqlitePlatform::new().for_server_version("3.46.0").is_none());
+    }
+
     #[test]
     fn test_postgres_parameter() {
         let platform = PostgresPlatform;
@@ -652,9 +2203,17 @@ mod tests {
         assert_eq!(platform.parameter_placeholder(1), "$2");
     }
 
+    #[test]
+    fn test_typed_parameter_placeholder() {
+        assert_eq!(PostgresPlatform.typed_parameter_placeholder(0, ParameterType::Uuid), "$1::uuid");
+        assert_eq!(PostgresPlatform.typed_parameter_placeholder(1, ParameterType::Json), "$2::jsonb");
+        assert_eq!(PostgresPlatform.typed_parameter_placeholder(2, ParameterType::Integer), "$3");
+        assert_eq!(MySqlPlatform::new().typed_parameter_placeholder(0, ParameterType::Uuid), "?");
+    }
+
     #[test]
     fn test_mysql_parameter() {
-        let platform = MySqlPlatform;
+        let platform = MySqlPlatform::new();
         assert_eq!(platform.parameter_placeholder(0), "?");
         assert_eq!(platform.parameter_placeholder(1), "?");
     }
@@ -685,7 +2244,7 @@ mod tests {
 
     #[test]
     fn test_mysql_type_declarations() {
-        let platform = MySqlPlatform;
+        let platform = MySqlPlatform::new();
         assert_eq!(platform.get_type_declaration(&SqlType::Integer), "INT");
         assert_eq!(platform.get_type_declaration(&SqlType::Boolean), "TINYINT(1)");
         assert_eq!(platform.get_type_declaration(&SqlType::Uuid), "CHAR(36)");
@@ -695,16 +2254,55 @@ mod tests {
 
     #[test]
     fn test_sqlite_type_declarations() {
-        let platform = SqlitePlatform;
-        // SQLite uses type affinity
+        let platform = SqlitePlatform::new();
+        // Declared types are preserved, SQLite derives the affinity
+        assert_eq!(platform.get_type_declaration(&SqlType::Integer), "INTEGER");
+        assert_eq!(platform.get_type_declaration(&SqlType::BigInt), "BIGINT");
+        assert_eq!(platform.get_type_declaration(&SqlType::varchar(255)), "VARCHAR(255)");
+        assert_eq!(platform.get_type_declaration(&SqlType::decimal(10, 2)), "DECIMAL(10, 2)");
+        assert_eq!(platform.get_type_declaration(&SqlType::Boolean), "BOOLEAN");
+        assert_eq!(platform.get_type_declaration(&SqlType::timestamp(None)), "DATETIME");
+        assert_eq!(platform.get_type_declaration(&SqlType::Uuid), "UUID");
+        assert_eq!(platform.get_type_declaration(&SqlType::Date), "DATE");
+        assert_eq!(platform.get_type_declaration(&SqlType::Json), "TEXT");
+    }
+
+    #[test]
+    fn test_sqlite_strict_affinity_type_declarations() {
+        let platform = SqlitePlatform::new().with_strict_affinity(true);
         assert_eq!(platform.get_type_declaration(&SqlType::Integer), "INTEGER");
         assert_eq!(platform.get_type_declaration(&SqlType::BigInt), "INTEGER");
         assert_eq!(platform.get_type_declaration(&SqlType::varchar(255)), "TEXT");
+        assert_eq!(platform.get_type_declaration(&SqlType::decimal(10, 2)), "REAL");
         assert_eq!(platform.get_type_declaration(&SqlType::Boolean), "INTEGER");
         assert_eq!(platform.get_type_declaration(&SqlType::Uuid), "TEXT");
         assert_eq!(platform.get_type_declaration(&SqlType::Date), "TEXT");
     }
 
+    #[test]
+    fn test_sqlite_type_declaration_round_trip() {
+        let platform = SqlitePlatform::new();
+        let types = [
+            SqlType::SmallInt,
+            SqlType::BigInt,
+            SqlType::Double,
+            SqlType::decimal(10, 2),
+            SqlType::varchar(255),
+            SqlType::char(2),
+            SqlType::Boolean,
+            SqlType::Date,
+            SqlType::Time { precision: Some(3) },
+            SqlType::timestamp(None),
+            SqlType::TimestampTz { precision: None },
+            SqlType::Uuid,
+        ];
+
+        for sql_type in types {
+            let declaration = platform.get_type_declaration(&sql_type);
+            assert_eq!(platform.parse_type_declaration(&declaration), Some(sql_type));
+        }
+    }
+
     // DDL generation tests
     #[test]
     fn test_postgres_create_table() {
@@ -725,7 +2323,7 @@ mod tests {
 
     #[test]
     fn test_mysql_create_table() {
-        let platform = MySqlPlatform;
+        let platform = MySqlPlatform::new();
         let table = Table::new("users")
             .column(Column::new("id", SqlType::Serial).not_null())
             .column(Column::new("name", SqlType::varchar(100)).not_null())
@@ -737,9 +2335,35 @@ mod tests {
         assert!(sql.contains("`name` VARCHAR(100) NOT NULL"));
     }
 
+    #[test]
+    fn test_mysql_column_platform_options() {
+        let platform = MySqlPlatform::new();
+        let column = Column::new("updated_at", SqlType::timestamp(None))
+            .not_null()
+            .default_current_timestamp()
+            .platform_option("on_update", "CURRENT_TIMESTAMP")
+            .platform_option("invisible", "true");
+        assert_eq!(
+            platform.get_column_declaration(&column),
+            "`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP INVISIBLE"
+        );
+        assert_eq!(PostgresPlatform.get_column_declaration(&column), "\"updated_at\" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP");
+
+        let options = platform.parse_column_options("DEFAULT_GENERATED on update current_timestamp() INVISIBLE");
+        assert_eq!(options.get("on_update").map(String::as_str), Some("CURRENT_TIMESTAMP"));
+        assert_eq!(options.get("invisible").map(String::as_str), Some("true"));
+        assert!(platform.parse_column_options("auto_increment").is_empty());
+
+        let location = Column::new("location", SqlType::Blob).not_null().platform_option("srid", "4326");
+        assert_eq!(platform.get_column_declaration(&location), "`location` LONGBLOB SRID 4326 NOT NULL");
+        assert!(platform.get_list_columns_sql("places").contains(", srs_id FROM"));
+        let mariadb = MySqlPlatform::new().with_server_version("10.11.2-MariaDB");
+        assert!(mariadb.get_list_columns_sql("places").contains(", NULL AS srs_id FROM"));
+    }
+
     #[test]
     fn test_sqlite_create_table() {
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let table = Table::new("users")
             .column(Column::new("id", SqlType::Integer).not_null().auto_increment())
             .column(Column::new("name", SqlType::varchar(100)).not_null())
@@ -748,9 +2372,14 @@ mod tests {
         let sql = platform.get_create_table_sql(&table);
         assert!(sql.contains("CREATE TABLE \"users\""));
         assert!(sql.contains("\"id\" INTEGER PRIMARY KEY AUTOINCREMENT"));
-        assert!(sql.contains("\"name\" TEXT NOT NULL"));
+        assert!(sql.contains("\"name\" VARCHAR(100) NOT NULL"));
         // Should not have separate PRIMARY KEY since AUTOINCREMENT implies it
         assert!(!sql.contains("PRIMARY KEY (\"id\")"));
+
+        for sql_type in [SqlType::BigInt, SqlType::SmallInt] {
+            let column = Column::new("id", sql_type).auto_increment();
+            assert_eq!(platform.get_column_declaration(&column), "\"id\" INTEGER PRIMARY KEY AUTOINCREMENT");
+        }
     }
 
     #[test]
@@ -775,6 +2404,43 @@ mod tests {
         assert!(sql.contains("ON DELETE CASCADE"));
     }
 
+    #[test]
+    fn test_schema_sql_defers_cyclic_foreign_keys() {
+        let fk = |name: &str, table: &str| ForeignKey {
+            name: name.to_string(),
+            local_columns: vec![format!("{table}_id")],
+            foreign_table: table.to_string(),
+            foreign_columns: vec!["id".to_string()],
+            on_delete: ForeignKeyAction::NoAction,
+            on_update: ForeignKeyAction::NoAction,
+        };
+        let table = |name: &str, references: &str| {
+            Table::new(name)
+                .column(Column::new("id", SqlType::Integer))
+                .column(Column::new(format!("{references}_id"), SqlType::Integer))
+                .foreign_key(fk(&format!("fk_{name}_{references}"), references))
+        };
+        let schema = Schema::new().table(table("users", "teams")).table(table("teams", "users"));
+
+        let create = PostgresPlatform.get_create_schema_sql(&schema);
+        assert_eq!(create.len(), 3);
+        assert!(create[0].starts_with("CREATE TABLE \"users\"") && !create[0].contains("FOREIGN KEY"));
+        assert!(create[1].starts_with("CREATE TABLE \"teams\"") && create[1].contains("FOREIGN KEY"));
+        assert_eq!(
+            create[2],
+            "ALTER TABLE \"users\" ADD CONSTRAINT \"fk_users_teams\" FOREIGN KEY (\"teams_id\") REFERENCES \"teams\" (\"id\")"
+        );
+        assert_eq!(
+            MySqlPlatform::new().get_drop_schema_sql(&schema),
+            ["ALTER TABLE `users` DROP FOREIGN KEY `fk_users_teams`", "DROP TABLE `teams`", "DROP TABLE `users`"]
+        );
+
+        // SQLite keeps the foreign key inline, references may precede the table
+        let create = SqlitePlatform::new().get_create_schema_sql(&schema);
+        assert_eq!(create.len(), 2);
+        assert!(create[0].contains("FOREIGN KEY"));
+    }
+
     #[test]
     fn test_drop_table() {
         let platform = PostgresPlatform;
@@ -797,6 +2463,21 @@ mod tests {
         assert_eq!(sql, "CREATE UNIQUE INDEX \"idx_users_email_unique\" ON \"users\" (\"email\")");
     }
 
+    #[test]
+    fn test_alter_column_sql() {
+        let platform = MySqlPlatform::new();
+        let column = Column::new("email", SqlType::varchar(255)).not_null();
+        assert_eq!(
+            platform.get_add_column_sql("users", &column),
+            "ALTER TABLE `users` ADD COLUMN `email` VARCHAR(255) NOT NULL"
+        );
+        assert_eq!(platform.get_drop_column_sql("users", "email"), "ALTER TABLE `users` DROP COLUMN `email`");
+        assert_eq!(
+            PostgresPlatform.get_rename_column_sql("users", "email", "mail"),
+            "ALTER TABLE \"users\" RENAME COLUMN \"email\" TO \"mail\""
+        );
+    }
+
     // Schema introspection SQL tests
     #[test]
     fn test_postgres_introspection_sql() {
@@ -808,26 +2489,394 @@ mod tests {
 
     #[test]
     fn test_mysql_introspection_sql() {
-        let platform = MySqlPlatform;
+        let platform = MySqlPlatform::new();
         assert!(platform.get_list_tables_sql().contains("information_schema.tables"));
         assert!(platform.get_list_tables_sql().contains("DATABASE()"));
     }
 
+    #[test]
+    fn test_introspection_sql_quotes_table_names() {
+        let name = "o'neil\\";
+        for sql in [
+            PostgresPlatform.get_list_columns_sql(name),
+            PostgresPlatform.get_list_indexes_sql(name),
+            PostgresPlatform.get_list_foreign_keys_sql(name),
+            AnsiPlatform::new().get_list_columns_sql(name),
+        ] {
+            assert!(sql.contains("= 'o''neil\\'"), "{sql}");
+        }
+        // A trailing backslash would escape the closing quote in MySQL
+        let mysql = MySqlPlatform::new();
+        for sql in [
+            mysql.get_list_columns_sql(name),
+            mysql.get_list_indexes_sql(name),
+            mysql.get_list_foreign_keys_sql(name),
+        ] {
+            assert!(sql.contains("table_name = 'o''neil\\\\'"), "{sql}");
+        }
+        assert!(PostgresPlatform.get_table_statistics_sql(name).unwrap().contains("c.relname = 'o''neil\\'"));
+        assert!(mysql.get_table_statistics_sql(name).unwrap().contains("table_name = 'o''neil\\\\'"));
+        assert_eq!(AnsiPlatform::new().get_table_statistics_sql(name), None);
+        assert_eq!(
+            SqlitePlatform::new().get_list_columns_sql("a\"b"),
+            "PRAGMA table_info(\"a\"\"b\")"
+        );
+    }
+
     #[test]
     fn test_sqlite_introspection_sql() {
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         assert!(platform.get_list_tables_sql().contains("sqlite_master"));
         assert!(platform.get_list_columns_sql("users").contains("PRAGMA table_info"));
         assert!(platform.get_list_indexes_sql("users").contains("PRAGMA index_list"));
     }
 
+    #[test]
+    fn test_user_and_grant_sql() {
+        let privileges = [Privilege::Select, Privilege::Insert];
+        assert_eq!(
+            PostgresPlatform.get_create_user_sql("app", "it's").as_deref(),
+            Some("CREATE ROLE \"app\" LOGIN PASSWORD 'it''s'")
+        );
+        assert_eq!(
+            PostgresPlatform.get_grant_sql(&privileges, "users", "app").as_deref(),
+            Some("GRANT SELECT, INSERT ON \"users\" TO \"app\"")
+        );
+        assert_eq!(
+            PostgresPlatform.get_revoke_sql(&[Privilege::All], "users", "app").as_deref(),
+            Some("REVOKE ALL PRIVILEGES ON \"users\" FROM \"app\"")
+        );
+        assert!(PostgresPlatform.get_list_grants_sql("users").unwrap().contains("role_table_grants"));
+
+        let mysql = MySqlPlatform::new();
+        assert_eq!(
+            mysql.get_create_user_sql("app", "secret").as_deref(),
+            Some("CREATE USER 'app' IDENTIFIED BY 'secret'")
+        );
+        assert_eq!(
+            mysql.get_grant_sql(&privileges, "users", "app").as_deref(),
+            Some("GRANT SELECT, INSERT ON `users` TO 'app'")
+        );
+        assert_eq!(mysql.get_drop_user_sql("app").as_deref(), Some("DROP USER 'app'"));
+        assert!(mysql.get_list_users_sql().unwrap().contains("mysql.user"));
+
+        let sqlite = SqlitePlatform::new();
+        assert_eq!(sqlite.get_list_users_sql(), None);
+        assert_eq!(sqlite.get_grant_sql(&privileges, "users", "app"), None);
+        assert_eq!(sqlite.get_list_grants_sql("users"), None);
+    }
+
+    #[test]
+    fn test_set_time_zone_sql() {
+        assert_eq!(
+            PostgresPlatform.set_time_zone_sql("UTC").as_deref(),
+            Some("SET TIME ZONE 'UTC'")
+        );
+        assert_eq!(
+            MySqlPlatform::new().set_time_zone_sql("UTC").as_deref(),
+            Some("SET time_zone = '+00:00'")
+        );
+        assert_eq!(
+            MySqlPlatform::new().set_time_zone_sql("Europe/Berlin").as_deref(),
+            Some("SET time_zone = 'Europe/Berlin'")
+        );
+        assert_eq!(SqlitePlatform::new().set_time_zone_sql("UTC"), None);
+    }
+
+    #[test]
+    fn test_format_literal() {
+        let sqlite = SqlitePlatform::new();
+        assert_eq!(sqlite.format_literal(&SqlValue::String("it's".into())), "'it''s'");
+        assert_eq!(sqlite.format_literal(&SqlValue::Bool(true)), "TRUE");
+        assert_eq!(sqlite.format_literal(&SqlValue::Null), "NULL");
+        assert_eq!(sqlite.format_literal(&SqlValue::I64(-3)), "-3");
+        assert_eq!(sqlite.format_literal(&SqlValue::Bytes(vec![1, 171])), "X'01ab'");
+        assert_eq!(
+            PostgresPlatform.format_literal(&SqlValue::Bytes(vec![1, 171])),
+            "'\\x01ab'::bytea"
+        );
+    }
+
+    #[test]
+    fn test_non_finite_float_literals() {
+        assert_eq!(
+            PostgresPlatform.format_literal(&SqlValue::F64(f64::NAN)),
+            "CAST('NaN' AS DOUBLE PRECISION)"
+        );
+        assert_eq!(
+            PostgresPlatform.format_literal(&SqlValue::F32(f32::NEG_INFINITY)),
+            "CAST('-Infinity' AS DOUBLE PRECISION)"
+        );
+        assert_eq!(PostgresPlatform.format_literal(&SqlValue::F64(1.5)), "1.5");
+        assert!(!MySqlPlatform::new().supports_non_finite_floats());
+        assert_eq!(MySqlPlatform::new().non_finite_float_sql(f64::INFINITY), None);
+        assert!(!SqlitePlatform::new().supports_non_finite_floats());
+    }
+
+    #[test]
+    fn test_default_values() {
+        let mysql = MySqlPlatform::new();
+        let status = SqlType::varchar(20);
+        assert_eq!(mysql.normalize_default("active", &status), Some(DefaultValue::value("active")));
+        assert_eq!(mysql.normalize_default("'active'", &status), Some(DefaultValue::value("active")));
+        assert_eq!(
+            mysql.normalize_default("current_timestamp()", &SqlType::timestamp(None)),
+            Some(DefaultValue::CurrentTimestamp)
+        );
+        assert_eq!(
+            SqlitePlatform::new().normalize_default("active", &status),
+            Some(DefaultValue::Expression("active".to_string()))
+        );
+
+        let column = Column::new("status", status).default_value("it's");
+        assert_eq!(mysql.get_column_declaration(&column), "`status` VARCHAR(20) DEFAULT 'it''s'");
+        let column = Column::new("active", SqlType::Boolean).default_value(false);
+        assert_eq!(mysql.get_column_declaration(&column), "`active` TINYINT(1) DEFAULT FALSE");
+        let column = Column::new("created_at", SqlType::timestamp(None)).default_current_timestamp();
+        assert_eq!(
+            PostgresPlatform.get_column_declaration(&column),
+            "\"created_at\" TIMESTAMP DEFAULT CURRENT_TIMESTAMP"
+        );
+        assert_eq!(PostgresPlatform.get_default_value_sql(&DefaultValue::Expression("1 + 1".to_string())), "1 + 1");
+    }
+
+    #[test]
+    fn test_session_settings_sql() {
+        assert_eq!(
+            PostgresPlatform.set_schema_sql("app").as_deref(),
+            Some("SET search_path TO \"app\"")
+        );
+        assert_eq!(
+            PostgresPlatform.set_application_name_sql("worker").as_deref(),
+            Some("SET application_name TO 'worker'")
+        );
+        assert_eq!(MySqlPlatform::new().set_schema_sql("app").as_deref(), Some("USE `app`"));
+        assert_eq!(
+            MySqlPlatform::new().set_charset_sql("utf8mb4").as_deref(),
+            Some("SET NAMES 'utf8mb4'")
+        );
+        assert_eq!(
+            PostgresPlatform.set_charset_sql("utf8mb4").as_deref(),
+            Some("SET client_encoding TO 'UTF8'")
+        );
+        assert_eq!(
+            PostgresPlatform.set_charset_sql("LATIN1").as_deref(),
+            Some("SET client_encoding TO 'LATIN1'")
+        );
+        assert_eq!(MySqlPlatform::new().set_application_name_sql("worker"), None);
+        assert_eq!(SqlitePlatform::new().set_charset_sql("utf8"), None);
+        assert_eq!(MySqlPlatform::new().set_auto_commit_sql(false).as_deref(), Some("SET autocommit = 0"));
+        assert_eq!(PostgresPlatform.set_auto_commit_sql(false), None);
+        assert_eq!(MySqlPlatform::new().use_database_sql("app").as_deref(), Some("USE `app`"));
+        assert_eq!(PostgresPlatform.use_database_sql("app"), None);
+    }
+
+    #[test]
+    fn test_session_variable_sql() {
+        assert_eq!(
+            PostgresPlatform.set_session_variable_sql("app.user_id", "4'2").as_deref(),
+            Some("SELECT set_config('app.user_id', '4''2', true)")
+        );
+        assert_eq!(
+            PostgresPlatform.get_session_variable_sql("app.user_id").as_deref(),
+            Some("SELECT current_setting('app.user_id', true)")
+        );
+        assert_eq!(SqlitePlatform::new().set_session_variable_sql("app.user_id", "42"), None);
+        assert_eq!(MySqlPlatform::new().get_session_variable_sql("app.user_id"), None);
+
+        let mysql = MySqlPlatform::new();
+        assert_eq!(
+            mysql.set_session_setting_sql("max_execution_time", Some("500")).as_deref(),
+            Some("SET SESSION max_execution_time = 500")
+        );
+        assert_eq!(
+            mysql.set_session_setting_sql("sql_mode", Some("ANSI")).as_deref(),
+            Some("SET SESSION sql_mode = 'ANSI'")
+        );
+        assert_eq!(
+            mysql.set_session_setting_sql("character_set_results", None).as_deref(),
+            Some("SET SESSION character_set_results = NULL")
+        );
+        assert_eq!(mysql.reset_session_setting_sql("sql_mode").as_deref(), Some("SET SESSION sql_mode = DEFAULT"));
+        assert_eq!(mysql.get_session_setting_sql("sql_mode").as_deref(), Some("SELECT @@SESSION.sql_mode"));
+        assert_eq!(PostgresPlatform.set_session_setting_sql("statement_timeout", Some("5s")), None);
+    }
+
+    #[test]
+    fn test_generated_key_sql() {
+        assert_eq!(
+            PostgresPlatform.sequence_next_value_sql("orders_id_seq").as_deref(),
+            Some("SELECT nextval('\"orders_id_seq\"')")
+        );
+        assert_eq!(MySqlPlatform::new().sequence_next_value_sql("orders_id_seq"), None);
+        assert_eq!(MySqlPlatform::new().last_insert_id_sql().as_deref(), Some("SELECT LAST_INSERT_ID()"));
+        assert!(PostgresPlatform.supports_transaction_id());
+        assert_eq!(PostgresPlatform.current_transaction_id_sql().as_deref(), Some("SELECT txid_current()"));
+        assert!(MySqlPlatform::new().supports_transaction_id());
+        assert!(!SqlitePlatform::new().supports_transaction_id());
+    }
+
+    #[test]
+    fn test_for_update_sql() {
+        assert_eq!(PostgresPlatform.for_update_sql(true), Some("FOR UPDATE SKIP LOCKED"));
+        assert_eq!(MySqlPlatform::new().for_update_sql(false), Some("FOR UPDATE"));
+        assert_eq!(AnsiPlatform::new().for_update_sql(true), Some("FOR UPDATE"));
+        assert_eq!(SqlitePlatform::new().for_update_sql(true), None);
+        assert_eq!(PostgresPlatform.last_insert_id_sql(), None);
+    }
+
+    #[test]
+    fn test_assert_valid_identifier() {
+        let postgres = PostgresPlatform;
+        assert!(postgres.assert_valid_identifier("users").is_ok());
+        assert!(postgres.assert_valid_identifier("app.users").is_ok());
+        assert!(postgres.assert_valid_identifier(&"a".repeat(63)).is_ok());
+        // Quoted as one identifier, so the dot counts towards the limit
+        let dotted = format!("a.{}", "b".repeat(62));
+        assert_eq!(postgres.quote_identifier(&dotted), format!("\"{dotted}\""));
+        for invalid in ["", "us\0ers", "\"users\"", &"a".repeat(64), &dotted] {
+            assert!(
+                matches!(
+                    postgres.assert_valid_identifier(invalid),
+                    Err(crate::core::Error::Schema(SchemaError::InvalidDefinition(_)))
+                ),
+                "{invalid:?}"
+            );
+        }
+        // Only the platform's own quote character is rejected
+        assert!(MySqlPlatform::new().assert_valid_identifier("\"users\"").is_ok());
+        assert!(MySqlPlatform::new().assert_valid_identifier("`users`").is_err());
+        assert!(SqlitePlatform::new().assert_valid_identifier(&"a".repeat(200)).is_ok());
+    }
+
+    #[test]
+    fn test_max_bind_parameters() {
+        assert_eq!(SqlitePlatform::new().max_bind_parameters(), Some(999));
+        assert_eq!(PostgresPlatform.max_bind_parameters(), Some(65_535));
+        assert_eq!(AnsiPlatform::new().max_bind_parameters(), None);
+    }
+
     #[test]
     fn test_sqlite_release_savepoint() {
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         // SQLite uses RELEASE without SAVEPOINT keyword
         assert_eq!(platform.release_savepoint_sql("sp1"), "RELEASE \"sp1\"");
 
         let pg = PostgresPlatform;
         assert_eq!(pg.release_savepoint_sql("sp1"), "RELEASE SAVEPOINT \"sp1\"");
     }
+
+    #[test]
+    fn test_binary_uuid_storage() {
+        let mysql = MySqlPlatform::new().with_binary_uuid(true);
+        assert_eq!(mysql.get_type_declaration(&SqlType::Uuid), "BINARY(16)");
+        assert_eq!(mysql.parse_type_declaration("binary(16)"), Some(SqlType::Uuid));
+        assert_eq!(
+            MySqlPlatform::new().parse_type_declaration("BINARY(16)"),
+            Some(SqlType::Binary { length: 16 })
+        );
+
+        let sqlite = SqlitePlatform::new().with_binary_uuid(true);
+        assert_eq!(sqlite.get_type_declaration(&SqlType::Uuid), "BLOB(16)");
+        assert_eq!(sqlite.parse_type_declaration("BLOB(16)"), Some(SqlType::Uuid));
+        assert_eq!(
+            sqlite.with_strict_affinity(true).get_type_declaration(&SqlType::Uuid),
+            "BLOB"
+        );
+
+        // Column setting overrides the platform
+        let column = Column::new("id", SqlType::Uuid).uuid_storage(UuidStorage::Binary);
+        assert_eq!(MySqlPlatform::new().get_column_declaration(&column), "`id` BINARY(16)");
+        assert_eq!(PostgresPlatform.get_column_declaration(&column), "\"id\" UUID");
+        let column = Column::new("id", SqlType::Uuid).uuid_storage(UuidStorage::Text);
+        assert_eq!(mysql.get_column_declaration(&column), "`id` CHAR(36)");
+    }
+
+    #[test]
+    fn test_compressed_column_declaration() {
+        let column = Column::new("payload", SqlType::Json).compressed(crate::core::Compression::Zstd);
+        assert_eq!(PostgresPlatform.get_column_declaration(&column), "\"payload\" BYTEA");
+        assert_eq!(MySqlPlatform::new().get_column_declaration(&column), "`payload` LONGBLOB");
+        assert_eq!(SqlitePlatform::new().get_column_declaration(&column), "\"payload\" BLOB");
+    }
+
+    #[test]
+    fn test_update_batch_sql() {
+        let rows = vec![vec![SqlValue::I64(1), "a".into()], vec![SqlValue::I64(2), "it's".into()]];
+        assert_eq!(
+            SqlitePlatform::new().get_update_batch_sql("users", &["id"], &["id", "name"], &rows),
+            ["UPDATE \"users\" SET \"name\" = CASE WHEN \"id\" = 1 THEN 'a' WHEN \"id\" = 2 THEN 'it''s' ELSE \"name\" END WHERE \"id\" IN (1, 2)"]
+        );
+        assert_eq!(
+            AnsiPlatform::new().get_update_batch_sql("t", &["a", "b"], &["a", "b", "c"], &[vec![1.into(), 2.into(), 3.into()]]),
+            ["UPDATE \"t\" SET \"c\" = CASE WHEN \"a\" = 1 AND \"b\" = 2 THEN 3 ELSE \"c\" END WHERE (\"a\" = 1 AND \"b\" = 2)"]
+        );
+        assert_eq!(
+            PostgresPlatform.get_update_batch_sql("users", &["id"], &["id", "name"], &rows),
+            ["UPDATE \"users\" SET \"name\" = \"batch_values\".\"name\" FROM (VALUES (1, 'a'), (2, 'it''s')) AS \"batch_values\"(\"id\", \"name\") \
+              WHERE \"users\".\"id\" = \"batch_values\".\"id\""]
+        );
+        assert_eq!(
+            MySqlPlatform::new().get_update_batch_sql("users", &["id"], &["id", "name"], &rows),
+            ["INSERT INTO `users` (`id`, `name`) VALUES (1, 'a'), (2, 'it''s') ON DUPLICATE KEY UPDATE `name` = VALUES(`name`)"]
+        );
+        assert!(PostgresPlatform.get_update_batch_sql("users", &["id"], &["id", "name"], &[]).is_empty());
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_postgres_update_batch_casts() {
+        let id = uuid::Uuid::nil();
+        let rows = vec![vec![SqlValue::Uuid(id), SqlValue::Null], vec![SqlValue::Uuid(id), SqlValue::I64(1)]];
+        let sql = PostgresPlatform.get_update_batch_sql("users", &["id"], &["id", "age"], &rows);
+        assert!(sql[0].contains("(VALUES (CAST('00000000-0000-0000-0000-000000000000' AS uuid), NULL), ('00000000-0000-0000-0000-000000000000', 1))"), "{}", sql[0]);
+    }
+
+    #[test]
+    fn test_enum_column_declaration() {
+        let role = Column::new("role", SqlType::varchar(10)).not_null().allowed_values(["admin", "it's"]);
+        assert_eq!(
+            SqlitePlatform::new().get_column_declaration(&role),
+            "\"role\" VARCHAR(10) NOT NULL CHECK (\"role\" IN ('admin', 'it''s'))"
+        );
+        assert_eq!(MySqlPlatform::new().get_column_declaration(&role), "`role` ENUM('admin', 'it''s') NOT NULL");
+        let priority = Column::new("priority", SqlType::Integer).allowed_values(["1", "2"]);
+        assert_eq!(
+            MySqlPlatform::new().get_column_declaration(&priority),
+            "`priority` INT CHECK (`priority` IN (1, 2))"
+        );
+
+        let native = role.native_enum("user_role");
+        assert_eq!(PostgresPlatform.get_column_declaration(&native), "\"role\" \"user_role\" NOT NULL");
+        let schema = Schema::new()
+            .table(Table::new("users").column(native.clone()))
+            .table(Table::new("invites").column(native));
+        let create = PostgresPlatform.get_create_schema_sql(&schema);
+        assert_eq!(create[0], "CREATE TYPE \"user_role\" AS ENUM ('admin', 'it''s')");
+        assert_eq!(create.len(), 3);
+        assert_eq!(PostgresPlatform.get_drop_schema_sql(&schema).last().unwrap(), "DROP TYPE \"user_role\"");
+        assert_eq!(
+            PostgresPlatform.get_add_enum_value_sql("user_role", "guest").unwrap(),
+            "ALTER TYPE \"user_role\" ADD VALUE IF NOT EXISTS 'guest'"
+        );
+        assert_eq!(SqlitePlatform::new().get_create_schema_sql(&schema).len(), 2);
+    }
+
+    #[test]
+    fn test_ansi_platform() {
+        let platform = AnsiPlatform::new();
+        assert_eq!(platform.name(), "ansi");
+        assert_eq!(platform.quote_identifier("users"), "\"users\"");
+        assert_eq!(platform.parameter_placeholder(1), "?");
+        assert_eq!(
+            AnsiPlatform::new().with_numbered_parameters(true).parameter_placeholder(1),
+            "$2"
+        );
+        assert_eq!(platform.get_type_declaration(&SqlType::Text), "CLOB");
+        assert_eq!(
+            platform.get_type_declaration(&SqlType::Serial),
+            "INTEGER GENERATED BY DEFAULT AS IDENTITY"
+        );
+        assert!(!platform.supports_returning());
+    }
 }
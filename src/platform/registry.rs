@@ -0,0 +1,158 @@
+//! Registry mapping driver names to platforms
+//!
+//! Lets plugins and applications look up the [`Platform`] for a driver name
+//! at runtime and register custom platforms.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::core::{ConnectionParams, Error, Result};
+
+use super::{AnsiPlatform, MySqlPlatform, Platform, PostgresPlatform, SqlitePlatform};
+
+/// Maps driver names (case-insensitive) to platform instances
+#[derive(Clone)]
+pub struct PlatformRegistry {
+    /// Platforms by lower-case driver name
+    platforms: HashMap<String, Arc<dyn Platform>>,
+}
+
+impl PlatformRegistry {
+    /// Create an empty registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            platforms: HashMap::new(),
+        }
+    }
+
+    /// Create a registry with the built-in platforms
+    ///
+    /// Registers `postgres`/`postgresql`/`pgsql`, `mysql`/`mariadb`,
+    /// `sqlite`/`sqlite3` and `ansi`.
+    #[must_use]
+    pub fn with_defaults() -> Self {
+        let postgres: Arc<dyn Platform> = Arc::new(PostgresPlatform);
+        let mysql: Arc<dyn Platform> = Arc::new(MySqlPlatform::new());
+        let sqlite: Arc<dyn Platform> = Arc::new(SqlitePlatform::new());
+
+        let mut registry = Self::new();
+        for name in ["postgres", "postgresql", "pgsql"] {
+            registry.register_shared(name, Arc::clone(&postgres));
+        }
+        for name in ["mysql", "mariadb"] {
+            registry.register_shared(name, Arc::clone(&mysql));
+        }
+        for name in ["sqlite", "sqlite3"] {
+            registry.register_shared(name, Arc::clone(&sqlite));
+        }
+        registry.register("ansi", AnsiPlatform::new());
+        registry
+    }
+
+    /// Register a platform for a driver name, replacing any existing one
+    pub fn register(&mut self, driver: &str, platform: impl Platform + 'static) {
+        self.register_shared(driver, Arc::new(platform));
+    }
+
+    /// Register a shared platform instance for a driver name
+    pub fn register_shared(&mut self, driver: &str, platform: Arc<dyn Platform>) {
+        self.platforms.insert(driver.to_lowercase(), platform);
+    }
+
+    /// Register a platform for a driver name (builder style)
+    #[must_use]
+    pub fn with_platform(mut self, driver: &str, platform: impl Platform + 'static) -> Self {
+        self.register(driver, platform);
+        self
+    }
+
+    /// Get the platform for a driver name
+    #[must_use]
+    pub fn get(&self, driver: &str) -> Option<Arc<dyn Platform>> {
+        self.platforms.get(&driver.to_lowercase()).cloned()
+    }
+
+    /// Get the platform for a driver name, adjusted to a server version
+    ///
+    /// Platforms whose capabilities vary by version (e.g. `MariaDB` 10.5+
+    /// accepting RETURNING) return a copy matching `version`, see
+    /// [`Platform::for_server_version`].
+    #[must_use]
+    pub fn for_server_version(&self, driver: &str, version: &str) -> Option<Arc<dyn Platform>> {
+        let platform = self.get(driver)?;
+        Some(platform.for_server_version(version).map_or(platform, Arc::from))
+    }
+
+    /// Get the platform for the driver of the connection parameters
+    ///
+    /// # Errors
+    ///
+    /// Returns a configuration error if no platform is registered for the driver.
+    pub fn for_params(&self, params: &ConnectionParams) -> Result<Arc<dyn Platform>> {
+        self.get(&params.driver).ok_or_else(|| {
+            Error::config(format!("No platform registered for driver '{}'", params.driver))
+        })
+    }
+
+    /// Get the registered driver names, sorted
+    #[must_use]
+    pub fn drivers(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.platforms.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl Default for PlatformRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+impl std::fmt::Debug for PlatformRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlatformRegistry")
+            .field("drivers", &self.drivers())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_platforms() {
+        let registry = PlatformRegistry::default();
+        assert_eq!(registry.get("PostgreSQL").map(|p| p.name()), Some("postgresql"));
+        assert_eq!(registry.get("mariadb").map(|p| p.name()), Some("mysql"));
+        assert!(registry.get("oracle").is_none());
+
+        let mariadb = registry.for_server_version("mariadb", "10.11.6-MariaDB").unwrap();
+        assert!(mariadb.supports_returning() && !registry.get("mariadb").unwrap().supports_returning());
+        assert_eq!(registry.for_server_version("sqlite", "3.46.0").map(|p| p.name()), Some("sqlite"));
+
+        let params = ConnectionParams::sqlite_memory();
+        assert_eq!(registry.for_params(&params).map(|p| p.name()).ok(), Some("sqlite"));
+        assert!(registry.for_params(&ConnectionParams::new("oracle")).is_err());
+    }
+
+    #[test]
+    fn test_register_custom_platform() {
+        /// Platform relying on the ANSI defaults
+        struct Custom;
+
+        impl Platform for Custom {
+            fn name(&self) -> &'static str {
+                "custom"
+            }
+        }
+
+        let registry = PlatformRegistry::new().with_platform("Custom", Custom);
+        assert_eq!(registry.drivers(), vec!["custom"]);
+
+        let platform = registry.get("custom").map(|p| p.quote_identifier("users"));
+        assert_eq!(platform.as_deref(), Some("\"users\""));
+    }
+}
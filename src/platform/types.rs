@@ -3,8 +3,12 @@
 //! These types represent SQL column types with their parameters
 //! for DDL generation and schema introspection.
 
+use alloc::{collections::BTreeMap, string::{String, ToString}, vec::Vec, format, vec};
+use crate::core::{Compression, SqlEnum, SqlValue};
+
 /// SQL column type with optional parameters
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SqlType {
     // Integer types
     /// SMALLINT (2 bytes)
@@ -82,7 +86,7 @@ pub enum SqlType {
     },
 
     // Special types
-    /// UUID (native or CHAR(36))
+    /// UUID (native, CHAR(36) or 16 bytes, see [`UuidStorage`])
     Uuid,
     /// JSON/JSONB
     Json,
@@ -165,10 +169,239 @@ impl SqlType {
     pub const fn is_auto_increment(&self) -> bool {
         matches!(self, Self::Serial | Self::BigSerial)
     }
+
+    /// Parse a type declaration as reported by schema introspection
+    ///
+    /// Understands the declarations generated by the built-in platforms
+    /// (e.g. `VARCHAR(255)`, `NUMERIC(10, 2)`, `TIMESTAMP(3) WITH TIME ZONE`,
+    /// `TINYINT(1)`). Returns `None` for unknown types.
+    #[must_use]
+    pub fn from_declaration(declaration: &str) -> Option<Self> {
+        let upper = declaration.trim().to_uppercase();
+
+        // Split "NAME(args) SUFFIX" into base name and arguments
+        let (base, args) = match (upper.find('('), upper.find(')')) {
+            (Some(open), Some(close)) if open < close => {
+                let base = format!("{} {}", &upper[..open], &upper[close + 1..]);
+                let args: Vec<u32> = upper[open + 1..close]
+                    .split(',')
+                    .filter_map(|arg| arg.trim().parse().ok())
+                    .collect();
+                (base, args)
+            }
+            _ => (upper, Vec::new()),
+        };
+        let base = base.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let first = args.first().copied();
+        let length = |default: u32| first.unwrap_or(default);
+        let precision = first.and_then(|p| u8::try_from(p).ok());
+
+        let sql_type = match base.as_str() {
+            "SMALLINT" | "INT2" => Self::SmallInt,
+            "INTEGER" | "INT" | "INT4" | "MEDIUMINT" => Self::Integer,
+            "BIGINT" | "INT8" => Self::BigInt,
+            "REAL" | "FLOAT" | "FLOAT4" => Self::Float,
+            "DOUBLE" | "DOUBLE PRECISION" | "FLOAT8" => Self::Double,
+            "DECIMAL" | "NUMERIC" => Self::Decimal {
+                precision: precision.unwrap_or(10),
+                scale: args.get(1).and_then(|s| u8::try_from(*s).ok()).unwrap_or(0),
+            },
+            "CHAR" | "CHARACTER" | "BPCHAR" => Self::Char { length: length(1) },
+            "VARCHAR" | "CHARACTER VARYING" | "NVARCHAR" => Self::Varchar { length: length(255) },
+            "TEXT" | "CLOB" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" => Self::Text,
+            "BINARY" => Self::Binary { length: length(1) },
+            "VARBINARY" => Self::VarBinary { length: length(255) },
+            "BLOB" | "BYTEA" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" => Self::Blob,
+            "TINYINT" if first == Some(1) => Self::Boolean,
+            "BOOLEAN" | "BOOL" => Self::Boolean,
+            "DATE" => Self::Date,
+            "TIME" | "TIME WITHOUT TIME ZONE" => Self::Time { precision },
+            "TIMESTAMP" | "DATETIME" | "TIMESTAMP WITHOUT TIME ZONE" => {
+                Self::Timestamp { precision }
+            }
+            "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => Self::TimestampTz { precision },
+            "UUID" => Self::Uuid,
+            "JSON" | "JSONB" => Self::Json,
+            "SERIAL" => Self::Serial,
+            "BIGSERIAL" => Self::BigSerial,
+            _ => return None,
+        };
+
+        Some(sql_type)
+    }
+}
+
+/// How UUIDs are stored on platforms without a native UUID type
+///
+/// Platforms with a native type (`PostgreSQL`) always use it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UuidStorage {
+    /// 36 character text (`CHAR(36)` on `MySQL`)
+    #[default]
+    Text,
+    /// 16 raw bytes (`BINARY(16)` on `MySQL`, `BLOB` on `SQLite`)
+    Binary,
+}
+
+/// How a database folds the case of identifiers
+///
+/// Quoted identifiers (`"Users"`, `` `Users` `` or `[Users]`) always keep
+/// their case; the policy decides what happens to unquoted ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum IdentifierCase {
+    /// Identifiers keep their case as written
+    #[default]
+    PreserveQuoted,
+    /// Unquoted identifiers are folded to lowercase (`PostgreSQL`)
+    LowerUnquoted,
+    /// Unquoted identifiers are folded to uppercase (Oracle)
+    UpperUnquoted,
+}
+
+impl IdentifierCase {
+    /// Get the name the database stores for an identifier
+    ///
+    /// Quotes are removed from quoted identifiers, unquoted ones are folded
+    /// according to the policy. Each part of a qualified name is handled on
+    /// its own, so `app."Users"` becomes `app.Users` under
+    /// [`LowerUnquoted`](Self::LowerUnquoted).
+    #[must_use]
+    pub fn normalize(self, identifier: &str) -> String {
+        identifier
+            .split('.')
+            .map(|part| match unquoted(part) {
+                Some(name) => name.to_string(),
+                None => match self {
+                    Self::PreserveQuoted => part.to_string(),
+                    Self::LowerUnquoted => part.to_lowercase(),
+                    Self::UpperUnquoted => part.to_uppercase(),
+                },
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Check if two identifiers name the same object under this policy
+    #[must_use]
+    pub fn same(self, a: &str, b: &str) -> bool {
+        a == b || self.normalize(a) == self.normalize(b)
+    }
+}
+
+/// Get the name inside a quoted identifier part
+fn unquoted(part: &str) -> Option<&str> {
+    ['"', '`', '[']
+        .into_iter()
+        .find(|quote| part.starts_with(*quote))
+        .and_then(|quote| part.strip_prefix(quote)?.strip_suffix(if quote == '[' { ']' } else { quote }))
+}
+
+/// Default value of a column
+///
+/// Values are quoted by the platform when DDL is rendered, so they are safe
+/// to take from user input; expressions are inserted verbatim. Introspected
+/// defaults are parsed with
+/// [`Platform::normalize_default`](super::Platform::normalize_default).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DefaultValue {
+    /// A constant value, rendered as a platform literal
+    Value(SqlValue),
+    /// A SQL expression, rendered verbatim
+    Expression(String),
+    /// The current date and time (`CURRENT_TIMESTAMP`, `now()`)
+    CurrentTimestamp,
+    /// An explicit `DEFAULT NULL`
+    Null,
+}
+
+impl DefaultValue {
+    /// Create a default value from a constant
+    #[must_use]
+    pub fn value(value: impl Into<SqlValue>) -> Self {
+        Self::Value(value.into())
+    }
+
+    /// Parse a default value as written in SQL for a column of the given type
+    ///
+    /// Strips surrounding parentheses and `PostgreSQL` casts (`'a'::text`),
+    /// unquotes string literals, reads numbers and booleans according to the
+    /// column type and recognizes `NULL` and the current timestamp
+    /// functions. Anything else is kept as an expression. Returns `None` for
+    /// an empty string.
+    #[must_use]
+    pub fn parse(sql: &str, sql_type: &SqlType) -> Option<Self> {
+        let mut value = sql.trim();
+        while let Some(inner) = value
+            .strip_prefix('(')
+            .and_then(|v| v.strip_suffix(')'))
+            .or_else(|| strip_cast(value))
+        {
+            value = inner.trim();
+        }
+        if value.is_empty() {
+            return None;
+        }
+
+        if let Some(text) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            let text = text.replace("''", "'");
+            return Some(typed_value(&text, sql_type).unwrap_or(Self::Value(SqlValue::String(text))));
+        }
+        if value.eq_ignore_ascii_case("NULL") {
+            return Some(Self::Null);
+        }
+        if is_current_timestamp(value) {
+            return Some(Self::CurrentTimestamp);
+        }
+        if sql_type.is_string() && value.parse::<f64>().is_ok() {
+            return Some(Self::Value(SqlValue::String(value.to_string())));
+        }
+        Some(typed_value(value, sql_type).unwrap_or_else(|| Self::Expression(value.to_string())))
+    }
+}
+
+impl From<SqlValue> for DefaultValue {
+    fn from(value: SqlValue) -> Self {
+        Self::Value(value)
+    }
+}
+
+/// Remove a trailing `::type` cast, unless the `::` is inside a string or call
+fn strip_cast(value: &str) -> Option<&str> {
+    let index = value.rfind("::")?;
+    let (expression, cast) = value.split_at(index);
+    let balanced = expression.matches('(').count() == expression.matches(')').count();
+    (balanced && !cast.contains('\'')).then_some(expression)
+}
+
+/// Check if an expression spells the current timestamp
+fn is_current_timestamp(value: &str) -> bool {
+    let name = value.strip_suffix("()").unwrap_or(value);
+    ["CURRENT_TIMESTAMP", "NOW", "LOCALTIMESTAMP"].iter().any(|f| name.eq_ignore_ascii_case(f))
+}
+
+/// Read a number or boolean for a column of the given type
+fn typed_value(text: &str, sql_type: &SqlType) -> Option<DefaultValue> {
+    let value = match sql_type {
+        SqlType::Boolean => match text.to_ascii_lowercase().as_str() {
+            "1" | "true" | "t" | "b'1'" => SqlValue::Bool(true),
+            "0" | "false" | "f" | "b'0'" => SqlValue::Bool(false),
+            _ => return None,
+        },
+        _ if sql_type.is_string() => return None,
+        _ => match text.parse::<i64>() {
+            Ok(number) => SqlValue::I64(number),
+            Err(_) => SqlValue::F64(text.parse().ok().filter(|_| sql_type.is_numeric())?),
+        },
+    };
+    Some(DefaultValue::Value(value))
 }
 
 /// Column definition for schema operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Column {
     /// Column name
     pub name: String,
@@ -176,12 +409,31 @@ pub struct Column {
     pub sql_type: SqlType,
     /// Whether the column allows NULL values
     pub nullable: bool,
-    /// Default value expression (as SQL string)
-    pub default: Option<String>,
+    /// Default value
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub default: Option<DefaultValue>,
     /// Whether this column auto-increments
+    #[cfg_attr(feature = "serde", serde(default))]
     pub auto_increment: bool,
     /// Column comment
+    #[cfg_attr(feature = "serde", serde(default))]
     pub comment: Option<String>,
+    /// UUID storage, overrides the platform setting for UUID columns
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub uuid_storage: Option<UuidStorage>,
+    /// Compression of the values, which makes the column binary
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub compression: Option<Compression>,
+    /// Values the column is restricted to, empty for any value
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub enum_values: Vec<String>,
+    /// Name of the platform enum type declaring the column (`PostgreSQL`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub enum_type: Option<String>,
+    /// Platform-specific attributes, rendered by the platform that knows them
+    /// (e.g. `on_update` for `MySQL`) and ignored by others
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub platform_options: BTreeMap<String, String>,
 }
 
 impl Column {
@@ -195,6 +447,11 @@ impl Column {
             default: None,
             auto_increment: false,
             comment: None,
+            uuid_storage: None,
+            compression: None,
+            enum_values: Vec::new(),
+            enum_type: None,
+            platform_options: BTreeMap::new(),
         }
     }
 
@@ -205,13 +462,34 @@ impl Column {
         self
     }
 
-    /// Set a default value
+    /// Set a default value, quoted by the platform when rendering DDL
+    #[must_use]
+    pub fn default_value(mut self, value: impl Into<SqlValue>) -> Self {
+        self.default = Some(DefaultValue::Value(value.into()));
+        self
+    }
+
+    /// Set a default SQL expression, inserted into DDL verbatim
     #[must_use]
-    pub fn default(mut self, value: impl Into<String>) -> Self {
-        self.default = Some(value.into());
+    pub fn default_expression(mut self, sql: impl Into<String>) -> Self {
+        self.default = Some(DefaultValue::Expression(sql.into()));
         self
     }
 
+    /// Default to the current date and time
+    #[must_use]
+    pub fn default_current_timestamp(mut self) -> Self {
+        self.default = Some(DefaultValue::CurrentTimestamp);
+        self
+    }
+
+    /// Set a default value as raw SQL
+    #[deprecated(note = "use `default_value` or `default_expression`")]
+    #[must_use]
+    pub fn default(self, sql: impl Into<String>) -> Self {
+        self.default_expression(sql)
+    }
+
     /// Set as auto-incrementing
     #[must_use]
     pub const fn auto_increment(mut self) -> Self {
@@ -225,18 +503,86 @@ impl Column {
         self.comment = Some(comment.into());
         self
     }
+
+    /// Set how a UUID column is stored
+    #[must_use]
+    pub const fn uuid_storage(mut self, storage: UuidStorage) -> Self {
+        self.uuid_storage = Some(storage);
+        self
+    }
+
+    /// Store compressed values, see [`Compressed`](crate::core::Compressed)
+    ///
+    /// The column is declared with the platform's binary type regardless
+    /// of its SQL type.
+    #[must_use]
+    pub const fn compressed(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Restrict the column to a set of values
+    ///
+    /// Declared as `ENUM(...)` on `MySQL` text columns, with the type set by
+    /// [`native_enum`](Self::native_enum) on `PostgreSQL`, and with a CHECK
+    /// constraint otherwise.
+    #[must_use]
+    pub fn allowed_values<I>(mut self, values: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.enum_values = values.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Restrict the column to the values of a [`SqlEnum`](crate::core::SqlEnum)
+    #[must_use]
+    pub fn sql_enum<E: SqlEnum>(self) -> Self {
+        self.allowed_values(E::sql_values().into_iter().filter_map(SqlValue::into_string))
+    }
+
+    /// Declare the column with a named enum type on platforms that have
+    /// them (`PostgreSQL`)
+    ///
+    /// The type is created with the schema, see
+    /// [`Platform::get_create_schema_sql`](super::Platform::get_create_schema_sql).
+    #[must_use]
+    pub fn native_enum(mut self, type_name: impl Into<String>) -> Self {
+        self.enum_type = Some(type_name.into());
+        self
+    }
+
+    /// Set a platform-specific attribute
+    ///
+    /// See the platform documentation for the recognized names, e.g.
+    /// [`MySqlPlatform`](super::MySqlPlatform).
+    #[must_use]
+    pub fn platform_option(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.platform_options.insert(name.into(), value.into());
+        self
+    }
+
+    /// Get a platform-specific attribute
+    #[must_use]
+    pub fn get_platform_option(&self, name: &str) -> Option<&str> {
+        self.platform_options.get(name).map(String::as_str)
+    }
 }
 
 /// Index definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Index {
     /// Index name
     pub name: String,
     /// Column names in the index
     pub columns: Vec<String>,
     /// Whether this is a unique index
+    #[cfg_attr(feature = "serde", serde(default))]
     pub unique: bool,
     /// Whether this is the primary key
+    #[cfg_attr(feature = "serde", serde(default))]
     pub primary: bool,
 }
 
@@ -276,7 +622,8 @@ impl Index {
 }
 
 /// Foreign key definition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ForeignKey {
     /// Constraint name
     pub name: String,
@@ -287,13 +634,16 @@ pub struct ForeignKey {
     /// Referenced column names
     pub foreign_columns: Vec<String>,
     /// ON DELETE action
+    #[cfg_attr(feature = "serde", serde(default))]
     pub on_delete: ForeignKeyAction,
     /// ON UPDATE action
+    #[cfg_attr(feature = "serde", serde(default))]
     pub on_update: ForeignKeyAction,
 }
 
 /// Foreign key referential action
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ForeignKeyAction {
     /// No action (error if referenced row is modified)
     #[default]
@@ -322,18 +672,54 @@ impl ForeignKeyAction {
     }
 }
 
+/// Privilege on a table, see [`Platform::get_grant_sql`](super::Platform::get_grant_sql)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Privilege {
+    /// Read rows
+    Select,
+    /// Insert rows
+    Insert,
+    /// Update rows
+    Update,
+    /// Delete rows
+    Delete,
+    /// Reference the table from foreign keys
+    References,
+    /// All privileges on the table
+    All,
+}
+
+impl Privilege {
+    /// Get the SQL representation
+    #[must_use]
+    pub const fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Select => "SELECT",
+            Self::Insert => "INSERT",
+            Self::Update => "UPDATE",
+            Self::Delete => "DELETE",
+            Self::References => "REFERENCES",
+            Self::All => "ALL PRIVILEGES",
+        }
+    }
+}
+
 /// Table definition for schema operations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Table {
     /// Table name
     pub name: String,
     /// Columns
     pub columns: Vec<Column>,
     /// Indexes (including primary key)
+    #[cfg_attr(feature = "serde", serde(default))]
     pub indexes: Vec<Index>,
     /// Foreign keys
+    #[cfg_attr(feature = "serde", serde(default))]
     pub foreign_keys: Vec<ForeignKey>,
     /// Table comment
+    #[cfg_attr(feature = "serde", serde(default))]
     pub comment: Option<String>,
 }
 
@@ -388,6 +774,126 @@ impl Table {
     }
 }
 
+/// A set of tables created or dropped together
+///
+/// See [`Platform::get_create_schema_sql`](super::Platform::get_create_schema_sql)
+/// for how foreign keys between the tables order the statements.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Schema {
+    /// Tables of the schema
+    pub tables: Vec<Table>,
+}
+
+/// Creation order of a schema's tables, see [`Schema::creation_order`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreationOrder<'a> {
+    /// Tables ordered so referenced tables come first
+    pub tables: Vec<&'a Table>,
+    /// Foreign keys closing a reference cycle, with the name of their table
+    ///
+    /// They reference a table created later and have to be added after
+    /// all tables exist.
+    pub deferred_foreign_keys: Vec<(&'a str, &'a ForeignKey)>,
+}
+
+impl Schema {
+    /// Create an empty schema
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { tables: Vec::new() }
+    }
+
+    /// Add a table
+    #[must_use]
+    pub fn table(mut self, table: Table) -> Self {
+        self.tables.push(table);
+        self
+    }
+
+    /// Get a table by name (case-insensitive)
+    #[must_use]
+    pub fn get_table(&self, name: &str) -> Option<&Table> {
+        self.tables.iter().find(|table| table.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Get the named enum types of the columns with their values, see
+    /// [`Column::native_enum`]
+    ///
+    /// A type used by several columns takes the values of the first.
+    #[must_use]
+    pub fn enum_types(&self) -> BTreeMap<&str, &[String]> {
+        let mut types = BTreeMap::new();
+        for column in self.tables.iter().flat_map(|table| &table.columns) {
+            if let Some(name) = column.enum_type.as_deref().filter(|_| !column.enum_values.is_empty()) {
+                types.entry(name).or_insert(column.enum_values.as_slice());
+            }
+        }
+        types
+    }
+
+    /// Order the tables by their foreign keys
+    ///
+    /// A table comes after the tables it references; references to itself
+    /// and to tables outside the schema are ignored. Tables referencing each
+    /// other in a cycle keep their given order, and the foreign keys of the
+    /// first table pointing at tables not created yet are deferred.
+    #[must_use]
+    pub fn creation_order(&self) -> CreationOrder<'_> {
+        let mut pending: Vec<&Table> = self.tables.iter().collect();
+        let mut order = CreationOrder { tables: Vec::with_capacity(pending.len()), deferred_foreign_keys: Vec::new() };
+        let waits_for = |table: &Table, fk: &ForeignKey, pending: &[&Table]| {
+            !fk.foreign_table.eq_ignore_ascii_case(&table.name)
+                && pending.iter().any(|other| other.name.eq_ignore_ascii_case(&fk.foreign_table))
+        };
+
+        while !pending.is_empty() {
+            let ready = pending
+                .iter()
+                .position(|table| !table.foreign_keys.iter().any(|fk| waits_for(table, fk, &pending)));
+            // Stuck on a cycle: break it at its first table
+            let next = ready.or_else(|| pending.iter().position(|table| in_cycle(table, &pending)));
+            let table = pending.remove(next.unwrap_or(0));
+            if ready.is_none() {
+                for fk in table.foreign_keys.iter().filter(|fk| waits_for(table, fk, &pending)) {
+                    order.deferred_foreign_keys.push((&table.name, fk));
+                }
+            }
+            order.tables.push(table);
+        }
+        order
+    }
+}
+
+/// Check if a table reaches itself through foreign keys between pending tables
+fn in_cycle(table: &Table, pending: &[&Table]) -> bool {
+    let mut visited: Vec<&str> = Vec::new();
+    let mut stack = vec![table];
+    while let Some(current) = stack.pop() {
+        for fk in &current.foreign_keys {
+            if fk.foreign_table.eq_ignore_ascii_case(&current.name)
+                || visited.iter().any(|name| name.eq_ignore_ascii_case(&fk.foreign_table))
+            {
+                continue;
+            }
+            if fk.foreign_table.eq_ignore_ascii_case(&table.name) {
+                return true;
+            }
+            if let Some(next) = pending.iter().find(|other| other.name.eq_ignore_ascii_case(&fk.foreign_table)) {
+                visited.push(&next.name);
+                stack.push(next);
+            }
+        }
+    }
+    false
+}
+
+impl From<Vec<Table>> for Schema {
+    fn from(tables: Vec<Table>) -> Self {
+        Self { tables }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -420,6 +926,25 @@ mod tests {
         assert!(!SqlType::Integer.is_datetime());
     }
 
+    #[test]
+    fn test_sql_type_from_declaration() {
+        assert_eq!(SqlType::from_declaration("VARCHAR(255)"), Some(SqlType::varchar(255)));
+        assert_eq!(SqlType::from_declaration("numeric(10, 2)"), Some(SqlType::decimal(10, 2)));
+        assert_eq!(SqlType::from_declaration("DECIMAL(10,2)"), Some(SqlType::decimal(10, 2)));
+        assert_eq!(SqlType::from_declaration("TINYINT(1)"), Some(SqlType::Boolean));
+        assert_eq!(SqlType::from_declaration("TINYINT(4)"), None);
+        assert_eq!(
+            SqlType::from_declaration("DATETIME"),
+            Some(SqlType::Timestamp { precision: None })
+        );
+        assert_eq!(
+            SqlType::from_declaration("TIMESTAMP(3) WITH TIME ZONE"),
+            Some(SqlType::TimestampTz { precision: Some(3) })
+        );
+        assert_eq!(SqlType::from_declaration("character varying"), Some(SqlType::varchar(255)));
+        assert_eq!(SqlType::from_declaration("GEOMETRY"), None);
+    }
+
     #[test]
     fn test_column_builder() {
         let col = Column::new("id", SqlType::Integer)
@@ -429,6 +954,31 @@ mod tests {
         assert_eq!(col.name, "id");
         assert!(!col.nullable);
         assert!(col.auto_increment);
+
+        let col = Column::new("status", SqlType::Text).default_value("x'); DROP TABLE t; --");
+        assert_eq!(col.default, Some(DefaultValue::Value(SqlValue::String("x'); DROP TABLE t; --".into()))));
+        #[allow(deprecated)]
+        let col = Column::new("status", SqlType::Text).default("'active'");
+        assert_eq!(col.default, Some(DefaultValue::Expression("'active'".to_string())));
+    }
+
+    #[test]
+    fn test_default_value_parse() {
+        let parse = |sql: &str, sql_type: SqlType| DefaultValue::parse(sql, &sql_type);
+        assert_eq!(parse("('active'::character varying)", SqlType::varchar(20)), Some(DefaultValue::value("active")));
+        assert_eq!(parse("'it''s'", SqlType::Text), Some(DefaultValue::value("it's")));
+        assert_eq!(parse("0", SqlType::Text), Some(DefaultValue::value("0")));
+        assert_eq!(parse("'42'::integer", SqlType::Integer), Some(DefaultValue::value(42_i64)));
+        assert_eq!(parse("1.5", SqlType::Double), Some(DefaultValue::value(1.5)));
+        assert_eq!(parse("b'1'", SqlType::Boolean), Some(DefaultValue::value(true)));
+        assert_eq!(parse("false", SqlType::Boolean), Some(DefaultValue::value(false)));
+        assert_eq!(parse("now()", SqlType::TimestampTz { precision: None }), Some(DefaultValue::CurrentTimestamp));
+        assert_eq!(parse("NULL::character varying", SqlType::Text), Some(DefaultValue::Null));
+        assert_eq!(
+            parse("nextval('users_id_seq'::regclass)", SqlType::Integer),
+            Some(DefaultValue::Expression("nextval('users_id_seq'::regclass)".to_string()))
+        );
+        assert_eq!(parse(" ", SqlType::Text), None);
     }
 
     #[test]
@@ -448,9 +998,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_schema_creation_order() {
+        let fk = |name: &str, table: &str| ForeignKey {
+            name: name.to_string(),
+            local_columns: vec![format!("{table}_id")],
+            foreign_table: table.to_string(),
+            foreign_columns: vec!["id".to_string()],
+            on_delete: ForeignKeyAction::NoAction,
+            on_update: ForeignKeyAction::NoAction,
+        };
+        let schema = Schema::new()
+            .table(Table::new("comments").foreign_key(fk("fk_comments_posts", "posts")))
+            .table(Table::new("posts").foreign_key(fk("fk_posts_users", "users")).foreign_key(fk("fk_posts_parent", "posts")))
+            .table(Table::new("users").foreign_key(fk("fk_users_teams", "teams")))
+            .table(Table::new("teams").foreign_key(fk("fk_teams_owner", "users")).foreign_key(fk("fk_teams_org", "orgs")));
+
+        let order = schema.creation_order();
+        let names: Vec<&str> = order.tables.iter().map(|table| table.name.as_str()).collect();
+        // users and teams reference each other: the cycle is broken at users, given first
+        assert_eq!(names, ["users", "posts", "comments", "teams"]);
+        assert_eq!(order.deferred_foreign_keys.len(), 1);
+        assert_eq!((order.deferred_foreign_keys[0].0, order.deferred_foreign_keys[0].1.name.as_str()), ("users", "fk_users_teams"));
+        assert!(schema.get_table("POSTS").is_some());
+    }
+
+    #[test]
+    fn test_identifier_case() {
+        assert_eq!(IdentifierCase::LowerUnquoted.normalize("Users"), "users");
+        assert_eq!(IdentifierCase::LowerUnquoted.normalize("\"Users\""), "Users");
+        assert_eq!(IdentifierCase::UpperUnquoted.normalize("app.users"), "APP.USERS");
+        assert_eq!(IdentifierCase::UpperUnquoted.normalize("app.`users`"), "APP.users");
+        assert_eq!(IdentifierCase::PreserveQuoted.normalize("[Users]"), "Users");
+        assert_eq!(IdentifierCase::PreserveQuoted.normalize("Users"), "Users");
+
+        assert!(IdentifierCase::LowerUnquoted.same("Users", "users"));
+        assert!(!IdentifierCase::LowerUnquoted.same("\"Users\"", "users"));
+        assert!(IdentifierCase::UpperUnquoted.same("users", "\"USERS\""));
+        assert!(!IdentifierCase::PreserveQuoted.same("Users", "users"));
+    }
+
     #[test]
     fn test_foreign_key_action() {
         assert_eq!(ForeignKeyAction::Cascade.as_sql(), "CASCADE");
         assert_eq!(ForeignKeyAction::SetNull.as_sql(), "SET NULL");
     }
+
+    #[cfg(all(feature = "serde", feature = "json"))]
+    #[test]
+    fn test_schema_serde_round_trip() {
+        let schema = Schema {
+            tables: vec![Table::new("users")
+                .column(Column::new("id", SqlType::BigInt).not_null())
+                .column(Column::new("name", SqlType::varchar(100)).default_value("anonymous"))
+                .index(Index::primary(vec!["id".to_string()]))],
+        };
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(serde_json::from_str::<Schema>(&json).unwrap(), schema);
+
+        // Optional fields may be left out
+        let table: Table = serde_json::from_str(
+            r#"{"name": "users", "columns": [{"name": "id", "sql_type": "BigInt", "nullable": false}]}"#,
+        )
+        .unwrap();
+        assert_eq!(table, Table::new("users").column(Column::new("id", SqlType::BigInt).not_null()));
+    }
 }
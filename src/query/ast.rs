@@ -0,0 +1,937 @@
+//! Statement tree between the query builder and the rendered SQL
+//!
+//! [`QueryBuilder::to_statement`](super::QueryBuilder::to_statement) produces
+//! a [`Statement`] holding final table names and unquoted identifiers. A
+//! [`SqlRenderer`] walks the tree and renders each node; its default methods
+//! produce the SQL of [`PlatformRenderer`], and a dialect overrides just the
+//! nodes it renders differently (e.g. `TOP n` or `OFFSET ... FETCH`).
+
+use alloc::{boxed::Box, string::{String, ToString}, vec::Vec, format};
+use crate::core::{Error, Result};
+use crate::platform::{AnsiPlatform, Platform};
+use super::expr::Expr;
+
+/// JOIN type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinType {
+    /// INNER JOIN
+    Inner,
+    /// LEFT OUTER JOIN
+    Left,
+    /// RIGHT OUTER JOIN
+    Right,
+    /// FULL OUTER JOIN
+    Full,
+    /// CROSS JOIN
+    Cross,
+}
+
+impl JoinType {
+    /// Get the SQL representation of this join type
+    #[must_use]
+    pub const fn as_sql(self) -> &'static str {
+        match self {
+            Self::Inner => "INNER JOIN",
+            Self::Left => "LEFT JOIN",
+            Self::Right => "RIGHT JOIN",
+            Self::Full => "FULL JOIN",
+            Self::Cross => "CROSS JOIN",
+        }
+    }
+}
+
+/// ORDER BY direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrderDirection {
+    /// Ascending order (A-Z, 0-9)
+    #[default]
+    Asc,
+    /// Descending order (Z-A, 9-0)
+    Desc,
+}
+
+impl OrderDirection {
+    /// Get the SQL representation of this order direction
+    #[must_use]
+    pub const fn as_sql(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// Row lock taken by a SELECT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowLock {
+    /// FOR UPDATE: wait for rows locked by other transactions
+    Update,
+    /// FOR UPDATE SKIP LOCKED: leave out rows locked by other transactions
+    UpdateSkipLocked,
+}
+
+/// A SQL statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// SELECT statement
+    Select(SelectStatement),
+    /// INSERT statement
+    Insert(InsertStatement),
+    /// UPDATE statement
+    Update(UpdateStatement),
+    /// DELETE statement
+    Delete(DeleteStatement),
+}
+
+/// A SELECT statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectStatement {
+    /// Whether duplicate rows are removed
+    pub distinct: bool,
+    /// Selected items, `*` if empty
+    pub projection: Vec<SelectItem>,
+    /// Table or subquery selected from
+    pub from: TableSource,
+    /// Alias of the table or subquery
+    pub alias: Option<String>,
+    /// JOIN clauses
+    pub joins: Vec<Join>,
+    /// WHERE condition
+    pub filter: Option<Expr>,
+    /// GROUP BY columns
+    pub group_by: Vec<String>,
+    /// HAVING condition
+    pub having: Option<Expr>,
+    /// ORDER BY clauses
+    pub order_by: Vec<OrderBy>,
+    /// Maximum number of rows
+    pub limit: Option<u64>,
+    /// Number of rows skipped
+    pub offset: Option<u64>,
+    /// Row lock taken on the selected rows
+    pub lock: Option<RowLock>,
+}
+
+/// An item of the SELECT list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectItem {
+    /// All columns (`*`)
+    Wildcard,
+    /// A column, quoted when rendered
+    Column(String),
+    /// A raw SQL expression, rendered verbatim
+    Raw(String),
+}
+
+/// What a SELECT reads from
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableSource {
+    /// A table, quoted when rendered
+    Table(String),
+    /// A subquery, rendered in parentheses
+    Subquery(Box<Statement>),
+    /// A literal table of rows, named by the SELECT alias and these columns
+    Values {
+        /// Rows of values
+        rows: Vec<Vec<Expr>>,
+        /// Column names
+        columns: Vec<String>,
+    },
+}
+
+/// A JOIN clause
+///
+/// Created with [`Join::new`] or [`Join::lateral`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Join {
+    /// Type of join (INNER, LEFT, etc.)
+    pub kind: JoinType,
+    /// Table to join
+    pub table: String,
+    /// Optional table alias
+    pub alias: Option<String>,
+    /// Correlated subquery joined with LATERAL in place of `table`
+    pub lateral: Option<Box<Statement>>,
+    /// Join condition
+    pub condition: Expr,
+}
+
+impl Join {
+    /// Create a join of a table
+    #[must_use]
+    pub fn new(kind: JoinType, table: impl Into<String>, condition: Expr) -> Self {
+        Self {
+            kind,
+            table: table.into(),
+            alias: None,
+            lateral: None,
+            condition,
+        }
+    }
+
+    /// Create a LATERAL join of a correlated subquery
+    ///
+    /// Platforms without [`Platform::supports_lateral_join`] reject it in
+    /// [`SqlRenderer::try_render`]; [`SqlRenderer::render`] renders it as-is
+    /// and the database rejects it.
+    #[must_use]
+    pub fn lateral(kind: JoinType, subquery: Statement, alias: impl Into<String>, condition: Expr) -> Self {
+        Self {
+            kind,
+            table: String::new(),
+            alias: Some(alias.into()),
+            lateral: Some(Box::new(subquery)),
+            condition,
+        }
+    }
+
+    /// Set the table alias
+    #[must_use]
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.alias = Some(alias.into());
+        self
+    }
+}
+
+/// An ORDER BY clause
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderBy {
+    /// Column to order by
+    pub column: String,
+    /// Sort direction
+    pub direction: OrderDirection,
+}
+
+/// An INSERT statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertStatement {
+    /// Table inserted into
+    pub table: String,
+    /// Inserted columns, all columns in table order if empty
+    pub columns: Vec<String>,
+    /// Inserted rows
+    pub rows: Vec<Vec<Expr>>,
+    /// RETURNING columns
+    pub returning: Vec<String>,
+}
+
+/// An UPDATE statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateStatement {
+    /// Table updated
+    pub table: String,
+    /// Column-value assignments of the SET clause
+    pub assignments: Vec<(String, Expr)>,
+    /// Rows affected
+    pub filter: ModifyFilter,
+    /// RETURNING columns
+    pub returning: Vec<String>,
+}
+
+/// A DELETE statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteStatement {
+    /// Table deleted from
+    pub table: String,
+    /// Rows affected
+    pub filter: ModifyFilter,
+    /// RETURNING columns
+    pub returning: Vec<String>,
+}
+
+/// The rows an UPDATE or DELETE affects
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModifyFilter {
+    /// WHERE condition
+    pub condition: Option<Expr>,
+    /// ORDER BY clauses
+    pub order_by: Vec<OrderBy>,
+    /// Maximum number of rows
+    pub limit: Option<u64>,
+    /// Number of rows skipped
+    pub offset: Option<u64>,
+    /// Key column for emulating ORDER BY/LIMIT
+    pub limit_key: Option<String>,
+}
+
+impl ModifyFilter {
+    /// Check if ORDER BY, LIMIT or OFFSET is present
+    #[must_use]
+    pub fn is_limited(&self) -> bool {
+        !self.order_by.is_empty() || self.limit.is_some() || self.offset.is_some()
+    }
+
+    /// Get a SELECT of `projection` reading the rows the filter affects in `table`
+    ///
+    /// Used to emulate RETURNING by reading rows before or after a change.
+    #[must_use]
+    pub fn select(&self, table: &str, projection: Vec<SelectItem>) -> SelectStatement {
+        SelectStatement {
+            distinct: false,
+            projection,
+            from: TableSource::Table(table.to_string()),
+            alias: None,
+            joins: Vec::new(),
+            filter: self.condition.clone(),
+            group_by: Vec::new(),
+            having: None,
+            order_by: self.order_by.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            lock: None,
+        }
+    }
+}
+
+/// How ORDER BY/LIMIT of an UPDATE or DELETE is rendered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModifyLimit {
+    /// No ORDER BY/LIMIT present
+    None,
+    /// Appended directly to the statement (`MySQL`)
+    Native,
+    /// Emulated via `key IN (SELECT key ... LIMIT n)` using the given key column
+    Emulated(String),
+}
+
+impl Statement {
+    /// Rename every table the statement reads or writes
+    ///
+    /// Covers the main table, joined tables and subqueries, e.g. to apply a
+    /// tenant's table prefix to parsed SQL:
+    /// `statement.map_tables(&|table| tenant.table_name(table).into_owned())`.
+    /// Table names inside raw expressions are left unchanged.
+    #[must_use]
+    pub fn map_tables(self, rename: &dyn Fn(&str) -> String) -> Self {
+        match self {
+            Self::Select(mut select) => {
+                select.from = match select.from {
+                    TableSource::Table(table) => TableSource::Table(rename(&table)),
+                    TableSource::Subquery(query) => TableSource::Subquery(Box::new(query.map_tables(rename))),
+                    values @ TableSource::Values { .. } => values,
+                };
+                for join in &mut select.joins {
+                    match join.lateral.take() {
+                        Some(query) => join.lateral = Some(Box::new(query.map_tables(rename))),
+                        None => join.table = rename(&join.table),
+                    }
+                }
+                Self::Select(select)
+            }
+            Self::Insert(mut insert) => {
+                insert.table = rename(&insert.table);
+                Self::Insert(insert)
+            }
+            Self::Update(mut update) => {
+                update.table = rename(&update.table);
+                Self::Update(update)
+            }
+            Self::Delete(mut delete) => {
+                delete.table = rename(&delete.table);
+                Self::Delete(delete)
+            }
+        }
+    }
+
+    /// Get the RETURNING columns, empty for SELECT statements
+    #[must_use]
+    pub fn returning(&self) -> &[String] {
+        match self {
+            Self::Select(_) => &[],
+            Self::Insert(insert) => &insert.returning,
+            Self::Update(update) => &update.returning,
+            Self::Delete(delete) => &delete.returning,
+        }
+    }
+
+    /// Check if the statement or one of its subqueries has a LATERAL join
+    fn uses_lateral_join(&self) -> bool {
+        let Self::Select(select) = self else {
+            return false;
+        };
+        select.joins.iter().any(|join| join.lateral.is_some())
+            || matches!(select.from, TableSource::Subquery(ref query) if query.uses_lateral_join())
+    }
+
+    /// Get every expression of the statement, including those of subqueries
+    fn expressions(&self) -> Vec<&Expr> {
+        match self {
+            Self::Select(select) => {
+                let mut exprs: Vec<&Expr> = select.filter.iter()
+                    .chain(&select.having)
+                    .chain(select.joins.iter().map(|join| &join.condition))
+                    .collect();
+                match select.from {
+                    TableSource::Subquery(ref query) => exprs.extend(query.expressions()),
+                    TableSource::Values { ref rows, .. } => exprs.extend(rows.iter().flatten()),
+                    TableSource::Table(_) => {}
+                }
+                for join in &select.joins {
+                    exprs.extend(join.lateral.iter().flat_map(|query| query.expressions()));
+                }
+                exprs
+            }
+            Self::Insert(insert) => insert.rows.iter().flatten().collect(),
+            Self::Update(update) => update.assignments.iter()
+                .map(|(_, value)| value)
+                .chain(&update.filter.condition)
+                .collect(),
+            Self::Delete(delete) => delete.filter.condition.iter().collect(),
+        }
+    }
+
+    /// Get the table, column and alias names the statement quotes
+    fn identifiers(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        match self {
+            Self::Select(select) => {
+                names.extend(select.projection.iter().filter_map(|item| match item {
+                    SelectItem::Column(column) => Some(column.as_str()),
+                    SelectItem::Wildcard | SelectItem::Raw(_) => None,
+                }));
+                match select.from {
+                    TableSource::Table(ref table) => names.push(table),
+                    TableSource::Subquery(ref query) => names.extend(query.identifiers()),
+                    TableSource::Values { ref rows, ref columns } => {
+                        names.extend(columns.iter().map(String::as_str));
+                        for value in rows.iter().flatten() {
+                            expr_columns(value, &mut names);
+                        }
+                    }
+                }
+                names.extend(select.alias.as_deref());
+                for join in &select.joins {
+                    match join.lateral {
+                        Some(ref query) => names.extend(query.identifiers()),
+                        None => names.push(&join.table),
+                    }
+                    names.extend(join.alias.as_deref());
+                }
+                names.extend(select.group_by.iter().map(String::as_str));
+                names.extend(select.order_by.iter().map(|o| o.column.as_str()));
+            }
+            Self::Insert(insert) => {
+                names.push(&insert.table);
+                names.extend(insert.columns.iter().map(String::as_str));
+                names.extend(insert.returning.iter().map(String::as_str));
+            }
+            Self::Update(update) => {
+                names.push(&update.table);
+                names.extend(update.assignments.iter().map(|(column, _)| column.as_str()));
+                names.extend(update.filter.order_by.iter().map(|o| o.column.as_str()));
+                names.extend(update.filter.limit_key.as_deref());
+                names.extend(update.returning.iter().map(String::as_str));
+            }
+            Self::Delete(delete) => {
+                names.push(&delete.table);
+                names.extend(delete.filter.order_by.iter().map(|o| o.column.as_str()));
+                names.extend(delete.filter.limit_key.as_deref());
+                names.extend(delete.returning.iter().map(String::as_str));
+            }
+        }
+        for expr in self.expressions() {
+            expr_columns(expr, &mut names);
+        }
+        // `*` in RETURNING and COUNT(*) style columns aren't quoted
+        names.retain(|name| *name != "*");
+        names
+    }
+
+    /// Add a condition to the WHERE clause of a SELECT, UPDATE or DELETE
+    ///
+    /// Existing conditions are kept and combined with AND, e.g. to hide
+    /// soft-deleted rows: `statement.and_where(col("deleted_at").is_null())`.
+    /// Subqueries and INSERT statements are left unchanged.
+    #[must_use]
+    pub fn and_where(self, condition: Expr) -> Self {
+        let combine = |existing: Option<Expr>| match existing {
+            Some(existing) => existing.and(condition),
+            None => condition,
+        };
+        match self {
+            Self::Select(mut select) => {
+                select.filter = Some(combine(select.filter));
+                Self::Select(select)
+            }
+            Self::Update(mut update) => {
+                update.filter.condition = Some(combine(update.filter.condition));
+                Self::Update(update)
+            }
+            Self::Delete(mut delete) => {
+                delete.filter.condition = Some(combine(delete.filter.condition));
+                Self::Delete(delete)
+            }
+            Self::Insert(_) => self,
+        }
+    }
+}
+
+impl core::fmt::Display for Statement {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&PlatformRenderer::new(&AnsiPlatform::new()).render(self))
+    }
+}
+
+/// Collect the column names referenced by an expression
+fn expr_columns<'a>(expr: &'a Expr, names: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Column(name) => names.push(name),
+        Expr::Comparison(left, _, right) => {
+            expr_columns(left, names);
+            expr_columns(right, names);
+        }
+        Expr::And(exprs) | Expr::Or(exprs) => {
+            for expr in exprs {
+                expr_columns(expr, names);
+            }
+        }
+        Expr::Not(inner) | Expr::IsNull(inner) | Expr::IsNotNull(inner) | Expr::Like(inner, _) => {
+            expr_columns(inner, names);
+        }
+        Expr::Regexp { expr, .. } => expr_columns(expr, names),
+        Expr::In(inner, values) | Expr::NotIn(inner, values) => {
+            expr_columns(inner, names);
+            for value in values {
+                expr_columns(value, names);
+            }
+        }
+        Expr::Between(inner, low, high) => {
+            expr_columns(inner, names);
+            expr_columns(low, names);
+            expr_columns(high, names);
+        }
+        Expr::Value(_) | Expr::Param(_) | Expr::Raw(_) => {}
+    }
+}
+
+/// Renders statements as SQL, node by node
+///
+/// Every method has a default built on [`platform`](Self::platform); a
+/// dialect overrides the nodes it renders differently and keeps the rest.
+pub trait SqlRenderer {
+    /// Get the platform used for quoting and capabilities
+    fn platform(&self) -> &dyn Platform;
+
+    /// Render a statement
+    ///
+    /// ORDER BY/LIMIT on UPDATE/DELETE that the platform can neither express
+    /// nor emulate, and LATERAL joins on platforms without them, are
+    /// rendered as-is and rejected by the database.
+    fn render(&self, statement: &Statement) -> String {
+        let limit = self.modify_limit(statement).unwrap_or(ModifyLimit::Native);
+        self.render_with_limit(statement, &limit)
+    }
+
+    /// Render a statement, validating platform support
+    ///
+    /// # Errors
+    ///
+    /// Returns a platform error if an UPDATE/DELETE uses ORDER BY/LIMIT/OFFSET
+    /// that the platform cannot express natively or emulate, or if the
+    /// statement uses LATERAL joins, regular expressions or non-finite float
+    /// literals the platform lacks, and
+    /// `SchemaError::InvalidDefinition` for identifiers rejected by
+    /// [`Platform::assert_valid_identifier`].
+    fn try_render(&self, statement: &Statement) -> Result<String> {
+        for identifier in statement.identifiers() {
+            self.platform().assert_valid_identifier(identifier)?;
+        }
+        self.check_support(statement)?;
+        let limit = self.modify_limit(statement)?;
+        Ok(self.render_with_limit(statement, &limit))
+    }
+
+    /// Check that the platform supports the expressions of a statement
+    ///
+    /// # Errors
+    ///
+    /// Returns a platform error for regular expression matches or LATERAL
+    /// joins on a platform without support.
+    fn check_support(&self, statement: &Statement) -> Result<()> {
+        let platform = self.platform();
+        if !platform.supports_lateral_join() && statement.uses_lateral_join() {
+            return Err(Error::platform(format!(
+                "{} does not support LATERAL joins; use a correlated subquery instead",
+                platform.name()
+            )));
+        }
+        if !platform.supports_regexp() && statement.expressions().into_iter().any(Expr::uses_regexp) {
+            return Err(Error::platform(format!(
+                "{} does not support regular expressions; enable them on the platform if a REGEXP function is available",
+                platform.name()
+            )));
+        }
+        if !platform.supports_non_finite_floats() && statement.expressions().into_iter().any(Expr::has_non_finite_float) {
+            return Err(Error::platform(format!(
+                "{} cannot store NaN or infinite floats; bind them as parameters with a NonFiniteFloatPolicy",
+                platform.name()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Render a statement with a resolved ORDER BY/LIMIT strategy
+    fn render_with_limit(&self, statement: &Statement, limit: &ModifyLimit) -> String {
+        match statement {
+            Statement::Select(select) => self.select_sql(select),
+            Statement::Insert(insert) => self.insert_sql(insert),
+            Statement::Update(update) => self.update_sql(update, limit),
+            Statement::Delete(delete) => self.delete_sql(delete, limit),
+        }
+    }
+
+    /// Decide how ORDER BY/LIMIT of an UPDATE/DELETE is rendered
+    ///
+    /// # Errors
+    ///
+    /// Returns a platform error if the platform can neither express nor
+    /// emulate the ORDER BY/LIMIT/OFFSET.
+    fn modify_limit(&self, statement: &Statement) -> Result<ModifyLimit> {
+        let filter = match statement {
+            Statement::Update(update) => &update.filter,
+            Statement::Delete(delete) => &delete.filter,
+            Statement::Select(_) | Statement::Insert(_) => return Ok(ModifyLimit::None),
+        };
+        if !filter.is_limited() {
+            return Ok(ModifyLimit::None);
+        }
+
+        let platform = self.platform();
+        if platform.supports_update_delete_limit() {
+            if filter.offset.is_some() {
+                return Err(Error::platform(format!(
+                    "{} does not support OFFSET in UPDATE/DELETE",
+                    platform.name()
+                )));
+            }
+            return Ok(ModifyLimit::Native);
+        }
+
+        filter.limit_key
+            .clone()
+            .or_else(|| platform.row_identifier().map(str::to_string))
+            .map(ModifyLimit::Emulated)
+            .ok_or_else(|| {
+                Error::platform(format!(
+                    "{} does not support ORDER BY/LIMIT in UPDATE/DELETE; set limit_key() to emulate it",
+                    platform.name()
+                ))
+            })
+    }
+
+    /// Render a SELECT statement
+    fn select_sql(&self, select: &SelectStatement) -> String {
+        let mut sql = String::from("SELECT ");
+        sql.push_str(&self.select_modifiers_sql(select));
+        sql.push_str(&self.projection_sql(&select.projection));
+        sql.push_str(" FROM ");
+        match select.from {
+            // The alias names the columns too, so it is part of the table
+            TableSource::Values { ref rows, ref columns } => {
+                sql.push_str(&self.values_table_sql(rows, columns, select.alias.as_deref().unwrap_or("v")));
+            }
+            ref from => {
+                sql.push_str(&self.table_source_sql(from));
+                if let Some(ref alias) = select.alias {
+                    sql.push_str(" AS ");
+                    sql.push_str(&self.platform().quote_identifier(alias));
+                }
+            }
+        }
+        for join in &select.joins {
+            sql.push(' ');
+            sql.push_str(&self.join_sql(join));
+        }
+        if let Some(ref filter) = select.filter {
+            sql.push_str(" WHERE ");
+            sql.push_str(&self.expr_sql(filter));
+        }
+        if !select.group_by.is_empty() {
+            sql.push_str(" GROUP BY ");
+            sql.push_str(&self.column_list_sql(&select.group_by));
+        }
+        if let Some(ref having) = select.having {
+            sql.push_str(" HAVING ");
+            sql.push_str(&self.expr_sql(having));
+        }
+        sql.push_str(&self.order_by_sql(&select.order_by));
+        sql.push_str(&self.limit_sql(select.limit, select.offset));
+        if let Some(lock) = select.lock {
+            sql.push_str(&self.row_lock_sql(lock));
+        }
+        sql
+    }
+
+    /// Render the keywords between SELECT and the projection (with trailing space)
+    fn select_modifiers_sql(&self, select: &SelectStatement) -> String {
+        if select.distinct { "DISTINCT ".to_string() } else { String::new() }
+    }
+
+    /// Render the SELECT list
+    fn projection_sql(&self, projection: &[SelectItem]) -> String {
+        if projection.is_empty() {
+            return "*".to_string();
+        }
+        let items: Vec<String> = projection.iter()
+            .map(|item| match item {
+                SelectItem::Wildcard => "*".to_string(),
+                SelectItem::Column(column) => self.platform().quote_identifier(column),
+                SelectItem::Raw(sql) => sql.clone(),
+            })
+            .collect();
+        items.join(", ")
+    }
+
+    /// Render the table or subquery of a FROM clause
+    fn table_source_sql(&self, source: &TableSource) -> String {
+        match source {
+            TableSource::Table(table) => self.platform().quote_identifier(table),
+            TableSource::Subquery(statement) => format!("({})", self.render(statement)),
+            TableSource::Values { rows, columns } => self.values_table_sql(rows, columns, "v"),
+        }
+    }
+
+    /// Render a literal table of rows including its alias
+    ///
+    /// Without rows, a SELECT of NULL columns that returns nothing is
+    /// rendered, as VALUES needs at least one row.
+    fn values_table_sql(&self, rows: &[Vec<Expr>], columns: &[String], alias: &str) -> String {
+        let platform = self.platform();
+        if rows.is_empty() {
+            let nulls: Vec<String> = columns.iter()
+                .map(|column| format!("NULL AS {}", platform.quote_identifier(column)))
+                .collect();
+            return format!("(SELECT {} WHERE 1 = 0) AS {}", nulls.join(", "), platform.quote_identifier(alias));
+        }
+        let rows: Vec<Vec<String>> = rows.iter()
+            .map(|row| row.iter().map(|value| self.expr_sql(value)).collect())
+            .collect();
+        platform.values_table_sql(&rows, alias, columns)
+    }
+
+    /// Render a JOIN clause
+    fn join_sql(&self, join: &Join) -> String {
+        let platform = self.platform();
+        let alias = join.alias.as_ref()
+            .map(|alias| format!(" AS {}", platform.quote_identifier(alias)))
+            .unwrap_or_default();
+        let source = match join.lateral {
+            Some(ref query) => format!("LATERAL ({})", self.render(query)),
+            None => platform.quote_identifier(&join.table),
+        };
+        format!("{} {source}{alias} ON {}", join.kind.as_sql(), self.expr_sql(&join.condition))
+    }
+
+    /// Render the ORDER BY clause (with leading space), empty if not set
+    fn order_by_sql(&self, order_by: &[OrderBy]) -> String {
+        if order_by.is_empty() {
+            return String::new();
+        }
+        let orders: Vec<String> = order_by.iter()
+            .map(|o| format!("{} {}", self.platform().quote_identifier(&o.column), o.direction.as_sql()))
+            .collect();
+        format!(" ORDER BY {}", orders.join(", "))
+    }
+
+    /// Render LIMIT/OFFSET (with leading space), empty if not set
+    fn limit_sql(&self, limit: Option<u64>, offset: Option<u64>) -> String {
+        self.platform().limit_offset_sql(limit, offset)
+    }
+
+    /// Render the row locking clause (with leading space)
+    ///
+    /// Empty on platforms without row locks (`SQLite`).
+    fn row_lock_sql(&self, lock: RowLock) -> String {
+        self.platform()
+            .for_update_sql(lock == RowLock::UpdateSkipLocked)
+            .map(|clause| format!(" {clause}"))
+            .unwrap_or_default()
+    }
+
+    /// Render an INSERT statement
+    fn insert_sql(&self, insert: &InsertStatement) -> String {
+        let mut sql = String::from("INSERT INTO ");
+        sql.push_str(&self.platform().quote_identifier(&insert.table));
+        if !insert.columns.is_empty() {
+            sql.push_str(" (");
+            sql.push_str(&self.column_list_sql(&insert.columns));
+            sql.push(')');
+        }
+        sql.push_str(" VALUES ");
+        let rows: Vec<String> = insert.rows.iter()
+            .map(|row| {
+                let values: Vec<String> = row.iter().map(|v| self.expr_sql(v)).collect();
+                format!("({})", values.join(", "))
+            })
+            .collect();
+        sql.push_str(&rows.join(", "));
+        sql.push_str(&self.returning_sql(&insert.returning));
+        sql
+    }
+
+    /// Render an UPDATE statement
+    fn update_sql(&self, update: &UpdateStatement, limit: &ModifyLimit) -> String {
+        let mut sql = String::from("UPDATE ");
+        sql.push_str(&self.platform().quote_identifier(&update.table));
+        sql.push_str(" SET ");
+        let sets: Vec<String> = update.assignments.iter()
+            .map(|(column, value)| {
+                format!("{} = {}", self.platform().quote_identifier(column), self.expr_sql(value))
+            })
+            .collect();
+        sql.push_str(&sets.join(", "));
+        sql.push_str(&self.modify_filter_sql(&update.table, &update.filter, limit));
+        if self.platform().supports_update_returning() {
+            sql.push_str(&self.returning_sql(&update.returning));
+        }
+        sql
+    }
+
+    /// Render a DELETE statement
+    fn delete_sql(&self, delete: &DeleteStatement, limit: &ModifyLimit) -> String {
+        let mut sql = String::from("DELETE FROM ");
+        sql.push_str(&self.platform().quote_identifier(&delete.table));
+        sql.push_str(&self.modify_filter_sql(&delete.table, &delete.filter, limit));
+        sql.push_str(&self.returning_sql(&delete.returning));
+        sql
+    }
+
+    /// Render the WHERE clause of an UPDATE/DELETE including ORDER BY/LIMIT
+    fn modify_filter_sql(&self, table: &str, filter: &ModifyFilter, limit: &ModifyLimit) -> String {
+        let where_sql = filter.condition.as_ref()
+            .map(|expr| format!(" WHERE {}", self.expr_sql(expr)))
+            .unwrap_or_default();
+
+        match limit {
+            ModifyLimit::None => where_sql,
+            ModifyLimit::Native => format!(
+                "{where_sql}{}{}",
+                self.order_by_sql(&filter.order_by),
+                self.limit_sql(filter.limit, filter.offset)
+            ),
+            ModifyLimit::Emulated(key) => {
+                let platform = self.platform();
+                let key = platform.quote_identifier(key);
+                format!(
+                    " WHERE {key} IN (SELECT {key} FROM {}{where_sql}{}{})",
+                    platform.quote_identifier(table),
+                    self.order_by_sql(&filter.order_by),
+                    self.limit_sql(filter.limit, filter.offset)
+                )
+            }
+        }
+    }
+
+    /// Render the RETURNING clause (with leading space), empty if unsupported
+    fn returning_sql(&self, columns: &[String]) -> String {
+        if columns.is_empty() || !self.platform().supports_returning() {
+            return String::new();
+        }
+        format!(" RETURNING {}", self.column_list_sql(columns))
+    }
+
+    /// Render a comma-separated list of quoted columns
+    fn column_list_sql(&self, columns: &[String]) -> String {
+        let quoted: Vec<String> = columns.iter().map(|c| self.platform().quote_identifier(c)).collect();
+        quoted.join(", ")
+    }
+
+    /// Render an expression
+    fn expr_sql(&self, expr: &Expr) -> String {
+        expr.to_sql(self.platform())
+    }
+}
+
+/// Renders statements with the default SQL of a platform
+#[derive(Clone, Copy)]
+pub struct PlatformRenderer<'a> {
+    /// Platform used for quoting and capabilities
+    platform: &'a dyn Platform,
+}
+
+impl<'a> PlatformRenderer<'a> {
+    /// Create a renderer for a platform
+    #[must_use]
+    pub const fn new(platform: &'a dyn Platform) -> Self {
+        Self { platform }
+    }
+}
+
+impl core::fmt::Debug for PlatformRenderer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PlatformRenderer").field("platform", &self.platform.name()).finish()
+    }
+}
+
+impl SqlRenderer for PlatformRenderer<'_> {
+    fn platform(&self) -> &dyn Platform {
+        self.platform
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::SqlitePlatform;
+    use crate::query::QueryBuilder;
+
+    /// Renders limits the SQL Server way
+    struct TopRenderer(AnsiPlatform);
+
+    impl SqlRenderer for TopRenderer {
+        fn platform(&self) -> &dyn Platform {
+            &self.0
+        }
+
+        fn select_modifiers_sql(&self, select: &SelectStatement) -> String {
+            match (select.limit, select.offset) {
+                (Some(limit), None) => format!("TOP {limit} "),
+                _ => String::new(),
+            }
+        }
+
+        fn limit_sql(&self, limit: Option<u64>, offset: Option<u64>) -> String {
+            match (limit, offset) {
+                (Some(limit), Some(offset)) => format!(" OFFSET {offset} ROWS FETCH NEXT {limit} ROWS ONLY"),
+                _ => String::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_dialect_override() {
+        let top = QueryBuilder::select().columns(&["id"]).from("users").limit(5).to_statement();
+        assert_eq!(TopRenderer(AnsiPlatform::new()).render(&top), "SELECT TOP 5 \"id\" FROM \"users\"");
+
+        let page = QueryBuilder::select()
+            .from("users")
+            .order_by_asc("id")
+            .limit(10)
+            .offset(20)
+            .to_statement();
+        assert_eq!(
+            TopRenderer(AnsiPlatform::new()).render(&page),
+            "SELECT * FROM \"users\" ORDER BY \"id\" ASC OFFSET 20 ROWS FETCH NEXT 10 ROWS ONLY"
+        );
+        assert_eq!(page.to_string(), "SELECT * FROM \"users\" ORDER BY \"id\" ASC LIMIT 10 OFFSET 20");
+    }
+
+    #[test]
+    fn test_statement_tree() {
+        let statement = QueryBuilder::delete().from("logs").order_by_asc("id").limit(1).to_statement();
+        let Statement::Delete(ref delete) = statement else {
+            unreachable!("expected a DELETE statement");
+        };
+        assert_eq!(delete.table, "logs");
+        assert!(delete.filter.is_limited());
+
+        let sqlite = SqlitePlatform::new();
+        let renderer = PlatformRenderer::new(&sqlite);
+        assert_eq!(
+            renderer.modify_limit(&statement).ok(),
+            Some(ModifyLimit::Emulated("rowid".to_string()))
+        );
+        assert!(PlatformRenderer::new(&AnsiPlatform::new()).try_render(&statement).is_err());
+    }
+}
@@ -1,8 +1,13 @@
 //! Query Builder for constructing SQL queries
 
-use crate::core::SqlValue;
-use crate::platform::Platform;
-use super::expr::Expr;
+use alloc::{boxed::Box, format, string::{String, ToString}, vec::Vec};
+use crate::core::{Result, SqlValue, TenantStrategy, ValueRedactionPolicy};
+use crate::platform::{AnsiPlatform, Platform, Table};
+use super::ast::{
+    DeleteStatement, InsertStatement, Join, JoinType, ModifyFilter, OrderBy, OrderDirection, PlatformRenderer,
+    RowLock, SelectItem, SelectStatement, SqlRenderer, Statement, TableSource, UpdateStatement,
+};
+use super::expr::{ComparisonOp, Expr};
 
 /// The type of SQL query
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,78 +22,55 @@ pub enum QueryType {
     Delete,
 }
 
-/// JOIN type
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum JoinType {
-    /// INNER JOIN
-    Inner,
-    /// LEFT OUTER JOIN
-    Left,
-    /// RIGHT OUTER JOIN
-    Right,
-    /// FULL OUTER JOIN
-    Full,
-    /// CROSS JOIN
-    Cross,
-}
-
-impl JoinType {
-    /// Get the SQL representation of this join type
-    const fn as_sql(self) -> &'static str {
-        match self {
-            Self::Inner => "INNER JOIN",
-            Self::Left => "LEFT JOIN",
-            Self::Right => "RIGHT JOIN",
-            Self::Full => "FULL JOIN",
-            Self::Cross => "CROSS JOIN",
+/// Find the end of a statement's keyword in rendered SQL
+///
+/// The first `keyword` outside parentheses and quotes is the statement's
+/// own, so a leading `WITH ...` clause and its subqueries are skipped.
+fn keyword_end(sql: &str, keyword: &str) -> Option<usize> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (index, c) in sql.char_indices() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' | '`' => quote = Some(c),
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ if depth == 0 && !sql[..index].ends_with(is_word_char) => {
+                    let end = index + keyword.len();
+                    let matches = sql.get(index..end).is_some_and(|word| word.eq_ignore_ascii_case(keyword));
+                    if matches && !sql[end..].starts_with(is_word_char) {
+                        return Some(end);
+                    }
+                }
+                _ => {}
+            },
         }
     }
+    None
 }
 
-/// ORDER BY direction
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum OrderDirection {
-    /// Ascending order (A-Z, 0-9)
-    #[default]
-    Asc,
-    /// Descending order (Z-A, 9-0)
-    Desc,
-}
-
-impl OrderDirection {
-    /// Get the SQL representation of this order direction
-    const fn as_sql(self) -> &'static str {
-        match self {
-            Self::Asc => "ASC",
-            Self::Desc => "DESC",
-        }
+/// Find the value a column is compared to with `=` in a condition
+fn key_in_expr<'a>(expr: &'a Expr, column: &str) -> Option<&'a SqlValue> {
+    match expr {
+        Expr::Comparison(left, ComparisonOp::Eq, right) => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(c), Expr::Value(value)) | (Expr::Value(value), Expr::Column(c)) if c == column => {
+                Some(value)
+            }
+            _ => None,
+        },
+        Expr::And(exprs) => exprs.iter().find_map(|e| key_in_expr(e, column)),
+        _ => None,
     }
 }
 
-/// A JOIN clause
-#[derive(Debug, Clone)]
-struct Join {
-    /// Type of join (INNER, LEFT, etc.)
-    kind: JoinType,
-    /// Table to join
-    table: String,
-    /// Optional table alias
-    alias: Option<String>,
-    /// Join condition
-    condition: Expr,
-}
-
-/// An ORDER BY clause
-#[derive(Debug, Clone)]
-struct OrderBy {
-    /// Column to order by
-    column: String,
-    /// Sort direction
-    direction: OrderDirection,
-}
-
 /// A fluent SQL query builder
-#[derive(Debug, Clone)]
+///
+/// Builders compare structurally and display as SQL rendered for
+/// [`AnsiPlatform`], which makes them easy to assert on in tests.
+#[derive(Debug, Clone, PartialEq)]
 pub struct QueryBuilder {
     /// Type of query (SELECT, INSERT, etc.)
     query_type: QueryType,
@@ -98,14 +80,20 @@ pub struct QueryBuilder {
     table_alias: Option<String>,
     /// Selected columns
     columns: Vec<String>,
+    /// Raw SQL select expressions, rendered verbatim after the columns
+    select_raw: Vec<String>,
+    /// Subquery selected from instead of the table
+    from_subquery: Option<Box<Self>>,
+    /// Literal rows and their column names selected from instead of the table
+    from_values: Option<(Vec<Vec<SqlValue>>, Vec<String>)>,
     /// Values for INSERT
     values: Vec<Vec<SqlValue>>,
     /// Column-value pairs for UPDATE
     set_values: Vec<(String, SqlValue)>,
     /// WHERE clause expression
     where_expr: Option<Expr>,
-    /// JOIN clauses
-    joins: Vec<Join>,
+    /// JOIN clauses, with the subquery of LATERAL joins
+    joins: Vec<(Join, Option<Box<Self>>)>,
     /// GROUP BY columns
     group_by: Vec<String>,
     /// HAVING clause expression
@@ -120,6 +108,16 @@ pub struct QueryBuilder {
     distinct: bool,
     /// RETURNING columns
     returning: Vec<String>,
+    /// Key column for emulating ORDER BY/LIMIT in UPDATE/DELETE
+    limit_key: Option<String>,
+    /// Row lock taken by a SELECT
+    lock: Option<RowLock>,
+    /// Tenant whose table names are used
+    tenant: Option<TenantStrategy>,
+    /// Optimizer hints, rendered where the platform supports them
+    hints: Vec<String>,
+    /// Comments rendered before the statement
+    comments: Vec<String>,
 }
 
 impl QueryBuilder {
@@ -131,6 +129,9 @@ impl QueryBuilder {
             table: String::new(),
             table_alias: None,
             columns: Vec::new(),
+            select_raw: Vec::new(),
+            from_subquery: None,
+            from_values: None,
             values: Vec::new(),
             set_values: Vec::new(),
             where_expr: None,
@@ -142,6 +143,11 @@ impl QueryBuilder {
             offset: None,
             distinct: false,
             returning: Vec::new(),
+            limit_key: None,
+            lock: None,
+            tenant: None,
+            hints: Vec::new(),
+            comments: Vec::new(),
         }
     }
 
@@ -204,6 +210,68 @@ impl QueryBuilder {
         self
     }
 
+    /// Add a raw SQL expression to select (e.g. `COUNT(*)`), rendered verbatim
+    #[must_use]
+    pub fn select_raw(mut self, expr: &str) -> Self {
+        self.select_raw.push(expr.to_string());
+        self
+    }
+
+    /// Replace the selected columns with raw SQL expressions
+    #[must_use]
+    pub fn select_only(mut self, exprs: &[&str]) -> Self {
+        self.columns.clear();
+        self.select_raw = exprs.iter().map(|s| (*s).to_string()).collect();
+        self
+    }
+
+    /// Replace `*` with explicit columns of the main table
+    ///
+    /// Only applies to SELECTs from a single table (see
+    /// [`wildcard_table`](Self::wildcard_table)); other queries are returned
+    /// unchanged. An empty projection, which also selects `*`, is expanded too.
+    ///
+    /// ```rust
+    /// use rustine_dbal::query::QueryBuilder;
+    /// use rustine_dbal::platform::PostgresPlatform;
+    ///
+    /// let query = QueryBuilder::select().all().from("users").expand_wildcard(&["id", "name"]);
+    /// assert_eq!(query.to_sql(&PostgresPlatform), "SELECT \"id\", \"name\" FROM \"users\"");
+    /// ```
+    #[must_use]
+    pub fn expand_wildcard(mut self, columns: &[&str]) -> Self {
+        if self.wildcard_table().is_none() {
+            return self;
+        }
+        let mut expanded = Some(columns.iter().map(|c| (*c).to_string()).collect::<Vec<_>>());
+        if self.columns.is_empty() {
+            self.columns = expanded.unwrap_or_default();
+        } else {
+            self.columns = core::mem::take(&mut self.columns)
+                .into_iter()
+                .flat_map(|c| if c == "*" { expanded.take().unwrap_or_default() } else { alloc::vec![c] })
+                .collect();
+        }
+        self
+    }
+
+    /// Derive a query counting the rows this SELECT returns
+    ///
+    /// ORDER BY, LIMIT, OFFSET and row locks are dropped. DISTINCT and grouped
+    /// queries are wrapped in a subquery so that groups, not rows, are counted.
+    #[must_use]
+    pub fn count_query(&self) -> Self {
+        let mut base = self.clone().reset_order_by().reset_limit();
+        base.lock = None;
+        if base.distinct || !base.group_by.is_empty() {
+            Self::select()
+                .select_only(&["COUNT(*)"])
+                .from_subquery(base, "count_query")
+        } else {
+            base.select_only(&["COUNT(*)"])
+        }
+    }
+
     // ========================================================================
     // Common methods
     // ========================================================================
@@ -215,6 +283,46 @@ impl QueryBuilder {
         self
     }
 
+    /// Select from a subquery with the given alias
+    #[must_use]
+    pub fn from_subquery(mut self, query: Self, alias: &str) -> Self {
+        self.from_subquery = Some(Box::new(query));
+        self.table_alias = Some(alias.to_string());
+        self
+    }
+
+    /// Select from a literal table of rows with the given alias and column names
+    ///
+    /// Renders `(VALUES (...), (...)) AS alias(columns)` on `PostgreSQL`,
+    /// and equivalent SELECTs on `SQLite` and `MySQL` (see
+    /// [`Platform::values_table_sql`]). Join a real table against it to
+    /// match many keys at once:
+    ///
+    /// ```rust
+    /// use rustine_dbal::core::SqlValue;
+    /// use rustine_dbal::query::{Expr, QueryBuilder};
+    /// use rustine_dbal::platform::PostgresPlatform;
+    ///
+    /// let keys = vec![vec![SqlValue::from(1i64), "a".into()], vec![2i64.into(), "b".into()]];
+    /// let sql = QueryBuilder::select()
+    ///     .select_only(&["users.*"])
+    ///     .values_table(keys, "k", &["id", "code"])
+    ///     .inner_join("users", Expr::raw("users.id = k.id"))
+    ///     .to_sql(&PostgresPlatform);
+    /// assert_eq!(
+    ///     sql,
+    ///     "SELECT users.* FROM (VALUES (1, 'a'), (2, 'b')) AS \"k\"(\"id\", \"code\") \
+    ///      INNER JOIN \"users\" ON users.id = k.id"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn values_table(mut self, rows: Vec<Vec<SqlValue>>, alias: &str, columns: &[&str]) -> Self {
+        let columns = columns.iter().map(|c| (*c).to_string()).collect();
+        self.from_values = Some((rows, columns));
+        self.table_alias = Some(alias.to_string());
+        self
+    }
+
     /// Set table alias
     #[must_use]
     pub fn alias(mut self, alias: &str) -> Self {
@@ -236,6 +344,19 @@ impl QueryBuilder {
         self
     }
 
+    /// Build the query for a tenant
+    ///
+    /// With [`TenantStrategy::TablePrefix`] the main table and joined tables
+    /// get the tenant's prefix; qualify columns with aliases, as qualified
+    /// column names aren't rewritten. Subqueries need their own tenant.
+    /// Schema and database strategies apply to the connection and leave the
+    /// SQL unchanged.
+    #[must_use]
+    pub fn tenant(mut self, tenant: &TenantStrategy) -> Self {
+        self.tenant = Some(tenant.clone());
+        self
+    }
+
     // ========================================================================
     // WHERE clause
     // ========================================================================
@@ -281,6 +402,13 @@ impl QueryBuilder {
         self.where_expr(Expr::col(column).like(pattern))
     }
 
+    /// Remove all WHERE conditions
+    #[must_use]
+    pub fn reset_where(mut self) -> Self {
+        self.where_expr = None;
+        self
+    }
+
     /// Add a WHERE with OR condition
     #[must_use]
     pub fn or_where(mut self, expr: Expr) -> Self {
@@ -298,48 +426,75 @@ impl QueryBuilder {
     /// Add an INNER JOIN
     #[must_use]
     pub fn inner_join(mut self, table: &str, condition: Expr) -> Self {
-        self.joins.push(Join {
-            kind: JoinType::Inner,
-            table: table.to_string(),
-            alias: None,
-            condition,
-        });
+        self.joins.push((Join::new(JoinType::Inner, table, condition), None));
         self
     }
 
     /// Add a LEFT JOIN
     #[must_use]
     pub fn left_join(mut self, table: &str, condition: Expr) -> Self {
-        self.joins.push(Join {
-            kind: JoinType::Left,
-            table: table.to_string(),
-            alias: None,
-            condition,
-        });
+        self.joins.push((Join::new(JoinType::Left, table, condition), None));
         self
     }
 
     /// Add a RIGHT JOIN
     #[must_use]
     pub fn right_join(mut self, table: &str, condition: Expr) -> Self {
-        self.joins.push(Join {
-            kind: JoinType::Right,
-            table: table.to_string(),
-            alias: None,
-            condition,
-        });
+        self.joins.push((Join::new(JoinType::Right, table, condition), None));
         self
     }
 
     /// Add a JOIN with alias
     #[must_use]
     pub fn join_alias(mut self, kind: JoinType, table: &str, alias: &str, condition: Expr) -> Self {
-        self.joins.push(Join {
-            kind,
-            table: table.to_string(),
-            alias: Some(alias.to_string()),
-            condition,
-        });
+        self.joins.push((Join::new(kind, table, condition).with_alias(alias), None));
+        self
+    }
+
+    /// Add an INNER JOIN LATERAL of a subquery that may refer to earlier tables
+    ///
+    /// Builds "top N per group" queries: the subquery is evaluated for each
+    /// row of the tables before it. Use `Expr::raw("TRUE")` as condition
+    /// when the subquery filters on its own. On platforms without LATERAL
+    /// (see [`Platform::supports_lateral_join`]) it is rejected by
+    /// [`try_to_sql`](Self::try_to_sql), while [`to_sql`](Self::to_sql)
+    /// renders it as-is and the database rejects it.
+    ///
+    /// ```rust
+    /// use rustine_dbal::query::{Expr, QueryBuilder};
+    /// use rustine_dbal::platform::PostgresPlatform;
+    ///
+    /// let latest = QueryBuilder::select()
+    ///     .columns(&["total"])
+    ///     .from("orders")
+    ///     .where_expr(Expr::raw("orders.user_id = users.id"))
+    ///     .order_by_desc("created_at")
+    ///     .limit(3);
+    /// let sql = QueryBuilder::select()
+    ///     .select_only(&["users.name", "latest.total"])
+    ///     .from("users")
+    ///     .join_lateral(latest, "latest", Expr::raw("TRUE"))
+    ///     .to_sql(&PostgresPlatform);
+    /// assert_eq!(
+    ///     sql,
+    ///     "SELECT users.name, latest.total FROM \"users\" INNER JOIN LATERAL (SELECT \"total\" FROM \"orders\" \
+    ///      WHERE orders.user_id = users.id ORDER BY \"created_at\" DESC LIMIT 3) AS \"latest\" ON TRUE"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn join_lateral(self, subquery: Self, alias: &str, condition: Expr) -> Self {
+        self.lateral(JoinType::Inner, subquery, alias, condition)
+    }
+
+    /// Add a LEFT JOIN LATERAL, keeping rows for which the subquery returns nothing
+    #[must_use]
+    pub fn left_join_lateral(self, subquery: Self, alias: &str, condition: Expr) -> Self {
+        self.lateral(JoinType::Left, subquery, alias, condition)
+    }
+
+    /// Add a LATERAL join of the given type
+    fn lateral(mut self, kind: JoinType, subquery: Self, alias: &str, condition: Expr) -> Self {
+        self.joins.push((Join::new(kind, "", condition).with_alias(alias), Some(Box::new(subquery))));
         self
     }
 
@@ -387,6 +542,40 @@ impl QueryBuilder {
         self.order_by(column, OrderDirection::Desc)
     }
 
+    /// Order by the primary key of a table after the current ORDER BY
+    ///
+    /// Rows equal in the ordered columns come back in any order, so pages
+    /// overlap or skip rows; the primary key makes the order total. Key
+    /// columns already ordered by are skipped, the others sorted in the
+    /// direction of the last ORDER BY (ascending without one). Tables without
+    /// primary key are left as is. For introspected tables pass
+    /// `TableInfo::to_table()`.
+    ///
+    /// ```rust
+    /// use rustine_dbal::platform::{Column, Index, PostgresPlatform, SqlType, Table};
+    /// use rustine_dbal::query::QueryBuilder;
+    ///
+    /// let users = Table::new("users")
+    ///     .column(Column::new("id", SqlType::BigInt))
+    ///     .column(Column::new("name", SqlType::Text))
+    ///     .index(Index::primary(vec!["id".into()]));
+    /// let page = QueryBuilder::select().all().from("users").order_by_desc("name").order_by_primary_key(&users);
+    /// assert_eq!(
+    ///     page.to_sql(&PostgresPlatform),
+    ///     "SELECT * FROM \"users\" ORDER BY \"name\" DESC, \"id\" DESC"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn order_by_primary_key(mut self, table: &Table) -> Self {
+        let direction = self.order_by.last().map_or(OrderDirection::Asc, |order| order.direction);
+        for column in table.primary_key_columns().unwrap_or_default() {
+            if !self.order_by.iter().any(|order| order.column == *column) {
+                self.order_by.push(OrderBy { column: column.clone(), direction });
+            }
+        }
+        self
+    }
+
     /// Set LIMIT
     #[must_use]
     pub const fn limit(mut self, limit: u64) -> Self {
@@ -401,6 +590,73 @@ impl QueryBuilder {
         self
     }
 
+    /// Remove all ORDER BY clauses
+    #[must_use]
+    pub fn reset_order_by(mut self) -> Self {
+        self.order_by.clear();
+        self
+    }
+
+    /// Remove LIMIT and OFFSET
+    #[must_use]
+    pub const fn reset_limit(mut self) -> Self {
+        self.limit = None;
+        self.offset = None;
+        self
+    }
+
+    /// Set the key column used to emulate ORDER BY/LIMIT in UPDATE/DELETE
+    ///
+    /// Platforms without native support (everything except `MySQL`) rewrite
+    /// the statement to `WHERE key IN (SELECT key ... ORDER BY ... LIMIT n)`.
+    /// Defaults to the platform's row identifier (`ctid`, `rowid`).
+    #[must_use]
+    pub fn limit_key(mut self, column: &str) -> Self {
+        self.limit_key = Some(column.to_string());
+        self
+    }
+
+    /// Lock the selected rows until the end of the transaction (FOR UPDATE)
+    ///
+    /// Dropped on platforms without row locks (`SQLite`, where a write
+    /// transaction locks the whole database).
+    #[must_use]
+    pub const fn for_update(mut self) -> Self {
+        self.lock = Some(RowLock::Update);
+        self
+    }
+
+    /// Lock the selected rows, skipping rows other transactions have locked
+    ///
+    /// Renders FOR UPDATE SKIP LOCKED where supported (see
+    /// [`Platform::supports_skip_locked`]) and a waiting FOR UPDATE elsewhere.
+    #[must_use]
+    pub const fn for_update_skip_locked(mut self) -> Self {
+        self.lock = Some(RowLock::UpdateSkipLocked);
+        self
+    }
+
+    /// Add an optimizer hint, e.g. `MAX_EXECUTION_TIME(1000)`
+    ///
+    /// Rendered as `SELECT /*+ MAX_EXECUTION_TIME(1000) */ ...` on platforms
+    /// with inline hints (see [`Platform::optimizer_hints_sql`]) and dropped
+    /// elsewhere. Hints of a subquery builder are not rendered.
+    #[must_use]
+    pub fn hint(mut self, hint: &str) -> Self {
+        self.hints.push(hint.to_string());
+        self
+    }
+
+    /// Add a comment rendered before the statement, e.g. `service=billing`
+    ///
+    /// Lets slow query logs and monitoring tools correlate statements with
+    /// application traces (sqlcommenter style).
+    #[must_use]
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.comments.push(comment.to_string());
+        self
+    }
+
     // ========================================================================
     // INSERT specific methods
     // ========================================================================
@@ -442,6 +698,10 @@ impl QueryBuilder {
     // ========================================================================
 
     /// Add RETURNING clause (`PostgreSQL`, `SQLite` 3.35+)
+    ///
+    /// Other platforms drop the clause from the rendered SQL;
+    /// [`Connection::execute_returning`](crate::connection::Connection::execute_returning)
+    /// emulates it there with follow-up SELECTs.
     #[must_use]
     pub fn returning(mut self, columns: &[&str]) -> Self {
         self.returning.extend(columns.iter().map(|s| (*s).to_string()));
@@ -452,259 +712,278 @@ impl QueryBuilder {
     // SQL Generation
     // ========================================================================
 
-    /// Build the SQL query for a specific platform
+    // ========================================================================
+    // Accessors
+    // ========================================================================
+
+    /// Get the type of the query
     #[must_use]
-    pub fn to_sql<P: Platform>(&self, platform: &P) -> String {
-        match self.query_type {
-            QueryType::Select => self.build_select(platform),
-            QueryType::Insert => self.build_insert(platform),
-            QueryType::Update => self.build_update(platform),
-            QueryType::Delete => self.build_delete(platform),
-        }
+    pub const fn query_type(&self) -> QueryType {
+        self.query_type
     }
 
-    /// Build a SELECT SQL statement
-    fn build_select<P: Platform>(&self, platform: &P) -> String {
-        let mut sql = String::from("SELECT ");
+    /// Get the LIMIT of the query
+    #[must_use]
+    pub const fn limit_value(&self) -> Option<u64> {
+        self.limit
+    }
 
-        if self.distinct {
-            sql.push_str("DISTINCT ");
-        }
+    /// Check if the query has clauses acting on its whole row set
+    ///
+    /// True for DISTINCT, GROUP BY, HAVING, ORDER BY, LIMIT and OFFSET,
+    /// whose result changes when the rows are fetched in several statements.
+    #[must_use]
+    pub fn acts_on_row_set(&self) -> bool {
+        self.distinct
+            || !self.group_by.is_empty()
+            || self.having.is_some()
+            || !self.order_by.is_empty()
+            || self.limit.is_some()
+            || self.offset.is_some()
+    }
 
-        // Columns
-        if self.columns.is_empty() {
-            sql.push('*');
-        } else {
-            let cols: Vec<String> = self.columns.iter()
-                .map(|c| if c == "*" { c.clone() } else { platform.quote_identifier(c) })
-                .collect();
-            sql.push_str(&cols.join(", "));
+    /// Get the main table name (without tenant prefix)
+    #[must_use]
+    pub fn table_name(&self) -> &str {
+        &self.table
+    }
+
+    /// Check if the query is a SELECT returning `*`
+    ///
+    /// True for an explicit `*` and for an empty projection.
+    #[must_use]
+    pub fn selects_wildcard(&self) -> bool {
+        self.query_type == QueryType::Select
+            && (self.columns.iter().any(|c| c == "*") || (self.columns.is_empty() && self.select_raw.is_empty()))
+    }
+
+    /// Get the table whose columns `*` stands for
+    ///
+    /// `None` unless the query selects `*` from a single table without joins,
+    /// subqueries or literal rows. The name includes the tenant prefix.
+    #[must_use]
+    pub fn wildcard_table(&self) -> Option<String> {
+        let single_table = self.from_subquery.is_none()
+            && self.from_values.is_none()
+            && self.joins.is_empty()
+            && !self.table.is_empty();
+        (single_table && self.selects_wildcard()).then(|| self.prefixed_table(&self.table))
+    }
+
+    /// Get the columns an INSERT or UPDATE writes, empty for other queries
+    #[must_use]
+    pub fn changed_columns(&self) -> Vec<&str> {
+        match self.query_type {
+            QueryType::Insert => self.columns.iter().map(String::as_str).collect(),
+            QueryType::Update => self.set_values.iter().map(|(column, _)| column.as_str()).collect(),
+            QueryType::Select | QueryType::Delete => Vec::new(),
         }
+    }
 
-        // FROM
-        sql.push_str(" FROM ");
-        sql.push_str(&platform.quote_identifier(&self.table));
-        if let Some(ref alias) = self.table_alias {
-            sql.push_str(" AS ");
-            sql.push_str(&platform.quote_identifier(alias));
+    /// Get the value of a key column identifying the single affected row
+    ///
+    /// Taken from the inserted row of a single-row INSERT, or from a
+    /// `column = value` condition (alone or inside AND) of an UPDATE/DELETE.
+    #[must_use]
+    pub fn key_value(&self, column: &str) -> Option<&SqlValue> {
+        match self.query_type {
+            QueryType::Insert => match self.values.as_slice() {
+                [row] => self.columns.iter().position(|c| c == column).and_then(|i| row.get(i)),
+                _ => None,
+            },
+            QueryType::Update | QueryType::Delete => self.where_expr.as_ref().and_then(|expr| key_in_expr(expr, column)),
+            QueryType::Select => None,
         }
+    }
 
-        // JOINs
-        for join in &self.joins {
-            sql.push(' ');
-            sql.push_str(join.kind.as_sql());
-            sql.push(' ');
-            sql.push_str(&platform.quote_identifier(&join.table));
-            if let Some(ref alias) = join.alias {
-                sql.push_str(" AS ");
-                sql.push_str(&platform.quote_identifier(alias));
+    /// Copy the query with sensitive values replaced by a placeholder
+    ///
+    /// Inserted and updated values of redacted columns, and values in
+    /// WHERE, HAVING and JOIN conditions (see [`Expr::redacted`]) are
+    /// replaced, so `query.redacted(&policy).to_string()` is safe to log.
+    #[must_use]
+    pub fn redacted(&self, policy: &ValueRedactionPolicy) -> Self {
+        let mut query = self.clone();
+        for row in &mut query.values {
+            for (value, column) in row.iter_mut().zip(&self.columns) {
+                *value = policy.redact_value(Some(column), value);
             }
-            sql.push_str(" ON ");
-            sql.push_str(&self.expr_to_sql(&join.condition, platform));
         }
-
-        // WHERE
-        if let Some(ref where_expr) = self.where_expr {
-            sql.push_str(" WHERE ");
-            sql.push_str(&self.expr_to_sql(where_expr, platform));
+        for (column, value) in &mut query.set_values {
+            *value = policy.redact_value(Some(column), value);
         }
-
-        // GROUP BY
-        if !self.group_by.is_empty() {
-            sql.push_str(" GROUP BY ");
-            let cols: Vec<String> = self.group_by.iter()
-                .map(|c| platform.quote_identifier(c))
-                .collect();
-            sql.push_str(&cols.join(", "));
+        query.where_expr = self.where_expr.as_ref().map(|expr| expr.redacted(policy));
+        query.having = self.having.as_ref().map(|expr| expr.redacted(policy));
+        for (join, lateral) in &mut query.joins {
+            join.condition = join.condition.redacted(policy);
+            if let Some(subquery) = lateral {
+                **subquery = subquery.redacted(policy);
+            }
         }
-
-        // HAVING
-        if let Some(ref having) = self.having {
-            sql.push_str(" HAVING ");
-            sql.push_str(&self.expr_to_sql(having, platform));
+        query.from_subquery = self.from_subquery.as_ref().map(|sub| Box::new(sub.redacted(policy)));
+        if let Some((ref mut rows, ref columns)) = query.from_values {
+            for row in rows {
+                for (value, column) in row.iter_mut().zip(columns) {
+                    *value = policy.redact_value(Some(column), value);
+                }
+            }
         }
+        query
+    }
 
-        // ORDER BY
-        if !self.order_by.is_empty() {
-            sql.push_str(" ORDER BY ");
-            let orders: Vec<String> = self.order_by.iter()
-                .map(|o| format!("{} {}", platform.quote_identifier(&o.column), o.direction.as_sql()))
-                .collect();
-            sql.push_str(&orders.join(", "));
+    /// Build the statement tree of the query
+    ///
+    /// Table names already carry the tenant's prefix; render the statement
+    /// with a [`SqlRenderer`] to customize the SQL of a dialect.
+    #[must_use]
+    pub fn to_statement(&self) -> Statement {
+        match self.query_type {
+            QueryType::Select => Statement::Select(self.select_statement()),
+            QueryType::Insert => Statement::Insert(InsertStatement {
+                table: self.prefixed_table(&self.table),
+                columns: self.columns.clone(),
+                rows: self.values.iter()
+                    .map(|row| row.iter().cloned().map(Expr::Value).collect())
+                    .collect(),
+                returning: self.returning.clone(),
+            }),
+            QueryType::Update => Statement::Update(UpdateStatement {
+                table: self.prefixed_table(&self.table),
+                assignments: self.set_values.iter()
+                    .map(|(column, value)| (column.clone(), Expr::Value(value.clone())))
+                    .collect(),
+                filter: self.modify_filter(),
+                returning: self.returning.clone(),
+            }),
+            QueryType::Delete => Statement::Delete(DeleteStatement {
+                table: self.prefixed_table(&self.table),
+                filter: self.modify_filter(),
+                returning: self.returning.clone(),
+            }),
         }
+    }
 
-        // LIMIT/OFFSET
-        sql.push_str(&platform.limit_offset_sql(self.limit, self.offset));
+    /// Build the SQL query for a specific platform
+    ///
+    /// ORDER BY/LIMIT on UPDATE/DELETE that the platform can neither express
+    /// nor emulate, and LATERAL joins on platforms without them, are rendered
+    /// as-is and rejected by the database; use [`try_to_sql`](Self::try_to_sql)
+    /// to detect this up front.
+    #[must_use]
+    pub fn to_sql(&self, platform: &dyn Platform) -> String {
+        self.to_sql_with(&PlatformRenderer::new(platform))
+    }
 
-        sql
+    /// Build the SQL query for a specific platform, validating platform support
+    ///
+    /// # Errors
+    ///
+    /// Returns a platform error if an UPDATE/DELETE uses ORDER BY/LIMIT/OFFSET
+    /// that the platform cannot express natively or emulate, or if the query
+    /// uses LATERAL joins, regular expressions or non-finite float literals
+    /// the platform lacks.
+    pub fn try_to_sql(&self, platform: &dyn Platform) -> Result<String> {
+        let sql = PlatformRenderer::new(platform).try_render(&self.to_statement())?;
+        Ok(self.annotated(platform, sql))
     }
 
-    /// Build an INSERT SQL statement
-    fn build_insert<P: Platform>(&self, platform: &P) -> String {
-        let mut sql = String::from("INSERT INTO ");
-        sql.push_str(&platform.quote_identifier(&self.table));
+    /// Build the SQL query with a custom renderer
+    #[must_use]
+    pub fn to_sql_with(&self, renderer: &dyn SqlRenderer) -> String {
+        self.annotated(renderer.platform(), renderer.render(&self.to_statement()))
+    }
 
-        // Columns
-        if !self.columns.is_empty() {
-            sql.push_str(" (");
-            let cols: Vec<String> = self.columns.iter()
-                .map(|c| platform.quote_identifier(c))
-                .collect();
-            sql.push_str(&cols.join(", "));
-            sql.push(')');
+    /// Add the optimizer hints and comments to rendered SQL
+    fn annotated(&self, platform: &dyn Platform, mut sql: String) -> String {
+        if !self.hints.is_empty() {
+            let keyword = match self.query_type {
+                QueryType::Select => "SELECT",
+                QueryType::Insert => "INSERT",
+                QueryType::Update => "UPDATE",
+                QueryType::Delete => "DELETE",
+            };
+            if let (Some(hints), Some(end)) = (platform.optimizer_hints_sql(&self.hints), keyword_end(&sql, keyword)) {
+                sql.insert_str(end, &format!(" {hints}"));
+            }
         }
-
-        // VALUES
-        sql.push_str(" VALUES ");
-        let rows: Vec<String> = self.values.iter()
-            .map(|row| {
-                let vals: Vec<String> = row.iter()
-                    .map(|v| self.value_to_sql(v))
-                    .collect();
-                format!("({})", vals.join(", "))
-            })
-            .collect();
-        sql.push_str(&rows.join(", "));
-
-        // RETURNING
-        if !self.returning.is_empty() && platform.supports_returning() {
-            sql.push_str(" RETURNING ");
-            let cols: Vec<String> = self.returning.iter()
-                .map(|c| platform.quote_identifier(c))
-                .collect();
-            sql.push_str(&cols.join(", "));
+        for comment in self.comments.iter().rev() {
+            sql.insert_str(0, &format!("{} ", platform.comment_sql(comment)));
         }
-
         sql
     }
 
-    /// Build an UPDATE SQL statement
-    fn build_update<P: Platform>(&self, platform: &P) -> String {
-        let mut sql = String::from("UPDATE ");
-        sql.push_str(&platform.quote_identifier(&self.table));
-
-        // SET
-        sql.push_str(" SET ");
-        let sets: Vec<String> = self.set_values.iter()
-            .map(|(col, val)| {
-                format!("{} = {}", platform.quote_identifier(col), self.value_to_sql(val))
+    /// Build the statement tree of a SELECT
+    fn select_statement(&self) -> SelectStatement {
+        let projection = self.columns.iter()
+            .map(|c| if c == "*" { SelectItem::Wildcard } else { SelectItem::Column(c.clone()) })
+            .chain(self.select_raw.iter().cloned().map(SelectItem::Raw))
+            .collect();
+        let from = match (&self.from_subquery, &self.from_values) {
+            (Some(subquery), _) => TableSource::Subquery(Box::new(subquery.to_statement())),
+            (None, Some((rows, columns))) => TableSource::Values {
+                rows: rows.iter().map(|row| row.iter().cloned().map(Expr::Value).collect()).collect(),
+                columns: columns.clone(),
+            },
+            (None, None) => TableSource::Table(self.prefixed_table(&self.table)),
+        };
+        let joins = self.joins.iter()
+            .map(|(join, lateral)| match lateral {
+                Some(subquery) => Join::lateral(
+                    join.kind,
+                    subquery.to_statement(),
+                    join.alias.clone().unwrap_or_default(),
+                    join.condition.clone(),
+                ),
+                None => Join {
+                    table: self.prefixed_table(&join.table),
+                    ..join.clone()
+                },
             })
             .collect();
-        sql.push_str(&sets.join(", "));
 
-        // WHERE
-        if let Some(ref where_expr) = self.where_expr {
-            sql.push_str(" WHERE ");
-            sql.push_str(&self.expr_to_sql(where_expr, platform));
+        SelectStatement {
+            distinct: self.distinct,
+            projection,
+            from,
+            alias: self.table_alias.clone(),
+            joins,
+            filter: self.where_expr.clone(),
+            group_by: self.group_by.clone(),
+            having: self.having.clone(),
+            order_by: self.order_by.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            lock: self.lock,
         }
-
-        // RETURNING
-        if !self.returning.is_empty() && platform.supports_returning() {
-            sql.push_str(" RETURNING ");
-            let cols: Vec<String> = self.returning.iter()
-                .map(|c| platform.quote_identifier(c))
-                .collect();
-            sql.push_str(&cols.join(", "));
-        }
-
-        sql
     }
 
-    /// Build a DELETE SQL statement
-    fn build_delete<P: Platform>(&self, platform: &P) -> String {
-        let mut sql = String::from("DELETE FROM ");
-        sql.push_str(&platform.quote_identifier(&self.table));
-
-        // WHERE
-        if let Some(ref where_expr) = self.where_expr {
-            sql.push_str(" WHERE ");
-            sql.push_str(&self.expr_to_sql(where_expr, platform));
-        }
-
-        // RETURNING
-        if !self.returning.is_empty() && platform.supports_returning() {
-            sql.push_str(" RETURNING ");
-            let cols: Vec<String> = self.returning.iter()
-                .map(|c| platform.quote_identifier(c))
-                .collect();
-            sql.push_str(&cols.join(", "));
+    /// Build the affected rows of an UPDATE/DELETE
+    fn modify_filter(&self) -> ModifyFilter {
+        ModifyFilter {
+            condition: self.where_expr.clone(),
+            order_by: self.order_by.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            limit_key: self.limit_key.clone(),
         }
-
-        sql
     }
 
-    /// Convert an expression to SQL
-    fn expr_to_sql<P: Platform>(&self, expr: &Expr, platform: &P) -> String {
-        match expr {
-            Expr::Column(name) => platform.quote_identifier(name),
-            Expr::Value(val) => self.value_to_sql(val),
-            Expr::Param(name) => name.clone(),
-            Expr::Comparison(left, op, right) => {
-                format!(
-                    "{} {} {}",
-                    self.expr_to_sql(left, platform),
-                    op.as_sql(),
-                    self.expr_to_sql(right, platform)
-                )
-            }
-            Expr::And(exprs) => {
-                let parts: Vec<String> = exprs.iter()
-                    .map(|e| self.expr_to_sql(e, platform))
-                    .collect();
-                format!("({})", parts.join(" AND "))
-            }
-            Expr::Or(exprs) => {
-                let parts: Vec<String> = exprs.iter()
-                    .map(|e| self.expr_to_sql(e, platform))
-                    .collect();
-                format!("({})", parts.join(" OR "))
-            }
-            Expr::Not(inner) => {
-                format!("NOT ({})", self.expr_to_sql(inner, platform))
-            }
-            Expr::IsNull(inner) => {
-                format!("{} IS NULL", self.expr_to_sql(inner, platform))
-            }
-            Expr::IsNotNull(inner) => {
-                format!("{} IS NOT NULL", self.expr_to_sql(inner, platform))
-            }
-            Expr::In(col, values) => {
-                let vals: Vec<String> = values.iter()
-                    .map(|v| self.expr_to_sql(v, platform))
-                    .collect();
-                format!("{} IN ({})", self.expr_to_sql(col, platform), vals.join(", "))
-            }
-            Expr::NotIn(col, values) => {
-                let vals: Vec<String> = values.iter()
-                    .map(|v| self.expr_to_sql(v, platform))
-                    .collect();
-                format!("{} NOT IN ({})", self.expr_to_sql(col, platform), vals.join(", "))
-            }
-            Expr::Between(col, low, high) => {
-                format!(
-                    "{} BETWEEN {} AND {}",
-                    self.expr_to_sql(col, platform),
-                    self.expr_to_sql(low, platform),
-                    self.expr_to_sql(high, platform)
-                )
-            }
-            Expr::Like(col, pattern) => {
-                format!("{} LIKE {}", self.expr_to_sql(col, platform), platform.quote_string(pattern))
-            }
-            Expr::Raw(sql) => sql.clone(),
-        }
+    /// Get a table name with the tenant's table prefix applied
+    fn prefixed_table(&self, table: &str) -> String {
+        self.tenant.as_ref().map_or_else(|| table.to_string(), |tenant| tenant.table_name(table).into_owned())
     }
+}
 
-    /// Convert a SQL value to its SQL representation
-    #[allow(clippy::unused_self)]
-    fn value_to_sql(&self, value: &SqlValue) -> String {
-        value.to_string()
+impl core::fmt::Display for QueryBuilder {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.to_sql(&AnsiPlatform::new()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::Error;
     use crate::platform::{PostgresPlatform, MySqlPlatform, SqlitePlatform};
 
     #[test]
@@ -790,6 +1069,152 @@ mod tests {
         assert!(sql.contains("OFFSET 20"));
     }
 
+    #[test]
+    fn test_order_by_primary_key() {
+        use crate::platform::{Column, Index, SqlType};
+
+        let lines = Table::new("order_lines")
+            .column(Column::new("order_id", SqlType::BigInt))
+            .column(Column::new("line", SqlType::Integer))
+            .column(Column::new("price", SqlType::decimal(10, 2)))
+            .index(Index::primary(vec!["order_id".into(), "line".into()]));
+        let sql = QueryBuilder::select().all().from("order_lines").order_by_primary_key(&lines).to_sql(&PostgresPlatform);
+        assert_eq!(sql, "SELECT * FROM \"order_lines\" ORDER BY \"order_id\" ASC, \"line\" ASC");
+
+        let sql = QueryBuilder::select()
+            .all()
+            .from("order_lines")
+            .order_by_desc("price")
+            .order_by_asc("order_id")
+            .order_by_primary_key(&lines)
+            .limit(20)
+            .to_sql(&MySqlPlatform::new());
+        assert_eq!(sql, "SELECT * FROM `order_lines` ORDER BY `price` DESC, `order_id` ASC, `line` ASC LIMIT 20");
+
+        let keyless = Table::new("log").column(Column::new("message", SqlType::Text));
+        let query = QueryBuilder::select().all().from("log").order_by_asc("message");
+        assert_eq!(query.clone().order_by_primary_key(&keyless), query);
+    }
+
+    #[test]
+    fn test_values_table() {
+        let rows = vec![vec![SqlValue::I64(1), "a".into()], vec![SqlValue::I64(2), "b".into()]];
+        let query = QueryBuilder::select().all().values_table(rows, "v", &["id", "code"]);
+        assert_eq!(
+            query.to_sql(&PostgresPlatform),
+            "SELECT * FROM (VALUES (1, 'a'), (2, 'b')) AS \"v\"(\"id\", \"code\")"
+        );
+        assert_eq!(
+            query.to_sql(&SqlitePlatform::new()),
+            "SELECT * FROM (SELECT column1 AS \"id\", column2 AS \"code\" FROM (VALUES (1, 'a'), (2, 'b'))) AS \"v\""
+        );
+        assert_eq!(
+            query.to_sql(&MySqlPlatform::new()),
+            "SELECT * FROM (SELECT 1 AS `id`, 'a' AS `code` UNION ALL SELECT 2, 'b') AS `v`"
+        );
+        let empty = QueryBuilder::select().all().values_table(Vec::new(), "v", &["id"]);
+        assert_eq!(
+            empty.to_sql(&PostgresPlatform),
+            "SELECT * FROM (SELECT NULL AS \"id\" WHERE 1 = 0) AS \"v\""
+        );
+    }
+
+    #[test]
+    fn test_lateral_join() {
+        let latest = QueryBuilder::select()
+            .columns(&["total"])
+            .from("orders")
+            .where_expr(Expr::raw("orders.user_id = users.id").and(Expr::col("status").eq(Expr::val("paid"))))
+            .limit(1);
+        let query = QueryBuilder::select()
+            .all()
+            .from("users")
+            .left_join_lateral(latest, "latest", Expr::raw("TRUE"));
+        assert_eq!(
+            query.try_to_sql(&MySqlPlatform::new()).unwrap(),
+            "SELECT * FROM `users` LEFT JOIN LATERAL (SELECT `total` FROM `orders` \
+             WHERE (orders.user_id = users.id AND `status` = 'paid') LIMIT 1) AS `latest` ON TRUE"
+        );
+        assert!(query.try_to_sql(&SqlitePlatform::new()).is_err());
+        assert!(query.to_sql(&SqlitePlatform::new()).contains("LEFT JOIN LATERAL"));
+        assert!(query.try_to_sql(&MySqlPlatform::new().with_server_version("10.11.2-MariaDB")).is_err());
+
+        let policy = ValueRedactionPolicy::new().with_column_pattern("status");
+        assert!(!query.redacted(&policy).to_string().contains("paid"));
+    }
+
+    #[test]
+    fn test_hints_and_comments() {
+        let query = QueryBuilder::select()
+            .all()
+            .from("users")
+            .hint("MAX_EXECUTION_TIME(1000)")
+            .hint("NO_INDEX_MERGE(users)")
+            .comment("service=billing")
+            .comment("trace=*/ DROP");
+        assert_eq!(
+            query.to_sql(&MySqlPlatform::new()),
+            "/* service=billing */ /* trace=* / DROP */ SELECT /*+ MAX_EXECUTION_TIME(1000) NO_INDEX_MERGE(users) */ * FROM `users`"
+        );
+        assert_eq!(
+            query.try_to_sql(&PostgresPlatform).unwrap(),
+            "/* service=billing */ /* trace=* / DROP */ SELECT * FROM \"users\""
+        );
+        let delete = QueryBuilder::delete().from("users").hint("BKA(users)");
+        assert_eq!(delete.to_sql(&MySqlPlatform::new()), "DELETE /*+ BKA(users) */ FROM `users`");
+
+        // PostgreSQL nests comments, so an opening `/*` is broken up too
+        let nested = QueryBuilder::select().all().from("users").comment("a /* b");
+        assert_eq!(nested.to_sql(&PostgresPlatform), "/* a / * b */ SELECT * FROM \"users\"");
+
+        // Hints follow the statement's own keyword, not a leading WITH
+        struct WithRenderer(MySqlPlatform);
+        impl SqlRenderer for WithRenderer {
+            fn platform(&self) -> &dyn Platform {
+                &self.0
+            }
+
+            fn render(&self, statement: &Statement) -> String {
+                let sql = PlatformRenderer::new(&self.0).render(statement);
+                format!("WITH `active` AS (SELECT 'with select' AS `id`) {sql}")
+            }
+        }
+        let query = QueryBuilder::select().all().from("active").hint("BKA(active)");
+        assert_eq!(
+            query.to_sql_with(&WithRenderer(MySqlPlatform::new())),
+            "WITH `active` AS (SELECT 'with select' AS `id`) SELECT /*+ BKA(active) */ * FROM `active`"
+        );
+    }
+
+    #[test]
+    fn test_invalid_identifiers_rejected() {
+        let query = QueryBuilder::select().columns(&["id"]).from("users").where_eq("na\0me", "x");
+        assert!(matches!(
+            query.try_to_sql(&PostgresPlatform),
+            Err(Error::Schema(crate::core::SchemaError::InvalidDefinition(_)))
+        ));
+        let update = QueryBuilder::update().table(&"t".repeat(64)).set("a", 1i64);
+        assert!(update.try_to_sql(&PostgresPlatform).is_err());
+        assert!(update.try_to_sql(&SqlitePlatform::new()).is_ok());
+        assert!(QueryBuilder::select().all().from("`users`").try_to_sql(&MySqlPlatform::new()).is_err());
+    }
+
+    #[test]
+    fn test_select_for_update() {
+        let query = QueryBuilder::select().columns(&["id"]).from("jobs").limit(5).for_update_skip_locked();
+        assert_eq!(
+            query.to_sql(&PostgresPlatform),
+            "SELECT \"id\" FROM \"jobs\" LIMIT 5 FOR UPDATE SKIP LOCKED"
+        );
+        // No SKIP LOCKED in ANSI SQL, no row locks in SQLite
+        assert_eq!(query.to_sql(&AnsiPlatform::new()), "SELECT \"id\" FROM \"jobs\" LIMIT 5 FOR UPDATE");
+        assert_eq!(query.to_sql(&SqlitePlatform::new()), "SELECT \"id\" FROM \"jobs\" LIMIT 5");
+        assert_eq!(
+            QueryBuilder::select().all().from("jobs").for_update().to_sql(&MySqlPlatform::new()),
+            "SELECT * FROM `jobs` FOR UPDATE"
+        );
+    }
+
     #[test]
     fn test_select_with_group_by() {
         let sql = QueryBuilder::select()
@@ -868,12 +1293,70 @@ mod tests {
         assert_eq!(sql, "DELETE FROM \"users\" WHERE \"id\" = 1");
     }
 
+    #[test]
+    fn test_delete_limit_mysql_native() {
+        let sql = QueryBuilder::delete()
+            .from("logs")
+            .where_eq("level", "debug")
+            .order_by_asc("created_at")
+            .limit(1000)
+            .try_to_sql(&MySqlPlatform::new())
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "DELETE FROM `logs` WHERE `level` = 'debug' ORDER BY `created_at` ASC LIMIT 1000"
+        );
+    }
+
+    #[test]
+    fn test_delete_limit_emulated() {
+        let sql = QueryBuilder::delete()
+            .from("logs")
+            .where_eq("level", "debug")
+            .order_by_asc("created_at")
+            .limit(1000)
+            .try_to_sql(&SqlitePlatform::new())
+            .unwrap();
+
+        assert_eq!(
+            sql,
+            "DELETE FROM \"logs\" WHERE \"rowid\" IN (SELECT \"rowid\" FROM \"logs\" \
+             WHERE \"level\" = 'debug' ORDER BY \"created_at\" ASC LIMIT 1000)"
+        );
+
+        let sql = QueryBuilder::update()
+            .table("jobs")
+            .set("claimed", true)
+            .order_by_asc("id")
+            .limit(10)
+            .limit_key("id")
+            .to_sql(&PostgresPlatform);
+
+        assert_eq!(
+            sql,
+            "UPDATE \"jobs\" SET \"claimed\" = true WHERE \"id\" IN \
+             (SELECT \"id\" FROM \"jobs\" ORDER BY \"id\" ASC LIMIT 10)"
+        );
+    }
+
+    #[test]
+    fn test_delete_offset_unsupported_on_mysql() {
+        let result = QueryBuilder::delete()
+            .from("logs")
+            .limit(10)
+            .offset(5)
+            .try_to_sql(&MySqlPlatform::new());
+
+        assert!(matches!(result, Err(Error::Platform(_))));
+    }
+
     #[test]
     fn test_mysql_quoting() {
         let sql = QueryBuilder::select()
             .all()
             .from("users")
-            .to_sql(&MySqlPlatform);
+            .to_sql(&MySqlPlatform::new());
 
         assert_eq!(sql, "SELECT * FROM `users`");
     }
@@ -883,7 +1366,7 @@ mod tests {
         let sql = QueryBuilder::select()
             .all()
             .from("users")
-            .to_sql(&SqlitePlatform);
+            .to_sql(&SqlitePlatform::new());
 
         assert_eq!(sql, "SELECT * FROM \"users\"");
     }
@@ -940,4 +1423,231 @@ mod tests {
         assert!(sql.contains("\"age\" <= 65"));
         assert!(sql.contains("\"status\" = 'active'"));
     }
+
+    #[test]
+    fn test_reset_clauses() {
+        let sql = QueryBuilder::select()
+            .from("users")
+            .where_eq("active", true)
+            .order_by_asc("name")
+            .limit(10)
+            .offset(20)
+            .reset_where()
+            .reset_order_by()
+            .reset_limit()
+            .to_sql(&PostgresPlatform);
+
+        assert_eq!(sql, "SELECT * FROM \"users\"");
+    }
+
+    #[test]
+    fn test_count_query() {
+        let query = QueryBuilder::select()
+            .columns(&["id", "name"])
+            .from("users")
+            .where_eq("active", true)
+            .order_by_asc("name")
+            .limit(10);
+
+        assert_eq!(
+            query.count_query().to_sql(&PostgresPlatform),
+            "SELECT COUNT(*) FROM \"users\" WHERE \"active\" = true"
+        );
+        // The original query is untouched
+        assert!(query.to_sql(&PostgresPlatform).ends_with("LIMIT 10"));
+    }
+
+    #[test]
+    fn test_count_query_grouped() {
+        let sql = QueryBuilder::select()
+            .columns(&["role"])
+            .from("users")
+            .group_by(&["role"])
+            .order_by_asc("role")
+            .count_query()
+            .to_sql(&PostgresPlatform);
+
+        assert_eq!(
+            sql,
+            "SELECT COUNT(*) FROM (SELECT \"role\" FROM \"users\" GROUP BY \"role\") AS \"count_query\""
+        );
+    }
+
+    #[test]
+    fn test_builder_eq_and_display() {
+        let build = || QueryBuilder::select().columns(&["id"]).from("users").where_eq("id", 1);
+
+        assert_eq!(build(), build());
+        assert_ne!(build(), build().limit(1));
+        assert_eq!(build().to_string(), "SELECT \"id\" FROM \"users\" WHERE \"id\" = 1");
+    }
+
+    #[test]
+    fn test_tenant_table_prefix() {
+        let tenant = TenantStrategy::table_prefix("acme_");
+        let sql = QueryBuilder::select()
+            .from("users")
+            .alias("u")
+            .join_alias(JoinType::Inner, "orders", "o", Expr::col("u.id").eq(Expr::col("o.user_id")))
+            .tenant(&tenant)
+            .to_sql(&PostgresPlatform);
+        assert!(sql.starts_with("SELECT * FROM \"acme_users\" AS \"u\" INNER JOIN \"acme_orders\" AS \"o\" ON "));
+
+        let sql = QueryBuilder::delete().from("users").where_eq("id", 1).tenant(&tenant).to_sql(&PostgresPlatform);
+        assert_eq!(sql, "DELETE FROM \"acme_users\" WHERE \"id\" = 1");
+
+        // Schema tenants are selected on the connection
+        let sql = QueryBuilder::update()
+            .table("users")
+            .set("name", "Bob")
+            .tenant(&TenantStrategy::schema("acme"))
+            .to_sql(&PostgresPlatform);
+        assert_eq!(sql, "UPDATE \"users\" SET \"name\" = 'Bob'");
+    }
+
+    #[test]
+    fn test_expand_wildcard() {
+        let tenant = TenantStrategy::table_prefix("acme_");
+        let query = QueryBuilder::select().from("users").tenant(&tenant);
+        assert!(query.selects_wildcard());
+        assert_eq!(query.wildcard_table().as_deref(), Some("acme_users"));
+        assert_eq!(
+            query.expand_wildcard(&["id", "name"]).to_sql(&PostgresPlatform),
+            "SELECT \"id\", \"name\" FROM \"acme_users\""
+        );
+
+        let query = QueryBuilder::select().column("name").all().from("users").expand_wildcard(&["id", "name"]);
+        assert_eq!(query.to_sql(&PostgresPlatform), "SELECT \"name\", \"id\", \"name\" FROM \"users\"");
+        assert!(!query.selects_wildcard());
+
+        // Joined tables contribute to `*`, so the query is left alone
+        let joined = QueryBuilder::select()
+            .all()
+            .from("users")
+            .inner_join("orders", Expr::raw("users.id = orders.user_id"));
+        assert_eq!(joined.wildcard_table(), None);
+        assert_eq!(joined.clone().expand_wildcard(&["id"]), joined);
+        assert!(!QueryBuilder::select().select_raw("COUNT(*)").from("users").selects_wildcard());
+    }
+
+    #[test]
+    fn test_accessors() {
+        let insert = QueryBuilder::insert()
+            .into("users")
+            .insert_columns(&["id", "name"])
+            .values(vec![SqlValue::I64(7), "Alice".into()]);
+        assert_eq!(insert.query_type(), QueryType::Insert);
+        assert_eq!(insert.table_name(), "users");
+        assert_eq!(insert.changed_columns(), ["id", "name"]);
+        assert_eq!(insert.key_value("id"), Some(&SqlValue::I64(7)));
+
+        let update = QueryBuilder::update()
+            .table("users")
+            .set("name", "Bob")
+            .where_expr(Expr::col("active").eq(true).and(Expr::col("id").eq(7)));
+        assert_eq!(update.changed_columns(), ["name"]);
+        assert_eq!(update.key_value("id"), Some(&SqlValue::I32(7)));
+
+        let delete = QueryBuilder::delete().from("users").where_expr(Expr::col("id").gt(7));
+        assert!(delete.changed_columns().is_empty());
+        assert_eq!(delete.key_value("id"), None);
+    }
+
+    #[test]
+    fn test_redacted() {
+        let policy = ValueRedactionPolicy::sensitive_columns();
+        let insert = QueryBuilder::insert()
+            .into("users")
+            .insert_columns(&["name", "password"])
+            .values(vec!["alice".into(), "hunter2".into()]);
+        assert_eq!(
+            insert.redacted(&policy).to_string(),
+            "INSERT INTO \"users\" (\"name\", \"password\") VALUES ('alice', '***')"
+        );
+
+        let update = QueryBuilder::update()
+            .table("users")
+            .set("api_token", "abc")
+            .where_expr(Expr::col("ssn").in_list(vec![Expr::val("123"), Expr::val("456")]).and(Expr::col("id").eq(7)));
+        assert_eq!(
+            update.redacted(&policy).to_string(),
+            "UPDATE \"users\" SET \"api_token\" = '***' WHERE (\"ssn\" IN ('***', '***') AND \"id\" = 7)"
+        );
+        assert_eq!(update.redacted(&ValueRedactionPolicy::new()), update);
+    }
+
+    #[test]
+    fn test_regexp() {
+        let query = QueryBuilder::select()
+            .from("users")
+            .where_expr(Expr::col("name").regexp_ci("^a").and(Expr::col("code").not_regexp(r"\d+")));
+
+        assert_eq!(
+            query.to_sql(&PostgresPlatform),
+            "SELECT * FROM \"users\" WHERE (\"name\" ~* '^a' AND \"code\" !~ '\\d+')"
+        );
+        assert_eq!(
+            query.to_sql(&MySqlPlatform::new()),
+            "SELECT * FROM `users` WHERE (`name` REGEXP '(?i)^a' AND `code` NOT REGEXP '\\\\d+')"
+        );
+
+        let result = query.try_to_sql(&SqlitePlatform::new());
+        assert!(matches!(result, Err(Error::Platform(_))));
+        assert_eq!(
+            query.try_to_sql(&SqlitePlatform::new().with_regexp(true)).unwrap(),
+            "SELECT * FROM \"users\" WHERE (\"name\" REGEXP '(?i)^a' AND \"code\" NOT REGEXP '\\d+')"
+        );
+    }
+
+    #[test]
+    fn test_non_finite_float_values() {
+        let query = QueryBuilder::insert()
+            .into("readings")
+            .insert_columns(&["value"])
+            .values(vec![SqlValue::F64(f64::INFINITY)]);
+
+        assert_eq!(
+            query.try_to_sql(&PostgresPlatform).unwrap(),
+            "INSERT INTO \"readings\" (\"value\") VALUES (CAST('Infinity' AS DOUBLE PRECISION))"
+        );
+        let result = query.try_to_sql(&MySqlPlatform::new());
+        assert!(matches!(result, Err(Error::Platform(_))));
+        let filter = QueryBuilder::select().from("readings").where_expr(Expr::col("value").lt(Expr::val(f64::NAN)));
+        assert!(filter.try_to_sql(&SqlitePlatform::new()).is_err());
+        assert!(matches!(filter.try_to_sql(&MySqlPlatform::new()), Err(Error::Platform(_))));
+        assert_eq!(
+            filter.try_to_sql(&PostgresPlatform).unwrap(),
+            "SELECT * FROM \"readings\" WHERE \"value\" < CAST('NaN' AS DOUBLE PRECISION)"
+        );
+
+        let update = QueryBuilder::update()
+            .table("readings")
+            .set("value", f64::NEG_INFINITY)
+            .where_expr(Expr::col("value").eq(Expr::val(f64::NAN)));
+        assert_eq!(
+            update.try_to_sql(&PostgresPlatform).unwrap(),
+            "UPDATE \"readings\" SET \"value\" = CAST('-Infinity' AS DOUBLE PRECISION) \
+             WHERE \"value\" = CAST('NaN' AS DOUBLE PRECISION)"
+        );
+        assert!(matches!(update.try_to_sql(&MySqlPlatform::new()), Err(Error::Platform(_))));
+        let update = QueryBuilder::update().table("readings").set("value", 1.5).where_expr(
+            Expr::col("value").eq(Expr::val(f64::INFINITY)),
+        );
+        assert!(matches!(update.try_to_sql(&MySqlPlatform::new()), Err(Error::Platform(_))));
+    }
+
+    #[test]
+    fn test_dyn_platform() {
+        let platforms: Vec<Box<dyn Platform>> = vec![
+            Box::new(PostgresPlatform),
+            Box::new(MySqlPlatform::new()),
+            Box::new(SqlitePlatform::new()),
+        ];
+        let query = QueryBuilder::select().columns(&["id"]).from("users").where_eq("id", 1);
+
+        let sql: Vec<String> = platforms.iter().map(|p| query.to_sql(p.as_ref())).collect();
+        assert_eq!(sql[0], "SELECT \"id\" FROM \"users\" WHERE \"id\" = 1");
+        assert_eq!(sql[1], "SELECT `id` FROM `users` WHERE `id` = 1");
+        assert_eq!(sql[2], sql[0]);
+    }
 }
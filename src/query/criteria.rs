@@ -0,0 +1,206 @@
+//! Criteria for simple filtering without a full query builder
+
+use alloc::{string::ToString, vec::Vec};
+use crate::core::SqlValue;
+use super::ast::{OrderBy, OrderDirection};
+use super::builder::QueryBuilder;
+use super::expr::Expr;
+
+/// Conditions, ordering and limits for the simple table helpers
+///
+/// A lighter-weight alternative to [`QueryBuilder`] for the common case of
+/// filtering one table: conditions are combined with AND and compare
+/// columns to values. Used by `Connection::select`, `update` and `delete`,
+/// or applied to a builder with [`apply`](Self::apply).
+///
+/// ```rust
+/// use rustine_dbal::query::{Criteria, QueryBuilder};
+/// use rustine_dbal::platform::PostgresPlatform;
+///
+/// let criteria = Criteria::new().eq("status", "active").gt("age", 18).order_by("name").limit(10);
+/// let sql = criteria.apply(QueryBuilder::select().all().from("users")).to_sql(&PostgresPlatform);
+/// assert_eq!(
+///     sql,
+///     "SELECT * FROM \"users\" WHERE (\"status\" = 'active' AND \"age\" > 18) ORDER BY \"name\" ASC LIMIT 10"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Criteria {
+    /// Conditions, combined with AND
+    conditions: Vec<Expr>,
+    /// ORDER BY clauses
+    order_by: Vec<OrderBy>,
+    /// LIMIT value
+    limit: Option<u64>,
+    /// OFFSET value
+    offset: Option<u64>,
+}
+
+impl Criteria {
+    /// Create criteria matching all rows
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a condition
+    #[must_use]
+    pub fn with(mut self, condition: Expr) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Require column = value
+    #[must_use]
+    pub fn eq(self, column: &str, value: impl Into<SqlValue>) -> Self {
+        self.with(Expr::col(column).eq(Expr::val(value)))
+    }
+
+    /// Require column <> value
+    #[must_use]
+    pub fn ne(self, column: &str, value: impl Into<SqlValue>) -> Self {
+        self.with(Expr::col(column).ne(Expr::val(value)))
+    }
+
+    /// Require column < value
+    #[must_use]
+    pub fn lt(self, column: &str, value: impl Into<SqlValue>) -> Self {
+        self.with(Expr::col(column).lt(Expr::val(value)))
+    }
+
+    /// Require column <= value
+    #[must_use]
+    pub fn le(self, column: &str, value: impl Into<SqlValue>) -> Self {
+        self.with(Expr::col(column).le(Expr::val(value)))
+    }
+
+    /// Require column > value
+    #[must_use]
+    pub fn gt(self, column: &str, value: impl Into<SqlValue>) -> Self {
+        self.with(Expr::col(column).gt(Expr::val(value)))
+    }
+
+    /// Require column >= value
+    #[must_use]
+    pub fn ge(self, column: &str, value: impl Into<SqlValue>) -> Self {
+        self.with(Expr::col(column).ge(Expr::val(value)))
+    }
+
+    /// Require column IN (values)
+    #[must_use]
+    pub fn in_list(self, column: &str, values: Vec<SqlValue>) -> Self {
+        self.with(Expr::col(column).in_list(values.into_iter().map(Expr::val).collect()))
+    }
+
+    /// Require column LIKE pattern
+    #[must_use]
+    pub fn like(self, column: &str, pattern: &str) -> Self {
+        self.with(Expr::col(column).like(pattern))
+    }
+
+    /// Require column IS NULL
+    #[must_use]
+    pub fn is_null(self, column: &str) -> Self {
+        self.with(Expr::col(column).is_null())
+    }
+
+    /// Require column IS NOT NULL
+    #[must_use]
+    pub fn is_not_null(self, column: &str) -> Self {
+        self.with(Expr::col(column).is_not_null())
+    }
+
+    /// Order by a column ascending
+    #[must_use]
+    pub fn order_by(self, column: &str) -> Self {
+        self.order_by_direction(column, OrderDirection::Asc)
+    }
+
+    /// Order by a column descending
+    #[must_use]
+    pub fn order_by_desc(self, column: &str) -> Self {
+        self.order_by_direction(column, OrderDirection::Desc)
+    }
+
+    /// Order by a column in the given direction
+    #[must_use]
+    pub fn order_by_direction(mut self, column: &str, direction: OrderDirection) -> Self {
+        self.order_by.push(OrderBy {
+            column: column.to_string(),
+            direction,
+        });
+        self
+    }
+
+    /// Set LIMIT
+    #[must_use]
+    pub const fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set OFFSET
+    #[must_use]
+    pub const fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Get the conditions combined with AND, `None` when all rows match
+    #[must_use]
+    pub fn to_expr(&self) -> Option<Expr> {
+        let mut conditions = self.conditions.iter().cloned();
+        let first = conditions.next()?;
+        Some(conditions.fold(first, Expr::and))
+    }
+
+    /// Add the conditions, ordering and limits to a query builder
+    #[must_use]
+    pub fn apply(&self, query: QueryBuilder) -> QueryBuilder {
+        let mut query = match self.to_expr() {
+            Some(expr) => query.where_expr(expr),
+            None => query,
+        };
+        for order_by in &self.order_by {
+            query = query.order_by(&order_by.column, order_by.direction);
+        }
+        if let Some(limit) = self.limit {
+            query = query.limit(limit);
+        }
+        if let Some(offset) = self.offset {
+            query = query.offset(offset);
+        }
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{PostgresPlatform, SqlitePlatform};
+
+    #[test]
+    fn test_to_expr() {
+        assert_eq!(Criteria::new().to_expr(), None);
+        assert_eq!(
+            Criteria::new().eq("status", "active").to_expr(),
+            Some(Expr::col("status").eq(Expr::val("active")))
+        );
+        assert_eq!(
+            Criteria::new().ge("age", 18).is_not_null("email").to_expr().unwrap().to_string(),
+            "(\"age\" >= 18 AND \"email\" IS NOT NULL)"
+        );
+    }
+
+    #[test]
+    fn test_apply_to_update_and_delete() {
+        let criteria = Criteria::new().in_list("id", vec![1i64.into(), 2i64.into()]);
+        let update = criteria.apply(QueryBuilder::update().table("users").set("active", false));
+        assert_eq!(
+            update.to_sql(&PostgresPlatform),
+            "UPDATE \"users\" SET \"active\" = false WHERE \"id\" IN (1, 2)"
+        );
+        let delete = Criteria::new().like("name", "A%").apply(QueryBuilder::delete().from("users"));
+        assert_eq!(delete.to_sql(&SqlitePlatform::new()), "DELETE FROM \"users\" WHERE \"name\" LIKE 'A%'");
+    }
+}
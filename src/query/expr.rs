@@ -1,9 +1,14 @@
 //! Expression types for building WHERE clauses and conditions
 
-use crate::core::SqlValue;
+use alloc::{boxed::Box, string::{String, ToString}, vec::Vec, format, vec};
+use crate::core::{SqlValue, ValueRedactionPolicy};
+use crate::platform::{AnsiPlatform, Platform};
 
 /// A SQL expression that can be used in WHERE clauses
-#[derive(Debug, Clone)]
+///
+/// Expressions compare structurally and display as SQL rendered for
+/// [`AnsiPlatform`].
+#[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
     /// Column reference
     Column(String),
@@ -12,25 +17,36 @@ pub enum Expr {
     /// Parameter placeholder (e.g., $1, ?, :name)
     Param(String),
     /// Comparison: column op value
-    Comparison(Box<Expr>, ComparisonOp, Box<Expr>),
+    Comparison(Box<Self>, ComparisonOp, Box<Self>),
     /// AND of multiple expressions
-    And(Vec<Expr>),
+    And(Vec<Self>),
     /// OR of multiple expressions
-    Or(Vec<Expr>),
+    Or(Vec<Self>),
     /// NOT expression
-    Not(Box<Expr>),
+    Not(Box<Self>),
     /// IS NULL
-    IsNull(Box<Expr>),
+    IsNull(Box<Self>),
     /// IS NOT NULL
-    IsNotNull(Box<Expr>),
+    IsNotNull(Box<Self>),
     /// IN (values)
-    In(Box<Expr>, Vec<Expr>),
+    In(Box<Self>, Vec<Self>),
     /// NOT IN (values)
-    NotIn(Box<Expr>, Vec<Expr>),
+    NotIn(Box<Self>, Vec<Self>),
     /// BETWEEN low AND high
-    Between(Box<Expr>, Box<Expr>, Box<Expr>),
+    Between(Box<Self>, Box<Self>, Box<Self>),
     /// LIKE pattern
-    Like(Box<Expr>, String),
+    Like(Box<Self>, String),
+    /// Regular expression match, rendered by [`Platform::regexp_sql`]
+    Regexp {
+        /// Matched expression
+        expr: Box<Self>,
+        /// Regular expression
+        pattern: String,
+        /// Whether rows must not match
+        negated: bool,
+        /// Whether case is ignored
+        case_insensitive: bool,
+    },
     /// Raw SQL expression
     Raw(String),
 }
@@ -164,6 +180,78 @@ impl Expr {
         Self::Like(Box::new(self), pattern.into())
     }
 
+    /// Create a regular expression match
+    ///
+    /// Check [`Platform::supports_regexp`] first, or build queries with
+    /// [`QueryBuilder::try_to_sql`](super::QueryBuilder::try_to_sql), which
+    /// rejects regular expressions on platforms without support.
+    #[must_use]
+    pub fn regexp(self, pattern: impl Into<String>) -> Self {
+        self.regexp_match(pattern.into(), false, false)
+    }
+
+    /// Create a negated regular expression match
+    #[must_use]
+    pub fn not_regexp(self, pattern: impl Into<String>) -> Self {
+        self.regexp_match(pattern.into(), true, false)
+    }
+
+    /// Create a case-insensitive regular expression match
+    #[must_use]
+    pub fn regexp_ci(self, pattern: impl Into<String>) -> Self {
+        self.regexp_match(pattern.into(), false, true)
+    }
+
+    /// Create a negated case-insensitive regular expression match
+    #[must_use]
+    pub fn not_regexp_ci(self, pattern: impl Into<String>) -> Self {
+        self.regexp_match(pattern.into(), true, true)
+    }
+
+    /// Create a regular expression match with explicit flags
+    fn regexp_match(self, pattern: String, negated: bool, case_insensitive: bool) -> Self {
+        Self::Regexp { expr: Box::new(self), pattern, negated, case_insensitive }
+    }
+
+    /// Check if the expression contains a regular expression match
+    #[must_use]
+    pub fn uses_regexp(&self) -> bool {
+        match self {
+            Self::Regexp { .. } => true,
+            Self::Comparison(left, _, right) => left.uses_regexp() || right.uses_regexp(),
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().any(Self::uses_regexp),
+            Self::Not(inner) | Self::IsNull(inner) | Self::IsNotNull(inner) | Self::Like(inner, _) => {
+                inner.uses_regexp()
+            }
+            Self::In(col, values) | Self::NotIn(col, values) => {
+                col.uses_regexp() || values.iter().any(Self::uses_regexp)
+            }
+            Self::Between(col, low, high) => col.uses_regexp() || low.uses_regexp() || high.uses_regexp(),
+            Self::Column(_) | Self::Value(_) | Self::Param(_) | Self::Raw(_) => false,
+        }
+    }
+
+    /// Check if the expression contains a NaN or infinite float value
+    #[must_use]
+    pub fn has_non_finite_float(&self) -> bool {
+        match self {
+            Self::Value(value) => value.non_finite_float().is_some(),
+            Self::Comparison(left, _, right) => left.has_non_finite_float() || right.has_non_finite_float(),
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().any(Self::has_non_finite_float),
+            Self::Not(inner) | Self::IsNull(inner) | Self::IsNotNull(inner) | Self::Like(inner, _) => {
+                inner.has_non_finite_float()
+            }
+            Self::Regexp { expr, .. } => expr.has_non_finite_float(),
+            Self::In(col, values) | Self::NotIn(col, values) => {
+                col.has_non_finite_float() || values.iter().any(Self::has_non_finite_float)
+            }
+            Self::Between(col, low, high) => {
+                col.has_non_finite_float() || low.has_non_finite_float() || high.has_non_finite_float()
+            }
+            Self::Column(_) | Self::Param(_) | Self::Raw(_) => false,
+        }
+    }
+
     /// Negate this expression
     #[must_use]
     #[allow(clippy::should_implement_trait)]
@@ -194,6 +282,160 @@ impl Expr {
             _ => Self::Or(vec![self, other.into()]),
         }
     }
+
+    /// Copy the expression with sensitive values replaced by a placeholder
+    ///
+    /// Values compared to a column the policy redacts (`password = '...'`,
+    /// `ssn IN (...)`, `token LIKE '...'`) and values of redacted parameter
+    /// types are replaced, e.g. before rendering a query for a log.
+    #[must_use]
+    pub fn redacted(&self, policy: &ValueRedactionPolicy) -> Self {
+        self.redacted_for(None, policy)
+    }
+
+    /// Redact the expression, treating its values as belonging to `column`
+    fn redacted_for(&self, column: Option<&str>, policy: &ValueRedactionPolicy) -> Self {
+        let redact_list = |col: &Self, values: &[Self]| -> Vec<Self> {
+            values.iter().map(|v| v.redacted_for(column_name(col), policy)).collect()
+        };
+        match self {
+            Self::Value(value) => Self::Value(policy.redact_value(column, value)),
+            Self::Comparison(left, op, right) => {
+                let column = column_name(left).or_else(|| column_name(right));
+                Self::Comparison(
+                    Box::new(left.redacted_for(column, policy)),
+                    *op,
+                    Box::new(right.redacted_for(column, policy)),
+                )
+            }
+            Self::And(exprs) => Self::And(exprs.iter().map(|e| e.redacted(policy)).collect()),
+            Self::Or(exprs) => Self::Or(exprs.iter().map(|e| e.redacted(policy)).collect()),
+            Self::Not(inner) => Self::Not(Box::new(inner.redacted(policy))),
+            Self::In(col, values) => Self::In(col.clone(), redact_list(col, values)),
+            Self::NotIn(col, values) => Self::NotIn(col.clone(), redact_list(col, values)),
+            Self::Between(col, low, high) => Self::Between(
+                col.clone(),
+                Box::new(low.redacted_for(column_name(col), policy)),
+                Box::new(high.redacted_for(column_name(col), policy)),
+            ),
+            Self::Like(col, _) if column_name(col).is_some_and(|c| policy.redacts_column(c)) => {
+                Self::Like(col.clone(), ValueRedactionPolicy::PLACEHOLDER.to_string())
+            }
+            Self::Regexp { expr, negated, case_insensitive, .. }
+                if column_name(expr).is_some_and(|c| policy.redacts_column(c)) =>
+            {
+                Self::Regexp {
+                    expr: expr.clone(),
+                    pattern: ValueRedactionPolicy::PLACEHOLDER.to_string(),
+                    negated: *negated,
+                    case_insensitive: *case_insensitive,
+                }
+            }
+            Self::Column(_)
+            | Self::Param(_)
+            | Self::IsNull(_)
+            | Self::IsNotNull(_)
+            | Self::Like(..)
+            | Self::Regexp { .. }
+            | Self::Raw(_) => self.clone(),
+        }
+    }
+
+    /// Render the expression as SQL for a platform
+    #[must_use]
+    pub fn to_sql<P: Platform + ?Sized>(&self, platform: &P) -> String {
+        match self {
+            Self::Column(name) => platform.quote_identifier(name),
+            Self::Value(val) => value_sql(val, platform),
+            Self::Param(name) => name.clone(),
+            Self::Comparison(left, op, right) => {
+                format!(
+                    "{} {} {}",
+                    left.to_sql(platform),
+                    op.as_sql(),
+                    right.to_sql(platform)
+                )
+            }
+            Self::And(exprs) => {
+                let parts: Vec<String> = exprs.iter()
+                    .map(|e| e.to_sql(platform))
+                    .collect();
+                format!("({})", parts.join(" AND "))
+            }
+            Self::Or(exprs) => {
+                let parts: Vec<String> = exprs.iter()
+                    .map(|e| e.to_sql(platform))
+                    .collect();
+                format!("({})", parts.join(" OR "))
+            }
+            Self::Not(inner) => {
+                format!("NOT ({})", inner.to_sql(platform))
+            }
+            Self::IsNull(inner) => {
+                format!("{} IS NULL", inner.to_sql(platform))
+            }
+            Self::IsNotNull(inner) => {
+                format!("{} IS NOT NULL", inner.to_sql(platform))
+            }
+            Self::In(col, values) => {
+                let vals: Vec<String> = values.iter()
+                    .map(|v| v.to_sql(platform))
+                    .collect();
+                format!("{} IN ({})", col.to_sql(platform), vals.join(", "))
+            }
+            Self::NotIn(col, values) => {
+                let vals: Vec<String> = values.iter()
+                    .map(|v| v.to_sql(platform))
+                    .collect();
+                format!("{} NOT IN ({})", col.to_sql(platform), vals.join(", "))
+            }
+            Self::Between(col, low, high) => {
+                format!(
+                    "{} BETWEEN {} AND {}",
+                    col.to_sql(platform),
+                    low.to_sql(platform),
+                    high.to_sql(platform)
+                )
+            }
+            Self::Like(col, pattern) => {
+                format!("{} LIKE {}", col.to_sql(platform), platform.quote_string(pattern))
+            }
+            Self::Regexp { expr, pattern, negated, case_insensitive } => {
+                platform.regexp_sql(&expr.to_sql(platform), pattern, *negated, *case_insensitive)
+            }
+            Self::Raw(sql) => sql.clone(),
+        }
+    }
+}
+
+/// Render a literal value
+///
+/// Strings, bytes and JSON are quoted by the platform, so `MySQL` backslashes
+/// and `PostgreSQL` `bytea` come out right; other values use their `Display`.
+fn value_sql<P: Platform + ?Sized>(value: &SqlValue, platform: &P) -> String {
+    match value {
+        SqlValue::String(_) | SqlValue::Bytes(_) => platform.format_literal(value),
+        #[cfg(feature = "json")]
+        SqlValue::Json(_) => platform.format_literal(value),
+        _ => value
+            .non_finite_float()
+            .and_then(|float| platform.non_finite_float_sql(float))
+            .unwrap_or_else(|| value.to_string()),
+    }
+}
+
+/// Get the column name of a column expression
+fn column_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Column(name) => Some(name),
+        _ => None,
+    }
+}
+
+impl core::fmt::Display for Expr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.to_sql(&AnsiPlatform::new()))
+    }
 }
 
 // Convenience conversions
@@ -333,4 +575,27 @@ mod tests {
         assert_eq!(ComparisonOp::Gt.as_sql(), ">");
         assert_eq!(ComparisonOp::Ge.as_sql(), ">=");
     }
+
+    #[test]
+    fn test_expr_eq_and_display() {
+        let expr = col("age").ge(18).and(col("name").is_not_null());
+        assert_eq!(expr, Expr::col("age").ge(18).and(Expr::col("name").is_not_null()));
+        assert_ne!(expr, col("age").gt(18).and(col("name").is_not_null()));
+        assert_eq!(expr.to_string(), "(\"age\" >= 18 AND \"name\" IS NOT NULL)");
+    }
+
+    #[test]
+    fn test_platform_literals() {
+        use crate::platform::{MySqlPlatform, PostgresPlatform};
+
+        let expr = col("path").eq(val("C:\\temp\\")).and(col("data").eq(val(vec![1_u8, 255])));
+        assert_eq!(
+            expr.to_sql(&MySqlPlatform::new()),
+            "(`path` = 'C:\\\\temp\\\\' AND `data` = X'01ff')"
+        );
+        assert_eq!(
+            expr.to_sql(&PostgresPlatform),
+            "(\"path\" = 'C:\\temp\\' AND \"data\" = '\\x01ff'::bytea)"
+        );
+    }
 }
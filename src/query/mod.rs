@@ -43,8 +43,17 @@
 //!     .to_sql(&PostgresPlatform);
 //! ```
 
+mod ast;
 mod builder;
+mod criteria;
 mod expr;
+#[cfg(feature = "sql-parser")]
+mod parser;
 
-pub use builder::{QueryBuilder, QueryType, JoinType, OrderDirection};
+pub use ast::{
+    DeleteStatement, InsertStatement, Join, JoinType, ModifyFilter, ModifyLimit, OrderBy, OrderDirection,
+    PlatformRenderer, RowLock, SelectItem, SelectStatement, SqlRenderer, Statement, TableSource, UpdateStatement,
+};
+pub use builder::{QueryBuilder, QueryType};
+pub use criteria::Criteria;
 pub use expr::{Expr, ComparisonOp, col, val, param, and, or};
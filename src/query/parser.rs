@@ -0,0 +1,597 @@
+//! Parsing raw SQL into the statement tree
+//!
+//! Simple SELECT, INSERT, UPDATE and DELETE statements are parsed with
+//! [`sqlparser`] and converted into a [`Statement`], so user-supplied SQL can
+//! be rewritten like built queries (see [`Statement::map_tables`] and
+//! [`Statement::and_where`]) and rendered again for any platform.
+//!
+//! Expressions the statement tree has no node for (functions, arithmetic,
+//! qualified columns, ...) are kept as raw SQL. Clauses it cannot represent
+//! (CTEs, UNION, multi-table FROM, ON CONFLICT, ...) are rejected with a
+//! syntax error rather than silently dropped.
+
+use alloc::{boxed::Box, format, string::{String, ToString}, vec::Vec};
+use sqlparser::ast as sql;
+use sqlparser::dialect::{Dialect, GenericDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
+
+use crate::core::{hex_decode, Error, QueryError, Result, SqlValue};
+use crate::platform::Platform;
+use super::ast::{
+    DeleteStatement, InsertStatement, Join, JoinType, ModifyFilter, OrderBy, OrderDirection, RowLock,
+    SelectItem, SelectStatement, Statement, TableSource, UpdateStatement,
+};
+use super::expr::{ComparisonOp, Expr};
+
+impl Statement {
+    /// Parse a single SQL statement in the dialect of a platform
+    ///
+    /// # Errors
+    ///
+    /// Returns `QueryError::Syntax` if the SQL is invalid, holds more than
+    /// one statement, or uses a clause the statement tree cannot represent.
+    pub fn parse(sql: &str, platform: &dyn Platform) -> Result<Self> {
+        let dialect: Box<dyn Dialect> = match platform.name() {
+            "postgresql" => Box::new(PostgreSqlDialect {}),
+            "mysql" => Box::new(MySqlDialect {}),
+            "sqlite" => Box::new(SQLiteDialect {}),
+            _ => Box::new(GenericDialect {}),
+        };
+        let mut statements = Parser::parse_sql(dialect.as_ref(), sql)
+            .map_err(|e| syntax_error(e.to_string(), sql))?;
+        let statement = match statements.len() {
+            1 => statements.remove(0),
+            count => return Err(syntax_error(format!("expected one statement, found {count}"), sql)),
+        };
+
+        convert_statement(statement).map_err(|message| syntax_error(message, sql))
+    }
+}
+
+/// Create the error for SQL that cannot be parsed or converted
+fn syntax_error(message: String, sql: &str) -> Error {
+    QueryError::Syntax {
+        message,
+        sql: Some(sql.to_string()),
+        position: None,
+    }
+    .into()
+}
+
+/// Result of a conversion, the error being a description of the problem
+type Converted<T> = core::result::Result<T, String>;
+
+/// Describe a clause the statement tree cannot represent
+fn unsupported(what: &str) -> String {
+    format!("{what} is not supported")
+}
+
+/// Fail if a clause is present
+fn reject(present: bool, what: &str) -> Converted<()> {
+    if present { Err(unsupported(what)) } else { Ok(()) }
+}
+
+/// Convert a parsed statement
+fn convert_statement(statement: sql::Statement) -> Converted<Statement> {
+    match statement {
+        sql::Statement::Query(query) => convert_query(*query).map(Statement::Select),
+        sql::Statement::Insert(insert) => convert_insert(insert).map(Statement::Insert),
+        sql::Statement::Update { table, assignments, from, selection, returning, or } => {
+            reject(from.is_some(), "UPDATE ... FROM")?;
+            reject(or.is_some(), "UPDATE OR")?;
+            reject(!table.joins.is_empty(), "UPDATE with joins")?;
+            let (table, alias) = convert_table_factor(table.relation)?;
+            reject(alias.is_some(), "a table alias in UPDATE")?;
+            let TableSource::Table(table) = table else {
+                return Err(unsupported("UPDATE of a subquery"));
+            };
+            let assignments = assignments
+                .into_iter()
+                .map(|assignment| match assignment.target {
+                    sql::AssignmentTarget::ColumnName(name) => {
+                        Ok((object_name(name)?, convert_expr(assignment.value)))
+                    }
+                    sql::AssignmentTarget::Tuple(_) => Err(unsupported("tuple assignment")),
+                })
+                .collect::<Converted<_>>()?;
+
+            Ok(Statement::Update(UpdateStatement {
+                table,
+                assignments,
+                filter: modify_filter(selection, Vec::new(), None)?,
+                returning: convert_returning(returning)?,
+            }))
+        }
+        sql::Statement::Delete(delete) => convert_delete(delete).map(Statement::Delete),
+        other => Err(format!("{} statements are not supported", statement_keyword(&other))),
+    }
+}
+
+/// Get the leading keyword of a statement for error messages
+fn statement_keyword(statement: &sql::Statement) -> String {
+    statement.to_string().split_whitespace().next().unwrap_or_default().to_uppercase()
+}
+
+/// Convert a query into a SELECT statement
+fn convert_query(query: sql::Query) -> Converted<SelectStatement> {
+    let sql::Query { with, body, order_by, limit, limit_by, offset, fetch, locks, for_clause, settings, format_clause } =
+        query;
+    reject(with.is_some(), "WITH")?;
+    reject(!limit_by.is_empty(), "LIMIT BY")?;
+    reject(fetch.is_some(), "FETCH")?;
+    reject(locks.len() > 1, "multiple locking clauses")?;
+    reject(for_clause.is_some() || settings.is_some() || format_clause.is_some(), "FOR/SETTINGS/FORMAT")?;
+
+    let sql::SetExpr::Select(select) = *body else {
+        return Err(unsupported("a query other than a plain SELECT (UNION, VALUES, ...)"));
+    };
+    let mut statement = convert_select(*select)?;
+    statement.order_by = match order_by {
+        Some(order_by) => {
+            reject(order_by.interpolate.is_some(), "INTERPOLATE")?;
+            convert_order_by(order_by.exprs)?
+        }
+        None => Vec::new(),
+    };
+    statement.limit = limit.map(convert_count).transpose()?;
+    statement.offset = offset.map(|offset| convert_count(offset.value)).transpose()?;
+    statement.lock = locks.first().map(convert_lock).transpose()?;
+    Ok(statement)
+}
+
+/// Convert a FOR UPDATE clause
+fn convert_lock(lock: &sql::LockClause) -> Converted<RowLock> {
+    match lock {
+        sql::LockClause { lock_type: sql::LockType::Update, of: None, nonblock: None } => Ok(RowLock::Update),
+        sql::LockClause { lock_type: sql::LockType::Update, of: None, nonblock: Some(sql::NonBlock::SkipLocked) } => {
+            Ok(RowLock::UpdateSkipLocked)
+        }
+        _ => Err(unsupported("FOR SHARE, FOR UPDATE OF or NOWAIT")),
+    }
+}
+
+/// Convert the SELECT body of a query, without ORDER BY and LIMIT
+fn convert_select(select: sql::Select) -> Converted<SelectStatement> {
+    reject(select.top.is_some(), "TOP")?;
+    reject(select.into.is_some(), "SELECT INTO")?;
+    reject(!select.lateral_views.is_empty(), "LATERAL VIEW")?;
+    reject(select.prewhere.is_some(), "PREWHERE")?;
+    reject(
+        !select.cluster_by.is_empty() || !select.distribute_by.is_empty() || !select.sort_by.is_empty(),
+        "CLUSTER/DISTRIBUTE/SORT BY",
+    )?;
+    reject(!select.named_window.is_empty(), "WINDOW")?;
+    reject(select.qualify.is_some(), "QUALIFY")?;
+    reject(select.value_table_mode.is_some(), "SELECT AS VALUE/STRUCT")?;
+    reject(select.connect_by.is_some(), "CONNECT BY")?;
+    let distinct = match select.distinct {
+        None => false,
+        Some(sql::Distinct::Distinct) => true,
+        Some(sql::Distinct::On(_)) => return Err(unsupported("DISTINCT ON")),
+    };
+
+    let mut from = select.from.into_iter();
+    let (Some(table), None) = (from.next(), from.next()) else {
+        return Err(unsupported("SELECT without exactly one table in FROM"));
+    };
+    let (from, alias) = convert_table_factor(table.relation)?;
+    let joins = table.joins.into_iter().map(convert_join).collect::<Converted<_>>()?;
+
+    let group_by = match select.group_by {
+        sql::GroupByExpr::Expressions(exprs, modifiers) if modifiers.is_empty() => {
+            exprs.into_iter().map(column_name).collect::<Converted<_>>()?
+        }
+        _ => return Err(unsupported("GROUP BY ALL or with modifiers")),
+    };
+
+    Ok(SelectStatement {
+        distinct,
+        projection: select.projection.into_iter().map(convert_select_item).collect(),
+        from,
+        alias,
+        joins,
+        filter: select.selection.map(convert_expr),
+        group_by,
+        having: select.having.map(convert_expr),
+        order_by: Vec::new(),
+        limit: None,
+        offset: None,
+        lock: None,
+    })
+}
+
+/// Convert an item of the SELECT list
+fn convert_select_item(item: sql::SelectItem) -> SelectItem {
+    match item {
+        sql::SelectItem::Wildcard(options) if options == sql::WildcardAdditionalOptions::default() => {
+            SelectItem::Wildcard
+        }
+        sql::SelectItem::UnnamedExpr(sql::Expr::Identifier(ident)) => SelectItem::Column(ident.value),
+        other => SelectItem::Raw(other.to_string()),
+    }
+}
+
+/// Convert a table or subquery with its alias
+fn convert_table_factor(factor: sql::TableFactor) -> Converted<(TableSource, Option<String>)> {
+    match factor {
+        sql::TableFactor::Table { name, alias, args: None, with_hints, version: None, with_ordinality: false, partitions, json_path: None }
+            if with_hints.is_empty() && partitions.is_empty() =>
+        {
+            Ok((TableSource::Table(object_name(name)?), convert_alias(alias)?))
+        }
+        sql::TableFactor::Derived { lateral: false, subquery, alias } => Ok((
+            TableSource::Subquery(Box::new(Statement::Select(convert_query(*subquery)?))),
+            convert_alias(alias)?,
+        )),
+        other => Err(format!("table expression `{other}` is not supported")),
+    }
+}
+
+/// Convert a table alias without column list
+fn convert_alias(alias: Option<sql::TableAlias>) -> Converted<Option<String>> {
+    match alias {
+        Some(alias) if !alias.columns.is_empty() => Err(unsupported("a table alias with columns")),
+        alias => Ok(alias.map(|alias| alias.name.value)),
+    }
+}
+
+/// Convert a JOIN with an ON condition
+fn convert_join(join: sql::Join) -> Converted<Join> {
+    let (kind, constraint) = match join.join_operator {
+        sql::JoinOperator::Inner(constraint) => (JoinType::Inner, constraint),
+        sql::JoinOperator::LeftOuter(constraint) => (JoinType::Left, constraint),
+        sql::JoinOperator::RightOuter(constraint) => (JoinType::Right, constraint),
+        sql::JoinOperator::FullOuter(constraint) => (JoinType::Full, constraint),
+        _ => return Err(unsupported("this kind of JOIN")),
+    };
+    let sql::JoinConstraint::On(condition) = constraint else {
+        return Err(unsupported("a JOIN without ON condition"));
+    };
+    let (TableSource::Table(table), alias) = convert_table_factor(join.relation)? else {
+        return Err(unsupported("joining a subquery"));
+    };
+
+    let join = Join::new(kind, table, convert_expr(condition));
+    Ok(match alias {
+        Some(alias) => join.with_alias(alias),
+        None => join,
+    })
+}
+
+/// Convert ORDER BY expressions on plain columns
+fn convert_order_by(exprs: Vec<sql::OrderByExpr>) -> Converted<Vec<OrderBy>> {
+    exprs
+        .into_iter()
+        .map(|order| {
+            reject(order.nulls_first.is_some(), "NULLS FIRST/LAST")?;
+            reject(order.with_fill.is_some(), "WITH FILL")?;
+            Ok(OrderBy {
+                column: column_name(order.expr)?,
+                direction: if order.asc == Some(false) { OrderDirection::Desc } else { OrderDirection::Asc },
+            })
+        })
+        .collect()
+}
+
+/// Convert a LIMIT/OFFSET row count
+fn convert_count(expr: sql::Expr) -> Converted<u64> {
+    match expr {
+        sql::Expr::Value(sql::Value::Number(ref number, _)) => {
+            number.parse().map_err(|_| format!("invalid row count `{number}`"))
+        }
+        other => Err(format!("row count `{other}` is not supported, only numbers are")),
+    }
+}
+
+/// Convert an INSERT ... VALUES statement
+fn convert_insert(insert: sql::Insert) -> Converted<InsertStatement> {
+    reject(insert.or.is_some() || insert.ignore || insert.replace_into, "INSERT OR/IGNORE/REPLACE")?;
+    reject(insert.table_alias.is_some(), "a table alias in INSERT")?;
+    reject(insert.overwrite || insert.table || insert.partitioned.is_some(), "INSERT OVERWRITE/TABLE/PARTITION")?;
+    reject(!insert.after_columns.is_empty(), "columns after PARTITION")?;
+    reject(insert.on.is_some(), "ON CONFLICT/ON DUPLICATE KEY")?;
+    reject(insert.priority.is_some() || insert.insert_alias.is_some(), "INSERT priority or row alias")?;
+
+    let rows = match insert.source.map(|source| *source.body) {
+        Some(sql::SetExpr::Values(values)) => values.rows,
+        _ => return Err(unsupported("INSERT without VALUES")),
+    };
+
+    Ok(InsertStatement {
+        table: object_name(insert.table_name)?,
+        columns: insert.columns.into_iter().map(|ident| ident.value).collect(),
+        rows: rows.into_iter().map(|row| row.into_iter().map(convert_expr).collect()).collect(),
+        returning: convert_returning(insert.returning)?,
+    })
+}
+
+/// Convert a single-table DELETE statement
+fn convert_delete(delete: sql::Delete) -> Converted<DeleteStatement> {
+    reject(!delete.tables.is_empty() || delete.using.is_some(), "multi-table DELETE")?;
+    let (sql::FromTable::WithFromKeyword(tables) | sql::FromTable::WithoutKeyword(tables)) = delete.from;
+    let mut tables = tables.into_iter();
+    let (Some(table), None) = (tables.next(), tables.next()) else {
+        return Err(unsupported("DELETE without exactly one table"));
+    };
+    reject(!table.joins.is_empty(), "DELETE with joins")?;
+    let (TableSource::Table(name), None) = convert_table_factor(table.relation)? else {
+        return Err(unsupported("DELETE from a subquery or alias"));
+    };
+
+    Ok(DeleteStatement {
+        table: name,
+        filter: modify_filter(delete.selection, delete.order_by, delete.limit)?,
+        returning: convert_returning(delete.returning)?,
+    })
+}
+
+/// Convert the WHERE/ORDER BY/LIMIT of an UPDATE or DELETE
+fn modify_filter(
+    selection: Option<sql::Expr>,
+    order_by: Vec<sql::OrderByExpr>,
+    limit: Option<sql::Expr>,
+) -> Converted<ModifyFilter> {
+    Ok(ModifyFilter {
+        condition: selection.map(convert_expr),
+        order_by: convert_order_by(order_by)?,
+        limit: limit.map(convert_count).transpose()?,
+        offset: None,
+        limit_key: None,
+    })
+}
+
+/// Convert RETURNING columns
+fn convert_returning(returning: Option<Vec<sql::SelectItem>>) -> Converted<Vec<String>> {
+    returning
+        .unwrap_or_default()
+        .into_iter()
+        .map(|item| match item {
+            sql::SelectItem::UnnamedExpr(expr) => column_name(expr),
+            other => Err(format!("RETURNING `{other}` is not supported, only columns are")),
+        })
+        .collect()
+}
+
+/// Get the name of an unqualified table
+fn object_name(name: sql::ObjectName) -> Converted<String> {
+    let mut parts = name.0.into_iter();
+    match (parts.next(), parts.next()) {
+        (Some(ident), None) => Ok(ident.value),
+        _ => Err(unsupported("a qualified table name")),
+    }
+}
+
+/// Get the name of a plain column
+fn column_name(expr: sql::Expr) -> Converted<String> {
+    match expr {
+        sql::Expr::Identifier(ident) => Ok(ident.value),
+        other => Err(format!("`{other}` is not supported here, only plain columns are")),
+    }
+}
+
+/// Convert an expression, keeping unknown expressions as raw SQL
+fn convert_expr(expr: sql::Expr) -> Expr {
+    match expr {
+        sql::Expr::Identifier(ident) => Expr::Column(ident.value),
+        sql::Expr::Value(value) => convert_value(value),
+        sql::Expr::BinaryOp { left, op, right } => {
+            let comparison = match op {
+                sql::BinaryOperator::Eq => ComparisonOp::Eq,
+                sql::BinaryOperator::NotEq => ComparisonOp::Ne,
+                sql::BinaryOperator::Lt => ComparisonOp::Lt,
+                sql::BinaryOperator::LtEq => ComparisonOp::Le,
+                sql::BinaryOperator::Gt => ComparisonOp::Gt,
+                sql::BinaryOperator::GtEq => ComparisonOp::Ge,
+                sql::BinaryOperator::And => return convert_expr(*left).and(convert_expr(*right)),
+                sql::BinaryOperator::Or => return convert_expr(*left).or(convert_expr(*right)),
+                op @ (sql::BinaryOperator::PGRegexMatch
+                | sql::BinaryOperator::PGRegexIMatch
+                | sql::BinaryOperator::PGRegexNotMatch
+                | sql::BinaryOperator::PGRegexNotIMatch) => {
+                    let negated =
+                        matches!(op, sql::BinaryOperator::PGRegexNotMatch | sql::BinaryOperator::PGRegexNotIMatch);
+                    let case_insensitive =
+                        matches!(op, sql::BinaryOperator::PGRegexIMatch | sql::BinaryOperator::PGRegexNotIMatch);
+                    return match string_literal(*right) {
+                        Ok(pattern) => regexp(*left, pattern, negated, case_insensitive),
+                        Err(right) => Expr::Raw(sql::Expr::BinaryOp { left, op, right }.to_string()),
+                    };
+                }
+                op => return Expr::Raw(sql::Expr::BinaryOp { left, op, right }.to_string()),
+            };
+            Expr::Comparison(Box::new(convert_expr(*left)), comparison, Box::new(convert_expr(*right)))
+        }
+        sql::Expr::UnaryOp { op: sql::UnaryOperator::Not, expr } => Expr::Not(Box::new(convert_expr(*expr))),
+        sql::Expr::IsNull(expr) => Expr::IsNull(Box::new(convert_expr(*expr))),
+        sql::Expr::IsNotNull(expr) => Expr::IsNotNull(Box::new(convert_expr(*expr))),
+        sql::Expr::InList { expr, list, negated } => {
+            let list = list.into_iter().map(convert_expr).collect();
+            if negated {
+                Expr::NotIn(Box::new(convert_expr(*expr)), list)
+            } else {
+                Expr::In(Box::new(convert_expr(*expr)), list)
+            }
+        }
+        sql::Expr::Between { expr, negated, low, high } => {
+            let between = Expr::Between(
+                Box::new(convert_expr(*expr)),
+                Box::new(convert_expr(*low)),
+                Box::new(convert_expr(*high)),
+            );
+            if negated { between.not() } else { between }
+        }
+        sql::Expr::Like { negated, any: false, expr, pattern, escape_char: None } => match string_literal(*pattern) {
+            Ok(pattern) => {
+                let like = Expr::Like(Box::new(convert_expr(*expr)), pattern);
+                if negated { like.not() } else { like }
+            }
+            Err(pattern) => Expr::Raw(
+                sql::Expr::Like { negated, any: false, expr, pattern, escape_char: None }.to_string(),
+            ),
+        },
+        sql::Expr::RLike { negated, expr, pattern, regexp: keyword } => match string_literal(*pattern) {
+            // Case-insensitive matches are rendered with an inline flag
+            Ok(pattern) => match pattern.strip_prefix("(?i)") {
+                Some(pattern) => regexp(*expr, pattern.to_string(), negated, true),
+                None => regexp(*expr, pattern, negated, false),
+            },
+            Err(pattern) => Expr::Raw(sql::Expr::RLike { negated, expr, pattern, regexp: keyword }.to_string()),
+        },
+        // PostgreSQL binary literals, '\x0102'::bytea
+        sql::Expr::Cast { kind, expr, data_type: sql::DataType::Bytea, format: None } => {
+            let bytes = match expr.as_ref() {
+                sql::Expr::Value(sql::Value::SingleQuotedString(text)) => text.strip_prefix("\\x").and_then(hex_decode),
+                _ => None,
+            };
+            bytes.map_or_else(
+                || Expr::Raw(sql::Expr::Cast { kind, expr, data_type: sql::DataType::Bytea, format: None }.to_string()),
+                |bytes| Expr::Value(SqlValue::Bytes(bytes)),
+            )
+        }
+        sql::Expr::Nested(inner) => match convert_expr(*inner) {
+            Expr::Raw(sql) => Expr::Raw(format!("({sql})")),
+            converted => converted,
+        },
+        other => Expr::Raw(other.to_string()),
+    }
+}
+
+/// Take the text of a string literal, or give the expression back
+fn string_literal(expr: sql::Expr) -> core::result::Result<String, Box<sql::Expr>> {
+    match expr {
+        sql::Expr::Value(sql::Value::SingleQuotedString(text)) => Ok(text),
+        other => Err(Box::new(other)),
+    }
+}
+
+/// Convert a regular expression match
+fn regexp(expr: sql::Expr, pattern: String, negated: bool, case_insensitive: bool) -> Expr {
+    Expr::Regexp { expr: Box::new(convert_expr(expr)), pattern, negated, case_insensitive }
+}
+
+/// Convert a literal or placeholder
+fn convert_value(value: sql::Value) -> Expr {
+    match value {
+        sql::Value::Null => Expr::Value(SqlValue::Null),
+        sql::Value::Boolean(b) => Expr::Value(SqlValue::Bool(b)),
+        sql::Value::SingleQuotedString(s) => Expr::Value(SqlValue::String(s)),
+        sql::Value::Placeholder(name) => Expr::Param(name),
+        sql::Value::HexStringLiteral(ref hex) => {
+            hex_decode(hex).map_or_else(|| Expr::Raw(value.to_string()), |bytes| Expr::Value(SqlValue::Bytes(bytes)))
+        }
+        sql::Value::Number(ref number, false) => number
+            .parse::<i64>()
+            .map(SqlValue::I64)
+            .map_or_else(|_| Expr::Raw(value.to_string()), Expr::Value),
+        other => Expr::Raw(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::TenantStrategy;
+    use crate::query::{PlatformRenderer, SqlRenderer};
+    use crate::platform::{MySqlPlatform, PostgresPlatform, SqlitePlatform};
+    use crate::query::{col, QueryBuilder};
+
+    #[test]
+    fn test_round_trip() {
+        let platform = PostgresPlatform;
+        let cases = [
+            (
+                "SELECT DISTINCT id, COUNT(*) AS n FROM users u LEFT JOIN orders o ON o.user_id = u.id \
+                 WHERE active = true AND (age > 18 OR name LIKE 'A%') GROUP BY id HAVING COUNT(*) > 1 \
+                 ORDER BY id DESC LIMIT 10 OFFSET 5",
+                "SELECT DISTINCT \"id\", COUNT(*) AS n FROM \"users\" AS \"u\" LEFT JOIN \"orders\" AS \"o\" \
+                 ON o.user_id = u.id WHERE (\"active\" = true AND (\"age\" > 18 OR \"name\" LIKE 'A%')) \
+                 GROUP BY \"id\" HAVING COUNT(*) > 1 ORDER BY \"id\" DESC LIMIT 10 OFFSET 5",
+            ),
+            (
+                "INSERT INTO users (name, age) VALUES ($1, 30), ('Bob', NULL) RETURNING id",
+                "INSERT INTO \"users\" (\"name\", \"age\") VALUES ($1, 30), ('Bob', NULL) RETURNING \"id\"",
+            ),
+            (
+                "UPDATE users SET name = 'Eve', age = age + 1 WHERE id IN (1, 2) AND NOT deleted",
+                "UPDATE \"users\" SET \"name\" = 'Eve', \"age\" = age + 1 WHERE (\"id\" IN (1, 2) AND NOT (\"deleted\"))",
+            ),
+            (
+                "DELETE FROM logs WHERE created_at NOT BETWEEN '2024-01-01' AND '2024-12-31'",
+                "DELETE FROM \"logs\" WHERE NOT (\"created_at\" BETWEEN '2024-01-01' AND '2024-12-31')",
+            ),
+        ];
+        for (sql, expected) in cases {
+            let statement = Statement::parse(sql, &platform).unwrap();
+            assert_eq!(PlatformRenderer::new(&platform).render(&statement), expected, "{sql}");
+        }
+
+        let locked = Statement::parse("SELECT id FROM jobs LIMIT 5 FOR UPDATE SKIP LOCKED", &platform).unwrap();
+        assert_eq!(
+            PlatformRenderer::new(&platform).render(&locked),
+            "SELECT \"id\" FROM \"jobs\" LIMIT 5 FOR UPDATE SKIP LOCKED"
+        );
+
+        let subquery = Statement::parse("SELECT * FROM (SELECT id FROM users) AS t", &platform).unwrap();
+        assert_eq!(subquery.to_string(), "SELECT * FROM (SELECT \"id\" FROM \"users\") AS \"t\"");
+    }
+
+    #[test]
+    fn test_rewrite_parsed_sql() {
+        let tenant = TenantStrategy::table_prefix("acme_");
+        let statement = Statement::parse("SELECT * FROM users JOIN orders ON user_id = id WHERE a = 1 OR b = 2", &MySqlPlatform::new())
+            .unwrap()
+            .map_tables(&|table| tenant.table_name(table).into_owned())
+            .and_where(col("deleted_at").is_null());
+        assert_eq!(
+            statement.to_string(),
+            "SELECT * FROM \"acme_users\" INNER JOIN \"acme_orders\" ON \"user_id\" = \"id\" \
+             WHERE ((\"a\" = 1 OR \"b\" = 2) AND \"deleted_at\" IS NULL)"
+        );
+
+        let delete = Statement::parse("DELETE FROM logs ORDER BY id LIMIT 100", &SqlitePlatform::new()).unwrap();
+        assert_eq!(
+            PlatformRenderer::new(&SqlitePlatform::new()).render(&delete),
+            "DELETE FROM \"logs\" WHERE \"rowid\" IN (SELECT \"rowid\" FROM \"logs\" ORDER BY \"id\" ASC LIMIT 100)"
+        );
+    }
+
+    #[test]
+    fn test_regexp() {
+        let statement = Statement::parse("SELECT * FROM users WHERE name !~* '^a' AND email ~ '@x$'", &PostgresPlatform).unwrap();
+        assert_eq!(
+            statement,
+            Statement::parse("SELECT * FROM users WHERE name NOT REGEXP '(?i)^a' AND email REGEXP '@x$'", &MySqlPlatform::new())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_binary_literals() {
+        let bytes = SqlValue::Bytes(vec![0, 171]);
+        for (sql, platform) in [
+            ("SELECT * FROM t WHERE data = '\\x00ab'::bytea", &PostgresPlatform as &dyn Platform),
+            ("SELECT * FROM t WHERE data = X'00ab'", &SqlitePlatform::new()),
+        ] {
+            let expected = QueryBuilder::select().all().from("t").where_eq("data", bytes.clone());
+            assert_eq!(Statement::parse(sql, platform).unwrap(), expected.to_statement(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_unsupported_sql() {
+        let platform = PostgresPlatform;
+        for sql in [
+            "WITH t AS (SELECT 1) SELECT * FROM t",
+            "SELECT id FROM a UNION SELECT id FROM b",
+            "SELECT id FROM a FOR SHARE",
+            "SELECT * FROM a, b",
+            "INSERT INTO t (a) VALUES (1) ON CONFLICT DO NOTHING",
+            "CREATE TABLE t (id INT)",
+            "SELECT 1; SELECT 2",
+            "SELEC 1",
+        ] {
+            let error = Statement::parse(sql, &platform).unwrap_err();
+            assert!(matches!(error, Error::Query(QueryError::Syntax { .. })), "{sql}: {error}");
+        }
+    }
+}
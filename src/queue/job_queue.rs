@@ -0,0 +1,454 @@
+//! Job queue table with SKIP LOCKED claims, retries and dead-lettering
+
+use std::time::Duration;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::{SystemTime, UNIX_EPOCH};
+// `std::time::SystemTime::now()` panics on wasm32-unknown-unknown
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::connection::Connection;
+use crate::core::{Result, SqlValue};
+use crate::driver::Driver;
+use crate::platform::{Column, Index, Platform, SqlType, Table};
+use crate::query::{col, QueryBuilder};
+
+/// Status of a job waiting to be claimed
+const PENDING: &str = "pending";
+/// Status of a job claimed by a worker
+const RUNNING: &str = "running";
+/// Status of a job whose attempts are used up
+const DEAD: &str = "dead";
+
+/// A job claimed from a [`JobQueue`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    /// Row id of the job
+    pub id: i64,
+    /// Job data, e.g. JSON
+    pub payload: String,
+    /// Number of failed runs so far
+    pub attempts: u32,
+    /// Error of the last failed run
+    pub last_error: Option<String>,
+}
+
+/// A job queue in a database table
+///
+/// Several queues can share one table; each `JobQueue` only sees the jobs
+/// of its [queue name](Self::with_queue). Times (`available_at`,
+/// `locked_at`) are stored as Unix milliseconds, so they compare the same
+/// way on every platform.
+///
+/// Workers [`claim`](Self::claim) pending jobs, then either
+/// [`complete`](Self::complete) them (deleting the row) or report a
+/// [failure](Self::fail). A failed job becomes available again after
+/// [`retry_delay`](Self::retry_delay); once it has failed
+/// `max_attempts` times it is marked dead and kept for inspection with
+/// [`dead_letters`](Self::dead_letters).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobQueue {
+    /// Name of the queue table
+    table: String,
+    /// Name of the queue within the table
+    queue: String,
+    /// Failed runs after which a job is dead-lettered
+    max_attempts: u32,
+    /// Delay before the first retry
+    initial_backoff: Duration,
+    /// Upper bound of the retry delay
+    max_backoff: Duration,
+}
+
+impl JobQueue {
+    /// Default number of failed runs after which a job is dead-lettered
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+
+    /// Create a queue named `default` in the given table
+    ///
+    /// Jobs are retried up to [`DEFAULT_MAX_ATTEMPTS`](Self::DEFAULT_MAX_ATTEMPTS)
+    /// times, first after 1 second and with the delay doubling up to 1 hour.
+    #[must_use]
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+            queue: "default".to_string(),
+            max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(3600),
+        }
+    }
+
+    /// Set the name of the queue within the table
+    #[must_use]
+    pub fn with_queue(mut self, queue: impl Into<String>) -> Self {
+        self.queue = queue.into();
+        self
+    }
+
+    /// Set the number of failed runs after which a job is dead-lettered
+    #[must_use]
+    pub const fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the first retry and its upper bound
+    #[must_use]
+    pub const fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Get the name of the queue table
+    #[must_use]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// Get the definition of the queue table
+    ///
+    /// Includes an index for claiming; create it with
+    /// [`Platform::get_create_schema_sql`] to get the index as well.
+    #[must_use]
+    pub fn table_definition(&self) -> Table {
+        Table::new(&self.table)
+            .column(Column::new("id", SqlType::BigSerial).not_null().auto_increment())
+            .column(Column::new("queue", SqlType::varchar(255)).not_null())
+            .column(Column::new("payload", SqlType::Text).not_null())
+            .column(Column::new("status", SqlType::varchar(20)).not_null().default_value(PENDING))
+            .column(Column::new("attempts", SqlType::Integer).not_null().default_value(0))
+            .column(Column::new("available_at", SqlType::BigInt).not_null())
+            .column(Column::new("locked_by", SqlType::varchar(255)))
+            .column(Column::new("locked_at", SqlType::BigInt))
+            .column(Column::new("last_error", SqlType::Text))
+            .index(Index::primary(vec!["id".to_string()]))
+            .index(Index::new(
+                format!("idx_{}_claim", self.table),
+                vec!["queue".to_string(), "status".to_string(), "available_at".to_string()],
+            ))
+    }
+
+    /// Get the delay before retrying a job that has failed `attempts` times
+    #[must_use]
+    pub fn retry_delay(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// Add a job to the queue
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the INSERT fails.
+    pub async fn enqueue<D: Driver>(
+        &self,
+        conn: &Connection<D>,
+        platform: &dyn Platform,
+        payload: impl Into<String>,
+    ) -> Result<()> {
+        self.enqueue_delayed(conn, platform, payload, Duration::ZERO).await
+    }
+
+    /// Add a job that can't be claimed before `delay` has passed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the INSERT fails.
+    pub async fn enqueue_delayed<D: Driver>(
+        &self,
+        conn: &Connection<D>,
+        platform: &dyn Platform,
+        payload: impl Into<String>,
+        delay: Duration,
+    ) -> Result<()> {
+        let query = QueryBuilder::insert()
+            .into(&self.table)
+            .insert_columns(&["queue", "payload", "status", "attempts", "available_at"])
+            .values(vec![
+                self.queue.as_str().into(),
+                payload.into().into(),
+                PENDING.into(),
+                0i64.into(),
+                (now_millis() + millis(delay)).into(),
+            ]);
+        conn.execute(&query.try_to_sql(platform)?).await?;
+        Ok(())
+    }
+
+    /// Claim up to `limit` available jobs for a worker
+    ///
+    /// Jobs are claimed oldest first in their own transaction (a savepoint
+    /// inside an active one). `worker` identifies the claiming process and
+    /// is stored in `locked_by`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction or one of the queries fails.
+    pub async fn claim<D: Driver>(
+        &self,
+        conn: &Connection<D>,
+        platform: &dyn Platform,
+        worker: &str,
+        limit: u64,
+    ) -> Result<Vec<Job>> {
+        conn.begin_transaction().await?;
+        let result = self.claim_available(conn, platform, worker, limit).await;
+        conn.in_transaction(result).await
+    }
+
+    /// Lock and mark available jobs as running, inside a transaction
+    async fn claim_available<D: Driver>(
+        &self,
+        conn: &Connection<D>,
+        platform: &dyn Platform,
+        worker: &str,
+        limit: u64,
+    ) -> Result<Vec<Job>> {
+        let now = now_millis();
+        let available = QueryBuilder::select()
+            .columns(&["id"])
+            .from(&self.table)
+            .where_eq("queue", self.queue.as_str())
+            .where_eq("status", PENDING)
+            .where_expr(col("available_at").le(now))
+            .order_by_asc("id")
+            .limit(limit)
+            .for_update_skip_locked();
+        let ids: Vec<SqlValue> = conn
+            .fetch_all_as::<(i64,)>(&available.try_to_sql(platform)?, &[])
+            .await?
+            .into_iter()
+            .map(|(id,)| SqlValue::I64(id))
+            .collect();
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Without SKIP LOCKED another worker may have claimed some of the
+        // jobs meanwhile; they are no longer pending and stay with it
+        let claim = QueryBuilder::update()
+            .table(&self.table)
+            .set("status", RUNNING)
+            .set("locked_by", worker)
+            .set("locked_at", now)
+            .where_in("id", ids.clone())
+            .where_eq("status", PENDING);
+        conn.execute(&claim.try_to_sql(platform)?).await?;
+
+        let claimed = QueryBuilder::select()
+            .columns(&["id", "payload", "attempts", "last_error"])
+            .from(&self.table)
+            .where_in("id", ids)
+            .where_eq("status", RUNNING)
+            .where_eq("locked_by", worker)
+            .where_eq("locked_at", now)
+            .order_by_asc("id");
+        let rows = conn
+            .fetch_all_as::<(i64, String, i64, Option<String>)>(&claimed.try_to_sql(platform)?, &[])
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, payload, attempts, last_error)| Job {
+                id,
+                payload,
+                attempts: u32::try_from(attempts).unwrap_or(0),
+                last_error,
+            })
+            .collect())
+    }
+
+    /// Remove a finished job from the queue
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the DELETE fails.
+    pub async fn complete<D: Driver>(&self, conn: &Connection<D>, platform: &dyn Platform, job: &Job) -> Result<()> {
+        let query = QueryBuilder::delete().from(&self.table).where_eq("id", job.id);
+        conn.execute(&query.try_to_sql(platform)?).await?;
+        Ok(())
+    }
+
+    /// Record a failed run of a job
+    ///
+    /// The job becomes available again after [`retry_delay`](Self::retry_delay),
+    /// or is marked dead once it has failed `max_attempts` times.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the UPDATE fails.
+    pub async fn fail<D: Driver>(
+        &self,
+        conn: &Connection<D>,
+        platform: &dyn Platform,
+        job: &Job,
+        error: &str,
+    ) -> Result<()> {
+        let attempts = job.attempts.saturating_add(1);
+        let status = if attempts >= self.max_attempts { DEAD } else { PENDING };
+        let query = QueryBuilder::update()
+            .table(&self.table)
+            .set("status", status)
+            .set("attempts", i64::from(attempts))
+            .set("available_at", now_millis() + millis(self.retry_delay(attempts)))
+            .set("locked_by", SqlValue::Null)
+            .set("locked_at", SqlValue::Null)
+            .set("last_error", error)
+            .where_eq("id", job.id);
+        conn.execute(&query.try_to_sql(platform)?).await?;
+        Ok(())
+    }
+
+    /// Return running jobs locked longer than `timeout` to the queue
+    ///
+    /// Recovers jobs of workers that crashed after claiming them. The run
+    /// is not counted as a failed attempt. Returns the number of jobs
+    /// released.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the UPDATE fails.
+    pub async fn release_stale<D: Driver>(
+        &self,
+        conn: &Connection<D>,
+        platform: &dyn Platform,
+        timeout: Duration,
+    ) -> Result<u64> {
+        let query = QueryBuilder::update()
+            .table(&self.table)
+            .set("status", PENDING)
+            .set("locked_by", SqlValue::Null)
+            .set("locked_at", SqlValue::Null)
+            .where_eq("queue", self.queue.as_str())
+            .where_eq("status", RUNNING)
+            .where_expr(col("locked_at").lt(now_millis() - millis(timeout)));
+        conn.execute(&query.try_to_sql(platform)?).await
+    }
+
+    /// Get the dead-lettered jobs of the queue, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SELECT fails.
+    pub async fn dead_letters<D: Driver>(&self, conn: &Connection<D>, platform: &dyn Platform) -> Result<Vec<Job>> {
+        let query = QueryBuilder::select()
+            .columns(&["id", "payload", "attempts", "last_error"])
+            .from(&self.table)
+            .where_eq("queue", self.queue.as_str())
+            .where_eq("status", DEAD)
+            .order_by_asc("id");
+        let rows = conn
+            .fetch_all_as::<(i64, String, i64, Option<String>)>(&query.try_to_sql(platform)?, &[])
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, payload, attempts, last_error)| Job {
+                id,
+                payload,
+                attempts: u32::try_from(attempts).unwrap_or(0),
+                last_error,
+            })
+            .collect())
+    }
+}
+
+/// Get the current time in Unix milliseconds
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, millis)
+}
+
+/// Convert a duration to milliseconds, saturating at `i64::MAX`
+fn millis(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::PostgresPlatform;
+
+    #[test]
+    fn test_retry_delay() {
+        let queue = JobQueue::new("jobs").with_backoff(Duration::from_secs(2), Duration::from_secs(10));
+        assert_eq!(queue.retry_delay(1), Duration::from_secs(2));
+        assert_eq!(queue.retry_delay(2), Duration::from_secs(4));
+        assert_eq!(queue.retry_delay(3), Duration::from_secs(8));
+        assert_eq!(queue.retry_delay(40), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_table_definition() {
+        let statements = PostgresPlatform.get_create_schema_sql(
+            &crate::platform::Schema::new().table(JobQueue::new("jobs").table_definition()),
+        );
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].contains("\"id\" BIGSERIAL NOT NULL"));
+        assert!(statements[0].contains("\"status\" VARCHAR(20) NOT NULL DEFAULT 'pending'"));
+        assert_eq!(
+            statements[1],
+            "CREATE INDEX \"idx_jobs_claim\" ON \"jobs\" (\"queue\", \"status\", \"available_at\")"
+        );
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod sqlite_tests {
+    use super::*;
+    use crate::driver::SqliteDriver;
+    use crate::platform::{Schema, SqlitePlatform};
+
+    #[tokio::test]
+    async fn test_claim_retry_and_dead_letter() {
+        let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+        let platform = SqlitePlatform::new();
+        let queue = JobQueue::new("jobs").with_max_attempts(2).with_backoff(Duration::ZERO, Duration::ZERO);
+        for sql in platform.get_create_schema_sql(&Schema::new().table(queue.table_definition())) {
+            conn.execute(&sql).await.unwrap();
+        }
+
+        queue.enqueue(&conn, &platform, "a").await.unwrap();
+        queue.enqueue(&conn, &platform, "b").await.unwrap();
+        queue.enqueue_delayed(&conn, &platform, "later", Duration::from_secs(3600)).await.unwrap();
+        JobQueue::new("jobs").with_queue("other").enqueue(&conn, &platform, "c").await.unwrap();
+
+        let jobs = queue.claim(&conn, &platform, "w1", 10).await.unwrap();
+        let payloads: Vec<&str> = jobs.iter().map(|job| job.payload.as_str()).collect();
+        assert_eq!(payloads, ["a", "b"]);
+        assert!(queue.claim(&conn, &platform, "w2", 10).await.unwrap().is_empty());
+
+        queue.complete(&conn, &platform, &jobs[0]).await.unwrap();
+        queue.fail(&conn, &platform, &jobs[1], "boom").await.unwrap();
+
+        let retried = queue.claim(&conn, &platform, "w2", 10).await.unwrap();
+        assert_eq!(retried.len(), 1);
+        assert_eq!((retried[0].attempts, retried[0].last_error.as_deref()), (1, Some("boom")));
+
+        queue.fail(&conn, &platform, &retried[0], "boom again").await.unwrap();
+        assert!(queue.claim(&conn, &platform, "w2", 10).await.unwrap().is_empty());
+        let dead = queue.dead_letters(&conn, &platform).await.unwrap();
+        assert_eq!((dead[0].payload.as_str(), dead[0].attempts), ("b", 2));
+    }
+
+    #[tokio::test]
+    async fn test_release_stale() {
+        let conn = Connection::<SqliteDriver>::from_url("sqlite::memory:").await.unwrap();
+        let platform = SqlitePlatform::new();
+        let queue = JobQueue::new("jobs");
+        for sql in platform.get_create_schema_sql(&Schema::new().table(queue.table_definition())) {
+            conn.execute(&sql).await.unwrap();
+        }
+
+        queue.enqueue(&conn, &platform, "a").await.unwrap();
+        assert_eq!(queue.claim(&conn, &platform, "w1", 1).await.unwrap().len(), 1);
+        assert_eq!(queue.release_stale(&conn, &platform, Duration::from_secs(60)).await.unwrap(), 0);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(queue.release_stale(&conn, &platform, Duration::ZERO).await.unwrap(), 1);
+
+        let job = queue.claim(&conn, &platform, "w2", 1).await.unwrap();
+        assert_eq!((job[0].payload.as_str(), job[0].attempts), ("a", 0));
+    }
+}
@@ -0,0 +1,39 @@
+//! # Queue Module
+//!
+//! Job queue stored in a database table.
+//!
+//! A [`JobQueue`] enqueues jobs as rows and lets workers claim them with
+//! `SELECT ... FOR UPDATE SKIP LOCKED`, so concurrent workers never wait for
+//! or receive the same job. Platforms without SKIP LOCKED fall back to a
+//! waiting FOR UPDATE, and `SQLite` to its database-wide write lock; claims
+//! stay exclusive as a job is only claimed while still pending. Failed jobs
+//! are retried with exponential backoff and dead-lettered once their
+//! attempts are used up.
+//!
+//! All SQL is generated through the query builder and the
+//! [`Platform`](crate::platform::Platform), so the same code runs on
+//! `PostgreSQL`, `MySQL` and `SQLite`.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rustine_dbal::queue::JobQueue;
+//!
+//! let queue = JobQueue::new("jobs").with_queue("emails").with_max_attempts(3);
+//! for sql in platform.get_create_schema_sql(&Schema::new().table(queue.table_definition())) {
+//!     conn.execute(&sql).await?;
+//! }
+//!
+//! queue.enqueue(&conn, &platform, r#"{"to":"alice@example.com"}"#).await?;
+//!
+//! for job in queue.claim(&conn, &platform, "worker-1", 10).await? {
+//!     match send_email(&job.payload).await {
+//!         Ok(()) => queue.complete(&conn, &platform, &job).await?,
+//!         Err(e) => queue.fail(&conn, &platform, &job, &e.to_string()).await?,
+//!     }
+//! }
+//! ```
+
+mod job_queue;
+
+pub use job_queue::{Job, JobQueue};
@@ -0,0 +1,556 @@
+//! Differences between two schema definitions
+
+use crate::core::SqlValue;
+use crate::platform::{Column, DefaultValue, ForeignKey, IdentifierCase, Index, Schema, SqlType, Table};
+
+/// What the [`Comparator`] treats as equal
+///
+/// The defaults suppress the noise of comparing a code-defined schema with
+/// an introspected one: type aliases (`SERIAL` vs auto-increment `INTEGER`,
+/// `CHAR(36)` vs `UUID`), identifier case, spellings of default values and
+/// column order. [`strict`](Self::strict) compares everything as written.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ComparatorOptions {
+    /// Compare types after mapping aliases to one type
+    normalize_types: bool,
+    /// Compare table, column, index and foreign key names case-insensitively
+    case_insensitive: bool,
+    /// Case policy identifiers are folded with, replacing `case_insensitive`
+    identifier_case: Option<IdentifierCase>,
+    /// Compare default values after parsing expressions with [`DefaultValue::parse`]
+    normalize_defaults: bool,
+    /// Report columns whose position changed
+    compare_column_order: bool,
+    /// Additional pairs of types treated as equal
+    type_aliases: Vec<(SqlType, SqlType)>,
+    /// Tables left out of the comparison
+    ignored_tables: Vec<String>,
+    /// Columns left out of the comparison, as (table, column)
+    ignored_columns: Vec<(String, String)>,
+}
+
+impl Default for ComparatorOptions {
+    fn default() -> Self {
+        Self {
+            normalize_types: true,
+            case_insensitive: true,
+            identifier_case: None,
+            normalize_defaults: true,
+            compare_column_order: false,
+            type_aliases: Vec::new(),
+            ignored_tables: Vec::new(),
+            ignored_columns: Vec::new(),
+        }
+    }
+}
+
+impl ComparatorOptions {
+    /// Create options with all normalizations enabled
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create options comparing names, types, defaults and column order exactly
+    #[must_use]
+    pub fn strict() -> Self {
+        Self {
+            normalize_types: false,
+            case_insensitive: false,
+            normalize_defaults: false,
+            compare_column_order: true,
+            ..Self::default()
+        }
+    }
+
+    /// Map type aliases to one type before comparing
+    #[must_use]
+    pub const fn with_type_normalization(mut self, normalize: bool) -> Self {
+        self.normalize_types = normalize;
+        self
+    }
+
+    /// Compare identifiers case-insensitively
+    #[must_use]
+    pub const fn with_case_insensitive_identifiers(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Fold identifiers with a case policy instead of comparing them case-insensitively
+    ///
+    /// Under [`IdentifierCase::LowerUnquoted`] `Users` and `users` are equal
+    /// while `"Users"` is not, matching how `PostgreSQL` resolves the names;
+    /// pass [`Platform::identifier_case`](crate::platform::Platform::identifier_case)
+    /// to compare like the target database.
+    #[must_use]
+    pub const fn with_identifier_case(mut self, identifier_case: IdentifierCase) -> Self {
+        self.identifier_case = Some(identifier_case);
+        self
+    }
+
+    /// Normalize default values before comparing
+    #[must_use]
+    pub const fn with_default_normalization(mut self, normalize: bool) -> Self {
+        self.normalize_defaults = normalize;
+        self
+    }
+
+    /// Report columns whose position changed
+    #[must_use]
+    pub const fn with_column_order(mut self, compare: bool) -> Self {
+        self.compare_column_order = compare;
+        self
+    }
+
+    /// Treat two types as equal, e.g. `JSON` and `TEXT` on `SQLite`
+    #[must_use]
+    pub fn with_type_alias(mut self, a: SqlType, b: SqlType) -> Self {
+        self.type_aliases.push((a, b));
+        self
+    }
+
+    /// Leave a table out of the comparison
+    #[must_use]
+    pub fn ignore_table(mut self, table: impl Into<String>) -> Self {
+        self.ignored_tables.push(table.into());
+        self
+    }
+
+    /// Leave a column of a table out of the comparison
+    #[must_use]
+    pub fn ignore_column(mut self, table: impl Into<String>, column: impl Into<String>) -> Self {
+        self.ignored_columns.push((table.into(), column.into()));
+        self
+    }
+
+    /// Check if two identifiers are equal
+    fn same_name(&self, a: &str, b: &str) -> bool {
+        if let Some(identifier_case) = self.identifier_case {
+            identifier_case.same(a, b)
+        } else if self.case_insensitive {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    }
+
+    /// Check if two lists of identifiers are equal
+    fn same_names(&self, a: &[String], b: &[String]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| self.same_name(a, b))
+    }
+
+    /// Check if a table is ignored
+    fn ignores_table(&self, table: &str) -> bool {
+        self.ignored_tables.iter().any(|ignored| self.same_name(ignored, table))
+    }
+
+    /// Check if a column is ignored
+    fn ignores_column(&self, table: &str, column: &str) -> bool {
+        self.ignored_columns
+            .iter()
+            .any(|(t, c)| self.same_name(t, table) && self.same_name(c, column))
+    }
+
+    /// Get the tables of a schema that are compared
+    fn compared_tables<'s>(&self, schema: &'s Schema) -> Vec<&'s Table> {
+        schema.tables.iter().filter(|table| !self.ignores_table(&table.name)).collect()
+    }
+
+    /// Get the columns of a table that are compared, ignoring columns listed for `table_name`
+    fn compared_columns<'t>(&self, table_name: &str, table: &'t Table) -> Vec<&'t Column> {
+        table.columns.iter().filter(|column| !self.ignores_column(table_name, &column.name)).collect()
+    }
+
+    /// Check if two column types are equal
+    fn same_type(&self, a: &SqlType, b: &SqlType) -> bool {
+        if a == b || self.type_aliases.iter().any(|(x, y)| (x == a && y == b) || (x == b && y == a)) {
+            return true;
+        }
+        self.normalize_types && normalized_type(a) == normalized_type(b)
+    }
+
+    /// Check if two columns have equal default values
+    fn same_default(&self, a: &Column, b: &Column) -> bool {
+        if !self.normalize_defaults {
+            return a.default == b.default;
+        }
+        normalized_default(a) == normalized_default(b)
+    }
+}
+
+/// Map a type to the type its aliases share
+fn normalized_type(sql_type: &SqlType) -> SqlType {
+    match sql_type {
+        SqlType::Serial => SqlType::Integer,
+        SqlType::BigSerial => SqlType::BigInt,
+        SqlType::Char { length: 36 } | SqlType::Binary { length: 16 } => SqlType::Uuid,
+        other => other.clone(),
+    }
+}
+
+/// Get the type a column is stored as, binary for compressed columns
+fn stored_type(column: &Column) -> SqlType {
+    if column.compression.is_some() {
+        SqlType::Blob
+    } else {
+        column.sql_type.clone()
+    }
+}
+
+/// Check if either column is a text column restricted to a set of values
+/// and both are text
+///
+/// Native enum types are introspected as unknown types, i.e. `TEXT`.
+fn text_enums(a: &Column, b: &Column) -> bool {
+    (!a.enum_values.is_empty() || !b.enum_values.is_empty()) && stored_type(a).is_string() && stored_type(b).is_string()
+}
+
+/// Get the default value of a column with expressions parsed, `None` for NULL
+///
+/// Integer values are widened to `I64` so `DefaultValue::value(0)`
+/// equals a parsed `0`.
+fn normalized_default(column: &Column) -> Option<DefaultValue> {
+    let default = match column.default.as_ref()? {
+        DefaultValue::Expression(sql) => DefaultValue::parse(sql, &column.sql_type)?,
+        DefaultValue::Value(value) => match value.as_i64() {
+            Some(number) if !matches!(value, SqlValue::Bool(_)) => DefaultValue::Value(SqlValue::I64(number)),
+            _ => DefaultValue::Value(value.clone()),
+        },
+        other => other.clone(),
+    };
+    (default != DefaultValue::Null).then_some(default)
+}
+
+/// A column whose definition differs between two tables
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDiff {
+    /// Definition in the compared-from table
+    pub from: Column,
+    /// Definition in the compared-to table
+    pub to: Column,
+}
+
+impl ColumnDiff {
+    /// Get the allowed values only the compared-to column has
+    ///
+    /// These can be added with
+    /// [`Platform::get_add_enum_value_sql`](crate::platform::Platform::get_add_enum_value_sql)
+    /// for native enum types.
+    #[must_use]
+    pub fn added_enum_values(&self) -> Vec<String> {
+        missing(&self.to.enum_values, &self.from.enum_values, PartialEq::eq)
+    }
+
+    /// Get the allowed values only the compared-from column has
+    #[must_use]
+    pub fn removed_enum_values(&self) -> Vec<String> {
+        missing(&self.from.enum_values, &self.to.enum_values, PartialEq::eq)
+    }
+}
+
+/// Differences between two versions of a table
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TableDiff {
+    /// Table name
+    pub name: String,
+    /// Columns only in the compared-to table
+    pub added_columns: Vec<Column>,
+    /// Columns only in the compared-from table
+    pub removed_columns: Vec<Column>,
+    /// Columns whose type, allowed values, nullability, default, auto-increment or platform options differ
+    pub changed_columns: Vec<ColumnDiff>,
+    /// Indexes only in the compared-to table
+    pub added_indexes: Vec<Index>,
+    /// Indexes only in the compared-from table
+    pub removed_indexes: Vec<Index>,
+    /// Foreign keys only in the compared-to table
+    pub added_foreign_keys: Vec<ForeignKey>,
+    /// Foreign keys only in the compared-from table
+    pub removed_foreign_keys: Vec<ForeignKey>,
+    /// Whether the columns both tables have are in a different order
+    pub column_order_changed: bool,
+}
+
+impl TableDiff {
+    /// Check if the tables are equal
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.changed_columns.is_empty()
+            && self.added_indexes.is_empty()
+            && self.removed_indexes.is_empty()
+            && self.added_foreign_keys.is_empty()
+            && self.removed_foreign_keys.is_empty()
+            && !self.column_order_changed
+    }
+}
+
+/// Differences between two schemas
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SchemaDiff {
+    /// Tables only in the compared-to schema
+    pub created_tables: Vec<Table>,
+    /// Tables only in the compared-from schema
+    pub dropped_tables: Vec<Table>,
+    /// Tables in both schemas that differ
+    pub changed_tables: Vec<TableDiff>,
+}
+
+impl SchemaDiff {
+    /// Check if the schemas are equal
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.created_tables.is_empty() && self.dropped_tables.is_empty() && self.changed_tables.is_empty()
+    }
+}
+
+/// Compares schema definitions, e.g. the schema an application expects
+/// with one read by [`SchemaManager::introspect_schema`](super::SchemaManager::introspect_schema)
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let options = ComparatorOptions::new().ignore_table("sessions").ignore_column("users", "legacy_flags");
+/// let diff = Comparator::with_options(options).compare(&manager.introspect_schema().await?, &expected);
+/// for table in &diff.created_tables {
+///     println!("missing table {}", table.name);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comparator {
+    /// What is treated as equal
+    options: ComparatorOptions,
+}
+
+impl Comparator {
+    /// Create a comparator with the default options
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a comparator with the given options
+    #[must_use]
+    pub const fn with_options(options: ComparatorOptions) -> Self {
+        Self { options }
+    }
+
+    /// Get the differences turning schema `from` into schema `to`
+    #[must_use]
+    pub fn compare(&self, from: &Schema, to: &Schema) -> SchemaDiff {
+        let options = &self.options;
+        let (from_tables, to_tables) = (options.compared_tables(from), options.compared_tables(to));
+        let find = |tables: &[&Table], name: &str| tables.iter().any(|t| options.same_name(&t.name, name));
+
+        let mut diff = SchemaDiff::default();
+        for table in &to_tables {
+            match from_tables.iter().find(|t| options.same_name(&t.name, &table.name)) {
+                Some(old) => {
+                    let table_diff = self.compare_tables(old, table);
+                    if !table_diff.is_empty() {
+                        diff.changed_tables.push(table_diff);
+                    }
+                }
+                None => diff.created_tables.push((*table).clone()),
+            }
+        }
+        diff.dropped_tables = from_tables
+            .into_iter()
+            .filter(|table| !find(&to_tables, &table.name))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Get the differences turning table `from` into table `to`
+    #[must_use]
+    pub fn compare_tables(&self, from: &Table, to: &Table) -> TableDiff {
+        let options = &self.options;
+        let (from_columns, to_columns) = (options.compared_columns(&to.name, from), options.compared_columns(&to.name, to));
+        let find = |columns: &[&Column], name: &str| columns.iter().any(|c| options.same_name(&c.name, name));
+
+        let mut diff = TableDiff { name: to.name.clone(), ..TableDiff::default() };
+        for column in &to_columns {
+            match from_columns.iter().find(|c| options.same_name(&c.name, &column.name)) {
+                Some(old) if !self.same_column(old, column) => {
+                    diff.changed_columns.push(ColumnDiff { from: (*old).clone(), to: (*column).clone() });
+                }
+                Some(_) => {}
+                None => diff.added_columns.push((*column).clone()),
+            }
+        }
+        diff.removed_columns = from_columns
+            .iter()
+            .filter(|column| !find(&to_columns, &column.name))
+            .map(|column| (*column).clone())
+            .collect();
+
+        if options.compare_column_order {
+            let common = |columns: &[&Column], others: &[&Column]| -> Vec<String> {
+                columns
+                    .iter()
+                    .filter(|column| find(others, &column.name))
+                    .map(|column| column.name.clone())
+                    .collect()
+            };
+            diff.column_order_changed =
+                !options.same_names(&common(&from_columns, &to_columns), &common(&to_columns, &from_columns));
+        }
+
+        let same_index = |a: &Index, b: &Index| {
+            a.primary == b.primary
+                && a.unique == b.unique
+                && options.same_names(&a.columns, &b.columns)
+                && (a.name.is_empty() || b.name.is_empty() || a.primary || options.same_name(&a.name, &b.name))
+        };
+        diff.added_indexes = missing(&to.indexes, &from.indexes, same_index);
+        diff.removed_indexes = missing(&from.indexes, &to.indexes, same_index);
+
+        let same_foreign_key = |a: &ForeignKey, b: &ForeignKey| {
+            options.same_names(&a.local_columns, &b.local_columns)
+                && options.same_name(&a.foreign_table, &b.foreign_table)
+                && options.same_names(&a.foreign_columns, &b.foreign_columns)
+                && a.on_delete == b.on_delete
+                && a.on_update == b.on_update
+                && (a.name.is_empty() || b.name.is_empty() || options.same_name(&a.name, &b.name))
+        };
+        diff.added_foreign_keys = missing(&to.foreign_keys, &from.foreign_keys, same_foreign_key);
+        diff.removed_foreign_keys = missing(&from.foreign_keys, &to.foreign_keys, same_foreign_key);
+        diff
+    }
+
+    /// Check if two columns have the same definition
+    fn same_column(&self, a: &Column, b: &Column) -> bool {
+        let options = &self.options;
+        let auto_increment = |column: &Column| {
+            column.auto_increment || (options.normalize_types && column.sql_type.is_auto_increment())
+        };
+        // Allowed values kept in a CHECK constraint are not introspected
+        let same_values = a.enum_values.is_empty() || b.enum_values.is_empty() || a.enum_values == b.enum_values;
+        ((options.normalize_types && text_enums(a, b)) || options.same_type(&stored_type(a), &stored_type(b)))
+            && same_values
+            && a.nullable == b.nullable
+            && auto_increment(a) == auto_increment(b)
+            && options.same_default(a, b)
+            && a.platform_options == b.platform_options
+    }
+}
+
+/// Get the items of `items` without an equal item in `others`
+fn missing<T: Clone>(items: &[T], others: &[T], same: impl Fn(&T, &T) -> bool) -> Vec<T> {
+    items.iter().filter(|item| !others.iter().any(|other| same(item, other))).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Compression;
+
+    fn users() -> Table {
+        Table::new("users")
+            .column(Column::new("id", SqlType::Serial).not_null())
+            .column(Column::new("name", SqlType::varchar(100)).not_null())
+            .column(Column::new("status", SqlType::varchar(20)).default_value("active"))
+            .column(Column::new("token", SqlType::Char { length: 36 }))
+            .index(Index::primary(vec!["id".to_string()]))
+    }
+
+    #[test]
+    fn test_normalized_comparison() {
+        // As introspected from PostgreSQL: folded names, other spellings, other column order
+        let introspected = Table::new("USERS")
+            .column(Column::new("ID", SqlType::Integer).not_null().auto_increment())
+            .column(Column::new("Status", SqlType::varchar(20)).default_expression("('active'::character varying)"))
+            .column(Column::new("name", SqlType::varchar(100)).not_null())
+            .column(Column::new("token", SqlType::Uuid))
+            .column(Column::new("legacy", SqlType::Text))
+            .index(Index::primary(vec!["id".to_string()]));
+        let from = Schema::new().table(introspected).table(Table::new("sessions"));
+        let to = Schema::new().table(users());
+
+        let options = ComparatorOptions::new().ignore_table("sessions").ignore_column("users", "legacy");
+        assert!(Comparator::with_options(options).compare(&from, &to).is_empty());
+
+        let diff = Comparator::with_options(ComparatorOptions::strict()).compare(&from, &to);
+        assert_eq!((diff.created_tables.len(), diff.dropped_tables.len()), (1, 2));
+        let diff = Comparator::with_options(ComparatorOptions::strict().with_case_insensitive_identifiers(true))
+            .compare(&from, &to);
+        let table = &diff.changed_tables[0];
+        let changed: Vec<&str> = table.changed_columns.iter().map(|c| c.to.name.as_str()).collect();
+        assert_eq!(changed, ["id", "status", "token"]);
+        assert_eq!(table.removed_columns[0].name, "legacy");
+        assert!(table.column_order_changed);
+    }
+
+    #[test]
+    fn test_compressed_column() {
+        let from = Table::new("events").column(Column::new("payload", SqlType::Blob));
+        let to = Table::new("events").column(Column::new("payload", SqlType::Json).compressed(Compression::Gzip));
+        assert!(Comparator::new().compare_tables(&from, &to).is_empty());
+        let to = Table::new("events").column(Column::new("payload", SqlType::Json));
+        assert!(!Comparator::new().compare_tables(&from, &to).is_empty());
+    }
+
+    #[test]
+    fn test_enum_column() {
+        let declared = Table::new("users").column(Column::new("role", SqlType::varchar(10)).allowed_values(["admin", "guest"]));
+        // Native enum types are introspected as TEXT, CHECK constraints not at all
+        let native = Table::new("users").column(Column::new("role", SqlType::Text).allowed_values(["admin", "guest"]));
+        let checked = Table::new("users").column(Column::new("role", SqlType::varchar(10)));
+        assert!(Comparator::new().compare_tables(&native, &declared).is_empty());
+        assert!(Comparator::new().compare_tables(&checked, &declared).is_empty());
+
+        let extended = Table::new("users").column(Column::new("role", SqlType::Text).allowed_values(["admin", "editor"]));
+        let diff = Comparator::new().compare_tables(&native, &extended);
+        assert_eq!(diff.changed_columns.len(), 1);
+        assert_eq!(diff.changed_columns[0].added_enum_values(), vec!["editor".to_string()]);
+        assert_eq!(diff.changed_columns[0].removed_enum_values(), vec!["guest".to_string()]);
+    }
+
+    #[test]
+    fn test_identifier_case() {
+        let from = Schema::new().table(Table::new("Users").column(Column::new("ID", SqlType::Integer)));
+        let to = Schema::new().table(Table::new("users").column(Column::new("id", SqlType::Integer)));
+        let lower = ComparatorOptions::new().with_identifier_case(IdentifierCase::LowerUnquoted);
+        assert!(Comparator::with_options(lower.clone()).compare(&from, &to).is_empty());
+
+        let quoted = Schema::new().table(Table::new("\"Users\"").column(Column::new("id", SqlType::Integer)));
+        let diff = Comparator::with_options(lower).compare(&quoted, &to);
+        assert_eq!((diff.created_tables.len(), diff.dropped_tables.len()), (1, 1));
+
+        let preserve = ComparatorOptions::new().with_identifier_case(IdentifierCase::PreserveQuoted);
+        assert!(!Comparator::with_options(preserve).compare(&from, &to).is_empty());
+    }
+
+    #[test]
+    fn test_table_changes() {
+        let to = users()
+            .column(Column::new("email", SqlType::varchar(255)))
+            .index(Index::unique("uniq_users_email", vec!["email".to_string()]));
+        let diff = Comparator::new().compare_tables(&users(), &to);
+        assert_eq!(diff.added_columns[0].name, "email");
+        assert_eq!(diff.added_indexes[0].name, "uniq_users_email");
+        assert!(diff.removed_columns.is_empty() && diff.changed_columns.is_empty());
+
+        let nullable = Table::new("users").column(Column::new("name", SqlType::varchar(100)));
+        let diff = Comparator::new().compare_tables(&Table::new("users").column(Column::new("name", SqlType::Text)), &nullable);
+        assert_eq!(diff.changed_columns.len(), 1);
+
+        let updated_at = Column::new("updated_at", SqlType::timestamp(None));
+        let on_update = Table::new("users").column(updated_at.clone().platform_option("on_update", "CURRENT_TIMESTAMP"));
+        let diff = Comparator::new().compare_tables(&Table::new("users").column(updated_at), &on_update);
+        assert_eq!(diff.changed_columns.len(), 1);
+
+        let expression = |sql: &str| DefaultValue::Expression(sql.to_string());
+        let status = |default| Column { default: Some(default), ..Column::new("status", SqlType::Text) };
+        assert_eq!(normalized_default(&status(expression("NULL"))), None);
+        assert_eq!(normalized_default(&status(expression("now()"))), Some(DefaultValue::CurrentTimestamp));
+        assert_eq!(normalized_default(&status(expression("('a::b'::text)"))), Some(DefaultValue::value("a::b")));
+        let count = |default| Column { default: Some(default), ..Column::new("count", SqlType::Integer) };
+        assert!(ComparatorOptions::new().same_default(&count(DefaultValue::value(0)), &count(expression("'0'::integer"))));
+        assert!(!ComparatorOptions::strict().same_default(&count(DefaultValue::value(0)), &count(expression("0"))));
+    }
+}
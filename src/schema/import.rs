@@ -0,0 +1,227 @@
+//! Loading of reference data from CSV and JSON lines
+
+use std::collections::BTreeMap;
+use std::io::Read;
+
+use crate::core::{Error, Result, SqlValue};
+#[cfg(feature = "json")]
+use crate::dump::CsvField;
+use crate::dump::{parse_field, parse_records};
+use crate::platform::Table;
+
+/// Options for [`SchemaManager::import_csv`](super::SchemaManager::import_csv)
+/// and [`import_json_lines`](super::SchemaManager::import_json_lines)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Field separator of CSV input
+    pub delimiter: char,
+    /// Table column for each CSV header or JSON key named differently
+    pub column_mapping: BTreeMap<String, String>,
+    /// Skip fields matching no table column instead of failing
+    pub ignore_unknown_columns: bool,
+    /// Number of rows per INSERT statement
+    pub batch_size: usize,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            column_mapping: BTreeMap::new(),
+            ignore_unknown_columns: false,
+            batch_size: 100,
+        }
+    }
+}
+
+impl ImportOptions {
+    /// Create options for comma-separated input whose fields are named like the columns
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the field separator of CSV input, e.g. `;` or `\t`
+    #[must_use]
+    pub const fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Load a CSV header or JSON key into a differently named column
+    #[must_use]
+    pub fn map_column(mut self, field: impl Into<String>, column: impl Into<String>) -> Self {
+        self.column_mapping.insert(field.into(), column.into());
+        self
+    }
+
+    /// Skip fields matching no table column instead of failing
+    #[must_use]
+    pub const fn with_ignore_unknown_columns(mut self, ignore: bool) -> Self {
+        self.ignore_unknown_columns = ignore;
+        self
+    }
+
+    /// Set the number of rows per INSERT statement (at least 1)
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Get the position of the table column a field is loaded into, `None`
+    /// for a skipped field
+    fn target(&self, table: &Table, field: &str) -> Result<Option<usize>> {
+        let name = self.column_mapping.get(field).map_or(field, String::as_str);
+        match table.columns.iter().position(|column| column.name == name) {
+            Some(position) => Ok(Some(position)),
+            None if self.ignore_unknown_columns => Ok(None),
+            None => Err(import_error(format!("unknown column '{name}'"))),
+        }
+    }
+}
+
+/// Create an error for input that can't be loaded
+fn import_error(message: impl Into<String>) -> Error {
+    Error::conversion("import", "row", message)
+}
+
+/// Read the whole import input as text
+pub(super) fn read_input(mut reader: impl Read) -> Result<String> {
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .map_err(|e| Error::driver("Failed to read import", e))?;
+    Ok(text)
+}
+
+/// Keep the columns at the given positions of a table, in that order
+fn select_columns(table: &Table, positions: &[usize]) -> Table {
+    let mut selected = Table::new(&table.name);
+    selected.columns = positions.iter().map(|&position| table.columns[position].clone()).collect();
+    selected
+}
+
+/// Read CSV rows for a table, returning the table reduced to the header's columns
+pub(super) fn csv_rows(table: &Table, csv: &str, options: &ImportOptions) -> Result<(Table, Vec<Vec<SqlValue>>)> {
+    let mut records = parse_records(csv, options.delimiter)?.into_iter();
+    let Some(header) = records.next() else {
+        return Ok((select_columns(table, &[]), Vec::new()));
+    };
+    let targets = header
+        .iter()
+        .map(|field| options.target(table, &field.text))
+        .collect::<Result<Vec<Option<usize>>>>()?;
+
+    let mut rows = Vec::new();
+    for (line, record) in records.enumerate() {
+        if record.len() != targets.len() {
+            return Err(import_error(format!(
+                "row {} has {} fields, expected {}",
+                line + 1,
+                record.len(),
+                targets.len()
+            )));
+        }
+        let row = record
+            .into_iter()
+            .zip(&targets)
+            .filter_map(|(field, target)| target.map(|position| parse_field(field, &table.columns[position].sql_type)))
+            .collect::<Result<Vec<SqlValue>>>()?;
+        rows.push(row);
+    }
+
+    let positions: Vec<usize> = targets.into_iter().flatten().collect();
+    Ok((select_columns(table, &positions), rows))
+}
+
+/// Read JSON objects, one per line, for a table, returning the table reduced
+/// to the columns any object has
+///
+/// Values are converted by the column type: strings and numbers like CSV
+/// fields, arrays and objects to JSON for JSON columns and to JSON text
+/// otherwise. Keys an object lacks are NULL.
+#[cfg(feature = "json")]
+pub(super) fn json_lines_rows(table: &Table, text: &str, options: &ImportOptions) -> Result<(Table, Vec<Vec<SqlValue>>)> {
+    use serde_json::{Map, Value};
+
+    let mut objects = Vec::new();
+    for (line, text) in text.lines().enumerate().filter(|(_, text)| !text.trim().is_empty()) {
+        let object: Map<String, Value> =
+            serde_json::from_str(text).map_err(|e| import_error(format!("line {}: {e}", line + 1)))?;
+        let mut fields = Vec::new();
+        for (key, value) in object {
+            if let Some(position) = options.target(table, &key)? {
+                fields.push((position, value));
+            }
+        }
+        objects.push(fields);
+    }
+
+    let mut positions: Vec<usize> = objects.iter().flatten().map(|(position, _)| *position).collect();
+    positions.sort_unstable();
+    positions.dedup();
+
+    let mut rows = Vec::with_capacity(objects.len());
+    for fields in objects {
+        let mut row = vec![SqlValue::Null; positions.len()];
+        for (position, value) in fields {
+            let sql_type = &table.columns[position].sql_type;
+            let index = positions.binary_search(&position).unwrap_or_default();
+            row[index] = match value {
+                Value::Null => SqlValue::Null,
+                Value::Bool(b) => SqlValue::Bool(b),
+                Value::Number(n) => parse_field(CsvField { text: n.to_string(), quoted: false }, sql_type)?,
+                Value::String(text) => parse_field(CsvField { text, quoted: true }, sql_type)?,
+                value if *sql_type == crate::platform::SqlType::Json => SqlValue::Json(value),
+                value => SqlValue::String(value.to_string()),
+            };
+        }
+        rows.push(row);
+    }
+
+    Ok((select_columns(table, &positions), rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::{Column, SqlType};
+
+    fn countries() -> Table {
+        Table::new("countries")
+            .column(Column::new("code", SqlType::Char { length: 2 }))
+            .column(Column::new("name", SqlType::Text))
+            .column(Column::new("population", SqlType::BigInt))
+            .column(Column::new("eu", SqlType::Boolean))
+    }
+
+    #[test]
+    fn test_csv_rows() {
+        let options = ImportOptions::new().with_delimiter(';').map_column("Country", "name");
+        let (table, rows) = csv_rows(&countries(), "code;Country;eu\nDE;Germany;1\nFR;\"France; République\";\n", &options).unwrap();
+        let names: Vec<&str> = table.columns.iter().map(|column| column.name.as_str()).collect();
+        assert_eq!(names, ["code", "name", "eu"]);
+        assert_eq!(rows[0], ["DE".into(), "Germany".into(), SqlValue::Bool(true)]);
+        assert_eq!(rows[1], ["FR".into(), "France; République".into(), SqlValue::Null]);
+
+        assert!(csv_rows(&countries(), "code,capital\nDE,Berlin\n", &ImportOptions::new()).is_err());
+        let lenient = ImportOptions::new().with_ignore_unknown_columns(true);
+        let (table, rows) = csv_rows(&countries(), "code,capital\nDE,Berlin\n", &lenient).unwrap();
+        assert_eq!((table.columns.len(), rows[0].clone()), (1, vec!["DE".into()]));
+        assert!(csv_rows(&countries(), "population\nmany\n", &ImportOptions::new()).is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_lines_rows() {
+        let input = "{\"name\": \"Germany\", \"population\": 83000000}\n\n{\"code\": \"FR\", \"eu\": true, \"population\": \"68000000\"}\n";
+        let (table, rows) = json_lines_rows(&countries(), input, &ImportOptions::new()).unwrap();
+        assert_eq!(table.columns.len(), 4);
+        assert_eq!(rows[0], [SqlValue::Null, "Germany".into(), SqlValue::I64(83_000_000), SqlValue::Null]);
+        assert_eq!(rows[1], ["FR".into(), SqlValue::Null, SqlValue::I64(68_000_000), SqlValue::Bool(true)]);
+
+        assert!(json_lines_rows(&countries(), "{\"name\": ", &ImportOptions::new()).is_err());
+        assert!(json_lines_rows(&countries(), "{\"capital\": \"Paris\"}", &ImportOptions::new()).is_err());
+    }
+}
@@ -1,33 +1,122 @@
 //! Schema Manager for database introspection and manipulation
 
-use crate::core::{Result, SqlValue};
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::core::{Error, Result, SchemaError, SqlValue};
 use crate::driver::{DriverConnection, DriverResult};
-use crate::platform::{ForeignKeyAction, Index, Platform, Table};
+use crate::dump::TableDump;
+use crate::platform::{Column, DefaultValue, ForeignKey, ForeignKeyAction, IdentifierCase, Index, Platform, Schema, SqlType, Table};
+
+use super::import::{self, ImportOptions};
 
 /// Schema Manager for introspecting and manipulating database schemas
 ///
 /// The `SchemaManager` provides methods to:
 /// - List tables, columns, indexes, and foreign keys
 /// - Create and drop tables
+/// - Create and drop whole schemas in foreign key order
 /// - Create and drop indexes
-#[derive(Debug)]
-pub struct SchemaManager<'a, C: DriverConnection, P: Platform> {
+/// - Load reference data from CSV or JSON lines
+///
+/// Table, column and index names are checked with
+/// [`Platform::assert_valid_identifier`] before any SQL is generated.
+/// Introspection takes names as stored, minus any quotes; with
+/// [`with_identifier_case`](Self::with_identifier_case) unquoted names are
+/// folded first, so `Users` finds the `users` table on `PostgreSQL`.
+///
+/// [`new`](Self::new) borrows the connection and platform for a short-lived
+/// manager; [`new_owned`](Self::new_owned) shares them through `Arc` for a
+/// `SchemaManager<'static, C>` that can be kept in application state.
+pub struct SchemaManager<'a, C: DriverConnection> {
     /// Database connection for executing schema queries
-    connection: &'a C,
+    connection: Handle<'a, C>,
     /// Platform for generating SQL
-    platform: &'a P,
+    platform: Handle<'a, dyn Platform + 'a>,
+    /// Case folding applied to names passed to introspection methods
+    identifier_case: Option<IdentifierCase>,
 }
 
-impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
+/// A borrowed or shared reference
+enum Handle<'a, T: ?Sized> {
+    /// Borrowed for the manager's lifetime
+    Borrowed(&'a T),
+    /// Shared ownership
+    Shared(Arc<T>),
+}
+
+impl<T: ?Sized> Deref for Handle<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Self::Borrowed(value) => value,
+            Self::Shared(value) => value,
+        }
+    }
+}
+
+impl<C: DriverConnection> std::fmt::Debug for SchemaManager<'_, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SchemaManager")
+            .field("platform", &self.platform.name())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: DriverConnection> SchemaManager<'static, C> {
+    /// Create a `SchemaManager` owning shared handles to the connection and platform
+    #[must_use]
+    pub const fn new_owned(connection: Arc<C>, platform: Arc<dyn Platform>) -> Self {
+        Self {
+            connection: Handle::Shared(connection),
+            platform: Handle::Shared(platform),
+            identifier_case: None,
+        }
+    }
+}
+
+impl<'a, C: DriverConnection> SchemaManager<'a, C> {
     /// Create a new `SchemaManager`
     #[must_use]
-    pub const fn new(connection: &'a C, platform: &'a P) -> Self {
+    pub const fn new(connection: &'a C, platform: &'a dyn Platform) -> Self {
         Self {
-            connection,
-            platform,
+            connection: Handle::Borrowed(connection),
+            platform: Handle::Borrowed(platform),
+            identifier_case: None,
         }
     }
 
+    /// Fold unquoted names passed to introspection methods like the database does
+    ///
+    /// Usually [`Platform::identifier_case`], see
+    /// [`with_platform_identifier_case`](Self::with_platform_identifier_case).
+    /// Tables created by this manager have quoted names that keep their case,
+    /// so folding is off by default.
+    #[must_use]
+    pub const fn with_identifier_case(mut self, identifier_case: IdentifierCase) -> Self {
+        self.identifier_case = Some(identifier_case);
+        self
+    }
+
+    /// Fold unquoted names passed to introspection methods like the platform's database
+    ///
+    /// Same as [`with_identifier_case`](Self::with_identifier_case) with the
+    /// platform's [`identifier_case`](Platform::identifier_case).
+    #[must_use]
+    pub fn with_platform_identifier_case(self) -> Self {
+        let identifier_case = self.platform.identifier_case();
+        self.with_identifier_case(identifier_case)
+    }
+
+    /// Get the name the database stores for a table name passed by the caller
+    fn stored_name(&self, name: &str) -> Result<String> {
+        let name = self.identifier_case.unwrap_or_default().normalize(name);
+        self.platform.assert_valid_identifier(&name)?;
+        Ok(name)
+    }
+
     /// List all table names in the database
     ///
     /// # Errors
@@ -54,7 +143,8 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
     ///
     /// Returns an error if the query fails.
     pub async fn list_table_columns(&self, table_name: &str) -> Result<Vec<ColumnInfo>> {
-        let sql = self.platform.get_list_columns_sql(table_name);
+        let table_name = self.stored_name(table_name)?;
+        let sql = self.platform.get_list_columns_sql(&table_name);
         let mut result = self.connection.query(&sql).await?;
         let rows = result.all_rows()?;
 
@@ -74,27 +164,55 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
     ///
     /// Returns an error if the query fails.
     pub async fn list_table_indexes(&self, table_name: &str) -> Result<Vec<IndexInfo>> {
-        let sql = self.platform.get_list_indexes_sql(table_name);
+        let table_name = self.stored_name(table_name)?;
+        let sql = self.platform.get_list_indexes_sql(&table_name);
         let mut result = self.connection.query(&sql).await?;
         let rows = result.all_rows()?;
 
-        let mut indexes = Vec::new();
+        let mut indexes: Vec<IndexInfo> = Vec::new();
         for row in rows {
             if let Some(info) = self.parse_index_row(&row) {
-                indexes.push(info);
+                // Information schema queries return one row per indexed column
+                match indexes.iter_mut().find(|index| index.name == info.name) {
+                    Some(index) => index.columns.extend(info.columns),
+                    None => indexes.push(info),
+                }
+            }
+        }
+
+        if self.platform.name() == "sqlite" {
+            for index in &mut indexes {
+                index.columns = self.list_sqlite_index_columns(&index.name).await?;
             }
         }
 
         Ok(indexes)
     }
 
+    /// List the columns of a `SQLite` index in index order
+    async fn list_sqlite_index_columns(&self, index_name: &str) -> Result<Vec<String>> {
+        // PRAGMA index_info returns: seqno, cid, name
+        let sql = format!("PRAGMA index_info({})", self.platform.quote_identifier(index_name));
+        let mut result = self.connection.query(&sql).await?;
+        let rows = result.all_rows()?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| match row.into_iter().nth(2) {
+                Some(SqlValue::String(name)) => Some(name),
+                _ => None,
+            })
+            .collect())
+    }
+
     /// List all foreign keys of a table
     ///
     /// # Errors
     ///
     /// Returns an error if the query fails.
     pub async fn list_table_foreign_keys(&self, table_name: &str) -> Result<Vec<ForeignKeyInfo>> {
-        let sql = self.platform.get_list_foreign_keys_sql(table_name);
+        let table_name = self.stored_name(table_name)?;
+        let sql = self.platform.get_list_foreign_keys_sql(&table_name);
         let mut result = self.connection.query(&sql).await?;
         let rows = result.all_rows()?;
 
@@ -112,10 +230,14 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
     ///
     /// # Errors
     ///
-    /// Returns an error if listing tables fails.
+    /// Returns an error if the name is invalid or listing tables fails.
     pub async fn table_exists(&self, table_name: &str) -> Result<bool> {
+        let name = self.stored_name(table_name)?;
         let tables = self.list_table_names().await?;
-        Ok(tables.iter().any(|t| t.eq_ignore_ascii_case(table_name)))
+        Ok(tables.iter().any(|t| match self.identifier_case {
+            Some(_) => *t == name,
+            None => t.eq_ignore_ascii_case(&name),
+        }))
     }
 
     /// Get full table information including columns, indexes, and foreign keys
@@ -129,22 +251,86 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
         let foreign_keys = self.list_table_foreign_keys(table_name).await?;
 
         Ok(TableInfo {
-            name: table_name.to_string(),
+            name: self.stored_name(table_name)?,
             columns,
             indexes,
             foreign_keys,
         })
     }
 
+    /// Get the row count and on-disk size of a table, including its indexes
+    ///
+    /// `PostgreSQL` and `MySQL` report the estimates kept by the database,
+    /// `SQLite` counts the rows. Statistics the database does not provide are
+    /// `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchemaError::TableNotFound`] if the database has no
+    /// statistics for the table, a platform error if the platform keeps
+    /// none, and an error if the query fails.
+    pub async fn table_statistics(&self, table_name: &str) -> Result<TableStatistics> {
+        let table_name = self.stored_name(table_name)?;
+        let sql = self.platform.get_table_statistics_sql(&table_name).ok_or_else(|| {
+            Error::platform(format!("{} does not report table statistics", self.platform.name()))
+        })?;
+        let mut result = self.connection.query(&sql).await?;
+        let row = result.next_row()?.ok_or(SchemaError::TableNotFound(table_name))?;
+
+        Ok(TableStatistics {
+            row_count: row.first().and_then(statistic),
+            size_bytes: row.get(1).and_then(statistic),
+        })
+    }
+
+    /// List the privileges granted on a table
+    ///
+    /// # Errors
+    ///
+    /// Returns a platform error if the platform has no grants (`SQLite`), and
+    /// an error if the name is invalid or the query fails.
+    pub async fn grants_for(&self, table_name: &str) -> Result<Vec<GrantInfo>> {
+        let table_name = self.stored_name(table_name)?;
+        let sql = self.platform.get_list_grants_sql(&table_name).ok_or_else(|| {
+            Error::platform(format!("{} does not support users and grants", self.platform.name()))
+        })?;
+        let mut result = self.connection.query(&sql).await?;
+
+        Ok(result
+            .all_rows()?
+            .into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [SqlValue::String(grantee), SqlValue::String(privilege), ..] => Some(GrantInfo {
+                    grantee: grantee.clone(),
+                    privilege: privilege.clone(),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Introspect all tables of the database as a schema definition
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if introspecting a table fails.
+    pub async fn introspect_schema(&self) -> Result<Schema> {
+        let mut schema = Schema::new();
+        for name in self.list_table_names().await? {
+            schema = schema.table(self.introspect_table(&name).await?.to_table());
+        }
+        Ok(schema)
+    }
+
     /// Create a table from a Table definition
     ///
     /// # Errors
     ///
     /// Returns an error if the CREATE TABLE statement fails.
     pub async fn create_table(&self, table: &Table) -> Result<()> {
+        self.assert_valid_table(table)?;
         let sql = self.platform.get_create_table_sql(table);
-        self.connection.execute(&sql).await?;
-        Ok(())
+        self.execute_schema_change(&sql).await
     }
 
     /// Drop a table
@@ -153,9 +339,9 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
     ///
     /// Returns an error if the DROP TABLE statement fails.
     pub async fn drop_table(&self, table_name: &str) -> Result<()> {
+        self.platform.assert_valid_identifier(table_name)?;
         let sql = self.platform.get_drop_table_sql(table_name);
-        self.connection.execute(&sql).await?;
-        Ok(())
+        self.execute_schema_change(&sql).await
     }
 
     /// Drop a table if it exists
@@ -164,9 +350,50 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
     ///
     /// Returns an error if the DROP TABLE IF EXISTS statement fails.
     pub async fn drop_table_if_exists(&self, table_name: &str) -> Result<()> {
+        self.platform.assert_valid_identifier(table_name)?;
         let sql = self.platform.get_drop_table_if_exists_sql(table_name);
-        self.connection.execute(&sql).await?;
-        Ok(())
+        self.execute_schema_change(&sql).await
+    }
+
+    /// Create all tables of a schema
+    ///
+    /// Referenced tables are created before the tables referencing them,
+    /// and foreign keys closing a reference cycle are added once all tables
+    /// exist, see [`Platform::get_create_schema_sql`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a statement fails; tables created before the
+    /// failure are kept.
+    pub async fn create_schema(&self, schema: &Schema) -> Result<()> {
+        for table in &schema.tables {
+            self.assert_valid_table(table)?;
+        }
+        self.execute_schema_changes(&self.platform.get_create_schema_sql(schema)).await
+    }
+
+    /// Drop all tables of a schema
+    ///
+    /// Tables are dropped in reverse creation order, after the foreign keys
+    /// closing a reference cycle.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SchemaError::InvalidDefinition`] if a foreign key closing a
+    /// cycle has no name to drop it by, or an error if a statement fails.
+    pub async fn drop_schema(&self, schema: &Schema) -> Result<()> {
+        let order = schema.creation_order();
+        let unnamed = order.deferred_foreign_keys.iter().find(|(table_name, fk)| {
+            fk.name.is_empty() && self.platform.get_create_foreign_key_sql(table_name, fk).is_some()
+        });
+        if let Some((table_name, fk)) = unnamed {
+            return Err(SchemaError::InvalidDefinition(format!(
+                "foreign key of {table_name} referencing {} closes a cycle and needs a name to be dropped",
+                fk.foreign_table
+            ))
+            .into());
+        }
+        self.execute_schema_changes(&self.platform.get_drop_schema_sql(schema)).await
     }
 
     /// Create an index
@@ -175,9 +402,10 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
     ///
     /// Returns an error if the CREATE INDEX statement fails.
     pub async fn create_index(&self, table_name: &str, index: &Index) -> Result<()> {
+        self.platform.assert_valid_identifier(table_name)?;
+        self.assert_valid_index(index)?;
         let sql = self.platform.get_create_index_sql(table_name, index);
-        self.connection.execute(&sql).await?;
-        Ok(())
+        self.execute_schema_change(&sql).await
     }
 
     /// Drop an index
@@ -186,11 +414,114 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
     ///
     /// Returns an error if the DROP INDEX statement fails.
     pub async fn drop_index(&self, index_name: &str, table_name: &str) -> Result<()> {
+        self.platform.assert_valid_identifier(index_name)?;
+        self.platform.assert_valid_identifier(table_name)?;
         let sql = self.platform.get_drop_index_sql(index_name, table_name);
-        self.connection.execute(&sql).await?;
+        self.execute_schema_change(&sql).await
+    }
+
+    /// Load CSV reference data into an existing table
+    ///
+    /// The first record names the columns, mapped through
+    /// [`ImportOptions::map_column`]. Fields are parsed by the introspected
+    /// column type, an empty unquoted field is NULL. All rows are inserted
+    /// in one transaction with multi-row INSERT statements of
+    /// [`batch_size`](ImportOptions::batch_size) rows. Returns the number of
+    /// rows loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input can't be read or parsed, names an
+    /// unknown column, or an INSERT fails; nothing is loaded then.
+    pub async fn import_csv(&self, table_name: &str, reader: impl std::io::Read, options: &ImportOptions) -> Result<u64> {
+        let csv = import::read_input(reader)?;
+        let table = self.introspect_table(table_name).await?.to_table();
+        let (table, rows) = import::csv_rows(&table, &csv, options)?;
+        self.import_rows(TableDump::new(table, rows), options).await
+    }
+
+    /// Load reference data from JSON objects, one per line, into an existing table
+    ///
+    /// Keys are mapped like CSV headers, keys an object lacks are NULL.
+    /// Arrays and objects are stored as JSON. See
+    /// [`import_csv`](Self::import_csv) for batching and the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input can't be read or parsed, names an
+    /// unknown column, or an INSERT fails; nothing is loaded then.
+    #[cfg(feature = "json")]
+    pub async fn import_json_lines(&self, table_name: &str, reader: impl std::io::Read, options: &ImportOptions) -> Result<u64> {
+        let text = import::read_input(reader)?;
+        let table = self.introspect_table(table_name).await?.to_table();
+        let (table, rows) = import::json_lines_rows(&table, &text, options)?;
+        self.import_rows(TableDump::new(table, rows), options).await
+    }
+
+    /// Insert the rows of a table dump in one transaction
+    async fn import_rows(&self, dump: TableDump, options: &ImportOptions) -> Result<u64> {
+        let count = dump.rows.len() as u64;
+        let statements = dump.insert_statements(&*self.platform, options.batch_size);
+        self.connection.begin_transaction().await?;
+        for statement in &statements {
+            if let Err(e) = self.connection.execute(statement).await {
+                let _ = self.connection.rollback().await;
+                return Err(e);
+            }
+        }
+        self.connection.commit().await?;
+        Ok(count)
+    }
+
+    /// Check the names of a table definition with [`Platform::assert_valid_identifier`]
+    fn assert_valid_table(&self, table: &Table) -> Result<()> {
+        self.platform.assert_valid_identifier(&table.name)?;
+        for column in &table.columns {
+            self.platform.assert_valid_identifier(&column.name)?;
+        }
+        for index in &table.indexes {
+            self.assert_valid_index(index)?;
+        }
+        for fk in &table.foreign_keys {
+            if !fk.name.is_empty() {
+                self.platform.assert_valid_identifier(&fk.name)?;
+            }
+            self.platform.assert_valid_identifier(&fk.foreign_table)?;
+            for column in fk.local_columns.iter().chain(&fk.foreign_columns) {
+                self.platform.assert_valid_identifier(column)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the names of an index definition, primary keys may be unnamed
+    fn assert_valid_index(&self, index: &Index) -> Result<()> {
+        if !(index.primary && index.name.is_empty()) {
+            self.platform.assert_valid_identifier(&index.name)?;
+        }
+        for column in &index.columns {
+            self.platform.assert_valid_identifier(column)?;
+        }
         Ok(())
     }
 
+    /// Run a schema-altering statement and flush the statement cache
+    ///
+    /// Statements prepared before the change may refer to dropped objects
+    /// or return outdated columns.
+    async fn execute_schema_change(&self, sql: &str) -> Result<()> {
+        self.connection.execute(sql).await?;
+        self.connection.clear_statement_cache().await
+    }
+
+    /// Run schema-altering statements in order and flush the statement cache
+    async fn execute_schema_changes(&self, statements: &[String]) -> Result<()> {
+        for sql in statements {
+            self.connection.execute(sql).await?;
+        }
+        self.connection.clear_statement_cache().await
+    }
+
     // ========================================================================
     // Platform-specific row parsing
     // ========================================================================
@@ -214,7 +545,6 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
     }
 
     /// Parse a `SQLite` `PRAGMA` `table_info` row
-    #[allow(clippy::unused_self)]
     fn parse_sqlite_column_row(&self, row: &[SqlValue]) -> Option<ColumnInfo> {
         // SQLite PRAGMA table_info returns: cid, name, type, notnull, dflt_value, pk
         if row.len() < 6 {
@@ -238,8 +568,9 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
             _ => false,
         };
 
+        let sql_type = self.platform.parse_type_declaration(&type_name);
         let default = match &row[4] {
-            SqlValue::String(s) if !s.is_empty() => Some(s.clone()),
+            SqlValue::String(s) => self.normalize_default(s, sql_type.as_ref()),
             _ => None,
         };
 
@@ -258,15 +589,17 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
         Some(ColumnInfo {
             name,
             type_name,
+            sql_type,
             nullable,
             default,
             is_primary_key,
             is_auto_increment,
+            platform_options: BTreeMap::new(),
+            enum_values: Vec::new(),
         })
     }
 
     /// Parse a standard `information_schema` column row
-    #[allow(clippy::unused_self)]
     fn parse_standard_column_row(&self, row: &[SqlValue]) -> Option<ColumnInfo> {
         // Standard information_schema format: column_name, data_type, is_nullable, column_default, ...
         if row.is_empty() {
@@ -297,25 +630,51 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
             true
         };
 
+        let sql_type = self.platform.parse_type_declaration(&type_name);
         let default = if row.len() > 3 {
             match &row[3] {
-                SqlValue::String(s) if !s.is_empty() => Some(s.clone()),
+                SqlValue::String(s) => self.normalize_default(s, sql_type.as_ref()),
                 _ => None,
             }
         } else {
             None
         };
 
+        // MySQL: column_name, data_type, is_nullable, column_default, ..., extra
+        let mut platform_options = match row.get(7) {
+            Some(SqlValue::String(extra)) => self.platform.parse_column_options(extra),
+            _ => BTreeMap::new(),
+        };
+
+        // MySQL 8 `srs_id` of spatial columns declared with an SRID
+        if let Some(srid) = row.get(9).and_then(SqlValue::as_i64) {
+            platform_options.insert("srid".to_string(), srid.to_string());
+        }
+
+        // MySQL `column_type` like `enum('a','b')`, emulated for PostgreSQL
+        let enum_values = match row.get(8) {
+            Some(SqlValue::String(column_type)) => parse_enum_values(column_type),
+            _ => Vec::new(),
+        };
+
         Some(ColumnInfo {
             name,
             type_name,
+            sql_type,
             nullable,
             default,
             is_primary_key: false, // Would need additional query
             is_auto_increment: false, // Would need additional query
+            platform_options,
+            enum_values,
         })
     }
 
+    /// Parse an introspected default, reading it as text if the type is unknown
+    fn normalize_default(&self, default: &str, sql_type: Option<&SqlType>) -> Option<DefaultValue> {
+        self.platform.normalize_default(default, sql_type.unwrap_or(&SqlType::Text))
+    }
+
     /// Parse an index metadata row from the database
     fn parse_index_row(&self, row: &[SqlValue]) -> Option<IndexInfo> {
         if row.is_empty() {
@@ -361,7 +720,7 @@ impl<'a, C: DriverConnection, P: Platform> SchemaManager<'a, C, P> {
 
         Some(IndexInfo {
             name,
-            columns: Vec::new(), // Would need PRAGMA index_info to get columns
+            columns: Vec::new(), // Filled from PRAGMA index_info
             unique,
             primary: origin == "pk",
         })
@@ -537,14 +896,20 @@ pub struct ColumnInfo {
     pub name: String,
     /// SQL type name as reported by the database
     pub type_name: String,
+    /// Parsed SQL type (`None` if the type name is not recognized)
+    pub sql_type: Option<SqlType>,
     /// Whether the column allows NULL values
     pub nullable: bool,
-    /// Default value expression
-    pub default: Option<String>,
+    /// Default value, parsed with [`Platform::normalize_default`]
+    pub default: Option<DefaultValue>,
     /// Whether this column is part of the primary key
     pub is_primary_key: bool,
     /// Whether this column auto-increments
     pub is_auto_increment: bool,
+    /// Platform-specific attributes, parsed with [`Platform::parse_column_options`]
+    pub platform_options: BTreeMap<String, String>,
+    /// Values of a native enum type (`MySQL`, `PostgreSQL`), empty otherwise
+    pub enum_values: Vec<String>,
 }
 
 /// Information about a database index
@@ -577,6 +942,65 @@ pub struct ForeignKeyInfo {
     pub on_delete: ForeignKeyAction,
 }
 
+/// Size of a table, see [`SchemaManager::table_statistics`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TableStatistics {
+    /// Number of rows, estimated by `PostgreSQL` and `MySQL`
+    pub row_count: Option<u64>,
+    /// Bytes used by the table and its indexes
+    pub size_bytes: Option<u64>,
+}
+
+/// A privilege granted on a table, see [`SchemaManager::grants_for`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrantInfo {
+    /// User or role holding the privilege (`'user'@'host'` on `MySQL`)
+    pub grantee: String,
+    /// Privilege name, e.g. `SELECT`
+    pub privilege: String,
+}
+
+/// Read the values of an enum column type like `enum('a','b')`
+fn parse_enum_values(column_type: &str) -> Vec<String> {
+    let Some(list) = column_type
+        .get(..5)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("enum("))
+        .and_then(|_| column_type[5..].strip_suffix(')'))
+    else {
+        return Vec::new();
+    };
+    let mut values = Vec::new();
+    let mut chars = list.chars().peekable();
+    while chars.next() == Some('\'') {
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                if chars.peek() != Some(&'\'') {
+                    break;
+                }
+                chars.next();
+            }
+            value.push(c);
+        }
+        values.push(value);
+        if chars.next() != Some(',') {
+            break;
+        }
+    }
+    values
+}
+
+/// Read a non-negative statistic, as returned by any of the platforms
+fn statistic(value: &SqlValue) -> Option<u64> {
+    match value {
+        SqlValue::U64(n) => Some(*n),
+        SqlValue::String(s) => s.parse().ok(),
+        #[cfg(feature = "decimal")]
+        SqlValue::Decimal(d) => rust_decimal::prelude::ToPrimitive::to_u64(d),
+        _ => value.as_i64().and_then(|n| u64::try_from(n).ok()),
+    }
+}
+
 /// Complete table information from introspection
 #[derive(Debug, Clone)]
 pub struct TableInfo {
@@ -612,6 +1036,51 @@ impl TableInfo {
     pub fn get_column(&self, name: &str) -> Option<&ColumnInfo> {
         self.columns.iter().find(|c| c.name.eq_ignore_ascii_case(name))
     }
+
+    /// Convert the introspected table into a table definition
+    ///
+    /// Columns with unrecognized types become `TEXT`. Index names generated
+    /// by the database (e.g. `SQLite`'s `sqlite_autoindex_*`) are dropped so
+    /// the definition can be created on any platform.
+    #[must_use]
+    pub fn to_table(&self) -> Table {
+        let mut table = Table::new(&self.name);
+
+        for info in &self.columns {
+            let mut column = Column::new(&info.name, info.sql_type.clone().unwrap_or(SqlType::Text));
+            column.nullable = info.nullable;
+            column.default.clone_from(&info.default);
+            column.auto_increment = info.is_auto_increment;
+            column.platform_options.clone_from(&info.platform_options);
+            column.enum_values.clone_from(&info.enum_values);
+            table = table.column(column);
+        }
+
+        let primary_key: Vec<String> = self.primary_key_columns().into_iter().map(String::from).collect();
+        if !primary_key.is_empty() {
+            table = table.index(Index::primary(primary_key));
+        }
+
+        for info in self.indexes.iter().filter(|index| !index.primary) {
+            let name = if info.name.starts_with("sqlite_autoindex_") { "" } else { info.name.as_str() };
+            let mut index = Index::new(name, info.columns.clone());
+            index.unique = info.unique;
+            table = table.index(index);
+        }
+
+        for info in &self.foreign_keys {
+            table = table.foreign_key(ForeignKey {
+                name: info.name.clone(),
+                local_columns: info.local_columns.clone(),
+                foreign_table: info.foreign_table.clone(),
+                foreign_columns: info.foreign_columns.clone(),
+                on_delete: info.on_delete,
+                on_update: info.on_update,
+            });
+        }
+
+        table
+    }
 }
 
 #[cfg(test)]
@@ -623,10 +1092,13 @@ mod tests {
         let info = ColumnInfo {
             name: "id".to_string(),
             type_name: "INTEGER".to_string(),
+            sql_type: Some(SqlType::Integer),
             nullable: false,
             default: None,
             is_primary_key: true,
             is_auto_increment: true,
+            platform_options: BTreeMap::new(),
+            enum_values: Vec::new(),
         };
 
         assert_eq!(info.name, "id");
@@ -634,6 +1106,13 @@ mod tests {
         assert!(info.is_primary_key);
     }
 
+    #[test]
+    fn test_parse_enum_values() {
+        assert_eq!(parse_enum_values("enum('a','it''s')"), vec!["a".to_string(), "it's".to_string()]);
+        assert_eq!(parse_enum_values("ENUM('x, y')"), vec!["x, y".to_string()]);
+        assert!(parse_enum_values("varchar(20)").is_empty());
+    }
+
     #[test]
     fn test_table_info_primary_key() {
         let info = TableInfo {
@@ -642,18 +1121,24 @@ mod tests {
                 ColumnInfo {
                     name: "id".to_string(),
                     type_name: "INTEGER".to_string(),
+                    sql_type: Some(SqlType::Integer),
                     nullable: false,
                     default: None,
                     is_primary_key: true,
                     is_auto_increment: true,
+                    platform_options: BTreeMap::new(),
+                    enum_values: Vec::new(),
                 },
                 ColumnInfo {
                     name: "name".to_string(),
                     type_name: "TEXT".to_string(),
+                    sql_type: Some(SqlType::Text),
                     nullable: false,
                     default: None,
                     is_primary_key: false,
                     is_auto_increment: false,
+                    platform_options: BTreeMap::new(),
+                    enum_values: Vec::new(),
                 },
             ],
             indexes: Vec::new(),
@@ -673,10 +1158,13 @@ mod tests {
             columns: vec![ColumnInfo {
                 name: "MyColumn".to_string(),
                 type_name: "TEXT".to_string(),
+                sql_type: Some(SqlType::Text),
                 nullable: true,
                 default: None,
                 is_primary_key: false,
                 is_auto_increment: false,
+                platform_options: BTreeMap::new(),
+                enum_values: Vec::new(),
             }],
             indexes: Vec::new(),
             foreign_keys: Vec::new(),
@@ -693,7 +1181,7 @@ mod tests {
 mod sqlite_tests {
     use super::*;
     use crate::driver::{Driver, SqliteDriver};
-    use crate::platform::{Column, SqlType, SqlitePlatform};
+    use crate::platform::{Column, DefaultValue, SqlType, SqlitePlatform};
 
     async fn setup_connection() -> <SqliteDriver as Driver>::Connection {
         let driver = SqliteDriver::new();
@@ -704,7 +1192,7 @@ mod sqlite_tests {
     #[tokio::test]
     async fn test_list_table_names_empty() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         let tables = manager.list_table_names().await.unwrap();
@@ -714,7 +1202,7 @@ mod sqlite_tests {
     #[tokio::test]
     async fn test_create_and_list_tables() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         // Create a table
@@ -731,10 +1219,26 @@ mod sqlite_tests {
         assert_eq!(tables[0], "users");
     }
 
+    #[tokio::test]
+    async fn test_invalid_identifiers_rejected() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+
+        let table = Table::new("users").column(Column::new("na\0me", SqlType::Text));
+        assert!(matches!(
+            manager.create_table(&table).await,
+            Err(crate::core::Error::Schema(SchemaError::InvalidDefinition(_)))
+        ));
+        assert!(manager.drop_table("\"users\"").await.is_err());
+        assert!(manager.list_table_columns("").await.is_err());
+        assert!(manager.list_table_names().await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_table_exists() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         assert!(!manager.table_exists("users").await.unwrap());
@@ -749,10 +1253,108 @@ mod sqlite_tests {
         assert!(!manager.table_exists("posts").await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_auto_increment_integer_types() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+
+        for (name, sql_type) in [("big", SqlType::BigInt), ("small", SqlType::SmallInt)] {
+            let table = Table::new(name)
+                .column(Column::new("id", sql_type).not_null().auto_increment())
+                .column(Column::new("label", SqlType::Text));
+            manager.create_table(&table).await.unwrap();
+            conn.execute(&format!("INSERT INTO {name} (label) VALUES ('a'), ('b')")).await.unwrap();
+            let mut result = conn.query(&format!("SELECT MAX(id) FROM {name}")).await.unwrap();
+            assert_eq!(result.all_rows().unwrap(), vec![vec![SqlValue::I64(2)]]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_table_statistics() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+        let table = Table::new("it's").column(Column::new("id", SqlType::Integer).not_null());
+        manager.create_table(&table).await.unwrap();
+        manager.create_index("it's", &Index::new("idx_id", vec!["id".to_string()])).await.unwrap();
+        conn.execute("INSERT INTO \"it's\" (id) VALUES (1), (2), (3)").await.unwrap();
+
+        let stats = manager.table_statistics("it's").await.unwrap();
+        assert_eq!(stats.row_count, Some(3));
+        // Table and index take a page each at least
+        assert!(stats.size_bytes.unwrap() >= 2 * 4096);
+
+        assert!(manager.table_statistics("posts").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_grants_unsupported() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+        assert!(matches!(
+            manager.grants_for("users").await,
+            Err(crate::core::Error::Platform(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_identifier_case() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+        manager
+            .create_table(&Table::new("users").column(Column::new("id", SqlType::Integer)))
+            .await
+            .unwrap();
+        assert!(manager.table_exists("\"users\"").await.unwrap());
+
+        let manager = manager.with_identifier_case(IdentifierCase::LowerUnquoted);
+        assert!(manager.table_exists("Users").await.unwrap());
+        assert!(!manager.table_exists("\"Users\"").await.unwrap());
+        let info = manager.introspect_table("USERS").await.unwrap();
+        assert_eq!((info.name.as_str(), info.columns.len()), ("users", 1));
+
+        let manager = SchemaManager::new(&conn, &platform).with_platform_identifier_case();
+        assert_eq!(manager.stored_name("Users").unwrap(), "Users");
+        let postgres = crate::platform::PostgresPlatform;
+        let manager = SchemaManager::new(&conn, &postgres).with_platform_identifier_case();
+        assert_eq!(manager.stored_name("Users").unwrap(), "users");
+        assert_eq!(manager.stored_name("\"Users\"").unwrap(), "Users");
+    }
+
+    #[tokio::test]
+    async fn test_mysql_column_row_platform_options() {
+        let conn = setup_connection().await;
+        let platform = crate::platform::MySqlPlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+        let text = |value: &str| SqlValue::String(value.to_string());
+
+        let row = [
+            text("location"),
+            text("point"),
+            text("NO"),
+            SqlValue::Null,
+            SqlValue::Null,
+            SqlValue::Null,
+            SqlValue::Null,
+            text("INVISIBLE"),
+            text("point"),
+            SqlValue::U32(4326),
+        ];
+        let info = manager.parse_column_row(&row).unwrap();
+        assert_eq!(info.platform_options.get("srid").map(String::as_str), Some("4326"));
+        assert_eq!(info.platform_options.get("invisible").map(String::as_str), Some("true"));
+
+        let info = manager.parse_column_row(&[&row[..9], &[SqlValue::Null]].concat()).unwrap();
+        assert!(!info.platform_options.contains_key("srid"));
+    }
+
     #[tokio::test]
     async fn test_drop_table() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         // Create table
@@ -767,10 +1369,37 @@ mod sqlite_tests {
         assert!(!manager.table_exists("test_table").await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_recreated_table_returns_new_columns() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+
+        manager
+            .create_table(&Table::new("test").column(Column::new("id", SqlType::Integer)))
+            .await
+            .unwrap();
+        conn.execute("INSERT INTO test VALUES (1)").await.unwrap();
+        assert_eq!(conn.query("SELECT * FROM test").await.unwrap().column_names(), ["id"]);
+
+        // The cached statement for the old table must not be reused
+        manager.drop_table("test").await.unwrap();
+        manager
+            .create_table(
+                &Table::new("test")
+                    .column(Column::new("id", SqlType::Integer))
+                    .column(Column::new("name", SqlType::Text)),
+            )
+            .await
+            .unwrap();
+        conn.execute("INSERT INTO test VALUES (1, 'a')").await.unwrap();
+        assert_eq!(conn.query("SELECT * FROM test").await.unwrap().column_names(), ["id", "name"]);
+    }
+
     #[tokio::test]
     async fn test_drop_table_if_exists() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         // Should not error even if table doesn't exist
@@ -788,7 +1417,7 @@ mod sqlite_tests {
     #[tokio::test]
     async fn test_list_table_columns() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         // Create table with various column types
@@ -796,7 +1425,7 @@ mod sqlite_tests {
             .column(Column::new("id", SqlType::Integer).not_null().auto_increment())
             .column(Column::new("name", SqlType::Text).not_null())
             .column(Column::new("email", SqlType::Text))
-            .column(Column::new("age", SqlType::Integer).default("0"));
+            .column(Column::new("age", SqlType::Integer).default_expression("0"));
 
         manager.create_table(&table).await.unwrap();
 
@@ -821,13 +1450,36 @@ mod sqlite_tests {
 
         // Check age column (with default)
         let age_col = columns.iter().find(|c| c.name == "age").unwrap();
-        assert!(age_col.default.is_some());
+        assert_eq!(age_col.default, Some(DefaultValue::value(0_i64)));
+    }
+
+    #[tokio::test]
+    async fn test_column_types_round_trip() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+
+        let table = Table::new("typed")
+            .column(Column::new("id", SqlType::Integer).not_null().auto_increment())
+            .column(Column::new("code", SqlType::varchar(255)))
+            .column(Column::new("price", SqlType::decimal(10, 2)))
+            .column(Column::new("active", SqlType::Boolean))
+            .column(Column::new("created_at", SqlType::timestamp(None)));
+
+        manager.create_table(&table).await.unwrap();
+
+        let columns = manager.list_table_columns("typed").await.unwrap();
+        let types: Vec<_> = columns.iter().map(|c| c.sql_type.clone()).collect();
+        assert_eq!(
+            types,
+            table.columns.iter().map(|c| Some(c.sql_type.clone())).collect::<Vec<_>>()
+        );
     }
 
     #[tokio::test]
     async fn test_introspect_table() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         // Create table
@@ -856,7 +1508,7 @@ mod sqlite_tests {
     #[tokio::test]
     async fn test_list_indexes() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         // Create table
@@ -881,7 +1533,7 @@ mod sqlite_tests {
     #[tokio::test]
     async fn test_create_unique_index() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         // Create table
@@ -905,7 +1557,7 @@ mod sqlite_tests {
     #[tokio::test]
     async fn test_foreign_keys() {
         let conn = setup_connection().await;
-        let platform = SqlitePlatform;
+        let platform = SqlitePlatform::new();
         let manager = SchemaManager::new(&conn, &platform);
 
         // Enable foreign keys in SQLite
@@ -940,4 +1592,97 @@ mod sqlite_tests {
         assert_eq!(fk.local_columns, vec!["user_id"]);
         assert_eq!(fk.foreign_columns, vec!["id"]);
     }
+    #[tokio::test]
+    async fn test_owned_manager() {
+        let conn = Arc::new(setup_connection().await);
+        let manager = SchemaManager::new_owned(Arc::clone(&conn), Arc::new(SqlitePlatform::new()));
+
+        let table = Table::new("users").column(Column::new("id", SqlType::Integer).not_null());
+        let handle = tokio::spawn(async move {
+            manager.create_table(&table).await.unwrap();
+            manager.list_table_names().await.unwrap()
+        });
+        assert_eq!(handle.await.unwrap(), ["users"]);
+        assert_eq!(Arc::strong_count(&conn), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_and_drop_schema() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+        conn.execute("PRAGMA foreign_keys = ON").await.unwrap();
+
+        let fk = |name: &str, column: &str, table: &str| ForeignKey {
+            name: name.to_string(),
+            local_columns: vec![column.to_string()],
+            foreign_table: table.to_string(),
+            foreign_columns: vec!["id".to_string()],
+            on_delete: ForeignKeyAction::NoAction,
+            on_update: ForeignKeyAction::NoAction,
+        };
+        let id = || Column::new("id", SqlType::Integer).not_null();
+        let schema = Schema::new()
+            .table(Table::new("posts").column(id()).column(Column::new("user_id", SqlType::Integer))
+                .index(Index::primary(vec!["id".to_string()]))
+                .index(Index::new("idx_posts_user", vec!["user_id".to_string()]))
+                .foreign_key(fk("fk_posts_user", "user_id", "users")))
+            .table(Table::new("users").column(id()).column(Column::new("team_id", SqlType::Integer))
+                .index(Index::primary(vec!["id".to_string()]))
+                .foreign_key(fk("fk_users_team", "team_id", "teams")))
+            .table(Table::new("teams").column(id()).column(Column::new("owner_id", SqlType::Integer))
+                .index(Index::primary(vec!["id".to_string()]))
+                .foreign_key(fk("fk_teams_owner", "owner_id", "users")));
+
+        manager.create_schema(&schema).await.unwrap();
+        let mut tables = manager.list_table_names().await.unwrap();
+        tables.sort();
+        assert_eq!(tables, ["posts", "teams", "users"]);
+        assert_eq!(manager.list_table_foreign_keys("users").await.unwrap().len(), 1);
+        assert_eq!(manager.list_table_indexes("posts").await.unwrap().len(), 1);
+
+        manager.drop_schema(&schema).await.unwrap();
+        assert!(manager.list_table_names().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_csv() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+        conn.execute("CREATE TABLE countries (code TEXT PRIMARY KEY, name TEXT NOT NULL, population BIGINT)")
+            .await
+            .unwrap();
+
+        let csv = "Code;Name;population\nDE;Germany;83000000\nFR;France;\nIT;Italy;59000000\n";
+        let options = ImportOptions::new().with_delimiter(';').map_column("Code", "code").map_column("Name", "name").with_batch_size(2);
+        assert_eq!(manager.import_csv("countries", csv.as_bytes(), &options).await.unwrap(), 3);
+
+        let rows = conn.query("SELECT code, population FROM countries ORDER BY code").await.unwrap().all_rows().unwrap();
+        assert_eq!(rows[0], ["DE".into(), SqlValue::I64(83_000_000)]);
+        assert_eq!(rows[1], ["FR".into(), SqlValue::Null]);
+
+        // A failing row leaves nothing behind
+        let duplicate = "code,name\nES,Spain\nDE,Germany\n";
+        let options = ImportOptions::new().with_batch_size(1);
+        assert!(manager.import_csv("countries", duplicate.as_bytes(), &options).await.is_err());
+        let rows = conn.query("SELECT COUNT(*) FROM countries").await.unwrap().all_rows().unwrap();
+        assert_eq!(rows[0], [SqlValue::I64(3)]);
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn test_import_json_lines() {
+        let conn = setup_connection().await;
+        let platform = SqlitePlatform::new();
+        let manager = SchemaManager::new(&conn, &platform);
+        conn.execute("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT, note TEXT)").await.unwrap();
+
+        let input = "{\"key\": \"theme\", \"value\": \"dark\"}\n{\"key\": \"tags\", \"value\": [\"a\"], \"source\": \"x\"}\n";
+        let options = ImportOptions::new().with_ignore_unknown_columns(true);
+        assert_eq!(manager.import_json_lines("settings", input.as_bytes(), &options).await.unwrap(), 2);
+
+        let rows = conn.query("SELECT value FROM settings WHERE key = 'tags'").await.unwrap().all_rows().unwrap();
+        assert_eq!(rows[0], [SqlValue::from("[\"a\"]")]);
+    }
 }
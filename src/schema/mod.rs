@@ -3,8 +3,10 @@
 //! Schema introspection and management for database operations.
 //!
 //! This module provides types for representing database schema objects
-//! (tables, columns, indexes, foreign keys) and the `SchemaManager`
-//! for introspecting and manipulating schemas.
+//! (tables, columns, indexes, foreign keys), the `SchemaManager`
+//! for introspecting and manipulating schemas and the `Comparator` for
+//! diffing schema definitions. `SchemaManager::import_csv` loads reference
+//! data into a table, configured by `ImportOptions`.
 //!
 //! ## Example
 //!
@@ -13,7 +15,7 @@
 //! use rustine_dbal::platform::SqlitePlatform;
 //!
 //! // Create a schema manager
-//! let manager = SchemaManager::new(&connection, &SqlitePlatform);
+//! let manager = SchemaManager::new(&connection, &SqlitePlatform::new());
 //!
 //! // List all tables
 //! let tables = manager.list_table_names().await?;
@@ -30,6 +32,10 @@
 //! }
 //! ```
 
+mod comparator;
+mod import;
 mod manager;
 
-pub use manager::{SchemaManager, ColumnInfo, IndexInfo, ForeignKeyInfo, TableInfo};
+pub use comparator::{ColumnDiff, Comparator, ComparatorOptions, SchemaDiff, TableDiff};
+pub use import::ImportOptions;
+pub use manager::{SchemaManager, ColumnInfo, GrantInfo, IndexInfo, ForeignKeyInfo, TableInfo, TableStatistics};
@@ -0,0 +1,324 @@
+//! Property tests for generated SQL
+//!
+//! Random tables, expressions and queries are rendered for every platform and
+//! parsed back with `sqlparser` in the platform's dialect. Besides parsing,
+//! quoted identifiers and string literals must come back unchanged, every
+//! parameter placeholder must be found again, and statements parsed back into
+//! the query tree must render the same SQL every time.
+
+#![allow(clippy::unwrap_used, clippy::needless_pass_by_value)]
+
+use proptest::prelude::*;
+use rustine_dbal::driver::{rewrite_placeholders, Placeholder, PlaceholderStyle};
+use rustine_dbal::platform::{
+    AnsiPlatform, Column, Index, MySqlPlatform, Platform, PostgresPlatform, SqlType, SqlitePlatform, Table,
+};
+use rustine_dbal::query::{self, Expr, OrderDirection, PlatformRenderer, QueryBuilder, SqlRenderer, Statement};
+use rustine_dbal::SqlValue;
+use sqlparser::ast;
+use sqlparser::dialect::{AnsiDialect, Dialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::Parser;
+
+/// All built-in platforms
+fn platforms() -> Vec<Box<dyn Platform>> {
+    vec![
+        Box::new(PostgresPlatform),
+        Box::new(MySqlPlatform::new()),
+        Box::new(SqlitePlatform::new()),
+        Box::new(AnsiPlatform::new()),
+    ]
+}
+
+/// Get the `sqlparser` dialect of a platform
+fn dialect(platform: &dyn Platform) -> Box<dyn Dialect> {
+    match platform.name() {
+        "postgresql" => Box::new(PostgreSqlDialect {}),
+        "mysql" => Box::new(MySqlDialect {}),
+        "sqlite" => Box::new(SQLiteDialect {}),
+        _ => Box::new(AnsiDialect {}),
+    }
+}
+
+/// Parse SQL in the dialect of a platform, failing the case if it doesn't parse
+fn parse(platform: &dyn Platform, sql: &str) -> Result<Vec<ast::Statement>, TestCaseError> {
+    Parser::parse_sql(dialect(platform).as_ref(), sql)
+        .map_err(|e| TestCaseError::fail(format!("{} can't parse {sql}: {e}", platform.name())))
+}
+
+/// Get the single item of `SELECT <item>`
+fn select_item(statements: &[ast::Statement]) -> Option<&ast::Expr> {
+    let [ast::Statement::Query(query)] = statements else { return None };
+    let ast::SetExpr::Select(select) = query.body.as_ref() else { return None };
+    match select.projection.as_slice() {
+        [ast::SelectItem::UnnamedExpr(expr)] => Some(expr),
+        _ => None,
+    }
+}
+
+/// Render a query with `?` parameters for a platform, rewriting them to its
+/// placeholder style like `Connection` does
+fn render(platform: &dyn Platform, query: &QueryBuilder) -> (String, Vec<Placeholder>) {
+    let sql = query.to_sql(platform);
+    let rewritten = rewrite_placeholders(&sql, PlaceholderStyle::for_platform(platform));
+    (rewritten.sql, rewritten.parameters)
+}
+
+/// Parse SQL into a statement and render it again
+fn reparse(platform: &dyn Platform, sql: &str) -> Result<String, TestCaseError> {
+    let statement = Statement::parse(sql, platform)
+        .map_err(|e| TestCaseError::fail(format!("{}: {sql}: {e}", platform.name())))?;
+    Ok(PlatformRenderer::new(platform).render(&statement))
+}
+
+/// Count the parameter placeholders of an expression
+fn param_count(expr: &Expr) -> usize {
+    match expr {
+        Expr::Param(_) => 1,
+        Expr::Column(_) | Expr::Value(_) | Expr::Raw(_) => 0,
+        Expr::Comparison(left, _, right) => param_count(left) + param_count(right),
+        Expr::And(exprs) | Expr::Or(exprs) => exprs.iter().map(param_count).sum(),
+        Expr::Not(inner) | Expr::IsNull(inner) | Expr::IsNotNull(inner) | Expr::Like(inner, _) => {
+            param_count(inner)
+        }
+        Expr::In(inner, values) | Expr::NotIn(inner, values) => {
+            param_count(inner) + values.iter().map(param_count).sum::<usize>()
+        }
+        Expr::Between(inner, low, high) => param_count(inner) + param_count(low) + param_count(high),
+        Expr::Regexp { expr, .. } => param_count(expr),
+    }
+}
+
+/// Identifiers including keywords, spaces and every quote character
+fn identifier() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[a-z_][a-z0-9_]{0,10}",
+        "[A-Za-z0-9 _\"'`\\[\\]\\\\$?:;.()-]{1,12}",
+        Just("select".to_string()),
+        Just("order".to_string()),
+        Just("User".to_string()),
+    ]
+}
+
+/// String literals with quotes, backslashes, comments and placeholder lookalikes
+fn text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[ -~]{0,16}",
+        "[a-z '\"\\\\?:$/*%-]{0,16}",
+        "[äöüß€😀\n\t ]{0,8}",
+    ]
+}
+
+/// Literal values
+fn value() -> impl Strategy<Value = SqlValue> {
+    prop_oneof![
+        Just(SqlValue::Null),
+        any::<bool>().prop_map(SqlValue::Bool),
+        any::<i64>().prop_map(SqlValue::I64),
+        any::<f64>().prop_filter("finite", |f| f.is_finite()).prop_map(SqlValue::F64),
+        text().prop_map(SqlValue::String),
+        prop::collection::vec(any::<u8>(), 0..8).prop_map(SqlValue::Bytes),
+    ]
+}
+
+/// Operands of a condition: columns, literals and `?` parameters
+fn operand() -> impl Strategy<Value = Expr> {
+    prop_oneof![
+        identifier().prop_map(Expr::col),
+        value().prop_map(Expr::Value),
+        Just(Expr::param("?")),
+    ]
+}
+
+/// Conditions nested up to three levels deep
+fn condition() -> impl Strategy<Value = Expr> {
+    let predicate = prop_oneof![
+        (operand(), 0..6_u8, operand()).prop_map(|(left, op, right)| match op {
+            0 => left.eq(right),
+            1 => left.ne(right),
+            2 => left.lt(right),
+            3 => left.le(right),
+            4 => left.gt(right),
+            _ => left.ge(right),
+        }),
+        operand().prop_map(Expr::is_null),
+        operand().prop_map(Expr::is_not_null),
+        (operand(), prop::collection::vec(operand(), 1..4)).prop_map(|(left, values)| left.in_list(values)),
+        (operand(), prop::collection::vec(operand(), 1..4)).prop_map(|(left, values)| left.not_in_list(values)),
+        (operand(), operand(), operand()).prop_map(|(left, low, high)| left.between(low, high)),
+        (identifier(), text()).prop_map(|(column, pattern)| Expr::col(column).like(pattern)),
+    ];
+    predicate.prop_recursive(3, 24, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 2..4).prop_map(query::and),
+            prop::collection::vec(inner.clone(), 2..4).prop_map(query::or),
+            inner.prop_map(Expr::not),
+        ]
+    })
+}
+
+/// Column types every platform can declare
+fn sql_type() -> impl Strategy<Value = SqlType> {
+    prop_oneof![
+        Just(SqlType::SmallInt),
+        Just(SqlType::Integer),
+        Just(SqlType::BigInt),
+        Just(SqlType::Float),
+        Just(SqlType::Double),
+        (1..38_u8, 0..10_u8).prop_map(|(precision, scale)| SqlType::decimal(precision, scale.min(precision))),
+        (1..255_u32).prop_map(SqlType::char),
+        (1..4000_u32).prop_map(SqlType::varchar),
+        Just(SqlType::Text),
+        Just(SqlType::Blob),
+        Just(SqlType::Boolean),
+        Just(SqlType::Date),
+        prop::option::of(0..7_u8).prop_map(SqlType::timestamp),
+        Just(SqlType::Uuid),
+        Just(SqlType::Json),
+    ]
+}
+
+/// Columns with optional NOT NULL and literal defaults
+fn column() -> impl Strategy<Value = Column> {
+    (identifier(), sql_type(), any::<bool>(), prop::option::of(any::<i32>())).prop_map(
+        |(name, sql_type, not_null, default)| {
+            let column = Column::new(name, sql_type);
+            let column = if not_null { column.not_null() } else { column };
+            match default {
+                Some(default) => column.default_value(default),
+                None => column,
+            }
+        },
+    )
+}
+
+/// Tables with distinctly named columns and a primary key on the first one
+fn table() -> impl Strategy<Value = Table> {
+    (identifier(), prop::collection::vec(column(), 1..6)).prop_map(|(name, columns)| {
+        let mut table = Table::new(name);
+        for column in columns {
+            if !table.columns.iter().any(|c| c.name.eq_ignore_ascii_case(&column.name)) {
+                table = table.column(column);
+            }
+        }
+        let key = table.columns[0].name.clone();
+        table.index(Index::primary(vec![key]))
+    })
+}
+
+/// SELECT, INSERT, UPDATE and DELETE queries
+fn query() -> impl Strategy<Value = QueryBuilder> {
+    let select = (
+        identifier(),
+        prop::collection::vec(identifier(), 0..4),
+        prop::option::of(condition()),
+        prop::option::of((identifier(), any::<bool>())),
+        prop::option::of(1..1000_u64),
+    )
+        .prop_map(|(table, columns, condition, order, limit)| {
+            let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+            let mut query = QueryBuilder::select().columns(&columns).from(&table);
+            if let Some(condition) = condition {
+                query = query.where_expr(condition);
+            }
+            if let Some((column, ascending)) = order {
+                let direction = if ascending { OrderDirection::Asc } else { OrderDirection::Desc };
+                query = query.order_by(&column, direction);
+            }
+            match limit {
+                Some(limit) => query.limit(limit),
+                None => query,
+            }
+        });
+    let insert = (identifier(), 1..4_usize, prop::collection::vec(prop::collection::vec(value(), 4), 1..4))
+        .prop_flat_map(|(table, width, rows)| {
+            (Just(table), prop::collection::vec(identifier(), width), Just(rows))
+        })
+        .prop_map(|(table, columns, rows)| {
+            let names: Vec<&str> = columns.iter().map(String::as_str).collect();
+            let rows = rows.into_iter().map(|row| row[..columns.len()].to_vec()).collect();
+            QueryBuilder::insert().into(&table).insert_columns(&names).values_batch(rows)
+        });
+    let update = (identifier(), prop::collection::vec((identifier(), value()), 1..4), prop::option::of(condition()))
+        .prop_map(|(table, assignments, condition)| {
+            let mut query = QueryBuilder::update().table(&table);
+            for (column, value) in assignments {
+                query = query.set(&column, value);
+            }
+            match condition {
+                Some(condition) => query.where_expr(condition),
+                None => query,
+            }
+        });
+    let delete = (identifier(), condition())
+        .prop_map(|(table, condition)| QueryBuilder::delete().from(&table).where_expr(condition));
+    prop_oneof![select, insert, update, delete]
+}
+
+proptest! {
+    #[test]
+    fn quoted_identifiers_round_trip(name in identifier()) {
+        for platform in platforms() {
+            let sql = format!("SELECT {} FROM t", platform.quote_identifier(&name));
+            let statements = parse(platform.as_ref(), &sql)?;
+            let Some(ast::Expr::Identifier(ident)) = select_item(&statements) else {
+                return Err(TestCaseError::fail(format!("{sql} doesn't select a column")));
+            };
+            prop_assert_eq!(&ident.value, &name, "{}: {}", platform.name(), sql);
+        }
+    }
+
+    #[test]
+    fn string_literals_round_trip(value in text()) {
+        for platform in platforms() {
+            let sql = format!("SELECT {}", platform.quote_string(&value));
+            let statements = parse(platform.as_ref(), &sql)?;
+            let Some(ast::Expr::Value(ast::Value::SingleQuotedString(parsed))) = select_item(&statements) else {
+                return Err(TestCaseError::fail(format!("{sql} doesn't select a string")));
+            };
+            prop_assert_eq!(parsed, &value, "{}: {}", platform.name(), sql);
+        }
+    }
+
+    #[test]
+    fn conditions_parse_with_all_parameters(condition in condition()) {
+        let expected = param_count(&condition);
+        let query = QueryBuilder::select().from("t").where_expr(condition);
+        for platform in platforms() {
+            let (sql, parameters) = render(platform.as_ref(), &query);
+            parse(platform.as_ref(), &sql)?;
+            prop_assert_eq!(
+                parameters,
+                (0..expected).map(Placeholder::Positional).collect::<Vec<_>>(),
+                "{}: {}",
+                platform.name(),
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn queries_parse_and_render_again(query in query()) {
+        for platform in platforms() {
+            let (sql, _) = render(platform.as_ref(), &query);
+            parse(platform.as_ref(), &sql)?;
+
+            // Parsing flattens nested AND/OR, after that the SQL must stay the same
+            let rendered = reparse(platform.as_ref(), &sql)?;
+            prop_assert_eq!(reparse(platform.as_ref(), &rendered)?, rendered);
+        }
+    }
+
+    #[test]
+    fn create_table_parses_with_all_columns(table in table()) {
+        for platform in platforms() {
+            let sql = platform.get_create_table_sql(&table);
+            let statements = parse(platform.as_ref(), &sql)?;
+            let Some(ast::Statement::CreateTable(create)) = statements.first() else {
+                return Err(TestCaseError::fail(format!("{sql} doesn't create a table")));
+            };
+            let names: Vec<&str> = create.columns.iter().map(|column| column.name.value.as_str()).collect();
+            let expected: Vec<&str> = table.columns.iter().map(|column| column.name.as_str()).collect();
+            prop_assert_eq!(names, expected, "{}: {}", platform.name(), sql);
+        }
+    }
+}